@@ -0,0 +1,11 @@
+-- Content freshness tracking
+-- Records when each node was last reviewed so stale trouble trees can be reported
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS last_reviewed_at TIMESTAMPTZ;
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS last_reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- Index for the stale-content report (grouped by category, filtered by review age)
+CREATE INDEX IF NOT EXISTS idx_nodes_category_reviewed ON nodes(category, last_reviewed_at);
+
+COMMENT ON COLUMN nodes.last_reviewed_at IS 'Timestamp of the last content review of this node';
+COMMENT ON COLUMN nodes.last_reviewed_by IS 'User who performed the last content review';
@@ -0,0 +1,56 @@
+-- Incremental content sync
+-- Keeps updated_at accurate on every content change and records deletions as
+-- tombstones so exports can ship only what changed since the last sync
+
+-- Bump updated_at whenever content columns change (review stamps are excluded on purpose)
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+DROP TRIGGER IF EXISTS update_connections_updated_at ON connections;
+CREATE TRIGGER update_connections_updated_at
+    BEFORE UPDATE OF from_node_id, to_node_id, label, order_index, is_active
+    ON connections
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Tombstones for hard-deleted nodes and connections
+CREATE TABLE IF NOT EXISTS deleted_records (
+    id BIGSERIAL PRIMARY KEY,
+    entity_type VARCHAR(20) NOT NULL CHECK (entity_type IN ('node', 'connection')),
+    entity_id UUID NOT NULL,
+    -- NULL when a connection was removed by cascade after its source node was already gone
+    category VARCHAR(255),
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_deleted_records_category_deleted_at ON deleted_records(category, deleted_at);
+
+CREATE OR REPLACE FUNCTION record_node_deletion()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO deleted_records (entity_type, entity_id, category)
+    VALUES ('node', OLD.id, OLD.category);
+    RETURN OLD;
+END;
+$$ language 'plpgsql';
+
+CREATE OR REPLACE FUNCTION record_connection_deletion()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO deleted_records (entity_type, entity_id, category)
+    VALUES ('connection', OLD.id, (SELECT category FROM nodes WHERE id = OLD.from_node_id));
+    RETURN OLD;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS record_nodes_deleted ON nodes;
+CREATE TRIGGER record_nodes_deleted AFTER DELETE ON nodes
+    FOR EACH ROW EXECUTE FUNCTION record_node_deletion();
+
+DROP TRIGGER IF EXISTS record_connections_deleted ON connections;
+CREATE TRIGGER record_connections_deleted AFTER DELETE ON connections
+    FOR EACH ROW EXECUTE FUNCTION record_connection_deletion();
+
+COMMENT ON TABLE deleted_records IS 'Tombstones for deleted nodes/connections, consumed by incremental export';
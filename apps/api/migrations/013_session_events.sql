@@ -0,0 +1,46 @@
+-- Session lifecycle events
+-- High-volume analytics stream (started/completed/abandoned/feedback), kept
+-- separate from audit_logs and partitioned by month so old data can be dropped cheaply
+
+CREATE TABLE IF NOT EXISTS session_events (
+    id BIGSERIAL,
+    session_id VARCHAR(100) NOT NULL,
+    event_type VARCHAR(30) NOT NULL CHECK (event_type IN ('started', 'completed', 'abandoned', 'feedback')),
+    category VARCHAR(255),
+    details JSONB,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (id, occurred_at)
+) PARTITION BY RANGE (occurred_at);
+
+-- Catches rows outside the pre-created monthly partitions
+CREATE TABLE IF NOT EXISTS session_events_default PARTITION OF session_events DEFAULT;
+
+CREATE INDEX IF NOT EXISTS idx_session_events_session_id ON session_events(session_id);
+CREATE INDEX IF NOT EXISTS idx_session_events_type_occurred ON session_events(event_type, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_session_events_category_occurred ON session_events(category, occurred_at);
+
+-- Create monthly partitions for the current month and the next `months_ahead` months
+CREATE OR REPLACE FUNCTION ensure_session_event_partitions(months_ahead INTEGER DEFAULT 2)
+RETURNS VOID AS $$
+DECLARE
+    month_start DATE;
+    partition_name TEXT;
+BEGIN
+    FOR i IN 0..months_ahead LOOP
+        month_start := (date_trunc('month', NOW()) + make_interval(months => i))::date;
+        partition_name := 'session_events_' || to_char(month_start, 'YYYY_MM');
+        IF to_regclass(partition_name) IS NULL THEN
+            EXECUTE format(
+                'CREATE TABLE %I PARTITION OF session_events FOR VALUES FROM (%L) TO (%L)',
+                partition_name,
+                month_start,
+                (month_start + INTERVAL '1 month')::date
+            );
+        END IF;
+    END LOOP;
+END;
+$$ language 'plpgsql';
+
+SELECT ensure_session_event_partitions(2);
+
+COMMENT ON TABLE session_events IS 'Session lifecycle events for analytics (partitioned monthly by occurred_at)';
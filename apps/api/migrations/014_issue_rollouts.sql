@@ -0,0 +1,28 @@
+-- Percentage rollout of revised issue graphs
+-- A revised graph lives in its own (candidate) category; a rollout routes a share
+-- of new sessions for the current category to the candidate's root node
+
+CREATE TABLE IF NOT EXISTS issue_rollouts (
+    category VARCHAR(255) PRIMARY KEY,
+    candidate_category VARCHAR(255) NOT NULL UNIQUE,
+    percentage SMALLINT NOT NULL CHECK (percentage BETWEEN 0 AND 100),
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    CHECK (category <> candidate_category)
+);
+
+-- Which version each session was served, for per-version completion stats
+CREATE TABLE IF NOT EXISTS session_rollout_assignments (
+    session_id VARCHAR(100) NOT NULL,
+    category VARCHAR(255) NOT NULL,
+    variant VARCHAR(20) NOT NULL CHECK (variant IN ('current', 'candidate')),
+    served_category VARCHAR(255) NOT NULL,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (session_id, category)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rollout_assignments_category ON session_rollout_assignments(category, assigned_at);
+
+COMMENT ON TABLE issue_rollouts IS 'Active percentage rollouts of candidate issue graphs';
+COMMENT ON TABLE session_rollout_assignments IS 'Rollout arm served to each session';
@@ -0,0 +1,20 @@
+-- Knowledge base links on conclusion nodes
+-- Lets a conclusion point techs at the full repair manual for the fix
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS kb_url TEXT;
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS doc_reference VARCHAR(255);
+
+-- Only conclusions carry knowledge base links
+ALTER TABLE nodes DROP CONSTRAINT IF EXISTS nodes_kb_links_conclusion_only;
+ALTER TABLE nodes ADD CONSTRAINT nodes_kb_links_conclusion_only
+    CHECK (node_type = 'conclusion' OR (kb_url IS NULL AND doc_reference IS NULL));
+
+-- Link edits count as content changes for incremental sync
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, kb_url, doc_reference
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN nodes.kb_url IS 'Knowledge base / repair manual URL (conclusions only)';
+COMMENT ON COLUMN nodes.doc_reference IS 'Document reference such as a manual section number (conclusions only)';
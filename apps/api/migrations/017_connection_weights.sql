@@ -0,0 +1,24 @@
+-- Connection weights for analytics-driven answer ordering
+-- A weight is set by an admin or recomputed from how often techs pick the
+-- answer. Each node chooses whether its options follow order_index or weight.
+
+ALTER TABLE connections ADD COLUMN IF NOT EXISTS weight DOUBLE PRECISION;
+ALTER TABLE connections DROP CONSTRAINT IF EXISTS connections_weight_non_negative;
+ALTER TABLE connections ADD CONSTRAINT connections_weight_non_negative
+    CHECK (weight IS NULL OR weight >= 0);
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS option_order VARCHAR(20) NOT NULL DEFAULT 'order_index';
+ALTER TABLE nodes DROP CONSTRAINT IF EXISTS nodes_option_order_valid;
+ALTER TABLE nodes ADD CONSTRAINT nodes_option_order_valid
+    CHECK (option_order IN ('order_index', 'weight'));
+
+-- The ordering mode is content; weights are per-deployment stats and
+-- deliberately do not bump connections.updated_at (no incremental sync churn)
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, kb_url, doc_reference, option_order
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN connections.weight IS 'Ordering weight (manual or recomputed from traversal stats); higher sorts first';
+COMMENT ON COLUMN nodes.option_order IS 'How answer options are ordered: order_index or weight';
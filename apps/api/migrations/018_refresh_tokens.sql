@@ -0,0 +1,26 @@
+-- Server-side refresh tokens
+-- Each sign-in starts a token family (one per device). Every refresh rotates
+-- the token within its family; replaying a rotated token revokes the family.
+-- Only SHA-256 digests are stored.
+
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id UUID NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    remember_me BOOLEAN NOT NULL DEFAULT false,
+    user_agent VARCHAR(500),
+    ip_address VARCHAR(45),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    -- 'rotated', 'logout', 'reuse_detected', 'password_changed', 'revoked', 'deactivated'
+    revoked_reason VARCHAR(50),
+    replaced_by UUID REFERENCES refresh_tokens(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family_id ON refresh_tokens(family_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_expires_at ON refresh_tokens(expires_at);
+
+COMMENT ON TABLE refresh_tokens IS 'Rotating refresh tokens (SHA-256 digests), grouped into per-device families';
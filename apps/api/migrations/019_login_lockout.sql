@@ -0,0 +1,30 @@
+-- Account lockout and login throttling
+-- Consecutive failed logins lock an account for a cooldown; failures are also
+-- recorded per IP so one address can't spray passwords across accounts.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS failed_login_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS locked_until TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_failed_login_at TIMESTAMPTZ;
+
+-- Lockout bookkeeping isn't an account edit; only profile columns bump updated_at
+DROP TRIGGER IF EXISTS update_users_updated_at ON users;
+CREATE TRIGGER update_users_updated_at
+    BEFORE UPDATE OF email, password_hash, role, is_active
+    ON users
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE IF NOT EXISTS failed_logins (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email VARCHAR(255) NOT NULL,
+    -- NULL when the email doesn't belong to an account
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    ip_address VARCHAR(45),
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_failed_logins_ip_time ON failed_logins(ip_address, attempted_at DESC);
+CREATE INDEX IF NOT EXISTS idx_failed_logins_attempted_at ON failed_logins(attempted_at);
+
+COMMENT ON TABLE failed_logins IS 'Failed login attempts, used for per-IP throttling (purged after a day)';
+COMMENT ON COLUMN users.failed_login_count IS 'Consecutive failed logins since the last success or lockout';
+COMMENT ON COLUMN users.locked_until IS 'Login is refused until this time after too many failures';
@@ -0,0 +1,25 @@
+-- Uploaded files (photos, documents) for node and session attachments
+-- File contents live in the configured storage backend (local disk or S3);
+-- this table is the index. Files are only served through signed URLs.
+
+CREATE TABLE IF NOT EXISTS uploads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    storage_key VARCHAR(500) NOT NULL UNIQUE,
+    original_filename VARCHAR(255) NOT NULL,
+    -- Detected from the file contents, not the client's Content-Type
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    sha256 VARCHAR(64) NOT NULL,
+    -- NULL while the upload isn't attached to anything yet
+    owner_type VARCHAR(20) CHECK (owner_type IN ('node', 'session')),
+    owner_id VARCHAR(255),
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- 'clean' (passed ClamAV) or 'not_scanned' (scanning disabled)
+    scan_status VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_uploads_owner ON uploads(owner_type, owner_id);
+CREATE INDEX IF NOT EXISTS idx_uploads_created_at ON uploads(created_at DESC);
+
+COMMENT ON TABLE uploads IS 'Index of uploaded files; contents are in the storage backend';
@@ -0,0 +1,19 @@
+-- Fine-grained permissions attached to roles
+-- ADMIN implicitly holds every permission and has no rows here. VIEWER is
+-- seeded with read-only access; TECH gets no admin permissions unless granted
+-- through /api/v1/admin/roles/:role/permissions.
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role user_role NOT NULL,
+    permission VARCHAR(50) NOT NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (role, permission)
+);
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('VIEWER', 'issues:read'),
+    ('VIEWER', 'sessions:read')
+ON CONFLICT DO NOTHING;
+
+COMMENT ON TABLE role_permissions IS 'Permissions granted to non-admin roles (ADMIN always has all)';
+COMMENT ON COLUMN role_permissions.permission IS 'resource:action, e.g. issues:write';
@@ -0,0 +1,12 @@
+-- Per-user category edit restrictions
+-- A user with no rows may edit every category; with rows, only those listed.
+
+CREATE TABLE IF NOT EXISTS user_category_access (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category VARCHAR(255) NOT NULL,
+    granted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, category)
+);
+
+COMMENT ON TABLE user_category_access IS 'Categories a user is restricted to editing (no rows = unrestricted)';
@@ -0,0 +1,19 @@
+-- Per-node change history
+-- One row per changed field, written by node updates and reverts. Layout
+-- (position_x/position_y) is not tracked.
+
+CREATE TABLE IF NOT EXISTS node_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    node_id UUID NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+    field VARCHAR(50) NOT NULL,
+    old_value JSONB NOT NULL,
+    new_value JSONB NOT NULL,
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set when this change undid an earlier revision
+    reverted_revision_id UUID REFERENCES node_revisions(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_node_revisions_node_time ON node_revisions(node_id, changed_at DESC);
+
+COMMENT ON TABLE node_revisions IS 'Field-level change history of nodes (old/new value, who, when)';
@@ -0,0 +1,5 @@
+-- Optional display name, editable by the user through PATCH /api/v1/auth/me
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS display_name VARCHAR(100);
+
+COMMENT ON COLUMN users.display_name IS 'Name shown in the UI instead of the email (NULL = not set)';
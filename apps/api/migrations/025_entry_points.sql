@@ -0,0 +1,20 @@
+-- Named entry points (alternative start nodes)
+-- Kiosks pass `entry_point` when starting a session to boot into the
+-- question of their choice instead of the global 'start' node.
+
+CREATE TABLE IF NOT EXISTS entry_points (
+    name VARCHAR(100) PRIMARY KEY,
+    node_id UUID NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+    description TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_entry_points_node ON entry_points(node_id);
+
+-- Entry point a session was started from (NULL = global start or direct category)
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS entry_point VARCHAR(100);
+
+COMMENT ON TABLE entry_points IS 'Named alternative start nodes selectable via StartSessionRequest.entry_point';
@@ -0,0 +1,25 @@
+-- Service accounts and their long-lived scoped tokens
+-- Service accounts are users that cannot sign in interactively; automation
+-- (CI import/export jobs) authenticates with a service token instead. Only
+-- the SHA-256 digest of a token is stored.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_service BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS service_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    -- First characters of the token, to recognise it in listings
+    token_prefix VARCHAR(16) NOT NULL,
+    scopes VARCHAR(50)[] NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    revoked_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_tokens_user ON service_tokens(user_id);
+
+COMMENT ON TABLE service_tokens IS 'Non-expiring, revocable API tokens for service accounts';
@@ -0,0 +1,12 @@
+-- Track the last client activity of a session
+-- Answers and heartbeats bump last_activity_at; a session counts as abandoned
+-- once it has been idle (not merely old) for an hour.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS last_activity_at TIMESTAMPTZ;
+
+UPDATE sessions SET last_activity_at = COALESCE(completed_at, started_at) WHERE last_activity_at IS NULL;
+
+ALTER TABLE sessions ALTER COLUMN last_activity_at SET DEFAULT NOW();
+ALTER TABLE sessions ALTER COLUMN last_activity_at SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_open_activity ON sessions(last_activity_at) WHERE completed_at IS NULL;
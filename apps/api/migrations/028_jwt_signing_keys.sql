@@ -0,0 +1,15 @@
+-- Rotated JWT signing keys
+-- Each key's random HMAC secret is stored encrypted (see 062). A new key
+-- starts signing at activates_at (giving every instance time to load it); the
+-- key it replaces is retired at the same moment and keeps verifying tokens for
+-- JWT_KEY_RETENTION_HOURS.
+
+CREATE TABLE IF NOT EXISTS jwt_signing_keys (
+    kid VARCHAR(64) PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    activates_at TIMESTAMPTZ NOT NULL,
+    retired_at TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_jwt_signing_keys_activates ON jwt_signing_keys(activates_at);
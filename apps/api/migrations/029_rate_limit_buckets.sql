@@ -0,0 +1,14 @@
+-- Rate limiter snapshots
+-- When RATE_LIMIT_SNAPSHOT_SECONDS is set, the in-memory per-IP buckets are
+-- written here periodically and restored on startup, so a restart does not
+-- hand every client a fresh quota. Rows outside the current window are
+-- pruned on each snapshot.
+
+CREATE TABLE IF NOT EXISTS rate_limit_buckets (
+    ip VARCHAR(45) PRIMARY KEY,
+    request_count INTEGER NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_buckets_window ON rate_limit_buckets(window_start);
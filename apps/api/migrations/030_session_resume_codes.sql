@@ -0,0 +1,8 @@
+-- Short resume codes
+-- Techs switching devices mid-diagnosis type a 6-character code instead of
+-- the session UUID. Codes use an alphabet without look-alike characters and
+-- only resolve for open sessions with recent activity.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS resume_code VARCHAR(6);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sessions_resume_code ON sessions(resume_code) WHERE resume_code IS NOT NULL;
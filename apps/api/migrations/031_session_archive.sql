@@ -0,0 +1,23 @@
+-- Session archive
+-- Old sessions are moved here by the archival task (SESSION_ARCHIVE_AFTER_DAYS)
+-- or POST /api/v1/admin/sessions/archive. The steps JSON is kept gzip-compressed;
+-- the columns the dashboard aggregates on are stored alongside it so stats keep
+-- covering archived sessions without decompressing anything.
+
+CREATE TABLE IF NOT EXISTS session_archive (
+    session_id VARCHAR(100) PRIMARY KEY,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    abandoned BOOLEAN NOT NULL,
+    last_activity_at TIMESTAMPTZ NOT NULL,
+    final_conclusion TEXT,
+    category TEXT,
+    step_count INTEGER NOT NULL,
+    tech_identifier VARCHAR(100),
+    client_site VARCHAR(100),
+    entry_point VARCHAR(100),
+    steps_gz BYTEA NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_archive_started ON session_archive(started_at);
@@ -0,0 +1,16 @@
+-- Threshold routing for measurement questions
+-- A connection may carry a numeric condition (e.g. ">= 11.5 and < 13.8").
+-- When a tech submits a reading, the first active connection from the node
+-- whose condition matches is followed; a connection without a condition is
+-- the fallback for readings no range covers.
+
+ALTER TABLE connections ADD COLUMN IF NOT EXISTS condition TEXT;
+
+-- The condition is content, so it bumps updated_at for incremental sync
+DROP TRIGGER IF EXISTS update_connections_updated_at ON connections;
+CREATE TRIGGER update_connections_updated_at
+    BEFORE UPDATE OF from_node_id, to_node_id, label, order_index, is_active, condition
+    ON connections
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN connections.condition IS 'Numeric range this answer covers when the node takes a measurement (NULL = plain answer / fallback)';
@@ -0,0 +1,18 @@
+-- Multi-select questions
+-- A checklist-style question ("which of these symptoms are present?") lets
+-- the tech pick several answers at once. The answer mode decides where the
+-- session goes next: the highest-priority pick only (first_match), or each
+-- pick in turn, returning to the queued ones after a conclusion (queue).
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS answer_mode VARCHAR(20) NOT NULL DEFAULT 'single';
+ALTER TABLE nodes DROP CONSTRAINT IF EXISTS nodes_answer_mode_valid;
+ALTER TABLE nodes ADD CONSTRAINT nodes_answer_mode_valid
+    CHECK (answer_mode IN ('single', 'first_match', 'queue'));
+
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, kb_url, doc_reference, option_order, answer_mode
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN nodes.answer_mode IS 'single, or multi-select routing: first_match or queue';
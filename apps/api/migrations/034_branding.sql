@@ -0,0 +1,19 @@
+-- Per-deployment branding
+-- One row holding what the public config endpoint serves, so a single
+-- frontend build can be branded per install without a rebuild.
+
+CREATE TABLE IF NOT EXISTS branding (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    product_name VARCHAR(100) NOT NULL,
+    logo_url TEXT,
+    support_email VARCHAR(255),
+    support_phone VARCHAR(50),
+    support_url TEXT,
+    features TEXT[] NOT NULL DEFAULT '{}',
+    locales TEXT[] NOT NULL DEFAULT '{en}',
+    default_locale VARCHAR(20) NOT NULL DEFAULT 'en',
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE branding IS 'Single-row deployment branding served by GET /api/v1/config/public';
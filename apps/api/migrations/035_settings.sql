@@ -0,0 +1,13 @@
+-- Runtime settings
+-- Admin overrides for knobs that used to need an environment edit and a
+-- restart. Settings without a row use their default (usually the matching
+-- environment variable).
+
+CREATE TABLE IF NOT EXISTS settings (
+    key VARCHAR(100) PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE settings IS 'Admin overrides of runtime settings; see GET /api/v1/admin/settings for the known keys';
@@ -0,0 +1,8 @@
+-- Equipment on sessions
+-- Kiosks can name the unit being worked on (asset tag or serial) so a second
+-- tech starting on the same unit is told about the session already open.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS equipment_id VARCHAR(100);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_open_equipment ON sessions(equipment_id, started_at)
+    WHERE equipment_id IS NOT NULL AND completed_at IS NULL;
@@ -0,0 +1,5 @@
+-- Captured session variables
+-- Values the tech enters along the way (serial number, error code, ...) so
+-- node text can refer to them as {{serial}} and conditions can test them.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS variables JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,24 @@
+-- Conclusion feedback
+-- After a conclusion the tech says whether it actually fixed the problem.
+-- Sessions remember which conclusion node they ended on so the dashboard can
+-- score each conclusion by how often it worked; archived sessions keep both
+-- uncompressed so the score survives archiving.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS conclusion_node_id UUID;
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS feedback_resolved BOOLEAN;
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS feedback_comment TEXT;
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS feedback_at TIMESTAMPTZ;
+
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS conclusion_node_id UUID;
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS feedback_resolved BOOLEAN;
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS feedback_comment TEXT;
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS feedback_at TIMESTAMPTZ;
+
+-- Completed sessions ended on the target of their last answer
+UPDATE sessions s
+SET conclusion_node_id = c.to_node_id
+FROM connections c
+WHERE s.completed_at IS NOT NULL
+  AND s.conclusion_node_id IS NULL
+  AND s.steps->-1->>'connection_id' ~ '^[0-9a-fA-F-]{36}$'
+  AND c.id = (s.steps->-1->>'connection_id')::uuid;
@@ -0,0 +1,17 @@
+-- Session step attachments
+-- Photos a technician takes during a session. The files are uploads owned by
+-- the session; this records which step each one belongs to. step_index is
+-- the number of answers the session had when the photo was attached, so a
+-- photo of the question on screen gets the index its answer will have. Rows
+-- outlive the session so archived sessions keep their photos.
+
+CREATE TABLE IF NOT EXISTS session_attachments (
+    upload_id UUID PRIMARY KEY REFERENCES uploads(id) ON DELETE CASCADE,
+    session_id VARCHAR(255) NOT NULL,
+    step_index INTEGER NOT NULL CHECK (step_index >= 0),
+    -- Question the photo was taken at
+    node_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_attachments_session ON session_attachments(session_id, step_index);
@@ -0,0 +1,10 @@
+-- Explicit abandonment
+-- Techs can give up on a session and say why ("equipment replaced",
+-- "escalated to vendor") instead of waiting for the idle timeout to count it
+-- as abandoned. Answering again reopens the session and clears both columns.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS abandoned_at TIMESTAMPTZ;
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS abandon_reason TEXT;
+
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS abandoned_at TIMESTAMPTZ;
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS abandon_reason TEXT;
@@ -0,0 +1,25 @@
+-- Registered kiosk devices
+-- Kiosks on a shared network all look like one IP, so each is registered and
+-- sends its own device token. The token gives it its own rate limit bucket
+-- and ties the sessions it starts to it. Only the SHA-256 digest of a token
+-- is stored.
+
+CREATE TABLE IF NOT EXISTS devices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    -- First characters of the token, to recognise it in listings
+    token_prefix VARCHAR(16) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ,
+    disabled_at TIMESTAMPTZ,
+    disabled_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Device that started the session (NULL for browsers without a token)
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS device_id UUID REFERENCES devices(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_device ON sessions(device_id) WHERE device_id IS NOT NULL;
+
+COMMENT ON TABLE devices IS 'Kiosks allowed to start troubleshooting sessions';
@@ -0,0 +1,22 @@
+-- Time-boxed category notices
+-- A temporary message shown to techs troubleshooting a category, e.g. a known
+-- firmware bug this week with the conclusion to skip to. Notices only show
+-- between starts_at and ends_at and expire on their own.
+
+CREATE TABLE IF NOT EXISTS category_notices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    category VARCHAR(100) NOT NULL,
+    message TEXT NOT NULL,
+    -- Conclusion the notice suggests going straight to, if any
+    conclusion_node_id UUID REFERENCES nodes(id) ON DELETE SET NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT category_notices_window CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_category_notices_active ON category_notices(category, ends_at);
+
+COMMENT ON TABLE category_notices IS 'Temporary messages shown in sessions for a category while active';
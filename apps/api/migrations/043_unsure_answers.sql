@@ -0,0 +1,10 @@
+-- Unsure answers
+-- Techs can mark an answer as unsure; the flag is kept on the session step.
+-- A question can have one conservative branch that unsure answers are sent
+-- down instead of the answer picked.
+
+ALTER TABLE connections ADD COLUMN IF NOT EXISTS unsure_route BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_connections_unsure_route ON connections(from_node_id) WHERE unsure_route;
+
+COMMENT ON COLUMN connections.unsure_route IS 'Followed instead of the picked answer when the tech marks it unsure';
@@ -0,0 +1,6 @@
+-- Session start node
+-- Sessions started on a category (or routed to a rollout candidate) begin at
+-- that category's root rather than the global start; remember which node so
+-- a session reloaded before its first answer is still at the right question.
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS start_node_id UUID REFERENCES nodes(id) ON DELETE SET NULL;
@@ -0,0 +1,15 @@
+-- SIEM forwarding checkpoints
+-- The forwarder sends audit log entries (and optionally session events) to a
+-- security monitoring endpoint. Each stream's position is kept here so a
+-- restart picks up where it left off, and the row lock keeps instances from
+-- forwarding the same entries twice.
+
+CREATE TABLE IF NOT EXISTS siem_cursors (
+    stream VARCHAR(50) PRIMARY KEY,
+    -- Timestamp and id of the last entry delivered
+    last_at TIMESTAMPTZ,
+    last_id TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE siem_cursors IS 'Position of the SIEM forwarder in each forwarded stream';
@@ -0,0 +1,17 @@
+-- "I'm not sure" fallback
+-- A question can offer techs an "I'm not sure" option that leads to a
+-- configured node: the first question of a diagnostic sub-flow or an
+-- escalation conclusion. Configured per question so editors don't have to
+-- wire an extra answer onto every question.
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS not_sure_node_id UUID REFERENCES nodes(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_nodes_not_sure_node_id ON nodes(not_sure_node_id) WHERE not_sure_node_id IS NOT NULL;
+
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN nodes.not_sure_node_id IS 'Where the question''s "I''m not sure" option leads: a sub-flow question or an escalation conclusion';
@@ -0,0 +1,24 @@
+-- Per-category daily session quotas
+-- Some categories lead to expensive field dispatches. A quota caps the new
+-- sessions a category can start each (UTC) day, with an optional warning
+-- threshold below the cap. Starts let through and sessions refused are
+-- counted per day in category_quota_usage.
+
+CREATE TABLE IF NOT EXISTS category_quotas (
+    category VARCHAR(255) PRIMARY KEY,
+    daily_limit INTEGER NOT NULL CHECK (daily_limit > 0),
+    warn_at INTEGER CHECK (warn_at > 0 AND warn_at <= daily_limit),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS category_quota_usage (
+    category VARCHAR(255) NOT NULL,
+    day DATE NOT NULL,
+    sessions INTEGER NOT NULL DEFAULT 0,
+    rejected INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (category, day)
+);
+
+COMMENT ON TABLE category_quotas IS 'Daily limits on new sessions per category';
+COMMENT ON TABLE category_quota_usage IS 'Sessions started and refused per category and UTC day, for categories with a quota';
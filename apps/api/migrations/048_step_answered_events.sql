@@ -0,0 +1,7 @@
+-- Step events in the session event stream
+-- Session events now go through the analytics sinks, which also see every
+-- answered step. The postgres sink stores those as 'step_answered'.
+
+ALTER TABLE session_events DROP CONSTRAINT IF EXISTS session_events_event_type_check;
+ALTER TABLE session_events ADD CONSTRAINT session_events_event_type_check
+    CHECK (event_type IN ('started', 'step_answered', 'completed', 'abandoned', 'feedback'));
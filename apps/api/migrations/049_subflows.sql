@@ -0,0 +1,26 @@
+-- Shared sub-flows
+-- A 'subflow' node stands in for another category's whole flow (e.g. a
+-- power supply check used by many issues): sessions reaching it continue at
+-- that category's start node. A 'return' node ends the sub-flow and sends
+-- the session back to the sub-flow node's answers; its text names the
+-- outcome, which picks the answer with the same label.
+
+ALTER TABLE nodes DROP CONSTRAINT IF EXISTS nodes_node_type_check;
+ALTER TABLE nodes ADD CONSTRAINT nodes_node_type_check
+    CHECK (node_type IN ('question', 'conclusion', 'subflow', 'return'));
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS subflow_category VARCHAR(255);
+
+ALTER TABLE nodes DROP CONSTRAINT IF EXISTS nodes_subflow_category_valid;
+ALTER TABLE nodes ADD CONSTRAINT nodes_subflow_category_valid
+    CHECK ((node_type = 'subflow') = (subflow_category IS NOT NULL));
+
+CREATE INDEX IF NOT EXISTS idx_nodes_subflow_category ON nodes(subflow_category) WHERE subflow_category IS NOT NULL;
+
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN nodes.subflow_category IS 'Category whose flow a subflow node runs before continuing with its own answers';
@@ -0,0 +1,15 @@
+-- Rich node content
+-- Markdown instructions, images and links shown with a node's text (e.g.
+-- illustrated repair steps on a conclusion). The plain text stays required as
+-- the fallback for clients that can't show rich content. The API sanitizes
+-- content before storing it.
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS content JSONB;
+
+DROP TRIGGER IF EXISTS update_nodes_updated_at ON nodes;
+CREATE TRIGGER update_nodes_updated_at
+    BEFORE UPDATE OF category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content
+    ON nodes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON COLUMN nodes.content IS 'Sanitized rich content: {markdown, images: [{url, alt, caption}], links: [{url, label}]}';
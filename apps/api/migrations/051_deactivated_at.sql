@@ -0,0 +1,39 @@
+-- When nodes and connections were switched off
+-- Inactive records can be restored, and purged once they have been inactive
+-- for a while. The trigger stamps the moment is_active turns false however
+-- the record is updated (editor, merge, orphan cleanup, import).
+
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+ALTER TABLE connections ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+
+-- Existing inactive records: their last update is the best guess
+UPDATE nodes SET deactivated_at = updated_at WHERE is_active = false AND deactivated_at IS NULL;
+UPDATE connections SET deactivated_at = updated_at WHERE is_active = false AND deactivated_at IS NULL;
+
+CREATE OR REPLACE FUNCTION track_deactivation()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.is_active THEN
+        NEW.deactivated_at = NULL;
+    ELSIF TG_OP = 'INSERT' OR OLD.is_active THEN
+        NEW.deactivated_at = NOW();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS track_nodes_deactivation ON nodes;
+CREATE TRIGGER track_nodes_deactivation
+    BEFORE INSERT OR UPDATE OF is_active ON nodes
+    FOR EACH ROW EXECUTE FUNCTION track_deactivation();
+
+DROP TRIGGER IF EXISTS track_connections_deactivation ON connections;
+CREATE TRIGGER track_connections_deactivation
+    BEFORE INSERT OR UPDATE OF is_active ON connections
+    FOR EACH ROW EXECUTE FUNCTION track_deactivation();
+
+CREATE INDEX IF NOT EXISTS idx_nodes_deactivated_at ON nodes(deactivated_at) WHERE deactivated_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_connections_deactivated_at ON connections(deactivated_at) WHERE deactivated_at IS NOT NULL;
+
+COMMENT ON COLUMN nodes.deactivated_at IS 'When the node was switched off (NULL while active)';
+COMMENT ON COLUMN connections.deactivated_at IS 'When the connection was switched off (NULL while active)';
@@ -0,0 +1,21 @@
+-- Semantic IDs unique per category
+-- Sessions find a category's start by `{category}_start` and conditions read
+-- answers by semantic ID, so a duplicate made either lookup pick a node at
+-- random. Existing duplicates keep the oldest node's ID; later ones get a
+-- suffix from their own UUID so they stay recognisable.
+
+UPDATE nodes SET semantic_id = NULLIF(TRIM(semantic_id), '')
+WHERE semantic_id IS DISTINCT FROM NULLIF(TRIM(semantic_id), '');
+
+UPDATE nodes n
+SET semantic_id = n.semantic_id || '_dup_' || LEFT(n.id::text, 8)
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY category, semantic_id ORDER BY created_at, id) AS position
+    FROM nodes
+    WHERE semantic_id IS NOT NULL
+) ranked
+WHERE ranked.id = n.id AND ranked.position > 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_nodes_category_semantic_id ON nodes(category, semantic_id);
+
+COMMENT ON INDEX idx_nodes_category_semantic_id IS 'A semantic ID names at most one node per category';
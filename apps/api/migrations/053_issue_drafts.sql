@@ -0,0 +1,22 @@
+-- Draft versions of issue graphs
+-- A draft is a copy of an issue in its own hidden category. Editors change
+-- the copy while sessions keep using the published graph; publishing writes
+-- the draft back over the nodes and answers it was copied from (so their IDs,
+-- history and attachments carry on), moves nodes added in the draft across
+-- and switches off what the draft removed.
+
+CREATE TABLE IF NOT EXISTS issue_drafts (
+    category VARCHAR(255) PRIMARY KEY,
+    draft_category VARCHAR(255) NOT NULL UNIQUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (category <> draft_category)
+);
+
+-- The published record a draft copy replaces when the draft is published
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS draft_origin_id UUID REFERENCES nodes(id) ON DELETE SET NULL;
+ALTER TABLE connections ADD COLUMN IF NOT EXISTS draft_origin_id UUID REFERENCES connections(id) ON DELETE SET NULL;
+
+COMMENT ON TABLE issue_drafts IS 'Unpublished draft of an issue, kept in draft_category';
+COMMENT ON COLUMN nodes.draft_origin_id IS 'Published node this draft copy replaces on publish';
+COMMENT ON COLUMN connections.draft_origin_id IS 'Published connection this draft copy replaces on publish';
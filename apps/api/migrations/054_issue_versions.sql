@@ -0,0 +1,26 @@
+-- Issue version history
+-- Every draft publish stores the issue's full node and connection rows as
+-- JSON, numbered per category, so two versions can be compared and an issue
+-- rolled back to any earlier one. The first publish also keeps the graph as
+-- it was before, so that publish can be undone too.
+
+CREATE TABLE IF NOT EXISTS issue_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    category VARCHAR(255) NOT NULL,
+    version INTEGER NOT NULL CHECK (version > 0),
+    -- baseline (graph before the first publish), publish or rollback
+    source VARCHAR(20) NOT NULL CHECK (source IN ('baseline', 'publish', 'rollback')),
+    -- Version a rollback restored
+    restored_version INTEGER,
+    nodes JSONB NOT NULL,
+    connections JSONB NOT NULL,
+    node_count INTEGER NOT NULL,
+    connection_count INTEGER NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (category, version)
+);
+
+COMMENT ON TABLE issue_versions IS 'Snapshot of an issue''s nodes and connections taken on publish and rollback';
+COMMENT ON COLUMN issue_versions.nodes IS 'Node rows (to_jsonb) in the issue when the version was taken';
+COMMENT ON COLUMN issue_versions.connections IS 'Connection rows (to_jsonb) leaving the issue''s nodes';
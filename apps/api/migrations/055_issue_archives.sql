@@ -0,0 +1,13 @@
+-- Issue archival
+-- An archived issue is kept with its graph and sessions but hidden from
+-- technicians: left out of the start menu, direct and entry point starts and
+-- the kiosk manifest. Unarchiving brings it back exactly as it was; node and
+-- answer active flags are left alone either way.
+
+CREATE TABLE IF NOT EXISTS issue_archives (
+    category VARCHAR(255) PRIMARY KEY,
+    archived_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE issue_archives IS 'Issues retired from technicians without deleting them';
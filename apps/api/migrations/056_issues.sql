@@ -0,0 +1,45 @@
+-- Issue metadata
+-- An issue used to be just a category of nodes, named by the start
+-- question's answer leading to it. Each issue now has a row here with its
+-- name, a long description, an owner and the equipment models and tags it
+-- applies to. The start question's answer still shows technicians the name;
+-- renaming an issue updates both.
+
+CREATE TABLE IF NOT EXISTS issues (
+    category VARCHAR(255) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    owner_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    equipment_models TEXT[] NOT NULL DEFAULT '{}',
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_issues_owner_id ON issues(owner_id) WHERE owner_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_issues_tags ON issues USING GIN (tags);
+CREATE INDEX IF NOT EXISTS idx_issues_equipment_models ON issues USING GIN (equipment_models);
+
+DROP TRIGGER IF EXISTS update_issues_updated_at ON issues;
+CREATE TRIGGER update_issues_updated_at
+    BEFORE UPDATE ON issues
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Existing issues keep the name technicians see on the start question
+INSERT INTO issues (category, name, created_at)
+SELECT DISTINCT ON (n.category)
+    n.category,
+    COALESCE(c.label, n.category),
+    COALESCE(n.created_at, NOW())
+FROM nodes n
+LEFT JOIN connections c ON c.to_node_id = n.id
+    AND c.from_node_id IN (SELECT id FROM nodes WHERE semantic_id = 'start')
+WHERE n.category NOT IN (SELECT draft_category FROM issue_drafts)
+ORDER BY n.category, n.created_at ASC, c.created_at ASC
+ON CONFLICT (category) DO NOTHING;
+
+COMMENT ON TABLE issues IS 'Issue name and metadata, keyed by the category holding its nodes';
+COMMENT ON COLUMN issues.name IS 'Issue name; also the label of the start question''s answer leading to the issue';
+COMMENT ON COLUMN issues.equipment_models IS 'Equipment models the issue applies to';
+COMMENT ON COLUMN issues.tags IS 'Lowercase tags for finding and grouping issues';
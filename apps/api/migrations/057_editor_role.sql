@@ -0,0 +1,6 @@
+-- EDITOR role
+-- Editors change issues through drafts that an approver reviews before they
+-- are published. A new enum value can't be used in the transaction that adds
+-- it, so its grants are seeded by the next migration.
+
+ALTER TYPE user_role ADD VALUE IF NOT EXISTS 'EDITOR';
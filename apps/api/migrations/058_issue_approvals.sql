@@ -0,0 +1,39 @@
+-- Issue approval workflow
+-- An editor submits an issue's draft for approval; someone else holding
+-- issues:approve approves it (publishing the draft) or rejects it with a
+-- comment. The draft's content is fingerprinted on submission so changes
+-- made afterwards can't be approved unseen.
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('EDITOR', 'issues:read'),
+    ('EDITOR', 'issues:write'),
+    ('EDITOR', 'sessions:read'),
+    ('EDITOR', 'uploads:read'),
+    ('EDITOR', 'uploads:write')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS issue_approvals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    category VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'withdrawn')),
+    -- Submitter's note to the reviewer
+    comment TEXT,
+    draft_fingerprint TEXT NOT NULL,
+    submitted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMPTZ,
+    -- Reviewer's reason for the decision (required when rejecting)
+    decision_comment TEXT,
+    -- Version the approved draft was published as
+    version INTEGER
+);
+
+-- At most one submission waiting per issue
+CREATE UNIQUE INDEX IF NOT EXISTS idx_issue_approvals_pending
+    ON issue_approvals (category) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_issue_approvals_category ON issue_approvals (category, submitted_at DESC);
+
+COMMENT ON TABLE issue_approvals IS 'Drafts submitted for approval before publishing';
+COMMENT ON COLUMN issue_approvals.draft_fingerprint IS 'Hash of the draft''s nodes and answers when submitted';
@@ -0,0 +1,23 @@
+-- Scheduled issue activation
+-- Seasonal issues (heaters in winter, chillers in summer) can be given a
+-- window. Outside it the issue is never offered to technicians, and a
+-- background job flips the issue's active flag as each boundary passes so
+-- the admin list matches. `schedule_applied` is the state the job last
+-- applied (NULL until it first runs, or once the schedule is cleared), so a
+-- manual toggle in between sticks until the next boundary.
+
+ALTER TABLE issues
+    ADD COLUMN IF NOT EXISTS active_from TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS active_until TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS schedule_applied BOOLEAN;
+
+ALTER TABLE issues DROP CONSTRAINT IF EXISTS issues_schedule_order;
+ALTER TABLE issues ADD CONSTRAINT issues_schedule_order
+    CHECK (active_from IS NULL OR active_until IS NULL OR active_until > active_from);
+
+CREATE INDEX IF NOT EXISTS idx_issues_scheduled ON issues(category)
+    WHERE active_from IS NOT NULL OR active_until IS NOT NULL;
+
+COMMENT ON COLUMN issues.active_from IS 'Issue is hidden from technicians before this time';
+COMMENT ON COLUMN issues.active_until IS 'Issue is hidden from technicians from this time on';
+COMMENT ON COLUMN issues.schedule_applied IS 'Active state last set by the schedule job';
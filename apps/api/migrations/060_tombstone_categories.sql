@@ -0,0 +1,24 @@
+-- Keep the category on cascade-deleted connection tombstones
+-- Deleting a node cascades to its connections after the node row is gone, so
+-- the connection trigger couldn't look up a category and stored NULL, and
+-- incremental exports had to ship every category's orphaned tombstones.
+-- Record the node's tombstone before the delete so the cascade can fall back
+-- to it. Existing NULL tombstones can't be attributed and are left out of exports.
+
+CREATE OR REPLACE FUNCTION record_connection_deletion()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO deleted_records (entity_type, entity_id, category)
+    VALUES ('connection', OLD.id, COALESCE(
+        (SELECT category FROM nodes WHERE id = OLD.from_node_id),
+        (SELECT category FROM deleted_records
+         WHERE entity_type = 'node' AND entity_id = OLD.from_node_id
+         ORDER BY id DESC LIMIT 1)
+    ));
+    RETURN OLD;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS record_nodes_deleted ON nodes;
+CREATE TRIGGER record_nodes_deleted BEFORE DELETE ON nodes
+    FOR EACH ROW EXECUTE FUNCTION record_node_deletion();
@@ -0,0 +1,9 @@
+-- Keep every session column through archival
+-- Equipment, captured variables, the kiosk and the start node were added to
+-- sessions after the archive table and were lost when a session moved there.
+-- No foreign keys: the archive outlives the devices and nodes it mentions.
+
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS equipment_id VARCHAR(100);
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS variables JSONB NOT NULL DEFAULT '{}'::jsonb;
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS device_id UUID;
+ALTER TABLE session_archive ADD COLUMN IF NOT EXISTS start_node_id UUID;
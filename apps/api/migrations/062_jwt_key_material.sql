@@ -0,0 +1,12 @@
+-- Rotated JWT keys get their own random secret
+-- Stored encrypted (AES-256-GCM, nonce first) under JWT_KEY_ENCRYPTION_KEY,
+-- which is kept apart from JWT_SECRET. Keys created before this migration
+-- were derived from JWT_SECRET and have no stored secret; they are retired
+-- so signing falls back to JWT_SECRET until the next rotation.
+
+ALTER TABLE jwt_signing_keys ADD COLUMN IF NOT EXISTS encrypted_secret BYTEA;
+
+UPDATE jwt_signing_keys SET retired_at = NOW()
+WHERE encrypted_secret IS NULL AND (retired_at IS NULL OR retired_at > NOW());
+
+COMMENT ON COLUMN jwt_signing_keys.encrypted_secret IS 'HMAC secret encrypted under JWT_KEY_ENCRYPTION_KEY';
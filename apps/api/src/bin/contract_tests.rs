@@ -0,0 +1,685 @@
+//! OpenAPI contract tests against a running server
+//!
+//! Loads the OpenAPI document (generated from `ApiDoc`, or `CONTRACT_SPEC`)
+//! and probes every documented endpoint:
+//! - endpoints from the Markdown tables in the description: the route exists,
+//!   the documented auth matches (🔑/✅ reject anonymous calls with 401,
+//!   ❌ endpoints don't), permissions are real and nothing answers with a 5xx
+//! - operations under `paths`: schema-valid and schema-invalid request bodies
+//!   get the documented status codes, and JSON responses match their schemas
+//!
+//! Usage (server already running):
+//!   CONTRACT_BASE_URL=http://localhost:5000 \
+//!   CONTRACT_EMAIL=admin@example.com CONTRACT_PASSWORD=... \
+//!   cargo run --bin contract_tests
+//!
+//! Without credentials only anonymous checks run. Requests that could change
+//! data (authenticated POST/PUT/PATCH/DELETE, public writes with valid
+//! bodies) are skipped unless CONTRACT_MUTATIONS=1 — only set that against a
+//! disposable database. Exits with status 1 when any check fails.
+use equipment_troubleshooting::models::Permission;
+use equipment_troubleshooting::openapi::ApiDoc;
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Map, Value};
+use std::env;
+use utoipa::OpenApi;
+
+/// Placeholder for `:id`-style path parameters (never matches a real row)
+const PLACEHOLDER_ID: &str = "00000000-0000-0000-0000-000000000000";
+/// Placeholder for other path parameters
+const PLACEHOLDER_NAME: &str = "contract-test-missing";
+/// Times a request is retried after a 429
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Access level documented in the "Auth Required" column
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Access {
+    Public,
+    Authenticated,
+    Permission(String),
+}
+
+/// One documented endpoint
+#[derive(Debug, Clone)]
+struct Operation {
+    method: Method,
+    /// Concrete request path with placeholders filled in
+    path: String,
+    /// Path as documented
+    documented: String,
+    access: Access,
+    /// JSON request body schema (operations under `paths` only)
+    request_schema: Option<Value>,
+    /// Documented status codes with their JSON response schemas
+    responses: Vec<(String, Option<Value>)>,
+}
+
+struct Harness {
+    client: Client,
+    base_url: String,
+    token: Option<String>,
+    mutations: bool,
+    spec: Value,
+    passed: usize,
+    failed: Vec<String>,
+    skipped: usize,
+}
+
+struct Reply {
+    status: StatusCode,
+    content_type: String,
+    body: Option<Value>,
+}
+
+impl Reply {
+    /// The router has no such route: SPA fallback (HTML or bare 404) or 405
+    fn route_missing(&self) -> bool {
+        self.status == StatusCode::METHOD_NOT_ALLOWED
+            || self.content_type.starts_with("text/html")
+            || (self.status == StatusCode::NOT_FOUND && self.body.is_none())
+    }
+
+    fn error_type(&self) -> Option<&str> {
+        self.body.as_ref()?.pointer("/error/type")?.as_str()
+    }
+}
+
+/// Map a documented path to the served one (`/api/...` is mounted at `/api/v1/...`)
+fn served_path(documented: &str) -> String {
+    let path = documented.split('?').next().unwrap_or(documented).trim();
+    let path = match path.strip_prefix("/api/") {
+        Some(rest) if !rest.starts_with("v1/") => format!("/api/v1/{}", rest),
+        _ => path.to_string(),
+    };
+
+    path.split('/')
+        .map(|segment| {
+            let name = segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')));
+            match name {
+                Some(name) if name == "id" || name.ends_with("_id") => PLACEHOLDER_ID.to_string(),
+                Some("role") => "Viewer".to_string(),
+                Some(_) => PLACEHOLDER_NAME.to_string(),
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Parse the access column of an endpoint table row
+fn parse_access(column: &str) -> Option<Access> {
+    let column = column.trim();
+    if column.starts_with('❌') {
+        Some(Access::Public)
+    } else if column.starts_with('🔑') {
+        let permission = column.trim_start_matches('🔑').trim().trim_matches('`');
+        Some(Access::Permission(permission.to_string()))
+    } else if column.starts_with('✅') {
+        Some(Access::Authenticated)
+    } else {
+        None
+    }
+}
+
+/// Endpoints from the Markdown tables (`| \`GET\` | \`/api/...\` | ... | access |`)
+fn table_operations(description: &str) -> Vec<Operation> {
+    description
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.trim().trim_matches('|').split('|').map(str::trim).collect();
+            if columns.len() < 4 {
+                return None;
+            }
+            let method = columns[0].trim_matches('`').parse::<Method>().ok()?;
+            let documented = columns[1].trim_matches('`');
+            if !documented.starts_with('/') || !columns[0].starts_with('`') {
+                return None;
+            }
+            let access = parse_access(columns[columns.len() - 1])?;
+
+            Some(Operation {
+                method,
+                path: served_path(documented),
+                documented: documented.to_string(),
+                access,
+                request_schema: None,
+                responses: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Resolve `#/components/schemas/...` references
+fn resolve<'a>(spec: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => reference
+            .strip_prefix('#')
+            .and_then(|pointer| spec.pointer(pointer))
+            .map(|target| resolve(spec, target))
+            .unwrap_or(schema),
+        None => schema,
+    }
+}
+
+/// Operations declared under `paths`
+fn path_operations(spec: &Value) -> Vec<Operation> {
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut operations = Vec::new();
+    for (documented, item) in paths {
+        let Some(item) = item.as_object() else { continue };
+        for (method, operation) in item {
+            let Ok(method) = method.to_uppercase().parse::<Method>() else { continue };
+            let secured = operation
+                .get("security")
+                .or_else(|| spec.get("security"))
+                .and_then(Value::as_array)
+                .is_some_and(|s| !s.is_empty());
+
+            let request_schema = operation
+                .pointer("/requestBody/content/application~1json/schema")
+                .map(|s| resolve(spec, s).clone());
+            let responses = operation
+                .get("responses")
+                .and_then(Value::as_object)
+                .map(|responses| {
+                    responses
+                        .iter()
+                        .map(|(status, response)| {
+                            let response = resolve(spec, response);
+                            let schema = response
+                                .pointer("/content/application~1json/schema")
+                                .map(|s| resolve(spec, s).clone());
+                            (status.clone(), schema)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            operations.push(Operation {
+                method,
+                path: served_path(documented),
+                documented: documented.clone(),
+                access: if secured { Access::Authenticated } else { Access::Public },
+                request_schema,
+                responses,
+            });
+        }
+    }
+    operations
+}
+
+/// A minimal value that satisfies a schema
+fn valid_value(spec: &Value, schema: &Value) -> Value {
+    let schema = resolve(spec, schema);
+    if let Some(example) = schema.get("example") {
+        return example.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|e| e.first()) {
+        return first.clone();
+    }
+    if let Some(variant) = ["oneOf", "anyOf", "allOf"]
+        .iter()
+        .find_map(|k| schema.get(*k).and_then(Value::as_array).and_then(|v| v.first()))
+    {
+        return valid_value(spec, variant);
+    }
+
+    match schema.get("type").and_then(Value::as_str) {
+        Some("object") | None if schema.get("properties").is_some() => {
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|r| r.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut object = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    if required.contains(&name.as_str()) {
+                        object.insert(name.clone(), valid_value(spec, property));
+                    }
+                }
+            }
+            Value::Object(object)
+        }
+        Some("object") => json!({}),
+        Some("array") => json!([]),
+        Some("integer") => json!(schema.get("minimum").and_then(Value::as_i64).unwrap_or(1)),
+        Some("number") => json!(schema.get("minimum").and_then(Value::as_f64).unwrap_or(1.0)),
+        Some("boolean") => json!(true),
+        Some("string") => match schema.get("format").and_then(Value::as_str) {
+            Some("uuid") => json!(PLACEHOLDER_ID),
+            Some("date-time") => json!("2024-01-01T00:00:00Z"),
+            Some("email") => json!("contract-test@example.com"),
+            _ => json!("contract test"),
+        },
+        _ => Value::Null,
+    }
+}
+
+/// Payloads that violate a request schema, with a description of each
+fn invalid_values(spec: &Value, schema: &Value) -> Vec<(String, Value)> {
+    let schema = resolve(spec, schema);
+    let mut cases = vec![("wrong top-level type".to_string(), json!("not an object"))];
+
+    let valid = valid_value(spec, schema);
+    if let (Some(object), Some(properties)) =
+        (valid.as_object(), schema.get("properties").and_then(Value::as_object))
+    {
+        for name in object.keys() {
+            let mut missing = object.clone();
+            missing.remove(name);
+            cases.push((format!("missing required '{}'", name), Value::Object(missing)));
+
+            let wrong = match resolve(spec, &properties[name]).get("type").and_then(Value::as_str) {
+                Some("string") => json!(12345),
+                _ => json!("wrong type"),
+            };
+            let mut mistyped = object.clone();
+            mistyped.insert(name.clone(), wrong);
+            cases.push((format!("wrong type for '{}'", name), Value::Object(mistyped)));
+        }
+    }
+    cases
+}
+
+/// Check a value against a schema; returns the first mismatch
+fn validate(spec: &Value, schema: &Value, value: &Value, at: &str) -> Result<(), String> {
+    let schema = resolve(spec, schema);
+    if value.is_null() && schema.get("nullable").and_then(Value::as_bool).unwrap_or(false) {
+        return Ok(());
+    }
+    if let Some(variants) = schema.get("oneOf").or_else(|| schema.get("anyOf")).and_then(Value::as_array) {
+        return if variants.iter().any(|v| validate(spec, v, value, at).is_ok()) {
+            Ok(())
+        } else {
+            Err(format!("{}: matches none of the allowed schemas", at))
+        };
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        return parts.iter().try_for_each(|p| validate(spec, p, value, at));
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!("{}: {} is not one of {:?}", at, value, allowed));
+        }
+    }
+
+    let type_ok = match schema.get("type").and_then(Value::as_str) {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string(),
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        _ => true,
+    };
+    if !type_ok {
+        return Err(format!("{}: expected {}, got {}", at, schema["type"], value));
+    }
+
+    if let Some(object) = value.as_object() {
+        for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            let name = required.as_str().unwrap_or_default();
+            if !object.contains_key(name) {
+                return Err(format!("{}: missing required '{}'", at, name));
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                if let Some(field) = object.get(name) {
+                    validate(spec, property, field, &format!("{}.{}", at, name))?;
+                }
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            validate(spec, items, item, &format!("{}[{}]", at, i))?;
+        }
+    }
+    Ok(())
+}
+
+impl Harness {
+    async fn send(&self, op: &Operation, authenticated: bool, body: Option<RawBody>) -> Result<Reply, String> {
+        let url = format!("{}{}", self.base_url, op.path);
+        let mut attempts = 0;
+        let response = loop {
+            let mut request = self.client.request(op.method.clone(), &url);
+            if authenticated {
+                if let Some(token) = &self.token {
+                    request = request.bearer_auth(token);
+                }
+            }
+            match &body {
+                Some(RawBody::Json(value)) => request = request.json(value),
+                Some(RawBody::Malformed) => {
+                    request = request.header("content-type", "application/json").body("{\"contract\": ");
+                }
+                None => {}
+            }
+
+            let response = request.send().await.map_err(|e| format!("request failed: {}", e))?;
+
+            // Wait out the per-IP rate limit instead of reporting it as drift
+            attempts += 1;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || attempts > MAX_RATE_LIMIT_RETRIES {
+                break response;
+            }
+            let wait = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(5)
+                .clamp(1, 120);
+            println!("  ⏳ rate limited, retrying in {}s", wait);
+            tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+        };
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let bytes = response.bytes().await.map_err(|e| format!("reading body failed: {}", e))?;
+        let body = content_type
+            .starts_with("application/json")
+            .then(|| serde_json::from_slice(&bytes).ok())
+            .flatten();
+
+        Ok(Reply { status, content_type, body })
+    }
+
+    fn record(&mut self, op: &Operation, check: &str, outcome: Result<(), String>) {
+        let label = format!("{} {} — {}", op.method, op.documented, check);
+        match outcome {
+            Ok(()) => {
+                self.passed += 1;
+                println!("  ✅ {}", label);
+            }
+            Err(reason) => {
+                println!("  ❌ {}: {}", label, reason);
+                self.failed.push(format!("{}: {}", label, reason));
+            }
+        }
+    }
+
+    fn is_write(op: &Operation) -> bool {
+        op.method != Method::GET && op.method != Method::HEAD
+    }
+
+    /// Checks shared by every documented endpoint
+    async fn check_access(&mut self, op: &Operation) {
+        if let Access::Permission(permission) = &op.access {
+            let outcome = match Permission::parse(permission) {
+                Some(_) => Ok(()),
+                None => Err(format!("'{}' is not a known permission", permission)),
+            };
+            self.record(op, "documented permission exists", outcome);
+        }
+
+        // Anonymous probe; writes only send a malformed body so handlers never run
+        let body = Self::is_write(op).then_some(RawBody::Malformed);
+        let outcome = match self.send(op, false, body).await {
+            Err(e) => Err(e),
+            Ok(reply) if reply.route_missing() => Err(format!("route not served ({})", reply.status)),
+            Ok(reply) if reply.status.is_server_error() => Err(format!("server error {}", reply.status)),
+            Ok(reply) => match &op.access {
+                Access::Public if reply.status == StatusCode::UNAUTHORIZED => {
+                    Err("documented as public but answered 401".to_string())
+                }
+                Access::Authenticated | Access::Permission(_) if reply.status != StatusCode::UNAUTHORIZED => Err(
+                    format!("documented as requiring auth but answered {} without a token", reply.status),
+                ),
+                _ => Ok(()),
+            },
+        };
+        self.record(op, "route and anonymous access", outcome);
+
+        if self.token.is_none() || op.access == Access::Public {
+            return;
+        }
+        if Self::is_write(op) && !self.mutations {
+            self.skipped += 1;
+            return;
+        }
+
+        // Authenticated probe (admin holds every permission)
+        let body = Self::is_write(op).then_some(RawBody::Malformed);
+        let outcome = match self.send(op, true, body).await {
+            Err(e) => Err(e),
+            Ok(reply) if reply.route_missing() => Err(format!("route not served ({})", reply.status)),
+            Ok(reply) if reply.status.is_server_error() => Err(format!("server error {}", reply.status)),
+            Ok(reply) if matches!(reply.status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                Err(format!("admin token rejected with {} ({:?})", reply.status, reply.error_type()))
+            }
+            Ok(_) => Ok(()),
+        };
+        self.record(op, "authenticated access", outcome);
+    }
+
+    /// Schema-driven fuzzing for operations under `paths`
+    async fn check_schemas(&mut self, op: &Operation) {
+        let authenticated = op.access != Access::Public;
+        if authenticated && self.token.is_none() {
+            self.skipped += 1;
+            return;
+        }
+
+        if let Some(schema) = op.request_schema.clone() {
+            let spec = self.spec.clone();
+            for (case, payload) in invalid_values(&spec, &schema) {
+                let outcome = match self.send(op, authenticated, Some(RawBody::Json(payload))).await {
+                    Err(e) => Err(e),
+                    Ok(reply) if reply.status.is_client_error() && reply.status != StatusCode::UNAUTHORIZED => Ok(()),
+                    Ok(reply) => Err(format!("expected a 4xx rejection, got {}", reply.status)),
+                };
+                self.record(op, &format!("rejects {}", case), outcome);
+            }
+
+            if !self.mutations {
+                self.skipped += 1;
+                return;
+            }
+            let payload = valid_value(&spec, &schema);
+            let reply = self.send(op, authenticated, Some(RawBody::Json(payload))).await;
+            self.check_response(op, "schema-valid body", reply);
+        } else if !Self::is_write(op) || self.mutations {
+            let reply = self.send(op, authenticated, None).await;
+            self.check_response(op, "documented response", reply);
+        } else {
+            self.skipped += 1;
+        }
+    }
+
+    /// The status is documented and a JSON body matches that status's schema
+    fn check_response(&mut self, op: &Operation, check: &str, reply: Result<Reply, String>) {
+        let outcome = reply.and_then(|reply| {
+            let status = reply.status.as_str().to_string();
+            let class = format!("{}XX", &status[..1]);
+            let documented = op
+                .responses
+                .iter()
+                .find(|(code, _)| *code == status || code.eq_ignore_ascii_case(&class) || code == "default");
+
+            match (documented, &reply.body) {
+                (None, _) if op.responses.is_empty() => Ok(()),
+                (None, _) => Err(format!("undocumented status {}", reply.status)),
+                (Some((_, Some(schema))), Some(body)) => validate(&self.spec, schema, body, "body"),
+                (Some((_, Some(_))), None) => Err(format!("expected a JSON body, got '{}'", reply.content_type)),
+                (Some(_), _) => Ok(()),
+            }
+        });
+        self.record(op, check, outcome);
+    }
+}
+
+enum RawBody {
+    Json(Value),
+    Malformed,
+}
+
+async fn login(client: &Client, base_url: &str) -> Result<Option<String>, String> {
+    if let Ok(token) = env::var("CONTRACT_TOKEN") {
+        return Ok(Some(token));
+    }
+    let (Ok(email), Ok(password)) = (env::var("CONTRACT_EMAIL"), env::var("CONTRACT_PASSWORD")) else {
+        return Ok(None);
+    };
+
+    let response = client
+        .post(format!("{}/api/v1/auth/login", base_url))
+        .json(&json!({ "email": email, "password": password }))
+        .send()
+        .await
+        .map_err(|e| format!("login request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("login failed with {}", response.status()));
+    }
+    let body: Value = response.json().await.map_err(|e| format!("login response unreadable: {}", e))?;
+    Ok(body.get("token").and_then(Value::as_str).map(str::to_string))
+}
+
+fn load_spec() -> Result<Value, String> {
+    match env::var("CONTRACT_SPEC") {
+        Ok(path) => {
+            let contents = std::fs::read_to_string(&path).map_err(|e| format!("can't read {}: {}", path, e))?;
+            serde_json::from_str(&contents).map_err(|e| format!("{} is not valid JSON: {}", path, e))
+        }
+        Err(_) => serde_json::to_value(ApiDoc::openapi()).map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let base_url = env::var("CONTRACT_BASE_URL")
+        .unwrap_or_else(|_| "http://localhost:5000".to_string())
+        .trim_end_matches('/')
+        .to_string();
+    let mutations = env::var("CONTRACT_MUTATIONS").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+
+    let spec = load_spec().unwrap_or_else(|e| {
+        eprintln!("❌ Failed to load OpenAPI document: {}", e);
+        std::process::exit(2);
+    });
+
+    let client = Client::new();
+    let token = login(&client, &base_url).await.unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(2);
+    });
+
+    let description = spec.pointer("/info/description").and_then(Value::as_str).unwrap_or_default();
+    let tables = table_operations(description);
+    let paths = path_operations(&spec);
+
+    println!("🧪 Contract tests against {}", base_url);
+    println!(
+        "   {} documented endpoints, {} schema operations, {}, mutations {}",
+        tables.len(),
+        paths.len(),
+        if token.is_some() { "authenticated" } else { "anonymous only" },
+        if mutations { "enabled" } else { "disabled" }
+    );
+
+    let mut harness = Harness {
+        client,
+        base_url,
+        token,
+        mutations,
+        spec,
+        passed: 0,
+        failed: Vec::new(),
+        skipped: 0,
+    };
+
+    for op in &tables {
+        harness.check_access(op).await;
+    }
+    for op in &paths {
+        harness.check_schemas(op).await;
+    }
+
+    println!();
+    println!(
+        "📊 {} passed, {} failed, {} skipped",
+        harness.passed,
+        harness.failed.len(),
+        harness.skipped
+    );
+    if !harness.failed.is_empty() {
+        for failure in &harness.failed {
+            println!("   ❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_operations() {
+        let description = "
+| Method | Endpoint | Description | Auth Required |
+|--------|----------|-------------|---------------|
+| `GET` | `/health` | Health check | ❌ No |
+| `PUT` | `/api/admin/users/:id/role` | Change a user's role | 🔑 `users:manage` |
+| `GET` | `/api/admin/uploads?owner_type=&owner_id=` | List uploads | 🔑 `uploads:read` |
+| `POST` | `/api/auth/change-password` | Change own password | ✅ Yes |
+| 🔑 Permissions | Admin endpoints require permissions |
+";
+        let ops = table_operations(description);
+        assert_eq!(ops.len(), 4);
+        assert_eq!(ops[0].path, "/health");
+        assert_eq!(ops[0].access, Access::Public);
+        assert_eq!(ops[1].path, format!("/api/v1/admin/users/{}/role", PLACEHOLDER_ID));
+        assert_eq!(ops[1].access, Access::Permission("users:manage".to_string()));
+        assert_eq!(ops[2].path, "/api/v1/admin/uploads");
+        assert_eq!(ops[3].access, Access::Authenticated);
+    }
+
+    #[test]
+    fn test_generated_payloads_follow_schema() {
+        let spec = json!({
+            "components": { "schemas": { "Login": {
+                "type": "object",
+                "required": ["email", "remember"],
+                "properties": {
+                    "email": { "type": "string", "format": "email" },
+                    "remember": { "type": "boolean" },
+                    "note": { "type": "string" }
+                }
+            }}}
+        });
+        let schema = json!({ "$ref": "#/components/schemas/Login" });
+
+        let valid = valid_value(&spec, &schema);
+        assert_eq!(valid, json!({ "email": "contract-test@example.com", "remember": true }));
+        assert!(validate(&spec, &schema, &valid, "body").is_ok());
+
+        let invalid = invalid_values(&spec, &schema);
+        assert_eq!(invalid.len(), 5);
+        for (case, payload) in invalid {
+            assert!(validate(&spec, &schema, &payload, "body").is_err(), "{} should be invalid", case);
+        }
+    }
+
+    #[test]
+    fn test_served_path_fills_placeholders() {
+        assert_eq!(served_path("/api/v1/uploads/:id/content?expires="), format!("/api/v1/uploads/{}/content", PLACEHOLDER_ID));
+        assert_eq!(served_path("/api/admin/issues/{category}/graph"), format!("/api/v1/admin/issues/{}/graph", PLACEHOLDER_NAME));
+        assert_eq!(served_path("/api/admin/roles/:role/permissions"), "/api/v1/admin/roles/Viewer/permissions");
+    }
+}
@@ -0,0 +1,1858 @@
+/// Troubleshooting engine
+///
+/// Walks a tech through the node graph: starting a session at a category,
+/// entry point or the global start; following answers, readings,
+/// multi-select picks and "I'm not sure"; skipping to a conclusion; going
+/// back; and working out where a session stands. Sub-flow nodes are passed
+/// through: the session runs the sub-flow's category and comes back out at
+/// one of its returns. It also checks that an
+/// issue's graph can be walked before the issue goes live. Sessions are kept
+/// in the `sessions` table and every step goes to the session watchers and
+/// analytics sinks, whichever way the engine is driven.
+///
+/// The HTTP routes in `routes::troubleshoot` are thin wrappers around the
+/// functions here, so another service can embed the engine with nothing but
+/// an [`AppState`] built from its own pool:
+///
+/// ```ignore
+/// let state = AppState::new(db);
+/// let started = engine::start(&state, request, SessionOrigin::default()).await?;
+/// let next = engine::answer(&state, started.session_id, answer).await?;
+/// ```
+///
+/// Errors are [`ApiError`]s; `ApiError::status_code` tells a caller whether
+/// the request or the server was at fault.
+use crate::error::{ApiError, ApiResult};
+use crate::uploads::{self, NodeAttachment};
+use crate::models::{AnswerMode, Connection, Node, NodeContent, NodeType, OptionOrder};
+use crate::utils::category_notices::{self, CategoryNotice};
+use crate::utils::category_quotas::{self, QuotaClaim};
+use crate::utils::expression::{self, Value, Variables};
+use crate::utils::graph_validation::{self, GraphValidationReport};
+use crate::utils::remaining_depth::{self, RemainingDepth};
+use crate::utils::session_events::{self, SessionEventType};
+use crate::utils::session_watch::{self, SessionChangeKind};
+use crate::utils::{conditions, rollout, template};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Request to start a new troubleshooting session
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StartSessionRequest {
+    pub tech_identifier: Option<String>,
+    pub client_site: Option<String>,
+    pub category: Option<String>, // Optional: for direct category access
+    /// Named entry point to start from instead of the global start node
+    #[ts(optional)]
+    pub entry_point: Option<String>,
+    /// Asset tag or serial of the unit being worked on
+    #[serde(default)]
+    #[ts(optional)]
+    pub equipment_id: Option<String>,
+    /// Values captured up front, such as a serial number
+    #[serde(default)]
+    #[ts(optional)]
+    pub variables: Option<Captured>,
+}
+
+/// Response when starting a session (NODE-GRAPH VERSION)
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StartSessionResponse {
+    pub session_id: String,
+    /// Short code for continuing the session on another device
+    pub resume_code: String,
+    pub node: Node,
+    pub options: Vec<NavigationOption>,
+    /// Another open session on the same equipment started shortly before
+    /// this one; the tech may want to continue it instead
+    pub duplicate_of: Option<DuplicateSession>,
+    /// Active notices on the start node's category
+    pub notices: Vec<CategoryNotice>,
+    /// Reference files attached to the start node
+    pub attachments: Vec<NodeAttachment>,
+}
+
+/// An open session on the same equipment
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct DuplicateSession {
+    pub session_id: String,
+    /// Code for continuing that session on this device
+    pub resume_code: Option<String>,
+    pub started_at: String,
+    pub tech_identifier: Option<String>,
+}
+
+/// Navigation option (connection to next node)
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NavigationOption {
+    pub connection_id: Uuid,
+    pub label: String,
+    /// Reading range this option covers; when a question's options have
+    /// conditions the client asks for a number instead of showing buttons.
+    /// Conditions on earlier answers are settled server-side and not sent.
+    #[serde(default)]
+    pub condition: Option<String>,
+    pub target_category: String,
+    pub display_category: Option<String>,
+}
+
+/// Request to submit an answer (NODE-GRAPH VERSION)
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SubmitAnswerRequest {
+    /// The chosen answer (omit when submitting a measurement)
+    #[serde(default)]
+    #[ts(optional)]
+    pub connection_id: Option<Uuid>,
+    /// Measurement question being answered (required with `value`)
+    #[serde(default)]
+    #[ts(optional)]
+    pub node_id: Option<Uuid>,
+    /// Numeric reading; routed to the first connection whose condition matches
+    #[serde(default)]
+    #[ts(optional)]
+    pub value: Option<f64>,
+    /// Several answers on a multi-select question
+    #[serde(default)]
+    #[ts(optional)]
+    pub connection_ids: Option<Vec<Uuid>>,
+    /// Values captured at this question, such as an error code; node text
+    /// shows them as `{{name}}` and conditions can test them. A blank value
+    /// clears the variable.
+    #[serde(default)]
+    #[ts(optional)]
+    pub variables: Option<Captured>,
+    /// The tech isn't confident in this answer; recorded on the step, and
+    /// sent down the question's conservative branch if it has one
+    #[serde(default)]
+    #[ts(optional)]
+    pub unsure: Option<bool>,
+    /// The tech can't answer at all: go where the current question's
+    /// "I'm not sure" option leads (sent instead of an answer)
+    #[serde(default)]
+    #[ts(optional)]
+    pub not_sure: Option<bool>,
+}
+
+/// Response after submitting an answer (NODE-GRAPH VERSION)
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SubmitAnswerResponse {
+    pub session_id: String,
+    pub node: Node,
+    pub options: Vec<NavigationOption>,
+    pub is_conclusion: bool,
+    pub conclusion_text: Option<String>,
+    /// Repair manual link for the conclusion, if one is set
+    pub kb_url: Option<String>,
+    /// Manual section or document number for the conclusion
+    pub doc_reference: Option<String>,
+    /// Answers picked on a queue-mode question that are still to be followed;
+    /// at a conclusion the session stays open until these are worked through
+    #[ts(type = "number")]
+    pub follow_ups_remaining: usize,
+    /// Active notices on the current node's category (e.g. a known issue
+    /// this week with a conclusion to skip to)
+    pub notices: Vec<CategoryNotice>,
+    /// Reference files attached to the current node, such as a manual or wiring diagram
+    pub attachments: Vec<NodeAttachment>,
+    /// Fewest and most questions left before a conclusion (0 at a
+    /// conclusion), for a progress indicator; null when no conclusion can be
+    /// reached from here
+    pub remaining: Option<RemainingDepth>,
+}
+
+
+/// Resume code alphabet: uppercase letters and digits without look-alikes (0/O, 1/I/L)
+const RESUME_CODE_ALPHABET: &[u8] = b"23456789ABCDEFGHJKMNPQRSTUVWXYZ";
+
+/// Characters in a resume code
+pub const RESUME_CODE_LENGTH: usize = 6;
+
+/// Maximum length of an equipment identifier (matches the column)
+const EQUIPMENT_ID_MAX_LENGTH: usize = 100;
+
+/// Attempts at finding an unused resume code before giving up
+const RESUME_CODE_ATTEMPTS: usize = 5;
+
+/// Most variables a session can capture
+const MAX_CAPTURED_VARIABLES: usize = 50;
+
+/// Maximum length of a captured value
+const CAPTURED_VALUE_MAX_LENGTH: usize = 200;
+
+/// Answer recorded on a step where the tech picked "I'm not sure"
+pub const NOT_SURE_LABEL: &str = "I'm not sure";
+
+/// Most sub-flows a session can be inside at once
+const MAX_SUBFLOW_DEPTH: usize = 10;
+
+/// Most sub-flow entries and returns one step can pass through
+const MAX_SUBFLOW_HOPS: usize = 50;
+
+/// Values the tech entered during a session, as stored in `sessions.variables`
+pub type Captured = BTreeMap<String, String>;
+
+/// Where a session is being started from, recorded on the session
+#[derive(Debug, Clone, Default)]
+pub struct SessionOrigin {
+    /// Registered device the session runs on
+    pub device_id: Option<Uuid>,
+    pub user_agent: Option<String>,
+    /// Client address; only a hash of it is stored
+    pub ip_address: Option<String>,
+}
+
+fn generate_resume_code() -> String {
+    crate::utils::password::generate_code(RESUME_CODE_ALPHABET, RESUME_CODE_LENGTH)
+}
+
+/// Uppercase and drop separators, so "abc-123" and "ABC 123" both match
+pub fn normalize_resume_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// A start node with its navigation options (cached in the questions cache)
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct StartQuestion {
+    pub node: Node,
+    pub options: Vec<NavigationOption>,
+}
+
+/// SQL conditions keeping drafts, archived issues and issues outside their
+/// schedule out of sessions, for the given category column
+fn served_category_filter(column: &str) -> String {
+    format!(
+        "{0} NOT IN (SELECT draft_category FROM issue_drafts)
+           AND {0} NOT IN (SELECT category FROM issue_archives)
+           AND {0} NOT IN (SELECT category FROM issues WHERE active_from > NOW() OR active_until <= NOW())",
+        column
+    )
+}
+
+/// Columns of `Node`, in the order `query_as::<_, Node>` reads them
+pub(crate) const NODE_COLUMNS: &str = "id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at";
+
+/// A node by ID, active or not
+async fn fetch_node<'e, E: sqlx::PgExecutor<'e>>(executor: E, node_id: Uuid) -> Result<Option<Node>, sqlx::Error> {
+    sqlx::query_as::<_, Node>(&format!("SELECT {} FROM nodes WHERE id = $1", NODE_COLUMNS))
+        .bind(node_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Answers at a node that lead to active nodes, in the order they're shown
+///
+/// With `served_only`, answers into rollout candidates and categories kept
+/// out of sessions (see `served_category_filter`) are left out too.
+fn options_query(served_only: bool) -> String {
+    let served = if served_only {
+        format!(
+            "AND n.category NOT IN (SELECT candidate_category FROM issue_rollouts)
+           AND {}",
+            served_category_filter("n.category")
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "SELECT c.id, c.label, c.condition, n.category, n.display_category
+         FROM connections c
+         INNER JOIN nodes n ON c.to_node_id = n.id
+         INNER JOIN nodes p ON c.from_node_id = p.id
+         WHERE c.from_node_id = $1
+           AND c.is_active = true
+           AND n.is_active = true
+           {}
+         ORDER BY CASE WHEN p.option_order = 'weight' THEN c.weight END DESC NULLS LAST, c.order_index ASC",
+        served
+    )
+}
+
+/// A node's answers before conditions are applied (see `options_query`)
+async fn fetch_options<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
+    node_id: Uuid,
+    served_only: bool,
+) -> Result<Vec<NavigationOption>, sqlx::Error> {
+    // PERFORMANCE: Get connections with their target nodes in a single JOIN query (avoids N+1)
+    let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, String, Option<String>)>(&options_query(served_only))
+        .bind(node_id)
+        .fetch_all(executor)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(connection_id, label, condition, target_category, display_category)| NavigationOption {
+            connection_id,
+            label,
+            condition,
+            target_category,
+            display_category,
+        })
+        .collect())
+}
+
+/// Start question for a semantic ID ("start" or "{category}_start")
+///
+/// Served from the questions cache when possible. Returns None when no active
+/// node has that semantic ID. Only the owning category's node counts (`root`
+/// for "start"), so another category's node can't stand in for the start.
+/// Drafts, archived issues and issues outside their schedule have no start as
+/// far as sessions are concerned.
+pub(crate) async fn load_start_question(state: &AppState, semantic_id: &str) -> ApiResult<Option<StartQuestion>> {
+    let cache_key = semantic_id.to_string();
+    if let Some(cached) = state.questions_cache.get(&cache_key).await {
+        tracing::debug!("✅ Cache HIT: start question {}", semantic_id);
+        return Ok(Some(serde_json::from_value(cached)?));
+    }
+
+    let node = sqlx::query_as::<_, Node>(&format!(
+        "SELECT {}
+         FROM nodes
+         WHERE semantic_id = $1 AND is_active = true
+           AND (category || '_start' = $1 OR (category = 'root' AND $1 = 'start'))
+           AND {}",
+        NODE_COLUMNS,
+        served_category_filter("category")
+    ))
+    .bind(semantic_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(node) = node else {
+        return Ok(None);
+    };
+
+    let question = StartQuestion { options: start_options(state, node.id).await?, node };
+    state.questions_cache.set(cache_key, serde_json::to_value(&question)?).await;
+
+    Ok(Some(question))
+}
+
+/// Active start node of an entry point, unless its issue isn't served (see `served_category_filter`)
+fn entry_point_question_query() -> String {
+    format!(
+        "SELECT n.id, n.category, n.node_type, n.text, n.semantic_id, n.display_category, n.position_x, n.position_y, n.kb_url, n.doc_reference, n.option_order, n.answer_mode, n.not_sure_node_id, n.subflow_category, n.content, n.is_active, n.created_at, n.updated_at
+         FROM entry_points e
+         JOIN nodes n ON n.id = e.node_id
+         WHERE e.name = $1 AND e.is_active = true AND n.is_active = true
+           AND {}",
+        served_category_filter("n.category")
+    )
+}
+
+/// Start question of a named entry point
+///
+/// Returns None when the entry point does not exist, is disabled, or points
+/// at an inactive node or into a draft, archived or out-of-schedule issue.
+pub(crate) async fn load_entry_point_question(state: &AppState, name: &str) -> ApiResult<Option<StartQuestion>> {
+    let cache_key = format!("entry_point:{}", name);
+    if let Some(cached) = state.questions_cache.get(&cache_key).await {
+        tracing::debug!("✅ Cache HIT: entry point {}", name);
+        return Ok(Some(serde_json::from_value(cached)?));
+    }
+
+    let node = sqlx::query_as::<_, Node>(&entry_point_question_query())
+    .bind(name)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(node) = node else {
+        return Ok(None);
+    };
+
+    let question = StartQuestion { options: start_options(state, node.id).await?, node };
+    state.questions_cache.set(cache_key, serde_json::to_value(&question)?).await;
+
+    Ok(Some(question))
+}
+
+/// Options offered at a start node (rollout candidates, drafts, archived issues and
+/// issues outside their schedule are hidden)
+async fn start_options(state: &AppState, node_id: Uuid) -> ApiResult<Vec<NavigationOption>> {
+    let options = fetch_options(&state.db, node_id, true).await?;
+
+    // Nothing has been answered yet
+    Ok(offered_options(options, &Variables::new()))
+}
+
+/// Drop answers whose conditions rule them out given the session so far
+///
+/// Only reading ranges are passed on; they're what tells the client to ask
+/// for a measurement.
+fn offered_options(options: Vec<NavigationOption>, variables: &Variables) -> Vec<NavigationOption> {
+    options
+        .into_iter()
+        .filter(|option| conditions::available(option.condition.as_deref(), variables))
+        .map(|option| NavigationOption {
+            condition: option.condition.filter(|c| conditions::uses_reading(Some(c))),
+            ..option
+        })
+        .collect()
+}
+
+/// Variables set by a session's answers, for evaluating connection conditions
+///
+/// Each answered question with a semantic ID sets a variable of that name to
+/// the reading it was given or else the label of the answer followed; a
+/// question answered again later overwrites it.
+fn variables_from_steps(steps: &[serde_json::Value], semantic_ids: &std::collections::HashMap<Uuid, String>) -> Variables {
+    let mut variables = Variables::new();
+
+    for step in steps {
+        let Some(name) = serde_json::from_value::<Uuid>(step["node_id"].clone())
+            .ok()
+            .and_then(|node_id| semantic_ids.get(&node_id))
+        else {
+            continue;
+        };
+
+        let value = match (step["value"].as_f64(), step["connection_label"].as_str()) {
+            (Some(reading), _) => Value::Number(reading),
+            (None, Some(label)) => Value::Text(label.to_string()),
+            (None, None) => continue,
+        };
+        variables.insert(name.clone(), value);
+    }
+
+    variables
+}
+
+/// Check submitted values and merge them into the session's captured variables
+pub(crate) fn capture(mut captured: Captured, submitted: Option<Captured>) -> ApiResult<Captured> {
+    let invalid = |message: String| ApiError::validation(vec![("variables".to_string(), message)]);
+
+    for (name, value) in submitted.into_iter().flatten() {
+        if !expression::is_variable_name(&name) {
+            return Err(invalid(format!(
+                "'{}' is not a valid variable name (letters, digits and underscores)",
+                name
+            )));
+        }
+        let value = value.trim();
+        if value.is_empty() {
+            captured.remove(&name);
+        } else if value.chars().count() > CAPTURED_VALUE_MAX_LENGTH {
+            return Err(invalid(format!(
+                "'{}' must be at most {} characters",
+                name, CAPTURED_VALUE_MAX_LENGTH
+            )));
+        } else {
+            captured.insert(name, value.to_string());
+        }
+    }
+
+    if captured.len() > MAX_CAPTURED_VARIABLES {
+        return Err(invalid(format!("A session can capture at most {} variables", MAX_CAPTURED_VARIABLES)));
+    }
+    Ok(captured)
+}
+
+/// Fewest and most questions left from a node, cached per category
+async fn remaining_depth(state: &AppState, node: &Node) -> ApiResult<Option<RemainingDepth>> {
+    let depths = match state.remaining_depth_cache.get(&node.category).await {
+        Some(depths) => depths,
+        None => {
+            let depths = std::sync::Arc::new(remaining_depth::load_category(&state.db, &node.category).await?);
+            state.remaining_depth_cache.set(node.category.clone(), depths.clone()).await;
+            depths
+        }
+    };
+    Ok(depths.get(&node.id).copied().map(RemainingDepth::from))
+}
+
+/// Fill in the placeholders of a node's text and rich content
+pub(crate) fn render_node(node: &mut Node, variables: &Variables) {
+    node.text = template::render(&node.text, variables);
+    if let Some(content) = &mut node.content {
+        content.markdown = template::render(&content.markdown, variables);
+    }
+}
+
+/// Collect the session's variables: its answers, overridden by captured values
+async fn session_variables(state: &AppState, captured: &Captured, steps: &[serde_json::Value]) -> ApiResult<Variables> {
+    let node_ids: Vec<Uuid> = steps
+        .iter()
+        .filter_map(|step| serde_json::from_value(step["node_id"].clone()).ok())
+        .collect();
+
+    let mut variables = if node_ids.is_empty() {
+        Variables::new()
+    } else {
+        let semantic_ids = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, semantic_id FROM nodes WHERE id = ANY($1) AND semantic_id IS NOT NULL"
+        )
+        .bind(&node_ids)
+        .fetch_all(&state.db)
+        .await?
+        .into_iter()
+        .collect();
+        variables_from_steps(steps, &semantic_ids)
+    };
+
+    variables.extend(captured.iter().map(|(name, value)| (name.clone(), Value::Text(value.clone()))));
+    Ok(variables)
+}
+
+/// Start a new session at a category, an entry point or the global start
+///
+/// Direct category starts may be routed to a rollout candidate, and count
+/// against the category's daily quota.
+pub async fn start(state: &AppState, req: StartSessionRequest, origin: SessionOrigin) -> ApiResult<StartSessionResponse> {
+    // Generate session ID (also used to bucket the session for rollouts)
+    let session_id = Uuid::new_v4().to_string();
+
+    if req.category.is_some() && req.entry_point.is_some() {
+        return Err(ApiError::validation(vec![(
+            "entry_point".to_string(),
+            "Specify either category or entry_point, not both".to_string(),
+        )]));
+    }
+
+    // Get the starting node based on category or entry point, defaulting to global start
+    let mut start = if let Some(category) = &req.category {
+        // Direct category access: find the category's start node
+        load_start_question(state, &format!("{}_start", category))
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Issue category '{}' not found", category)))?
+    } else if let Some(entry_point) = &req.entry_point {
+        load_entry_point_question(state, entry_point)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Entry point '{}' not found", entry_point)))?
+    } else {
+        // No category specified: use global start node
+        load_start_question(state, "start")
+            .await?
+            .ok_or_else(|| ApiError::internal("Global start node not found. Please run ensure_global_start.sql"))?
+    };
+
+    // Quotas are set on the category asked for, whichever version serves it
+    let quota_category = start.node.category.clone();
+
+    // Direct category access may be routed to a rollout candidate
+    if req.category.is_some() {
+        if let Some(candidate_root) = rollout::route_session(&state.db, &session_id, &start.node.category).await? {
+            if let Some(semantic_id) = &candidate_root.semantic_id {
+                if let Some(candidate) = load_start_question(state, semantic_id).await? {
+                    start = candidate;
+                }
+            }
+        }
+    }
+
+    let StartQuestion { node: mut root_node, options } = start;
+
+    let equipment_id = req.equipment_id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+    if equipment_id.is_some_and(|id| id.chars().count() > EQUIPMENT_ID_MAX_LENGTH) {
+        return Err(ApiError::validation(vec![(
+            "equipment_id".to_string(),
+            format!("Must be at most {} characters", EQUIPMENT_ID_MAX_LENGTH),
+        )]));
+    }
+
+    let captured = capture(Captured::new(), req.variables)?;
+
+    // Another tech may already be working on this unit
+    let duplicate_of = match (equipment_id, state.settings.duplicate_session_window_minutes()) {
+        (Some(equipment_id), Some(window_minutes)) => sqlx::query_as::<_, (String, Option<String>, chrono::DateTime<chrono::Utc>, Option<String>)>(
+            "SELECT session_id, resume_code, started_at, tech_identifier
+             FROM sessions
+             WHERE equipment_id = $1
+               AND completed_at IS NULL
+               AND abandoned = false
+               AND started_at > NOW() - make_interval(mins => $2)
+             ORDER BY started_at DESC
+             LIMIT 1"
+        )
+        .bind(equipment_id)
+        .bind(window_minutes)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|(session_id, resume_code, started_at, tech_identifier)| DuplicateSession {
+            session_id,
+            resume_code,
+            started_at: started_at.to_rfc3339(),
+            tech_identifier,
+        }),
+        _ => None,
+    };
+
+    match category_quotas::claim(&state.db, &quota_category).await? {
+        QuotaClaim::Refused { daily_limit } => {
+            tracing::info!("🎟️  Session refused: {} has reached its daily limit of {}", quota_category, daily_limit);
+            return Err(ApiError::too_many_requests(
+                format!(
+                    "'{}' has reached its limit of {} sessions today; try again tomorrow or contact your supervisor",
+                    quota_category, daily_limit
+                ),
+                category_quotas::seconds_until_reset(chrono::Utc::now()),
+            ));
+        }
+        QuotaClaim::Allowed { sessions, daily_limit, warn: true } => {
+            tracing::warn!("⚠️  {} has used {} of its {} daily sessions", quota_category, sessions, daily_limit);
+        }
+        _ => {}
+    }
+
+    let SessionOrigin { device_id, user_agent, ip_address } = origin;
+
+    // Hash IP address for privacy (simple MD5 for now)
+    let ip_hash = ip_address.map(|ip| format!("{:x}", md5::compute(ip.as_bytes())));
+
+    // Create session in database (retrying on the rare resume code collision)
+    let initial_steps = serde_json::json!([]);
+    let mut resume_code = None;
+
+    for _ in 0..RESUME_CODE_ATTEMPTS {
+        let code = generate_resume_code();
+        let inserted = sqlx::query(
+            "INSERT INTO sessions (session_id, started_at, steps, tech_identifier, client_site, user_agent, ip_hash, abandoned, entry_point, resume_code, equipment_id, variables, device_id, start_node_id)
+             VALUES ($1, NOW(), $2, $3, $4, $5, $6, false, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (resume_code) WHERE resume_code IS NOT NULL DO NOTHING",
+        )
+        .bind(&session_id)
+        .bind(&initial_steps)
+        .bind(&req.tech_identifier)
+        .bind(&req.client_site)
+        .bind(&user_agent)
+        .bind(&ip_hash)
+        .bind(&req.entry_point)
+        .bind(&code)
+        .bind(equipment_id)
+        .bind(serde_json::to_value(&captured)?)
+        .bind(device_id)
+        .bind(root_node.id)
+        .execute(&state.db)
+        .await?;
+
+        if inserted.rows_affected() == 1 {
+            resume_code = Some(code);
+            break;
+        }
+    }
+    let resume_code = resume_code.ok_or_else(|| ApiError::internal("Could not allocate a resume code"))?;
+
+    session_events::record(
+        &session_id,
+        SessionEventType::Started,
+        Some(&root_node.category),
+        Some(serde_json::json!({
+            "direct_category": req.category.is_some(),
+            "entry_point": &req.entry_point,
+            "duplicate_of": duplicate_of.as_ref().map(|d| &d.session_id),
+            "device_id": device_id,
+        })),
+    );
+
+    if let Some(duplicate) = &duplicate_of {
+        tracing::info!("👥 Session {} started on equipment already in session {}", session_id, duplicate.session_id);
+    }
+
+    render_node(&mut root_node, &session_variables(state, &captured, &[]).await?);
+
+    Ok(StartSessionResponse {
+        session_id,
+        resume_code,
+        notices: category_notices::active(&root_node.category),
+        attachments: uploads::node_attachments(&state.db, root_node.id).await?,
+        node: root_node,
+        options,
+        duplicate_of,
+    })
+}
+
+/// Transaction holding a session's row lock while a step is taken
+type SessionTx = sqlx::Transaction<'static, sqlx::Postgres>;
+
+/// A session's answers so far, read under a row lock
+struct LockedSession {
+    steps: Vec<serde_json::Value>,
+    captured: Captured,
+    completed: bool,
+}
+
+/// Read a session and lock its row until the transaction ends
+///
+/// Requests on the same session (a double-click, a retry) then take turns
+/// instead of each appending to the steps they read and overwriting the other.
+async fn lock_session(conn: &mut sqlx::PgConnection, session_id: &str) -> ApiResult<LockedSession> {
+    let (steps, captured, completed) = sqlx::query_as::<_, (serde_json::Value, serde_json::Value, bool)>(
+        "SELECT steps, variables, completed_at IS NOT NULL FROM sessions WHERE session_id = $1 FOR UPDATE"
+    )
+    .bind(session_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Session not found"))?;
+
+    Ok(LockedSession {
+        steps: serde_json::from_value(steps).unwrap_or_default(),
+        captured: serde_json::from_value(captured).unwrap_or_default(),
+        completed,
+    })
+}
+
+/// Answer the current question and move the session on
+///
+/// Takes exactly one of an answer, a reading or multi-select picks, or
+/// "I'm not sure" on its own.
+pub async fn answer(state: &AppState, session_id: String, req: SubmitAnswerRequest) -> ApiResult<SubmitAnswerResponse> {
+    let mut tx = state.db.begin().await?;
+    let LockedSession { steps, captured, completed } = lock_session(&mut tx, &session_id).await?;
+
+    // Check if session is already completed
+    if completed {
+        return Err(ApiError::bad_request("Session is already completed"));
+    }
+
+    let captured = capture(captured, req.variables)?;
+    let variables = session_variables(state, &captured, &steps).await?;
+
+    if req.not_sure == Some(true) {
+        if req.connection_id.is_some() || req.value.is_some() || req.connection_ids.is_some() || req.unsure.is_some() {
+            return Err(ApiError::validation(vec![(
+                "not_sure".to_string(),
+                "\"I'm not sure\" is sent instead of an answer".to_string(),
+            )]));
+        }
+        return follow_not_sure(state, tx, session_id, steps, &captured).await;
+    }
+
+    // Extra fields recorded on the step alongside the followed connection
+    let mut details = serde_json::Map::new();
+
+    let connection_id = match (req.connection_id, req.value, req.connection_ids) {
+        (Some(connection_id), None, None) => {
+            let condition = sqlx::query_scalar::<_, Option<String>>("SELECT condition FROM connections WHERE id = $1")
+                .bind(connection_id)
+                .fetch_optional(&state.db)
+                .await?
+                .flatten();
+            if !conditions::available(condition.as_deref(), &variables) {
+                return Err(ApiError::validation(vec![(
+                    "connection_id".to_string(),
+                    "This answer does not apply given the earlier answers".to_string(),
+                )]));
+            }
+            connection_id
+        }
+        (None, Some(value), None) => {
+            details.insert("value".to_string(), serde_json::json!(value));
+            route_measurement(state, req.node_id, value, &variables).await?
+        }
+        (None, None, Some(connection_ids)) => {
+            let (answer_mode, picks) = route_multi_select(state, &connection_ids, &variables).await?;
+            details.insert("connection_ids".to_string(), serde_json::json!(picks.iter().map(|(id, _)| id).collect::<Vec<_>>()));
+            details.insert("connection_labels".to_string(), serde_json::json!(picks.iter().map(|(_, label)| label).collect::<Vec<_>>()));
+            if answer_mode == AnswerMode::Queue && picks.len() > 1 {
+                details.insert("queued".to_string(), serde_json::json!(picks[1..].iter().map(|(id, _)| id).collect::<Vec<_>>()));
+            }
+            picks[0].0
+        }
+        _ => {
+            return Err(ApiError::validation(vec![(
+                "connection_id".to_string(),
+                "Provide exactly one of connection_id, connection_ids or value".to_string(),
+            )]))
+        }
+    };
+
+    let connection_id = if req.unsure == Some(true) {
+        details.insert("unsure".to_string(), serde_json::json!(true));
+        match unsure_route(state, connection_id, &variables).await? {
+            Some(route) if route != connection_id => {
+                details.insert("picked_connection_id".to_string(), serde_json::json!(connection_id));
+                route
+            }
+            _ => connection_id,
+        }
+    } else {
+        connection_id
+    };
+
+    follow_connection(state, tx, session_id, steps, &captured, connection_id, details).await
+}
+
+/// The conservative branch of the question `connection_id` answers, if it
+/// has one that applies given the earlier answers
+async fn unsure_route(state: &AppState, connection_id: Uuid, variables: &Variables) -> ApiResult<Option<Uuid>> {
+    let route = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        "SELECT r.id, r.condition
+         FROM connections c
+         INNER JOIN connections r ON r.from_node_id = c.from_node_id
+         WHERE c.id = $1 AND r.unsure_route AND r.is_active",
+    )
+    .bind(connection_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    Ok(route
+        .filter(|(_, condition)| conditions::available(condition.as_deref(), variables))
+        .map(|(id, _)| id))
+}
+
+/// Record "I'm not sure" at the current question and move to where it leads
+///
+/// The step has no answer, is flagged unsure for the stats, and keeps the
+/// target in `not_sure_to` so going back and reloading work like a skip.
+async fn follow_not_sure(
+    state: &AppState,
+    tx: SessionTx,
+    session_id: String,
+    mut steps: Vec<serde_json::Value>,
+    captured: &Captured,
+) -> ApiResult<SubmitAnswerResponse> {
+    let current = current(state, session_id.clone()).await?;
+    if current.is_conclusion {
+        return Err(ApiError::bad_request("Session is already at a conclusion"));
+    }
+    let unavailable = || {
+        ApiError::validation(vec![(
+            "not_sure".to_string(),
+            "This question has no \"I'm not sure\" option".to_string(),
+        )])
+    };
+
+    let target_id = current.node.not_sure_node_id.ok_or_else(unavailable)?;
+    let target = fetch_node(&state.db, target_id)
+        .await?
+        .filter(|node| node.is_active)
+        .ok_or_else(unavailable)?;
+    let target = enter(state, &session_id, target).await?;
+
+    // Steps record the question as written, like answered steps
+    let question_text = sqlx::query_scalar::<_, String>("SELECT text FROM nodes WHERE id = $1")
+        .bind(current.node.id)
+        .fetch_one(&state.db)
+        .await?;
+
+    steps.push(serde_json::json!({
+        "node_id": current.node.id,
+        "node_text": question_text,
+        "connection_id": null,
+        "connection_label": NOT_SURE_LABEL,
+        "not_sure_to": target.id,
+        "unsure": true,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }));
+
+    arrive_at(state, tx, session_id, steps, captured, target).await
+}
+
+/// Continue with the next queued answer of a multi-select question
+///
+/// Only allowed once the current branch has reached a conclusion.
+pub async fn follow_up(state: &AppState, session_id: String) -> ApiResult<SubmitAnswerResponse> {
+    let mut tx = state.db.begin().await?;
+    let LockedSession { steps, captured, completed } = lock_session(&mut tx, &session_id).await?;
+
+    if completed {
+        return Err(ApiError::bad_request("Session is already completed"));
+    }
+
+    let Some(&connection_id) = pending_follow_ups(&steps).first() else {
+        return Err(ApiError::bad_request("No follow-up answers are queued"));
+    };
+
+    let last_step = steps.last().ok_or_else(|| ApiError::internal("Invalid session data"))?;
+    let at_conclusion = sqlx::query_scalar::<_, bool>("SELECT node_type IN ('conclusion', 'return') FROM nodes WHERE id = $1")
+    .bind(step_target(state, last_step).await?)
+    .fetch_optional(&state.db)
+    .await?
+    .unwrap_or(false);
+
+    if !at_conclusion {
+        return Err(ApiError::bad_request("Finish the current answer before moving to the next one"));
+    }
+
+    let mut details = serde_json::Map::new();
+    details.insert("follow_up".to_string(), serde_json::json!(true));
+
+    follow_connection(state, tx, session_id, steps, &captured, connection_id, details).await
+}
+
+/// Jump from the current question straight to a conclusion in its category
+///
+/// For techs who already know the fix. The skip is recorded as a step, so
+/// going back returns to the question it was made from.
+pub async fn skip_to_conclusion(state: &AppState, session_id: String, node_id: Uuid) -> ApiResult<SubmitAnswerResponse> {
+    let current = current(state, session_id.clone()).await?;
+    if current.is_conclusion {
+        return Err(ApiError::bad_request("Session is already at a conclusion"));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let LockedSession { mut steps, captured, completed } = lock_session(&mut tx, &session_id).await?;
+    if completed {
+        return Err(ApiError::bad_request("Session is already completed"));
+    }
+
+    let conclusion = fetch_node(&state.db, node_id)
+        .await?
+        .filter(|node| node.is_active)
+        .ok_or_else(|| ApiError::validation(vec![("node_id".to_string(), "Conclusion not found".to_string())]))?;
+
+    if !matches!(conclusion.node_type, NodeType::Conclusion) {
+        return Err(ApiError::validation(vec![(
+            "node_id".to_string(),
+            "Only conclusions can be skipped to".to_string(),
+        )]));
+    }
+    if conclusion.category != current.node.category {
+        return Err(ApiError::validation(vec![(
+            "node_id".to_string(),
+            format!("Conclusion is not in the current category ({})", current.node.category),
+        )]));
+    }
+
+    // Steps record the question as written, like answered steps
+    let question_text = sqlx::query_scalar::<_, String>("SELECT text FROM nodes WHERE id = $1")
+        .bind(current.node.id)
+        .fetch_one(&state.db)
+        .await?;
+
+    steps.push(serde_json::json!({
+        "node_id": current.node.id,
+        "node_text": question_text,
+        "connection_id": null,
+        "connection_label": null,
+        "skipped_to": conclusion.id,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }));
+
+    arrive_at(state, tx, session_id, steps, &captured, conclusion).await
+}
+
+/// Answers picked on queue-mode questions that haven't been followed yet, next first
+///
+/// Derived from the steps alone so going back or returning to an earlier step
+/// restores the queue. Picks queued inside a follow-up branch come before the
+/// ones queued earlier (depth first).
+fn pending_follow_ups(steps: &[serde_json::Value]) -> Vec<Uuid> {
+    let mut queue: std::collections::VecDeque<Uuid> = std::collections::VecDeque::new();
+
+    for step in steps {
+        if step["follow_up"] == serde_json::json!(true) {
+            if let Ok(connection_id) = serde_json::from_value::<Uuid>(step["connection_id"].clone()) {
+                if let Some(position) = queue.iter().position(|id| *id == connection_id) {
+                    queue.remove(position);
+                }
+            }
+        }
+
+        let queued: Vec<Uuid> = serde_json::from_value(step["queued"].clone()).unwrap_or_default();
+        for connection_id in queued.into_iter().rev() {
+            queue.push_front(connection_id);
+        }
+    }
+
+    queue.into()
+}
+
+/// Node a step led to: where it came out after passing through sub-flows,
+/// the target of its answer, the conclusion it skipped to, or where
+/// "I'm not sure" led
+async fn step_target(state: &AppState, step: &serde_json::Value) -> ApiResult<Uuid> {
+    for key in ["arrived_at", "skipped_to", "not_sure_to"] {
+        if let Ok(node_id) = serde_json::from_value::<Uuid>(step[key].clone()) {
+            return Ok(node_id);
+        }
+    }
+
+    let connection_id: Uuid = serde_json::from_value(step["connection_id"].clone())
+        .map_err(|_| ApiError::internal("Invalid session data"))?;
+    sqlx::query_scalar::<_, Uuid>("SELECT to_node_id FROM connections WHERE id = $1")
+        .bind(connection_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Previous answer no longer exists"))
+}
+
+/// Record a step along `connection_id` and move the session to its target
+async fn follow_connection(
+    state: &AppState,
+    tx: SessionTx,
+    session_id: String,
+    mut steps: Vec<serde_json::Value>,
+    captured: &Captured,
+    connection_id: Uuid,
+    details: serde_json::Map<String, serde_json::Value>,
+) -> ApiResult<SubmitAnswerResponse> {
+    // PERFORMANCE OPTIMIZATION: Get connection and both nodes in a single JOIN query
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            c.id as connection_id,
+            c.from_node_id,
+            c.to_node_id,
+            c.label as connection_label,
+            c.order_index,
+            c.weight,
+            c.condition,
+            c.unsure_route,
+            c.created_at as connection_created_at,
+            c.updated_at as connection_updated_at,
+            fn.id as from_id,
+            fn.category as from_category,
+            fn.node_type as "from_node_type: NodeType",
+            fn.text as from_text,
+            fn.semantic_id as from_semantic_id,
+            fn.display_category as from_display_category,
+            fn.position_x as from_position_x,
+            fn.position_y as from_position_y,
+            fn.kb_url as from_kb_url,
+            fn.doc_reference as from_doc_reference,
+            fn.option_order as "from_option_order: OptionOrder",
+            fn.answer_mode as "from_answer_mode: AnswerMode",
+            fn.not_sure_node_id as from_not_sure_node_id,
+            fn.subflow_category as from_subflow_category,
+            fn.content as "from_content: sqlx::types::Json<NodeContent>",
+            fn.is_active as from_is_active,
+            fn.created_at as from_created_at,
+            fn.updated_at as from_updated_at,
+            tn.id as to_id,
+            tn.category as to_category,
+            tn.node_type as "to_node_type: NodeType",
+            tn.text as to_text,
+            tn.semantic_id as to_semantic_id,
+            tn.display_category as to_display_category,
+            tn.position_x as to_position_x,
+            tn.position_y as to_position_y,
+            tn.kb_url as to_kb_url,
+            tn.doc_reference as to_doc_reference,
+            tn.option_order as "to_option_order: OptionOrder",
+            tn.answer_mode as "to_answer_mode: AnswerMode",
+            tn.not_sure_node_id as to_not_sure_node_id,
+            tn.subflow_category as to_subflow_category,
+            tn.content as "to_content: sqlx::types::Json<NodeContent>",
+            tn.is_active as to_is_active,
+            tn.created_at as to_created_at,
+            tn.updated_at as to_updated_at
+        FROM connections c
+        INNER JOIN nodes fn ON c.from_node_id = fn.id
+        INNER JOIN nodes tn ON c.to_node_id = tn.id
+        WHERE c.id = $1 AND c.is_active = true
+        "#,
+        connection_id
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Connection not found"))?;
+
+    // Reconstruct the connection and nodes from the joined result
+    let connection = Connection {
+        id: result.connection_id,
+        from_node_id: result.from_node_id,
+        to_node_id: result.to_node_id,
+        label: result.connection_label,
+        order_index: result.order_index.unwrap_or(0),
+        weight: result.weight,
+        condition: result.condition,
+        unsure_route: result.unsure_route,
+        is_active: true,
+        created_at: result.connection_created_at.unwrap_or_default(),
+        updated_at: result.connection_updated_at.unwrap_or_default(),
+    };
+
+    let from_node = Node {
+        id: result.from_id,
+        category: result.from_category,
+        node_type: result.from_node_type,
+        text: result.from_text,
+        semantic_id: result.from_semantic_id,
+        display_category: result.from_display_category,
+        position_x: result.from_position_x,
+        position_y: result.from_position_y,
+        kb_url: result.from_kb_url,
+        doc_reference: result.from_doc_reference,
+        option_order: result.from_option_order,
+        answer_mode: result.from_answer_mode,
+        not_sure_node_id: result.from_not_sure_node_id,
+        subflow_category: result.from_subflow_category,
+        content: result.from_content.map(|content| content.0),
+        is_active: result.from_is_active.unwrap_or(true),
+        created_at: result.from_created_at.unwrap_or_default(),
+        updated_at: result.from_updated_at.unwrap_or_default(),
+    };
+
+    let next_node = Node {
+        id: result.to_id,
+        category: result.to_category,
+        node_type: result.to_node_type,
+        text: result.to_text,
+        semantic_id: result.to_semantic_id,
+        display_category: result.to_display_category,
+        position_x: result.to_position_x,
+        position_y: result.to_position_y,
+        kb_url: result.to_kb_url,
+        doc_reference: result.to_doc_reference,
+        option_order: result.to_option_order,
+        answer_mode: result.to_answer_mode,
+        not_sure_node_id: result.to_not_sure_node_id,
+        subflow_category: result.to_subflow_category,
+        content: result.to_content.map(|content| content.0),
+        is_active: result.to_is_active.unwrap_or(true),
+        created_at: result.to_created_at.unwrap_or_default(),
+        updated_at: result.to_updated_at.unwrap_or_default(),
+    };
+
+    let next_node = enter(state, &session_id, next_node).await?;
+
+    // Update session steps
+    let mut step = serde_json::json!({
+        "node_id": from_node.id,
+        "node_text": from_node.text,
+        "connection_id": connection.id,
+        "connection_label": connection.label,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    });
+    if let Some(step) = step.as_object_mut() {
+        step.extend(details);
+    }
+    steps.push(step);
+
+    arrive_at(state, tx, session_id, steps, captured, next_node).await
+}
+
+/// The node a session moving to `node` actually arrives at
+///
+/// Entering a category's root: a rollout may send this session to the candidate version.
+async fn enter(state: &AppState, session_id: &str, node: Node) -> ApiResult<Node> {
+    if node.semantic_id.as_deref() == Some(format!("{}_start", node.category).as_str()) {
+        if let Some(candidate_root) = rollout::route_session(&state.db, session_id, &node.category).await? {
+            return Ok(candidate_root);
+        }
+    }
+    Ok(node)
+}
+
+/// Hand the step a session just took to the analytics sinks
+fn record_step(session_id: &str, steps: &[serde_json::Value], next_node: &Node) {
+    let Some(mut step) = steps.last().cloned() else {
+        return;
+    };
+    if let Some(fields) = step.as_object_mut() {
+        fields.insert("step_index".to_string(), serde_json::json!(steps.len() - 1));
+        fields.insert("to_node_id".to_string(), serde_json::json!(next_node.id));
+    }
+    session_events::record(session_id, SessionEventType::StepAnswered, Some(&next_node.category), Some(step));
+}
+
+/// Sub-flow nodes a session is inside, innermost last
+///
+/// Derived from the steps alone, like the follow-up queue, so going back
+/// restores it.
+fn subflow_stack(steps: &[serde_json::Value]) -> Vec<Uuid> {
+    let mut stack = Vec::new();
+    for event in steps.iter().flat_map(|step| step["subflows"].as_array().cloned().unwrap_or_default()) {
+        if let Ok(node_id) = serde_json::from_value::<Uuid>(event["entered"].clone()) {
+            stack.push(node_id);
+        } else if !event["returned"].is_null() {
+            stack.pop();
+        }
+    }
+    stack
+}
+
+/// The start node of the category a sub-flow node runs
+async fn subflow_start(state: &AppState, subflow: &Node) -> ApiResult<Node> {
+    let category = subflow
+        .subflow_category
+        .as_deref()
+        .ok_or_else(|| ApiError::internal(format!("Sub-flow node {} has no category", subflow.id)))?;
+
+    sqlx::query_as::<_, Node>(&format!(
+        "SELECT {} FROM nodes WHERE category = $1 AND semantic_id = $1 || '_start' AND is_active = true",
+        NODE_COLUMNS
+    ))
+    .bind(category)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Sub-flow '{}' has no active start", category)))
+}
+
+/// The answer a sub-flow node continues with once its sub-flow returns `outcome`
+///
+/// The answer labelled like the outcome (ignoring case), or the only answer.
+async fn subflow_continuation(state: &AppState, subflow_id: Uuid, outcome: &str) -> ApiResult<(Uuid, Node)> {
+    let answers = sqlx::query_as::<_, (Uuid, String, Uuid)>(
+        "SELECT c.id, c.label, c.to_node_id
+         FROM connections c
+         INNER JOIN nodes n ON c.to_node_id = n.id
+         WHERE c.from_node_id = $1
+           AND c.is_active = true
+           AND n.is_active = true
+         ORDER BY c.order_index ASC",
+    )
+    .bind(subflow_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let outcome = outcome.trim().to_lowercase();
+    let (connection_id, _, to_node_id) = answers
+        .iter()
+        .find(|(_, label, _)| label.trim().to_lowercase() == outcome)
+        .or(if answers.len() == 1 { answers.first() } else { None })
+        .ok_or_else(|| ApiError::not_found("No answer continues from this sub-flow's outcome"))?;
+
+    let node = fetch_node(&state.db, *to_node_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+    Ok((*connection_id, node))
+}
+
+/// Pass through sub-flows from `node`: into the category a sub-flow node
+/// runs, and from a return back out along the answer of the sub-flow node
+/// entered by
+///
+/// `stack` holds the sub-flow nodes already entered, innermost last, and is
+/// kept up to date. Returns the node where it stops, with what happened on
+/// the way (for a step's `subflows`). A return with nothing on the stack is
+/// where a sub-flow started on its own ends. Given a session, a rollout may
+/// send it to a candidate version of each sub-flow it enters.
+pub(crate) async fn pass_subflows(
+    state: &AppState,
+    session_id: Option<&str>,
+    stack: &mut Vec<Uuid>,
+    mut node: Node,
+) -> ApiResult<(Node, Vec<serde_json::Value>)> {
+    let mut events = Vec::new();
+
+    loop {
+        if events.len() >= MAX_SUBFLOW_HOPS {
+            return Err(ApiError::bad_request("Sub-flows lead round in a loop"));
+        }
+        let next = match node.node_type {
+            NodeType::Subflow => {
+                if stack.len() >= MAX_SUBFLOW_DEPTH {
+                    return Err(ApiError::bad_request(format!(
+                        "Sub-flows are nested more than {} deep",
+                        MAX_SUBFLOW_DEPTH
+                    )));
+                }
+                let start = subflow_start(state, &node).await?;
+                events.push(serde_json::json!({ "entered": node.id, "category": &node.subflow_category }));
+                stack.push(node.id);
+                start
+            }
+            NodeType::Return => {
+                let Some(subflow_id) = stack.pop() else {
+                    break;
+                };
+                let (connection_id, next) = subflow_continuation(state, subflow_id, &node.text).await?;
+                events.push(serde_json::json!({ "returned": subflow_id, "outcome": &node.text, "connection_id": connection_id }));
+                next
+            }
+            NodeType::Question | NodeType::Conclusion => break,
+        };
+        node = match session_id {
+            Some(session_id) => enter(state, session_id, next).await?,
+            None => next,
+        };
+    }
+
+    Ok((node, events))
+}
+
+/// Pass a session arriving at `node` through sub-flows (see `pass_subflows`)
+///
+/// Records what happened on the latest step, with `arrived_at` for where it
+/// came out.
+async fn through_subflows(
+    state: &AppState,
+    session_id: &str,
+    steps: &mut [serde_json::Value],
+    node: Node,
+) -> ApiResult<Node> {
+    let mut stack = subflow_stack(steps);
+    let (node, events) = pass_subflows(state, Some(session_id), &mut stack, node).await?;
+
+    if let (false, Some(fields)) = (events.is_empty(), steps.last_mut().and_then(|step| step.as_object_mut())) {
+        fields.insert("subflows".to_string(), serde_json::json!(events));
+        fields.insert("arrived_at".to_string(), serde_json::json!(node.id));
+    }
+    Ok(node)
+}
+
+/// Save a session whose latest step leads to `next_node` and describe where it now stands
+///
+/// Sub-flows on the way are passed through first. A conclusion completes
+/// the session unless queued answers remain. The session row was locked by
+/// the caller in `tx`, which commits with the update.
+async fn arrive_at(
+    state: &AppState,
+    mut tx: SessionTx,
+    session_id: String,
+    mut steps: Vec<serde_json::Value>,
+    captured: &Captured,
+    next_node: Node,
+) -> ApiResult<SubmitAnswerResponse> {
+    let mut next_node = through_subflows(state, &session_id, &mut steps, next_node).await?;
+    let steps_json = serde_json::to_value(&steps)?;
+    let captured_json = serde_json::to_value(captured)?;
+    let follow_ups_remaining = pending_follow_ups(&steps).len();
+
+    let variables = session_variables(state, captured, &steps).await?;
+    // Conclusions are recorded as written so they group together in stats
+    let final_conclusion = next_node.text.clone();
+    render_node(&mut next_node, &variables);
+
+    // A conclusion with queued answers left ends the branch, not the session
+    if next_node.node_type.ends_session() && follow_ups_remaining > 0 {
+        sqlx::query(
+            "UPDATE sessions
+             SET steps = $1, variables = $2, last_activity_at = NOW(),
+                 abandoned = false, abandoned_at = NULL, abandon_reason = NULL
+             WHERE session_id = $3"
+        )
+        .bind(&steps_json)
+        .bind(&captured_json)
+        .bind(&session_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        session_watch::publish(&state.db, SessionChangeKind::Answered, &session_id, next_node.id, steps.len());
+        record_step(&session_id, &steps, &next_node);
+
+        return Ok(SubmitAnswerResponse {
+            session_id,
+            notices: category_notices::active(&next_node.category),
+            attachments: uploads::node_attachments(&state.db, next_node.id).await?,
+            remaining: remaining_depth(state, &next_node).await?,
+            node: next_node.clone(),
+            options: vec![],
+            is_conclusion: true,
+            kb_url: next_node.kb_url,
+            doc_reference: next_node.doc_reference,
+            conclusion_text: Some(next_node.text),
+            follow_ups_remaining,
+        });
+    }
+
+    // Check if this is a conclusion node
+    if next_node.node_type.ends_session() {
+        // Session is complete
+        sqlx::query(
+            "UPDATE sessions
+             SET steps = $1, variables = $2, final_conclusion = $3, conclusion_node_id = $4,
+                 completed_at = NOW(), last_activity_at = NOW(),
+                 abandoned = false, abandoned_at = NULL, abandon_reason = NULL
+             WHERE session_id = $5"
+        )
+        .bind(&steps_json)
+        .bind(&captured_json)
+        .bind(&final_conclusion)
+        .bind(next_node.id)
+        .bind(&session_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        session_watch::publish(&state.db, SessionChangeKind::Completed, &session_id, next_node.id, steps.len());
+        record_step(&session_id, &steps, &next_node);
+        session_events::record(
+            &session_id,
+            SessionEventType::Completed,
+            Some(&next_node.category),
+            Some(serde_json::json!({
+                "conclusion_node_id": next_node.id,
+                "step_count": steps.len(),
+            })),
+        );
+
+        return Ok(SubmitAnswerResponse {
+            session_id,
+            notices: category_notices::active(&next_node.category),
+            attachments: uploads::node_attachments(&state.db, next_node.id).await?,
+            remaining: remaining_depth(state, &next_node).await?,
+            node: next_node.clone(),
+            options: vec![],
+            is_conclusion: true,
+            kb_url: next_node.kb_url,
+            doc_reference: next_node.doc_reference,
+            conclusion_text: Some(next_node.text),
+            follow_ups_remaining: 0,
+        });
+    }
+
+    let options = fetch_options(&state.db, next_node.id, false).await?;
+    let options = offered_options(options, &variables);
+
+    // Update session
+    sqlx::query(
+        "UPDATE sessions
+         SET steps = $1, variables = $2, last_activity_at = NOW(),
+             abandoned = false, abandoned_at = NULL, abandon_reason = NULL
+         WHERE session_id = $3"
+    )
+    .bind(&steps_json)
+    .bind(&captured_json)
+    .bind(&session_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    session_watch::publish(&state.db, SessionChangeKind::Answered, &session_id, next_node.id, steps.len());
+    record_step(&session_id, &steps, &next_node);
+
+    Ok(SubmitAnswerResponse {
+        session_id,
+        notices: category_notices::active(&next_node.category),
+        attachments: uploads::node_attachments(&state.db, next_node.id).await?,
+        remaining: remaining_depth(state, &next_node).await?,
+        node: next_node,
+        options,
+        is_conclusion: false,
+        conclusion_text: None,
+        kb_url: None,
+        doc_reference: None,
+        follow_ups_remaining: 0,
+    })
+}
+
+/// Check the picks on a multi-select question and put them in priority order
+///
+/// Priority is the order the options are shown in; the first pick is followed.
+async fn route_multi_select(
+    state: &AppState,
+    connection_ids: &[Uuid],
+    variables: &Variables,
+) -> ApiResult<(AnswerMode, Vec<(Uuid, String)>)> {
+    let invalid = |message: &str| ApiError::validation(vec![("connection_ids".to_string(), message.to_string())]);
+
+    let mut requested = connection_ids.to_vec();
+    requested.sort();
+    requested.dedup();
+    if requested.is_empty() {
+        return Err(invalid("Select at least one answer"));
+    }
+
+    let picks = sqlx::query_as::<_, (Uuid, String, Uuid, AnswerMode, Option<String>)>(
+        "SELECT c.id, c.label, c.from_node_id, p.answer_mode, c.condition
+         FROM connections c
+         INNER JOIN nodes n ON c.to_node_id = n.id
+         INNER JOIN nodes p ON c.from_node_id = p.id
+         WHERE c.id = ANY($1)
+           AND c.is_active = true
+           AND n.is_active = true
+         ORDER BY CASE WHEN p.option_order = 'weight' THEN c.weight END DESC NULLS LAST, c.order_index ASC",
+    )
+    .bind(&requested)
+    .fetch_all(&state.db)
+    .await?;
+
+    if picks.len() != requested.len() {
+        return Err(invalid("One or more answers do not exist"));
+    }
+    if picks.iter().any(|(_, _, from_node_id, _, _)| *from_node_id != picks[0].2) {
+        return Err(invalid("All answers must belong to the same question"));
+    }
+    let answer_mode = picks[0].3;
+    if !answer_mode.is_multi_select() {
+        return Err(invalid("This question takes a single answer"));
+    }
+    if picks.iter().any(|(_, _, _, _, condition)| !conditions::available(condition.as_deref(), variables)) {
+        return Err(invalid("One or more answers do not apply given the earlier answers"));
+    }
+
+    Ok((answer_mode, picks.into_iter().map(|(id, label, _, _, _)| (id, label)).collect()))
+}
+
+/// Pick the connection a measurement reading routes to
+async fn route_measurement(state: &AppState, node_id: Option<Uuid>, value: f64, variables: &Variables) -> ApiResult<Uuid> {
+    let node_id = node_id.ok_or_else(|| {
+        ApiError::validation(vec![("node_id".to_string(), "Required when submitting a value".to_string())])
+    })?;
+
+    if !value.is_finite() {
+        return Err(ApiError::validation(vec![("value".to_string(), "Must be a finite number".to_string())]));
+    }
+
+    // Conditions are checked in the author's order, regardless of weights
+    let routes = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        "SELECT c.id, c.condition
+         FROM connections c
+         INNER JOIN nodes n ON c.to_node_id = n.id
+         WHERE c.from_node_id = $1
+           AND c.is_active = true
+           AND n.is_active = true
+         ORDER BY c.order_index ASC",
+    )
+    .bind(node_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    if !routes.iter().any(|(_, condition)| conditions::uses_reading(condition.as_deref())) {
+        return Err(ApiError::validation(vec![(
+            "node_id".to_string(),
+            "This question does not take a measurement".to_string(),
+        )]));
+    }
+
+    conditions::route(&routes, variables, value).ok_or_else(|| {
+        ApiError::validation(vec![(
+            "value".to_string(),
+            format!("No answer covers a reading of {}", value),
+        )])
+    })
+}
+
+/// Where a session stands: its current question and options, or the
+/// conclusion it reached
+pub async fn current(state: &AppState, session_id: String) -> ApiResult<SubmitAnswerResponse> {
+    // Get session
+    let session = sqlx::query!(
+        "SELECT steps, variables, final_conclusion, completed_at, entry_point, start_node_id FROM sessions WHERE session_id = $1",
+        session_id
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Session not found"))?;
+
+    // Parse steps to find current position
+    let steps: Vec<serde_json::Value> = serde_json::from_value(session.steps)
+        .unwrap_or_default();
+    let captured: Captured = serde_json::from_value(session.variables).unwrap_or_default();
+    let variables = session_variables(state, &captured, &steps).await?;
+
+    // If no steps, return starting node
+    if steps.is_empty() {
+        if let Some(start_node_id) = session.start_node_id {
+            let mut node = fetch_node(&state.db, start_node_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+            render_node(&mut node, &variables);
+
+            return Ok(SubmitAnswerResponse {
+                session_id,
+                notices: category_notices::active(&node.category),
+                attachments: uploads::node_attachments(&state.db, node.id).await?,
+                remaining: remaining_depth(state, &node).await?,
+                options: start_options(state, node.id).await?,
+                node,
+                is_conclusion: false,
+                conclusion_text: None,
+                kb_url: None,
+                doc_reference: None,
+                follow_ups_remaining: 0,
+            });
+        }
+
+        // Sessions started before the start node was recorded
+        if let Some(entry_point) = &session.entry_point {
+            let StartQuestion { mut node, options } = load_entry_point_question(state, entry_point)
+                .await?
+                .ok_or_else(|| ApiError::not_found(format!("Entry point '{}' not found", entry_point)))?;
+            render_node(&mut node, &variables);
+
+            return Ok(SubmitAnswerResponse {
+                session_id,
+                notices: category_notices::active(&node.category),
+                attachments: uploads::node_attachments(&state.db, node.id).await?,
+                remaining: remaining_depth(state, &node).await?,
+                node,
+                options,
+                is_conclusion: false,
+                conclusion_text: None,
+                kb_url: None,
+                doc_reference: None,
+                follow_ups_remaining: 0,
+            });
+        }
+
+        let mut root_node = sqlx::query_as::<_, Node>(&format!(
+            "SELECT {} FROM nodes WHERE semantic_id = 'start' AND is_active = true",
+            NODE_COLUMNS
+        ))
+        .fetch_one(&state.db)
+        .await?;
+        render_node(&mut root_node, &variables);
+
+        let options = fetch_options(&state.db, root_node.id, false).await?;
+        let options = offered_options(options, &variables);
+
+        return Ok(SubmitAnswerResponse {
+            session_id,
+            notices: category_notices::active(&root_node.category),
+            attachments: uploads::node_attachments(&state.db, root_node.id).await?,
+            remaining: remaining_depth(state, &root_node).await?,
+            node: root_node,
+            options,
+            is_conclusion: false,
+            conclusion_text: None,
+            kb_url: None,
+            doc_reference: None,
+            follow_ups_remaining: 0,
+        });
+    }
+
+    // The last step determines the current node
+    let current_node_id = step_target(state, &steps[steps.len() - 1]).await?;
+
+    let mut current_node = fetch_node(&state.db, current_node_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+    render_node(&mut current_node, &variables);
+
+    let follow_ups_remaining = pending_follow_ups(&steps).len();
+
+    // If current node is a conclusion, session should be marked complete
+    // (unless queued answers remain)
+    if current_node.node_type.ends_session() {
+        return Ok(SubmitAnswerResponse {
+            session_id,
+            notices: category_notices::active(&current_node.category),
+            attachments: uploads::node_attachments(&state.db, current_node.id).await?,
+            remaining: remaining_depth(state, &current_node).await?,
+            node: current_node.clone(),
+            options: vec![],
+            is_conclusion: true,
+            kb_url: current_node.kb_url,
+            doc_reference: current_node.doc_reference,
+            conclusion_text: Some(current_node.text),
+            follow_ups_remaining,
+        });
+    }
+
+    let options = fetch_options(&state.db, current_node.id, false).await?;
+    let options = offered_options(options, &variables);
+
+    Ok(SubmitAnswerResponse {
+        session_id,
+        notices: category_notices::active(&current_node.category),
+        attachments: uploads::node_attachments(&state.db, current_node.id).await?,
+        remaining: remaining_depth(state, &current_node).await?,
+        node: current_node,
+        options,
+        is_conclusion: false,
+        conclusion_text: None,
+        kb_url: None,
+        doc_reference: None,
+        follow_ups_remaining,
+    })
+}
+
+/// Check that every path through an issue can end (see `graph_validation`)
+pub async fn validate(state: &AppState, category: &str) -> ApiResult<GraphValidationReport> {
+    graph_validation::validate_category(&state.db, category)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Issue category '{}' not found", category)))
+}
+
+/// Truncate a session's steps at `step_index` (default: the last step) and
+/// resume at the question that step answered
+///
+/// Returning to an earlier question reopens a concluded session.
+pub async fn rewind(
+    state: &AppState,
+    session_id: String,
+    step_index: Option<usize>,
+) -> ApiResult<SubmitAnswerResponse> {
+    let mut tx = state.db.begin().await?;
+    let LockedSession { mut steps, captured, completed } = lock_session(&mut tx, &session_id).await?;
+
+    let step_index = match step_index {
+        Some(index) if index >= steps.len() => {
+            return Err(ApiError::validation(vec![(
+                "step_index".to_string(),
+                format!("Session has {} step(s)", steps.len()),
+            )]));
+        }
+        Some(index) => index,
+        None => steps
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| ApiError::bad_request("Session is already at its first question"))?,
+    };
+
+    let node_id: Uuid = serde_json::from_value(steps[step_index]["node_id"].clone())
+        .map_err(|_| ApiError::internal("Invalid session data"))?;
+    steps.truncate(step_index);
+
+    let mut node = fetch_node(&mut *tx, node_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Previous question no longer exists"))?;
+
+    sqlx::query(
+        "UPDATE sessions
+         SET steps = $1, final_conclusion = NULL, conclusion_node_id = NULL, completed_at = NULL,
+             feedback_resolved = NULL, feedback_comment = NULL, feedback_at = NULL, last_activity_at = NOW(),
+             abandoned = false, abandoned_at = NULL, abandon_reason = NULL
+         WHERE session_id = $2"
+    )
+    .bind(serde_json::to_value(&steps)?)
+    .bind(&session_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    session_watch::publish(&state.db, SessionChangeKind::Rewound, &session_id, node.id, steps.len());
+
+    if completed {
+        tracing::debug!("↩️ Session {} reopened by returning to step {}", session_id, step_index);
+    }
+
+    // Captured values are kept; only answers are undone
+    let variables = session_variables(state, &captured, &steps).await?;
+    render_node(&mut node, &variables);
+
+    // The first question hides rollout candidates, like at session start
+    let options = if steps.is_empty() {
+        start_options(state, node.id).await?
+    } else {
+        let options = fetch_options(&state.db, node.id, false).await?;
+        offered_options(options, &variables)
+    };
+
+    Ok(SubmitAnswerResponse {
+        session_id,
+        notices: category_notices::active(&node.category),
+        attachments: uploads::node_attachments(&state.db, node.id).await?,
+        remaining: remaining_depth(state, &node).await?,
+        node,
+        options,
+        is_conclusion: false,
+        conclusion_text: None,
+        kb_url: None,
+        doc_reference: None,
+        follow_ups_remaining: pending_follow_ups(&steps).len(),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_points_skip_unserved_categories() {
+        let query = entry_point_question_query();
+        assert!(query.contains("n.category NOT IN (SELECT draft_category FROM issue_drafts)"));
+        assert!(query.contains("n.category NOT IN (SELECT category FROM issue_archives)"));
+        assert!(query.contains("n.category NOT IN (SELECT category FROM issues WHERE active_from > NOW()"));
+    }
+
+    #[test]
+    fn test_start_options_skip_unserved_categories() {
+        let served = options_query(true);
+        assert!(served.contains("n.category NOT IN (SELECT candidate_category FROM issue_rollouts)"));
+        assert!(served.contains("n.category NOT IN (SELECT draft_category FROM issue_drafts)"));
+
+        let all = options_query(false);
+        assert!(!all.contains("issue_rollouts"));
+        assert!(!all.contains("issue_drafts"));
+    }
+
+    #[test]
+    fn test_resume_codes() {
+        let code = generate_resume_code();
+        assert_eq!(code.len(), RESUME_CODE_LENGTH);
+        assert!(code.bytes().all(|b| RESUME_CODE_ALPHABET.contains(&b)));
+        assert_eq!(normalize_resume_code(&code.to_lowercase()), code);
+        assert_eq!(normalize_resume_code(" abc-234 "), "ABC234");
+    }
+
+    #[test]
+    fn test_start_session_request() {
+        let req = StartSessionRequest {
+            tech_identifier: Some("Tech123".to_string()),
+            client_site: Some("Site A".to_string()),
+            category: None,
+            entry_point: Some("lobby".to_string()),
+            equipment_id: None,
+            variables: None,
+        };
+        assert!(req.tech_identifier.is_some());
+    }
+
+    #[test]
+    fn test_capture() {
+        let captured = capture(
+            Captured::from([("serial".to_string(), "SN-1".to_string())]),
+            Some(Captured::from([
+                ("error_code".to_string(), " E42 ".to_string()),
+                ("serial".to_string(), "".to_string()),
+            ])),
+        )
+        .unwrap();
+        assert_eq!(captured, Captured::from([("error_code".to_string(), "E42".to_string())]));
+
+        assert!(capture(Captured::new(), Some(Captured::from([("value".to_string(), "1".to_string())]))).is_err());
+        assert!(capture(Captured::new(), Some(Captured::from([("serial".to_string(), "x".repeat(201))]))).is_err());
+        let too_many = (0..=MAX_CAPTURED_VARIABLES).map(|i| (format!("v{}", i), "x".to_string())).collect();
+        assert!(capture(Captured::new(), Some(too_many)).is_err());
+    }
+
+    #[test]
+    fn test_submit_answer_request() {
+        let req: SubmitAnswerRequest =
+            serde_json::from_value(serde_json::json!({ "node_id": Uuid::new_v4(), "value": 12.6 })).unwrap();
+        assert!(req.connection_id.is_none());
+        assert_eq!(req.value, Some(12.6));
+    }
+
+    #[test]
+    fn test_pending_follow_ups() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut steps = vec![
+            serde_json::json!({ "connection_id": Uuid::new_v4() }),
+            serde_json::json!({ "connection_id": a, "connection_ids": [a, b, c], "queued": [b, c] }),
+        ];
+        assert_eq!(pending_follow_ups(&steps), vec![b, c]);
+
+        // Following b opens another multi-select whose queue goes first
+        steps.push(serde_json::json!({ "connection_id": b, "follow_up": true }));
+        steps.push(serde_json::json!({ "connection_id": Uuid::new_v4(), "queued": [d] }));
+        assert_eq!(pending_follow_ups(&steps), vec![d, c]);
+
+        // Going back restores the queue
+        steps.truncate(2);
+        assert_eq!(pending_follow_ups(&steps), vec![b, c]);
+        assert!(pending_follow_ups(&steps[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_subflow_stack() {
+        let (power, wiring) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut steps = vec![
+            serde_json::json!({ "connection_id": Uuid::new_v4(), "subflows": [{ "entered": power }] }),
+            serde_json::json!({ "connection_id": Uuid::new_v4(), "subflows": [{ "entered": wiring }] }),
+        ];
+        assert_eq!(subflow_stack(&steps), vec![power, wiring]);
+
+        // Coming out of the wiring check straight into another power check
+        steps.push(serde_json::json!({
+            "connection_id": Uuid::new_v4(),
+            "subflows": [{ "returned": wiring }, { "returned": power }, { "entered": power }],
+        }));
+        assert_eq!(subflow_stack(&steps), vec![power]);
+
+        // Going back restores it
+        steps.truncate(1);
+        assert_eq!(subflow_stack(&steps), vec![power]);
+        assert!(subflow_stack(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_variables_from_steps() {
+        let (pump, meter, unnamed) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let semantic_ids = std::collections::HashMap::from([
+            (pump, "pump_start".to_string()),
+            (meter, "battery_voltage".to_string()),
+        ]);
+        let steps = vec![
+            serde_json::json!({ "node_id": pump, "connection_label": "Yes" }),
+            serde_json::json!({ "node_id": meter, "connection_label": "Low", "value": 11.2 }),
+            serde_json::json!({ "node_id": unnamed, "connection_label": "Done" }),
+            // Answering a question again replaces its variable
+            serde_json::json!({ "node_id": pump, "connection_label": "No" }),
+        ];
+
+        let variables = variables_from_steps(&steps, &semantic_ids);
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables["pump_start"], Value::Text("No".to_string()));
+        assert_eq!(variables["battery_voltage"], Value::Number(11.2));
+    }
+}
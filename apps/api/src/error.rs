@@ -6,6 +6,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use crate::utils::graph_validation::GraphValidationReport;
+
 /// API Error types with TypeScript export
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
@@ -23,6 +25,12 @@ pub enum ApiError {
     /// Validation error with field-specific messages (422)
     ValidationError { fields: Vec<ValidationField> },
 
+    /// An issue's graph has problems that stop it being activated (422)
+    InvalidGraph {
+        message: String,
+        report: GraphValidationReport,
+    },
+
     /// Database error (500)
     DatabaseError { message: String },
 
@@ -34,6 +42,13 @@ pub enum ApiError {
 
     /// Conflict - resource already exists (409)
     Conflict { message: String },
+
+    /// Too many attempts - retry after the given number of seconds (429)
+    TooManyRequests {
+        message: String,
+        #[ts(type = "number")]
+        retry_after_seconds: u64,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -41,8 +56,45 @@ pub enum ApiError {
 pub struct ValidationField {
     pub field: String,
     pub message: String,
+    /// JSON pointer (RFC 6901) to the offending element of a nested payload,
+    /// e.g. `/connections/3/label`
+    #[serde(default)]
+    pub pointer: Option<String>,
+}
+
+impl ValidationField {
+    /// Field error located by a JSON pointer; `field` is the dotted form (`connections[3].label`)
+    pub fn at(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        let pointer = pointer.into();
+        Self {
+            field: pointer_to_field(&pointer),
+            message: message.into(),
+            pointer: Some(pointer),
+        }
+    }
 }
 
+/// Dotted field name for a JSON pointer (`/connections/3/label` → `connections[3].label`)
+fn pointer_to_field(pointer: &str) -> String {
+    let mut field = String::new();
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+            field.push_str(&format!("[{}]", segment));
+        } else {
+            if !field.is_empty() {
+                field.push('.');
+            }
+            field.push_str(&segment);
+        }
+    }
+    field
+}
+
+/// Error code attached to error responses as a response extension
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorCode(pub &'static str);
+
 /// Standard error response format
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
@@ -74,11 +126,29 @@ impl ApiError {
         ApiError::ValidationError {
             fields: fields
                 .into_iter()
-                .map(|(field, message)| ValidationField { field, message })
+                .map(|(field, message)| ValidationField { field, message, pointer: None })
                 .collect(),
         }
     }
 
+    /// Validation error for elements of a nested payload, given as (JSON pointer, message)
+    pub fn validation_at(fields: Vec<(String, String)>) -> Self {
+        ApiError::ValidationError {
+            fields: fields
+                .into_iter()
+                .map(|(pointer, message)| ValidationField::at(pointer, message))
+                .collect(),
+        }
+    }
+
+    /// Refuse a change because of the problems in a graph validation report
+    pub fn invalid_graph(report: GraphValidationReport) -> Self {
+        ApiError::InvalidGraph {
+            message: report.summary(),
+            report,
+        }
+    }
+
     pub fn database(message: impl Into<String>) -> Self {
         ApiError::DatabaseError {
             message: message.into(),
@@ -97,6 +167,49 @@ impl ApiError {
         }
     }
 
+    pub fn too_many_requests(message: impl Into<String>, retry_after_seconds: u64) -> Self {
+        ApiError::TooManyRequests {
+            message: message.into(),
+            retry_after_seconds,
+        }
+    }
+
+    /// Stable error code (matches the serialized `type` tag)
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound { .. } => "NotFound",
+            ApiError::Unauthorized { .. } => "Unauthorized",
+            ApiError::Forbidden { .. } => "Forbidden",
+            ApiError::ValidationError { .. } => "ValidationError",
+            ApiError::InvalidGraph { .. } => "InvalidGraph",
+            ApiError::DatabaseError { .. } => "DatabaseError",
+            ApiError::InternalError { .. } => "InternalError",
+            ApiError::BadRequest { .. } => "BadRequest",
+            ApiError::Conflict { .. } => "Conflict",
+            ApiError::TooManyRequests { .. } => "TooManyRequests",
+        }
+    }
+
+    /// Human-readable message (validation errors list their fields)
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::ValidationError { fields } => fields
+                .iter()
+                .map(|f| format!("{}: {}", f.field, f.message))
+                .collect::<Vec<_>>()
+                .join("; "),
+            ApiError::NotFound { message }
+            | ApiError::Unauthorized { message }
+            | ApiError::Forbidden { message }
+            | ApiError::InvalidGraph { message, .. }
+            | ApiError::DatabaseError { message }
+            | ApiError::InternalError { message }
+            | ApiError::BadRequest { message }
+            | ApiError::Conflict { message }
+            | ApiError::TooManyRequests { message, .. } => message.clone(),
+        }
+    }
+
     /// Get HTTP status code for this error
     pub fn status_code(&self) -> StatusCode {
         match self {
@@ -104,10 +217,12 @@ impl ApiError {
             ApiError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
             ApiError::Forbidden { .. } => StatusCode::FORBIDDEN,
             ApiError::ValidationError { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::InvalidGraph { .. } => StatusCode::UNPROCESSABLE_ENTITY,
             ApiError::DatabaseError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::BadRequest { .. } => StatusCode::BAD_REQUEST,
             ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
@@ -116,13 +231,24 @@ impl ApiError {
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let status = self.status_code();
+        let code = ErrorCode(self.code());
+        let retry_after = match &self {
+            ApiError::TooManyRequests { retry_after_seconds, .. } => Some(*retry_after_seconds),
+            _ => None,
+        };
 
         let error_response = ErrorResponse {
             error: self,
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
 
-        (status, Json(error_response)).into_response()
+        let mut response = (status, Json(error_response)).into_response();
+        // Lets middleware see which error produced the response without parsing the body
+        response.extensions_mut().insert(code);
+        if let Some(seconds) = retry_after {
+            response.headers_mut().insert(axum::http::header::RETRY_AFTER, seconds.into());
+        }
+        response
     }
 }
 
@@ -131,6 +257,10 @@ impl From<sqlx::Error> for ApiError {
     fn from(err: sqlx::Error) -> Self {
         match err {
             sqlx::Error::RowNotFound => ApiError::not_found("Resource not found"),
+            sqlx::Error::PoolTimedOut => {
+                crate::utils::pool_metrics::record_acquire_timeout();
+                ApiError::database("Database is busy, please retry")
+            }
             sqlx::Error::Database(db_err) => {
                 tracing::error!("Database error: {}", db_err);
                 ApiError::database("Database operation failed")
@@ -172,6 +302,40 @@ mod tests {
             ApiError::forbidden("test").status_code(),
             StatusCode::FORBIDDEN
         );
+        assert_eq!(
+            ApiError::too_many_requests("test", 30).status_code(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[test]
+    fn test_too_many_requests_sets_retry_after() {
+        let response = ApiError::too_many_requests("Slow down", 42).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[axum::http::header::RETRY_AFTER], "42");
+    }
+
+    #[test]
+    fn test_validation_pointer_fields() {
+        let field = ValidationField::at("/connections/3/label", "Label is required");
+        assert_eq!(field.pointer.as_deref(), Some("/connections/3/label"));
+        assert_eq!(field.field, "connections[3].label");
+        assert_eq!(pointer_to_field("/0/nodes/12"), "[0].nodes[12]");
+        assert_eq!(pointer_to_field("/a~1b"), "a/b");
+
+        // Flat field errors have no pointer
+        let json = serde_json::to_value(ApiError::validation(vec![("email".to_string(), "Invalid".to_string())])).unwrap();
+        assert!(json["data"]["fields"][0]["pointer"].is_null());
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(ApiError::not_found("Issue not found").message(), "Issue not found");
+        let error = ApiError::validation(vec![
+            ("name".to_string(), "Must not be empty".to_string()),
+            ("tags".to_string(), "At most 20 tags allowed".to_string()),
+        ]);
+        assert_eq!(error.message(), "name: Must not be empty; tags: At most 20 tags allowed");
     }
 
     #[test]
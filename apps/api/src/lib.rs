@@ -1,25 +1,38 @@
 // Re-export modules
+pub mod engine;
 pub mod error;
 pub mod middleware;
 pub mod models;
 pub mod openapi;
 pub mod routes;
+pub mod uploads;
 pub mod utils;
 
 use sqlx::PgPool;
 use crate::utils::cache::Cache;
+use crate::utils::settings::Settings;
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Fewest and most questions left from each node, as cached per category
+pub type RemainingDepths = Arc<HashMap<Uuid, (u32, u32)>>;
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
-    /// Cache for questions list (5 minute TTL)
+    /// Cache for start questions and their options (5 minute TTL)
     pub questions_cache: Cache<String, JsonValue>,
     /// Cache for issue trees (10 minute TTL)
     pub issue_tree_cache: Cache<String, JsonValue>,
     /// Cache for issue graphs (10 minute TTL)
     pub issue_graph_cache: Cache<String, JsonValue>,
+    /// Cache of (min, max) questions left from each node, by category (10 minute TTL)
+    pub remaining_depth_cache: Cache<String, RemainingDepths>,
+    /// Admin-managed runtime settings (refreshed every minute)
+    pub settings: Settings,
 }
 
 impl AppState {
@@ -27,14 +40,29 @@ impl AppState {
     pub fn new(db: PgPool) -> Self {
         Self {
             db,
-            // Cache questions for 5 minutes, max 10 entries
-            questions_cache: Cache::new(300, 10),
+            // Cache start questions for 5 minutes, max 60 entries (global start + warmed categories)
+            questions_cache: Cache::new(300, 60),
             // Cache issue trees for 10 minutes, max 50 entries
             issue_tree_cache: Cache::new(600, 50),
             // Cache issue graphs for 10 minutes, max 50 entries
             issue_graph_cache: Cache::new(600, 50),
+            // Cache remaining depths for 10 minutes, max 50 categories
+            remaining_depth_cache: Cache::new(600, 50),
+            settings: Settings::default(),
         }
     }
+
+    /// Drop cached data after an issue category changes
+    ///
+    /// Start questions list options that span categories, and answers can
+    /// lead into other categories, so the questions and remaining depth
+    /// caches are cleared entirely.
+    pub async fn invalidate_issue(&self, category: &str) {
+        self.issue_graph_cache.invalidate(&format!("graph_{}", category)).await;
+        self.issue_tree_cache.invalidate(&category.to_string()).await;
+        self.questions_cache.clear().await;
+        self.remaining_depth_cache.clear().await;
+    }
 }
 
 #[cfg(test)]
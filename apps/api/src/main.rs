@@ -1,12 +1,14 @@
+mod engine;
 mod error;
 mod middleware;
 mod models;
 mod openapi;
 mod routes;
+mod uploads;
 mod utils;
 
 use axum::{
-    extract::State,
+    extract::{DefaultBodyLimit, State},
     http::{StatusCode, Uri},
     middleware as axum_middleware,
     response::{Html, IntoResponse, Response},
@@ -16,12 +18,13 @@ use axum::{
 use error::{ApiError, ApiResult};
 use equipment_troubleshooting::AppState;
 use middleware::auth::auth_middleware;
+use middleware::client_ip::client_ip_middleware;
+use middleware::error_tracking::error_tracking_middleware;
 use middleware::performance::performance_monitoring_middleware;
-use middleware::rate_limit::{rate_limit_middleware, RateLimiter, RateLimiterExtension};
+use middleware::rate_limit::{rate_limit_middleware, DeviceRateLimiterExtension, RateLimiter, RateLimiterExtension};
 use middleware::security::security_headers_middleware;
 use openapi::ApiDoc;
 use serde::Serialize;
-use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use std::sync::Arc;
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
@@ -79,6 +82,8 @@ async fn spa_fallback_handler(uri: Uri) -> Response {
         }
     };
 
+    let cache_control = (header::CACHE_CONTROL, utils::static_cache::cache_control(path));
+
     match tokio::fs::read_to_string(&safe_path).await {
         Ok(contents) => {
             // Determine content type based on file extension
@@ -91,20 +96,20 @@ async fn spa_fallback_handler(uri: Uri) -> Response {
             } else if path.ends_with(".json") {
                 "application/json"
             } else if path.ends_with(".png") || path.ends_with(".jpg") || path.ends_with(".jpeg") {
-                return (StatusCode::OK, tokio::fs::read(&safe_path).await.unwrap()).into_response();
+                return (StatusCode::OK, [cache_control], tokio::fs::read(&safe_path).await.unwrap()).into_response();
             } else if path.ends_with(".svg") {
                 "image/svg+xml"
             } else {
                 "text/plain"
             };
 
-            (StatusCode::OK, [(axum::http::header::CONTENT_TYPE, content_type)], contents).into_response()
+            (StatusCode::OK, [(axum::http::header::CONTENT_TYPE, content_type), cache_control], contents).into_response()
         }
         Err(_) => {
-            // File doesn't exist, serve index.html for SPA routing
+            // File doesn't exist, serve index.html for SPA routing (always revalidated)
             let index_path = base_path.join("index.html");
             match tokio::fs::read_to_string(&index_path).await {
-                Ok(contents) => Html(contents).into_response(),
+                Ok(contents) => ([(header::CACHE_CONTROL, utils::static_cache::NO_CACHE)], Html(contents)).into_response(),
                 Err(_) => (StatusCode::NOT_FOUND, "Frontend not built").into_response(),
             }
         }
@@ -113,12 +118,12 @@ async fn spa_fallback_handler(uri: Uri) -> Response {
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
-
-    // Load environment variables
+    // Load environment variables (before tracing so RUST_LOG from .env applies)
     dotenvy::dotenv().ok();
 
+    // Initialize tracing with a filter that can be adjusted at runtime
+    utils::log_level::init();
+
     // Get frontend URL for CORS configuration
     let frontend_url = std::env::var("FRONTEND_URL")
         .unwrap_or_else(|_| {
@@ -136,27 +141,67 @@ async fn main() {
 
     tracing::info!("✅ JWT_SECRET validated ({} characters)", jwt_secret.len());
 
+    // Environment JWT keys (previous secrets, optional RSA pair)
+    match utils::jwt_keys::init() {
+        Ok(count) => tracing::info!("🔑 JWT keyset: {} environment key(s)", count),
+        Err(e) => panic!("❌ CRITICAL: invalid JWT key configuration: {}", e),
+    }
+
+    // Upload storage (fail fast on a bad backend config rather than on first upload)
+    match uploads::init() {
+        Ok(service) => tracing::info!(
+            "📎 Upload storage: {}, max {} bytes, virus scanning {}",
+            service.storage.describe(),
+            service.config.max_bytes,
+            service.scanner.as_ref().map_or("disabled".to_string(), |s| format!("via {}", s.address()))
+        ),
+        Err(e) => panic!("❌ CRITICAL: Upload storage misconfigured: {}", e),
+    }
+
     // Get database URL
     let database_url = std::env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set in .env file");
 
-    // Create database connection pool with disabled statement caching
-    // Note: Supabase pooler requires statement_cache_capacity=0 to avoid conflicts
+    // Create database connection pool
+    // Note: transaction-mode poolers (e.g. Supabase) need statement_cache_capacity=0 to avoid
+    // prepared statement conflicts; direct connections keep the cache enabled
     tracing::info!("📦 Connecting to database...");
+    let statement_cache_capacity = utils::pool_metrics::statement_cache_capacity(&database_url);
     let connect_options = PgConnectOptions::from_str(&database_url)
         .expect("Invalid DATABASE_URL")
-        .statement_cache_capacity(0); // Disable prepared statements for Supabase pooler
+        .statement_cache_capacity(statement_cache_capacity);
 
+    if statement_cache_capacity == 0 {
+        tracing::info!("📦 Prepared statement cache disabled (connection pooler mode)");
+    } else {
+        tracing::info!("📦 Prepared statement cache enabled ({} statements)", statement_cache_capacity);
+    }
+
+    // Pool sizing comes from DB_* env vars (defaults: 2-20 connections, 3s acquire timeout);
+    // the db_max_connections setting lowers the limit at runtime once settings load
+    let pool_config = utils::pool_metrics::PoolConfig::from_env();
+    let pool_limit = utils::pool_metrics::PoolLimit::default();
     let pool = PgPoolOptions::new()
-        .max_connections(20) // Increased from 5 to 20 for better concurrency
-        .min_connections(2)  // Maintain 2 connections ready
-        .acquire_timeout(std::time::Duration::from_secs(3)) // 3s timeout
-        .idle_timeout(Some(std::time::Duration::from_secs(600))) // 10 min idle timeout
+        .max_connections(pool_config.max_connections)
+        .after_release({
+            let pool_limit = pool_limit.clone();
+            move |_, _| {
+                let keep = pool_limit.keep_released();
+                Box::pin(async move { Ok(keep) })
+            }
+        })
+        .min_connections(pool_config.min_connections)
+        .acquire_timeout(pool_config.acquire_timeout)
+        .idle_timeout(Some(pool_config.idle_timeout))
         .connect_with(connect_options)
         .await
         .expect("Failed to create database pool");
 
-    tracing::info!("✅ Database connected successfully (pool: 2-20 connections)");
+    tracing::info!(
+        "✅ Database connected successfully (pool: {}-{} connections)",
+        pool_config.min_connections,
+        pool_config.max_connections
+    );
 
     // Run migrations (commented out to avoid prepared statement conflicts with pooler)
     // Note: Migrations have already been applied to the database
@@ -173,64 +218,377 @@ async fn main() {
 
     // Create rate limiter (100 requests per 60 seconds per IP)
     let rate_limiter = Arc::new(RateLimiter::new(100, 60));
+    // Registered kiosks get the same allowance each, however many share an IP
+    let device_rate_limiter = Arc::new(RateLimiter::new(100, 60));
     tracing::info!("🚦 Rate limiter initialized (100 requests/60 seconds)");
 
     // Spawn background task to clean up old rate limit entries every 5 minutes
     // This prevents memory leak by removing expired entries from the HashMap
     {
         let rate_limiter_cleanup = Arc::clone(&rate_limiter);
+        let device_rate_limiter_cleanup = Arc::clone(&device_rate_limiter);
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(300)); // 5 minutes
             loop {
                 interval.tick().await;
                 rate_limiter_cleanup.cleanup().await;
+                device_rate_limiter_cleanup.cleanup().await;
                 tracing::debug!("🧹 Rate limiter cleanup completed");
             }
         });
         tracing::info!("🧹 Rate limiter cleanup task started (runs every 5 minutes)");
     }
 
+    // Optionally snapshot rate limit buckets so restarts don't reset everyone's quota
+    let rate_limit_snapshot_secs = std::env::var("RATE_LIMIT_SNAPSHOT_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    if rate_limit_snapshot_secs > 0 {
+        match rate_limiter.load(&state.db).await {
+            Ok(count) => tracing::info!("🚦 Restored {} rate limit bucket(s)", count),
+            Err(e) => tracing::warn!("⚠️  Failed to restore rate limit buckets: {}", e),
+        }
+
+        let rate_limiter_snapshot = Arc::clone(&rate_limiter);
+        let db = state.db.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(rate_limit_snapshot_secs));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = rate_limiter_snapshot.save(&db).await {
+                    tracing::warn!("Failed to snapshot rate limit buckets: {}", e);
+                }
+            }
+        });
+        tracing::info!("🚦 Rate limit snapshots every {} seconds", rate_limit_snapshot_secs);
+    }
+
+    // Load role permission grants (falls back to read-only defaults)
+    match utils::permissions::reload(&state.db).await {
+        Ok(()) => tracing::info!("🔑 Role permissions loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load role permissions, using defaults: {}", e),
+    }
+    utils::permissions::spawn_refresh(state.db.clone());
+
+    // Load active service tokens (service accounts are refused until this succeeds)
+    match utils::service_tokens::reload(&state.db).await {
+        Ok(()) => tracing::info!("🤖 Service tokens loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load service tokens: {}", e),
+    }
+    utils::service_tokens::spawn_refresh(state.db.clone());
+
+    // Load registered kiosks (their tokens are refused until this succeeds)
+    match utils::devices::reload(&state.db).await {
+        Ok(()) => tracing::info!("📟 Kiosk devices loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load kiosk devices: {}", e),
+    }
+    utils::devices::spawn_refresh(state.db.clone());
+
+    // Load category notices (sessions show none until this succeeds)
+    match utils::category_notices::reload(&state.db).await {
+        Ok(()) => tracing::info!("📢 Category notices loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load category notices: {}", e),
+    }
+    utils::category_notices::spawn_refresh(state.db.clone());
+
+    // Load rotated JWT signing keys (JWT_SECRET keeps signing until this succeeds)
+    match utils::jwt_keys::reload(&state.db).await {
+        Ok(()) => tracing::info!("🔑 JWT signing keys loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load rotated JWT signing keys: {}", e),
+    }
+    utils::jwt_keys::spawn_refresh(state.db.clone());
+
+    // Load deployment branding (defaults are served until this succeeds)
+    match utils::branding::reload(&state.db).await {
+        Ok(()) => tracing::info!("🎨 Branding loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load branding: {}", e),
+    }
+    utils::branding::spawn_refresh(state.db.clone());
+
+    // Load admin overrides of runtime settings (defaults apply until this succeeds)
+    match state.settings.reload(&state.db).await {
+        Ok(()) => tracing::info!("⚙️  Settings loaded"),
+        Err(e) => tracing::warn!("⚠️  Failed to load settings: {}", e),
+    }
+    state.settings.spawn_refresh(state.db.clone());
+    {
+        let settings = state.settings.clone();
+        pool_limit.follow(state.db.clone(), move || settings.db_max_connections());
+    }
+
+    // Relay session changes from every instance to supervisors watching here
+    utils::session_watch::spawn_listener(state.db.clone());
+
+    // Mark sessions abandoned once they've been idle for `session_idle_minutes`
+    {
+        let db = state.db.clone();
+        let settings = state.settings.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let idle_minutes = settings.session_idle_minutes();
+                match utils::session_expiry::expire_idle(&db, idle_minutes).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("💤 Marked {} sessions idle for {}+ minutes as abandoned", n, idle_minutes),
+                    Err(e) => tracing::warn!("⚠️  Failed to expire idle sessions: {}", e),
+                }
+            }
+        });
+        tracing::info!("💤 Idle session expiry task started (runs every minute)");
+    }
+
+    // Activate and deactivate scheduled (seasonal) issues as their windows open and close
+    utils::issue_schedules::spawn(state.clone());
+    tracing::info!("📅 Issue schedule task started (runs every minute)");
+
+    // Drop long-expired refresh tokens and old failed logins, and archive old sessions, once an hour
+    {
+        let db = state.db.clone();
+        let settings = state.settings.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                match utils::refresh_tokens::purge_expired(&db).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("🧹 Purged {} expired refresh tokens", n),
+                    Err(e) => tracing::warn!("⚠️  Failed to purge expired refresh tokens: {}", e),
+                }
+                match utils::login_guard::purge_old_failures(&db).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("🧹 Purged {} old failed login records", n),
+                    Err(e) => tracing::warn!("⚠️  Failed to purge failed login records: {}", e),
+                }
+                if let Some(days) = settings.session_archive_after_days() {
+                    match utils::session_archive::archive_older_than(&db, days).await {
+                        Ok(0) => {}
+                        Ok(n) => tracing::info!("🗄️  Archived {} sessions older than {} days", n, days),
+                        Err(e) => tracing::warn!("⚠️  Failed to archive old sessions: {}", e),
+                    }
+                }
+            }
+        });
+    }
+
+    // Sample pool acquire latency and warn when the pool is saturated
+    utils::pool_metrics::spawn_monitor(state.db.clone(), pool_config, pool_limit);
+    tracing::info!("📈 Database pool monitor started (samples every 15 seconds)");
+
+    // Session lifecycle events go to the analytics sinks in ANALYTICS_SINKS (optional)
+    let analytics_sinks = utils::analytics::sinks_from_env(&state.db).await;
+    if analytics_sinks.iter().any(|sink| sink.name() == "postgres") {
+        utils::session_events::spawn_partition_maintenance(state.db.clone());
+    }
+    if !analytics_sinks.is_empty() {
+        let names: Vec<&str> = analytics_sinks.iter().map(|sink| sink.name()).collect();
+        tracing::info!("🗂️  Session analytics events sent to: {}", names.join(", "));
+    }
+    utils::analytics::spawn(analytics_sinks);
+
+    // Pre-populate caches for the busiest categories (optional)
+    let warming = utils::cache_warming::config();
+    if warming.enabled {
+        tracing::info!(
+            "🔥 Cache warming enabled (top {} categories over {} days)",
+            warming.top_categories,
+            warming.window_days
+        );
+        utils::cache_warming::schedule(state.clone(), "startup");
+    }
+
+    // Anonymous usage telemetry is opt-in; nothing is sent unless TELEMETRY_ENABLED=true
+    match utils::telemetry::TelemetryConfig::from_env() {
+        Some(config) => {
+            tracing::info!(
+                "📡 Anonymous telemetry enabled (every {}h to {})",
+                config.interval.as_secs() / 3600,
+                config.endpoint
+            );
+            utils::telemetry::spawn(state.db.clone(), config);
+        }
+        None => tracing::info!("📡 Anonymous telemetry disabled"),
+    }
+
+    // Audit entries are forwarded to a SIEM only when SIEM_ENDPOINT is set
+    if let Some(config) = utils::siem::SiemConfig::from_env() {
+        tracing::info!(
+            "🛡️ SIEM forwarding enabled (every {}s; auth events {}, session events {})",
+            config.interval.as_secs(),
+            if config.include_auth_events { "included" } else { "excluded" },
+            if config.include_session_events { "included" } else { "excluded" },
+        );
+        utils::siem::spawn(state.db.clone(), config);
+    }
+
     // Build protected routes (require authentication)
     let protected_routes = Router::new()
-        .route("/api/v1/auth/me", get(routes::auth::me))
+        .route("/api/v1/auth/me", get(routes::auth::me).patch(routes::auth::update_me))
+        .route("/api/v1/auth/change-password", post(routes::auth::change_password))
+        .route("/api/v1/auth/sessions", get(routes::auth::list_device_sessions))
+        .route("/api/v1/auth/sessions/:id", delete(routes::auth::revoke_device_session))
         .layer(axum_middleware::from_fn(auth_middleware));
 
-    // Build admin-only routes (require ADMIN role)
-    let admin_routes = Router::new()
-        // Admin dashboard routes
-        .route("/api/v1/admin/sessions", get(routes::admin::list_sessions))
-        .route("/api/v1/admin/sessions", delete(routes::admin::delete_sessions))
-        .route("/api/v1/admin/sessions/count", get(routes::admin::count_sessions))
-        .route("/api/v1/admin/stats", get(routes::admin::get_stats))
-        .route("/api/v1/admin/audit-logs", get(routes::admin::get_audit_logs))
-        .route("/api/v1/admin/performance", get(routes::admin::get_performance_metrics))
-        // Category management routes
-        .route("/api/v1/admin/categories", get(routes::admin::list_categories))
-        .route("/api/v1/admin/categories/:name", put(routes::admin::rename_category).delete(routes::admin::delete_category))
-        // Issues management routes
+    // Room for multipart framing on top of the largest accepted file
+    let upload_body_limit = uploads::service().map_or(0, |s| s.config.max_bytes) + 64 * 1024;
+
+    // Admin API routes, grouped by the permission each requires
+    let permission =
+        |required: models::Permission| axum_middleware::from_fn_with_state(required, middleware::auth::require_permission);
+
+    let issues_read_routes = Router::new()
         .route("/api/v1/admin/issues", get(routes::issues::list_issues))
-        .route("/api/v1/admin/issues", post(routes::issues::create_issue))
-        // Import/Export routes (must come before /:category routes to avoid conflicts)
+        // Export routes (must come before /:category routes to avoid conflicts)
         .route("/api/v1/admin/issues/export-all", get(routes::issues::export_all_issues))
-        .route("/api/v1/admin/issues/import", post(routes::issues::import_issues))
+        .route("/api/v1/admin/issues/stale", get(routes::issues::get_stale_issues))
+        .route("/api/v1/admin/issues/approvals", get(routes::issues::list_issue_approvals))
         .route("/api/v1/admin/issues/:category/graph", get(routes::issues::get_issue_graph))
         .route("/api/v1/admin/issues/:category/export", get(routes::issues::export_issue))
-        .route("/api/v1/admin/issues/:category", put(routes::issues::update_issue))
-        .route("/api/v1/admin/issues/:category", delete(routes::issues::delete_issue))
-        .route("/api/v1/admin/issues/:category/toggle", patch(routes::issues::toggle_issue))
-        // Node routes (NODE-GRAPH)
+        .route("/api/v1/admin/issues/:category/export/incremental", get(routes::issues::export_issue_incremental))
+        .route("/api/v1/admin/issues/:category/rollout", get(routes::issues::get_issue_rollout))
+        .route("/api/v1/admin/issues/:category/draft", get(routes::issues::get_issue_draft))
+        .route("/api/v1/admin/issues/:category/versions", get(routes::issues::list_issue_versions))
+        .route("/api/v1/admin/issues/:category/versions/diff", get(routes::issues::diff_issue_versions))
+        .route("/api/v1/admin/issues/:category/quota", get(routes::issues::get_issue_quota))
+        .route("/api/v1/admin/issues/:category/checksum", get(routes::issues::get_issue_checksum))
+        .route("/api/v1/admin/issues/:category/validate", get(routes::issues::validate_issue))
+        .route("/api/v1/admin/issues/:category/orphans", get(routes::issues::list_issue_orphans))
+        .route("/api/v1/admin/issues/:category/simulate", post(routes::issues::simulate_issue))
+        .route("/api/v1/admin/categories", get(routes::admin::list_categories))
+        .route("/api/v1/admin/lint", post(routes::admin::lint_content))
+        // Node and connection reads (NODE-GRAPH)
         .route("/api/v1/nodes", get(routes::nodes::list_nodes))
         .route("/api/v1/nodes/:id", get(routes::nodes::get_node))
         .route("/api/v1/nodes/:id/with-connections", get(routes::nodes::get_node_with_connections))
-        .route("/api/v1/nodes", post(routes::nodes::create_node))
-        .route("/api/v1/nodes/:id", put(routes::nodes::update_node))
-        .route("/api/v1/nodes/:id", delete(routes::nodes::delete_node))
-        // Connection routes (NODE-GRAPH)
+        .route("/api/v1/nodes/:id/history", get(routes::nodes::get_node_history))
+        .route("/api/v1/nodes/:id/attachments", get(routes::uploads::list_node_attachments))
         .route("/api/v1/connections", get(routes::connections::list_connections))
+        .route("/api/v1/admin/entry-points", get(routes::entry_points::list_entry_points))
+        .route("/api/v1/admin/notices", get(routes::notices::list_notices))
+        .layer(permission(models::Permission::IssuesRead));
+
+    let issues_write_routes = Router::new()
+        .route("/api/v1/admin/issues", post(routes::issues::create_issue))
+        .route("/api/v1/admin/issues/import", post(routes::issues::import_issues))
+        .route("/api/v1/admin/issues/import/incremental", post(routes::issues::import_issue_incremental))
+        .route("/api/v1/admin/issues/import/external", post(routes::issues::import_external))
+        .route(
+            "/api/v1/admin/issues/import/bundle",
+            post(routes::issues::import_bundle).layer(DefaultBodyLimit::max(utils::issue_bundle::MAX_BUNDLE_BYTES)),
+        )
+        .route("/api/v1/admin/issues/bulk-toggle", patch(routes::issues::bulk_toggle_issues))
+        .route("/api/v1/admin/issues/bulk", post(routes::issues::bulk_update_issues))
+        .route("/api/v1/admin/issues/:category/rollout", put(routes::issues::update_issue_rollout).delete(routes::issues::delete_issue_rollout))
+        .route("/api/v1/admin/issues/:category/draft", post(routes::issues::create_issue_draft).delete(routes::issues::discard_issue_draft))
+        .route("/api/v1/admin/issues/:category/approval", post(routes::issues::submit_issue_approval).delete(routes::issues::withdraw_issue_approval))
+        .route("/api/v1/admin/issues/:category/archive", post(routes::issues::archive_issue))
+        .route("/api/v1/admin/issues/:category/unarchive", post(routes::issues::unarchive_issue))
+        .route("/api/v1/admin/issues/:category/rename-slug", put(routes::issues::rename_issue_slug))
+        .route("/api/v1/admin/issues/:category/quota", put(routes::issues::update_issue_quota).delete(routes::issues::delete_issue_quota))
+        .route("/api/v1/admin/issues/:category/layout", post(routes::issues::auto_layout_issue))
+        .route("/api/v1/admin/issues/:category/layout/normalize", post(routes::issues::normalize_issue_layout))
+        .route("/api/v1/admin/issues/:category/orphans/cleanup", post(routes::issues::cleanup_issue_orphans))
+        .route("/api/v1/admin/issues/:category/purge", post(routes::issues::purge_inactive))
+        .route("/api/v1/admin/issues/:category", put(routes::issues::update_issue).delete(routes::issues::delete_issue))
+        .route("/api/v1/admin/issues/:category/toggle", patch(routes::issues::toggle_issue))
+        .route("/api/v1/admin/issues/:category/review", post(routes::issues::mark_issue_reviewed))
+        .route("/api/v1/admin/issues/:category/connection-weights/recompute", post(routes::issues::recompute_connection_weights))
+        .route("/api/v1/admin/categories/:name", put(routes::admin::rename_category).delete(routes::admin::delete_category))
+        // Node and connection edits (NODE-GRAPH)
+        .route("/api/v1/nodes", post(routes::nodes::create_node))
+        .route("/api/v1/nodes/positions", patch(routes::nodes::update_node_positions))
+        .route("/api/v1/nodes/:id", put(routes::nodes::update_node).delete(routes::nodes::delete_node))
+        .route("/api/v1/nodes/:id/clone", post(routes::nodes::clone_node))
+        .route("/api/v1/nodes/:id/subtree", post(routes::nodes::transfer_subtree))
+        .route("/api/v1/nodes/:id/merge", post(routes::nodes::merge_nodes))
+        .route("/api/v1/nodes/:id/restore", post(routes::nodes::restore_node))
+        .route("/api/v1/nodes/:id/history/:revision_id/revert", post(routes::nodes::revert_node_revision))
+        .route(
+            "/api/v1/nodes/:id/attachments",
+            post(routes::uploads::create_node_attachment).layer(DefaultBodyLimit::max(upload_body_limit)),
+        )
+        .route("/api/v1/nodes/:id/attachments/:upload_id", delete(routes::uploads::delete_node_attachment))
         .route("/api/v1/connections", post(routes::connections::create_connection))
-        .route("/api/v1/connections/:id", put(routes::connections::update_connection))
-        .route("/api/v1/connections/:id", delete(routes::connections::delete_connection))
-        .layer(axum_middleware::from_fn(middleware::auth::require_admin));
+        .route("/api/v1/connections/:id", put(routes::connections::update_connection).delete(routes::connections::delete_connection))
+        .route("/api/v1/connections/:id/restore", post(routes::connections::restore_connection))
+        .route("/api/v1/admin/entry-points", post(routes::entry_points::create_entry_point))
+        .route("/api/v1/admin/entry-points/:name", put(routes::entry_points::update_entry_point).delete(routes::entry_points::delete_entry_point))
+        .route("/api/v1/admin/notices", post(routes::notices::create_notice))
+        .route("/api/v1/admin/notices/:id", put(routes::notices::update_notice).delete(routes::notices::delete_notice))
+        .layer(permission(models::Permission::IssuesWrite));
+
+    // Changes that go live without an approval step
+    let issues_approve_routes = Router::new()
+        .route("/api/v1/admin/issues/:category/publish", post(routes::issues::publish_issue_draft))
+        .route("/api/v1/admin/issues/:category/versions/:version/rollback", post(routes::issues::rollback_issue_version))
+        .route("/api/v1/admin/issues/:category/approval/approve", post(routes::issues::approve_issue_approval))
+        .route("/api/v1/admin/issues/:category/approval/reject", post(routes::issues::reject_issue_approval))
+        .layer(permission(models::Permission::IssuesApprove));
+
+    let sessions_read_routes = Router::new()
+        .route("/api/v1/admin/sessions", get(routes::admin::list_sessions))
+        .route("/api/v1/admin/sessions/count", get(routes::admin::count_sessions))
+        .route("/api/v1/admin/issues/:category/sessions/export", get(routes::admin::export_category_sessions))
+        .route("/api/v1/admin/sessions/archive/:session_id", get(routes::admin::get_archived_session))
+        .route("/api/v1/admin/sessions/:session_id", get(routes::admin::get_session_detail))
+        .layer(permission(models::Permission::SessionsRead));
+
+    let sessions_delete_routes = Router::new()
+        .route("/api/v1/admin/sessions", delete(routes::admin::delete_sessions))
+        .route("/api/v1/admin/sessions/archive", post(routes::admin::archive_sessions))
+        .route("/api/v1/admin/sessions/:session_id/merge", post(routes::admin::merge_sessions))
+        .layer(permission(models::Permission::SessionsDelete));
+
+    let stats_routes = Router::new()
+        .route("/api/v1/admin/stats", get(routes::admin::get_stats))
+        .layer(permission(models::Permission::StatsRead));
+
+    let audit_routes = Router::new()
+        .route("/api/v1/admin/audit-logs", get(routes::admin::get_audit_logs))
+        .layer(permission(models::Permission::AuditRead));
+
+    let system_routes = Router::new()
+        .route("/api/v1/admin/performance", get(routes::admin::get_performance_metrics))
+        .route("/api/v1/admin/errors", get(routes::admin::get_recent_errors))
+        .route("/api/v1/admin/log-level", get(routes::admin::get_log_level).put(routes::admin::set_log_level).delete(routes::admin::reset_log_level))
+        .route("/api/v1/admin/jwt-keys", get(routes::admin::list_jwt_keys))
+        .route("/api/v1/admin/jwt-keys/rotate", post(routes::admin::rotate_jwt_key))
+        .route("/api/v1/admin/branding", get(routes::config::get_branding).put(routes::config::update_branding))
+        .route("/api/v1/admin/settings", get(routes::config::list_settings))
+        .route("/api/v1/admin/settings/:key", put(routes::config::update_setting).delete(routes::config::reset_setting))
+        .route("/api/v1/admin/migrate/legacy", post(routes::admin::migrate_legacy_tree))
+        .layer(permission(models::Permission::SystemManage));
+
+    let users_routes = Router::new()
+        .route("/api/v1/admin/users", get(routes::users::list_users).post(routes::users::create_user))
+        .route("/api/v1/admin/users/:id", get(routes::users::get_user).put(routes::users::update_user).delete(routes::users::deactivate_user))
+        .route("/api/v1/admin/users/:id/role", put(routes::users::change_user_role))
+        .route("/api/v1/admin/users/:id/categories", get(routes::users::get_user_categories).put(routes::users::update_user_categories))
+        .route("/api/v1/admin/roles/permissions", get(routes::users::list_role_permissions))
+        .route("/api/v1/admin/roles/:role/permissions", put(routes::users::update_role_permissions))
+        .route("/api/v1/admin/service-accounts", get(routes::service_accounts::list_service_accounts).post(routes::service_accounts::create_service_account))
+        .route("/api/v1/admin/service-accounts/:id/tokens", post(routes::service_accounts::create_service_token))
+        .route("/api/v1/admin/service-tokens/:id", delete(routes::service_accounts::revoke_service_token))
+        .route("/api/v1/admin/devices", get(routes::devices::list_devices).post(routes::devices::register_device))
+        .route("/api/v1/admin/devices/:id/disable", post(routes::devices::disable_device))
+        .route("/api/v1/admin/devices/:id/enable", post(routes::devices::enable_device))
+        .layer(permission(models::Permission::UsersManage));
+
+    let uploads_read_routes = Router::new()
+        .route("/api/v1/admin/uploads", get(routes::uploads::list_uploads))
+        .route("/api/v1/admin/uploads/:id", get(routes::uploads::get_upload))
+        .layer(permission(models::Permission::UploadsRead));
+
+    // Multipart bodies may exceed the default 2 MB limit
+    let uploads_write_routes = Router::new()
+        .route(
+            "/api/v1/admin/uploads",
+            post(routes::uploads::create_upload).layer(DefaultBodyLimit::max(upload_body_limit)),
+        )
+        .route("/api/v1/admin/uploads/:id", delete(routes::uploads::delete_upload))
+        .layer(permission(models::Permission::UploadsWrite));
 
     // Get static files path from environment or use default
     let static_files_path = std::env::var("STATIC_FILES_PATH")
@@ -238,6 +596,24 @@ async fn main() {
 
     tracing::info!("📁 Static files path: {}", static_files_path);
 
+    // Get host from env or use default
+    let host = std::env::var("HOST")
+        .unwrap_or_else(|_| "0.0.0.0".to_string());
+
+    // Get port from env or use default
+    let port = std::env::var("PORT")
+        .unwrap_or_else(|_| "5000".to_string())
+        .parse::<u16>()
+        .expect("PORT must be a valid number");
+
+    // Parse the host and port into a SocketAddr
+    let addr_str = format!("{}:{}", host, port);
+    let addr = addr_str.parse::<SocketAddr>()
+        .unwrap_or_else(|_| panic!("Invalid HOST:PORT combination: {}", addr_str));
+
+    // Point "Try it out" at this deployment rather than a fixed list of hosts
+    let api_doc = ApiDoc::for_deployment(&frontend_url, addr, std::env::var("ENVIRONMENT").ok().as_deref());
+
     // Build router
     let app = Router::new()
         .route("/health", get(health_check))
@@ -245,7 +621,7 @@ async fn main() {
         // OpenAPI/Swagger documentation with enhanced configuration
         .merge(
             SwaggerUi::new("/swagger-ui")
-                .url("/api-docs/openapi.json", ApiDoc::openapi())
+                .url("/api-docs/openapi.json", api_doc)
                 .config(utoipa_swagger_ui::Config::default()
                     .try_it_out_enabled(true)  // Enable "Try it out" by default
                     .filter(true)               // Enable search/filter
@@ -257,23 +633,60 @@ async fn main() {
         // Authentication routes (public)
         .route("/api/v1/auth/login", post(routes::auth::login))
         .route("/api/v1/auth/refresh", post(routes::auth::refresh))
+        .route("/api/v1/auth/logout", post(routes::auth::logout))
+        .route("/api/v1/auth/forgot-password", post(routes::auth::forgot_password))
+        .route("/api/v1/auth/reset-password", post(routes::auth::reset_password))
+        // Deployment branding (public)
+        .route("/api/v1/config/public", get(routes::config::get_public_config))
         // Troubleshooting routes (public)
         .route("/api/v1/troubleshoot/start", post(routes::troubleshoot::start_session))
+        .route("/api/v1/troubleshoot/resume/:code", get(routes::troubleshoot::resume_session))
         .route("/api/v1/troubleshoot/:session_id", get(routes::troubleshoot::get_session))
         .route("/api/v1/troubleshoot/:session_id/answer", post(routes::troubleshoot::submit_answer))
+        .route("/api/v1/troubleshoot/:session_id/heartbeat", post(routes::troubleshoot::heartbeat))
+        .route("/api/v1/troubleshoot/:session_id/back", post(routes::troubleshoot::go_back))
+        .route("/api/v1/troubleshoot/:session_id/follow-up", post(routes::troubleshoot::next_follow_up))
+        .route("/api/v1/troubleshoot/:session_id/skip", post(routes::troubleshoot::skip_to_conclusion))
+        .route("/api/v1/troubleshoot/:session_id/goto", post(routes::troubleshoot::goto_step))
+        .route("/api/v1/troubleshoot/:session_id/feedback", post(routes::troubleshoot::submit_feedback))
+        .route("/api/v1/troubleshoot/:session_id/abandon", post(routes::troubleshoot::abandon_session))
+        .route("/api/v1/troubleshoot/:session_id/steps/:index/note", post(routes::troubleshoot::set_step_note))
         .route("/api/v1/troubleshoot/:session_id/history", get(routes::troubleshoot::get_session_history))
+        .route(
+            "/api/v1/troubleshoot/:session_id/attachments",
+            post(routes::troubleshoot::upload_attachment).layer(DefaultBodyLimit::max(upload_body_limit)),
+        )
+        .route("/api/v1/troubleshoot/:session_id/events", get(routes::troubleshoot::watch_session))
+        // Content manifest for kiosks (public, optionally signed)
+        .route("/api/v1/kiosk/manifest", get(routes::issues::get_kiosk_manifest))
+        // Upload downloads (public, authorized by the URL signature)
+        .route("/api/v1/uploads/:id/content", get(routes::uploads::download_upload))
         // Merge protected routes
         .merge(protected_routes)
-        // Merge admin routes
-        .merge(admin_routes)
+        // Merge permission-gated admin routes
+        .merge(issues_read_routes)
+        .merge(issues_write_routes)
+        .merge(issues_approve_routes)
+        .merge(sessions_read_routes)
+        .merge(sessions_delete_routes)
+        .merge(stats_routes)
+        .merge(audit_routes)
+        .merge(system_routes)
+        .merge(users_routes)
+        .merge(uploads_read_routes)
+        .merge(uploads_write_routes)
         // Demo error endpoints
         .route("/api/v1/demo/not-found", get(demo_not_found))
         .route("/api/v1/demo/unauthorized", get(demo_unauthorized))
         .route("/api/v1/demo/validation", get(demo_validation))
         .layer(axum_middleware::from_fn(performance_monitoring_middleware))
+        .layer(axum_middleware::from_fn(error_tracking_middleware))
         .layer(axum_middleware::from_fn(security_headers_middleware))
         .layer(axum_middleware::from_fn(rate_limit_middleware))
         .layer(axum::Extension(RateLimiterExtension(rate_limiter)))
+        .layer(axum::Extension(DeviceRateLimiterExtension(device_rate_limiter)))
+        // Resolve the client IP before rate limiting (forwarded headers only from TRUSTED_PROXIES)
+        .layer(axum_middleware::from_fn(client_ip_middleware))
         // SECURITY: Configure CORS to only allow specific origins instead of permissive
         .layer(
             CorsLayer::new()
@@ -293,6 +706,7 @@ async fn main() {
                 .allow_headers([
                     header::CONTENT_TYPE,
                     header::AUTHORIZATION,
+                    header::HeaderName::from_static(utils::devices::HEADER),
                 ])
                 .allow_credentials(true)
         )
@@ -300,21 +714,6 @@ async fn main() {
         // Serve static files for SPA (fallback to index.html for client-side routing)
         .fallback(spa_fallback_handler);
 
-    // Get host from env or use default
-    let host = std::env::var("HOST")
-        .unwrap_or_else(|_| "0.0.0.0".to_string());
-
-    // Get port from env or use default
-    let port = std::env::var("PORT")
-        .unwrap_or_else(|_| "5000".to_string())
-        .parse::<u16>()
-        .expect("PORT must be a valid number");
-
-    // Parse the host and port into a SocketAddr
-    let addr_str = format!("{}:{}", host, port);
-    let addr = addr_str.parse::<SocketAddr>()
-        .unwrap_or_else(|_| panic!("Invalid HOST:PORT combination: {}", addr_str));
-
     tracing::info!("🚀 Equipment Troubleshooting System");
 
     // Check if HTTPS is requested via environment variables
@@ -384,7 +783,7 @@ async fn main() {
         .expect("Failed to load SSL certificates");
 
         axum_server::bind_rustls(addr, config)
-            .serve(app.into_make_service())
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .expect("Failed to start HTTPS server");
     } else {
@@ -399,7 +798,7 @@ async fn main() {
             .await
             .expect("Failed to bind to address");
 
-        axum::serve(listener, app)
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .expect("Failed to start server");
     }
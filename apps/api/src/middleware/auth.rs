@@ -1,17 +1,41 @@
 use crate::error::{ApiError, ApiResult};
-use crate::models::UserRole;
+use crate::models::Permission;
+use crate::utils::{permissions, service_tokens};
 use crate::utils::jwt::{extract_token, verify_token, Claims};
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::header,
     middleware::Next,
     response::Response,
 };
+use std::collections::BTreeSet;
 
 /// Extension type to store authenticated user claims in request
 #[derive(Clone, Debug)]
 pub struct AuthUser(pub Claims);
 
+/// Scopes of the service token a request was made with (absent for JWTs)
+#[derive(Clone, Debug)]
+pub struct ServiceScopes(pub BTreeSet<Permission>);
+
+/// Check a permission a handler needs beyond the one guarding its route
+///
+/// Resolved like `require_permission`: service tokens by their scopes,
+/// everyone else by their role's grants.
+pub fn ensure_permission(auth: &AuthUser, scopes: Option<&ServiceScopes>, permission: Permission) -> ApiResult<()> {
+    let granted = match scopes {
+        Some(ServiceScopes(scopes)) => scopes.contains(&permission),
+        None => permissions::has_permission(&auth.0.role, permission),
+    };
+    if !granted {
+        return Err(ApiError::forbidden(format!(
+            "This action requires the '{}' permission",
+            permission.as_str()
+        )));
+    }
+    Ok(())
+}
+
 /// Middleware to verify JWT token and extract user claims
 pub async fn auth_middleware(
     mut request: Request,
@@ -35,8 +59,14 @@ pub async fn auth_middleware(
     Ok(next.run(request).await)
 }
 
-/// Middleware to require ADMIN role
-pub async fn require_admin(
+/// Middleware to require a fine-grained permission
+///
+/// Use with `from_fn_with_state(Permission::IssuesWrite, require_permission)`.
+/// Grants are resolved from the caller's role on every request, so permission
+/// edits apply without the user logging in again. Service tokens are also
+/// accepted here (and only here), checked against the token's scopes.
+pub async fn require_permission(
+    State(required): State<Permission>,
     mut request: Request,
     next: Next,
 ) -> ApiResult<Response> {
@@ -48,13 +78,29 @@ pub async fn require_admin(
         .ok_or_else(|| ApiError::unauthorized("Missing authorization header"))?;
 
     let token = extract_token(auth_header)?;
+
+    // Service tokens carry their own scopes instead of the account's role grants
+    if service_tokens::is_service_token(token) {
+        let grant = service_tokens::lookup(token)
+            .ok_or_else(|| ApiError::unauthorized("Invalid or revoked service token"))?;
+        if !grant.scopes.contains(&required) {
+            return Err(ApiError::forbidden(format!(
+                "This service token is not scoped for '{}'",
+                required.as_str()
+            )));
+        }
+        request.extensions_mut().insert(AuthUser(grant.claims()));
+        request.extensions_mut().insert(ServiceScopes(grant.scopes));
+        return Ok(next.run(request).await);
+    }
+
     let claims = verify_token(token)?;
 
-    // Check if user is ADMIN
-    if !matches!(claims.role, UserRole::Admin) {
-        return Err(ApiError::forbidden(
-            "This action requires administrator privileges",
-        ));
+    if !permissions::has_permission(&claims.role, required) {
+        return Err(ApiError::forbidden(format!(
+            "Your role does not have the '{}' permission",
+            required.as_str()
+        )));
     }
 
     // Add claims to request extensions
@@ -66,6 +112,7 @@ pub async fn require_admin(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::UserRole;
 
     #[test]
     fn test_auth_user_clone() {
@@ -84,4 +131,12 @@ mod tests {
 
         assert_eq!(auth_user.0.email, cloned.0.email);
     }
+
+    #[test]
+    fn test_default_role_permissions() {
+        assert!(permissions::has_permission(&UserRole::Admin, Permission::UsersManage));
+        assert!(permissions::has_permission(&UserRole::Viewer, Permission::IssuesRead));
+        assert!(!permissions::has_permission(&UserRole::Viewer, Permission::IssuesWrite));
+        assert!(!permissions::has_permission(&UserRole::Tech, Permission::SessionsDelete));
+    }
 }
@@ -0,0 +1,51 @@
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::utils::client_ip::{resolve_client_ip, trusted_proxies};
+
+/// Resolved client address, inserted as a request extension
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+/// Client IP middleware
+/// Resolves the real client IP using TRUSTED_PROXIES and rewrites the
+/// forwarded headers so everything downstream (rate limiting, audit logs,
+/// session IP hashes) sees only the resolved address
+pub async fn client_ip_middleware(
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+
+    let trusted = trusted_proxies();
+    let client_ip = resolve_client_ip(peer, request.headers(), trusted);
+    let peer_trusted = peer.map(|ip| trusted.is_trusted(&ip)).unwrap_or(false);
+
+    let headers = request.headers_mut();
+    headers.remove("x-forwarded-for");
+    headers.remove("x-real-ip");
+
+    // Scheme/host hints are only meaningful when set by our own proxy
+    if !peer_trusted {
+        headers.remove("x-forwarded-proto");
+        headers.remove("x-forwarded-host");
+    }
+
+    if let Some(ip) = client_ip {
+        if let Ok(value) = HeaderValue::from_str(&ip.to_string()) {
+            headers.insert("x-forwarded-for", value.clone());
+            headers.insert("x-real-ip", value);
+        }
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+
+    next.run(request).await
+}
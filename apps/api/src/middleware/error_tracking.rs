@@ -0,0 +1,77 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::ErrorCode;
+use crate::utils::error_buffer::{self, RecentError};
+
+/// Header carrying the per-request correlation ID
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Accept a client-supplied request ID only if it is short and printable
+fn sanitize_request_id(value: &HeaderValue) -> Option<String> {
+    let value = value.to_str().ok()?;
+    if value.is_empty() || value.len() > 128 || !value.chars().all(|c| c.is_ascii_graphic()) {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+/// Error tracking middleware
+/// Assigns a correlation ID to every request and records error responses
+/// in the recent-errors buffer shown on the admin dashboard
+pub async fn error_tracking_middleware(
+    request: Request,
+    next: Next,
+) -> Response {
+    let correlation_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(sanitize_request_id)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let mut response = next.run(request).await;
+    let status = response.status();
+
+    if status.is_client_error() || status.is_server_error() {
+        error_buffer::global().push(RecentError {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            method,
+            route,
+            status: status.as_u16(),
+            error_code: response.extensions().get::<ErrorCode>().map(|c| c.0.to_string()),
+            correlation_id: correlation_id.clone(),
+        });
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_request_id() {
+        assert_eq!(
+            sanitize_request_id(&HeaderValue::from_static("abc-123")),
+            Some("abc-123".to_string())
+        );
+        assert_eq!(sanitize_request_id(&HeaderValue::from_static("")), None);
+        assert_eq!(sanitize_request_id(&HeaderValue::from_static("has space")), None);
+    }
+}
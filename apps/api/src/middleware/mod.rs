@@ -1,4 +1,6 @@
 pub mod auth;
+pub mod client_ip;
+pub mod error_tracking;
 pub mod performance;
 pub mod rate_limit;
 pub mod security;
@@ -5,6 +5,8 @@ use axum::{
 };
 use std::time::Instant;
 
+use crate::utils::telemetry;
+
 /// Performance monitoring middleware
 /// Logs request duration and adds timing header
 pub async fn performance_monitoring_middleware(
@@ -19,6 +21,7 @@ pub async fn performance_monitoring_middleware(
 
     let duration = start.elapsed();
     let status = response.status();
+    telemetry::record_response(status);
 
     // Log slow requests (>500ms)
     if duration.as_millis() > 500 {
@@ -4,13 +4,21 @@ use axum::{
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::net::IpAddr;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
+use uuid::Uuid;
 
-/// Rate limiter entry for tracking requests per IP
+use super::client_ip::ClientIp;
+use crate::utils::devices;
+
+/// Rate limiter entry for tracking requests per client
 #[derive(Debug, Clone)]
 struct RateLimitEntry {
     count: u32,
@@ -18,17 +26,20 @@ struct RateLimitEntry {
 }
 
 /// Simple in-memory rate limiter
+///
+/// Clients are keyed by IP address, or by device ID for registered kiosks
+/// that share one.
 #[derive(Debug, Clone)]
-pub struct RateLimiter {
-    /// Map of IP address to rate limit entry
-    entries: Arc<Mutex<HashMap<IpAddr, RateLimitEntry>>>,
+pub struct RateLimiter<K = IpAddr> {
+    /// Map of client to rate limit entry
+    entries: Arc<Mutex<HashMap<K, RateLimitEntry>>>,
     /// Maximum requests per window
     max_requests: u32,
     /// Time window duration
     window_duration: Duration,
 }
 
-impl RateLimiter {
+impl<K: Copy + Eq + Hash> RateLimiter<K> {
     /// Create a new rate limiter
     pub fn new(max_requests: u32, window_seconds: u64) -> Self {
         Self {
@@ -38,12 +49,12 @@ impl RateLimiter {
         }
     }
 
-    /// Check if IP is allowed to make a request
-    pub async fn check_rate_limit(&self, ip: IpAddr) -> Result<(), String> {
+    /// Check if a client is allowed to make a request
+    pub async fn check_rate_limit(&self, key: K) -> Result<(), String> {
         let mut entries = self.entries.lock().await;
         let now = Instant::now();
 
-        let entry = entries.entry(ip).or_insert(RateLimitEntry {
+        let entry = entries.entry(key).or_insert(RateLimitEntry {
             count: 0,
             window_start: now,
         });
@@ -81,10 +92,114 @@ impl RateLimiter {
             now.duration_since(entry.window_start) <= self.window_duration
         });
     }
+
+    /// Buckets still inside their window, with wall-clock window starts
+    pub async fn snapshot(&self) -> Vec<(K, u32, DateTime<Utc>)> {
+        let entries = self.entries.lock().await;
+        let now = Instant::now();
+        let wall_now = Utc::now();
+
+        entries
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.window_start) <= self.window_duration)
+            .filter_map(|(key, entry)| {
+                let elapsed = chrono::Duration::from_std(now.duration_since(entry.window_start)).ok()?;
+                Some((*key, entry.count, wall_now - elapsed))
+            })
+            .collect()
+    }
+
+    /// Merge saved buckets into the limiter, keeping the higher count per client
+    pub async fn restore(&self, buckets: Vec<(K, u32, DateTime<Utc>)>) -> usize {
+        let mut entries = self.entries.lock().await;
+        let now = Instant::now();
+        let wall_now = Utc::now();
+        let mut restored = 0;
+
+        for (key, count, window_start) in buckets {
+            let Ok(elapsed) = (wall_now - window_start).max(chrono::Duration::zero()).to_std() else {
+                continue;
+            };
+            if elapsed > self.window_duration {
+                continue;
+            }
+            let Some(start) = now.checked_sub(elapsed) else {
+                continue;
+            };
+
+            let entry = entries.entry(key).or_insert(RateLimitEntry { count: 0, window_start: start });
+            if count > entry.count {
+                *entry = RateLimitEntry { count, window_start: start };
+            }
+            restored += 1;
+        }
+        restored
+    }
+}
+
+/// Only per-IP buckets are saved; device buckets start fresh after a restart
+impl RateLimiter {
+    /// Write active buckets to the database and prune expired ones
+    pub async fn save(&self, db: &PgPool) -> Result<usize, sqlx::Error> {
+        let buckets = self.snapshot().await;
+        let window_seconds = self.window_duration.as_secs_f64();
+
+        let mut tx = db.begin().await?;
+        sqlx::query("DELETE FROM rate_limit_buckets WHERE window_start < NOW() - make_interval(secs => $1)")
+            .bind(window_seconds)
+            .execute(&mut *tx)
+            .await?;
+
+        if !buckets.is_empty() {
+            let ips: Vec<String> = buckets.iter().map(|(ip, _, _)| ip.to_string()).collect();
+            let counts: Vec<i32> = buckets.iter().map(|(_, count, _)| *count as i32).collect();
+            let starts: Vec<DateTime<Utc>> = buckets.iter().map(|(_, _, start)| *start).collect();
+
+            sqlx::query(
+                "INSERT INTO rate_limit_buckets (ip, request_count, window_start, updated_at)
+                 SELECT *, NOW() FROM UNNEST($1::varchar[], $2::int[], $3::timestamptz[])
+                 ON CONFLICT (ip) DO UPDATE
+                 SET request_count = EXCLUDED.request_count,
+                     window_start = EXCLUDED.window_start,
+                     updated_at = NOW()",
+            )
+            .bind(&ips)
+            .bind(&counts)
+            .bind(&starts)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(buckets.len())
+    }
+
+    /// Restore buckets saved by a previous process
+    pub async fn load(&self, db: &PgPool) -> Result<usize, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, i32, DateTime<Utc>)>(
+            "SELECT ip, request_count, window_start
+             FROM rate_limit_buckets
+             WHERE window_start >= NOW() - make_interval(secs => $1)",
+        )
+        .bind(self.window_duration.as_secs_f64())
+        .fetch_all(db)
+        .await?;
+
+        let buckets = rows
+            .into_iter()
+            .filter_map(|(ip, count, start)| Some((IpAddr::from_str(&ip).ok()?, u32::try_from(count).ok()?, start)))
+            .collect();
+        Ok(self.restore(buckets).await)
+    }
 }
 
 /// Extract IP address from request
+/// Prefers the address resolved by the client IP middleware (trusted proxies only)
 fn extract_ip(request: &Request) -> IpAddr {
+    if let Some(ClientIp(ip)) = request.extensions().get::<ClientIp>() {
+        return *ip;
+    }
+
     // Try to get real IP from X-Forwarded-For header (for proxies)
     if let Some(forwarded_for) = request
         .headers()
@@ -117,15 +232,26 @@ fn extract_ip(request: &Request) -> IpAddr {
 #[derive(Clone)]
 pub struct RateLimiterExtension(pub Arc<RateLimiter>);
 
+/// Extension wrapper for the per-device RateLimiter
+#[derive(Clone)]
+pub struct DeviceRateLimiterExtension(pub Arc<RateLimiter<Uuid>>);
+
 /// Rate limiting middleware
+///
+/// Requests from a registered kiosk count against its device; everything
+/// else counts against the client IP.
 pub async fn rate_limit_middleware(
     axum::Extension(rate_limiter): axum::Extension<RateLimiterExtension>,
+    axum::Extension(device_rate_limiter): axum::Extension<DeviceRateLimiterExtension>,
     request: Request,
     next: Next,
 ) -> Response {
-    let ip = extract_ip(&request);
+    let checked = match devices::from_headers(request.headers()) {
+        Ok(Some(device_id)) => device_rate_limiter.0.check_rate_limit(device_id).await,
+        _ => rate_limiter.0.check_rate_limit(extract_ip(&request)).await,
+    };
 
-    match rate_limiter.0.check_rate_limit(ip).await {
+    match checked {
         Ok(_) => next.run(request).await,
         Err(msg) => (
             StatusCode::TOO_MANY_REQUESTS,
@@ -174,6 +300,36 @@ mod tests {
         assert!(limiter.check_rate_limit(ip2).await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_rate_limiter_devices() {
+        let limiter = RateLimiter::new(1, 60);
+        let (kiosk1, kiosk2) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // Kiosks behind the same address still get a bucket each
+        assert!(limiter.check_rate_limit(kiosk1).await.is_ok());
+        assert!(limiter.check_rate_limit(kiosk2).await.is_ok());
+        assert!(limiter.check_rate_limit(kiosk1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_snapshot_restore() {
+        let limiter = RateLimiter::new(3, 60);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        limiter.check_rate_limit(ip).await.ok();
+        limiter.check_rate_limit(ip).await.ok();
+
+        // A fresh limiter (as after a restart) keeps the used quota
+        let restarted = RateLimiter::new(3, 60);
+        let mut buckets = limiter.snapshot().await;
+        buckets.push(("10.0.0.2".parse().unwrap(), 3, Utc::now() - chrono::Duration::seconds(120)));
+        assert_eq!(restarted.restore(buckets).await, 1);
+
+        assert!(restarted.check_rate_limit(ip).await.is_ok());
+        assert!(restarted.check_rate_limit(ip).await.is_err());
+        // Expired buckets are not restored
+        assert!(restarted.check_rate_limit("10.0.0.2".parse().unwrap()).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_rate_limiter_cleanup() {
         let limiter = RateLimiter::new(5, 1); // 1 second window
@@ -8,19 +8,96 @@ use uuid::Uuid;
 // USER MODELS
 // ============================================
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS, sqlx::Type)]
 #[sqlx(type_name = "user_role", rename_all = "SCREAMING_SNAKE_CASE")]
 #[ts(export, export_to = "../../web/src/types/")]
 pub enum UserRole {
     Admin,
     Viewer,
     Tech,
+    /// Changes issues only through drafts submitted for review
+    Editor,
+}
+
+/// Fine-grained permission granted to roles (`resource:action`)
+///
+/// ADMIN always holds every permission; grants for the other roles live in
+/// the `role_permissions` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum Permission {
+    /// Read issues, graphs, nodes, connections and exports
+    #[serde(rename = "issues:read")]
+    IssuesRead,
+    /// Create, edit, import and delete issues, nodes and connections
+    #[serde(rename = "issues:write")]
+    IssuesWrite,
+    /// Approve or reject drafts submitted for review, and publish or roll back issues directly
+    #[serde(rename = "issues:approve")]
+    IssuesApprove,
+    /// List and export troubleshooting sessions
+    #[serde(rename = "sessions:read")]
+    SessionsRead,
+    #[serde(rename = "sessions:delete")]
+    SessionsDelete,
+    /// Dashboard statistics
+    #[serde(rename = "stats:read")]
+    StatsRead,
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    #[serde(rename = "uploads:read")]
+    UploadsRead,
+    #[serde(rename = "uploads:write")]
+    UploadsWrite,
+    /// Manage users and role permissions
+    #[serde(rename = "users:manage")]
+    UsersManage,
+    /// Performance metrics, error log and runtime log level
+    #[serde(rename = "system:manage")]
+    SystemManage,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 11] = [
+        Permission::IssuesRead,
+        Permission::IssuesWrite,
+        Permission::IssuesApprove,
+        Permission::SessionsRead,
+        Permission::SessionsDelete,
+        Permission::StatsRead,
+        Permission::AuditRead,
+        Permission::UploadsRead,
+        Permission::UploadsWrite,
+        Permission::UsersManage,
+        Permission::SystemManage,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::IssuesRead => "issues:read",
+            Permission::IssuesWrite => "issues:write",
+            Permission::IssuesApprove => "issues:approve",
+            Permission::SessionsRead => "sessions:read",
+            Permission::SessionsDelete => "sessions:delete",
+            Permission::StatsRead => "stats:read",
+            Permission::AuditRead => "audit:read",
+            Permission::UploadsRead => "uploads:read",
+            Permission::UploadsWrite => "uploads:write",
+            Permission::UsersManage => "users:manage",
+            Permission::SystemManage => "system:manage",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Permission> {
+        Permission::ALL.into_iter().find(|p| p.as_str() == value)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
+    pub display_name: Option<String>,
     pub password_hash: String,
     pub role: UserRole,
     pub is_active: bool,
@@ -32,12 +109,95 @@ pub struct User {
 // NODE-GRAPH MODELS
 // ============================================
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS, sqlx::Type)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
 #[ts(export, export_to = "../../web/src/types/")]
 pub enum NodeType {
     Question,
     Conclusion,
+    /// Runs another category's flow, then continues with its own answers
+    Subflow,
+    /// Ends a sub-flow; its text names the outcome
+    Return,
+}
+
+impl NodeType {
+    /// Whether a session that stops here is over
+    ///
+    /// Sessions pass through sub-flow nodes and the returns of sub-flows they
+    /// entered, so a return is only where they stop when the sub-flow was
+    /// started on its own.
+    pub fn ends_session(&self) -> bool {
+        matches!(self, NodeType::Conclusion | NodeType::Return)
+    }
+}
+
+/// How a node orders its answer options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum OptionOrder {
+    /// Fixed order set by the author
+    #[default]
+    OrderIndex,
+    /// Highest connection weight first (ties and unweighted fall back to order_index)
+    Weight,
+}
+
+/// How many answers a question takes, and where several picks lead
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum AnswerMode {
+    /// Exactly one answer
+    #[default]
+    Single,
+    /// Several answers; only the highest-priority pick is followed
+    FirstMatch,
+    /// Several answers; each pick is followed in priority order, returning to
+    /// the next one after each branch reaches a conclusion
+    Queue,
+}
+
+impl AnswerMode {
+    pub fn is_multi_select(&self) -> bool {
+        !matches!(self, Self::Single)
+    }
+}
+
+/// Rich content of a node (sanitized by `utils::content` before it is stored)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodeContent {
+    /// Markdown without raw HTML
+    #[serde(default)]
+    pub markdown: String,
+    #[serde(default)]
+    pub images: Vec<ContentImage>,
+    #[serde(default)]
+    pub links: Vec<ContentLink>,
+}
+
+/// A picture in a node's content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ContentImage {
+    /// http(s) URL or a path on this site, such as an upload
+    pub url: String,
+    #[serde(default)]
+    pub alt: String,
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+/// Further reading linked from a node's content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ContentLink {
+    pub url: String,
+    pub label: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
@@ -51,6 +211,26 @@ pub struct Node {
     pub display_category: Option<String>,
     pub position_x: Option<f64>,
     pub position_y: Option<f64>,
+    /// Knowledge base / repair manual link (conclusions only)
+    pub kb_url: Option<String>,
+    /// Manual section or document number (conclusions only)
+    pub doc_reference: Option<String>,
+    #[serde(default)]
+    pub option_order: OptionOrder,
+    #[serde(default)]
+    pub answer_mode: AnswerMode,
+    /// Where the question's "I'm not sure" option leads: the first question
+    /// of a diagnostic sub-flow or an escalation conclusion; no option is
+    /// offered when unset
+    #[serde(default)]
+    pub not_sure_node_id: Option<Uuid>,
+    /// The category a sub-flow node runs (sub-flow nodes only)
+    #[serde(default)]
+    pub subflow_category: Option<String>,
+    /// Step-by-step instructions, pictures and links shown with `text`
+    #[serde(default)]
+    #[sqlx(json(nullable))]
+    pub content: Option<NodeContent>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -62,10 +242,23 @@ pub struct CreateNode {
     pub category: String,
     pub node_type: NodeType,
     pub text: String,
+    /// Unique within the category; blank means none
     pub semantic_id: Option<String>,
+    /// Derive a free semantic ID from the text when `semantic_id` is blank
+    #[ts(optional)]
+    pub generate_semantic_id: Option<bool>,
     pub display_category: Option<String>,
     pub position_x: Option<f64>,
     pub position_y: Option<f64>,
+    #[ts(optional)]
+    pub kb_url: Option<String>,
+    #[ts(optional)]
+    pub doc_reference: Option<String>,
+    /// The category a sub-flow node runs (required for sub-flow nodes)
+    #[ts(optional)]
+    pub subflow_category: Option<String>,
+    #[ts(optional)]
+    pub content: Option<NodeContent>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -73,8 +266,12 @@ pub struct CreateNode {
 pub struct UpdateNode {
     #[ts(optional)]
     pub text: Option<String>,
+    /// Unique within the category; empty string clears it
     #[ts(optional)]
     pub semantic_id: Option<String>,
+    /// Derive a free semantic ID from the (new) text; ignored when `semantic_id` is set
+    #[ts(optional)]
+    pub generate_semantic_id: Option<bool>,
     #[ts(optional)]
     pub node_type: Option<NodeType>,
     #[ts(optional)]
@@ -83,10 +280,109 @@ pub struct UpdateNode {
     pub position_x: Option<f64>,
     #[ts(optional)]
     pub position_y: Option<f64>,
+    /// Empty string clears the link
+    #[ts(optional)]
+    pub kb_url: Option<String>,
+    /// Empty string clears the reference
+    #[ts(optional)]
+    pub doc_reference: Option<String>,
+    #[ts(optional)]
+    pub option_order: Option<OptionOrder>,
+    #[ts(optional)]
+    pub answer_mode: Option<AnswerMode>,
+    /// Where "I'm not sure" leads (questions only)
+    #[ts(optional)]
+    pub not_sure_node_id: Option<Uuid>,
+    /// Remove the "I'm not sure" option (ignored when `not_sure_node_id` is set)
+    #[ts(optional)]
+    pub clear_not_sure: Option<bool>,
+    /// The category a sub-flow node runs (sub-flow nodes only)
+    #[ts(optional)]
+    pub subflow_category: Option<String>,
+    /// Replaces the node's rich content; empty content clears it
+    #[ts(optional)]
+    pub content: Option<NodeContent>,
     #[ts(optional)]
     pub is_active: Option<bool>,
 }
 
+/// One node's place in a saved graph layout
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodePosition {
+    pub id: Uuid,
+    pub position_x: f64,
+    pub position_y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodePositionsUpdated {
+    /// Number of nodes moved
+    #[ts(type = "number")]
+    pub updated: u64,
+}
+
+/// A copied node and the answers copied along with it
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ClonedNode {
+    pub node: Node,
+    /// Empty unless the clone asked for the outgoing connections
+    pub connections: Vec<Connection>,
+}
+
+/// Whether a subtree is copied or moved to the other category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum SubtreeMode {
+    Copy,
+    Move,
+}
+
+/// Copy or move a node and everything reachable from it into another category
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct TransferSubtree {
+    pub target_category: String,
+    pub mode: SubtreeMode,
+}
+
+/// Merge a duplicate node into another
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct MergeNode {
+    /// The node that takes over the duplicate's incoming answers
+    pub target_node_id: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodesMerged {
+    /// The duplicate, now inactive
+    pub source: Node,
+    pub target_node_id: Uuid,
+    /// Answers that now lead to the target
+    #[ts(type = "number")]
+    pub connections_rewired: u64,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SubtreeTransferred {
+    pub mode: SubtreeMode,
+    pub source_category: String,
+    pub target_category: String,
+    /// The subtree's root in the target category (a new node when copied)
+    pub root_node_id: Uuid,
+    /// The subtree's nodes in the target category, root first
+    pub node_ids: Vec<Uuid>,
+    /// Answers copied along with the nodes (0 when moved)
+    #[ts(type = "number")]
+    pub connections: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct Connection {
@@ -95,6 +391,16 @@ pub struct Connection {
     pub to_node_id: Uuid,
     pub label: String,
     pub order_index: i32,
+    /// Ordering weight used when the source node orders options by weight
+    pub weight: Option<f64>,
+    /// When this answer applies: a reading range on a measurement question
+    /// (e.g. ">= 11.5 and < 13.8") and/or earlier answers (e.g. `pump_start = "No"`)
+    #[serde(default)]
+    pub condition: Option<String>,
+    /// The question's conservative branch: followed instead of the picked
+    /// answer when the tech marks it unsure
+    #[serde(default)]
+    pub unsure_route: bool,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -107,6 +413,14 @@ pub struct CreateConnection {
     pub to_node_id: Uuid,
     pub label: String,
     pub order_index: i32,
+    /// When this answer applies (reading range and/or earlier answers)
+    #[serde(default)]
+    #[ts(optional)]
+    pub condition: Option<String>,
+    /// Send unsure answers to this question down this connection
+    #[serde(default)]
+    #[ts(optional)]
+    pub unsure_route: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -118,6 +432,18 @@ pub struct UpdateConnection {
     pub label: Option<String>,
     #[ts(optional)]
     pub order_index: Option<i32>,
+    /// Manual ordering weight (must be >= 0)
+    #[ts(optional)]
+    pub weight: Option<f64>,
+    /// Remove the weight (ignored when `weight` is set)
+    #[ts(optional)]
+    pub clear_weight: Option<bool>,
+    /// When this answer applies (empty string clears it)
+    #[ts(optional)]
+    pub condition: Option<String>,
+    /// Send unsure answers to this question down this connection
+    #[ts(optional)]
+    pub unsure_route: Option<bool>,
     #[ts(optional)]
     pub is_active: Option<bool>,
 }
@@ -147,4 +473,7 @@ pub struct IssueGraph {
     pub category: String,
     pub nodes: Vec<Node>,
     pub connections: Vec<Connection>,
+    /// Continuation token for the next chunk (null on the last chunk and for unpaginated requests)
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
@@ -1,11 +1,23 @@
-use crate::error::ApiResult;
+use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthUser;
-use crate::utils::audit;
+use crate::models::Node;
+use crate::routes::issues::{self, DuplicateConnectionMode, ImportMode};
+use crate::utils::{audit, category_access};
+use crate::utils::category_quotas::{self, CategoryQuota};
+use crate::utils::error_buffer::{self, RecentError};
+use crate::utils::legacy_tree::{self, LegacyAnswer, LegacyQuestion};
+use crate::utils::lint::{self, LintRules, NodeLintResult};
+use crate::utils::jwt_keys;
+use crate::utils::log_level;
+use crate::utils::pool_metrics;
+use crate::utils::session_archive;
 use crate::AppState;
-use axum::extract::{Query, State};
-use axum::http::HeaderMap;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Response};
 use axum::Extension;
 use axum::Json;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::Row;
@@ -20,10 +32,21 @@ pub struct SessionSummary {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub abandoned: bool,
+    /// Why the tech gave up, when they abandoned the session themselves
+    pub abandon_reason: Option<String>,
+    /// Last answer or heartbeat from the client
+    pub last_activity_at: String,
     pub tech_identifier: Option<String>,
     pub client_site: Option<String>,
+    /// Asset tag or serial of the unit worked on
+    pub equipment_id: Option<String>,
+    /// Kiosk the session was started from
+    pub device_id: Option<Uuid>,
     pub final_conclusion: Option<String>,
     pub step_count: i32,
+    /// Whether the tech said the conclusion fixed the problem
+    pub feedback_resolved: Option<bool>,
+    pub feedback_comment: Option<String>,
 }
 
 /// Response for admin sessions list
@@ -36,6 +59,17 @@ pub struct SessionsListResponse {
     pub page_size: i32,
 }
 
+/// A session with its steps, including any notes the tech left on them
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    pub summary: SessionSummary,
+    pub entry_point: Option<String>,
+    #[ts(type = "unknown")]
+    pub steps: serde_json::Value,
+}
+
 /// Dashboard statistics response
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
@@ -51,6 +85,12 @@ pub struct DashboardStats {
     pub avg_steps_to_completion: f64,
     pub most_common_conclusions: Vec<ConclusionStats>,
     pub sessions_by_category: Vec<CategoryStats>,
+    /// Conclusions with tech feedback, least effective first
+    pub conclusion_effectiveness: Vec<ConclusionEffectiveness>,
+    /// Questions techs most often answer unsure, highest share first
+    pub unsure_questions: Vec<UnsureQuestionStats>,
+    /// Daily session quotas with today's use (not affected by the date filter)
+    pub category_quotas: Vec<CategoryQuota>,
 }
 
 /// Statistics for a specific conclusion
@@ -62,6 +102,41 @@ pub struct ConclusionStats {
     pub count: i64,
 }
 
+/// How often a conclusion fixed the problem, according to tech feedback
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ConclusionEffectiveness {
+    pub node_id: Uuid,
+    pub conclusion: String,
+    pub category: String,
+    /// Sessions that ended at this conclusion
+    #[ts(type = "number")]
+    pub completed: i64,
+    #[ts(type = "number")]
+    pub feedback_count: i64,
+    #[ts(type = "number")]
+    pub resolved_count: i64,
+    /// Share of feedback saying the problem was fixed, from 0 to 1
+    pub effectiveness: f64,
+}
+
+/// How often techs were unsure answering a question
+///
+/// Counted from live sessions only; archived steps are compressed.
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UnsureQuestionStats {
+    pub node_id: Uuid,
+    pub question: String,
+    pub category: String,
+    #[ts(type = "number")]
+    pub answers: i64,
+    #[ts(type = "number")]
+    pub unsure_count: i64,
+    /// Share of answers marked unsure, from 0 to 1
+    pub unsure_rate: f64,
+}
+
 /// Statistics by category
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
@@ -106,7 +181,8 @@ pub struct SessionsQueryParams {
     pub status: Option<String>, // "completed", "abandoned", "active"
     pub start_date: Option<String>,
     pub end_date: Option<String>,
-    pub search: Option<String>, // Search in tech_identifier, client_site
+    pub search: Option<String>, // Search in tech_identifier, client_site, equipment_id
+    pub device_id: Option<Uuid>, // Sessions started from one kiosk
 }
 
 fn default_page() -> i32 {
@@ -117,6 +193,47 @@ fn default_page_size() -> i32 {
     50
 }
 
+/// GET /api/admin/sessions/:session_id
+/// Get a session with its steps and step notes
+pub async fn get_session_detail(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<SessionDetail>> {
+    let row = sqlx::query(
+        "SELECT session_id, started_at, completed_at, abandoned, abandon_reason, last_activity_at,
+                tech_identifier, client_site, equipment_id, device_id, final_conclusion, steps, entry_point,
+                feedback_resolved, feedback_comment
+         FROM sessions
+         WHERE session_id = $1",
+    )
+    .bind(&session_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Session not found"))?;
+
+    let steps: serde_json::Value = row.try_get("steps")?;
+    Ok(Json(SessionDetail {
+        summary: SessionSummary {
+            session_id: row.try_get("session_id")?,
+            started_at: row.try_get::<DateTime<Utc>, _>("started_at")?.to_rfc3339(),
+            completed_at: row.try_get::<Option<DateTime<Utc>>, _>("completed_at")?.map(|t| t.to_rfc3339()),
+            abandoned: row.try_get("abandoned")?,
+            abandon_reason: row.try_get("abandon_reason")?,
+            last_activity_at: row.try_get::<DateTime<Utc>, _>("last_activity_at")?.to_rfc3339(),
+            tech_identifier: row.try_get("tech_identifier")?,
+            client_site: row.try_get("client_site")?,
+            equipment_id: row.try_get("equipment_id")?,
+            device_id: row.try_get("device_id")?,
+            final_conclusion: row.try_get("final_conclusion")?,
+            step_count: steps.as_array().map_or(0, |s| s.len()) as i32,
+            feedback_resolved: row.try_get("feedback_resolved")?,
+            feedback_comment: row.try_get("feedback_comment")?,
+        },
+        entry_point: row.try_get("entry_point")?,
+        steps,
+    }))
+}
+
 /// Query parameters for stats endpoint
 #[derive(Debug, Deserialize)]
 pub struct StatsQueryParams {
@@ -186,6 +303,8 @@ pub async fn list_sessions(
         count_query.push_bind(format!("%{}%", search));
         count_query.push(" OR client_site ILIKE ");
         count_query.push_bind(format!("%{}%", search));
+        count_query.push(" OR equipment_id ILIKE ");
+        count_query.push_bind(format!("%{}%", search));
         count_query.push(")");
     }
 
@@ -194,6 +313,11 @@ pub async fn list_sessions(
         count_query.push_bind(category);
     }
 
+    if let Some(device_id) = params.device_id {
+        count_query.push(" AND device_id = ");
+        count_query.push_bind(device_id);
+    }
+
     // Execute count query
     let total_count = match count_query.build_query_scalar::<i64>()
         .fetch_one(&state.db)
@@ -216,9 +340,10 @@ pub async fn list_sessions(
 
     // Build sessions query with same filters
     let mut sessions_query = QueryBuilder::new(
-        "SELECT session_id, started_at, completed_at, abandoned, \
-         tech_identifier, client_site, final_conclusion, \
-         COALESCE(jsonb_array_length(steps), 0)::int as step_count \
+        "SELECT session_id, started_at, completed_at, abandoned, last_activity_at, \
+         tech_identifier, client_site, equipment_id, final_conclusion, \
+         COALESCE(jsonb_array_length(steps), 0)::int as step_count, \
+         feedback_resolved, feedback_comment, abandon_reason, device_id \
          FROM sessions WHERE 1=1"
     );
 
@@ -253,6 +378,8 @@ pub async fn list_sessions(
         sessions_query.push_bind(format!("%{}%", search));
         sessions_query.push(" OR client_site ILIKE ");
         sessions_query.push_bind(format!("%{}%", search));
+        sessions_query.push(" OR equipment_id ILIKE ");
+        sessions_query.push_bind(format!("%{}%", search));
         sessions_query.push(")");
     }
 
@@ -261,6 +388,11 @@ pub async fn list_sessions(
         sessions_query.push_bind(category);
     }
 
+    if let Some(device_id) = params.device_id {
+        sessions_query.push(" AND device_id = ");
+        sessions_query.push_bind(device_id);
+    }
+
     sessions_query.push(" ORDER BY started_at DESC LIMIT ");
     sessions_query.push_bind(page_size);
     sessions_query.push(" OFFSET ");
@@ -272,10 +404,16 @@ pub async fn list_sessions(
         chrono::DateTime<chrono::Utc>,
         Option<chrono::DateTime<chrono::Utc>>,
         bool,
+        chrono::DateTime<chrono::Utc>,
+        Option<String>,
         Option<String>,
         Option<String>,
         Option<String>,
         i32,
+        Option<bool>,
+        Option<String>,
+        Option<String>,
+        Option<Uuid>,
     )>()
     .fetch_all(&state.db)
     .await {
@@ -299,10 +437,16 @@ pub async fn list_sessions(
             started_at: s.1.to_rfc3339(),
             completed_at: s.2.map(|dt| dt.to_rfc3339()),
             abandoned: s.3,
-            tech_identifier: s.4,
-            client_site: s.5,
-            final_conclusion: s.6,
-            step_count: s.7,
+            abandon_reason: s.12,
+            last_activity_at: s.4.to_rfc3339(),
+            tech_identifier: s.5,
+            client_site: s.6,
+            equipment_id: s.7,
+            device_id: s.13,
+            final_conclusion: s.8,
+            step_count: s.9,
+            feedback_resolved: s.10,
+            feedback_comment: s.11,
         })
         .collect();
 
@@ -330,33 +474,48 @@ pub async fn get_stats(
                 started_at,
                 completed_at,
                 abandoned,
+                last_activity_at,
                 final_conclusion,
-                steps
+                jsonb_array_length(steps) as step_count,
+                (steps->0->>'category')::text as category,
+                conclusion_node_id,
+                feedback_resolved
             FROM sessions
             WHERE ($1::timestamp IS NULL OR started_at >= $1::timestamp)
               AND ($2::timestamp IS NULL OR started_at <= $2::timestamp)
+            UNION ALL
+            -- Archived sessions keep their aggregate columns uncompressed
+            SELECT
+                session_id,
+                started_at,
+                completed_at,
+                abandoned,
+                last_activity_at,
+                final_conclusion,
+                step_count,
+                category,
+                conclusion_node_id,
+                feedback_resolved
+            FROM session_archive
+            WHERE ($1::timestamp IS NULL OR started_at >= $1::timestamp)
+              AND ($2::timestamp IS NULL OR started_at <= $2::timestamp)
         ),
         basic_stats AS (
             SELECT
                 COALESCE(COUNT(*), 0) as total,
                 COALESCE(COUNT(*) FILTER (WHERE completed_at IS NOT NULL), 0) as completed,
-                -- Abandoned = explicitly marked OR incomplete sessions older than 1 hour
-                COALESCE(COUNT(*) FILTER (
-                    WHERE abandoned = true
-                    OR (completed_at IS NULL AND started_at <= NOW() - INTERVAL '1 hour')
-                ), 0) as abandoned,
-                -- Active = incomplete, not abandoned, and started within the last hour
+                -- Abandoned = given up by the tech or expired by the idle job
+                COALESCE(COUNT(*) FILTER (WHERE abandoned = true), 0) as abandoned,
+                -- Active = incomplete and not abandoned
                 COALESCE(COUNT(*) FILTER (
                     WHERE completed_at IS NULL
                     AND abandoned = false
-                    AND started_at > NOW() - INTERVAL '1 hour'
                 ), 0) as active,
                 -- Average steps only for completed sessions with valid steps data
-                COALESCE(AVG(jsonb_array_length(steps)) FILTER (
+                COALESCE(AVG(step_count) FILTER (
                     WHERE completed_at IS NOT NULL
-                    AND steps IS NOT NULL
-                    AND jsonb_array_length(steps) > 0
-                ), 0.0) as avg_steps
+                    AND step_count > 0
+                ), 0.0)::float8 as avg_steps
             FROM filtered_sessions
         ),
         conclusion_stats AS (
@@ -369,12 +528,45 @@ pub async fn get_stats(
         ),
         category_stats AS (
             SELECT
-                COALESCE((steps->0->>'category')::text, 'unknown') as category,
+                COALESCE(category, 'unknown') as category,
                 COUNT(*) as count
             FROM filtered_sessions
-            WHERE steps IS NOT NULL AND jsonb_array_length(steps) > 0
-            GROUP BY category
+            WHERE step_count > 0
+            GROUP BY 1
             ORDER BY count DESC
+        ),
+        effectiveness_stats AS (
+            SELECT
+                f.conclusion_node_id,
+                n.text as conclusion,
+                n.category,
+                COUNT(*) as completed,
+                COUNT(f.feedback_resolved) as feedback_count,
+                COUNT(*) FILTER (WHERE f.feedback_resolved) as resolved_count
+            FROM filtered_sessions f
+            INNER JOIN nodes n ON n.id = f.conclusion_node_id
+            WHERE f.completed_at IS NOT NULL
+            GROUP BY f.conclusion_node_id, n.text, n.category
+            HAVING COUNT(f.feedback_resolved) > 0
+            ORDER BY (COUNT(*) FILTER (WHERE f.feedback_resolved))::float8 / COUNT(f.feedback_resolved), feedback_count DESC
+            LIMIT 20
+        ),
+        unsure_stats AS (
+            SELECT
+                n.id as node_id,
+                n.text as question,
+                n.category,
+                COUNT(*) as answers,
+                COUNT(*) FILTER (WHERE step->>'unsure' = 'true') as unsure_count
+            FROM sessions s
+            CROSS JOIN LATERAL jsonb_array_elements(s.steps) step
+            INNER JOIN nodes n ON n.id::text = step->>'node_id'
+            WHERE ($1::timestamp IS NULL OR s.started_at >= $1::timestamp)
+              AND ($2::timestamp IS NULL OR s.started_at <= $2::timestamp)
+            GROUP BY n.id, n.text, n.category
+            HAVING COUNT(*) FILTER (WHERE step->>'unsure' = 'true') > 0
+            ORDER BY (COUNT(*) FILTER (WHERE step->>'unsure' = 'true'))::float8 / COUNT(*) DESC, unsure_count DESC
+            LIMIT 20
         )
         SELECT
             COALESCE((SELECT total FROM basic_stats), 0) as total_sessions,
@@ -391,7 +583,32 @@ pub async fn get_stats(
                 (SELECT json_agg(json_build_object('category', category, 'count', count))
                  FROM category_stats),
                 '[]'::json
-            ) as categories
+            ) as categories,
+            COALESCE(
+                (SELECT json_agg(json_build_object(
+                    'node_id', conclusion_node_id,
+                    'conclusion', conclusion,
+                    'category', category,
+                    'completed', completed,
+                    'feedback_count', feedback_count,
+                    'resolved_count', resolved_count,
+                    'effectiveness', resolved_count::float8 / feedback_count
+                 ))
+                 FROM effectiveness_stats),
+                '[]'::json
+            ) as effectiveness,
+            COALESCE(
+                (SELECT json_agg(json_build_object(
+                    'node_id', node_id,
+                    'question', question,
+                    'category', category,
+                    'answers', answers,
+                    'unsure_count', unsure_count,
+                    'unsure_rate', unsure_count::float8 / answers
+                 ))
+                 FROM unsure_stats),
+                '[]'::json
+            ) as unsure
         "#
     )
     .bind(params.start_date.as_ref())
@@ -420,6 +637,9 @@ pub async fn get_stats(
                 avg_steps_to_completion: 0.0,
                 most_common_conclusions: vec![],
                 sessions_by_category: vec![],
+                conclusion_effectiveness: vec![],
+                unsure_questions: vec![],
+                category_quotas: vec![],
             }));
         }
     };
@@ -451,6 +671,19 @@ pub async fn get_stats(
     let sessions_by_category: Vec<CategoryStats> = serde_json::from_value(categories_json)
         .unwrap_or_default();
 
+    let effectiveness_json: serde_json::Value = row.try_get("effectiveness").unwrap_or(serde_json::json!([]));
+    let conclusion_effectiveness: Vec<ConclusionEffectiveness> = serde_json::from_value(effectiveness_json)
+        .unwrap_or_default();
+
+    let unsure_json: serde_json::Value = row.try_get("unsure").unwrap_or(serde_json::json!([]));
+    let unsure_questions: Vec<UnsureQuestionStats> = serde_json::from_value(unsure_json)
+        .unwrap_or_default();
+
+    let category_quotas = category_quotas::list(&state.db, None).await.unwrap_or_else(|e| {
+        tracing::warn!("⚠️  Failed to load session quotas for stats: {}", e);
+        vec![]
+    });
+
     Ok(Json(DashboardStats {
         total_sessions,
         completed_sessions,
@@ -459,6 +692,9 @@ pub async fn get_stats(
         avg_steps_to_completion,
         most_common_conclusions,
         sessions_by_category,
+        conclusion_effectiveness,
+        unsure_questions,
+        category_quotas,
     }))
 }
 
@@ -491,8 +727,17 @@ pub struct PerformanceMetrics {
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct DatabaseMetrics {
     pub pool_size: u32,
+    pub max_connections: u32,
     pub active_connections: usize,
     pub idle_connections: usize,
+    /// Requests that failed waiting for a connection since startup
+    #[ts(type = "number")]
+    pub acquire_timeouts: u64,
+    /// Monitor samples where every connection was in use
+    #[ts(type = "number")]
+    pub saturation_events: u64,
+    pub avg_acquire_wait_ms: f64,
+    pub max_acquire_wait_ms: f64,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -522,6 +767,8 @@ pub async fn get_performance_metrics(
     let pool_size = state.db.size();
     let idle_connections = state.db.num_idle();
     let active_connections = (pool_size as usize).saturating_sub(idle_connections);
+    let max_connections = state.db.options().get_max_connections();
+    let pool_stats = pool_metrics::stats();
 
     // Cache metrics
     let questions_stats = state.questions_cache.stats().await;
@@ -531,8 +778,13 @@ pub async fn get_performance_metrics(
     Ok(Json(PerformanceMetrics {
         database: DatabaseMetrics {
             pool_size,
+            max_connections,
             active_connections,
             idle_connections,
+            acquire_timeouts: pool_stats.acquire_timeouts,
+            saturation_events: pool_stats.saturation_events,
+            avg_acquire_wait_ms: pool_stats.avg_acquire_wait_ms,
+            max_acquire_wait_ms: pool_stats.max_acquire_wait_ms,
         },
         cache: CacheMetrics {
             questions_cache: CacheStats {
@@ -560,6 +812,34 @@ pub async fn get_performance_metrics(
     }))
 }
 
+/// Query parameters for the recent errors endpoint
+#[derive(Debug, Deserialize)]
+pub struct RecentErrorsParams {
+    pub limit: Option<usize>,
+}
+
+/// Recent API errors, newest first
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RecentErrorsResponse {
+    pub errors: Vec<RecentError>,
+    pub capacity: usize,
+}
+
+/// GET /api/admin/errors
+/// Get the most recent API errors from the in-memory buffer (ADMIN only)
+pub async fn get_recent_errors(
+    Query(params): Query<RecentErrorsParams>,
+) -> ApiResult<Json<RecentErrorsResponse>> {
+    let buffer = error_buffer::global();
+    let limit = params.limit.unwrap_or(50).clamp(1, buffer.capacity().max(1));
+
+    Ok(Json(RecentErrorsResponse {
+        errors: buffer.recent(limit),
+        capacity: buffer.capacity(),
+    }))
+}
+
 /// DELETE /api/admin/sessions
 /// Delete sessions based on filters (ADMIN only)
 pub async fn delete_sessions(
@@ -606,12 +886,11 @@ pub async fn delete_sessions(
                 query.push(" AND completed_at IS NOT NULL");
             }
             "abandoned" => {
-                query.push(" AND (abandoned = true OR (completed_at IS NULL AND started_at <= NOW() - INTERVAL '1 hour'))");
+                query.push(" AND abandoned = true");
             }
             "active" => {
                 query.push(" AND completed_at IS NULL");
                 query.push(" AND abandoned = false");
-                query.push(" AND started_at > NOW() - INTERVAL '1 hour'");
             }
             "all" => {
                 // No status filter
@@ -667,6 +946,303 @@ pub async fn delete_sessions(
     Ok(Json(DeleteSessionsResponse { deleted_count }))
 }
 
+/// Request to archive old sessions
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ArchiveSessionsRequest {
+    /// Archive sessions started more than this many days ago (finished or idle since)
+    #[ts(type = "number")]
+    pub older_than_days: i64,
+}
+
+/// Result of archiving sessions
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ArchiveSessionsResponse {
+    #[ts(type = "number")]
+    pub archived_count: u64,
+}
+
+/// An archived session with its decompressed steps
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ArchivedSession {
+    pub session_id: String,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub abandoned: bool,
+    pub abandon_reason: Option<String>,
+    pub last_activity_at: String,
+    pub final_conclusion: Option<String>,
+    pub category: Option<String>,
+    pub step_count: i32,
+    pub tech_identifier: Option<String>,
+    pub client_site: Option<String>,
+    pub entry_point: Option<String>,
+    pub equipment_id: Option<String>,
+    #[ts(type = "Record<string, unknown>")]
+    pub variables: serde_json::Value,
+    pub device_id: Option<Uuid>,
+    pub start_node_id: Option<Uuid>,
+    pub archived_at: String,
+    #[ts(type = "unknown")]
+    pub steps: serde_json::Value,
+}
+
+/// POST /api/admin/sessions/archive
+/// Move old sessions into the compressed archive; they stay in dashboard stats
+pub async fn archive_sessions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<ArchiveSessionsRequest>,
+) -> ApiResult<Json<ArchiveSessionsResponse>> {
+    if req.older_than_days < 1 {
+        return Err(ApiError::validation(vec![(
+            "older_than_days".to_string(),
+            "Must be at least 1".to_string(),
+        )]));
+    }
+
+    let archived_count = session_archive::archive_older_than(&state.db, req.older_than_days).await?;
+    tracing::info!("🗄️  Archived {} sessions older than {} days", archived_count, req.older_than_days);
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::SessionsArchived,
+        "sessions",
+        None,
+        Some(json!({
+            "archived_count": archived_count,
+            "older_than_days": req.older_than_days,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ArchiveSessionsResponse { archived_count }))
+}
+
+/// GET /api/admin/sessions/archive/:session_id
+/// Get an archived session with its steps
+pub async fn get_archived_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<ArchivedSession>> {
+    let row = sqlx::query(
+        "SELECT session_id, started_at, completed_at, abandoned, abandon_reason, last_activity_at, final_conclusion,
+                category, step_count, tech_identifier, client_site, entry_point,
+                equipment_id, variables, device_id, start_node_id, archived_at, steps_gz
+         FROM session_archive
+         WHERE session_id = $1",
+    )
+    .bind(&session_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Archived session not found"))?;
+
+    let steps_gz: Vec<u8> = row.try_get("steps_gz")?;
+    let steps = session_archive::decompress_steps(&steps_gz)
+        .map_err(|e| ApiError::internal(format!("Corrupt archived session: {}", e)))?;
+
+    Ok(Json(ArchivedSession {
+        session_id: row.try_get("session_id")?,
+        started_at: row.try_get::<DateTime<Utc>, _>("started_at")?.to_rfc3339(),
+        completed_at: row.try_get::<Option<DateTime<Utc>>, _>("completed_at")?.map(|t| t.to_rfc3339()),
+        abandoned: row.try_get("abandoned")?,
+        abandon_reason: row.try_get("abandon_reason")?,
+        last_activity_at: row.try_get::<DateTime<Utc>, _>("last_activity_at")?.to_rfc3339(),
+        final_conclusion: row.try_get("final_conclusion")?,
+        category: row.try_get("category")?,
+        step_count: row.try_get("step_count")?,
+        tech_identifier: row.try_get("tech_identifier")?,
+        client_site: row.try_get("client_site")?,
+        entry_point: row.try_get("entry_point")?,
+        equipment_id: row.try_get("equipment_id")?,
+        variables: row.try_get("variables")?,
+        device_id: row.try_get("device_id")?,
+        start_node_id: row.try_get("start_node_id")?,
+        archived_at: row.try_get::<DateTime<Utc>, _>("archived_at")?.to_rfc3339(),
+        steps,
+    }))
+}
+
+/// Request to merge another session into this one
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct MergeSessionsRequest {
+    /// Session whose steps move over; it is deleted afterwards
+    pub source_session_id: String,
+}
+
+/// Result of merging two sessions
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct MergeSessionsResponse {
+    pub session_id: String,
+    pub merged_from: String,
+    pub step_count: i32,
+    pub completed: bool,
+}
+
+/// Interleave two sessions' steps by time, tagging the ones that came from `source_session_id`
+///
+/// Returns the steps and whether the latest one came from the source, in
+/// which case the source's outcome becomes the merged session's.
+fn merge_steps(
+    target: Vec<serde_json::Value>,
+    source: Vec<serde_json::Value>,
+    source_session_id: &str,
+) -> (Vec<serde_json::Value>, bool) {
+    let timestamp = |step: &serde_json::Value| {
+        step["timestamp"]
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    };
+
+    let mut steps: Vec<(DateTime<Utc>, bool, serde_json::Value)> = target
+        .into_iter()
+        .map(|step| (timestamp(&step), false, step))
+        .chain(source.into_iter().map(|mut step| {
+            if let Some(fields) = step.as_object_mut() {
+                fields.insert("merged_from".to_string(), json!(source_session_id));
+            }
+            (timestamp(&step), true, step)
+        }))
+        .collect();
+    steps.sort_by_key(|(at, _, _)| *at);
+
+    let last_from_source = steps.last().is_some_and(|(_, from_source, _)| *from_source);
+    (steps.into_iter().map(|(_, _, step)| step).collect(), last_from_source)
+}
+
+/// POST /api/admin/sessions/:session_id/merge
+/// Fold a duplicate session on the same equipment into this one
+///
+/// Steps from both are kept in time order; the session whose answer came
+/// last decides whether the merged session is finished. The source session
+/// is deleted.
+pub async fn merge_sessions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(session_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<MergeSessionsRequest>,
+) -> ApiResult<Json<MergeSessionsResponse>> {
+    if req.source_session_id == session_id {
+        return Err(ApiError::validation(vec![(
+            "source_session_id".to_string(),
+            "Cannot merge a session into itself".to_string(),
+        )]));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Lock both rows in a fixed order so concurrent merges can't deadlock
+    let rows = sqlx::query_as::<_, (String, serde_json::Value, Option<String>)>(
+        "SELECT session_id, steps, equipment_id
+         FROM sessions
+         WHERE session_id = ANY($1)
+         ORDER BY session_id
+         FOR UPDATE",
+    )
+    .bind(vec![session_id.clone(), req.source_session_id.clone()])
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let find = |id: &str| rows.iter().find(|(row_id, _, _)| row_id == id).cloned();
+    let (_, target_steps, target_equipment) = find(&session_id)
+        .ok_or_else(|| ApiError::not_found("Session not found"))?;
+    let (_, source_steps, source_equipment) = find(&req.source_session_id)
+        .ok_or_else(|| ApiError::not_found("Source session not found"))?;
+
+    if let (Some(target), Some(source)) = (&target_equipment, &source_equipment) {
+        if !target.eq_ignore_ascii_case(source) {
+            return Err(ApiError::bad_request(format!(
+                "Sessions are for different equipment ('{}' and '{}')",
+                target, source
+            )));
+        }
+    }
+
+    let (steps, last_from_source) = merge_steps(
+        serde_json::from_value(target_steps).unwrap_or_default(),
+        serde_json::from_value(source_steps).unwrap_or_default(),
+        &req.source_session_id,
+    );
+    let step_count = steps.len() as i32;
+
+    let completed = sqlx::query_scalar::<_, bool>(
+        "UPDATE sessions t
+         SET steps = $1,
+             started_at = LEAST(t.started_at, s.started_at),
+             last_activity_at = GREATEST(t.last_activity_at, s.last_activity_at),
+             tech_identifier = COALESCE(t.tech_identifier, s.tech_identifier),
+             client_site = COALESCE(t.client_site, s.client_site),
+             equipment_id = COALESCE(t.equipment_id, s.equipment_id),
+             variables = s.variables || t.variables,
+             completed_at = CASE WHEN $2 THEN s.completed_at ELSE t.completed_at END,
+             final_conclusion = CASE WHEN $2 THEN s.final_conclusion ELSE t.final_conclusion END,
+             abandoned = CASE WHEN $2 THEN s.abandoned ELSE t.abandoned END,
+             abandoned_at = CASE WHEN $2 THEN s.abandoned_at ELSE t.abandoned_at END,
+             abandon_reason = CASE WHEN $2 THEN s.abandon_reason ELSE t.abandon_reason END,
+             conclusion_node_id = CASE WHEN $2 THEN s.conclusion_node_id ELSE t.conclusion_node_id END,
+             feedback_resolved = CASE WHEN $2 THEN s.feedback_resolved ELSE t.feedback_resolved END,
+             feedback_comment = CASE WHEN $2 THEN s.feedback_comment ELSE t.feedback_comment END,
+             feedback_at = CASE WHEN $2 THEN s.feedback_at ELSE t.feedback_at END
+         FROM sessions s
+         WHERE t.session_id = $3 AND s.session_id = $4
+         RETURNING t.completed_at IS NOT NULL",
+    )
+    .bind(serde_json::to_value(&steps)?)
+    .bind(last_from_source)
+    .bind(&session_id)
+    .bind(&req.source_session_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM sessions WHERE session_id = $1")
+        .bind(&req.source_session_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("👥 Merged session {} into {}", req.source_session_id, session_id);
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::SessionsMerged,
+        "session",
+        Some(&session_id),
+        Some(json!({
+            "merged_from": req.source_session_id,
+            "step_count": step_count,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(MergeSessionsResponse {
+        session_id,
+        merged_from: req.source_session_id,
+        step_count,
+        completed,
+    }))
+}
+
 /// GET /api/admin/sessions/count
 /// Get count of sessions matching filters (for preview before delete)
 pub async fn count_sessions(
@@ -709,12 +1285,11 @@ pub async fn count_sessions(
                 query.push(" AND completed_at IS NOT NULL");
             }
             "abandoned" => {
-                query.push(" AND (abandoned = true OR (completed_at IS NULL AND started_at <= NOW() - INTERVAL '1 hour'))");
+                query.push(" AND abandoned = true");
             }
             "active" => {
                 query.push(" AND completed_at IS NULL");
                 query.push(" AND abandoned = false");
-                query.push(" AND started_at > NOW() - INTERVAL '1 hour'");
             }
             "all" => {}
             _ => {
@@ -739,6 +1314,85 @@ pub async fn count_sessions(
     Ok(Json(serde_json::json!({ "count": count })))
 }
 
+/// One exported session (a single NDJSON line)
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct SessionExportRow {
+    session_id: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+    completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    abandoned: bool,
+    tech_identifier: Option<String>,
+    client_site: Option<String>,
+    final_conclusion: Option<String>,
+    steps: serde_json::Value,
+}
+
+/// GET /api/admin/issues/:category/sessions/export
+/// Export all sessions for a category as NDJSON, including full step data (ADMIN only)
+pub async fn export_category_sessions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    axum::extract::Path(category): axum::extract::Path<String>,
+) -> ApiResult<Response> {
+    // A session belongs to a category when any of its steps visited a node in that category
+    let sessions = sqlx::query_as::<_, SessionExportRow>(
+        "SELECT session_id, started_at, completed_at, abandoned, tech_identifier, client_site, final_conclusion, steps
+         FROM sessions s
+         WHERE (s.steps->0->>'category')::text = $1
+            OR EXISTS (
+                SELECT 1
+                FROM jsonb_array_elements(s.steps) step
+                JOIN nodes n ON n.id::text = step->>'node_id'
+                WHERE n.category = $1
+            )
+         ORDER BY started_at ASC"
+    )
+    .bind(&category)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut body = String::new();
+    for session in &sessions {
+        body.push_str(&serde_json::to_string(session)?);
+        body.push('\n');
+    }
+
+    tracing::info!("📦 Exported {} sessions for category '{}'", sessions.len(), category);
+
+    // Audit log the export (session data includes technician identifiers)
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::SessionsExported,
+        "sessions",
+        Some(&category),
+        Some(json!({
+            "exported_count": sessions.len(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    let disposition = format!(
+        "attachment; filename=\"{}-sessions.ndjson\"",
+        category.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_', "_")
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
 /// Response for listing categories
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
@@ -784,13 +1438,30 @@ pub async fn list_categories(State(state): State<AppState>) -> ApiResult<Json<Ca
     }))
 }
 
+/// Display categories span issues; editors restricted to some issues may
+/// only change one whose nodes all belong to issues they can edit
+async fn ensure_can_edit_display_category(state: &AppState, auth: &AuthUser, name: &str) -> ApiResult<()> {
+    let categories = sqlx::query_scalar::<_, String>("SELECT DISTINCT category FROM nodes WHERE display_category = $1")
+        .bind(name)
+        .fetch_all(&state.db)
+        .await?;
+
+    category_access::ensure_can_edit(&state.db, auth, categories.iter().map(String::as_str)).await
+}
+
 /// PUT /api/admin/categories/:name
-/// Rename a category (updates all nodes using it)
+/// Rename a display category (updates all nodes using it)
+///
+/// Only the label changes; an issue's category slug is renamed with
+/// `PUT /api/admin/issues/:category/rename-slug`.
 pub async fn rename_category(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     axum::extract::Path(name): axum::extract::Path<String>,
     Json(req): Json<RenameCategoryRequest>,
 ) -> ApiResult<Json<CategoryUpdateResponse>> {
+    ensure_can_edit_display_category(&state, &auth, &name).await?;
+
     let result = sqlx::query!(
         r#"
         UPDATE nodes
@@ -812,8 +1483,11 @@ pub async fn rename_category(
 /// Delete a category by setting display_category to NULL for all nodes using it
 pub async fn delete_category(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     axum::extract::Path(name): axum::extract::Path<String>,
 ) -> ApiResult<Json<CategoryUpdateResponse>> {
+    ensure_can_edit_display_category(&state, &auth, &name).await?;
+
     let result = sqlx::query!(
         r#"
         UPDATE nodes
@@ -830,6 +1504,433 @@ pub async fn delete_category(
     }))
 }
 
+/// Request body for the content lint endpoint
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(default)]
+pub struct LintRequest {
+    /// Restrict the report to a single issue category
+    #[ts(optional)]
+    pub category: Option<String>,
+    /// Include inactive nodes in the report
+    pub include_inactive: bool,
+    /// Rule overrides (defaults are used for omitted fields)
+    #[ts(optional)]
+    pub rules: Option<LintRules>,
+}
+
+/// Per-node lint report
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LintReport {
+    pub rules: LintRules,
+    pub nodes_checked: usize,
+    pub issues_found: usize,
+    pub nodes: Vec<NodeLintResult>,
+}
+
+/// POST /api/admin/lint
+/// Run style lint rules over node text and return a per-node report
+pub async fn lint_content(
+    State(state): State<AppState>,
+    Json(req): Json<LintRequest>,
+) -> ApiResult<Json<LintReport>> {
+    let rules = req.rules.unwrap_or_default();
+
+    if rules.min_length > rules.max_length {
+        return Err(ApiError::validation(vec![(
+            "rules.min_length".to_string(),
+            "min_length cannot be greater than max_length".to_string(),
+        )]));
+    }
+
+    use sqlx::QueryBuilder;
+    let mut query = QueryBuilder::new(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE 1=1"
+    );
+
+    if !req.include_inactive {
+        query.push(" AND is_active = true");
+    }
+
+    if let Some(category) = &req.category {
+        query.push(" AND category = ");
+        query.push_bind(category);
+    }
+
+    query.push(" ORDER BY category ASC, created_at ASC");
+
+    let nodes = query.build_query_as::<Node>().fetch_all(&state.db).await?;
+
+    let results = lint::lint_nodes(&nodes, &rules).await;
+    let issues_found = results.iter().map(|r| r.issues.len()).sum();
+
+    Ok(Json(LintReport {
+        rules,
+        nodes_checked: nodes.len(),
+        issues_found,
+        nodes: results,
+    }))
+}
+
+/// Default lifetime of a runtime log filter override
+const LOG_LEVEL_DEFAULT_MINUTES: u64 = 30;
+/// Longest allowed lifetime of a runtime log filter override
+const LOG_LEVEL_MAX_MINUTES: u64 = 24 * 60;
+
+/// Active tracing filter
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LogLevelResponse {
+    pub startup_filter: String,
+    pub current_filter: String,
+    pub revert_at: Option<String>,
+}
+
+impl From<log_level::LogLevelStatus> for LogLevelResponse {
+    fn from(status: log_level::LogLevelStatus) -> Self {
+        Self {
+            startup_filter: status.startup_filter,
+            current_filter: status.current_filter,
+            revert_at: status.revert_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Request body for changing the tracing filter
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SetLogLevelRequest {
+    /// `EnvFilter` directives, e.g. "info,equipment_troubleshooting::routes=debug"
+    pub filter: String,
+    /// Minutes until the filter reverts to the startup value (default 30)
+    #[ts(optional, type = "number")]
+    pub duration_minutes: Option<u64>,
+}
+
+/// GET /api/admin/log-level
+/// Get the active tracing filter (ADMIN only)
+pub async fn get_log_level() -> ApiResult<Json<LogLevelResponse>> {
+    let status = log_level::status().map_err(ApiError::internal)?;
+    Ok(Json(status.into()))
+}
+
+/// PUT /api/admin/log-level
+/// Temporarily change the tracing filter; it reverts automatically (ADMIN only)
+pub async fn set_log_level(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<SetLogLevelRequest>,
+) -> ApiResult<Json<LogLevelResponse>> {
+    let filter = req.filter.trim();
+    if let Err(message) = log_level::validate_filter(filter) {
+        return Err(ApiError::validation(vec![("filter".to_string(), message)]));
+    }
+
+    let minutes = req.duration_minutes.unwrap_or(LOG_LEVEL_DEFAULT_MINUTES);
+    if minutes == 0 || minutes > LOG_LEVEL_MAX_MINUTES {
+        return Err(ApiError::validation(vec![(
+            "duration_minutes".to_string(),
+            format!("Duration must be between 1 and {} minutes", LOG_LEVEL_MAX_MINUTES),
+        )]));
+    }
+
+    let previous = log_level::status().map_err(ApiError::internal)?;
+    let status = log_level::set_filter(filter, std::time::Duration::from_secs(minutes * 60))
+        .map_err(ApiError::internal)?;
+
+    tracing::warn!("🔧 Log filter changed to '{}' for {} minutes", filter, minutes);
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::LogLevelChanged,
+        "log_level",
+        None,
+        Some(json!({
+            "previous_filter": previous.current_filter,
+            "filter": filter,
+            "duration_minutes": minutes,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(status.into()))
+}
+
+/// DELETE /api/admin/log-level
+/// Revert the tracing filter to the startup value (ADMIN only)
+pub async fn reset_log_level(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+) -> ApiResult<Json<LogLevelResponse>> {
+    let previous = log_level::status().map_err(ApiError::internal)?;
+    let status = log_level::reset().map_err(ApiError::internal)?;
+
+    tracing::info!("🔁 Log filter reset to '{}'", status.startup_filter);
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::LogLevelChanged,
+        "log_level",
+        None,
+        Some(json!({
+            "previous_filter": previous.current_filter,
+            "filter": status.current_filter,
+            "reset": true,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(status.into()))
+}
+
+/// Rotation result: the new key signs from `activates_at`, older keys keep verifying
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct JwtKeyRotationResponse {
+    pub kid: String,
+    pub activates_at: String,
+    pub keys: Vec<jwt_keys::JwtKeyInfo>,
+}
+
+/// GET /api/admin/jwt-keys
+/// List the keys currently accepted for JWT verification (no key material)
+pub async fn list_jwt_keys() -> ApiResult<Json<Vec<jwt_keys::JwtKeyInfo>>> {
+    Ok(Json(jwt_keys::key_infos().map_err(ApiError::internal)?))
+}
+
+/// POST /api/admin/jwt-keys/rotate
+/// Start signing with a new key; existing sessions stay valid while they expire
+pub async fn rotate_jwt_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+) -> ApiResult<Json<JwtKeyRotationResponse>> {
+    if jwt_keys::rsa_signing() {
+        return Err(ApiError::bad_request(
+            "Tokens are signed with the configured RSA key; rotate it by replacing the key files",
+        ));
+    }
+
+    if !jwt_keys::rotation_configured() {
+        return Err(ApiError::bad_request(
+            "Set JWT_KEY_ENCRYPTION_KEY (different from JWT_SECRET) to store rotated keys",
+        ));
+    }
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let previous = jwt_keys::signing_key().map_err(ApiError::internal)?;
+    let (kid, activates_at) = jwt_keys::rotate(&state.db, user_id)
+        .await?
+        .ok_or_else(|| ApiError::internal("JWT key encryption is not configured"))?;
+
+    tracing::warn!("🔑 JWT signing key rotated: {} (signing from {})", kid, activates_at);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::JwtKeyRotated,
+        "jwt_key",
+        Some(&kid),
+        Some(json!({
+            "previous_kid": previous.kid,
+            "activates_at": activates_at.to_rfc3339(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(JwtKeyRotationResponse {
+        kid,
+        activates_at: activates_at.to_rfc3339(),
+        keys: jwt_keys::key_infos().map_err(ApiError::internal)?,
+    }))
+}
+
+/// Request to convert the legacy question/answer tree
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(default)]
+pub struct LegacyMigrationRequest {
+    /// Report what would be created without writing anything
+    pub dry_run: bool,
+}
+
+/// What happened to one issue converted from the legacy tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(rename_all = "snake_case")]
+pub enum LegacyIssueStatus {
+    /// Would be created (dry run)
+    Ready,
+    /// The category already has nodes, so the issue was left alone
+    Exists,
+    Created,
+    /// The import rejected the issue (see `error`)
+    Failed,
+}
+
+/// One issue converted from the legacy tree
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LegacyIssueReport {
+    pub category: String,
+    pub name: String,
+    pub status: LegacyIssueStatus,
+    /// Question nodes (legacy questions)
+    pub questions: usize,
+    /// Conclusion nodes (legacy answers with conclusion text)
+    pub conclusions: usize,
+    pub connections: usize,
+    pub error: Option<String>,
+    /// Legacy rows left out of the issue or worth a look
+    pub warnings: Vec<String>,
+}
+
+/// Result of converting the legacy tree
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LegacyMigrationReport {
+    pub dry_run: bool,
+    pub legacy_questions: usize,
+    pub legacy_answers: usize,
+    pub issues: Vec<LegacyIssueReport>,
+    /// Legacy rows that didn't make it into any issue
+    pub warnings: Vec<String>,
+}
+
+/// POST /api/admin/migrate/legacy
+/// Convert the legacy `questions`/`answers` tables into node-graph issues (see `utils::legacy_tree`)
+///
+/// Categories that already have nodes are skipped, so running it again only
+/// adds what is missing. The legacy rows are left in place.
+pub async fn migrate_legacy_tree(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<LegacyMigrationRequest>,
+) -> ApiResult<Json<LegacyMigrationReport>> {
+    let present = sqlx::query_scalar::<_, bool>(
+        "SELECT to_regclass('questions') IS NOT NULL AND to_regclass('answers') IS NOT NULL",
+    )
+    .fetch_one(&state.db)
+    .await?;
+    if !present {
+        return Err(ApiError::not_found("This database has no legacy questions/answers tables"));
+    }
+
+    let questions = sqlx::query_as::<_, LegacyQuestion>(
+        "SELECT id, semantic_id, text, category, is_active FROM questions ORDER BY created_at ASC",
+    )
+    .fetch_all(&state.db)
+    .await?;
+    let answers = sqlx::query_as::<_, LegacyAnswer>(
+        "SELECT question_id, label, next_question_id, conclusion_text, order_index, is_active
+         FROM answers
+         ORDER BY created_at ASC",
+    )
+    .fetch_all(&state.db)
+    .await?;
+    let conversion = legacy_tree::convert(&questions, &answers).map_err(ApiError::bad_request)?;
+
+    let categories: Vec<String> = conversion.issues.iter().map(|i| i.data.issue.category.clone()).collect();
+    let existing: std::collections::HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT DISTINCT category FROM nodes WHERE category = ANY($1)")
+            .bind(&categories)
+            .fetch_all(&state.db)
+            .await?
+            .into_iter()
+            .collect();
+
+    let mut reports = Vec::new();
+    let mut to_import = Vec::new();
+    for issue in conversion.issues {
+        let category = issue.data.issue.category.clone();
+        let status = if existing.contains(&category) {
+            LegacyIssueStatus::Exists
+        } else {
+            LegacyIssueStatus::Ready
+        };
+        reports.push(LegacyIssueReport {
+            name: issue.data.issue.name.clone(),
+            category,
+            status,
+            questions: issue.questions,
+            conclusions: issue.conclusions,
+            connections: issue.data.connections.len(),
+            error: None,
+            warnings: issue.warnings,
+        });
+        if status == LegacyIssueStatus::Ready {
+            to_import.push(issue.data);
+        }
+    }
+
+    if !req.dry_run && !to_import.is_empty() {
+        // Legacy trees often repeat an answer; one connection is enough
+        let result = issues::import_issue_data(&state, to_import, DuplicateConnectionMode::Dedupe, ImportMode::Skip).await;
+        for report in reports.iter_mut().filter(|r| r.status == LegacyIssueStatus::Ready) {
+            if let Some(error) = result.errors.iter().find(|e| e.category == report.category) {
+                report.status = LegacyIssueStatus::Failed;
+                report.error = Some(error.error.clone());
+            } else {
+                report.status = LegacyIssueStatus::Created;
+            }
+        }
+
+        let created: Vec<&str> = reports
+            .iter()
+            .filter(|r| r.status == LegacyIssueStatus::Created)
+            .map(|r| r.category.as_str())
+            .collect();
+        tracing::info!("📥 Converted legacy tree into {} issue(s): {}", created.len(), created.join(", "));
+
+        let user_id = Uuid::parse_str(&auth.0.sub)
+            .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+        let ip = audit::extract_ip_address(&headers);
+        audit::log_event(
+            &state.db,
+            user_id,
+            audit::AuditAction::IssuesImported,
+            "issue",
+            None,
+            Some(json!({
+                "mode": "legacy",
+                "created": created,
+                "failed": result.errors.iter().map(|e| &e.category).collect::<Vec<_>>(),
+            })),
+            ip.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(Json(LegacyMigrationReport {
+        dry_run: req.dry_run,
+        legacy_questions: questions.len(),
+        legacy_answers: answers.len(),
+        issues: reports,
+        warnings: conversion.warnings,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -841,14 +1942,38 @@ mod tests {
             started_at: "2025-10-24T00:00:00Z".to_string(),
             completed_at: Some("2025-10-24T00:05:00Z".to_string()),
             abandoned: false,
+            abandon_reason: None,
+            last_activity_at: "2025-10-24T00:05:00Z".to_string(),
             tech_identifier: Some("Tech123".to_string()),
             client_site: Some("Site A".to_string()),
+            equipment_id: Some("PUMP-0042".to_string()),
+            device_id: None,
             final_conclusion: Some("Test conclusion".to_string()),
             step_count: 5,
+            feedback_resolved: Some(true),
+            feedback_comment: None,
         };
         assert_eq!(summary.step_count, 5);
     }
 
+    #[test]
+    fn test_merge_steps() {
+        let step = |label: &str, at: &str| json!({ "connection_label": label, "timestamp": at });
+        let target = vec![step("a", "2025-10-24T10:00:00+00:00"), step("c", "2025-10-24T10:02:00+00:00")];
+        let source = vec![step("b", "2025-10-24T10:01:00+00:00"), step("d", "2025-10-24T10:03:00Z")];
+
+        let (steps, last_from_source) = merge_steps(target.clone(), source, "dup");
+        let labels: Vec<&str> = steps.iter().map(|s| s["connection_label"].as_str().unwrap()).collect();
+        assert_eq!(labels, vec!["a", "b", "c", "d"]);
+        assert!(last_from_source);
+        assert_eq!(steps[1]["merged_from"], "dup");
+        assert!(steps[0].get("merged_from").is_none());
+
+        let (steps, last_from_source) = merge_steps(target, vec![], "dup");
+        assert_eq!(steps.len(), 2);
+        assert!(!last_from_source);
+    }
+
     #[test]
     fn test_dashboard_stats() {
         let stats = DashboardStats {
@@ -859,6 +1984,9 @@ mod tests {
             avg_steps_to_completion: 4.5,
             most_common_conclusions: vec![],
             sessions_by_category: vec![],
+            conclusion_effectiveness: vec![],
+            unsure_questions: vec![],
+            category_quotas: vec![],
         };
         assert_eq!(stats.total_sessions, 100);
     }
@@ -1,12 +1,24 @@
 use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthUser;
-use crate::models::{User, UserRole};
-use crate::utils::jwt::{generate_token, generate_token_with_expiration, verify_token};
+use crate::models::{Permission, User, UserRole};
+use crate::utils::audit;
+use crate::utils::jwt::generate_token_with_expiration;
+use crate::utils::login_guard;
+use crate::utils::password;
+use crate::utils::permissions;
+use crate::utils::refresh_tokens::{self, DeviceInfo, RevokeReason, RotateOutcome};
+use crate::routes::users;
 use crate::AppState;
-use argon2::PasswordVerifier;
-use axum::{extract::State, Extension, Json};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use ts_rs::TS;
+use uuid::Uuid;
 
 /// Login request payload
 #[derive(Debug, Deserialize, TS)]
@@ -14,16 +26,20 @@ use ts_rs::TS;
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
-    /// If true, token will not expire. If false, token expires in 15 minutes.
+    /// If true, the refresh token lasts 30 days; otherwise 12 hours
     #[serde(default)]
     pub remember_me: bool,
 }
 
-/// Login response with JWT token and user info
+/// Login response with access/refresh tokens and user info
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct LoginResponse {
+    /// Short-lived access JWT (15 minutes)
     pub token: String,
+    /// Opaque refresh token; single use, exchanged at /auth/refresh
+    pub refresh_token: String,
+    pub refresh_expires_at: String,
     pub user: UserInfo,
 }
 
@@ -33,13 +49,114 @@ pub struct LoginResponse {
 pub struct UserInfo {
     pub id: String,
     pub email: String,
+    pub display_name: Option<String>,
     pub role: UserRole,
+    /// Effective permissions of the role, for hiding unavailable admin screens
+    pub permissions: Vec<Permission>,
+}
+
+impl UserInfo {
+    fn new(user: User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            email: user.email,
+            display_name: user.display_name,
+            permissions: permissions::permissions_for(&user.role).into_iter().collect(),
+            role: user.role,
+        }
+    }
+}
+
+/// User agent and client IP recorded with refresh tokens
+fn device_info(headers: &HeaderMap) -> DeviceInfo {
+    DeviceInfo {
+        user_agent: headers
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string()),
+        ip_address: audit::extract_ip_address(headers),
+    }
+}
+
+fn account_locked(until: DateTime<Utc>) -> ApiError {
+    ApiError::too_many_requests(
+        "Account is temporarily locked after too many failed login attempts. Please try again later.",
+        login_guard::seconds_until(until),
+    )
+}
+
+/// Lockout limits currently in effect
+fn lockout_config(state: &AppState) -> login_guard::LockoutConfig {
+    login_guard::LockoutConfig {
+        max_failures: state.settings.login_max_failures(),
+        lockout_minutes: state.settings.login_lockout_minutes(),
+        ip_max_failures: state.settings.login_ip_max_failures(),
+        ip_window_minutes: state.settings.login_ip_window_minutes(),
+    }
+}
+
+/// Check a signed-in user's current password under the same limits as login
+///
+/// A wrong password counts toward the account and IP lockouts, and a locked
+/// account or throttled IP is refused without checking, so a stolen session
+/// can't be used to guess the password.
+async fn verify_current_password(state: &AppState, user: &User, candidate: &str, ip: Option<&str>) -> ApiResult<bool> {
+    let lockout = &lockout_config(state);
+    if let Some(ip) = ip {
+        if let Some(until) = login_guard::ip_blocked_until(&state.db, ip, lockout).await? {
+            return Err(ApiError::too_many_requests(
+                "Too many failed password attempts. Please try again later.",
+                login_guard::seconds_until(until),
+            ));
+        }
+    }
+    if let Some(until) = login_guard::locked_until(&state.db, user.id).await? {
+        return Err(account_locked(until));
+    }
+
+    if password::verify_password(candidate, &user.password_hash) {
+        login_guard::clear_failures(&state.db, user.id).await?;
+        return Ok(true);
+    }
+
+    let outcome = login_guard::record_failure(&state.db, &user.email, Some(user.id), ip, lockout).await?;
+    if let Some(until) = outcome.and_then(|o| o.locked_until) {
+        audit::log_event(
+            &state.db,
+            user.id,
+            audit::AuditAction::AccountLocked,
+            "user",
+            Some(&user.id.to_string()),
+            Some(json!({ "email": user.email, "reason": "current_password" })),
+            ip,
+        )
+        .await?;
+        return Err(account_locked(until));
+    }
+    Ok(false)
+}
+
+fn login_response(user: User, refresh: refresh_tokens::IssuedToken) -> ApiResult<Json<LoginResponse>> {
+    let token = generate_token_with_expiration(
+        user.id,
+        user.email.clone(),
+        user.role.clone(),
+        refresh_tokens::ACCESS_TOKEN_MINUTES,
+    )?;
+
+    Ok(Json(LoginResponse {
+        token,
+        refresh_token: refresh.token,
+        refresh_expires_at: refresh.expires_at.to_rfc3339(),
+        user: UserInfo::new(user),
+    }))
 }
 
 /// POST /api/auth/login
 /// Authenticate user with email and password
 pub async fn login(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<LoginRequest>,
 ) -> ApiResult<Json<LoginResponse>> {
     // Validate input
@@ -57,96 +174,253 @@ pub async fn login(
         )]));
     }
 
-    // Query user from database
+    let lockout = &lockout_config(&state);
+    let ip = audit::extract_ip_address(&headers);
+
+    // Refuse IPs that already burned their failure budget, before touching any account
+    if let Some(ip) = ip.as_deref() {
+        if let Some(until) = login_guard::ip_blocked_until(&state.db, ip, lockout).await? {
+            tracing::warn!("🚫 Login throttled for IP {} (too many failures)", ip);
+            return Err(ApiError::too_many_requests(
+                "Too many failed login attempts. Please try again later.",
+                login_guard::seconds_until(until),
+            ));
+        }
+    }
+
+    // Query user from database (service accounts cannot sign in interactively);
+    // stored emails are lowercase, but older rows may not be
     let user = sqlx::query_as::<_, User>(
-        "SELECT id, email, password_hash, role, is_active, created_at, updated_at
+        "SELECT id, email, display_name, password_hash, role, is_active, created_at, updated_at
          FROM users
-         WHERE email = $1"
+         WHERE LOWER(email) = LOWER($1) AND NOT is_service"
     )
-    .bind(&req.email)
+    .bind(req.email.trim())
     .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| ApiError::unauthorized("Invalid email or password"))?;
+    .await?;
+
+    let Some(user) = user else {
+        login_guard::record_failure(&state.db, &req.email, None, ip.as_deref(), lockout).await?;
+        tracing::warn!("🔐 Failed login for unknown email from {}", ip.as_deref().unwrap_or("unknown IP"));
+        return Err(ApiError::unauthorized("Invalid email or password"));
+    };
 
     // Check if user is active
     if !user.is_active {
         return Err(ApiError::forbidden("Account is disabled"));
     }
 
+    // A locked account is refused without checking the password, so guessing can't continue
+    if let Some(until) = login_guard::locked_until(&state.db, user.id).await? {
+        return Err(account_locked(until));
+    }
+
     // Verify password with Argon2
-    let password_hash = argon2::PasswordHash::new(&user.password_hash)
-        .map_err(|_| ApiError::internal("Invalid password hash format"))?;
+    if !password::verify_password(&req.password, &user.password_hash) {
+        let outcome = login_guard::record_failure(&state.db, &req.email, Some(user.id), ip.as_deref(), lockout)
+            .await?
+            .unwrap_or(login_guard::FailureOutcome { failed_count: 0, locked_until: None });
+
+        audit::log_event(
+            &state.db,
+            user.id,
+            audit::AuditAction::AdminLoginFailed,
+            "user",
+            Some(&user.id.to_string()),
+            Some(json!({
+                "email": user.email,
+                "reason": "invalid_password",
+                "consecutive_failures": outcome.failed_count,
+            })),
+            ip.as_deref(),
+        )
+        .await?;
 
-    argon2::Argon2::default()
-        .verify_password(req.password.as_bytes(), &password_hash)
-        .map_err(|_| ApiError::unauthorized("Invalid email or password"))?;
+        if let Some(until) = outcome.locked_until {
+            audit::log_event(
+                &state.db,
+                user.id,
+                audit::AuditAction::AccountLocked,
+                "user",
+                Some(&user.id.to_string()),
+                Some(json!({
+                    "email": user.email,
+                    "locked_until": until,
+                    "max_failures": lockout.max_failures,
+                })),
+                ip.as_deref(),
+            )
+            .await?;
+            tracing::warn!("🔒 Account {} locked until {} after repeated failed logins", user.email, until);
+            return Err(account_locked(until));
+        }
 
-    // Generate JWT token with appropriate expiration
-    // If remember_me is true: token valid for 30 days (43200 minutes)
-    // If remember_me is false: token valid for 15 minutes
-    let token = if req.remember_me {
+        return Err(ApiError::unauthorized("Invalid email or password"));
+    }
+
+    login_guard::clear_failures(&state.db, user.id).await?;
+
+    // Access tokens are always short-lived; "stay signed in" only extends the refresh token
+    let refresh = refresh_tokens::issue(&state.db, user.id, req.remember_me, &device_info(&headers)).await?;
+
+    if req.remember_me {
         tracing::info!("🔐 Login with 'stay signed in' enabled for user: {}", user.email);
-        generate_token_with_expiration(user.id, user.email.clone(), user.role.clone(), 43200)?
     } else {
-        tracing::info!("🔐 Login with short-lived session (15 min) for user: {}", user.email);
-        generate_token_with_expiration(user.id, user.email.clone(), user.role.clone(), 15)?
-    };
+        tracing::info!("🔐 Login for user: {}", user.email);
+    }
 
-    // Return response
-    Ok(Json(LoginResponse {
-        token,
-        user: UserInfo {
-            id: user.id.to_string(),
-            email: user.email,
-            role: user.role,
-        },
-    }))
+    login_response(user, refresh)
 }
 
 /// Refresh token request payload
 #[derive(Debug, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct RefreshRequest {
-    pub token: String,
+    pub refresh_token: String,
 }
 
 /// POST /api/auth/refresh
-/// Refresh a JWT token
+/// Exchange a refresh token for a new access token and a new refresh token.
+/// The presented refresh token can't be used again.
 pub async fn refresh(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<RefreshRequest>,
 ) -> ApiResult<Json<LoginResponse>> {
-    // Verify the current token
-    let claims = verify_token(&req.token)?;
+    let (user_id, refresh) = match refresh_tokens::rotate(&state.db, &req.refresh_token, &device_info(&headers)).await? {
+        RotateOutcome::Rotated { user_id, token } => (user_id, token),
+        RotateOutcome::Invalid => {
+            return Err(ApiError::unauthorized("Refresh token is invalid or expired"));
+        }
+        RotateOutcome::ReuseDetected { user_id, family_id } => {
+            tracing::warn!(
+                "🚨 Refresh token reuse for user {} (family {}); device signed out",
+                user_id,
+                family_id
+            );
+            return Err(ApiError::unauthorized("Refresh token is invalid or expired"));
+        }
+    };
 
     // Look up user to ensure they still exist and are active
     let user = sqlx::query_as::<_, User>(
-        "SELECT id, email, password_hash, role, is_active, created_at, updated_at
+        "SELECT id, email, display_name, password_hash, role, is_active, created_at, updated_at
          FROM users
          WHERE id = $1"
     )
-    .bind(uuid::Uuid::parse_str(&claims.sub).map_err(|_| ApiError::unauthorized("Invalid token"))?)
+    .bind(user_id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| ApiError::unauthorized("User not found"))?;
 
     // Check if user is still active
     if !user.is_active {
+        refresh_tokens::revoke_all_for_user(&state.db, user.id, RevokeReason::Deactivated).await?;
         return Err(ApiError::forbidden("Account is disabled"));
     }
 
-    // Generate new token
-    let new_token = generate_token(user.id, user.email.clone(), user.role.clone())?;
+    login_response(user, refresh)
+}
 
-    // Return response with new token
-    Ok(Json(LoginResponse {
-        token: new_token,
-        user: UserInfo {
-            id: user.id.to_string(),
-            email: user.email,
-            role: user.role,
-        },
-    }))
+/// Logout request payload
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LogoutRequest {
+    pub refresh_token: String,
+}
+
+/// POST /api/auth/logout
+/// Revoke the refresh token (and its device family). Access tokens already
+/// issued stay valid until they expire (at most 15 minutes).
+pub async fn logout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<LogoutRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let user_id = refresh_tokens::revoke_token_family(&state.db, &req.refresh_token, RevokeReason::Logout).await?;
+
+    if let Some(user_id) = user_id {
+        let ip = audit::extract_ip_address(&headers);
+        audit::log_event(
+            &state.db,
+            user_id,
+            audit::AuditAction::AdminLogout,
+            "user",
+            Some(&user_id.to_string()),
+            None,
+            ip.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(Json(json!({ "message": "Signed out" })))
+}
+
+/// A signed-in device (one refresh token family)
+#[derive(Debug, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct DeviceSession {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub remember_me: bool,
+    pub signed_in_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// GET /api/auth/sessions
+/// List the current user's signed-in devices
+pub async fn list_device_sessions(
+    Extension(auth_user): Extension<AuthUser>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<DeviceSession>>> {
+    let user_id = Uuid::parse_str(&auth_user.0.sub).map_err(|_| ApiError::internal("Invalid user ID"))?;
+
+    let sessions = sqlx::query_as::<_, DeviceSession>(
+        "SELECT t.family_id AS id, t.user_agent, t.ip_address, t.remember_me,
+                (SELECT MIN(created_at) FROM refresh_tokens f WHERE f.family_id = t.family_id) AS signed_in_at,
+                t.created_at AS last_refreshed_at,
+                t.expires_at
+         FROM refresh_tokens t
+         WHERE t.user_id = $1 AND t.revoked_at IS NULL AND t.expires_at > NOW()
+         ORDER BY t.created_at DESC"
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(sessions))
+}
+
+/// DELETE /api/auth/sessions/:id
+/// Sign out one of the current user's devices
+pub async fn revoke_device_session(
+    Extension(auth_user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(family_id): Path<Uuid>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let user_id = Uuid::parse_str(&auth_user.0.sub).map_err(|_| ApiError::internal("Invalid user ID"))?;
+
+    let revoked = refresh_tokens::revoke_family(&state.db, user_id, family_id, RevokeReason::Revoked).await?;
+    if revoked == 0 {
+        return Err(ApiError::not_found("Session not found"));
+    }
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::AdminLogout,
+        "user",
+        Some(&user_id.to_string()),
+        Some(json!({ "device_session": family_id })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({ "message": "Device signed out" })))
 }
 
 /// GET /api/auth/me
@@ -157,7 +431,7 @@ pub async fn me(
 ) -> ApiResult<Json<UserInfo>> {
     // Look up user from database to get latest info
     let user = sqlx::query_as::<_, User>(
-        "SELECT id, email, password_hash, role, is_active, created_at, updated_at
+        "SELECT id, email, display_name, password_hash, role, is_active, created_at, updated_at
          FROM users
          WHERE id = $1"
     )
@@ -171,11 +445,465 @@ pub async fn me(
         return Err(ApiError::forbidden("Account is disabled"));
     }
 
-    Ok(Json(UserInfo {
-        id: user.id.to_string(),
-        email: user.email,
-        role: user.role,
-    }))
+    Ok(Json(UserInfo::new(user)))
+}
+
+/// Change password request (authenticated user)
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Update the caller's own profile; omitted fields are left unchanged
+///
+/// Changing the email or password requires `current_password`.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateProfileRequest {
+    /// Empty string clears the display name
+    #[ts(optional)]
+    pub display_name: Option<String>,
+    #[ts(optional)]
+    pub email: Option<String>,
+    #[ts(optional)]
+    pub current_password: Option<String>,
+    #[ts(optional)]
+    pub new_password: Option<String>,
+}
+
+/// Maximum length of a display name (matches the column)
+const DISPLAY_NAME_MAX_LENGTH: usize = 100;
+
+/// Trim a display name; empty means "not set"
+fn normalize_display_name(name: &str) -> Result<Option<String>, String> {
+    let name = name.trim();
+    if name.chars().count() > DISPLAY_NAME_MAX_LENGTH {
+        return Err(format!("Must be at most {} characters", DISPLAY_NAME_MAX_LENGTH));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Must not contain control characters".to_string());
+    }
+    Ok((!name.is_empty()).then(|| name.to_string()))
+}
+
+/// Request a password reset link
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+/// Set a new password with a reset token
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Default lifetime of a password reset token
+const PASSWORD_RESET_DEFAULT_TTL_MINUTES: i64 = 30;
+
+/// Reset token lifetime (PASSWORD_RESET_TTL_MINUTES)
+fn password_reset_ttl() -> chrono::Duration {
+    let minutes = std::env::var("PASSWORD_RESET_TTL_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|m| *m > 0)
+        .unwrap_or(PASSWORD_RESET_DEFAULT_TTL_MINUTES);
+    chrono::Duration::minutes(minutes)
+}
+
+fn new_password_error(message: String) -> ApiError {
+    ApiError::validation(vec![("new_password".to_string(), message)])
+}
+
+/// Save a new password hash, void reset links and sign out every device
+async fn store_new_password(conn: &mut sqlx::PgConnection, user_id: Uuid, password_hash: &str) -> ApiResult<()> {
+    sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
+        .bind(password_hash)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+    // Outstanding reset links are void once the password changes
+    sqlx::query("UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+    // Sign out every device; the caller signs in again with the new password
+    refresh_tokens::revoke_all_for_user(&mut *conn, user_id, RevokeReason::PasswordChanged).await?;
+    Ok(())
+}
+
+/// POST /api/auth/change-password
+/// Change the current user's password (requires the current password)
+pub async fn change_password(
+    Extension(auth_user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ChangePasswordRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let user_id = Uuid::parse_str(&auth_user.0.sub).map_err(|_| ApiError::internal("Invalid user ID"))?;
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, email, display_name, password_hash, role, is_active, created_at, updated_at
+         FROM users
+         WHERE id = $1"
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::unauthorized("User not found"))?;
+
+    if !user.is_active {
+        return Err(ApiError::forbidden("Account is disabled"));
+    }
+
+    let ip = audit::extract_ip_address(&headers);
+    if !verify_current_password(&state, &user, &req.current_password, ip.as_deref()).await? {
+        return Err(ApiError::validation(vec![(
+            "current_password".to_string(),
+            "Current password is incorrect".to_string(),
+        )]));
+    }
+
+    password::validate_password(&req.new_password).map_err(new_password_error)?;
+    if req.new_password == req.current_password {
+        return Err(new_password_error(
+            "New password must be different from the current password".to_string(),
+        ));
+    }
+
+    let password_hash = password::hash_password(&req.new_password).map_err(ApiError::internal)?;
+
+    let mut tx = state.db.begin().await?;
+    store_new_password(&mut tx, user_id, &password_hash).await?;
+    tx.commit().await?;
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::PasswordChanged,
+        "user",
+        Some(&user_id.to_string()),
+        None,
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("🔑 Password changed for user: {}", user.email);
+
+    Ok(Json(json!({ "message": "Password changed" })))
+}
+
+/// PATCH /api/auth/me
+/// Update the caller's display name, email and/or password
+///
+/// A password change signs out every device, like change-password.
+pub async fn update_me(
+    Extension(auth_user): Extension<AuthUser>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<UpdateProfileRequest>,
+) -> ApiResult<Json<UserInfo>> {
+    let user_id = Uuid::parse_str(&auth_user.0.sub).map_err(|_| ApiError::internal("Invalid user ID"))?;
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, email, display_name, password_hash, role, is_active, created_at, updated_at
+         FROM users
+         WHERE id = $1"
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::unauthorized("User not found"))?;
+
+    if !user.is_active {
+        return Err(ApiError::forbidden("Account is disabled"));
+    }
+
+    let mut errors = Vec::new();
+
+    let display_name = match req.display_name.as_deref().map(normalize_display_name) {
+        Some(Ok(name)) => Some(name),
+        Some(Err(e)) => {
+            errors.push(("display_name".to_string(), e));
+            None
+        }
+        None => None,
+    };
+
+    let email = match req.email.as_deref().map(users::normalize_email) {
+        Some(Ok(email)) if email != user.email.to_lowercase() => Some(email),
+        Some(Ok(_)) | None => None,
+        Some(Err(e)) => {
+            errors.push(("email".to_string(), e));
+            None
+        }
+    };
+
+    if let Some(new_password) = &req.new_password {
+        if let Err(e) = password::validate_password(new_password) {
+            errors.push(("new_password".to_string(), e));
+        } else if req.current_password.as_deref() == Some(new_password.as_str()) {
+            errors.push((
+                "new_password".to_string(),
+                "New password must be different from the current password".to_string(),
+            ));
+        }
+    }
+
+    let ip = audit::extract_ip_address(&headers);
+    if email.is_some() || req.new_password.is_some() {
+        let verified = match req.current_password.as_deref() {
+            Some(current) => verify_current_password(&state, &user, current, ip.as_deref()).await?,
+            None => false,
+        };
+        if !verified {
+            errors.push((
+                "current_password".to_string(),
+                "Current password is required to change email or password".to_string(),
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+
+    if let Some(email) = &email {
+        users::ensure_email_available(&state, email, Some(user_id)).await?;
+    }
+
+    let password_hash = req
+        .new_password
+        .as_deref()
+        .map(password::hash_password)
+        .transpose()
+        .map_err(ApiError::internal)?;
+
+    let mut tx = state.db.begin().await?;
+    let updated = sqlx::query_as::<_, User>(
+        "UPDATE users
+         SET display_name = CASE WHEN $2 THEN $3 ELSE display_name END,
+             email = COALESCE($4, email),
+             updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, email, display_name, password_hash, role, is_active, created_at, updated_at"
+    )
+    .bind(user_id)
+    .bind(display_name.is_some())
+    .bind(display_name.clone().flatten())
+    .bind(&email)
+    .fetch_one(&mut *tx)
+    .await?;
+    if let Some(password_hash) = &password_hash {
+        store_new_password(&mut tx, user_id, password_hash).await?;
+    }
+    tx.commit().await?;
+
+    let mut changed = Vec::new();
+    if display_name.is_some_and(|name| name != user.display_name) {
+        changed.push("display_name");
+    }
+    if email.is_some() {
+        changed.push("email");
+    }
+    if password_hash.is_some() {
+        changed.push("password");
+    }
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::ProfileUpdated,
+        "user",
+        Some(&user_id.to_string()),
+        Some(json!({
+            "fields": &changed,
+            "before": { "email": &user.email, "display_name": &user.display_name },
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+    if password_hash.is_some() {
+        audit::log_event(
+            &state.db,
+            user_id,
+            audit::AuditAction::PasswordChanged,
+            "user",
+            Some(&user_id.to_string()),
+            None,
+            ip.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(Json(UserInfo::new(updated)))
+}
+
+/// POST /api/auth/forgot-password
+/// Issue a single-use reset token. Always returns the same response so the
+/// endpoint cannot be used to discover which emails have accounts.
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ForgotPasswordRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let response = json!({
+        "message": "If an active account exists for that email, a password reset link has been issued"
+    });
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, email, display_name, password_hash, role, is_active, created_at, updated_at
+         FROM users
+         WHERE LOWER(email) = LOWER($1) AND NOT is_service"
+    )
+    .bind(req.email.trim())
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(user) = user.filter(|u| u.is_active) else {
+        return Ok(Json(response));
+    };
+
+    // Without a delivery channel no token is issued; it must never end up in the logs
+    let Ok(webhook_url) = std::env::var("PASSWORD_RESET_WEBHOOK_URL") else {
+        tracing::warn!("🔑 Password reset requested, but PASSWORD_RESET_WEBHOOK_URL is not configured; no link was issued");
+        return Ok(Json(response));
+    };
+
+    let token = password::generate_opaque_token();
+    let expires_at = chrono::Utc::now() + password_reset_ttl();
+    let ip = audit::extract_ip_address(&headers);
+
+    let mut tx = state.db.begin().await?;
+    // Only the newest link works
+    sqlx::query("UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at, requested_ip)
+         VALUES ($1, $2, $3, $4)"
+    )
+    .bind(user.id)
+    .bind(password::hash_opaque_token(&token))
+    .bind(expires_at)
+    .bind(ip.as_deref())
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    audit::log_event(
+        &state.db,
+        user.id,
+        audit::AuditAction::PasswordResetRequested,
+        "user",
+        Some(&user.id.to_string()),
+        Some(json!({ "expires_at": expires_at.to_rfc3339() })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    deliver_reset_link(webhook_url, &user.email, &token, expires_at);
+
+    Ok(Json(response))
+}
+
+/// Send the reset link to the PASSWORD_RESET_WEBHOOK_URL (e.g. a mailer)
+fn deliver_reset_link(webhook_url: String, email: &str, token: &str, expires_at: chrono::DateTime<chrono::Utc>) {
+    let base_url = std::env::var("PASSWORD_RESET_URL")
+        .unwrap_or_else(|_| "/admin/reset-password".to_string());
+    let separator = if base_url.contains('?') { '&' } else { '?' };
+    let reset_url = format!("{}{}token={}", base_url, separator, token);
+
+    let payload = json!({
+        "email": email,
+        "reset_url": reset_url,
+        "expires_at": expires_at.to_rfc3339(),
+    });
+
+    tokio::spawn(async move {
+        let result = reqwest::Client::new()
+            .post(&webhook_url)
+            .timeout(std::time::Duration::from_secs(10))
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+
+        if let Err(e) = result {
+            tracing::error!("❌ Failed to deliver password reset link: {}", e);
+        }
+    });
+}
+
+/// POST /api/auth/reset-password
+/// Set a new password using a reset token (single use)
+pub async fn reset_password(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<ResetPasswordRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    password::validate_password(&req.new_password).map_err(new_password_error)?;
+
+    let mut tx = state.db.begin().await?;
+
+    // Claim the token inside the transaction so it can only be used once
+    let user_id = sqlx::query_scalar::<_, Uuid>(
+        "UPDATE password_reset_tokens
+         SET used_at = NOW()
+         WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+         RETURNING user_id"
+    )
+    .bind(password::hash_opaque_token(&req.token))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::bad_request("Reset link is invalid or has expired"))?;
+
+    let password_hash = password::hash_password(&req.new_password).map_err(ApiError::internal)?;
+
+    let updated = sqlx::query(
+        "UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2 AND is_active = true"
+    )
+    .bind(&password_hash)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(ApiError::bad_request("Reset link is invalid or has expired"));
+    }
+
+    // Proving control of the mailbox lifts a lockout
+    login_guard::clear_failures(&mut *tx, user_id).await?;
+
+    sqlx::query("UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    refresh_tokens::revoke_all_for_user(&mut *tx, user_id, RevokeReason::PasswordChanged).await?;
+    tx.commit().await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::PasswordReset,
+        "user",
+        Some(&user_id.to_string()),
+        None,
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("🔑 Password reset completed for user {}", user_id);
+
+    Ok(Json(json!({ "message": "Password has been reset. You can now sign in." })))
 }
 
 #[cfg(test)]
@@ -198,10 +926,22 @@ mod tests {
         let user_info = UserInfo {
             id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
             email: "test@example.com".to_string(),
+            display_name: Some("Pat Tech".to_string()),
             role: UserRole::Admin,
+            permissions: vec![Permission::IssuesRead, Permission::UsersManage],
         };
 
         let json = serde_json::to_string(&user_info).unwrap();
         assert!(json.contains("test@example.com"));
+        assert!(json.contains("\"users:manage\""));
+        assert!(json.contains("\"display_name\":\"Pat Tech\""));
+    }
+
+    #[test]
+    fn test_normalize_display_name() {
+        assert_eq!(normalize_display_name("  Pat Tech "), Ok(Some("Pat Tech".to_string())));
+        assert_eq!(normalize_display_name("   "), Ok(None));
+        assert!(normalize_display_name(&"x".repeat(DISPLAY_NAME_MAX_LENGTH + 1)).is_err());
+        assert!(normalize_display_name("Pat\nTech").is_err());
     }
 }
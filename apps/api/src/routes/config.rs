@@ -0,0 +1,152 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::utils::{audit, branding, settings};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// How long browsers and proxies may reuse the public config
+const PUBLIC_CONFIG_MAX_AGE_SECONDS: u32 = 60;
+
+/// GET /api/v1/config/public
+/// Deployment branding for the frontend (no auth required)
+pub async fn get_public_config() -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, format!("public, max-age={}", PUBLIC_CONFIG_MAX_AGE_SECONDS))],
+        Json(branding::current().as_ref().clone()),
+    )
+}
+
+/// GET /api/admin/branding
+/// Get the branding settings with their last change time (ADMIN only)
+pub async fn get_branding() -> ApiResult<Json<branding::BrandingSettings>> {
+    Ok(Json(branding::settings()))
+}
+
+/// PUT /api/admin/branding
+/// Update branding; omitted fields are left unchanged (ADMIN only)
+pub async fn update_branding(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<branding::UpdateBrandingRequest>,
+) -> ApiResult<Json<branding::BrandingSettings>> {
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    // Start from the stored row so a stale cache can't undo another admin's change
+    branding::reload(&state.db).await?;
+    let previous = branding::current();
+    let config = branding::merge(&previous, &req).map_err(ApiError::validation)?;
+    branding::save(&state.db, &config, user_id).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::BrandingUpdated,
+        "branding",
+        None,
+        Some(json!({
+            "changes": req,
+            "previous_product_name": previous.product_name,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(branding::settings()))
+}
+
+/// GET /api/admin/settings
+/// List runtime settings with their effective values and defaults (ADMIN only)
+pub async fn list_settings(State(state): State<AppState>) -> ApiResult<Json<Vec<settings::SettingInfo>>> {
+    Ok(Json(
+        settings::DEFINITIONS
+            .iter()
+            .map(|d| settings::info(d, state.settings.override_of(d.key)))
+            .collect(),
+    ))
+}
+
+/// PUT /api/admin/settings/:key
+/// Override a runtime setting; takes effect on every instance within a minute (ADMIN only)
+pub async fn update_setting(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<settings::UpdateSettingRequest>,
+) -> ApiResult<Json<settings::SettingInfo>> {
+    let definition = settings::definition(&key)
+        .ok_or_else(|| ApiError::not_found(format!("Setting '{}' not found", key)))?;
+    let value = settings::validate(definition, &req.value)
+        .map_err(|message| ApiError::validation(vec![("value".to_string(), message)]))?;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let previous = settings::info(definition, state.settings.override_of(&key));
+    state.settings.set(&state.db, &key, value, user_id).await?;
+
+    tracing::info!("⚙️  Setting {} changed from {} to {}", key, previous.value, value);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::SettingChanged,
+        "setting",
+        Some(&key),
+        Some(json!({
+            "previous": previous.value,
+            "value": value,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(settings::info(definition, state.settings.override_of(&key))))
+}
+
+/// DELETE /api/admin/settings/:key
+/// Drop the override so the default applies again (ADMIN only)
+pub async fn reset_setting(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<settings::SettingInfo>> {
+    let definition = settings::definition(&key)
+        .ok_or_else(|| ApiError::not_found(format!("Setting '{}' not found", key)))?;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let previous = settings::info(definition, state.settings.override_of(&key));
+    if state.settings.reset(&state.db, &key).await? {
+        let ip = audit::extract_ip_address(&headers);
+        audit::log_event(
+            &state.db,
+            user_id,
+            audit::AuditAction::SettingChanged,
+            "setting",
+            Some(&key),
+            Some(json!({
+                "previous": previous.value,
+                "value": previous.default_value,
+                "reset": true,
+            })),
+            ip.as_deref(),
+        )
+        .await?;
+    }
+
+    Ok(Json(settings::info(definition, state.settings.override_of(&key))))
+}
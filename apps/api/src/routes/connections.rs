@@ -1,7 +1,8 @@
 use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthUser;
 use crate::models::{Connection, CreateConnection, UpdateConnection};
-use crate::utils::audit;
+use crate::routes::nodes::contains_pattern;
+use crate::utils::{audit, category_access, conditions, cycles};
 use crate::AppState;
 use axum::{
     extract::{Path, Query, State},
@@ -16,10 +17,38 @@ use uuid::Uuid;
 pub struct ListConnectionsQuery {
     pub from_node_id: Option<Uuid>,
     pub to_node_id: Option<Uuid>,
+    /// Only active (true) or only switched-off (false) connections; overrides `include_inactive`
+    pub is_active: Option<bool>,
+    /// Also list switched-off connections
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Case-insensitive match anywhere in the answer label
+    pub search: Option<String>,
+}
+
+/// Query parameters for create_connection and update_connection
+#[derive(Debug, Deserialize)]
+pub struct ConnectionWriteQuery {
+    /// Keep an answer that leads back to an earlier question
+    #[serde(default)]
+    pub allow_cycle: bool,
+}
+
+/// Refuse an answer from `from` to `to` that closes a loop in the category
+async fn ensure_no_loop(state: &AppState, from: Uuid, to: Uuid, replacing: Option<Uuid>) -> ApiResult<()> {
+    match cycles::loop_closed_by(&state.db, from, to, replacing).await? {
+        Some(path) => Err(ApiError::Conflict {
+            message: format!(
+                "This answer would create a loop ({}); pass allow_cycle=true to keep it",
+                path.join(" → ")
+            ),
+        }),
+        None => Ok(()),
+    }
 }
 
 /// GET /api/connections
-/// List connections, optionally filtered by from/to node
+/// List connections, optionally filtered by from/to node, state or label
 pub async fn list_connections(
     State(state): State<AppState>,
     Query(query): Query<ListConnectionsQuery>,
@@ -27,11 +56,23 @@ pub async fn list_connections(
     // Build query safely using QueryBuilder to prevent SQL injection
     use sqlx::QueryBuilder;
     let mut query_builder = QueryBuilder::new(
-        "SELECT id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at
+        "SELECT id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at
          FROM connections
-         WHERE is_active = true"
+         WHERE true"
     );
 
+    // Switched-off connections are only listed on request
+    match query.is_active {
+        Some(is_active) => {
+            query_builder.push(" AND is_active = ");
+            query_builder.push_bind(is_active);
+        }
+        None if !query.include_inactive => {
+            query_builder.push(" AND is_active = true");
+        }
+        None => {}
+    }
+
     // From node filter - SAFE: uses parameterized query
     if let Some(from_id) = query.from_node_id {
         query_builder.push(" AND from_node_id = ");
@@ -44,6 +85,11 @@ pub async fn list_connections(
         query_builder.push_bind(to_id);
     }
 
+    if let Some(search) = query.search.as_deref().filter(|s| !s.trim().is_empty()) {
+        query_builder.push(" AND label ILIKE ");
+        query_builder.push_bind(contains_pattern(search));
+    }
+
     query_builder.push(" ORDER BY order_index ASC");
 
     let connections = query_builder
@@ -56,10 +102,12 @@ pub async fn list_connections(
 
 /// POST /api/connections
 /// Create new connection (ADMIN only)
+/// Answers that lead back to an earlier question are refused unless `allow_cycle=true`
 pub async fn create_connection(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
     headers: HeaderMap,
+    Query(query): Query<ConnectionWriteQuery>,
     Json(req): Json<CreateConnection>,
 ) -> ApiResult<Json<Connection>> {
     // Validate both nodes exist
@@ -84,6 +132,8 @@ pub async fn create_connection(
         )]));
     }
 
+    category_access::ensure_can_edit_node(&state.db, &auth, req.from_node_id).await?;
+
     // Validate label is not empty
     if req.label.is_empty() {
         return Err(ApiError::validation(vec![(
@@ -92,16 +142,37 @@ pub async fn create_connection(
         )]));
     }
 
+    let condition = match req.condition.as_deref() {
+        Some(condition) => conditions::normalize(condition)
+            .map_err(|e| ApiError::validation(vec![("condition".to_string(), e)]))?,
+        None => None,
+    };
+
+    if !query.allow_cycle {
+        ensure_no_loop(&state, req.from_node_id, req.to_node_id, None).await?;
+    }
+
+    // A question has one conservative branch; this one replaces any other
+    let unsure_route = req.unsure_route.unwrap_or(false);
+    if unsure_route {
+        sqlx::query("UPDATE connections SET unsure_route = false, updated_at = NOW() WHERE from_node_id = $1 AND unsure_route")
+            .bind(req.from_node_id)
+            .execute(&state.db)
+            .await?;
+    }
+
     // Insert connection
     let connection = sqlx::query_as::<_, Connection>(
-        "INSERT INTO connections (from_node_id, to_node_id, label, order_index, is_active)
-         VALUES ($1, $2, $3, $4, true)
-         RETURNING id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at"
+        "INSERT INTO connections (from_node_id, to_node_id, label, order_index, condition, unsure_route, is_active)
+         VALUES ($1, $2, $3, $4, $5, $6, true)
+         RETURNING id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at"
     )
     .bind(req.from_node_id)
     .bind(req.to_node_id)
     .bind(&req.label)
     .bind(req.order_index)
+    .bind(&condition)
+    .bind(unsure_route)
     .fetch_one(&state.db)
     .await?;
 
@@ -116,9 +187,7 @@ pub async fn create_connection(
 
     // Invalidate cache for the category
     if let Some(category) = &category {
-        let cache_key = format!("graph_{}", category);
-        state.issue_graph_cache.invalidate(&cache_key).await;
-        state.issue_tree_cache.invalidate(category).await;
+        state.invalidate_issue(category).await;
     }
 
     // Audit log the connection creation
@@ -147,24 +216,25 @@ pub async fn create_connection(
 
 /// PUT /api/connections/:id
 /// Update connection (ADMIN only)
+/// Retargeting or reactivating an answer so it closes a loop needs `allow_cycle=true`
 pub async fn update_connection(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
+    Query(query): Query<ConnectionWriteQuery>,
     Json(req): Json<UpdateConnection>,
 ) -> ApiResult<Json<Connection>> {
     // Check if connection exists
-    let exists = sqlx::query_scalar::<_, bool>(
-        "SELECT EXISTS(SELECT 1 FROM connections WHERE id = $1)"
+    let (from_node_id, current_to, current_active) = sqlx::query_as::<_, (Uuid, Uuid, bool)>(
+        "SELECT from_node_id, to_node_id, is_active FROM connections WHERE id = $1"
     )
     .bind(id)
-    .fetch_one(&state.db)
-    .await?;
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Connection not found"))?;
 
-    if !exists {
-        return Err(ApiError::not_found("Connection not found"));
-    }
+    category_access::ensure_can_edit_connection(&state.db, &auth, id).await?;
 
     // If changing to_node_id, validate it exists
     if let Some(to_node_id) = req.to_node_id {
@@ -183,6 +253,44 @@ pub async fn update_connection(
         }
     }
 
+    if let Some(weight) = req.weight {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(ApiError::validation(vec![(
+                "weight".to_string(),
+                "Weight must be a non-negative number".to_string(),
+            )]));
+        }
+    }
+
+    // Empty string clears the condition
+    let condition = match req.condition.as_deref() {
+        Some(condition) => Some(
+            conditions::normalize(condition)
+                .map_err(|e| ApiError::validation(vec![("condition".to_string(), e)]))?,
+        ),
+        None => None,
+    };
+
+    // Only a new target or switching the answer back on can close a loop
+    let to_node_id = req.to_node_id.unwrap_or(current_to);
+    let is_active = req.is_active.unwrap_or(current_active);
+    let rewired = to_node_id != current_to || !current_active;
+    if is_active && rewired && !query.allow_cycle {
+        ensure_no_loop(&state, from_node_id, to_node_id, Some(id)).await?;
+    }
+
+    // A question has one conservative branch; this one replaces any other
+    if req.unsure_route == Some(true) {
+        sqlx::query(
+            "UPDATE connections SET unsure_route = false, updated_at = NOW()
+             WHERE unsure_route AND id <> $1
+               AND from_node_id = (SELECT from_node_id FROM connections WHERE id = $1)",
+        )
+        .bind(id)
+        .execute(&state.db)
+        .await?;
+    }
+
     // Build dynamic update query
     let mut query = String::from("UPDATE connections SET updated_at = NOW()");
     let mut param_count = 1;
@@ -199,12 +307,26 @@ pub async fn update_connection(
         param_count += 1;
         query.push_str(&format!(", order_index = ${}", param_count));
     }
+    if req.weight.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", weight = ${}", param_count));
+    } else if req.clear_weight == Some(true) {
+        query.push_str(", weight = NULL");
+    }
+    if condition.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", condition = ${}", param_count));
+    }
+    if req.unsure_route.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", unsure_route = ${}", param_count));
+    }
     if req.is_active.is_some() {
         param_count += 1;
         query.push_str(&format!(", is_active = ${}", param_count));
     }
 
-    query.push_str(" WHERE id = $1 RETURNING id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at");
+    query.push_str(" WHERE id = $1 RETURNING id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at");
 
     let mut query_builder = sqlx::query_as::<_, Connection>(&query).bind(id);
 
@@ -217,6 +339,15 @@ pub async fn update_connection(
     if let Some(ref order_index) = req.order_index {
         query_builder = query_builder.bind(order_index);
     }
+    if let Some(weight) = req.weight {
+        query_builder = query_builder.bind(weight);
+    }
+    if let Some(ref condition) = condition {
+        query_builder = query_builder.bind(condition);
+    }
+    if let Some(unsure_route) = req.unsure_route {
+        query_builder = query_builder.bind(unsure_route);
+    }
     if let Some(ref is_active) = req.is_active {
         query_builder = query_builder.bind(is_active);
     }
@@ -234,9 +365,7 @@ pub async fn update_connection(
 
     // Invalidate cache for the category
     if let Some(category) = &category {
-        let cache_key = format!("graph_{}", category);
-        state.issue_graph_cache.invalidate(&cache_key).await;
-        state.issue_tree_cache.invalidate(category).await;
+        state.invalidate_issue(category).await;
     }
 
     // Audit log the connection update
@@ -263,6 +392,54 @@ pub async fn update_connection(
     Ok(Json(connection))
 }
 
+/// POST /api/connections/:id/restore
+/// Switch an inactive connection back on (ADMIN only)
+pub async fn restore_connection(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Connection>> {
+    category_access::ensure_can_edit_connection(&state.db, &auth, id).await?;
+
+    let connection = sqlx::query_as::<_, Connection>(
+        "UPDATE connections SET is_active = true, updated_at = NOW()
+         WHERE id = $1 AND is_active = false
+         RETURNING id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at"
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::bad_request("Connection is already active"))?;
+
+    let category = sqlx::query_scalar::<_, String>("SELECT category FROM nodes WHERE id = $1")
+        .bind(connection.from_node_id)
+        .fetch_one(&state.db)
+        .await?;
+    state.invalidate_issue(&category).await;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::ConnectionRestored,
+        "connection",
+        Some(&connection.id.to_string()),
+        Some(json!({
+            "category": &category,
+            "from_node_id": connection.from_node_id,
+            "to_node_id": connection.to_node_id,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(connection))
+}
+
 /// DELETE /api/connections/:id
 /// Hard delete connection (ADMIN only)
 pub async fn delete_connection(
@@ -273,7 +450,7 @@ pub async fn delete_connection(
 ) -> ApiResult<Json<Connection>> {
     // Fetch the connection first to return it and get category for cache invalidation
     let connection = sqlx::query_as::<_, Connection>(
-        "SELECT id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at
+        "SELECT id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at
          FROM connections
          WHERE id = $1"
     )
@@ -282,6 +459,8 @@ pub async fn delete_connection(
     .await?
     .ok_or_else(|| ApiError::not_found("Connection not found"))?;
 
+    category_access::ensure_can_edit_node(&state.db, &auth, connection.from_node_id).await?;
+
     // Get the from_node category for cache invalidation
     let category = sqlx::query_scalar::<_, Option<String>>(
         "SELECT category FROM nodes WHERE id = $1"
@@ -299,9 +478,7 @@ pub async fn delete_connection(
 
     // Invalidate cache for the category
     if let Some(category) = &category {
-        let cache_key = format!("graph_{}", category);
-        state.issue_graph_cache.invalidate(&cache_key).await;
-        state.issue_tree_cache.invalidate(category).await;
+        state.invalidate_issue(category).await;
     }
 
     // Audit log the connection deletion
@@ -0,0 +1,199 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::routes::users::acting_user;
+use crate::utils::{audit, devices, password};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Maximum length of a device name
+const NAME_MAX_LENGTH: usize = 100;
+
+/// A registered kiosk without its token
+#[derive(Debug, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct DeviceInfo {
+    pub id: Uuid,
+    pub name: String,
+    /// First characters of the token, to recognise it
+    pub token_prefix: String,
+    pub created_at: DateTime<Utc>,
+    /// Approximate (recorded about once a minute)
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub disabled_at: Option<DateTime<Utc>>,
+    /// Sessions started from this device
+    #[ts(type = "number")]
+    pub session_count: i64,
+}
+
+/// A newly registered device; `token` is shown only in this response
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssuedDeviceToken {
+    pub token: String,
+    pub device: DeviceInfo,
+}
+
+/// Request to register a kiosk
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RegisterDeviceRequest {
+    /// Where the kiosk is (e.g. "Plant 2 line 4")
+    pub name: String,
+}
+
+const DEVICE_COLUMNS: &str = "id, name, token_prefix, created_at, last_seen_at, disabled_at,
+    (SELECT COUNT(*) FROM sessions s WHERE s.device_id = devices.id) AS session_count";
+
+async fn fetch_device(state: &AppState, id: Uuid) -> ApiResult<DeviceInfo> {
+    sqlx::query_as::<_, DeviceInfo>(&format!("SELECT {} FROM devices WHERE id = $1", DEVICE_COLUMNS))
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Device not found"))
+}
+
+/// GET /api/admin/devices
+/// List registered kiosks (tokens are never returned)
+pub async fn list_devices(State(state): State<AppState>) -> ApiResult<Json<Vec<DeviceInfo>>> {
+    let devices = sqlx::query_as::<_, DeviceInfo>(&format!(
+        "SELECT {} FROM devices ORDER BY disabled_at IS NOT NULL, name",
+        DEVICE_COLUMNS
+    ))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(devices))
+}
+
+/// POST /api/admin/devices
+/// Register a kiosk and issue its device token
+pub async fn register_device(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<RegisterDeviceRequest>,
+) -> ApiResult<Json<IssuedDeviceToken>> {
+    let user_id = acting_user(&auth)?;
+
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > NAME_MAX_LENGTH {
+        return Err(ApiError::validation(vec![(
+            "name".to_string(),
+            format!("Must be 1-{} characters", NAME_MAX_LENGTH),
+        )]));
+    }
+
+    let token = devices::generate();
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO devices (name, token_hash, token_prefix, created_by)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id",
+    )
+    .bind(name)
+    .bind(password::hash_opaque_token(&token))
+    .bind(&token[..devices::DISPLAY_PREFIX_LENGTH])
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    devices::reload(&state.db).await?;
+
+    let device = fetch_device(&state, id).await?;
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::DeviceRegistered,
+        "device",
+        Some(&device.id.to_string()),
+        Some(json!({
+            "name": &device.name,
+            "token_prefix": &device.token_prefix,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("📟 Kiosk registered: {}", device.name);
+
+    Ok(Json(IssuedDeviceToken { token, device }))
+}
+
+/// Disable or re-enable a device and record it
+async fn set_disabled(
+    state: &AppState,
+    auth: &AuthUser,
+    headers: &HeaderMap,
+    id: Uuid,
+    disabled: bool,
+) -> ApiResult<Json<DeviceInfo>> {
+    let user_id = acting_user(auth)?;
+
+    let updated = sqlx::query(
+        "UPDATE devices
+         SET disabled_at = CASE WHEN $2 THEN COALESCE(disabled_at, NOW()) END,
+             disabled_by = CASE WHEN $2 THEN COALESCE(disabled_by, $3) END
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(disabled)
+    .bind(user_id)
+    .execute(&state.db)
+    .await?
+    .rows_affected();
+    if updated == 0 {
+        return Err(ApiError::not_found("Device not found"));
+    }
+
+    devices::reload(&state.db).await?;
+
+    let device = fetch_device(state, id).await?;
+    let ip = audit::extract_ip_address(headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        if disabled {
+            audit::AuditAction::DeviceDisabled
+        } else {
+            audit::AuditAction::DeviceEnabled
+        },
+        "device",
+        Some(&device.id.to_string()),
+        Some(json!({ "name": &device.name })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(device))
+}
+
+/// POST /api/admin/devices/:id/disable
+/// Stop accepting a device's token; it is refused immediately on this instance and within a minute elsewhere
+pub async fn disable_device(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<DeviceInfo>> {
+    set_disabled(&state, &auth, &headers, id, true).await
+}
+
+/// POST /api/admin/devices/:id/enable
+/// Accept a disabled device's token again
+pub async fn enable_device(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<DeviceInfo>> {
+    set_disabled(&state, &auth, &headers, id, false).await
+}
@@ -0,0 +1,293 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::utils::{audit, category_access};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Maximum length of an entry point name (matches the column)
+const NAME_MAX_LENGTH: usize = 100;
+
+/// Named alternative start node
+#[derive(Debug, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct EntryPoint {
+    pub name: String,
+    pub node_id: Uuid,
+    /// Category and text of the start node, for display
+    pub node_category: String,
+    pub node_text: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to create an entry point
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CreateEntryPointRequest {
+    /// Lowercase letters, digits, `-` and `_`
+    pub name: String,
+    pub node_id: Uuid,
+    #[ts(optional)]
+    pub description: Option<String>,
+}
+
+/// Request to update an entry point; omitted fields are left unchanged
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateEntryPointRequest {
+    #[ts(optional)]
+    pub node_id: Option<Uuid>,
+    /// Empty string clears the description
+    #[ts(optional)]
+    pub description: Option<String>,
+    #[ts(optional)]
+    pub is_active: Option<bool>,
+}
+
+const ENTRY_POINT_SELECT: &str =
+    "SELECT e.name, e.node_id, n.category AS node_category, n.text AS node_text, e.description, e.is_active,
+            e.created_at, e.updated_at
+     FROM entry_points e
+     JOIN nodes n ON n.id = e.node_id";
+
+/// Entry point names are used in kiosk configuration and URLs
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > NAME_MAX_LENGTH {
+        return Err(format!("Must be 1-{} characters", NAME_MAX_LENGTH));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        return Err("Only lowercase letters, digits, '-' and '_' are allowed".to_string());
+    }
+    Ok(())
+}
+
+async fn fetch_entry_point(state: &AppState, name: &str) -> ApiResult<EntryPoint> {
+    sqlx::query_as::<_, EntryPoint>(&format!("{} WHERE e.name = $1", ENTRY_POINT_SELECT))
+        .bind(name)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Entry point '{}' not found", name)))
+}
+
+/// Entry points must start at an active question
+async fn ensure_start_node(state: &AppState, auth: &AuthUser, node_id: Uuid) -> ApiResult<()> {
+    let node = sqlx::query_as::<_, (String, bool, bool, bool)>(
+        "SELECT category, node_type = 'question', is_active,
+                category IN (SELECT draft_category FROM issue_drafts)
+         FROM nodes WHERE id = $1",
+    )
+    .bind(node_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some((category, is_question, is_active, is_draft)) = node else {
+        return Err(ApiError::validation(vec![("node_id".to_string(), "Node does not exist".to_string())]));
+    };
+    if let Some(problem) = start_node_problem(is_question, is_active, is_draft) {
+        return Err(ApiError::validation(vec![("node_id".to_string(), problem.to_string())]));
+    }
+    category_access::ensure_can_edit(&state.db, auth, [category.as_str()]).await
+}
+
+/// Why a node can't be an entry point's start, if it can't
+fn start_node_problem(is_question: bool, is_active: bool, is_draft: bool) -> Option<&'static str> {
+    if !is_question {
+        Some("Entry points must start at a question node")
+    } else if !is_active {
+        Some("Entry points must start at an active node")
+    } else if is_draft {
+        // Drafts go live through approval, not through an entry point
+        Some("Entry points can't start in an unpublished draft")
+    } else {
+        None
+    }
+}
+
+fn normalize_description(description: Option<&str>) -> Option<String> {
+    description.map(str::trim).filter(|d| !d.is_empty()).map(str::to_string)
+}
+
+/// GET /api/admin/entry-points
+/// List all entry points
+pub async fn list_entry_points(State(state): State<AppState>) -> ApiResult<Json<Vec<EntryPoint>>> {
+    let entry_points = sqlx::query_as::<_, EntryPoint>(&format!("{} ORDER BY e.name", ENTRY_POINT_SELECT))
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(entry_points))
+}
+
+/// POST /api/admin/entry-points
+/// Create a named start node
+pub async fn create_entry_point(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<CreateEntryPointRequest>,
+) -> ApiResult<Json<EntryPoint>> {
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    validate_name(&req.name).map_err(|e| ApiError::validation(vec![("name".to_string(), e)]))?;
+    ensure_start_node(&state, &auth, req.node_id).await?;
+
+    let inserted = sqlx::query(
+        "INSERT INTO entry_points (name, node_id, description, created_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (name) DO NOTHING",
+    )
+    .bind(&req.name)
+    .bind(req.node_id)
+    .bind(normalize_description(req.description.as_deref()))
+    .bind(user_id)
+    .execute(&state.db)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(ApiError::Conflict {
+            message: format!("Entry point '{}' already exists", req.name),
+        });
+    }
+
+    let entry_point = fetch_entry_point(&state, &req.name).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::EntryPointCreated,
+        "entry_point",
+        Some(&entry_point.name),
+        Some(json!({ "node_id": entry_point.node_id, "category": &entry_point.node_category })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(entry_point))
+}
+
+/// PUT /api/admin/entry-points/:name
+/// Point an entry point at another node, or change its description or active flag
+pub async fn update_entry_point(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<UpdateEntryPointRequest>,
+) -> ApiResult<Json<EntryPoint>> {
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let before = fetch_entry_point(&state, &name).await?;
+    category_access::ensure_can_edit(&state.db, &auth, [before.node_category.as_str()]).await?;
+    if let Some(node_id) = req.node_id {
+        ensure_start_node(&state, &auth, node_id).await?;
+    }
+
+    sqlx::query(
+        "UPDATE entry_points
+         SET node_id = COALESCE($2, node_id),
+             description = CASE WHEN $3 THEN $4 ELSE description END,
+             is_active = COALESCE($5, is_active),
+             updated_at = NOW()
+         WHERE name = $1",
+    )
+    .bind(&name)
+    .bind(req.node_id)
+    .bind(req.description.is_some())
+    .bind(normalize_description(req.description.as_deref()))
+    .bind(req.is_active)
+    .execute(&state.db)
+    .await?;
+
+    let entry_point = fetch_entry_point(&state, &name).await?;
+    state.questions_cache.clear().await;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::EntryPointUpdated,
+        "entry_point",
+        Some(&entry_point.name),
+        Some(json!({
+            "before": { "node_id": before.node_id, "is_active": before.is_active },
+            "updates": &req,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(entry_point))
+}
+
+/// DELETE /api/admin/entry-points/:name
+/// Remove an entry point; kiosks using it fail to start sessions until reconfigured
+pub async fn delete_entry_point(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> ApiResult<Json<EntryPoint>> {
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let entry_point = fetch_entry_point(&state, &name).await?;
+    category_access::ensure_can_edit(&state.db, &auth, [entry_point.node_category.as_str()]).await?;
+
+    sqlx::query("DELETE FROM entry_points WHERE name = $1")
+        .bind(&name)
+        .execute(&state.db)
+        .await?;
+    state.questions_cache.clear().await;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::EntryPointDeleted,
+        "entry_point",
+        Some(&entry_point.name),
+        Some(json!({ "node_id": entry_point.node_id, "category": &entry_point.node_category })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(entry_point))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("lobby-kiosk_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Lobby").is_err());
+        assert!(validate_name("lobby kiosk").is_err());
+        assert!(validate_name(&"a".repeat(NAME_MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_start_node_problem() {
+        assert_eq!(start_node_problem(true, true, false), None);
+        assert!(start_node_problem(false, true, false).is_some());
+        assert!(start_node_problem(true, false, false).is_some());
+        assert_eq!(
+            start_node_problem(true, true, true),
+            Some("Entry points can't start in an unpublished draft")
+        );
+    }
+}
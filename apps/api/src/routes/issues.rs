@@ -1,11 +1,30 @@
-use crate::error::{ApiError, ApiResult};
-use crate::middleware::auth::AuthUser;
-use crate::models::{Node, Connection, IssueGraph, NodeType};
-use crate::utils::audit;
+use crate::engine;
+use crate::error::{ApiError, ApiResult, ValidationField};
+use crate::middleware::auth::{ensure_permission, AuthUser, ServiceScopes};
+use crate::models::{AnswerMode, Node, NodeContent, Connection, IssueGraph, NodeType, OptionOrder, Permission};
+use crate::utils::expression::{Value, Variables};
+use crate::utils::graph_validation::{self, GraphProblem, GraphValidationReport};
+use crate::routes::uploads;
+use crate::uploads::{NewUpload, UploadOwnerType};
+use crate::routes::users::acting_user;
+use crate::utils::category_quotas::{self, CategoryQuota};
+use crate::utils::issue_approvals::{self, ApprovalStatus, IssueApproval};
+use crate::utils::issue_bundle::{self, AttachmentFile};
+use crate::utils::diagrams;
+use crate::utils::issue_csv;
+use crate::utils::issue_drafts::{self, DraftPublished, IssueDraft};
+use crate::utils::issue_metadata;
+use crate::utils::issue_schedules;
+use crate::utils::issue_slugs;
+use crate::utils::issue_versions::{self, IssueRolledBack, IssueVersion, IssueVersionDiff};
+use crate::utils::layout::{self, LayoutResult};
+use crate::utils::orphans::{self, OrphanReport};
+use crate::utils::{audit, cache_warming, category_access, category_notices, conditions, content, import_adapters, permissions, semantic_ids};
 use crate::AppState;
 use axum::{
     extract::{Path, Query, State},
-    http::HeaderMap,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
 use serde::{Deserialize, Serialize};
@@ -30,6 +49,20 @@ pub struct Issue {
     pub question_count: i64,
     pub created_at: String,
     pub updated_at: String,
+    /// When the issue was archived (hidden from technicians); null while it's live
+    pub archived_at: Option<String>,
+    pub description: Option<String>,
+    /// User responsible for the issue's content
+    pub owner_id: Option<Uuid>,
+    /// Email of the owner (null once the account is gone)
+    pub owner_email: Option<String>,
+    /// Equipment models the issue applies to
+    pub equipment_models: Vec<String>,
+    pub tags: Vec<String>,
+    /// Hidden from technicians before this time
+    pub active_from: Option<String>,
+    /// Hidden from technicians from this time on
+    pub active_until: Option<String>,
 }
 
 /// Request to create a new issue
@@ -40,6 +73,26 @@ pub struct CreateIssueRequest {
     pub category: String,
     pub display_category: Option<String>,
     pub root_question_text: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub owner_id: Option<Uuid>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub equipment_models: Option<Vec<String>>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub tags: Option<Vec<String>>,
+    /// Start of the window technicians see the issue in
+    #[serde(default)]
+    #[ts(optional)]
+    pub active_from: Option<chrono::DateTime<chrono::Utc>>,
+    /// End of the window technicians see the issue in
+    #[serde(default)]
+    #[ts(optional)]
+    pub active_until: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Request to update issue metadata
@@ -49,6 +102,43 @@ pub struct UpdateIssueRequest {
     pub name: Option<String>,
     pub display_category: Option<String>,
     pub is_active: Option<bool>,
+    /// Empty string clears it
+    #[serde(default)]
+    #[ts(optional)]
+    pub description: Option<String>,
+    /// User ID; empty string clears it
+    #[serde(default)]
+    #[ts(optional)]
+    pub owner_id: Option<String>,
+    /// Replaces the list
+    #[serde(default)]
+    #[ts(optional)]
+    pub equipment_models: Option<Vec<String>>,
+    /// Replaces the list
+    #[serde(default)]
+    #[ts(optional)]
+    pub tags: Option<Vec<String>>,
+    /// RFC 3339 timestamp; empty string clears it
+    #[serde(default)]
+    #[ts(optional)]
+    pub active_from: Option<String>,
+    /// RFC 3339 timestamp; empty string clears it
+    #[serde(default)]
+    #[ts(optional)]
+    pub active_until: Option<String>,
+}
+
+/// Query parameters for list_issues
+#[derive(Debug, Default, Deserialize)]
+pub struct ListIssuesQuery {
+    /// Only archived (true) or only live (false) issues; all when omitted
+    pub archived: Option<bool>,
+    /// Only issues with this tag
+    pub tag: Option<String>,
+    /// Only issues for this equipment model (ignoring case)
+    pub equipment_model: Option<String>,
+    /// Only issues owned by this user
+    pub owner_id: Option<Uuid>,
 }
 
 /// Query parameters for toggle_issue
@@ -58,6 +148,20 @@ pub struct ToggleIssueQuery {
     pub force: bool,
 }
 
+/// Query parameters for get_issue_graph
+/// Without `limit`/`cursor` the whole graph is returned in one response
+#[derive(Debug, Deserialize)]
+pub struct GraphQueryParams {
+    /// Maximum number of nodes per chunk
+    pub limit: Option<i64>,
+    /// Continuation token from the previous chunk's `next_cursor`
+    pub cursor: Option<String>,
+}
+
+/// Default and maximum node batch sizes for paginated graph requests
+const GRAPH_DEFAULT_CHUNK_SIZE: i64 = 500;
+const GRAPH_MAX_CHUNK_SIZE: i64 = 2000;
+
 // ============================================
 // IMPORT/EXPORT TYPES
 // ============================================
@@ -78,21 +182,59 @@ pub struct IssueExportData {
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct IssueImportMetadata {
+    /// Issue name, as shown on the start question's answer
     pub name: String,
     pub category: String,
     pub display_category: Option<String>,
     pub root_question_text: String,
+    /// Whether the issue is switched on (imported as active when absent)
+    #[serde(default)]
+    #[ts(optional)]
+    pub is_active: Option<bool>,
+    /// Position among the start question's answers (after the existing ones when absent)
+    #[serde(default)]
+    #[ts(optional)]
+    pub start_order_index: Option<i32>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub equipment_models: Option<Vec<String>>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub tags: Option<Vec<String>>,
 }
 
 /// Node data for export (with index references instead of UUIDs)
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Default, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct NodeExportData {
-    pub node_type: String, // "Question" or "Conclusion"
+    pub node_type: String, // "question", "conclusion", "subflow" or "return"
     pub text: String,
     pub semantic_id: Option<String>,
     pub position_x: Option<f64>,
     pub position_y: Option<f64>,
+    #[serde(default)]
+    pub kb_url: Option<String>,
+    #[serde(default)]
+    pub doc_reference: Option<String>,
+    #[serde(default)]
+    pub option_order: OptionOrder,
+    #[serde(default)]
+    pub answer_mode: AnswerMode,
+    /// Index in nodes array of where "I'm not sure" leads (targets in other
+    /// categories are not exported, like answers into them)
+    #[serde(default)]
+    #[ts(optional)]
+    pub not_sure_node_index: Option<usize>,
+    /// The category a sub-flow node runs
+    #[serde(default)]
+    #[ts(optional)]
+    pub subflow_category: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub content: Option<NodeContent>,
 }
 
 /// Connection data for export (with node array indices instead of UUIDs)
@@ -105,6 +247,12 @@ pub struct ConnectionExportData {
     pub to_node_index: usize,
     pub label: String,
     pub order_index: i32,
+    /// When this answer applies (reading range and/or earlier answers)
+    #[serde(default)]
+    pub condition: Option<String>,
+    /// Conservative branch for unsure answers
+    #[serde(default)]
+    pub unsure_route: bool,
 }
 
 /// Result of importing issues
@@ -123,6 +271,79 @@ pub struct ImportSuccess {
     pub name: String,
     pub nodes_count: usize,
     pub connections_count: usize,
+    /// Duplicate connections dropped (only with `duplicates=dedupe`)
+    pub duplicates_removed: usize,
+    /// Nodes of the existing issue that were replaced (only with `mode=overwrite`)
+    pub nodes_replaced: usize,
+    /// Entry points of the replaced issue whose start question has no counterpart
+    /// (same semantic ID) in the import, and were removed with it
+    pub entry_points_dropped: Vec<String>,
+    /// Change history entries of the replaced nodes, removed with them
+    pub revisions_dropped: usize,
+    /// Nodes already in the issue under the same semantic ID, left as they were (only with `mode=merge`)
+    pub nodes_matched: usize,
+    /// IDs of the imported nodes, in import order; `None` for nodes matched by a merge
+    #[serde(skip)]
+    #[ts(skip)]
+    pub node_ids: Vec<Option<Uuid>>,
+}
+
+/// Result of importing a bundle: the issue import plus its attachments
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BundleImportResult {
+    #[serde(flatten)]
+    pub result: ImportResult,
+    /// Attachments stored on the imported nodes
+    pub attachments_count: usize,
+    /// Attachments that were rejected (the issue import stands)
+    pub attachment_errors: Vec<String>,
+}
+
+/// How import treats a category that already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Fail the issue import
+    #[default]
+    Skip,
+    /// Replace the issue's nodes and connections with the imported ones
+    Overwrite,
+    /// Add the nodes (keyed by semantic ID) and connections the issue doesn't have yet;
+    /// nodes without a semantic ID can't be matched and are always added
+    Merge,
+}
+
+/// How import handles connections with identical from/to/label
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateConnectionMode {
+    /// Fail the issue import and list the duplicates
+    #[default]
+    Reject,
+    /// Keep the first connection and drop the rest
+    Dedupe,
+}
+
+/// Query parameters for import_issues
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportIssuesQuery {
+    #[serde(default)]
+    pub duplicates: DuplicateConnectionMode,
+    #[serde(default)]
+    pub mode: ImportMode,
+}
+
+/// Query parameters for import_external
+#[derive(Debug, Deserialize)]
+pub struct ExternalImportQuery {
+    /// Adapter format, e.g. "opml" or "lucidchart-csv"
+    pub format: String,
+    pub category: String,
+    pub name: Option<String>,
+    pub display_category: Option<String>,
+    #[serde(default)]
+    pub duplicates: DuplicateConnectionMode,
 }
 
 /// Error during import
@@ -131,6 +352,84 @@ pub struct ImportSuccess {
 pub struct ImportError {
     pub category: String,
     pub error: String,
+    /// Itemized problems (e.g. each duplicate connection), empty when not applicable
+    pub details: Vec<String>,
+    /// Offending elements, with JSON pointers into the imported array (e.g. `/0/connections/3`)
+    pub fields: Vec<ValidationField>,
+}
+
+/// Format of a single-issue export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    /// Flat rows for spreadsheets (see `utils::issue_csv`)
+    Csv,
+    /// Graphviz diagram of the node graph (see `utils::diagrams`)
+    Dot,
+    /// Mermaid flowchart of the node graph
+    Mermaid,
+    /// ZIP of the JSON export, a manifest and the nodes' attachments (see `utils::issue_bundle`)
+    Zip,
+}
+
+/// Query parameters for export_issue
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportIssueQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Query parameters for incremental export
+#[derive(Debug, Deserialize)]
+pub struct IncrementalExportQuery {
+    /// Only include changes after this RFC 3339 timestamp (omit for a full snapshot)
+    pub since: Option<String>,
+}
+
+/// A hard-deleted node or connection
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct Tombstone {
+    /// "node" or "connection"
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Changes to one issue category since a point in time
+///
+/// Unlike `IssueExportData` this keeps UUIDs so the target can match records;
+/// seed the target with a snapshot (no `since`) before applying increments.
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IncrementalExportData {
+    pub category: String,
+    pub since: Option<String>,
+    /// Database time of the export; pass as `since` on the next sync
+    pub exported_at: String,
+    /// Nodes created or modified after `since` (including deactivated ones)
+    pub nodes: Vec<Node>,
+    /// Connections created or modified after `since`
+    pub connections: Vec<Connection>,
+    /// Records deleted after `since`
+    pub deleted: Vec<Tombstone>,
+}
+
+/// Result of applying an incremental import
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IncrementalImportResult {
+    pub category: String,
+    #[ts(type = "number")]
+    pub nodes_upserted: u64,
+    #[ts(type = "number")]
+    pub connections_upserted: u64,
+    #[ts(type = "number")]
+    pub nodes_deleted: u64,
+    #[ts(type = "number")]
+    pub connections_deleted: u64,
 }
 
 // ============================================
@@ -138,44 +437,138 @@ pub struct ImportError {
 // ============================================
 
 /// GET /api/admin/issues
-/// List all issues (categories with root nodes) - NODE-GRAPH VERSION
-pub async fn list_issues(State(state): State<AppState>) -> ApiResult<Json<Vec<Issue>>> {
-    let issues = sqlx::query!(
+/// An issue as read from the database
+#[derive(sqlx::FromRow)]
+struct IssueRow {
+    id: Uuid,
+    category: String,
+    name: String,
+    display_category: Option<String>,
+    is_active: Option<bool>,
+    question_count: i64,
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    description: Option<String>,
+    owner_id: Option<Uuid>,
+    owner_email: Option<String>,
+    equipment_models: Vec<String>,
+    tags: Vec<String>,
+    active_from: Option<chrono::DateTime<chrono::Utc>>,
+    active_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<IssueRow> for Issue {
+    fn from(row: IssueRow) -> Self {
+        Issue {
+            id: row.id.to_string(),
+            name: row.name,
+            category: row.category,
+            display_category: row.display_category,
+            root_question_id: row.id.to_string(),
+            is_active: row.is_active.unwrap_or(true),
+            question_count: row.question_count,
+            created_at: row.created_at.unwrap_or_else(chrono::Utc::now).to_rfc3339(),
+            updated_at: row.updated_at.unwrap_or_else(chrono::Utc::now).to_rfc3339(),
+            archived_at: row.archived_at.map(|at| at.to_rfc3339()),
+            description: row.description,
+            owner_id: row.owner_id,
+            owner_email: row.owner_email,
+            equipment_models: row.equipment_models,
+            tags: row.tags,
+            active_from: row.active_from.map(|at| at.to_rfc3339()),
+            active_until: row.active_until.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+/// Issues (one per category, described by its first node), optionally just `category`'s
+///
+/// Issues created before metadata was kept are named by the start
+/// question's answer leading to them.
+async fn fetch_issues(state: &AppState, category: Option<&str>, query: &ListIssuesQuery) -> ApiResult<Vec<Issue>> {
+    let rows = sqlx::query_as::<_, IssueRow>(
         r#"
         SELECT DISTINCT ON (n.category)
             n.id,
-            COALESCE(n.category, 'uncategorized') as "category!",
-            COALESCE(c.label, n.category, 'Uncategorized') as "name!",
+            n.category,
+            COALESCE(i.name, c.label, n.category) as name,
             n.display_category,
-            n.id as root_node_id,
             n.is_active,
+            (SELECT COUNT(*) FROM nodes n2 WHERE n2.category = n.category) as question_count,
             n.created_at,
             n.updated_at,
-            (SELECT COUNT(*) FROM nodes n2 WHERE n2.category = n.category OR (n2.category IS NULL AND n.category IS NULL)) as "question_count!"
+            a.archived_at,
+            i.description,
+            i.owner_id,
+            u.email as owner_email,
+            COALESCE(i.equipment_models, '{}') as equipment_models,
+            COALESCE(i.tags, '{}') as tags,
+            i.active_from,
+            i.active_until
         FROM nodes n
         LEFT JOIN connections c ON c.to_node_id = n.id AND c.from_node_id = (SELECT id FROM nodes WHERE semantic_id = 'start' LIMIT 1)
+        LEFT JOIN issue_archives a ON a.category = n.category
+        LEFT JOIN issues i ON i.category = n.category
+        LEFT JOIN users u ON u.id = i.owner_id
+        WHERE n.category NOT IN (SELECT draft_category FROM issue_drafts)
+          AND ($1::varchar IS NULL OR n.category = $1)
+          AND ($2::bool IS NULL OR (a.category IS NOT NULL) = $2)
+          AND ($3::varchar IS NULL OR $3 = ANY(i.tags))
+          AND ($4::varchar IS NULL OR EXISTS (SELECT 1 FROM unnest(i.equipment_models) m WHERE lower(m) = lower($4)))
+          AND ($5::uuid IS NULL OR i.owner_id = $5)
         ORDER BY n.category, n.created_at ASC
-        "#
+        "#,
     )
+    .bind(category)
+    .bind(query.archived)
+    .bind(query.tag.as_deref().map(|tag| tag.trim().to_lowercase()))
+    .bind(query.equipment_model.as_deref().map(str::trim))
+    .bind(query.owner_id)
     .fetch_all(&state.db)
     .await?;
 
-    let issue_list = issues
-        .into_iter()
-        .map(|row| Issue {
-            id: row.id.to_string(),
-            name: row.name,
-            category: row.category,
-            display_category: row.display_category,
-            root_question_id: row.root_node_id.to_string(),
-            is_active: row.is_active.unwrap_or(true),
-            question_count: row.question_count,
-            created_at: row.created_at.unwrap_or_else(chrono::Utc::now).to_rfc3339(),
-            updated_at: row.updated_at.unwrap_or_else(chrono::Utc::now).to_rfc3339(),
-        })
-        .collect();
+    Ok(rows.into_iter().map(Issue::from).collect())
+}
+
+/// One issue by category
+async fn load_issue(state: &AppState, category: &str) -> ApiResult<Issue> {
+    fetch_issues(state, Some(category), &ListIssuesQuery::default())
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::not_found("Issue not found"))
+}
+
+/// A schedule boundary from an update: None when not given, Some(None) when cleared
+fn parse_schedule_time(field: &str, value: Option<&str>) -> ApiResult<Option<Option<chrono::DateTime<chrono::Utc>>>> {
+    match value.map(str::trim) {
+        None => Ok(None),
+        Some("") => Ok(Some(None)),
+        Some(value) => chrono::DateTime::parse_from_rfc3339(value)
+            .map(|at| Some(Some(at.with_timezone(&chrono::Utc))))
+            .map_err(|_| ApiError::validation(vec![(field.to_string(), "Not a valid RFC 3339 timestamp".to_string())])),
+    }
+}
+
+/// Check an issue owner exists, reporting errors under `field`
+async fn ensure_owner_exists(state: &AppState, field: &str, owner_id: Uuid) -> ApiResult<()> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+        .bind(owner_id)
+        .fetch_one(&state.db)
+        .await?;
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::validation(vec![(field.to_string(), "No user with this ID".to_string())]))
+    }
+}
 
-    Ok(Json(issue_list))
+/// List all issues (categories with root nodes) - NODE-GRAPH VERSION
+pub async fn list_issues(
+    State(state): State<AppState>,
+    Query(query): Query<ListIssuesQuery>,
+) -> ApiResult<Json<Vec<Issue>>> {
+    Ok(Json(fetch_issues(&state, None, &query).await?))
 }
 
 /// GET /api/admin/issues/:category/graph
@@ -183,24 +576,40 @@ pub async fn list_issues(State(state): State<AppState>) -> ApiResult<Json<Vec<Is
 pub async fn get_issue_graph(
     State(state): State<AppState>,
     Path(category): Path<String>,
+    Query(params): Query<GraphQueryParams>,
 ) -> ApiResult<Json<IssueGraph>> {
+    // Chunked mode for very large categories (the editor stitches chunks together)
+    if params.limit.is_some() || params.cursor.is_some() {
+        let limit = params
+            .limit
+            .unwrap_or(GRAPH_DEFAULT_CHUNK_SIZE)
+            .clamp(1, GRAPH_MAX_CHUNK_SIZE);
+        let graph = get_issue_graph_chunk(&state, &category, limit, params.cursor.as_deref()).await?;
+        return Ok(Json(graph));
+    }
+
+    Ok(Json(load_issue_graph(&state, &category).await?))
+}
+
+/// Full graph for a category, served from the graph cache when possible
+pub(crate) async fn load_issue_graph(state: &AppState, category: &str) -> ApiResult<IssueGraph> {
     // Try to get from cache first
     let cache_key = format!("graph_{}", category);
     if let Some(cached) = state.issue_graph_cache.get(&cache_key).await {
         tracing::debug!("✅ Cache HIT: issue graph for {}", category);
-        return Ok(Json(serde_json::from_value(cached)?));
+        return Ok(serde_json::from_value(cached)?);
     }
 
     tracing::debug!("❌ Cache MISS: issue graph for {} - fetching from DB", category);
 
     // Get all active nodes in this category
     let nodes = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE category = $1 AND is_active = true
          ORDER BY created_at ASC"
     )
-    .bind(&category)
+    .bind(category)
     .fetch_all(&state.db)
     .await?;
 
@@ -213,7 +622,7 @@ pub async fn get_issue_graph(
 
     // Get all active connections between these nodes
     let connections = sqlx::query_as::<_, Connection>(
-        "SELECT id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at
+        "SELECT id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at
          FROM connections
          WHERE from_node_id = ANY($1) AND is_active = true
          ORDER BY order_index ASC"
@@ -223,15 +632,96 @@ pub async fn get_issue_graph(
     .await?;
 
     let result = IssueGraph {
-        category: category.clone(),
+        category: category.to_string(),
         nodes,
         connections,
+        next_cursor: None,
     };
 
     // Store in cache
     state.issue_graph_cache.set(cache_key, serde_json::to_value(&result)?).await;
 
-    Ok(Json(result))
+    Ok(result)
+}
+
+/// Encode a graph continuation token from the last node of a chunk
+fn encode_graph_cursor(node: &Node) -> String {
+    format!("{}.{}", node.created_at.timestamp_micros(), node.id)
+}
+
+/// Decode a graph continuation token into its (created_at, id) position
+fn decode_graph_cursor(cursor: &str) -> Option<(chrono::DateTime<chrono::Utc>, Uuid)> {
+    let (micros, id) = cursor.split_once('.')?;
+    let created_at = chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+    Some((created_at, Uuid::parse_str(id).ok()?))
+}
+
+/// Fetch one chunk of an issue graph: a batch of nodes (ordered by creation)
+/// plus the connections leaving those nodes. Not cached.
+async fn get_issue_graph_chunk(
+    state: &AppState,
+    category: &str,
+    limit: i64,
+    cursor: Option<&str>,
+) -> ApiResult<IssueGraph> {
+    let position = match cursor {
+        Some(cursor) => Some(decode_graph_cursor(cursor).ok_or_else(|| {
+            ApiError::validation(vec![("cursor".to_string(), "Invalid continuation token".to_string())])
+        })?),
+        None => None,
+    };
+
+    use sqlx::QueryBuilder;
+    let mut query = QueryBuilder::new(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE is_active = true AND category = "
+    );
+    query.push_bind(category);
+
+    if let Some((created_at, id)) = position {
+        query.push(" AND (created_at, id) > (");
+        query.push_bind(created_at);
+        query.push(", ");
+        query.push_bind(id);
+        query.push(")");
+    }
+
+    // Fetch one extra row to know whether another chunk follows
+    query.push(" ORDER BY created_at ASC, id ASC LIMIT ");
+    query.push_bind(limit + 1);
+
+    let mut nodes = query.build_query_as::<Node>().fetch_all(&state.db).await?;
+
+    if nodes.is_empty() && position.is_none() {
+        return Err(ApiError::not_found("Issue category not found"));
+    }
+
+    let next_cursor = if nodes.len() as i64 > limit {
+        nodes.truncate(limit as usize);
+        nodes.last().map(encode_graph_cursor)
+    } else {
+        None
+    };
+
+    let node_ids: Vec<Uuid> = nodes.iter().map(|n| n.id).collect();
+
+    let connections = sqlx::query_as::<_, Connection>(
+        "SELECT id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at
+         FROM connections
+         WHERE from_node_id = ANY($1) AND is_active = true
+         ORDER BY order_index ASC"
+    )
+    .bind(&node_ids)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(IssueGraph {
+        category: category.to_string(),
+        nodes,
+        connections,
+        next_cursor,
+    })
 }
 
 /// POST /api/admin/issues
@@ -242,6 +732,23 @@ pub async fn create_issue(
     headers: HeaderMap,
     Json(req): Json<CreateIssueRequest>,
 ) -> ApiResult<Json<Issue>> {
+    category_access::ensure_can_edit(&state.db, &auth, [req.category.as_str()]).await?;
+
+    let name = issue_metadata::normalize_name(&req.name).map_err(|e| ApiError::validation(vec![("name".to_string(), e)]))?;
+    let description = issue_metadata::normalize_description(req.description.as_deref())
+        .map_err(|e| ApiError::validation(vec![("description".to_string(), e)]))?;
+    let equipment_models = issue_metadata::normalize_equipment_models(req.equipment_models.as_deref().unwrap_or_default())
+        .map_err(|e| ApiError::validation(vec![("equipment_models".to_string(), e)]))?;
+    let tags = issue_metadata::normalize_tags(req.tags.as_deref().unwrap_or_default())
+        .map_err(|e| ApiError::validation(vec![("tags".to_string(), e)]))?;
+    if let Some(owner_id) = req.owner_id {
+        ensure_owner_exists(&state, "owner_id", owner_id).await?;
+    }
+    let schedule_errors = issue_schedules::validate(req.active_from, req.active_until);
+    if !schedule_errors.is_empty() {
+        return Err(ApiError::validation(schedule_errors));
+    }
+
     // Start a transaction for atomicity and use a single optimized query
     let mut tx = state.db.begin().await?;
 
@@ -267,7 +774,7 @@ pub async fn create_issue(
     let node = sqlx::query_as::<_, Node>(
         "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, is_active)
          VALUES ($1, $2, 'question', $3, $4, $5, false)
-         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at"
+         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at"
     )
     .bind(node_id)
     .bind(&req.category)
@@ -292,8 +799,27 @@ pub async fn create_issue(
         WHERE n.semantic_id = 'start'
         "#,
         node_id,
-        &req.name
+        &name
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO issues (category, name, description, owner_id, equipment_models, tags, active_from, active_until)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         ON CONFLICT (category) DO UPDATE SET
+            name = EXCLUDED.name, description = EXCLUDED.description, owner_id = EXCLUDED.owner_id,
+            equipment_models = EXCLUDED.equipment_models, tags = EXCLUDED.tags,
+            active_from = EXCLUDED.active_from, active_until = EXCLUDED.active_until, schedule_applied = NULL",
     )
+    .bind(&req.category)
+    .bind(&name)
+    .bind(&description)
+    .bind(req.owner_id)
+    .bind(&equipment_models)
+    .bind(&tags)
+    .bind(req.active_from)
+    .bind(req.active_until)
     .execute(&mut *tx)
     .await?;
 
@@ -312,25 +838,20 @@ pub async fn create_issue(
         "issue",
         Some(&req.category),
         Some(json!({
-            "name": &req.name,
+            "name": &name,
             "display_category": &node.display_category,
             "root_question_text": &req.root_question_text,
+            "owner_id": req.owner_id,
+            "equipment_models": &equipment_models,
+            "tags": &tags,
+            "active_from": req.active_from,
+            "active_until": req.active_until,
         })),
         ip.as_deref(),
     )
     .await?;
 
-    Ok(Json(Issue {
-        id: node.id.to_string(),
-        name: req.name,
-        category: req.category,
-        display_category: node.display_category,
-        root_question_id: node.id.to_string(),
-        is_active: node.is_active,
-        question_count: 1,
-        created_at: node.created_at.to_rfc3339(),
-        updated_at: node.updated_at.to_rfc3339(),
-    }))
+    Ok(Json(load_issue(&state, &req.category).await?))
 }
 
 /// PUT /api/admin/issues/:category
@@ -342,9 +863,61 @@ pub async fn update_issue(
     Path(category): Path<String>,
     Json(req): Json<UpdateIssueRequest>,
 ) -> ApiResult<Json<Issue>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let name = req
+        .name
+        .as_deref()
+        .map(issue_metadata::normalize_name)
+        .transpose()
+        .map_err(|e| ApiError::validation(vec![("name".to_string(), e)]))?;
+    let description = req
+        .description
+        .as_deref()
+        .map(|description| issue_metadata::normalize_description(Some(description)))
+        .transpose()
+        .map_err(|e| ApiError::validation(vec![("description".to_string(), e)]))?;
+    let equipment_models = req
+        .equipment_models
+        .as_deref()
+        .map(issue_metadata::normalize_equipment_models)
+        .transpose()
+        .map_err(|e| ApiError::validation(vec![("equipment_models".to_string(), e)]))?;
+    let tags = req
+        .tags
+        .as_deref()
+        .map(issue_metadata::normalize_tags)
+        .transpose()
+        .map_err(|e| ApiError::validation(vec![("tags".to_string(), e)]))?;
+    let owner_id = match req.owner_id.as_deref().map(str::trim) {
+        None => None,
+        Some("") => Some(None),
+        Some(owner_id) => {
+            let owner_id = Uuid::parse_str(owner_id)
+                .map_err(|_| ApiError::validation(vec![("owner_id".to_string(), "Not a valid user ID".to_string())]))?;
+            ensure_owner_exists(&state, "owner_id", owner_id).await?;
+            Some(Some(owner_id))
+        }
+    };
+    let active_from = parse_schedule_time("active_from", req.active_from.as_deref())?;
+    let active_until = parse_schedule_time("active_until", req.active_until.as_deref())?;
+    if active_from.is_some() || active_until.is_some() {
+        let (current_from, current_until) = sqlx::query_as::<_, (Option<chrono::DateTime<chrono::Utc>>, Option<chrono::DateTime<chrono::Utc>>)>(
+            "SELECT active_from, active_until FROM issues WHERE category = $1",
+        )
+        .bind(&category)
+        .fetch_optional(&state.db)
+        .await?
+        .unwrap_or_default();
+        let schedule_errors = issue_schedules::validate(active_from.unwrap_or(current_from), active_until.unwrap_or(current_until));
+        if !schedule_errors.is_empty() {
+            return Err(ApiError::validation(schedule_errors));
+        }
+    }
+
     // Check if issue exists
-    let mut node = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+    let node = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE category = $1
          ORDER BY created_at ASC
@@ -355,10 +928,8 @@ pub async fn update_issue(
     .await?
     .ok_or_else(|| ApiError::not_found("Issue not found"))?;
 
-    // Variable to track the updated name
-    let updated_name = if let Some(name) = &req.name {
-        // Update the connection label (where the issue name is actually stored)
-        // The connection goes from the 'start' node to this issue's root node
+    // Technicians see the name on the start question's answer leading to the issue
+    let current_name = if let Some(name) = &name {
         sqlx::query!(
             r#"
             UPDATE connections
@@ -388,6 +959,39 @@ pub async fn update_issue(
         conn.map(|c| c.label).unwrap_or_else(|| category.clone())
     };
 
+    // Issues created before metadata was kept get their row on first update
+    sqlx::query("INSERT INTO issues (category, name) VALUES ($1, $2) ON CONFLICT (category) DO NOTHING")
+        .bind(&category)
+        .bind(&current_name)
+        .execute(&state.db)
+        .await?;
+    sqlx::query(
+        "UPDATE issues SET
+            name = COALESCE($2, name),
+            description = CASE WHEN $3 THEN $4 ELSE description END,
+            owner_id = CASE WHEN $5 THEN $6 ELSE owner_id END,
+            equipment_models = COALESCE($7, equipment_models),
+            tags = COALESCE($8, tags),
+            active_from = CASE WHEN $9 THEN $10 ELSE active_from END,
+            active_until = CASE WHEN $11 THEN $12 ELSE active_until END,
+            schedule_applied = CASE WHEN $9 OR $11 THEN NULL ELSE schedule_applied END
+         WHERE category = $1",
+    )
+    .bind(&category)
+    .bind(&name)
+    .bind(description.is_some())
+    .bind(description.clone().flatten())
+    .bind(owner_id.is_some())
+    .bind(owner_id.flatten())
+    .bind(&equipment_models)
+    .bind(&tags)
+    .bind(active_from.is_some())
+    .bind(active_from.flatten())
+    .bind(active_until.is_some())
+    .bind(active_until.flatten())
+    .execute(&state.db)
+    .await?;
+
     // Update display_category if provided
     if let Some(display_category) = &req.display_category {
         // Update all nodes in this category
@@ -398,7 +1002,6 @@ pub async fn update_issue(
         )
         .execute(&state.db)
         .await?;
-        node.display_category = Some(display_category.clone());
     }
 
     // Update is_active status if provided
@@ -411,16 +1014,9 @@ pub async fn update_issue(
         )
         .execute(&state.db)
         .await?;
-        node.is_active = is_active;
     }
 
-    // Get updated count
-    let count = sqlx::query!(
-        "SELECT COUNT(*) as count FROM nodes WHERE category = $1",
-        &category
-    )
-    .fetch_one(&state.db)
-    .await?;
+    state.invalidate_issue(&category).await;
 
     // Audit log the issue update
     let user_id = Uuid::parse_str(&auth.0.sub)
@@ -434,25 +1030,31 @@ pub async fn update_issue(
         "issue",
         Some(&category),
         Some(json!({
-            "name": req.name,
+            "name": name,
             "display_category": req.display_category,
             "is_active": req.is_active,
+            "description_changed": description.is_some(),
+            "owner_id": owner_id,
+            "equipment_models": equipment_models,
+            "tags": tags,
+            "active_from": active_from,
+            "active_until": active_until,
         })),
         ip.as_deref(),
     )
     .await?;
 
-    Ok(Json(Issue {
-        id: node.id.to_string(),
-        name: updated_name,
-        category: category.clone(),
-        display_category: node.display_category,
-        root_question_id: node.id.to_string(),
-        is_active: node.is_active,
-        question_count: count.count.unwrap_or(0),
-        created_at: node.created_at.to_rfc3339(),
-        updated_at: node.updated_at.to_rfc3339(),
-    }))
+    Ok(Json(load_issue(&state, &category).await?))
+}
+
+/// GET /api/admin/issues/:category/validate
+/// Check an issue's graph without changing it; errors stop it being
+/// activated, warnings point at likely mistakes
+pub async fn validate_issue(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> ApiResult<Json<GraphValidationReport>> {
+    engine::validate(&state, &category).await.map(Json)
 }
 
 /// PATCH /api/admin/issues/:category/toggle
@@ -464,9 +1066,11 @@ pub async fn toggle_issue(
     Path(category): Path<String>,
     Query(query): Query<ToggleIssueQuery>,
 ) -> ApiResult<Json<Issue>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
     // Get current status and root node
     let node = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE category = $1
          ORDER BY created_at ASC
@@ -479,71 +1083,15 @@ pub async fn toggle_issue(
 
     let new_status = !node.is_active;
 
-    // If activating (turning on) and not forced, validate for incomplete nodes
+    // If activating (turning on) and not forced, check the whole graph first
     if new_status && !query.force {
-        // Find all Question nodes in this category that have no outgoing connections
-        let incomplete_nodes = sqlx::query!(
-            r#"
-            SELECT n.id, n.text, n.semantic_id
-            FROM nodes n
-            WHERE n.category = $1
-            AND n.node_type = 'Question'
-            AND NOT EXISTS (
-                SELECT 1 FROM connections c
-                WHERE c.from_node_id = n.id
-            )
-            "#,
-            &category
-        )
-        .fetch_all(&state.db)
-        .await?;
-
-        if !incomplete_nodes.is_empty() {
-            // Build error message with node details
-            let node_details: Vec<String> = incomplete_nodes
-                .iter()
-                .map(|n| {
-                    format!("{} ({})", n.text, n.semantic_id.as_ref().unwrap_or(&"no ID".to_string()))
-                })
-                .collect();
-
-            return Err(ApiError::validation(vec![(
-                "incomplete_nodes".to_string(),
-                format!(
-                    "This issue has {} end node(s) with no conclusion: {}. These nodes need outgoing connections or should be changed to Conclusion type.",
-                    incomplete_nodes.len(),
-                    node_details.join(", ")
-                ),
-            )]));
+        let report = engine::validate(&state, &category).await?;
+        if !report.is_valid() {
+            return Err(ApiError::invalid_graph(report));
         }
     }
 
-    // Toggle all nodes in this category
-    sqlx::query!(
-        "UPDATE nodes SET is_active = $1 WHERE category = $2",
-        new_status,
-        &category
-    )
-    .execute(&state.db)
-    .await?;
-
-    // IMPORTANT: Also toggle any connections that point to this category's root node
-    // This ensures that when you toggle "Brush" off, the "Brush" connection in the root node also gets disabled
-    sqlx::query!(
-        "UPDATE connections SET is_active = $1 WHERE to_node_id = $2",
-        new_status,
-        node.id
-    )
-    .execute(&state.db)
-    .await?;
-
-    // Get count
-    let count = sqlx::query!(
-        "SELECT COUNT(*) as count FROM nodes WHERE category = $1",
-        &category
-    )
-    .fetch_one(&state.db)
-    .await?;
+    set_issue_active(&state, &category, node.id, new_status).await?;
 
     // Audit log the issue toggle
     let user_id = Uuid::parse_str(&auth.0.sub)
@@ -564,83 +1112,449 @@ pub async fn toggle_issue(
     )
     .await?;
 
-    Ok(Json(Issue {
-        id: node.id.to_string(),
-        name: category.clone(),
-        category: category.clone(),
-        display_category: node.display_category,
-        root_question_id: node.id.to_string(),
-        is_active: new_status,
-        question_count: count.count.unwrap_or(0),
-        created_at: node.created_at.to_rfc3339(),
-        updated_at: node.updated_at.to_rfc3339(),
-    }))
-}
-
-/// Query parameters for delete issue endpoint
-#[derive(Debug, serde::Deserialize)]
-pub struct DeleteIssueParams {
-    #[serde(default)]
-    pub delete_sessions: bool,
+    Ok(Json(load_issue(&state, &category).await?))
 }
 
-/// DELETE /api/admin/issues/:category
-/// Delete entire issue and all its nodes/connections (NODE-GRAPH VERSION)
-pub async fn delete_issue(
-    State(state): State<AppState>,
-    Extension(auth): Extension<AuthUser>,
-    headers: HeaderMap,
-    Path(category): Path<String>,
-    Query(params): Query<DeleteIssueParams>,
-) -> ApiResult<Json<serde_json::Value>> {
-    // Check if issue exists
-    let count = sqlx::query!(
-        "SELECT COUNT(*) as count FROM nodes WHERE category = $1",
-        &category
+/// Set every node in a category active or inactive
+pub(crate) async fn set_issue_active(state: &AppState, category: &str, root_id: Uuid, is_active: bool) -> ApiResult<()> {
+    sqlx::query!(
+        "UPDATE nodes SET is_active = $1 WHERE category = $2",
+        is_active,
+        category
     )
-    .fetch_one(&state.db)
+    .execute(&state.db)
     .await?;
 
-    if count.count.unwrap_or(0) == 0 {
-        return Err(ApiError::not_found("Issue not found"));
-    }
-
-    // Delete all connections for nodes in this category
-    // (Note: cascade delete will handle this automatically if FK constraints are set up,
-    // but doing it explicitly for clarity)
+    // IMPORTANT: Also toggle any connections that point to this category's root node
+    // This ensures that when you toggle "Brush" off, the "Brush" connection in the root node also gets disabled
     sqlx::query!(
-        "DELETE FROM connections WHERE from_node_id IN (SELECT id FROM nodes WHERE category = $1)",
-        &category
+        "UPDATE connections SET is_active = $1 WHERE to_node_id = $2",
+        is_active,
+        root_id
     )
     .execute(&state.db)
     .await?;
 
-    // Delete all nodes in this category
-    let result = sqlx::query!(
-        "DELETE FROM nodes WHERE category = $1",
-        &category
+    state.invalidate_issue(category).await;
+
+    Ok(())
+}
+
+/// Most categories accepted by one bulk request
+const BULK_MAX_CATEGORIES: usize = 100;
+
+/// What a bulk request does to each listed issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum BulkIssueAction {
+    /// Enable or disable, as given by `is_active`
+    Toggle,
+    Delete,
+    Archive,
+    Unarchive,
+}
+
+impl BulkIssueAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            BulkIssueAction::Toggle => "toggle",
+            BulkIssueAction::Delete => "delete",
+            BulkIssueAction::Archive => "archive",
+            BulkIssueAction::Unarchive => "unarchive",
+        }
+    }
+}
+
+/// Request to apply one action to several issues
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BulkIssueRequest {
+    pub action: BulkIssueAction,
+    pub categories: Vec<String>,
+    /// Target state for `toggle`
+    #[serde(default)]
+    #[ts(optional)]
+    pub is_active: Option<bool>,
+    /// Skip the graph validation when enabling
+    #[serde(default)]
+    pub force: bool,
+    /// Also delete the issues' sessions (`delete` only)
+    #[serde(default)]
+    pub delete_sessions: bool,
+}
+
+/// Request to enable or disable several issues at once
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BulkToggleRequest {
+    pub categories: Vec<String>,
+    /// Target state for every listed category
+    pub is_active: bool,
+    /// Skip the graph validation when enabling
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Outcome for one category in a bulk request
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum BulkIssueStatus {
+    Updated,
+    /// Already in the requested state
+    Unchanged,
+    Failed,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BulkIssueItem {
+    pub category: String,
+    pub status: BulkIssueStatus,
+    pub error: Option<String>,
+    /// Graph problems that stopped the issue being enabled
+    pub problems: Vec<GraphProblem>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BulkIssueResult {
+    pub action: BulkIssueAction,
+    pub updated: usize,
+    pub failed: usize,
+    pub results: Vec<BulkIssueItem>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BulkToggleResult {
+    pub is_active: bool,
+    pub updated: usize,
+    pub failed: usize,
+    pub results: Vec<BulkIssueItem>,
+}
+
+/// Enable or disable one issue; false when it already was
+///
+/// Enabling checks the graph first unless `force` is set.
+async fn toggle_category(state: &AppState, category: &str, is_active: bool, force: bool) -> ApiResult<bool> {
+    let (root_id, current_status) = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT id, is_active FROM nodes WHERE category = $1 ORDER BY created_at ASC LIMIT 1"
     )
-    .execute(&state.db)
+    .bind(category)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Issue not found"))?;
+
+    if current_status == is_active {
+        return Ok(false);
+    }
+
+    if is_active && !force {
+        if let Some(report) = graph_validation::validate_category(&state.db, category).await? {
+            if !report.is_valid() {
+                return Err(ApiError::invalid_graph(report));
+            }
+        }
+    }
+
+    set_issue_active(state, category, root_id, is_active).await?;
+    Ok(true)
+}
+
+/// Apply a bulk request's action to one issue (already checked for access); false when nothing changed
+async fn apply_bulk_action(
+    state: &AppState,
+    category: &str,
+    req: &BulkIssueRequest,
+    user_id: Uuid,
+    ip: Option<&str>,
+) -> ApiResult<bool> {
+    let (action, details) = match req.action {
+        BulkIssueAction::Toggle => {
+            let is_active = req.is_active.unwrap_or_default();
+            if !toggle_category(state, category, is_active, req.force).await? {
+                return Ok(false);
+            }
+            (
+                audit::AuditAction::IssueToggled,
+                json!({ "new_status": is_active, "forced": req.force, "bulk": true }),
+            )
+        }
+        BulkIssueAction::Delete => {
+            let (nodes_deleted, sessions_deleted) = delete_category(state, category, req.delete_sessions).await?;
+            (
+                audit::AuditAction::IssueDeleted,
+                json!({
+                    "nodes_deleted": nodes_deleted,
+                    "sessions_deleted": sessions_deleted,
+                    "delete_sessions": req.delete_sessions,
+                    "bulk": true,
+                }),
+            )
+        }
+        BulkIssueAction::Archive => {
+            if archive_category(state, category, user_id).await?.is_none() {
+                return Ok(false);
+            }
+            (audit::AuditAction::IssueArchived, json!({ "bulk": true }))
+        }
+        BulkIssueAction::Unarchive => {
+            let Some(archived_at) = unarchive_category(state, category).await? else {
+                return Ok(false);
+            };
+            (
+                audit::AuditAction::IssueUnarchived,
+                json!({ "archived_at": archived_at, "bulk": true }),
+            )
+        }
+    };
+
+    audit::log_event(&state.db, user_id, action, "issue", Some(category), Some(details), ip).await?;
+    Ok(true)
+}
+
+/// Apply a bulk request to each listed issue in turn
+///
+/// A problem with one issue (no access, not found, a graph that doesn't
+/// validate) is reported against it and the rest carry on; database errors
+/// abort the request.
+async fn run_bulk(
+    state: &AppState,
+    auth: &AuthUser,
+    headers: &HeaderMap,
+    req: &BulkIssueRequest,
+) -> ApiResult<Vec<BulkIssueItem>> {
+    let mut categories: Vec<String> = Vec::new();
+    for category in &req.categories {
+        let category = category.trim();
+        if !category.is_empty() && !categories.iter().any(|c| c == category) {
+            categories.push(category.to_string());
+        }
+    }
+
+    if categories.is_empty() {
+        return Err(ApiError::validation_at(vec![(
+            "/categories".to_string(),
+            "At least one category is required".to_string(),
+        )]));
+    }
+    if categories.len() > BULK_MAX_CATEGORIES {
+        return Err(ApiError::validation_at(vec![(
+            "/categories".to_string(),
+            format!("At most {} categories can be changed at once", BULK_MAX_CATEGORIES),
+        )]));
+    }
+    if req.action == BulkIssueAction::Toggle && req.is_active.is_none() {
+        return Err(ApiError::validation_at(vec![(
+            "/is_active".to_string(),
+            "Required to toggle issues".to_string(),
+        )]));
+    }
+
+    let user_id = acting_user(auth)?;
+    let ip = audit::extract_ip_address(headers);
+
+    let denied: Vec<String> = category_access::denied_categories(&state.db, auth, categories.iter().map(String::as_str))
+        .await?
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut results = Vec::with_capacity(categories.len());
+
+    for category in categories {
+        let outcome = if denied.contains(&category) {
+            Err(ApiError::forbidden("You are not allowed to edit this category"))
+        } else {
+            apply_bulk_action(state, &category, req, user_id, ip.as_deref()).await
+        };
+
+        let (status, error, problems) = match outcome {
+            Ok(true) => (BulkIssueStatus::Updated, None, vec![]),
+            Ok(false) => (BulkIssueStatus::Unchanged, None, vec![]),
+            Err(e @ (ApiError::DatabaseError { .. } | ApiError::InternalError { .. })) => return Err(e),
+            Err(ApiError::InvalidGraph { message, report }) => (BulkIssueStatus::Failed, Some(message), report.problems),
+            Err(e) => (BulkIssueStatus::Failed, Some(e.message()), vec![]),
+        };
+        results.push(BulkIssueItem { category, status, error, problems });
+    }
+
+    let updated = results.iter().filter(|r| matches!(r.status, BulkIssueStatus::Updated)).count();
+    let failed = results.iter().filter(|r| matches!(r.status, BulkIssueStatus::Failed)).count();
+
+    if updated > 0 {
+        cache_warming::schedule(state.clone(), "bulk issue change");
+    }
+
+    tracing::info!("🔀 Bulk {}: {} updated, {} failed", req.action.as_str(), updated, failed);
+
+    Ok(results)
+}
+
+/// POST /api/admin/issues/bulk
+/// Toggle, delete, archive or unarchive several issues, with a result per category
+pub async fn bulk_update_issues(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    scopes: Option<Extension<ServiceScopes>>,
+    headers: HeaderMap,
+    Json(req): Json<BulkIssueRequest>,
+) -> ApiResult<Json<BulkIssueResult>> {
+    // Checked up front so the request is refused whole rather than per category
+    ensure_can_delete_sessions(&auth, scopes.as_deref(), req.delete_sessions)?;
+    let results = run_bulk(&state, &auth, &headers, &req).await?;
+
+    Ok(Json(BulkIssueResult {
+        action: req.action,
+        updated: results.iter().filter(|r| matches!(r.status, BulkIssueStatus::Updated)).count(),
+        failed: results.iter().filter(|r| matches!(r.status, BulkIssueStatus::Failed)).count(),
+        results,
+    }))
+}
+
+/// PATCH /api/admin/issues/bulk-toggle
+/// Enable or disable several issues, validating each category once
+pub async fn bulk_toggle_issues(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<BulkToggleRequest>,
+) -> ApiResult<Json<BulkToggleResult>> {
+    let bulk = BulkIssueRequest {
+        action: BulkIssueAction::Toggle,
+        categories: req.categories,
+        is_active: Some(req.is_active),
+        force: req.force,
+        delete_sessions: false,
+    };
+    let results = run_bulk(&state, &auth, &headers, &bulk).await?;
+
+    Ok(Json(BulkToggleResult {
+        is_active: req.is_active,
+        updated: results.iter().filter(|r| matches!(r.status, BulkIssueStatus::Updated)).count(),
+        failed: results.iter().filter(|r| matches!(r.status, BulkIssueStatus::Failed)).count(),
+        results,
+    }))
+}
+
+/// Query parameters for delete issue endpoint
+#[derive(Debug, serde::Deserialize)]
+pub struct DeleteIssueParams {
+    #[serde(default)]
+    pub delete_sessions: bool,
+}
+
+/// Deleting an issue's sessions with it also needs `sessions:delete`
+fn ensure_can_delete_sessions(auth: &AuthUser, scopes: Option<&ServiceScopes>, delete_sessions: bool) -> ApiResult<()> {
+    if delete_sessions {
+        ensure_permission(auth, scopes, Permission::SessionsDelete)?;
+    }
+    Ok(())
+}
+
+/// Delete an issue's nodes and connections and everything kept alongside them
+///
+/// All database rows go in one transaction; attachment files are removed
+/// once it has committed. Returns how many nodes and (with
+/// `delete_sessions`) sessions went.
+async fn delete_category(state: &AppState, category: &str, delete_sessions: bool) -> ApiResult<(u64, u64)> {
+    let mut tx = state.db.begin().await?;
+
+    // Check if issue exists
+    let count = sqlx::query!(
+        "SELECT COUNT(*) as count FROM nodes WHERE category = $1",
+        category
+    )
+    .fetch_one(&mut *tx)
     .await?;
 
-    let nodes_deleted = result.rows_affected();
+    if count.count.unwrap_or(0) == 0 {
+        return Err(ApiError::not_found("Issue not found"));
+    }
 
-    // Optionally delete all sessions associated with this category
-    let sessions_deleted = if params.delete_sessions {
-        let sessions_result = sqlx::query(
-            "DELETE FROM sessions WHERE (steps->0->>'category')::text = $1"
-        )
-        .bind(&category)
-        .execute(&state.db)
+    // The issue's metadata, draft, approvals, versions and archived state go with it; deleting a draft category unregisters it
+    let draft_node_ids = issue_drafts::discard(&mut tx, category).await?.unwrap_or_default();
+    sqlx::query("DELETE FROM issue_drafts WHERE draft_category = $1")
+        .bind(category)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM issue_versions WHERE category = $1")
+        .bind(category)
+        .execute(&mut *tx)
         .await?;
+    sqlx::query("DELETE FROM issue_archives WHERE category = $1")
+        .bind(category)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM issue_approvals WHERE category = $1")
+        .bind(category)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM issues WHERE category = $1")
+        .bind(category)
+        .execute(&mut *tx)
+        .await?;
+
+    let node_ids = delete_category_nodes(&mut tx, category).await?;
 
-        let count = sessions_result.rows_affected();
-        tracing::info!("🗑️  Deleted {} sessions for category '{}'", count, category);
-        count
+    // Optionally delete all sessions associated with this category
+    let sessions_deleted = if delete_sessions {
+        sqlx::query("DELETE FROM sessions WHERE (steps->0->>'category')::text = $1")
+            .bind(category)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
     } else {
         0
     };
 
+    tx.commit().await?;
+
+    for node_id in draft_node_ids.iter().chain(&node_ids) {
+        uploads::delete_node_attachments(&state.db, *node_id).await?;
+    }
+    state.invalidate_issue(category).await;
+
+    if delete_sessions {
+        tracing::info!("🗑️  Deleted {} sessions for category '{}'", sessions_deleted, category);
+    }
+
+    Ok((node_ids.len() as u64, sessions_deleted))
+}
+
+/// Delete a category's nodes and their connections, returning the node IDs
+///
+/// Entry points and change history of the nodes go with them (cascade); their
+/// attachments live outside the database, so callers remove those once committed.
+async fn delete_category_nodes(conn: &mut sqlx::PgConnection, category: &str) -> Result<Vec<Uuid>, sqlx::Error> {
+    sqlx::query("DELETE FROM connections WHERE from_node_id IN (SELECT id FROM nodes WHERE category = $1)")
+        .bind(category)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query_scalar("DELETE FROM nodes WHERE category = $1 RETURNING id")
+        .bind(category)
+        .fetch_all(&mut *conn)
+        .await
+}
+
+/// DELETE /api/admin/issues/:category
+/// Delete entire issue and all its nodes/connections (NODE-GRAPH VERSION)
+pub async fn delete_issue(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    scopes: Option<Extension<ServiceScopes>>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Query(params): Query<DeleteIssueParams>,
+) -> ApiResult<Json<serde_json::Value>> {
+    ensure_can_delete_sessions(&auth, scopes.as_deref(), params.delete_sessions)?;
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let (nodes_deleted, sessions_deleted) = delete_category(&state, &category, params.delete_sessions).await?;
+
     // Audit log the issue deletion
     let user_id = Uuid::parse_str(&auth.0.sub)
         .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
@@ -670,303 +1584,4011 @@ pub async fn delete_issue(
 }
 
 // ============================================
-// IMPORT/EXPORT ENDPOINTS
+// CONTENT REVIEW ENDPOINTS
 // ============================================
 
-/// GET /api/admin/issues/:category/export
-/// Export a single issue with all its nodes and connections as JSON
-pub async fn export_issue(
+/// Default review interval when CONTENT_REVIEW_INTERVAL_DAYS is not set (annual review)
+const DEFAULT_REVIEW_INTERVAL_DAYS: i32 = 365;
+
+/// Response after marking an issue as reviewed
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ReviewIssueResponse {
+    pub category: String,
+    #[ts(type = "number")]
+    pub nodes_reviewed: u64,
+    pub reviewed_at: String,
+}
+
+/// Query parameters for the stale content report
+#[derive(Debug, Deserialize)]
+pub struct StaleIssuesQuery {
+    pub max_age_days: Option<i32>,
+}
+
+/// A category that has not been reviewed within the review interval
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StaleIssue {
+    pub category: String,
+    #[ts(type = "number")]
+    pub node_count: i64,
+    /// Nodes in this category that have never been reviewed
+    #[ts(type = "number")]
+    pub unreviewed_nodes: i64,
+    /// Oldest review timestamp across the category's nodes
+    pub last_reviewed_at: Option<String>,
+    #[ts(type = "number | null")]
+    pub days_since_review: Option<i64>,
+}
+
+/// Stale content report
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StaleIssuesReport {
+    pub max_age_days: i32,
+    pub issues: Vec<StaleIssue>,
+}
+
+/// POST /api/admin/issues/:category/review
+/// Mark every node in an issue category as reviewed now
+pub async fn mark_issue_reviewed(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
     Path(category): Path<String>,
-) -> ApiResult<Json<IssueExportData>> {
-    tracing::info!("📦 Exporting issue: {}", category);
+) -> ApiResult<Json<ReviewIssueResponse>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
 
-    // Get all nodes for this category
-    let nodes = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
-         FROM nodes
-         WHERE category = $1 AND is_active = true
-         ORDER BY created_at ASC"
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let reviewed_at = chrono::Utc::now();
+
+    let result = sqlx::query(
+        "UPDATE nodes SET last_reviewed_at = $1, last_reviewed_by = $2 WHERE category = $3"
     )
+    .bind(reviewed_at)
+    .bind(user_id)
     .bind(&category)
-    .fetch_all(&state.db)
+    .execute(&state.db)
     .await?;
 
-    if nodes.is_empty() {
-        return Err(ApiError::not_found("Issue category not found"));
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Issue not found"));
     }
 
-    // Build ID to index mapping (use UUID as key)
-    let mut id_to_index = std::collections::HashMap::new();
-    for (index, node) in nodes.iter().enumerate() {
-        id_to_index.insert(node.id, index);
-    }
+    // Audit log the review
+    let ip = audit::extract_ip_address(&headers);
 
-    // Get the root node to extract issue metadata
-    let root_node = nodes.iter().find(|n| n.semantic_id.as_ref().map(|s| s.ends_with("_start")).unwrap_or(false))
-        .ok_or_else(|| ApiError::not_found("Root node not found for issue"))?;
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueReviewed,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "nodes_reviewed": result.rows_affected(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
 
-    // Get issue name from database (try to find it via display_category or use category)
-    let issue_name = root_node.display_category.clone().unwrap_or_else(|| category.clone());
+    Ok(Json(ReviewIssueResponse {
+        category,
+        nodes_reviewed: result.rows_affected(),
+        reviewed_at: reviewed_at.to_rfc3339(),
+    }))
+}
 
-    // Export nodes (without UUIDs)
-    let export_nodes: Vec<NodeExportData> = nodes.iter().map(|n| NodeExportData {
-        node_type: match n.node_type {
-            NodeType::Question => "question".to_string(),
-            NodeType::Conclusion => "conclusion".to_string(),
-        },
-        text: n.text.clone(),
-        semantic_id: n.semantic_id.clone(),
-        position_x: n.position_x,
-        position_y: n.position_y,
-    }).collect();
+/// GET /api/admin/issues/stale
+/// List categories not fully reviewed within the review interval
+pub async fn get_stale_issues(
+    State(state): State<AppState>,
+    Query(query): Query<StaleIssuesQuery>,
+) -> ApiResult<Json<StaleIssuesReport>> {
+    let max_age_days = query.max_age_days.unwrap_or_else(|| {
+        std::env::var("CONTENT_REVIEW_INTERVAL_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_REVIEW_INTERVAL_DAYS)
+    });
 
-    // Get all node IDs for connection query
-    let node_ids: Vec<Uuid> = nodes.iter().map(|n| n.id).collect();
+    if max_age_days < 0 {
+        return Err(ApiError::validation(vec![(
+            "max_age_days".to_string(),
+            "max_age_days must not be negative".to_string(),
+        )]));
+    }
 
-    // Get all connections
-    let connections = if !node_ids.is_empty() {
-        sqlx::query_as::<_, Connection>(
-            "SELECT id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at
-             FROM connections
-             WHERE from_node_id = ANY($1) AND is_active = true
-             ORDER BY from_node_id, order_index ASC"
-        )
-        .bind(&node_ids)
-        .fetch_all(&state.db)
+    let rows = sqlx::query_as::<_, (String, i64, i64, Option<chrono::DateTime<chrono::Utc>>)>(
+        "SELECT
+            category,
+            COUNT(*) as node_count,
+            COUNT(*) FILTER (WHERE last_reviewed_at IS NULL) as unreviewed_nodes,
+            MIN(last_reviewed_at) as last_reviewed_at
+         FROM nodes
+         WHERE category <> 'root'
+         GROUP BY category
+         HAVING BOOL_OR(last_reviewed_at IS NULL)
+             OR MIN(last_reviewed_at) < NOW() - make_interval(days => $1)
+         ORDER BY MIN(last_reviewed_at) ASC NULLS FIRST, category ASC"
+    )
+    .bind(max_age_days)
+    .fetch_all(&state.db)
+    .await?;
+
+    let now = chrono::Utc::now();
+    let issues = rows
+        .into_iter()
+        .map(|(category, node_count, unreviewed_nodes, last_reviewed_at)| StaleIssue {
+            category,
+            node_count,
+            unreviewed_nodes,
+            days_since_review: last_reviewed_at.map(|t| (now - t).num_days()),
+            last_reviewed_at: last_reviewed_at.map(|t| t.to_rfc3339()),
+        })
+        .collect();
+
+    Ok(Json(StaleIssuesReport {
+        max_age_days,
+        issues,
+    }))
+}
+
+// ============================================
+// ROLLOUT ENDPOINTS
+// ============================================
+
+/// Request to start or adjust a percentage rollout
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateRolloutRequest {
+    /// Category holding the revised graph
+    pub candidate_category: String,
+    /// Share of new sessions (0-100) routed to the candidate
+    pub percentage: i16,
+}
+
+/// Active rollout configuration
+#[derive(Debug, Serialize, sqlx::FromRow, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueRollout {
+    pub category: String,
+    pub candidate_category: String,
+    pub percentage: i16,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Completion stats for one rollout arm
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RolloutVariantStats {
+    /// "current" or "candidate"
+    pub variant: String,
+    pub served_category: String,
+    pub sessions: i64,
+    pub completed: i64,
+    pub completion_rate: f64,
+}
+
+/// Rollout configuration with per-version stats
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RolloutStatus {
+    pub rollout: IssueRollout,
+    pub variants: Vec<RolloutVariantStats>,
+}
+
+/// GET /api/admin/issues/:category/rollout
+/// Get the active rollout for a category with per-version completion stats
+pub async fn get_issue_rollout(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> ApiResult<Json<RolloutStatus>> {
+    let rollout = sqlx::query_as::<_, IssueRollout>(
+        "SELECT category, candidate_category, percentage, started_at, updated_at
+         FROM issue_rollouts
+         WHERE category = $1"
+    )
+    .bind(&category)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("No active rollout for this issue"))?;
+
+    let rows = sqlx::query_as::<_, (String, String, i64, i64)>(
+        "SELECT a.variant, a.served_category, COUNT(*), COUNT(s.completed_at)
+         FROM session_rollout_assignments a
+         JOIN sessions s ON s.session_id = a.session_id
+         WHERE a.category = $1 AND a.assigned_at >= $2
+         GROUP BY a.variant, a.served_category
+         ORDER BY a.variant"
+    )
+    .bind(&category)
+    .bind(rollout.started_at)
+    .fetch_all(&state.db)
+    .await?;
+
+    let variants = rows
+        .into_iter()
+        .map(|(variant, served_category, sessions, completed)| RolloutVariantStats {
+            variant,
+            served_category,
+            sessions,
+            completed,
+            completion_rate: if sessions > 0 {
+                completed as f64 / sessions as f64
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    Ok(Json(RolloutStatus { rollout, variants }))
+}
+
+/// PUT /api/admin/issues/:category/rollout
+/// Start or adjust a rollout routing a share of new sessions to a candidate category
+pub async fn update_issue_rollout(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<UpdateRolloutRequest>,
+) -> ApiResult<Json<IssueRollout>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str(), req.candidate_category.as_str()]).await?;
+
+    let mut errors = Vec::new();
+    if !(0..=100).contains(&req.percentage) {
+        errors.push(("percentage".to_string(), "Percentage must be between 0 and 100".to_string()));
+    }
+    if req.candidate_category == category {
+        errors.push(("candidate_category".to_string(), "Candidate must be a different category".to_string()));
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+
+    // Both the current and candidate graphs need a root node
+    for (field, cat) in [("category", &category), ("candidate_category", &req.candidate_category)] {
+        let has_root = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM nodes WHERE semantic_id = $1)"
+        )
+        .bind(format!("{}_start", cat))
+        .fetch_one(&state.db)
+        .await?;
+
+        if !has_root {
+            return Err(ApiError::validation(vec![(
+                field.to_string(),
+                format!("Issue '{}' not found", cat),
+            )]));
+        }
+    }
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    // Switching to a different candidate restarts the stats window
+    let rollout = sqlx::query_as::<_, IssueRollout>(
+        "INSERT INTO issue_rollouts (category, candidate_category, percentage, updated_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (category) DO UPDATE SET
+            started_at = CASE WHEN issue_rollouts.candidate_category = EXCLUDED.candidate_category
+                              THEN issue_rollouts.started_at ELSE NOW() END,
+            candidate_category = EXCLUDED.candidate_category,
+            percentage = EXCLUDED.percentage,
+            updated_at = NOW(),
+            updated_by = EXCLUDED.updated_by
+         RETURNING category, candidate_category, percentage, started_at, updated_at"
+    )
+    .bind(&category)
+    .bind(&req.candidate_category)
+    .bind(req.percentage)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::Conflict {
+            message: format!("'{}' is already a candidate in another rollout", req.candidate_category),
+        },
+        e => e.into(),
+    })?;
+
+    // Start questions hide candidate categories from navigation
+    state.questions_cache.clear().await;
+
+    tracing::info!(
+        "🚦 Rollout for {}: {}% of new sessions to {}",
+        category, rollout.percentage, rollout.candidate_category
+    );
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueRolloutUpdated,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "candidate_category": rollout.candidate_category,
+            "percentage": rollout.percentage,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(rollout))
+}
+
+/// DELETE /api/admin/issues/:category/rollout
+/// End a rollout; all new sessions use the current category again
+pub async fn delete_issue_rollout(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let deleted = sqlx::query_scalar::<_, String>(
+        "DELETE FROM issue_rollouts WHERE category = $1 RETURNING candidate_category"
+    )
+    .bind(&category)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("No active rollout for this issue"))?;
+
+    state.questions_cache.clear().await;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueRolloutUpdated,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "candidate_category": deleted,
+            "ended": true,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({ "category": category, "ended": true })))
+}
+
+// ============================================
+// DRAFT ENDPOINTS
+// ============================================
+
+/// Query parameters for publish_issue_draft
+#[derive(Debug, Deserialize)]
+pub struct PublishDraftQuery {
+    /// Publish even when the draft's graph has errors
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// GET /api/admin/issues/:category/draft
+/// Get the issue's unpublished draft
+pub async fn get_issue_draft(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> ApiResult<Json<IssueDraft>> {
+    issue_drafts::find(&mut *state.db.acquire().await?, &category)
         .await?
-    } else {
-        vec![]
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("This issue has no draft"))
+}
+
+/// POST /api/admin/issues/:category/draft
+/// Copy the issue into a draft that can be edited and simulated without
+/// affecting sessions
+pub async fn create_issue_draft(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<IssueDraft>> {
+    category_access::ensure_can_draft(&state.db, &auth, &category).await?;
+    let user_id = acting_user(&auth)?;
+
+    let mut tx = state.db.begin().await?;
+    if let Some(published) = issue_drafts::draft_of(&mut tx, &category).await? {
+        return Err(ApiError::bad_request(format!(
+            "'{}' is the draft of '{}'; publish or discard it instead",
+            category, published
+        )));
+    }
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(&category)
+        .fetch_one(&mut *tx)
+        .await?;
+    if !exists {
+        return Err(ApiError::not_found("Issue not found"));
+    }
+    if let Some(draft) = issue_drafts::find(&mut tx, &category).await? {
+        return Err(ApiError::Conflict {
+            message: format!("This issue already has a draft in '{}'", draft.draft_category),
+        });
+    }
+
+    let draft = issue_drafts::create(&mut tx, &category, user_id).await?;
+    tx.commit().await?;
+
+    tracing::info!("📝 Draft of {} created in {} ({} nodes)", category, draft.draft_category, draft.node_count);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueDraftCreated,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "draft_category": &draft.draft_category,
+            "nodes": draft.node_count,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(draft))
+}
+
+/// DELETE /api/admin/issues/:category/draft
+/// Throw the issue's draft away
+pub async fn discard_issue_draft(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    category_access::ensure_can_draft(&state.db, &auth, &category).await?;
+    let user_id = acting_user(&auth)?;
+
+    let mut tx = state.db.begin().await?;
+    let draft = issue_drafts::find(&mut tx, &category)
+        .await?
+        .ok_or_else(|| ApiError::not_found("This issue has no draft"))?;
+    let node_ids = issue_drafts::discard(&mut tx, &category).await?.unwrap_or_default();
+    // A submission of the draft goes with it
+    let withdrawn = match issue_approvals::pending(&mut tx, &category).await? {
+        Some((pending, _)) => issue_approvals::close(&mut tx, pending.id, ApprovalStatus::Withdrawn, user_id, None, None).await?,
+        None => None,
+    };
+    tx.commit().await?;
+
+    for node_id in &node_ids {
+        uploads::delete_node_attachments(&state.db, *node_id).await?;
+    }
+    state.invalidate_issue(&draft.draft_category).await;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueDraftDiscarded,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "draft_category": &draft.draft_category,
+            "nodes": node_ids.len(),
+            "approval_withdrawn": withdrawn.map(|approval| approval.id),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({ "category": category, "discarded": true, "nodes": node_ids.len() })))
+}
+
+/// POST /api/admin/issues/:category/publish
+/// Replace the issue's graph with its draft in one transaction
+///
+/// The draft's graph is checked first and refused with an `InvalidGraph`
+/// report when it has errors, unless `?force=true`. Published nodes and
+/// answers keep their IDs; see `utils::issue_drafts` for how the draft is
+/// written back. Needs `issues:approve`; editors submit the draft for
+/// approval instead.
+pub async fn publish_issue_draft(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Query(query): Query<PublishDraftQuery>,
+) -> ApiResult<Json<DraftPublished>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+
+    let mut conn = state.db.acquire().await?;
+    let draft = issue_drafts::find(&mut conn, &category)
+        .await?
+        .ok_or_else(|| ApiError::not_found("This issue has no draft"))?;
+    if issue_approvals::pending(&mut conn, &category).await?.is_some() {
+        return Err(ApiError::Conflict {
+            message: "This draft is waiting for approval; approve it, or withdraw it before publishing directly".to_string(),
+        });
+    }
+    drop(conn);
+    if !query.force {
+        let report = engine::validate(&state, &draft.draft_category).await?;
+        if !report.is_valid() {
+            return Err(ApiError::invalid_graph(report));
+        }
+    }
+
+    let mut tx = state.db.begin().await?;
+    let published = issue_drafts::publish(&mut tx, &category, &draft.draft_category, user_id).await?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&category).await;
+    state.invalidate_issue(&draft.draft_category).await;
+    state.questions_cache.clear().await;
+    category_notices::reload(&state.db).await?;
+
+    tracing::info!(
+        "🚀 Published draft of {}: {} node(s) updated, {} added, {} switched off",
+        category, published.nodes_updated, published.nodes_added, published.nodes_deactivated
+    );
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueDraftPublished,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "draft_category": &draft.draft_category,
+            "forced": query.force,
+            "result": &published,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(published))
+}
+
+// ============================================
+// APPROVAL WORKFLOW
+// ============================================
+
+/// Query parameters for list_issue_approvals
+#[derive(Debug, Deserialize)]
+pub struct ApprovalListQuery {
+    pub category: Option<String>,
+    pub status: Option<ApprovalStatus>,
+}
+
+/// Request to submit an issue's draft for approval
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SubmitApprovalRequest {
+    /// Note to the reviewer
+    #[serde(default)]
+    #[ts(optional)]
+    pub comment: Option<String>,
+}
+
+/// Request to approve or reject a submitted draft
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ApprovalDecisionRequest {
+    /// Reason for the decision; required when rejecting
+    #[serde(default)]
+    #[ts(optional)]
+    pub comment: Option<String>,
+}
+
+/// An approved submission and the publish it triggered
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ApprovalPublished {
+    pub approval: IssueApproval,
+    pub published: DraftPublished,
+}
+
+/// Submission comment checked and trimmed, reported under `comment`
+fn approval_comment(comment: Option<&str>) -> ApiResult<Option<String>> {
+    issue_approvals::normalize_comment(comment).map_err(|e| ApiError::validation(vec![("comment".to_string(), e)]))
+}
+
+/// The issue's pending submission and its draft fingerprint (404 when none)
+async fn pending_approval(conn: &mut sqlx::PgConnection, category: &str) -> ApiResult<(IssueApproval, String)> {
+    issue_approvals::pending(conn, category)
+        .await?
+        .ok_or_else(|| ApiError::not_found("No draft of this issue is waiting for approval"))
+}
+
+/// GET /api/admin/issues/approvals
+/// Submissions newest first, optionally for one issue (`category`) or in one `status`
+pub async fn list_issue_approvals(
+    State(state): State<AppState>,
+    Query(query): Query<ApprovalListQuery>,
+) -> ApiResult<Json<Vec<IssueApproval>>> {
+    Ok(Json(issue_approvals::list(&state.db, query.category.as_deref(), query.status).await?))
+}
+
+/// POST /api/admin/issues/:category/approval
+/// Submit the issue's draft for approval
+///
+/// The draft as it is now is what gets approved: changing it afterwards means
+/// submitting it again.
+pub async fn submit_issue_approval(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<SubmitApprovalRequest>,
+) -> ApiResult<Json<IssueApproval>> {
+    category_access::ensure_can_draft(&state.db, &auth, &category).await?;
+    let user_id = acting_user(&auth)?;
+    let comment = approval_comment(req.comment.as_deref())?;
+
+    let mut tx = state.db.begin().await?;
+    let draft = issue_drafts::find(&mut tx, &category)
+        .await?
+        .ok_or_else(|| ApiError::not_found("This issue has no draft"))?;
+    let approval = issue_approvals::submit(&mut tx, &category, &draft.draft_category, comment.as_deref(), user_id)
+        .await?
+        .ok_or_else(|| ApiError::Conflict {
+            message: "This issue's draft is already waiting for approval".to_string(),
+        })?;
+    tx.commit().await?;
+
+    tracing::info!("📨 Draft of {} submitted for approval", category);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueSubmittedForApproval,
+        "issue",
+        Some(&category),
+        Some(json!({ "approval_id": approval.id, "comment": &approval.comment })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(approval))
+}
+
+/// DELETE /api/admin/issues/:category/approval
+/// Withdraw a submission (its submitter, or anyone who may approve it)
+pub async fn withdraw_issue_approval(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<IssueApproval>> {
+    category_access::ensure_can_draft(&state.db, &auth, &category).await?;
+    let user_id = acting_user(&auth)?;
+
+    let mut conn = state.db.acquire().await?;
+    let (pending, _) = pending_approval(&mut conn, &category).await?;
+    if pending.submitted_by != Some(user_id) && !permissions::has_permission(&auth.0.role, Permission::IssuesApprove) {
+        return Err(ApiError::forbidden("Only the submitter or an approver can withdraw this submission"));
+    }
+    let approval = issue_approvals::close(&mut conn, pending.id, ApprovalStatus::Withdrawn, user_id, None, None)
+        .await?
+        .ok_or_else(|| ApiError::Conflict {
+            message: "This submission has already been decided".to_string(),
+        })?;
+    drop(conn);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueApprovalWithdrawn,
+        "issue",
+        Some(&category),
+        Some(json!({ "approval_id": approval.id })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(approval))
+}
+
+/// POST /api/admin/issues/:category/approval/approve
+/// Approve the submitted draft and publish it in the same transaction
+///
+/// Someone other than the submitter has to approve, and the draft must be
+/// unchanged since it was submitted. Like a direct publish, a draft whose
+/// graph has errors is refused unless `?force=true`.
+pub async fn approve_issue_approval(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Query(query): Query<PublishDraftQuery>,
+    Json(req): Json<ApprovalDecisionRequest>,
+) -> ApiResult<Json<ApprovalPublished>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+    let comment = approval_comment(req.comment.as_deref())?;
+
+    let mut conn = state.db.acquire().await?;
+    let (pending, fingerprint) = pending_approval(&mut conn, &category).await?;
+    if pending.submitted_by == Some(user_id) {
+        return Err(ApiError::forbidden("Submissions must be approved by someone other than their submitter"));
+    }
+    let draft = issue_drafts::find(&mut conn, &category)
+        .await?
+        .ok_or_else(|| ApiError::not_found("This issue has no draft"))?;
+    if issue_approvals::fingerprint(&mut conn, &draft.draft_category).await? != fingerprint {
+        return Err(ApiError::Conflict {
+            message: "The draft has changed since it was submitted; it needs submitting again".to_string(),
+        });
+    }
+    drop(conn);
+    if !query.force {
+        let report = engine::validate(&state, &draft.draft_category).await?;
+        if !report.is_valid() {
+            return Err(ApiError::invalid_graph(report));
+        }
+    }
+
+    let mut tx = state.db.begin().await?;
+    let published = issue_drafts::publish(&mut tx, &category, &draft.draft_category, user_id).await?;
+    let approval = issue_approvals::close(
+        &mut tx,
+        pending.id,
+        ApprovalStatus::Approved,
+        user_id,
+        comment.as_deref(),
+        Some(published.version),
+    )
+    .await?
+    .ok_or_else(|| ApiError::Conflict {
+        message: "This submission has already been decided".to_string(),
+    })?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&category).await;
+    state.invalidate_issue(&draft.draft_category).await;
+    state.questions_cache.clear().await;
+    category_notices::reload(&state.db).await?;
+
+    tracing::info!("✅ Approved and published draft of {} as version {}", category, published.version);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueApproved,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "approval_id": approval.id,
+            "submitted_by": approval.submitted_by,
+            "comment": &approval.decision_comment,
+            "forced": query.force,
+            "result": &published,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ApprovalPublished { approval, published }))
+}
+
+/// POST /api/admin/issues/:category/approval/reject
+/// Send the submitted draft back with a comment; the draft is kept for rework
+pub async fn reject_issue_approval(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<ApprovalDecisionRequest>,
+) -> ApiResult<Json<IssueApproval>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+    let comment = approval_comment(req.comment.as_deref())?.ok_or_else(|| {
+        ApiError::validation(vec![("comment".to_string(), "Say what needs changing".to_string())])
+    })?;
+
+    let mut conn = state.db.acquire().await?;
+    let (pending, _) = pending_approval(&mut conn, &category).await?;
+    let approval = issue_approvals::close(&mut conn, pending.id, ApprovalStatus::Rejected, user_id, Some(&comment), None)
+        .await?
+        .ok_or_else(|| ApiError::Conflict {
+            message: "This submission has already been decided".to_string(),
+        })?;
+    drop(conn);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueRejected,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "approval_id": approval.id,
+            "submitted_by": approval.submitted_by,
+            "comment": &comment,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(approval))
+}
+
+// ============================================
+// VERSION HISTORY
+// ============================================
+
+/// Query parameters for diff_issue_versions
+#[derive(Debug, Deserialize)]
+pub struct VersionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+/// GET /api/admin/issues/:category/versions
+/// List the versions recorded when the issue was published or rolled back, newest first
+pub async fn list_issue_versions(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> ApiResult<Json<Vec<IssueVersion>>> {
+    Ok(Json(issue_versions::list(&mut *state.db.acquire().await?, &category).await?))
+}
+
+/// GET /api/admin/issues/:category/versions/diff?from=&to=
+/// Nodes and connections added, removed and changed from one version to another
+pub async fn diff_issue_versions(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+    Query(query): Query<VersionDiffQuery>,
+) -> ApiResult<Json<IssueVersionDiff>> {
+    let diff = issue_versions::diff(&mut *state.db.acquire().await?, &category, query.from, query.to).await?;
+    Ok(Json(diff))
+}
+
+/// POST /api/admin/issues/:category/versions/:version/rollback
+/// Restore the issue's graph to a recorded version in one transaction
+///
+/// Refused while the issue has a draft; publish or discard it first.
+pub async fn rollback_issue_version(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path((category, version)): Path<(String, i32)>,
+) -> ApiResult<Json<IssueRolledBack>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+
+    let mut tx = state.db.begin().await?;
+    if issue_drafts::find(&mut tx, &category).await?.is_some() {
+        return Err(ApiError::Conflict {
+            message: "This issue has a draft; publish or discard it before rolling back".to_string(),
+        });
+    }
+    let rolled_back = issue_versions::rollback(&mut tx, &category, version, user_id).await?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&category).await;
+    state.questions_cache.clear().await;
+    category_notices::reload(&state.db).await?;
+
+    tracing::info!(
+        "⏪ Rolled {} back to version {} (recorded as version {})",
+        category, version, rolled_back.version
+    );
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueRolledBack,
+        "issue",
+        Some(&category),
+        Some(json!({ "result": &rolled_back })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(rolled_back))
+}
+
+// ============================================
+// ARCHIVAL
+// ============================================
+
+/// An archived issue
+#[derive(Debug, Serialize, sqlx::FromRow, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueArchive {
+    pub category: String,
+    pub archived_by: Option<Uuid>,
+    pub archived_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Start menu, direct and entry point starts and the kiosk manifest change with archival
+async fn invalidate_archival(state: &AppState, category: &str) {
+    state.invalidate_issue(category).await;
+    state.questions_cache.clear().await;
+}
+
+/// Archive an issue; None when it already is
+async fn archive_category(state: &AppState, category: &str, user_id: Uuid) -> ApiResult<Option<IssueArchive>> {
+    let mut conn = state.db.acquire().await?;
+    if let Some(published) = issue_drafts::draft_of(&mut conn, category).await? {
+        return Err(ApiError::bad_request(format!(
+            "'{}' is the draft of '{}'; archive that issue instead",
+            category, published
+        )));
+    }
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(category)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !exists {
+        return Err(ApiError::not_found("Issue not found"));
+    }
+
+    let archive = sqlx::query_as::<_, IssueArchive>(
+        "INSERT INTO issue_archives (category, archived_by) VALUES ($1, $2)
+         ON CONFLICT (category) DO NOTHING
+         RETURNING category, archived_by, archived_at",
+    )
+    .bind(category)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    drop(conn);
+
+    if archive.is_some() {
+        invalidate_archival(state, category).await;
+        tracing::info!("📦 Archived issue {}", category);
+    }
+    Ok(archive)
+}
+
+/// POST /api/admin/issues/:category/archive
+/// Hide the issue from technicians while keeping its graph and sessions
+///
+/// Sessions already in progress can still be finished, and other issues can
+/// still run it as a sub-flow.
+pub async fn archive_issue(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<IssueArchive>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+
+    let archive = archive_category(&state, &category, user_id)
+        .await?
+        .ok_or_else(|| ApiError::Conflict {
+            message: "This issue is already archived".to_string(),
+        })?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueArchived,
+        "issue",
+        Some(&category),
+        None,
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(archive))
+}
+
+/// Unarchive an issue, returning when it had been archived; None when it wasn't
+async fn unarchive_category(state: &AppState, category: &str) -> ApiResult<Option<chrono::DateTime<chrono::Utc>>> {
+    let archived_at = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>(
+        "DELETE FROM issue_archives WHERE category = $1 RETURNING archived_at",
+    )
+    .bind(category)
+    .fetch_optional(&state.db)
+    .await?;
+
+    if archived_at.is_some() {
+        invalidate_archival(state, category).await;
+        tracing::info!("📦 Unarchived issue {}", category);
+    }
+    Ok(archived_at)
+}
+
+/// POST /api/admin/issues/:category/unarchive
+/// Offer an archived issue to technicians again
+pub async fn unarchive_issue(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+
+    let archived_at = unarchive_category(&state, &category)
+        .await?
+        .ok_or_else(|| ApiError::Conflict {
+            message: "This issue is not archived".to_string(),
+        })?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueUnarchived,
+        "issue",
+        Some(&category),
+        Some(json!({ "archived_at": archived_at })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(json!({ "category": category, "archived": false })))
+}
+
+// ============================================
+// SLUG RENAME
+// ============================================
+
+/// Request to rename an issue's category slug
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RenameIssueSlugRequest {
+    /// New category key: letters, digits, underscores and hyphens
+    pub new_category: String,
+}
+
+/// Response after renaming an issue's category slug
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueSlugRenamed {
+    pub previous_category: String,
+    pub issue: Issue,
+    /// Nodes moved into the new category
+    #[ts(type = "number")]
+    pub nodes_updated: u64,
+    /// Sub-flow nodes in any issue now pointing at the new category
+    #[ts(type = "number")]
+    pub subflows_updated: u64,
+    /// Sessions whose recorded steps were moved to the new category
+    #[ts(type = "number")]
+    pub sessions_updated: u64,
+}
+
+/// PUT /api/admin/issues/:category/rename-slug
+/// Rename the category key an issue's nodes share, in one transaction
+///
+/// Unlike renaming a display category this changes the key itself: the start
+/// question's `{category}_start` semantic ID, sub-flows into the issue, its
+/// metadata, history and settings, and the category recorded on sessions all
+/// move to the new slug (see `utils::issue_slugs`). Refused while the issue
+/// has a draft, whose category is named after the slug.
+pub async fn rename_issue_slug(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<RenameIssueSlugRequest>,
+) -> ApiResult<Json<IssueSlugRenamed>> {
+    let new_category =
+        issue_slugs::normalize(&req.new_category).map_err(|e| ApiError::validation(vec![("new_category".to_string(), e)]))?;
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str(), new_category.as_str()]).await?;
+    let user_id = acting_user(&auth)?;
+
+    if category == "root" {
+        return Err(ApiError::bad_request("The start question's category can't be renamed"));
+    }
+    if new_category == category {
+        return Err(ApiError::validation(vec![(
+            "new_category".to_string(),
+            "Must differ from the current category".to_string(),
+        )]));
+    }
+
+    let mut tx = state.db.begin().await?;
+    if let Some(published) = issue_drafts::draft_of(&mut tx, &category).await? {
+        return Err(ApiError::bad_request(format!(
+            "'{}' is the draft of '{}'; rename that issue instead",
+            category, published
+        )));
+    }
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(&category)
+        .fetch_one(&mut *tx)
+        .await?;
+    if !exists {
+        return Err(ApiError::not_found("Issue not found"));
+    }
+    let taken = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)
+             OR EXISTS(SELECT 1 FROM issues WHERE category = $1)
+             OR EXISTS(SELECT 1 FROM issue_drafts WHERE draft_category = $1)",
+    )
+    .bind(&new_category)
+    .fetch_one(&mut *tx)
+    .await?;
+    if taken {
+        return Err(ApiError::validation(vec![(
+            "new_category".to_string(),
+            "Category already exists".to_string(),
+        )]));
+    }
+    let has_draft = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM issue_drafts WHERE category = $1)")
+        .bind(&category)
+        .fetch_one(&mut *tx)
+        .await?;
+    if has_draft {
+        return Err(ApiError::Conflict {
+            message: "Publish or discard this issue's draft before renaming it".to_string(),
+        });
+    }
+
+    let counts = issue_slugs::rename(&mut tx, &category, &new_category, user_id).await?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&category).await;
+    state.invalidate_issue(&new_category).await;
+    category_notices::reload(&state.db).await?;
+    tracing::info!("🏷️  Renamed issue category {} to {}", category, new_category);
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueSlugRenamed,
+        "issue",
+        Some(&new_category),
+        Some(json!({
+            "previous_category": &category,
+            "nodes_updated": counts.nodes,
+            "subflows_updated": counts.subflows,
+            "sessions_updated": counts.sessions,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(IssueSlugRenamed {
+        previous_category: category,
+        issue: load_issue(&state, &new_category).await?,
+        nodes_updated: counts.nodes,
+        subflows_updated: counts.subflows,
+        sessions_updated: counts.sessions,
+    }))
+}
+
+// ============================================
+// QUOTA ENDPOINTS
+// ============================================
+
+/// Request to set a category's daily session quota
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateQuotaRequest {
+    /// New sessions allowed per UTC day
+    pub daily_limit: i32,
+    /// Sessions per day after which ops are warned (at most the limit)
+    #[serde(default)]
+    #[ts(optional)]
+    pub warn_at: Option<i32>,
+}
+
+/// Today's quota for a category
+async fn fetch_quota(state: &AppState, category: &str) -> ApiResult<CategoryQuota> {
+    category_quotas::list(&state.db, Some(category))
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::not_found("No session quota for this issue"))
+}
+
+/// GET /api/admin/issues/:category/quota
+/// Get a category's daily session quota with today's use
+pub async fn get_issue_quota(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> ApiResult<Json<CategoryQuota>> {
+    Ok(Json(fetch_quota(&state, &category).await?))
+}
+
+/// PUT /api/admin/issues/:category/quota
+/// Set or change a category's daily session quota; takes effect for the next session started
+pub async fn update_issue_quota(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<UpdateQuotaRequest>,
+) -> ApiResult<Json<CategoryQuota>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let errors = category_quotas::validate(req.daily_limit, req.warn_at);
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+
+    let has_root = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE semantic_id = $1)")
+        .bind(format!("{}_start", category))
+        .fetch_one(&state.db)
+        .await?;
+    if !has_root {
+        return Err(ApiError::not_found(format!("Issue '{}' not found", category)));
+    }
+
+    let user_id = acting_user(&auth)?;
+
+    sqlx::query(
+        "INSERT INTO category_quotas (category, daily_limit, warn_at, updated_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (category) DO UPDATE SET
+            daily_limit = EXCLUDED.daily_limit,
+            warn_at = EXCLUDED.warn_at,
+            updated_at = NOW(),
+            updated_by = EXCLUDED.updated_by",
+    )
+    .bind(&category)
+    .bind(req.daily_limit)
+    .bind(req.warn_at)
+    .bind(user_id)
+    .execute(&state.db)
+    .await?;
+
+    let quota = fetch_quota(&state, &category).await?;
+    tracing::info!(
+        "🎟️  Session quota for {}: {} per day ({} used today)",
+        category, quota.daily_limit, quota.sessions_today
+    );
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueQuotaUpdated,
+        "issue",
+        Some(&category),
+        Some(json!(&req)),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(quota))
+}
+
+/// DELETE /api/admin/issues/:category/quota
+/// Remove a category's session quota
+pub async fn delete_issue_quota(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<CategoryQuota>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let quota = fetch_quota(&state, &category).await?;
+    sqlx::query("DELETE FROM category_quotas WHERE category = $1")
+        .bind(&category)
+        .execute(&state.db)
+        .await?;
+
+    let user_id = acting_user(&auth)?;
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueQuotaUpdated,
+        "issue",
+        Some(&category),
+        Some(json!({ "daily_limit": quota.daily_limit, "removed": true })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(quota))
+}
+
+// ============================================
+// LAYOUT ENDPOINTS
+// ============================================
+
+/// POST /api/admin/issues/:category/layout
+/// Lay out every node of the category in layers from the issue's start
+/// (Sugiyama-style) and save the positions
+pub async fn auto_layout_issue(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<LayoutResult>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let result = layout::layer_category(&state.db, &category).await?;
+    if result.nodes == 0 {
+        return Err(ApiError::not_found(format!("Issue '{}' not found", category)));
+    }
+    state.invalidate_issue(&category).await;
+
+    let user_id = acting_user(&auth)?;
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueLaidOut,
+        "issue",
+        Some(&category),
+        Some(json!({ "moved": result.moved, "placed": result.placed })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(result))
+}
+
+/// POST /api/admin/issues/:category/layout/normalize
+/// Snap the category's nodes to the grid, clamp them to the canvas, move
+/// overlapping nodes apart and place nodes that have no position
+pub async fn normalize_issue_layout(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+) -> ApiResult<Json<LayoutResult>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let result = layout::normalize_category(&state.db, &category).await?;
+    if result.nodes == 0 {
+        return Err(ApiError::not_found(format!("Issue '{}' not found", category)));
+    }
+    state.invalidate_issue(&category).await;
+
+    let user_id = acting_user(&auth)?;
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssueLayoutNormalized,
+        "issue",
+        Some(&category),
+        Some(json!({ "moved": result.moved, "placed": result.placed })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(result))
+}
+
+/// Default traversal window for weight recomputation
+const WEIGHT_DEFAULT_WINDOW_DAYS: i32 = 90;
+/// Default minimum choices at a node before its weights are recomputed
+const WEIGHT_DEFAULT_MIN_SAMPLES: i64 = 10;
+
+/// Query parameters for weight recomputation
+#[derive(Debug, Deserialize)]
+pub struct RecomputeWeightsQuery {
+    /// Only count sessions started in the last N days (default 90)
+    pub window_days: Option<i32>,
+    /// Skip nodes with fewer recorded choices than this (default 10)
+    pub min_samples: Option<i64>,
+}
+
+/// Result of recomputing connection weights for a category
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RecomputeWeightsResult {
+    pub category: String,
+    pub window_days: i32,
+    #[ts(type = "number")]
+    pub min_samples: i64,
+    pub nodes_updated: usize,
+    pub connections_updated: usize,
+}
+
+/// POST /api/admin/issues/:category/connection-weights/recompute
+/// Set each connection's weight to its share of the choices made at its
+/// source node. Nodes without enough traffic keep their current weights.
+pub async fn recompute_connection_weights(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Query(query): Query<RecomputeWeightsQuery>,
+) -> ApiResult<Json<RecomputeWeightsResult>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let window_days = query.window_days.unwrap_or(WEIGHT_DEFAULT_WINDOW_DAYS);
+    let min_samples = query.min_samples.unwrap_or(WEIGHT_DEFAULT_MIN_SAMPLES);
+
+    let mut errors = Vec::new();
+    if !(1..=3650).contains(&window_days) {
+        errors.push(("window_days".to_string(), "Must be between 1 and 3650".to_string()));
+    }
+    if min_samples < 1 {
+        errors.push(("min_samples".to_string(), "Must be at least 1".to_string()));
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(&category)
+        .fetch_one(&state.db)
+        .await?;
+    if !exists {
+        return Err(ApiError::not_found("Issue not found"));
+    }
+
+    // Weights are stats, not content: the update trigger leaves updated_at alone
+    let updated = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "WITH traversals AS (
+             SELECT (step->>'connection_id')::uuid AS connection_id, COUNT(*) AS picks
+             FROM sessions s, jsonb_array_elements(s.steps) step
+             WHERE s.started_at > NOW() - make_interval(days => $2)
+               AND step->>'connection_id' IS NOT NULL
+             GROUP BY 1
+         ),
+         scored AS (
+             SELECT c.id, c.from_node_id,
+                    COALESCE(t.picks, 0) AS picks,
+                    SUM(COALESCE(t.picks, 0)) OVER (PARTITION BY c.from_node_id) AS node_total
+             FROM connections c
+             INNER JOIN nodes n ON c.from_node_id = n.id
+             LEFT JOIN traversals t ON t.connection_id = c.id
+             WHERE n.category = $1 AND c.is_active = true
+         )
+         UPDATE connections c
+         SET weight = ROUND(s.picks::numeric / s.node_total, 4)::float8
+         FROM scored s
+         WHERE c.id = s.id AND s.node_total >= $3
+         RETURNING c.id, s.from_node_id"
+    )
+    .bind(&category)
+    .bind(window_days)
+    .bind(min_samples)
+    .fetch_all(&state.db)
+    .await?;
+
+    let nodes_updated = updated
+        .iter()
+        .map(|(_, from_node_id)| from_node_id)
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+
+    state.invalidate_issue(&category).await;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::ConnectionWeightsRecomputed,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "window_days": window_days,
+            "min_samples": min_samples,
+            "nodes_updated": nodes_updated,
+            "connections_updated": updated.len(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!(
+        "⚖️  Recomputed weights for {}: {} connections across {} nodes",
+        category, updated.len(), nodes_updated
+    );
+
+    Ok(Json(RecomputeWeightsResult {
+        category,
+        window_days,
+        min_samples,
+        nodes_updated,
+        connections_updated: updated.len(),
+    }))
+}
+
+// ============================================
+// ORPHAN CLEANUP ENDPOINTS
+// ============================================
+
+/// What to do with stranded nodes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum OrphanAction {
+    /// Switch them off; they can be linked up and switched back on later
+    Deactivate,
+    /// Delete them with their answers
+    Delete,
+}
+
+/// Request to clean up an issue's stranded nodes
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct OrphanCleanupRequest {
+    pub action: OrphanAction,
+    /// Only these nodes (each must be stranded); all of them when omitted
+    #[serde(default)]
+    #[ts(optional)]
+    pub node_ids: Option<Vec<Uuid>>,
+}
+
+/// Stranded nodes that were cleaned up
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct OrphanCleanupResult {
+    pub category: String,
+    pub action: OrphanAction,
+    /// Nodes switched off or deleted (already inactive ones are left out when deactivating)
+    pub node_ids: Vec<Uuid>,
+}
+
+/// GET /api/admin/issues/:category/orphans
+/// Nodes nothing leads to: not reached from the issue's start, an entry
+/// point or another category, whether answers are switched on or off
+pub async fn list_issue_orphans(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> ApiResult<Json<OrphanReport>> {
+    orphans::find(&state.db, &category)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Issue '{}' not found", category)))
+}
+
+/// POST /api/admin/issues/:category/orphans/cleanup
+/// Switch off or delete an issue's stranded nodes, or the listed ones
+pub async fn cleanup_issue_orphans(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<OrphanCleanupRequest>,
+) -> ApiResult<Json<OrphanCleanupResult>> {
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let report = orphans::find(&state.db, &category)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Issue '{}' not found", category)))?;
+    let stranded: std::collections::HashSet<Uuid> = report.nodes.iter().map(|node| node.id).collect();
+
+    let node_ids: Vec<Uuid> = match req.node_ids {
+        Some(node_ids) => {
+            let fields: Vec<(String, String)> = node_ids
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| !stranded.contains(id))
+                .map(|(i, _)| (format!("/node_ids/{}", i), "Node is not stranded in this issue".to_string()))
+                .collect();
+            if !fields.is_empty() {
+                return Err(ApiError::validation_at(fields));
+            }
+            let mut seen = std::collections::HashSet::new();
+            node_ids.into_iter().filter(|id| seen.insert(*id)).collect()
+        }
+        None => report.nodes.iter().map(|node| node.id).collect(),
+    };
+
+    let node_ids: Vec<Uuid> = match req.action {
+        OrphanAction::Deactivate => {
+            sqlx::query_scalar::<_, Uuid>(
+                "UPDATE nodes SET is_active = false, updated_at = NOW()
+                 WHERE id = ANY($1) AND is_active
+                 RETURNING id",
+            )
+            .bind(&node_ids)
+            .fetch_all(&state.db)
+            .await?
+        }
+        OrphanAction::Delete => {
+            let mut tx = state.db.begin().await?;
+            sqlx::query("DELETE FROM connections WHERE from_node_id = ANY($1) OR to_node_id = ANY($1)")
+                .bind(&node_ids)
+                .execute(&mut *tx)
+                .await?;
+            let deleted = sqlx::query_scalar::<_, Uuid>("DELETE FROM nodes WHERE id = ANY($1) RETURNING id")
+                .bind(&node_ids)
+                .fetch_all(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            deleted
+        }
+    };
+
+    if !node_ids.is_empty() {
+        state.invalidate_issue(&category).await;
+
+        let user_id = acting_user(&auth)?;
+        let ip = audit::extract_ip_address(&headers);
+        audit::log_event(
+            &state.db,
+            user_id,
+            audit::AuditAction::IssueOrphansCleaned,
+            "issue",
+            Some(&category),
+            Some(json!({ "action": req.action, "node_ids": &node_ids })),
+            ip.as_deref(),
+        )
+        .await?;
+    }
+
+    tracing::info!("🧹 {:?} {} stranded node(s) in {}", req.action, node_ids.len(), category);
+
+    Ok(Json(OrphanCleanupResult { category, action: req.action, node_ids }))
+}
+
+// ============================================
+// INACTIVE RECORD PURGE
+// ============================================
+
+/// Request to purge an issue's long-inactive nodes and answers
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct PurgeInactiveRequest {
+    /// Delete records switched off more than this many days ago
+    #[ts(type = "number")]
+    pub older_than_days: i64,
+}
+
+/// Nodes and answers deleted by a purge
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct PurgeInactiveResult {
+    pub category: String,
+    pub node_ids: Vec<Uuid>,
+    /// Answers deleted, including those of the purged nodes
+    #[ts(type = "number")]
+    pub connections: u64,
+}
+
+/// POST /api/admin/issues/:category/purge
+/// Permanently delete an issue's nodes and answers that have been switched
+/// off for longer than `older_than_days`; more recently switched-off records
+/// can still be restored
+pub async fn purge_inactive(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(category): Path<String>,
+    Json(req): Json<PurgeInactiveRequest>,
+) -> ApiResult<Json<PurgeInactiveResult>> {
+    if req.older_than_days < 1 {
+        return Err(ApiError::validation(vec![(
+            "older_than_days".to_string(),
+            "Must be at least 1".to_string(),
+        )]));
+    }
+    category_access::ensure_can_edit(&state.db, &auth, [category.as_str()]).await?;
+
+    let mut tx = state.db.begin().await?;
+    let node_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM nodes
+         WHERE category = $1 AND is_active = false AND deactivated_at < NOW() - make_interval(days => $2::int)
+         FOR UPDATE",
+    )
+    .bind(&category)
+    .bind(req.older_than_days)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let connections = sqlx::query(
+        "DELETE FROM connections c
+         USING nodes n
+         WHERE n.id = c.from_node_id AND n.category = $1
+           AND ((c.is_active = false AND c.deactivated_at < NOW() - make_interval(days => $2::int))
+                OR c.from_node_id = ANY($3) OR c.to_node_id = ANY($3))",
+    )
+    .bind(&category)
+    .bind(req.older_than_days)
+    .bind(&node_ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    // Answers from other issues into purged nodes go too
+    let connections = connections
+        + sqlx::query("DELETE FROM connections WHERE to_node_id = ANY($1)")
+            .bind(&node_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+    sqlx::query("DELETE FROM nodes WHERE id = ANY($1)")
+        .bind(&node_ids)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    for node_id in &node_ids {
+        uploads::delete_node_attachments(&state.db, *node_id).await?;
+    }
+
+    if !node_ids.is_empty() || connections > 0 {
+        state.invalidate_issue(&category).await;
+
+        let user_id = acting_user(&auth)?;
+        let ip = audit::extract_ip_address(&headers);
+        audit::log_event(
+            &state.db,
+            user_id,
+            audit::AuditAction::IssueInactivePurged,
+            "issue",
+            Some(&category),
+            Some(json!({
+                "older_than_days": req.older_than_days,
+                "node_ids": &node_ids,
+                "connections": connections,
+            })),
+            ip.as_deref(),
+        )
+        .await?;
+    }
+
+    tracing::info!(
+        "🗑️  Purged {} node(s) and {} answer(s) inactive for over {} days in {}",
+        node_ids.len(),
+        connections,
+        req.older_than_days,
+        category
+    );
+
+    Ok(Json(PurgeInactiveResult { category, node_ids, connections }))
+}
+
+// ============================================
+// IMPORT/EXPORT ENDPOINTS
+// ============================================
+
+/// GET /api/admin/issues/:category/export
+/// Export a single issue with all its nodes and connections as JSON, or as a download with
+/// `?format=csv` (flat rows), `?format=dot` (Graphviz), `?format=mermaid` or `?format=zip`
+/// (bundle with attachments)
+pub async fn export_issue(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+    Query(query): Query<ExportIssueQuery>,
+) -> ApiResult<Response> {
+    let (data, node_ids) = load_issue_export_with_ids(&state, &category).await?;
+    let (body, content_type, extension) = match query.format {
+        ExportFormat::Json => return Ok(Json(data).into_response()),
+        ExportFormat::Zip => (
+            bundle_issue(&state, data, &node_ids).await?,
+            "application/zip",
+            "zip",
+        ),
+        ExportFormat::Csv => (
+            issue_csv::to_csv(&data).map_err(ApiError::bad_request)?.into_bytes(),
+            "text/csv; charset=utf-8",
+            "csv",
+        ),
+        ExportFormat::Dot => (diagrams::to_dot(&data).into_bytes(), "text/vnd.graphviz; charset=utf-8", "dot"),
+        ExportFormat::Mermaid => (diagrams::to_mermaid(&data).into_bytes(), "text/plain; charset=utf-8", "mmd"),
+    };
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        category.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_', "_"),
+        extension
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Zip an exported issue with its nodes' attachments
+///
+/// Without upload storage configured the bundle simply has no attachments.
+async fn bundle_issue(state: &AppState, data: IssueExportData, node_ids: &[Uuid]) -> ApiResult<Vec<u8>> {
+    let mut attachments = Vec::new();
+    if let Ok(service) = crate::uploads::service() {
+        for (node_index, node_id) in node_ids.iter().enumerate() {
+            for upload in service.list_for_owner(&state.db, UploadOwnerType::Node, &node_id.to_string()).await? {
+                let bytes = service.read(&upload).await?;
+                attachments.push(AttachmentFile {
+                    node_index,
+                    filename: upload.original_filename,
+                    content_type: upload.content_type,
+                    bytes,
+                });
+            }
+        }
+    }
+
+    let count = attachments.len();
+    let bundle = tokio::task::spawn_blocking(move || issue_bundle::write_bundle(&data, &attachments))
+        .await
+        .map_err(|e| ApiError::internal(format!("Bundle export failed: {}", e)))?
+        .map_err(ApiError::internal)?;
+    tracing::info!("🗜️  Bundled issue with {} attachment(s) ({} bytes)", count, bundle.len());
+    Ok(bundle)
+}
+
+/// A single issue with its nodes and connections, as exported
+async fn load_issue_export(state: &AppState, category: &str) -> ApiResult<IssueExportData> {
+    load_issue_export_with_ids(state, category).await.map(|(data, _)| data)
+}
+
+/// An issue as exported, with the IDs of its nodes in export order
+async fn load_issue_export_with_ids(state: &AppState, category: &str) -> ApiResult<(IssueExportData, Vec<Uuid>)> {
+    tracing::info!("📦 Exporting issue: {}", category);
+
+    // Get all nodes for this category; a switched-off issue exports as it stands
+    let nodes = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE category = $1
+           AND (is_active = true
+                OR NOT (SELECT r.is_active FROM nodes r WHERE r.category = $1 ORDER BY r.created_at ASC LIMIT 1))
+         ORDER BY created_at ASC"
+    )
+    .bind(category)
+    .fetch_all(&state.db)
+    .await?;
+
+    if nodes.is_empty() {
+        return Err(ApiError::not_found("Issue category not found"));
+    }
+
+    // Build ID to index mapping (use UUID as key)
+    let mut id_to_index = std::collections::HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        id_to_index.insert(node.id, index);
+    }
+
+    // Get the root node to extract issue metadata
+    let root_node = nodes.iter().find(|n| n.semantic_id.as_ref().map(|s| s.ends_with("_start")).unwrap_or(false))
+        .ok_or_else(|| ApiError::not_found("Root node not found for issue"))?;
+
+    // The issue's name and position live on the start question's answer that leads to it
+    let start_link = sqlx::query_as::<_, (String, i32)>(
+        "SELECT c.label, c.order_index
+         FROM connections c
+         JOIN nodes s ON s.id = c.from_node_id AND s.semantic_id = 'start'
+         WHERE c.to_node_id = $1
+         ORDER BY c.created_at ASC
+         LIMIT 1"
+    )
+    .bind(root_node.id)
+    .fetch_optional(&state.db)
+    .await?;
+    let metadata = sqlx::query_as::<_, (String, Option<String>, Vec<String>, Vec<String>)>(
+        "SELECT name, description, equipment_models, tags FROM issues WHERE category = $1",
+    )
+    .bind(category)
+    .fetch_optional(&state.db)
+    .await?;
+    let issue_name = match (&metadata, &start_link) {
+        (Some((name, ..)), _) => name.clone(),
+        (None, Some((label, _))) => label.clone(),
+        (None, None) => root_node.display_category.clone().unwrap_or_else(|| category.to_string()),
+    };
+    let (description, equipment_models, tags) = match metadata {
+        Some((_, description, equipment_models, tags)) => (description, Some(equipment_models), Some(tags)),
+        None => (None, None, None),
+    };
+    // The issue's state is its first node's, as for toggling
+    let is_active = nodes[0].is_active;
+
+    // Export nodes (without UUIDs)
+    let export_nodes: Vec<NodeExportData> = nodes.iter().map(|n| NodeExportData {
+        node_type: match n.node_type {
+            NodeType::Question => "question".to_string(),
+            NodeType::Conclusion => "conclusion".to_string(),
+            NodeType::Subflow => "subflow".to_string(),
+            NodeType::Return => "return".to_string(),
+        },
+        text: n.text.clone(),
+        semantic_id: n.semantic_id.clone(),
+        position_x: n.position_x,
+        position_y: n.position_y,
+        kb_url: n.kb_url.clone(),
+        doc_reference: n.doc_reference.clone(),
+        option_order: n.option_order,
+        answer_mode: n.answer_mode,
+        not_sure_node_index: n.not_sure_node_id.and_then(|id| id_to_index.get(&id).copied()),
+        subflow_category: n.subflow_category.clone(),
+        content: n.content.clone(),
+    }).collect();
+
+    // Get all node IDs for connection query
+    let node_ids: Vec<Uuid> = nodes.iter().map(|n| n.id).collect();
+
+    // Get all connections
+    let connections = if !node_ids.is_empty() {
+        sqlx::query_as::<_, Connection>(
+            "SELECT id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at
+             FROM connections
+             WHERE from_node_id = ANY($1) AND is_active = true
+             ORDER BY from_node_id, order_index ASC"
+        )
+        .bind(&node_ids)
+        .fetch_all(&state.db)
+        .await?
+    } else {
+        vec![]
+    };
+
+    // Export connections (with indices instead of UUIDs)
+    let export_connections: Vec<ConnectionExportData> = connections.iter().filter_map(|c| {
+        let from_index = id_to_index.get(&c.from_node_id)?;
+        let to_index = id_to_index.get(&c.to_node_id)?;
+        Some(ConnectionExportData {
+            from_node_index: *from_index,
+            to_node_index: *to_index,
+            label: c.label.clone(),
+            order_index: c.order_index,
+            condition: c.condition.clone(),
+            unsure_route: c.unsure_route,
+        })
+    }).collect();
+
+    let export_data = IssueExportData {
+        issue: IssueImportMetadata {
+            name: issue_name,
+            category: category.to_string(),
+            display_category: root_node.display_category.clone(),
+            root_question_text: root_node.text.clone(),
+            is_active: Some(is_active),
+            start_order_index: start_link.map(|(_, order_index)| order_index),
+            description,
+            equipment_models,
+            tags,
+        },
+        nodes: export_nodes,
+        connections: export_connections,
+    };
+
+    tracing::info!("✅ Exported issue {} ({} nodes, {} connections)", category, nodes.len(), connections.len());
+
+    Ok((export_data, node_ids))
+}
+
+/// Indices of connections that repeat an earlier connection's from/to/label
+fn find_duplicate_connections(connections: &[ConnectionExportData]) -> Vec<usize> {
+    let mut seen = std::collections::HashSet::new();
+    connections
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !seen.insert((c.from_node_index, c.to_node_index, c.label.trim())))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// GET /api/admin/issues/export-all
+/// Export all issues as a JSON array
+pub async fn export_all_issues(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<IssueExportData>>> {
+    tracing::info!("📦 Exporting all issues");
+
+    // Get all distinct categories (excluding 'root' and utility categories), switched off or not
+    let categories: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT category FROM nodes
+         WHERE category NOT IN ('root', 'electrical', 'general', 'mechanical')
+         ORDER BY category ASC"
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut all_exports = Vec::new();
+
+    for category in categories {
+        // Reuse the single export logic
+        match load_issue_export(&state, &category).await {
+            Ok(export_data) => all_exports.push(export_data),
+            Err(e) => {
+                tracing::warn!("⚠️  Failed to export issue {}: {:?}", category, e);
+                continue;
+            }
+        }
+    }
+
+    tracing::info!("✅ Exported {} issues", all_exports.len());
+
+    Ok(Json(all_exports))
+}
+
+/// POST /api/admin/issues/import?mode=
+/// Import one or more issues from JSON, skipping, overwriting or merging into existing categories
+pub async fn import_issues(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Query(query): Query<ImportIssuesQuery>,
+    Json(data): Json<Vec<IssueExportData>>,
+) -> ApiResult<Json<ImportResult>> {
+    category_access::ensure_can_edit(&state.db, &auth, data.iter().map(|d| d.issue.category.as_str())).await?;
+
+    Ok(Json(import_issue_data(&state, data, query.duplicates, query.mode).await))
+}
+
+/// POST /api/admin/issues/import/external?format=&category=
+/// Convert a tree exported from another tool and import it as a new issue
+pub async fn import_external(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Query(query): Query<ExternalImportQuery>,
+    body: String,
+) -> ApiResult<Json<ImportResult>> {
+    let adapter = import_adapters::adapter_for(&query.format).ok_or_else(|| {
+        let formats: Vec<&str> = import_adapters::adapters().iter().map(|a| a.format()).collect();
+        ApiError::validation(vec![(
+            "format".to_string(),
+            format!("Unsupported format. Expected one of: {}", formats.join(", ")),
+        )])
+    })?;
+
+    let category = query.category.trim();
+    if category.is_empty() {
+        return Err(ApiError::validation(vec![(
+            "category".to_string(),
+            "Category is required".to_string(),
+        )]));
+    }
+    category_access::ensure_can_edit(&state.db, &auth, [category]).await?;
+
+    let target = import_adapters::AdapterTarget {
+        category: category.to_string(),
+        name: query.name.clone().filter(|n| !n.trim().is_empty()),
+        display_category: query.display_category.clone(),
+    };
+
+    let data = adapter
+        .convert(&body, &target)
+        .map_err(|e| ApiError::validation(vec![("body".to_string(), e)]))?;
+
+    tracing::info!("📥 Converted {} document into issue '{}'", adapter.format(), category);
+
+    Ok(Json(import_issue_data(&state, vec![data], query.duplicates, ImportMode::Skip).await))
+}
+
+/// POST /api/admin/issues/import/bundle?mode=&duplicates=
+/// Import an issue from a ZIP bundle (see `export?format=zip`), storing its attachments on the new nodes
+///
+/// Attachments go through the usual upload checks; one that fails is reported
+/// without undoing the import. Nodes matched by `mode=merge` keep their own attachments.
+pub async fn import_bundle(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Query(query): Query<ImportIssuesQuery>,
+    body: axum::body::Bytes,
+) -> ApiResult<Json<BundleImportResult>> {
+    let user_id = acting_user(&auth)?;
+    let service = crate::uploads::service().ok();
+    let max_attachment_bytes = service.map_or(issue_bundle::MAX_BUNDLE_BYTES, |s| s.config.max_bytes);
+    let bundle = tokio::task::spawn_blocking(move || issue_bundle::read_bundle(&body, max_attachment_bytes))
+        .await
+        .map_err(|e| ApiError::internal(format!("Bundle import failed: {}", e)))?
+        .map_err(ApiError::bad_request)?;
+
+    category_access::ensure_can_edit(&state.db, &auth, [bundle.data.issue.category.as_str()]).await?;
+    let service = match service {
+        Some(service) => Some(service),
+        None if bundle.attachments.is_empty() => None,
+        None => return Err(ApiError::bad_request("The bundle has attachments, but upload storage is not configured")),
+    };
+    tracing::info!(
+        "📥 Importing bundle for '{}' exported {} ({} attachment(s))",
+        bundle.data.issue.category,
+        bundle.exported_at,
+        bundle.attachments.len()
+    );
+
+    let result = import_issue_data(&state, vec![bundle.data], query.duplicates, query.mode).await;
+    let mut attachments_count = 0;
+    let mut attachment_errors = Vec::new();
+    if let (Some(service), Some(imported)) = (service, result.success.first()) {
+        for attachment in bundle.attachments {
+            let Some(node_id) = imported.node_ids.get(attachment.node_index).copied().flatten() else {
+                continue;
+            };
+            let filename = attachment.filename.clone();
+            let stored = service
+                .store(
+                    &state.db,
+                    NewUpload {
+                        filename: attachment.filename,
+                        bytes: attachment.bytes,
+                        owner: Some((UploadOwnerType::Node, node_id.to_string())),
+                        uploaded_by: Some(user_id),
+                    },
+                )
+                .await;
+            match stored {
+                Ok(_) => attachments_count += 1,
+                Err(e) => attachment_errors.push(format!("{}: {}", filename, e.message())),
+            }
+        }
+    }
+
+    Ok(Json(BundleImportResult {
+        result,
+        attachments_count,
+        attachment_errors,
+    }))
+}
+
+/// Lay out or normalize an imported category; the import stands if this fails
+async fn tidy_layout(state: &AppState, category: &str) {
+    match layout::tidy_category(&state.db, category).await {
+        Ok(result) if result.moved > 0 => {
+            tracing::info!("📐 Laid out imported issue {}: {} node(s) moved, {} placed", category, result.moved, result.placed)
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("⚠️  Failed to normalize the layout of imported issue {}: {}", category, e),
+    }
+}
+
+/// Nodes of an issue being merged into: IDs by semantic ID, and whether the issue is active
+async fn merge_targets(conn: &mut sqlx::PgConnection, category: &str) -> Result<(std::collections::HashMap<String, Uuid>, bool), sqlx::Error> {
+    let rows = sqlx::query_as::<_, (Uuid, Option<String>, Option<bool>)>(
+        "SELECT id, semantic_id, is_active FROM nodes WHERE category = $1 ORDER BY created_at ASC",
+    )
+    .bind(category)
+    .fetch_all(conn)
+    .await?;
+
+    let is_active = rows.first().and_then(|(_, _, active)| *active).unwrap_or(true);
+    let ids = rows.into_iter().filter_map(|(id, semantic_id, _)| Some((semantic_id?, id))).collect();
+    Ok((ids, is_active))
+}
+
+/// An entry point of an issue being overwritten, with its start question's semantic ID
+#[derive(Debug, sqlx::FromRow)]
+struct DisplacedEntryPoint {
+    name: String,
+    semantic_id: Option<String>,
+    description: Option<String>,
+    is_active: bool,
+    created_by: Option<Uuid>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// What clearing an issue for an overwrite removed
+#[derive(Debug, Default)]
+struct ClearedIssue {
+    node_ids: Vec<Uuid>,
+    entry_points: Vec<DisplacedEntryPoint>,
+    revisions: usize,
+}
+
+/// Delete an issue's nodes ahead of an overwrite, keeping its entry points aside to re-attach
+async fn clear_for_overwrite(conn: &mut sqlx::PgConnection, category: &str) -> Result<ClearedIssue, sqlx::Error> {
+    let entry_points = sqlx::query_as::<_, DisplacedEntryPoint>(
+        "SELECT e.name, n.semantic_id, e.description, e.is_active, e.created_by, e.created_at
+         FROM entry_points e
+         JOIN nodes n ON n.id = e.node_id
+         WHERE n.category = $1
+         ORDER BY e.name",
+    )
+    .bind(category)
+    .fetch_all(&mut *conn)
+    .await?;
+    let revisions: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM node_revisions WHERE node_id IN (SELECT id FROM nodes WHERE category = $1)",
+    )
+    .bind(category)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let node_ids = delete_category_nodes(conn, category).await?;
+    Ok(ClearedIssue {
+        node_ids,
+        entry_points,
+        revisions: revisions as usize,
+    })
+}
+
+/// Index of the imported question an entry point moves to: the one with its old start's semantic ID
+fn entry_point_target(nodes: &[NodeExportData], semantic_id: Option<&str>) -> Option<usize> {
+    let semantic_id = semantic_ids::normalize(semantic_id)?;
+    nodes.iter().position(|node| {
+        node.node_type == "question" && semantic_ids::normalize(node.semantic_id.as_deref()).as_deref() == Some(semantic_id.as_str())
+    })
+}
+
+/// Shared import pipeline: each issue is created (or overwritten, or merged into) in its own transaction
+pub(crate) async fn import_issue_data(
+    state: &AppState,
+    data: Vec<IssueExportData>,
+    duplicate_mode: DuplicateConnectionMode,
+    mode: ImportMode,
+) -> ImportResult {
+    tracing::info!("📥 Importing {} issue(s)", data.len());
+
+    let mut success_list = Vec::new();
+    let mut error_list = Vec::new();
+
+    for (issue_index, issue_data) in data.into_iter().enumerate() {
+        let category = issue_data.issue.category.clone();
+
+        // Check if category already exists
+        let existing_count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM nodes WHERE category = $1"
+        )
+        .bind(&category)
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(0);
+
+        let exists = existing_count > 0;
+        if exists && mode == ImportMode::Skip {
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: format!(
+                    "Issue with category '{}' already exists. Delete it first, choose a different category, or import with mode=overwrite or mode=merge.",
+                    category
+                ),
+                details: vec![],
+                fields: vec![ValidationField::at(
+                    format!("/{}/issue/category", issue_index),
+                    "Category already exists",
+                )],
+            });
+            continue;
+        }
+
+        // Identical from/to/label connections render as stacked edges in the editor
+        let duplicates = find_duplicate_connections(&issue_data.connections);
+        if !duplicates.is_empty() && duplicate_mode == DuplicateConnectionMode::Reject {
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: format!(
+                    "Found {} duplicate connection(s). Remove them or import with duplicates=dedupe.",
+                    duplicates.len()
+                ),
+                details: duplicates
+                    .iter()
+                    .map(|&i| {
+                        let c = &issue_data.connections[i];
+                        format!(
+                            "connections[{}]: {} -> {} '{}'",
+                            i, c.from_node_index, c.to_node_index, c.label
+                        )
+                    })
+                    .collect(),
+                fields: duplicates
+                    .iter()
+                    .map(|&i| ValidationField::at(format!("/{}/connections/{}", issue_index, i), "Duplicate connection"))
+                    .collect(),
+            });
+            continue;
+        }
+
+        // A semantic ID names at most one node per category
+        let repeated = semantic_ids::find_duplicates(issue_data.nodes.iter().map(|n| n.semantic_id.as_deref()));
+        if !repeated.is_empty() {
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: format!("Found {} node(s) repeating an earlier semantic ID.", repeated.len()),
+                details: repeated
+                    .iter()
+                    .map(|&i| format!("nodes[{}]: '{}'", i, issue_data.nodes[i].semantic_id.as_deref().unwrap_or_default().trim()))
+                    .collect(),
+                fields: repeated
+                    .iter()
+                    .map(|&i| ValidationField::at(format!("/{}/nodes/{}/semantic_id", issue_index, i), "Duplicate semantic ID"))
+                    .collect(),
+            });
+            continue;
+        }
+
+        // Issue metadata is checked as when an issue is created
+        let metadata = (|| {
+            let issue = &issue_data.issue;
+            Ok::<_, (&str, String)>((
+                issue_metadata::normalize_name(&issue.name).map_err(|e| ("name", e))?,
+                issue_metadata::normalize_description(issue.description.as_deref()).map_err(|e| ("description", e))?,
+                issue_metadata::normalize_equipment_models(issue.equipment_models.as_deref().unwrap_or_default())
+                    .map_err(|e| ("equipment_models", e))?,
+                issue_metadata::normalize_tags(issue.tags.as_deref().unwrap_or_default()).map_err(|e| ("tags", e))?,
+            ))
+        })();
+        let (name, description, equipment_models, tags) = match metadata {
+            Ok(metadata) => metadata,
+            Err((field, message)) => {
+                error_list.push(ImportError {
+                    category: category.clone(),
+                    error: format!("Invalid issue {}: {}", field, message),
+                    details: vec![],
+                    fields: vec![ValidationField::at(format!("/{}/issue/{}", issue_index, field), message)],
+                });
+                continue;
+            }
+        };
+
+        let connections_to_create: Vec<(usize, &ConnectionExportData)> = issue_data.connections
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !duplicates.contains(i))
+            .collect();
+
+        // Start transaction for atomicity
+        let mut tx = match state.db.begin().await {
+            Ok(tx) => tx,
+            Err(e) => {
+                error_list.push(ImportError {
+                    category: category.clone(),
+                    error: format!("Failed to start transaction: {}", e),
+                    details: vec![],
+                    fields: vec![],
+                });
+                continue;
+            }
+        };
+
+        // Validate nodes
+        if issue_data.nodes.is_empty() {
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: "Issue must have at least one node".to_string(),
+                details: vec![],
+                fields: vec![ValidationField::at(format!("/{}/nodes", issue_index), "At least one node is required")],
+            });
+            continue;
+        }
+
+        // Clear out or look up the existing issue; merged nodes keep its active state
+        let mut is_active = issue_data.issue.is_active.unwrap_or(true);
+        let mut cleared = ClearedIssue::default();
+        let mut merge_ids = std::collections::HashMap::new();
+        let prepared = match mode {
+            ImportMode::Overwrite if exists => clear_for_overwrite(&mut tx, &category).await.map(|c| cleared = c),
+            ImportMode::Merge if exists => merge_targets(&mut tx, &category).await.map(|(ids, active)| {
+                merge_ids = ids;
+                is_active = active;
+            }),
+            _ => Ok(()),
+        };
+        if let Err(e) = prepared {
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: format!("Failed to prepare existing issue: {}", e),
+                details: vec![],
+                fields: vec![],
+            });
+            continue;
+        }
+
+        // Create nodes and build mapping
+        let mut node_ids = Vec::new();
+        // Whether node_ids[i] was created by this import (rather than matched for a merge)
+        let mut created = Vec::new();
+        let mut error_msg: Option<(String, ValidationField)> = None;
+
+        for (node_index, node_data) in issue_data.nodes.iter().enumerate() {
+            if let Some(&existing_id) = semantic_ids::normalize(node_data.semantic_id.as_deref())
+                .and_then(|semantic_id| merge_ids.get(&semantic_id))
+            {
+                node_ids.push(existing_id);
+                created.push(false);
+                continue;
+            }
+
+            let node_id = Uuid::new_v4();
+            let node_type = node_data.node_type.as_str();
+
+            // Validate node_type (lowercase as per model definition)
+            if !["question", "conclusion", "subflow", "return"].contains(&node_type) {
+                error_msg = Some((
+                    format!("Invalid node_type: '{}'. Must be 'question', 'conclusion', 'subflow' or 'return'", node_type),
+                    ValidationField::at(
+                        format!("/{}/nodes/{}/node_type", issue_index, node_index),
+                        "Must be 'question', 'conclusion', 'subflow' or 'return'",
+                    ),
+                ));
+                break;
+            }
+            if (node_type == "subflow") != node_data.subflow_category.is_some() {
+                error_msg = Some((
+                    "Sub-flow nodes need a subflow_category, and only they can have one".to_string(),
+                    ValidationField::at(
+                        format!("/{}/nodes/{}/subflow_category", issue_index, node_index),
+                        "Required on sub-flow nodes and not allowed on others",
+                    ),
+                ));
+                break;
+            }
+            let content = match node_data.content.clone().map(content::sanitize).transpose() {
+                Ok(content) => content.flatten(),
+                Err(errors) => {
+                    let (pointer, message) = errors.into_iter().next().unwrap_or_default();
+                    error_msg = Some((
+                        format!("Invalid content: {}", message),
+                        ValidationField::at(format!("/{}/nodes/{}/content{}", issue_index, node_index, pointer), message),
+                    ));
+                    break;
+                }
+            };
+
+            match sqlx::query!(
+                "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, subflow_category, content, is_active)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+                node_id,
+                &category,
+                node_type,
+                &node_data.text,
+                semantic_ids::normalize(node_data.semantic_id.as_deref()),
+                issue_data.issue.display_category.as_deref(),
+                node_data.position_x,
+                node_data.position_y,
+                node_data.kb_url.as_deref(),
+                node_data.doc_reference.as_deref(),
+                node_data.option_order as _,
+                node_data.answer_mode as _,
+                node_data.subflow_category.as_deref(),
+                content.as_ref().map(sqlx::types::Json) as _,
+                is_active,
+            )
+            .execute(&mut *tx)
+            .await {
+                Ok(_) => {
+                    node_ids.push(node_id);
+                    created.push(true);
+                }
+                Err(e) => {
+                    error_msg = Some((
+                        format!("Failed to create node: {}", e),
+                        ValidationField::at(format!("/{}/nodes/{}", issue_index, node_index), "Node could not be created"),
+                    ));
+                    break;
+                }
+            }
+        }
+
+        // If there was an error, rollback and continue to next issue
+        if let Some((err, field)) = error_msg {
+            let _ = tx.rollback().await;
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: err,
+                details: vec![],
+                fields: vec![field],
+            });
+            continue;
+        }
+
+        // Create connections
+        let mut connections_created = 0;
+        let mut conn_error_msg: Option<(String, ValidationField)> = None;
+
+        for (conn_index, conn_data) in connections_to_create {
+            // Validate indices
+            let out_of_bounds = [("from_node_index", conn_data.from_node_index), ("to_node_index", conn_data.to_node_index)]
+                .into_iter()
+                .find(|(_, index)| *index >= node_ids.len());
+            if let Some((key, index)) = out_of_bounds {
+                conn_error_msg = Some((
+                    "Invalid connection: node index out of bounds".to_string(),
+                    ValidationField::at(
+                        format!("/{}/connections/{}/{}", issue_index, conn_index, key),
+                        format!("Node index {} is out of bounds ({} nodes)", index, node_ids.len()),
+                    ),
+                ));
+                break;
+            }
+
+            let condition = match conn_data.condition.as_deref().map(conditions::normalize).transpose() {
+                Ok(condition) => condition.flatten(),
+                Err(e) => {
+                    conn_error_msg = Some((
+                        "Invalid connection condition".to_string(),
+                        ValidationField::at(format!("/{}/connections/{}/condition", issue_index, conn_index), e),
+                    ));
+                    break;
+                }
+            };
+
+            let from_id = node_ids[conn_data.from_node_index];
+            let to_id = node_ids[conn_data.to_node_index];
+
+            // Answers a merged issue already has between the same nodes stay as they are
+            if !created[conn_data.from_node_index] && !created[conn_data.to_node_index] {
+                let present = sqlx::query_scalar::<_, bool>(
+                    "SELECT EXISTS(SELECT 1 FROM connections WHERE from_node_id = $1 AND to_node_id = $2 AND label = $3)",
+                )
+                .bind(from_id)
+                .bind(to_id)
+                .bind(&conn_data.label)
+                .fetch_one(&mut *tx)
+                .await;
+                match present {
+                    Ok(true) => continue,
+                    Ok(false) => {}
+                    Err(e) => {
+                        conn_error_msg = Some((
+                            format!("Failed to check existing connection: {}", e),
+                            ValidationField::at(
+                                format!("/{}/connections/{}", issue_index, conn_index),
+                                "Connection could not be created",
+                            ),
+                        ));
+                        break;
+                    }
+                }
+            }
+
+            match sqlx::query!(
+                "INSERT INTO connections (from_node_id, to_node_id, label, order_index, condition, unsure_route, is_active)
+                 VALUES ($1, $2, $3, $4, $5, $6, true)",
+                from_id,
+                to_id,
+                &conn_data.label,
+                conn_data.order_index,
+                condition,
+                conn_data.unsure_route,
+            )
+            .execute(&mut *tx)
+            .await {
+                Ok(_) => connections_created += 1,
+                Err(e) => {
+                    conn_error_msg = Some((
+                        format!("Failed to create connection: {}", e),
+                        ValidationField::at(
+                            format!("/{}/connections/{}", issue_index, conn_index),
+                            "Connection could not be created",
+                        ),
+                    ));
+                    break;
+                }
+            }
+        }
+
+        // "I'm not sure" targets, once every node exists
+        if conn_error_msg.is_none() {
+            for (node_index, node_data) in issue_data.nodes.iter().enumerate() {
+                let Some(target_index) = node_data.not_sure_node_index else {
+                    continue;
+                };
+                let pointer = format!("/{}/nodes/{}/not_sure_node_index", issue_index, node_index);
+                let problem = if node_data.node_type != "question" {
+                    Some("Only questions can offer \"I'm not sure\"".to_string())
+                } else if target_index >= node_ids.len() || target_index == node_index {
+                    Some(format!("Node index {} is not another node of this issue", target_index))
+                } else {
+                    None
+                };
+                if let Some(problem) = problem {
+                    conn_error_msg = Some(("Invalid \"I'm not sure\" target".to_string(), ValidationField::at(pointer, problem)));
+                    break;
+                }
+                if !created[node_index] {
+                    continue;
+                }
+
+                if let Err(e) = sqlx::query("UPDATE nodes SET not_sure_node_id = $2 WHERE id = $1")
+                    .bind(node_ids[node_index])
+                    .bind(node_ids[target_index])
+                    .execute(&mut *tx)
+                    .await
+                {
+                    conn_error_msg = Some((
+                        format!("Failed to set \"I'm not sure\" target: {}", e),
+                        ValidationField::at(pointer, "Target could not be set"),
+                    ));
+                    break;
+                }
+            }
+        }
+
+        // Entry points of an overwritten issue follow their start question to its replacement
+        let mut entry_points_dropped = Vec::new();
+        if conn_error_msg.is_none() {
+            for entry_point in &cleared.entry_points {
+                let Some(index) = entry_point_target(&issue_data.nodes, entry_point.semantic_id.as_deref()) else {
+                    entry_points_dropped.push(entry_point.name.clone());
+                    continue;
+                };
+                if let Err(e) = sqlx::query(
+                    "INSERT INTO entry_points (name, node_id, description, is_active, created_by, created_at)
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(&entry_point.name)
+                .bind(node_ids[index])
+                .bind(&entry_point.description)
+                .bind(entry_point.is_active)
+                .bind(entry_point.created_by)
+                .bind(entry_point.created_at)
+                .execute(&mut *tx)
+                .await
+                {
+                    conn_error_msg = Some((
+                        format!("Failed to keep entry point '{}': {}", entry_point.name, e),
+                        ValidationField::at(format!("/{}/nodes/{}", issue_index, index), "Entry point could not be kept"),
+                    ));
+                    break;
+                }
+            }
+        }
+
+        // Offer the issue on the start question under its name, like a newly created issue
+        // (an issue merged into is already offered)
+        let merged = exists && mode == ImportMode::Merge;
+        if conn_error_msg.is_none() && !merged {
+            let root_index = issue_data.nodes
+                .iter()
+                .position(|n| n.semantic_id.as_deref().is_some_and(|s| s.ends_with("_start")))
+                .unwrap_or(0);
+            if let Err(e) = sqlx::query(
+                "INSERT INTO connections (from_node_id, to_node_id, label, order_index, is_active)
+                 SELECT n.id, $1, $2,
+                        COALESCE($3, (SELECT COUNT(*) FROM connections WHERE from_node_id = n.id)::int),
+                        $4
+                 FROM nodes n
+                 WHERE n.semantic_id = 'start'"
+            )
+            .bind(node_ids[root_index])
+            .bind(&name)
+            .bind(issue_data.issue.start_order_index)
+            .bind(is_active)
+            .execute(&mut *tx)
+            .await
+            {
+                conn_error_msg = Some((
+                    format!("Failed to link issue to the start question: {}", e),
+                    ValidationField::at(format!("/{}/issue/name", issue_index), "Issue could not be linked"),
+                ));
+            }
+        }
+
+        // A merge keeps the issue's own metadata
+        if conn_error_msg.is_none() {
+            let on_conflict = if merged {
+                "DO NOTHING"
+            } else {
+                "DO UPDATE SET
+                    name = EXCLUDED.name, description = EXCLUDED.description,
+                    equipment_models = EXCLUDED.equipment_models, tags = EXCLUDED.tags"
+            };
+            if let Err(e) = sqlx::query(&format!(
+                "INSERT INTO issues (category, name, description, equipment_models, tags)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (category) {}",
+                on_conflict
+            ))
+            .bind(&category)
+            .bind(&name)
+            .bind(&description)
+            .bind(&equipment_models)
+            .bind(&tags)
+            .execute(&mut *tx)
+            .await
+            {
+                conn_error_msg = Some((
+                    format!("Failed to save issue metadata: {}", e),
+                    ValidationField::at(format!("/{}/issue", issue_index), "Issue metadata could not be saved"),
+                ));
+            }
+        }
+
+        // If there was a connection error, rollback and continue to next issue
+        if let Some((err, field)) = conn_error_msg {
+            let _ = tx.rollback().await;
+            error_list.push(ImportError {
+                category: category.clone(),
+                error: err,
+                details: vec![],
+                fields: vec![field],
+            });
+            continue;
+        }
+
+        // Commit transaction
+        match tx.commit().await {
+            Ok(_) => {
+                for node_id in &cleared.node_ids {
+                    if let Err(e) = uploads::delete_node_attachments(&state.db, *node_id).await {
+                        tracing::warn!("Failed to remove attachments of replaced node {}: {}", node_id, e.message());
+                    }
+                }
+                tidy_layout(state, &category).await;
+                state.invalidate_issue(&category).await;
+                let nodes_created = created.iter().filter(|c| **c).count();
+                success_list.push(ImportSuccess {
+                    category: category.clone(),
+                    name: name.clone(),
+                    nodes_count: nodes_created,
+                    connections_count: connections_created,
+                    duplicates_removed: duplicates.len(),
+                    nodes_replaced: cleared.node_ids.len(),
+                    entry_points_dropped,
+                    revisions_dropped: cleared.revisions,
+                    nodes_matched: node_ids.len() - nodes_created,
+                    node_ids: node_ids.iter().zip(&created).map(|(id, created)| created.then_some(*id)).collect(),
+                });
+                tracing::info!("✅ Imported issue: {} ({} nodes, {} connections)",
+                    category, nodes_created, connections_created);
+            }
+            Err(e) => {
+                error_list.push(ImportError {
+                    category: category.clone(),
+                    error: format!("Failed to commit transaction: {}", e),
+                    details: vec![],
+                    fields: vec![],
+                });
+            }
+        }
+    }
+
+    tracing::info!("📥 Import complete: {} succeeded, {} failed", success_list.len(), error_list.len());
+
+    if !success_list.is_empty() {
+        cache_warming::schedule(state.clone(), "import");
+    }
+
+    ImportResult {
+        success: success_list,
+        errors: error_list,
+    }
+}
+
+/// GET /api/admin/issues/:category/export/incremental?since=
+/// Export nodes/connections changed after `since`, plus tombstones for deletions
+pub async fn export_issue_incremental(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+    Query(query): Query<IncrementalExportQuery>,
+) -> ApiResult<Json<IncrementalExportData>> {
+    let since = match &query.since {
+        Some(since) => Some(
+            chrono::DateTime::parse_from_rfc3339(since)
+                .map_err(|_| {
+                    ApiError::validation(vec![(
+                        "since".to_string(),
+                        "Must be an RFC 3339 timestamp".to_string(),
+                    )])
+                })?
+                .with_timezone(&chrono::Utc),
+        ),
+        None => None,
     };
 
-    // Export connections (with indices instead of UUIDs)
-    let export_connections: Vec<ConnectionExportData> = connections.iter().filter_map(|c| {
-        let from_index = id_to_index.get(&c.from_node_id)?;
-        let to_index = id_to_index.get(&c.to_node_id)?;
-        Some(ConnectionExportData {
-            from_node_index: *from_index,
-            to_node_index: *to_index,
-            label: c.label.clone(),
-            order_index: c.order_index,
+    // Read everything from one snapshot so exported_at lines up with the data
+    let mut tx = state.db.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .execute(&mut *tx)
+        .await?;
+
+    // Writers still in flight stamp rows with their own start time but stay invisible
+    // to this snapshot, so end the window before the oldest of them; the next sync
+    // picks their changes up instead of skipping past them
+    let exported_at: chrono::DateTime<chrono::Utc> = sqlx::query_scalar(
+        "SELECT LEAST(NOW(), (
+            SELECT MIN(xact_start) FROM pg_stat_activity
+            WHERE backend_xid IS NOT NULL AND pid <> pg_backend_pid()
+         ))"
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let category_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)
+             OR EXISTS(SELECT 1 FROM deleted_records WHERE category = $1)"
+    )
+    .bind(&category)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if !category_exists {
+        return Err(ApiError::not_found("Issue category not found"));
+    }
+
+    let nodes = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE category = $1 AND ($2::timestamptz IS NULL OR updated_at > $2) AND updated_at <= $3
+         ORDER BY created_at ASC"
+    )
+    .bind(&category)
+    .bind(since)
+    .bind(exported_at)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let connections = sqlx::query_as::<_, Connection>(
+        "SELECT c.id, c.from_node_id, c.to_node_id, c.label, c.order_index, c.weight, c.condition, c.unsure_route, c.is_active, c.created_at, c.updated_at
+         FROM connections c
+         JOIN nodes n ON n.id = c.from_node_id
+         WHERE n.category = $1 AND ($2::timestamptz IS NULL OR c.updated_at > $2) AND c.updated_at <= $3
+         ORDER BY c.from_node_id, c.order_index ASC"
+    )
+    .bind(&category)
+    .bind(since)
+    .bind(exported_at)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // A snapshot has nothing to delete; tombstones only matter for increments
+    let deleted = match since {
+        Some(since) => {
+            sqlx::query_as::<_, Tombstone>(
+                "SELECT entity_type, entity_id, deleted_at
+                 FROM deleted_records
+                 WHERE category = $1 AND deleted_at > $2 AND deleted_at <= $3
+                 ORDER BY deleted_at ASC"
+            )
+            .bind(&category)
+            .bind(since)
+            .bind(exported_at)
+            .fetch_all(&mut *tx)
+            .await?
+        }
+        None => vec![],
+    };
+
+    tx.commit().await?;
+
+    tracing::info!(
+        "📦 Incremental export of {} ({} nodes, {} connections, {} deletions)",
+        category,
+        nodes.len(),
+        connections.len(),
+        deleted.len()
+    );
+
+    Ok(Json(IncrementalExportData {
+        category,
+        since: query.since,
+        exported_at: exported_at.to_rfc3339(),
+        nodes,
+        connections,
+        deleted,
+    }))
+}
+
+/// Query parameters for get_issue_checksum
+#[derive(Debug, Deserialize)]
+pub struct ChecksumQuery {
+    /// Leave editor positions out of the hash (default: false)
+    #[serde(default)]
+    pub ignore_layout: bool,
+}
+
+/// Deterministic content hash of a category, for sync drift detection
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueChecksum {
+    pub category: String,
+    pub algorithm: String,
+    /// Hash over nodes_checksum and connections_checksum
+    pub checksum: String,
+    pub nodes_checksum: String,
+    pub connections_checksum: String,
+    pub node_count: usize,
+    pub connection_count: usize,
+    pub ignore_layout: bool,
+}
+
+/// Hash nodes and connections independently of row order
+///
+/// Rows are sorted by ID and serialized field by field as JSON arrays, so the
+/// hash only changes when content does. Timestamps are left out (they differ
+/// between environments after a sync) and so are connection weights, which
+/// each environment recomputes from its own traffic.
+fn content_checksums(nodes: &[Node], connections: &[Connection], ignore_layout: bool) -> (String, String, String) {
+    use sha2::{Digest, Sha256};
+
+    let mut nodes: Vec<&Node> = nodes.iter().collect();
+    nodes.sort_by_key(|n| n.id);
+    let mut node_hasher = Sha256::new();
+    for node in nodes {
+        let (x, y) = if ignore_layout { (None, None) } else { (node.position_x, node.position_y) };
+        let row = json!([
+            node.id,
+            node.node_type,
+            node.text,
+            node.semantic_id,
+            node.display_category,
+            x,
+            y,
+            node.kb_url,
+            node.doc_reference,
+            node.option_order,
+            node.is_active,
+            node.answer_mode,
+            node.not_sure_node_id,
+            node.subflow_category,
+            node.content,
+        ]);
+        node_hasher.update(row.to_string().as_bytes());
+        node_hasher.update(b"\n");
+    }
+
+    let mut connections: Vec<&Connection> = connections.iter().collect();
+    connections.sort_by_key(|c| c.id);
+    let mut connection_hasher = Sha256::new();
+    for connection in connections {
+        let row = json!([
+            connection.id,
+            connection.from_node_id,
+            connection.to_node_id,
+            connection.label,
+            connection.order_index,
+            connection.is_active,
+            connection.condition,
+            connection.unsure_route,
+        ]);
+        connection_hasher.update(row.to_string().as_bytes());
+        connection_hasher.update(b"\n");
+    }
+
+    let nodes_checksum = format!("{:x}", node_hasher.finalize());
+    let connections_checksum = format!("{:x}", connection_hasher.finalize());
+    let checksum = format!(
+        "{:x}",
+        Sha256::digest(format!("nodes:{}\nconnections:{}\n", nodes_checksum, connections_checksum).as_bytes())
+    );
+
+    (checksum, nodes_checksum, connections_checksum)
+}
+
+/// GET /api/admin/issues/:category/checksum
+/// Content hash of a category's nodes and connections; equal hashes mean two environments are in sync
+pub async fn get_issue_checksum(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+    Query(query): Query<ChecksumQuery>,
+) -> ApiResult<Json<IssueChecksum>> {
+    // Nodes and connections from one snapshot, so a concurrent edit can't split the hash
+    let mut tx = state.db.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .execute(&mut *tx)
+        .await?;
+
+    let nodes = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE category = $1"
+    )
+    .bind(&category)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if nodes.is_empty() {
+        return Err(ApiError::not_found("Issue category not found"));
+    }
+
+    let connections = sqlx::query_as::<_, Connection>(
+        "SELECT c.id, c.from_node_id, c.to_node_id, c.label, c.order_index, c.weight, c.condition, c.unsure_route, c.is_active, c.created_at, c.updated_at
+         FROM connections c
+         JOIN nodes n ON n.id = c.from_node_id
+         WHERE n.category = $1"
+    )
+    .bind(&category)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let (checksum, nodes_checksum, connections_checksum) =
+        content_checksums(&nodes, &connections, query.ignore_layout);
+
+    Ok(Json(IssueChecksum {
+        category,
+        algorithm: "sha256".to_string(),
+        checksum,
+        nodes_checksum,
+        connections_checksum,
+        node_count: nodes.len(),
+        connection_count: connections.len(),
+        ignore_layout: query.ignore_layout,
+    }))
+}
+
+/// One category in the kiosk manifest
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ManifestCategory {
+    pub category: String,
+    pub name: String,
+    pub display_category: Option<String>,
+    /// Content hash (editor layout excluded); changes whenever the category's
+    /// questions or answers do
+    pub version: String,
+    /// Latest edit or deletion in the category
+    pub last_modified: String,
+    #[ts(type = "number")]
+    pub node_count: usize,
+}
+
+/// Active categories with their content versions
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CategoryManifest {
+    /// Hash over every category's version; unchanged means nothing to refresh
+    pub version: String,
+    pub generated_at: String,
+    pub categories: Vec<ManifestCategory>,
+}
+
+/// Response header carrying the manifest signature
+const MANIFEST_SIGNATURE_HEADER: &str = "x-manifest-signature";
+
+/// Key kiosks share with the server to verify the manifest (MANIFEST_SIGNING_SECRET)
+///
+/// Deliberately not defaulted to JWT_SECRET: whoever can verify an HMAC can
+/// also forge one, and kiosks must not be able to mint tokens.
+fn manifest_signing_secret() -> Option<&'static [u8]> {
+    static SECRET: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    SECRET
+        .get_or_init(|| std::env::var("MANIFEST_SIGNING_SECRET").ok().filter(|s| !s.is_empty()))
+        .as_deref()
+        .map(str::as_bytes)
+}
+
+/// HMAC-SHA256 of the exact response body, as sent in `X-Manifest-Signature`
+fn sign_manifest(secret: &[u8], body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(body);
+    let signature: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", signature)
+}
+
+/// Hash of the category versions, in category order
+fn manifest_version(categories: &[ManifestCategory]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for category in categories {
+        hasher.update(format!("{}:{}\n", category.category, category.version).as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// GET /api/kiosk/manifest
+/// Active categories with content hashes and last-modified times (public)
+///
+/// Kiosks keep the manifest's `version` and refresh offline bundles only for
+/// categories whose `version` changed. The `ETag` is the manifest version, so
+/// a request with `If-None-Match` gets an empty 304 when nothing changed. With
+/// MANIFEST_SIGNING_SECRET set the body is signed in `X-Manifest-Signature`.
+pub async fn get_kiosk_manifest(State(state): State<AppState>, headers: HeaderMap) -> ApiResult<Response> {
+    // One snapshot, so a concurrent edit can't leave a category half-hashed
+    let mut tx = state.db.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .execute(&mut *tx)
+        .await?;
+
+    // A category is active while its root question is
+    let roots = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT r.category,
+                COALESCE(l.label, r.display_category, r.category),
+                r.display_category
+         FROM nodes r
+         LEFT JOIN LATERAL (
+             SELECT c.label
+             FROM connections c
+             JOIN nodes g ON g.id = c.from_node_id AND g.semantic_id = 'start'
+             WHERE c.to_node_id = r.id AND c.is_active = true
+             LIMIT 1
+         ) l ON true
+         WHERE r.semantic_id = r.category || '_start' AND r.is_active = true
+           AND r.category NOT IN (SELECT draft_category FROM issue_drafts)
+           AND r.category NOT IN (SELECT category FROM issue_archives)
+           AND r.category NOT IN (SELECT category FROM issues WHERE active_from > NOW() OR active_until <= NOW())
+         ORDER BY r.category"
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    let category_names: Vec<&str> = roots.iter().map(|(category, _, _)| category.as_str()).collect();
+
+    let nodes = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE category = ANY($1)"
+    )
+    .bind(&category_names)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let connections = sqlx::query_as::<_, Connection>(
+        "SELECT c.id, c.from_node_id, c.to_node_id, c.label, c.order_index, c.weight, c.condition, c.unsure_route, c.is_active, c.created_at, c.updated_at
+         FROM connections c
+         JOIN nodes n ON n.id = c.from_node_id
+         WHERE n.category = ANY($1)"
+    )
+    .bind(&category_names)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let deletions: std::collections::HashMap<String, chrono::DateTime<chrono::Utc>> =
+        sqlx::query_as::<_, (String, chrono::DateTime<chrono::Utc>)>(
+            "SELECT category, MAX(deleted_at) FROM deleted_records WHERE category = ANY($1) GROUP BY category"
+        )
+        .bind(&category_names)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+    tx.commit().await?;
+
+    let node_categories: std::collections::HashMap<Uuid, String> =
+        nodes.iter().map(|n| (n.id, n.category.clone())).collect();
+    let mut nodes_by_category: std::collections::HashMap<String, Vec<Node>> = std::collections::HashMap::new();
+    for node in nodes {
+        nodes_by_category.entry(node.category.clone()).or_default().push(node);
+    }
+    let mut connections_by_category: std::collections::HashMap<String, Vec<Connection>> = std::collections::HashMap::new();
+    for connection in connections {
+        if let Some(category) = node_categories.get(&connection.from_node_id) {
+            connections_by_category.entry(category.clone()).or_default().push(connection);
+        }
+    }
+
+    let categories: Vec<ManifestCategory> = roots
+        .iter()
+        .map(|(category, name, display_category)| {
+            let nodes = nodes_by_category.remove(category).unwrap_or_default();
+            let connections = connections_by_category.remove(category).unwrap_or_default();
+            let (version, _, _) = content_checksums(&nodes, &connections, true);
+            let last_modified = nodes
+                .iter()
+                .map(|n| n.updated_at)
+                .chain(connections.iter().map(|c| c.updated_at))
+                .chain(deletions.get(category).copied())
+                .max()
+                .unwrap_or_default();
+
+            ManifestCategory {
+                category: category.clone(),
+                name: name.clone(),
+                display_category: display_category.clone(),
+                version,
+                last_modified: last_modified.to_rfc3339(),
+                node_count: nodes.len(),
+            }
         })
-    }).collect();
+        .collect();
 
-    let export_data = IssueExportData {
-        issue: IssueImportMetadata {
-            name: issue_name,
-            category: category.clone(),
-            display_category: root_node.display_category.clone(),
-            root_question_text: root_node.text.clone(),
-        },
-        nodes: export_nodes,
-        connections: export_connections,
+    let version = manifest_version(&categories);
+    let etag = format!("\"{}\"", version);
+
+    let unchanged = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+    if unchanged {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    let manifest = CategoryManifest {
+        version,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        categories,
     };
+    let body = serde_json::to_vec(&manifest)?;
+    let signature = manifest_signing_secret().map(|secret| sign_manifest(secret, &body));
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        body,
+    )
+        .into_response();
+    if let Some(signature) = signature {
+        response.headers_mut().insert(
+            MANIFEST_SIGNATURE_HEADER,
+            signature.parse().map_err(|_| ApiError::internal("Invalid manifest signature"))?,
+        );
+    }
+
+    Ok(response)
+}
+
+/// Longest answer script accepted by simulate_issue
+const MAX_SIMULATION_ANSWERS: usize = 200;
+
+/// Request for simulate_issue: a scripted walk through a category
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SimulateRequest {
+    /// Answer labels to pick in order (exact match first, then case-insensitive);
+    /// at a question taking a measurement, a number is the reading
+    pub answers: Vec<String>,
+    /// Conclusion text the walk must end at; sets `passed`
+    #[ts(optional)]
+    pub expect_conclusion: Option<String>,
+    /// Values a session would have captured, for conditions and placeholders
+    #[serde(default)]
+    #[ts(optional)]
+    pub variables: Option<engine::Captured>,
+}
+
+/// How a simulated walk ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum SimulationOutcome {
+    /// Every answer was used and the walk reached a conclusion
+    Concluded,
+    /// Answers ran out before a conclusion
+    NeedsMoreAnswers,
+    /// An answer matched none of the current node's options
+    UnmatchedAnswer,
+    /// A conclusion was reached with answers left over
+    ExtraAnswers,
+}
+
+/// One answered question in a simulated walk
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SimulationStep {
+    pub node_id: Uuid,
+    pub node_text: String,
+    pub semantic_id: Option<String>,
+    pub category: String,
+    pub connection_id: Uuid,
+    /// Label of the connection taken
+    pub answer: String,
+    /// Reading given, at a question taking a measurement
+    pub reading: Option<f64>,
+}
+
+/// Result of simulate_issue
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SimulationResult {
+    pub category: String,
+    pub outcome: SimulationOutcome,
+    pub steps: Vec<SimulationStep>,
+    /// Node the walk stopped at, with its placeholders filled in
+    pub final_node: Node,
+    /// Conclusion text when the walk stopped at a conclusion
+    pub conclusion: Option<String>,
+    /// Options offered at the final node given the answers so far (empty at a conclusion)
+    pub available_answers: Vec<String>,
+    /// The answer that matched no option, for `unmatched_answer`
+    pub unmatched_answer: Option<String>,
+    /// Whether the conclusion equals `expect_conclusion` (trimmed, case-insensitive); null when not given
+    pub passed: Option<bool>,
+}
+
+/// Outgoing option of a node during simulation
+#[derive(Debug, sqlx::FromRow)]
+struct SimulationOption {
+    connection_id: Uuid,
+    label: String,
+    to_node_id: Uuid,
+    condition: Option<String>,
+    order_index: i32,
+}
+
+/// Pick the option for a scripted answer: exact label first, then trimmed case-insensitive
+fn match_answer<'a>(options: &'a [SimulationOption], answer: &str) -> Option<&'a SimulationOption> {
+    options.iter().find(|o| o.label == answer).or_else(|| {
+        let answer = answer.trim().to_lowercase();
+        options.iter().find(|o| o.label.trim().to_lowercase() == answer)
+    })
+}
+
+/// Options a session would be offered at a node, in display order
+///
+/// Nodes of the simulated category count even when inactive, so a disabled
+/// issue can be tested before it is switched on. Answers whose conditions
+/// rule them out given `variables` are left out, as in a real session.
+async fn simulation_options(
+    state: &AppState,
+    node_id: Uuid,
+    category: &str,
+    variables: &Variables,
+) -> ApiResult<Vec<SimulationOption>> {
+    let options = sqlx::query_as::<_, SimulationOption>(
+        "SELECT c.id AS connection_id, c.label, c.to_node_id, c.condition, c.order_index
+         FROM connections c
+         INNER JOIN nodes n ON c.to_node_id = n.id
+         INNER JOIN nodes p ON c.from_node_id = p.id
+         WHERE c.from_node_id = $1
+           AND c.is_active = true
+           AND (n.is_active = true OR n.category = $2)
+         ORDER BY CASE WHEN p.option_order = 'weight' THEN c.weight END DESC NULLS LAST, c.order_index ASC"
+    )
+    .bind(node_id)
+    .bind(category)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(options
+        .into_iter()
+        .filter(|o| conditions::available(o.condition.as_deref(), variables))
+        .collect())
+}
 
-    tracing::info!("✅ Exported issue {} ({} nodes, {} connections)", category, nodes.len(), connections.len());
+/// Route a scripted reading at a question taking a measurement, checking conditions in the author's order
+fn route_reading<'a>(options: &'a [SimulationOption], answer: &str, variables: &Variables) -> Option<(&'a SimulationOption, f64)> {
+    if !options.iter().any(|o| conditions::uses_reading(o.condition.as_deref())) {
+        return None;
+    }
+    let reading = answer.trim().parse::<f64>().ok().filter(|r| r.is_finite())?;
+    let mut routes: Vec<(usize, Option<String>)> = options.iter().map(|o| o.condition.clone()).enumerate().collect();
+    routes.sort_by_key(|(i, _)| options[*i].order_index);
+    conditions::route(&routes, variables, reading).map(|i| (&options[i], reading))
+}
 
-    Ok(Json(export_data))
+async fn simulation_node(state: &AppState, node_id: Uuid) -> ApiResult<Node> {
+    sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE id = $1"
+    )
+    .bind(node_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Node not found"))
 }
 
-/// GET /api/admin/issues/export-all
-/// Export all issues as a JSON array
-pub async fn export_all_issues(
+/// POST /api/admin/issues/:category/simulate
+/// Walk a category with scripted answers, through any sub-flows, and report the path and conclusion
+///
+/// Answers follow the same rules as a session (conditions, readings,
+/// sub-flows), but no session is recorded and no analytics events are sent,
+/// so editors can try a draft or inactive issue freely.
+pub async fn simulate_issue(
     State(state): State<AppState>,
-) -> ApiResult<Json<Vec<IssueExportData>>> {
-    tracing::info!("📦 Exporting all issues");
+    Path(category): Path<String>,
+    Json(req): Json<SimulateRequest>,
+) -> ApiResult<Json<SimulationResult>> {
+    if req.answers.len() > MAX_SIMULATION_ANSWERS {
+        return Err(ApiError::validation(vec![(
+            "answers".to_string(),
+            format!("At most {} answers can be simulated", MAX_SIMULATION_ANSWERS),
+        )]));
+    }
 
-    // Get all distinct categories (excluding 'root' and utility categories)
-    let categories: Vec<String> = sqlx::query_scalar(
-        "SELECT DISTINCT category FROM nodes
-         WHERE category NOT IN ('root', 'electrical', 'general', 'mechanical')
-         AND is_active = true
-         ORDER BY category ASC"
+    let mut node = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE semantic_id = $1"
     )
-    .fetch_all(&state.db)
-    .await?;
+    .bind(format!("{}_start", category))
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Issue category '{}' not found", category)))?;
 
-    let mut all_exports = Vec::new();
+    let captured = engine::capture(engine::Captured::new(), req.variables)?;
+    // Answers to questions with a semantic ID set variables, as in a session; captured values win
+    let mut answered = Variables::new();
+    let variables = |answered: &Variables| {
+        let mut variables = answered.clone();
+        variables.extend(captured.iter().map(|(name, value)| (name.clone(), Value::Text(value.clone()))));
+        variables
+    };
 
-    for category in categories {
-        // Reuse the single export logic
-        match export_issue(State(state.clone()), Path(category.clone())).await {
-            Ok(Json(export_data)) => all_exports.push(export_data),
-            Err(e) => {
-                tracing::warn!("⚠️  Failed to export issue {}: {:?}", category, e);
-                continue;
-            }
+    let mut steps = Vec::new();
+    let mut answers = req.answers.iter();
+    let mut unmatched_answer = None;
+    let mut subflows = Vec::new();
+
+    let (outcome, options) = loop {
+        node = engine::pass_subflows(&state, None, &mut subflows, node).await?.0;
+        if node.node_type.ends_session() {
+            let outcome = if answers.len() == 0 {
+                SimulationOutcome::Concluded
+            } else {
+                SimulationOutcome::ExtraAnswers
+            };
+            break (outcome, Vec::new());
         }
-    }
 
-    tracing::info!("✅ Exported {} issues", all_exports.len());
+        let current = variables(&answered);
+        let options = simulation_options(&state, node.id, &category, &current).await?;
+        let Some(answer) = answers.next() else {
+            break (SimulationOutcome::NeedsMoreAnswers, options);
+        };
+        let (option, reading) = match match_answer(&options, answer) {
+            Some(option) => (option, None),
+            None => match route_reading(&options, answer, &current) {
+                Some((option, reading)) => (option, Some(reading)),
+                None => {
+                    unmatched_answer = Some(answer.clone());
+                    break (SimulationOutcome::UnmatchedAnswer, options);
+                }
+            },
+        };
+        if let Some(name) = &node.semantic_id {
+            let value = match reading {
+                Some(reading) => Value::Number(reading),
+                None => Value::Text(option.label.clone()),
+            };
+            answered.insert(name.clone(), value);
+        }
 
-    Ok(Json(all_exports))
+        steps.push(SimulationStep {
+            node_id: node.id,
+            node_text: node.text.clone(),
+            semantic_id: node.semantic_id.clone(),
+            category: node.category.clone(),
+            connection_id: option.connection_id,
+            answer: option.label.clone(),
+            reading,
+        });
+        node = simulation_node(&state, option.to_node_id).await?;
+    };
+    engine::render_node(&mut node, &variables(&answered));
+
+    let conclusion = node.node_type.ends_session().then(|| node.text.clone());
+    let passed = req.expect_conclusion.as_ref().map(|expected| {
+        conclusion
+            .as_ref()
+            .is_some_and(|c| c.trim().to_lowercase() == expected.trim().to_lowercase())
+    });
+
+    Ok(Json(SimulationResult {
+        category,
+        outcome,
+        steps,
+        final_node: node,
+        conclusion,
+        available_answers: options.into_iter().map(|o| o.label).collect(),
+        unmatched_answer,
+        passed,
+    }))
 }
 
-/// POST /api/admin/issues/import
-/// Import one or more issues from JSON
-pub async fn import_issues(
+/// POST /api/admin/issues/import/incremental
+/// Apply an incremental export: delete tombstoned records, then upsert nodes and connections by ID
+pub async fn import_issue_incremental(
     State(state): State<AppState>,
-    Json(data): Json<Vec<IssueExportData>>,
-) -> ApiResult<Json<ImportResult>> {
-    tracing::info!("📥 Importing {} issue(s)", data.len());
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(data): Json<IncrementalExportData>,
+) -> ApiResult<Json<IncrementalImportResult>> {
+    category_access::ensure_can_edit(&state.db, &auth, [data.category.as_str()]).await?;
 
-    let mut success_list = Vec::new();
-    let mut error_list = Vec::new();
+    let category = data.category.clone();
 
-    for issue_data in data {
-        let category = issue_data.issue.category.clone();
+    let misplaced: Vec<(String, String)> = data.nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.category != category)
+        .map(|(i, node)| (
+            format!("/nodes/{}/category", i),
+            format!("Node {} belongs to category '{}', expected '{}'", node.id, node.category, category),
+        ))
+        .collect();
+    if !misplaced.is_empty() {
+        return Err(ApiError::validation_at(misplaced));
+    }
 
-        // Check if category already exists
-        let existing_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM nodes WHERE category = $1"
-        )
+    let mut contents = Vec::with_capacity(data.nodes.len());
+    for (i, node) in data.nodes.iter().enumerate() {
+        contents.push(content::sanitize_at(node.content.clone(), &format!("/nodes/{}/content", i))?);
+    }
+
+    let deleted_node_ids: Vec<Uuid> = data.deleted.iter()
+        .filter(|t| t.entity_type == "node")
+        .map(|t| t.entity_id)
+        .collect();
+    let deleted_connection_ids: Vec<Uuid> = data.deleted.iter()
+        .filter(|t| t.entity_type == "connection")
+        .map(|t| t.entity_id)
+        .collect();
+
+    let mut tx = state.db.begin().await?;
+
+    // 1. Deletions (connections first, including any still attached to deleted nodes),
+    // touching only records of this category
+    let connections_deleted = sqlx::query(
+        "DELETE FROM connections c
+         WHERE (c.id = ANY($1) AND EXISTS(SELECT 1 FROM nodes n WHERE n.id = c.from_node_id AND n.category = $3))
+            OR EXISTS(
+                SELECT 1 FROM nodes n
+                WHERE n.id IN (c.from_node_id, c.to_node_id) AND n.id = ANY($2) AND n.category = $3
+            )"
+    )
+    .bind(&deleted_connection_ids)
+    .bind(&deleted_node_ids)
+    .bind(&category)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let nodes_deleted = sqlx::query("DELETE FROM nodes WHERE id = ANY($1) AND category = $2")
+        .bind(&deleted_node_ids)
         .bind(&category)
-        .fetch_one(&state.db)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // 2. Node upserts (never move a node out of another category)
+    let mut nodes_upserted = 0;
+    for ((i, node), content) in data.nodes.iter().enumerate().zip(&contents) {
+        let result = sqlx::query(
+            "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, subflow_category, content, is_active, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+             ON CONFLICT (id) DO UPDATE SET
+                node_type = EXCLUDED.node_type,
+                text = EXCLUDED.text,
+                semantic_id = EXCLUDED.semantic_id,
+                display_category = EXCLUDED.display_category,
+                position_x = EXCLUDED.position_x,
+                position_y = EXCLUDED.position_y,
+                kb_url = EXCLUDED.kb_url,
+                doc_reference = EXCLUDED.doc_reference,
+                option_order = EXCLUDED.option_order,
+                answer_mode = EXCLUDED.answer_mode,
+                subflow_category = EXCLUDED.subflow_category,
+                content = EXCLUDED.content,
+                is_active = EXCLUDED.is_active
+             WHERE nodes.category = EXCLUDED.category"
+        )
+        .bind(node.id)
+        .bind(&node.category)
+        .bind(&node.node_type)
+        .bind(&node.text)
+        .bind(semantic_ids::normalize(node.semantic_id.as_deref()))
+        .bind(&node.display_category)
+        .bind(node.position_x)
+        .bind(node.position_y)
+        .bind(&node.kb_url)
+        .bind(&node.doc_reference)
+        .bind(node.option_order)
+        .bind(node.answer_mode)
+        .bind(&node.subflow_category)
+        .bind(content.as_ref().map(sqlx::types::Json))
+        .bind(node.is_active)
+        .bind(node.created_at)
+        .bind(node.updated_at)
+        .execute(&mut *tx)
         .await
-        .unwrap_or(0);
+        .map_err(|e| ApiError::validation_at(vec![(
+            format!("/nodes/{}", i),
+            format!("Failed to apply node {}: {}", node.id, e),
+        )]))?;
 
-        if existing_count > 0 {
-            error_list.push(ImportError {
-                category: category.clone(),
-                error: format!("Issue with category '{}' already exists. Please delete it first or choose a different category.", category),
+        if result.rows_affected() == 0 {
+            return Err(ApiError::Conflict {
+                message: format!("Node {} already exists in a different category", node.id),
             });
-            continue;
         }
+        nodes_upserted += 1;
+    }
 
-        // Start transaction for atomicity
-        let mut tx = match state.db.begin().await {
-            Ok(tx) => tx,
-            Err(e) => {
-                error_list.push(ImportError {
-                    category: category.clone(),
-                    error: format!("Failed to start transaction: {}", e),
-                });
-                continue;
-            }
-        };
+    // Fallbacks and connections may only point at nodes of this category
+    let category_node_ids: std::collections::HashSet<Uuid> = sqlx::query_scalar("SELECT id FROM nodes WHERE category = $1")
+        .bind(&category)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+    let foreign = foreign_references(&data, &category_node_ids);
+    if !foreign.is_empty() {
+        return Err(ApiError::validation_at(foreign));
+    }
 
-        // Validate nodes
-        if issue_data.nodes.is_empty() {
-            error_list.push(ImportError {
-                category: category.clone(),
-                error: "Issue must have at least one node".to_string(),
-            });
-            continue;
-        }
+    // "I'm not sure" targets may be nodes later in the batch, so they're set once all exist
+    for (i, node) in data.nodes.iter().enumerate() {
+        sqlx::query(
+            "UPDATE nodes SET not_sure_node_id = $2
+             WHERE id = $1 AND category = $3 AND not_sure_node_id IS DISTINCT FROM $2"
+        )
+            .bind(node.id)
+            .bind(node.not_sure_node_id)
+            .bind(&category)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::validation_at(vec![(
+                format!("/nodes/{}/not_sure_node_id", i),
+                format!("Failed to apply node {}: {}", node.id, e),
+            )]))?;
+    }
 
-        // Create nodes and build mapping
-        let mut node_ids = Vec::new();
-        let mut error_msg: Option<String> = None;
+    // 3. Connection upserts (both endpoints must exist after the node upserts)
+    let mut connections_upserted = 0;
+    for (i, conn) in data.connections.iter().enumerate() {
+        let condition = conn
+            .condition
+            .as_deref()
+            .map(conditions::normalize)
+            .transpose()
+            .map_err(|e| ApiError::validation_at(vec![(format!("/connections/{}/condition", i), e)]))?
+            .flatten();
 
-        for node_data in &issue_data.nodes {
-            let node_id = Uuid::new_v4();
-            let node_type = node_data.node_type.as_str();
+        sqlx::query(
+            "INSERT INTO connections (id, from_node_id, to_node_id, label, order_index, condition, unsure_route, is_active, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT (id) DO UPDATE SET
+                from_node_id = EXCLUDED.from_node_id,
+                to_node_id = EXCLUDED.to_node_id,
+                label = EXCLUDED.label,
+                order_index = EXCLUDED.order_index,
+                condition = EXCLUDED.condition,
+                unsure_route = EXCLUDED.unsure_route,
+                is_active = EXCLUDED.is_active"
+        )
+        .bind(conn.id)
+        .bind(conn.from_node_id)
+        .bind(conn.to_node_id)
+        .bind(&conn.label)
+        .bind(conn.order_index)
+        .bind(&condition)
+        .bind(conn.unsure_route)
+        .bind(conn.is_active)
+        .bind(conn.created_at)
+        .bind(conn.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::validation_at(vec![(
+            format!("/connections/{}", i),
+            format!("Failed to apply connection {}: {}", conn.id, e),
+        )]))?;
 
-            // Validate node_type (lowercase as per model definition)
-            if node_type != "question" && node_type != "conclusion" {
-                error_msg = Some(format!("Invalid node_type: '{}'. Must be 'question' or 'conclusion'", node_type));
-                break;
-            }
+        connections_upserted += 1;
+    }
 
-            match sqlx::query!(
-                "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true)",
-                node_id,
-                &category,
-                node_type,
-                &node_data.text,
-                node_data.semantic_id.as_deref(),
-                issue_data.issue.display_category.as_deref(),
-                node_data.position_x,
-                node_data.position_y,
-            )
-            .execute(&mut *tx)
-            .await {
-                Ok(_) => node_ids.push(node_id),
-                Err(e) => {
-                    error_msg = Some(format!("Failed to create node: {}", e));
-                    break;
-                }
-            }
-        }
+    tx.commit().await?;
 
-        // If there was an error, rollback and continue to next issue
-        if let Some(err) = error_msg {
-            let _ = tx.rollback().await;
-            error_list.push(ImportError {
-                category: category.clone(),
-                error: err,
-            });
-            continue;
-        }
+    tidy_layout(&state, &category).await;
+    // Invalidate caches for the synced category
+    state.invalidate_issue(&category).await;
 
-        // Create connections
-        let mut connections_created = 0;
-        let mut conn_error_msg: Option<String> = None;
+    tracing::info!(
+        "📥 Incremental import of {}: {} nodes, {} connections upserted; {} nodes, {} connections deleted",
+        category, nodes_upserted, connections_upserted, nodes_deleted, connections_deleted
+    );
 
-        for conn_data in &issue_data.connections {
-            // Validate indices
-            if conn_data.from_node_index >= node_ids.len() || conn_data.to_node_index >= node_ids.len() {
-                conn_error_msg = Some("Invalid connection: node index out of bounds".to_string());
-                break;
-            }
+    // Audit log the import
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
 
-            let from_id = node_ids[conn_data.from_node_index];
-            let to_id = node_ids[conn_data.to_node_index];
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::IssuesImported,
+        "issue",
+        Some(&category),
+        Some(json!({
+            "mode": "incremental",
+            "since": data.since,
+            "exported_at": data.exported_at,
+            "nodes_upserted": nodes_upserted,
+            "connections_upserted": connections_upserted,
+            "nodes_deleted": nodes_deleted,
+            "connections_deleted": connections_deleted,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
 
-            match sqlx::query!(
-                "INSERT INTO connections (from_node_id, to_node_id, label, order_index, is_active)
-                 VALUES ($1, $2, $3, $4, true)",
-                from_id,
-                to_id,
-                &conn_data.label,
-                conn_data.order_index,
-            )
-            .execute(&mut *tx)
-            .await {
-                Ok(_) => connections_created += 1,
-                Err(e) => {
-                    conn_error_msg = Some(format!("Failed to create connection: {}", e));
-                    break;
-                }
-            }
-        }
+    Ok(Json(IncrementalImportResult {
+        category,
+        nodes_upserted,
+        connections_upserted,
+        nodes_deleted,
+        connections_deleted,
+    }))
+}
 
-        // If there was a connection error, rollback and continue to next issue
-        if let Some(err) = conn_error_msg {
-            let _ = tx.rollback().await;
-            error_list.push(ImportError {
-                category: category.clone(),
-                error: err,
-            });
-            continue;
+/// Fallback targets and connection endpoints in an incremental import that
+/// aren't nodes of its category, as (JSON pointer, message) pairs
+fn foreign_references(data: &IncrementalExportData, category_node_ids: &std::collections::HashSet<Uuid>) -> Vec<(String, String)> {
+    let outside = |id: &Uuid| !category_node_ids.contains(id);
+    let mut errors = Vec::new();
+    for (i, node) in data.nodes.iter().enumerate() {
+        if let Some(target) = node.not_sure_node_id.filter(outside) {
+            errors.push((
+                format!("/nodes/{}/not_sure_node_id", i),
+                format!("Node {} is not in category '{}'", target, data.category),
+            ));
         }
-
-        // Commit transaction
-        match tx.commit().await {
-            Ok(_) => {
-                success_list.push(ImportSuccess {
-                    category: category.clone(),
-                    name: issue_data.issue.name.clone(),
-                    nodes_count: node_ids.len(),
-                    connections_count: connections_created,
-                });
-                tracing::info!("✅ Imported issue: {} ({} nodes, {} connections)",
-                    category, node_ids.len(), connections_created);
-            }
-            Err(e) => {
-                error_list.push(ImportError {
-                    category: category.clone(),
-                    error: format!("Failed to commit transaction: {}", e),
-                });
+    }
+    for (i, conn) in data.connections.iter().enumerate() {
+        for (field, id) in [("from_node_id", conn.from_node_id), ("to_node_id", conn.to_node_id)] {
+            if outside(&id) {
+                errors.push((
+                    format!("/connections/{}/{}", i, field),
+                    format!("Node {} is not in category '{}'", id, data.category),
+                ));
             }
         }
     }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_cursor_round_trip() {
+        let created_at = chrono::DateTime::from_timestamp_micros(1_730_000_000_123_456).unwrap();
+        let id = Uuid::new_v4();
+        let cursor = format!("{}.{}", created_at.timestamp_micros(), id);
+
+        assert_eq!(decode_graph_cursor(&cursor), Some((created_at, id)));
+        assert_eq!(decode_graph_cursor("not-a-cursor"), None);
+        assert_eq!(decode_graph_cursor("123.not-a-uuid"), None);
+    }
 
-    tracing::info!("📥 Import complete: {} succeeded, {} failed", success_list.len(), error_list.len());
+    #[test]
+    fn test_deleting_sessions_needs_sessions_delete() {
+        let user = |role| {
+            AuthUser(crate::utils::jwt::Claims {
+                sub: Uuid::new_v4().to_string(),
+                email: "editor@example.com".to_string(),
+                role,
+                iat: 0,
+                exp: 9999999999,
+            })
+        };
+        let editor = user(crate::models::UserRole::Editor);
 
-    Ok(Json(ImportResult {
-        success: success_list,
-        errors: error_list,
-    }))
+        assert!(ensure_can_delete_sessions(&editor, None, false).is_ok());
+        assert!(matches!(
+            ensure_can_delete_sessions(&editor, None, true),
+            Err(ApiError::Forbidden { .. })
+        ));
+        assert!(ensure_can_delete_sessions(&user(crate::models::UserRole::Admin), None, true).is_ok());
+
+        // Service tokens go by their scopes, whatever the account's role
+        let issues_only = ServiceScopes([Permission::IssuesWrite].into_iter().collect());
+        assert!(ensure_can_delete_sessions(&user(crate::models::UserRole::Admin), Some(&issues_only), true).is_err());
+    }
+
+    #[test]
+    fn test_entry_point_target() {
+        let node = |node_type: &str, semantic_id: Option<&str>| NodeExportData {
+            node_type: node_type.to_string(),
+            text: "Is it on?".to_string(),
+            semantic_id: semantic_id.map(str::to_string),
+            ..Default::default()
+        };
+        let nodes = vec![
+            node("question", None),
+            node("conclusion", Some("printer_done")),
+            node("question", Some("printer_power")),
+        ];
+
+        assert_eq!(entry_point_target(&nodes, Some("printer_power")), Some(2));
+        assert_eq!(entry_point_target(&nodes, Some(" printer_power ")), Some(2));
+        // Only questions can start an entry point, and unnamed starts can't be matched
+        assert_eq!(entry_point_target(&nodes, Some("printer_done")), None);
+        assert_eq!(entry_point_target(&nodes, None), None);
+    }
+
+    #[test]
+    fn test_find_duplicate_connections() {
+        let conn = |from, to, label: &str| ConnectionExportData {
+            from_node_index: from,
+            to_node_index: to,
+            label: label.to_string(),
+            order_index: 0,
+            condition: None,
+            unsure_route: false,
+        };
+
+        let connections = vec![
+            conn(0, 1, "Yes"),
+            conn(0, 2, "No"),
+            conn(0, 1, "Yes"),
+            conn(0, 1, "Yes "),
+            conn(1, 2, "Yes"),
+        ];
+
+        assert_eq!(find_duplicate_connections(&connections), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_content_checksums_ignore_order_and_timestamps() {
+        let now = chrono::Utc::now();
+        let node = |text: &str, x: f64| Node {
+            id: Uuid::new_v4(),
+            category: "printer".to_string(),
+            node_type: NodeType::Question,
+            text: text.to_string(),
+            semantic_id: None,
+            display_category: None,
+            position_x: Some(x),
+            position_y: Some(0.0),
+            kb_url: None,
+            doc_reference: None,
+            option_order: OptionOrder::OrderIndex,
+            answer_mode: AnswerMode::Single,
+            not_sure_node_id: None,
+            subflow_category: None,
+            content: None,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        let a = node("Is it on?", 10.0);
+        let b = node("Is it plugged in?", 20.0);
+        let connection = Connection {
+            id: Uuid::new_v4(),
+            from_node_id: a.id,
+            to_node_id: b.id,
+            label: "No".to_string(),
+            order_index: 0,
+            weight: Some(0.4),
+            condition: None,
+            unsure_route: false,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let base = content_checksums(&[a.clone(), b.clone()], std::slice::from_ref(&connection), false);
+
+        // Row order, timestamps and weights don't matter
+        let mut synced = b.clone();
+        synced.updated_at = now + chrono::Duration::hours(1);
+        let mut reweighted = connection.clone();
+        reweighted.weight = Some(0.9);
+        assert_eq!(content_checksums(&[synced, a.clone()], &[reweighted], false), base);
+
+        // Content does
+        let mut edited = b.clone();
+        edited.text = "Is the cable seated?".to_string();
+        let changed = content_checksums(&[a.clone(), edited], std::slice::from_ref(&connection), false);
+        assert_ne!(changed.0, base.0);
+        assert_ne!(changed.1, base.1);
+        assert_eq!(changed.2, base.2);
+
+        let mut subflow = b.clone();
+        subflow.subflow_category = Some("toner".to_string());
+        assert_ne!(content_checksums(&[a.clone(), subflow], std::slice::from_ref(&connection), false).1, base.1);
+        let mut fallback = b.clone();
+        fallback.not_sure_node_id = Some(a.id);
+        assert_ne!(content_checksums(&[a.clone(), fallback], std::slice::from_ref(&connection), false).1, base.1);
+        let mut unsure = connection.clone();
+        unsure.unsure_route = true;
+        assert_ne!(content_checksums(&[a.clone(), b.clone()], &[unsure], false).2, base.2);
+
+        // Layout only counts unless ignored
+        let mut moved = b.clone();
+        moved.position_x = Some(99.0);
+        assert_ne!(content_checksums(&[a.clone(), moved.clone()], std::slice::from_ref(&connection), false).0, base.0);
+        assert_eq!(
+            content_checksums(&[a.clone(), moved], std::slice::from_ref(&connection), true).0,
+            content_checksums(&[a, b], &[connection], true).0
+        );
+    }
+
+    #[test]
+    fn test_incremental_import_rejects_foreign_references() {
+        let now = chrono::Utc::now();
+        let node = |not_sure_node_id| Node {
+            id: Uuid::new_v4(),
+            category: "printer".to_string(),
+            node_type: NodeType::Question,
+            text: "Is it on?".to_string(),
+            semantic_id: None,
+            display_category: None,
+            position_x: None,
+            position_y: None,
+            kb_url: None,
+            doc_reference: None,
+            option_order: OptionOrder::OrderIndex,
+            answer_mode: AnswerMode::Single,
+            not_sure_node_id,
+            subflow_category: None,
+            content: None,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        let connection = |from_node_id, to_node_id| Connection {
+            id: Uuid::new_v4(),
+            from_node_id,
+            to_node_id,
+            label: "Yes".to_string(),
+            order_index: 0,
+            weight: None,
+            condition: None,
+            unsure_route: false,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        let foreign = Uuid::new_v4();
+        let a = node(None);
+        let b = node(Some(a.id));
+        let category_node_ids = [a.id, b.id].into_iter().collect();
+        let data = |nodes, connections| IncrementalExportData {
+            category: "printer".to_string(),
+            since: None,
+            exported_at: now.to_rfc3339(),
+            nodes,
+            connections,
+            deleted: vec![],
+        };
+
+        let clean = data(vec![a.clone(), b.clone()], vec![connection(a.id, b.id)]);
+        assert!(foreign_references(&clean, &category_node_ids).is_empty());
+
+        let errors = foreign_references(
+            &data(vec![a.clone(), node(Some(foreign))], vec![connection(a.id, foreign), connection(foreign, b.id)]),
+            &category_node_ids,
+        );
+        let pointers: Vec<&str> = errors.iter().map(|(pointer, _)| pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            vec!["/nodes/1/not_sure_node_id", "/connections/0/to_node_id", "/connections/1/from_node_id"]
+        );
+    }
+
+    #[test]
+    fn test_manifest_version_and_signature() {
+        let category = |name: &str, version: &str| ManifestCategory {
+            category: name.to_string(),
+            name: name.to_string(),
+            display_category: None,
+            version: version.to_string(),
+            last_modified: "2025-10-24T00:00:00+00:00".to_string(),
+            node_count: 1,
+        };
+        let base = manifest_version(&[category("pump", "a"), category("printer", "b")]);
+        assert_eq!(manifest_version(&[category("pump", "a"), category("printer", "b")]), base);
+        assert_ne!(manifest_version(&[category("pump", "a"), category("printer", "c")]), base);
+        assert_ne!(manifest_version(&[category("pump", "a")]), base);
+
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_manifest(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_match_answer_prefers_exact_label() {
+        let option = |label: &str| SimulationOption {
+            connection_id: Uuid::new_v4(),
+            label: label.to_string(),
+            to_node_id: Uuid::new_v4(),
+            condition: None,
+            order_index: 0,
+        };
+        let options = vec![option("yes"), option("Yes"), option("No ")];
+
+        assert_eq!(match_answer(&options, "Yes").unwrap().connection_id, options[1].connection_id);
+        assert_eq!(match_answer(&options, "YES").unwrap().connection_id, options[0].connection_id);
+        assert_eq!(match_answer(&options, "no").unwrap().connection_id, options[2].connection_id);
+        assert!(match_answer(&options, "Maybe").is_none());
+    }
+
+    #[test]
+    fn test_route_reading_follows_author_order() {
+        let option = |label: &str, condition: Option<&str>, order_index: i32| SimulationOption {
+            connection_id: Uuid::new_v4(),
+            label: label.to_string(),
+            to_node_id: Uuid::new_v4(),
+            condition: condition.map(str::to_string),
+            order_index,
+        };
+        // Displayed by weight, routed by order_index
+        let options = vec![
+            option("Other", None, 2),
+            option("Normal", Some(">= 11.5 and < 13.8"), 1),
+            option("Low", Some("< 11.5"), 0),
+        ];
+        let variables = Variables::new();
+
+        let (low, reading) = route_reading(&options, "10", &variables).unwrap();
+        assert_eq!((low.label.as_str(), reading), ("Low", 10.0));
+        assert_eq!(route_reading(&options, " 12.4 ", &variables).unwrap().0.label, "Normal");
+        assert_eq!(route_reading(&options, "20", &variables).unwrap().0.label, "Other");
+        assert!(route_reading(&options, "high", &variables).is_none());
+        assert!(route_reading(&options[..1], "12", &variables).is_none());
+    }
 }
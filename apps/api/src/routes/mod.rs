@@ -1,6 +1,13 @@
 pub mod admin;
 pub mod auth;
+pub mod config;
 pub mod connections;
+pub mod devices;
+pub mod entry_points;
 pub mod issues;
 pub mod nodes;
+pub mod notices;
+pub mod service_accounts;
 pub mod troubleshoot;
+pub mod uploads;
+pub mod users;
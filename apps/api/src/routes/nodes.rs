@@ -1,7 +1,8 @@
 use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthUser;
-use crate::models::{Node, CreateNode, UpdateNode, NodeType, NodeWithConnections, ConnectionWithTarget};
-use crate::utils::audit;
+use crate::models::{AnswerMode, ClonedNode, Connection, MergeNode, Node, NodeContent, NodesMerged, SubtreeMode, SubtreeTransferred, TransferSubtree, CreateNode, UpdateNode, NodePosition, NodePositionsUpdated, NodeType, NodeWithConnections, OptionOrder, ConnectionWithTarget};
+use crate::routes::uploads;
+use crate::utils::{audit, category_access, category_notices, content, lint, node_revisions, orphans, semantic_ids};
 use crate::AppState;
 use axum::{
     extract::{Path, Query, State},
@@ -10,16 +11,145 @@ use axum::{
 };
 use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashSet;
 use uuid::Uuid;
 
+/// Maximum length of a conclusion's document reference
+const DOC_REFERENCE_MAX_LENGTH: usize = 255;
+
+/// Knowledge base links are only allowed on conclusions and must be http(s) URLs
+fn validate_kb_fields(
+    node_type: &NodeType,
+    kb_url: Option<&str>,
+    doc_reference: Option<&str>,
+) -> ApiResult<()> {
+    let kb_url = kb_url.map(str::trim).filter(|u| !u.is_empty());
+    let doc_reference = doc_reference.map(str::trim).filter(|d| !d.is_empty());
+    let mut errors = Vec::new();
+
+    if !matches!(node_type, NodeType::Conclusion) && (kb_url.is_some() || doc_reference.is_some()) {
+        errors.push((
+            "kb_url".to_string(),
+            "Only conclusion nodes can link to the knowledge base".to_string(),
+        ));
+    }
+
+    if let Some(url) = kb_url {
+        if !lint::is_http_url(url) {
+            errors.push(("kb_url".to_string(), "Must be an http or https URL".to_string()));
+        }
+    }
+
+    if doc_reference.is_some_and(|d| d.chars().count() > DOC_REFERENCE_MAX_LENGTH) {
+        errors.push((
+            "doc_reference".to_string(),
+            format!("Must be at most {} characters", DOC_REFERENCE_MAX_LENGTH),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::validation(errors))
+    }
+}
+
+/// "I'm not sure" is only offered on questions and must lead to another existing node
+async fn validate_not_sure_target(
+    conn: &mut sqlx::PgConnection,
+    id: Uuid,
+    node_type: &NodeType,
+    target: Option<Uuid>,
+) -> ApiResult<()> {
+    let Some(target) = target else {
+        return Ok(());
+    };
+
+    let message = if !matches!(node_type, NodeType::Question) {
+        Some("Only questions can offer \"I'm not sure\"")
+    } else if target == id {
+        Some("Must lead to a different node")
+    } else if !sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = $1)")
+        .bind(target)
+        .fetch_one(conn)
+        .await?
+    {
+        Some("Node does not exist")
+    } else {
+        None
+    };
+
+    match message {
+        Some(message) => Err(ApiError::validation(vec![("not_sure_node_id".to_string(), message.to_string())])),
+        None => Ok(()),
+    }
+}
+
+/// A sub-flow node must run another category that has a start node; no other node runs one
+async fn validate_subflow_category(
+    conn: &mut sqlx::PgConnection,
+    category: &str,
+    node_type: &NodeType,
+    subflow_category: Option<&str>,
+) -> ApiResult<()> {
+    let message = match (node_type, subflow_category) {
+        (NodeType::Subflow, None) => Some("Sub-flow nodes must name the category they run"),
+        (NodeType::Subflow, Some(runs)) if runs == category => Some("A sub-flow can't run its own category"),
+        (NodeType::Subflow, Some(runs)) => {
+            let has_start = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1 AND semantic_id = $1 || '_start')",
+            )
+            .bind(runs)
+            .fetch_one(conn)
+            .await?;
+            (!has_start).then_some("Category has no start node")
+        }
+        (_, Some(_)) => Some("Only sub-flow nodes run another category"),
+        (_, None) => None,
+    };
+
+    match message {
+        Some(message) => Err(ApiError::validation(vec![("subflow_category".to_string(), message.to_string())])),
+        None => Ok(()),
+    }
+}
+
+/// Load a node and lock its row for the rest of the transaction
+async fn fetch_node_for_update(conn: &mut sqlx::PgConnection, id: Uuid) -> ApiResult<Node> {
+    sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE id = $1
+         FOR UPDATE"
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Node not found"))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ListNodesQuery {
     pub category: Option<String>,
     pub node_type: Option<String>,
+    pub semantic_id: Option<String>,
+    /// Only active (true) or only switched-off (false) nodes; overrides `include_inactive`
+    pub is_active: Option<bool>,
+    /// Also list switched-off nodes (e.g. to restore one)
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Case-insensitive match anywhere in the text or semantic ID
+    pub search: Option<String>,
+}
+
+/// ILIKE pattern matching `search` anywhere, with its own wildcards taken literally
+pub(crate) fn contains_pattern(search: &str) -> String {
+    let escaped = search.trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
 }
 
 /// GET /api/nodes
-/// List all nodes, optionally filtered by category or type
+/// List all nodes, optionally filtered by category, type, semantic ID, state or text
 pub async fn list_nodes(
     State(state): State<AppState>,
     Query(query): Query<ListNodesQuery>,
@@ -27,11 +157,23 @@ pub async fn list_nodes(
     // Build query safely using QueryBuilder to prevent SQL injection
     use sqlx::QueryBuilder;
     let mut query_builder = QueryBuilder::new(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
-         WHERE is_active = true"
+         WHERE true"
     );
 
+    // Switched-off nodes are only listed on request
+    match query.is_active {
+        Some(is_active) => {
+            query_builder.push(" AND is_active = ");
+            query_builder.push_bind(is_active);
+        }
+        None if !query.include_inactive => {
+            query_builder.push(" AND is_active = true");
+        }
+        None => {}
+    }
+
     // Category filter - SAFE: uses parameterized query
     if let Some(ref category) = query.category {
         query_builder.push(" AND category = ");
@@ -44,6 +186,20 @@ pub async fn list_nodes(
         query_builder.push_bind(node_type);
     }
 
+    if let Some(ref semantic_id) = query.semantic_id {
+        query_builder.push(" AND semantic_id = ");
+        query_builder.push_bind(semantic_id);
+    }
+
+    if let Some(search) = query.search.as_deref().filter(|s| !s.trim().is_empty()) {
+        let pattern = contains_pattern(search);
+        query_builder.push(" AND (text ILIKE ");
+        query_builder.push_bind(pattern.clone());
+        query_builder.push(" OR semantic_id ILIKE ");
+        query_builder.push_bind(pattern);
+        query_builder.push(")");
+    }
+
     query_builder.push(" ORDER BY created_at ASC");
 
     let nodes = query_builder
@@ -61,7 +217,7 @@ pub async fn get_node(
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<Node>> {
     let node = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE id = $1"
     )
@@ -89,26 +245,41 @@ pub async fn create_node(
         )]));
     }
 
+    validate_kb_fields(&req.node_type, req.kb_url.as_deref(), req.doc_reference.as_deref())?;
+    let mut semantic_id = semantic_ids::normalize(req.semantic_id.as_deref());
+    semantic_ids::validate("semantic_id", semantic_id.as_deref())?;
+    let content = content::sanitize_at(req.content.clone(), "/content")?;
+    let subflow_category = req.subflow_category.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    validate_subflow_category(&mut *state.db.acquire().await?, &req.category, &req.node_type, subflow_category).await?;
+    category_access::ensure_can_edit(&state.db, &auth, [req.category.as_str()]).await?;
+
+    if semantic_id.is_none() && req.generate_semantic_id == Some(true) {
+        semantic_id = Some(semantic_ids::generate(&mut *state.db.acquire().await?, &req.category, &req.text, None).await?);
+    }
+
     // Insert node
     let node = sqlx::query_as::<_, Node>(
-        "INSERT INTO nodes (category, node_type, text, semantic_id, display_category, position_x, position_y, is_active)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, true)
-         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at"
+        "INSERT INTO nodes (category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, subflow_category, content, is_active)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, NULLIF(TRIM($8), ''), NULLIF(TRIM($9), ''), $10, $11, true)
+         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at"
     )
     .bind(&req.category)
     .bind(&req.node_type)
     .bind(&req.text)
-    .bind(&req.semantic_id)
+    .bind(&semantic_id)
     .bind(&req.display_category)
     .bind(req.position_x)
     .bind(req.position_y)
+    .bind(&req.kb_url)
+    .bind(&req.doc_reference)
+    .bind(subflow_category)
+    .bind(content.as_ref().map(sqlx::types::Json))
     .fetch_one(&state.db)
-    .await?;
+    .await
+    .map_err(|e| semantic_ids::map_taken("semantic_id", semantic_id.as_deref(), e))?;
 
     // Invalidate cache for the category
-    let cache_key = format!("graph_{}", node.category);
-    state.issue_graph_cache.invalidate(&cache_key).await;
-    state.issue_tree_cache.invalidate(&node.category).await;
+    state.invalidate_issue(&node.category).await;
 
     // Audit log the node creation
     let user_id = Uuid::parse_str(&auth.0.sub)
@@ -143,25 +314,46 @@ pub async fn update_node(
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateNode>,
 ) -> ApiResult<Json<Node>> {
-    // Check if node exists
-    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = $1)")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await?;
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
 
-    if !exists {
-        return Err(ApiError::not_found("Node not found"));
-    }
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    // Lock the current version so the recorded history matches the update
+    let mut tx = state.db.begin().await?;
+    let before = fetch_node_for_update(&mut tx, id).await?;
+
+    let node_type = req.node_type.clone().unwrap_or(before.node_type.clone());
+    validate_kb_fields(&node_type, req.kb_url.as_deref(), req.doc_reference.as_deref())?;
+    validate_not_sure_target(&mut tx, id, &node_type, req.not_sure_node_id).await?;
+    // A sub-flow node keeps the category it runs unless the request names another
+    let subflow_category = match req.subflow_category.as_deref() {
+        Some(requested) => Some(requested.trim()).filter(|c| !c.is_empty()),
+        None if matches!(node_type, NodeType::Subflow) => before.subflow_category.as_deref(),
+        None => None,
+    };
+    validate_subflow_category(&mut tx, &before.category, &node_type, subflow_category).await?;
+    let content = content::sanitize_at(req.content.clone(), "/content")?;
+    // Outer None leaves the semantic ID alone; Some(None) clears it
+    let semantic_id = match req.semantic_id.as_deref() {
+        Some(requested) => Some(semantic_ids::normalize(Some(requested))),
+        None if req.generate_semantic_id == Some(true) => {
+            let text = req.text.as_deref().unwrap_or(&before.text);
+            Some(Some(semantic_ids::generate(&mut tx, &before.category, text, Some(id)).await?))
+        }
+        None => None,
+    };
+    semantic_ids::validate("semantic_id", semantic_id.as_ref().and_then(Option::as_deref))?;
 
     // Build dynamic update query
-    let mut query = String::from("UPDATE nodes SET updated_at = NOW()");
-    let mut param_count = 1;
+    let mut query = String::from("UPDATE nodes SET updated_at = NOW(), subflow_category = $2");
+    let mut param_count = 2;
 
     if req.text.is_some() {
         param_count += 1;
         query.push_str(&format!(", text = ${}", param_count));
     }
-    if req.semantic_id.is_some() {
+    if semantic_id.is_some() {
         param_count += 1;
         query.push_str(&format!(", semantic_id = ${}", param_count));
     }
@@ -181,19 +373,52 @@ pub async fn update_node(
         param_count += 1;
         query.push_str(&format!(", position_y = ${}", param_count));
     }
+    if req.kb_url.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", kb_url = NULLIF(TRIM(${}), '')", param_count));
+    }
+    if req.doc_reference.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", doc_reference = NULLIF(TRIM(${}), '')", param_count));
+    }
+    if req.option_order.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", option_order = ${}", param_count));
+    }
+    if req.answer_mode.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", answer_mode = ${}", param_count));
+    }
+    if req.not_sure_node_id.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", not_sure_node_id = ${}", param_count));
+    } else if req.clear_not_sure == Some(true)
+        || matches!(req.node_type, Some(NodeType::Conclusion | NodeType::Subflow | NodeType::Return))
+    {
+        // Only questions are answered, so only they can be unsure about
+        query.push_str(", not_sure_node_id = NULL");
+    }
+    if req.content.is_some() {
+        param_count += 1;
+        query.push_str(&format!(", content = ${}", param_count));
+    }
     if req.is_active.is_some() {
         param_count += 1;
         query.push_str(&format!(", is_active = ${}", param_count));
     }
+    // Only conclusions keep knowledge base links
+    if matches!(req.node_type, Some(NodeType::Question | NodeType::Subflow | NodeType::Return)) {
+        query.push_str(", kb_url = NULL, doc_reference = NULL");
+    }
 
-    query.push_str(" WHERE id = $1 RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at");
+    query.push_str(" WHERE id = $1 RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at");
 
-    let mut query_builder = sqlx::query_as::<_, Node>(&query).bind(id);
+    let mut query_builder = sqlx::query_as::<_, Node>(&query).bind(id).bind(subflow_category);
 
     if let Some(ref text) = req.text {
         query_builder = query_builder.bind(text);
     }
-    if let Some(ref semantic_id) = req.semantic_id {
+    if let Some(ref semantic_id) = semantic_id {
         query_builder = query_builder.bind(semantic_id);
     }
     if let Some(ref node_type) = req.node_type {
@@ -208,20 +433,40 @@ pub async fn update_node(
     if let Some(ref position_y) = req.position_y {
         query_builder = query_builder.bind(position_y);
     }
+    if let Some(ref kb_url) = req.kb_url {
+        query_builder = query_builder.bind(kb_url);
+    }
+    if let Some(ref doc_reference) = req.doc_reference {
+        query_builder = query_builder.bind(doc_reference);
+    }
+    if let Some(option_order) = req.option_order {
+        query_builder = query_builder.bind(option_order);
+    }
+    if let Some(answer_mode) = req.answer_mode {
+        query_builder = query_builder.bind(answer_mode);
+    }
+    if let Some(not_sure_node_id) = req.not_sure_node_id {
+        query_builder = query_builder.bind(not_sure_node_id);
+    }
+    if req.content.is_some() {
+        query_builder = query_builder.bind(content.as_ref().map(sqlx::types::Json));
+    }
     if let Some(ref is_active) = req.is_active {
         query_builder = query_builder.bind(is_active);
     }
 
-    let node = query_builder.fetch_one(&state.db).await?;
+    let node = query_builder
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| semantic_ids::map_taken("semantic_id", semantic_id.as_ref().and_then(Option::as_deref), e))?;
+
+    node_revisions::record(&mut tx, id, user_id, &node_revisions::diff(&before, &node), None).await?;
+    tx.commit().await?;
 
     // Invalidate cache for the category
-    let cache_key = format!("graph_{}", node.category);
-    state.issue_graph_cache.invalidate(&cache_key).await;
-    state.issue_tree_cache.invalidate(&node.category).await;
+    state.invalidate_issue(&node.category).await;
 
     // Audit log the node update
-    let user_id = Uuid::parse_str(&auth.0.sub)
-        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
     let ip = audit::extract_ip_address(&headers);
 
     audit::log_event(
@@ -241,6 +486,154 @@ pub async fn update_node(
     Ok(Json(node))
 }
 
+/// POST /api/nodes/:id/restore
+/// Switch an inactive node back on (ADMIN only)
+pub async fn restore_node(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Node>> {
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let mut tx = state.db.begin().await?;
+    let before = fetch_node_for_update(&mut tx, id).await?;
+    if before.is_active {
+        return Err(ApiError::bad_request("Node is already active"));
+    }
+
+    let node = sqlx::query_as::<_, Node>(
+        "UPDATE nodes SET is_active = true, updated_at = NOW() WHERE id = $1
+         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at"
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    node_revisions::record(&mut tx, id, user_id, &node_revisions::diff(&before, &node), None).await?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&node.category).await;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NodeRestored,
+        "node",
+        Some(&node.id.to_string()),
+        Some(json!({
+            "category": &node.category,
+            "text": &node.text,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(node))
+}
+
+/// Most nodes one layout save can move
+const POSITIONS_MAX_NODES: usize = 5000;
+
+/// PATCH /api/nodes/positions
+/// Save a graph layout: move many nodes in one statement (ADMIN only)
+///
+/// Positions aren't tracked in node history, so this only logs one audit
+/// event per save.
+pub async fn update_node_positions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(positions): Json<Vec<NodePosition>>,
+) -> ApiResult<Json<NodePositionsUpdated>> {
+    if positions.is_empty() {
+        return Ok(Json(NodePositionsUpdated { updated: 0 }));
+    }
+    if positions.len() > POSITIONS_MAX_NODES {
+        return Err(ApiError::validation_at(vec![(
+            String::new(),
+            format!("At most {} nodes can be moved at once", POSITIONS_MAX_NODES),
+        )]));
+    }
+
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    for (i, position) in positions.iter().enumerate() {
+        if !seen.insert(position.id) {
+            errors.push((format!("/{}/id", i), "Node is listed more than once".to_string()));
+        }
+        if !position.position_x.is_finite() {
+            errors.push((format!("/{}/position_x", i), "Must be a finite number".to_string()));
+        }
+        if !position.position_y.is_finite() {
+            errors.push((format!("/{}/position_y", i), "Must be a finite number".to_string()));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::validation_at(errors));
+    }
+
+    let ids: Vec<Uuid> = positions.iter().map(|p| p.id).collect();
+    let xs: Vec<f64> = positions.iter().map(|p| p.position_x).collect();
+    let ys: Vec<f64> = positions.iter().map(|p| p.position_y).collect();
+
+    let found = sqlx::query_as::<_, (Uuid, String)>("SELECT id, category FROM nodes WHERE id = ANY($1)")
+        .bind(&ids)
+        .fetch_all(&state.db)
+        .await?;
+    if found.len() < ids.len() {
+        let known: HashSet<Uuid> = found.iter().map(|(id, _)| *id).collect();
+        let missing: Vec<String> = ids.iter().filter(|id| !known.contains(id)).map(Uuid::to_string).collect();
+        return Err(ApiError::not_found(format!("Node(s) not found: {}", missing.join(", "))));
+    }
+
+    let mut categories: Vec<&str> = found.iter().map(|(_, category)| category.as_str()).collect();
+    categories.sort_unstable();
+    categories.dedup();
+    category_access::ensure_can_edit(&state.db, &auth, categories.iter().copied()).await?;
+
+    let updated = sqlx::query(
+        "UPDATE nodes n SET position_x = p.x, position_y = p.y
+         FROM UNNEST($1::uuid[], $2::float8[], $3::float8[]) AS p(id, x, y)
+         WHERE n.id = p.id AND (n.position_x IS DISTINCT FROM p.x OR n.position_y IS DISTINCT FROM p.y)",
+    )
+    .bind(&ids)
+    .bind(&xs)
+    .bind(&ys)
+    .execute(&state.db)
+    .await?
+    .rows_affected();
+
+    for category in &categories {
+        state.invalidate_issue(category).await;
+    }
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NodePositionsUpdated,
+        "node",
+        None,
+        Some(json!({
+            "categories": &categories,
+            "nodes": positions.len(),
+            "moved": updated,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(NodePositionsUpdated { updated }))
+}
+
 /// DELETE /api/nodes/:id
 /// Hard delete a node and all its connections (ADMIN only)
 pub async fn delete_node(
@@ -251,7 +644,7 @@ pub async fn delete_node(
 ) -> ApiResult<Json<Node>> {
     // Fetch the node first to return it after deletion
     let node = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE id = $1"
     )
@@ -260,6 +653,8 @@ pub async fn delete_node(
     .await?
     .ok_or_else(|| ApiError::not_found("Node not found"))?;
 
+    category_access::ensure_can_edit(&state.db, &auth, [node.category.as_str()]).await?;
+
     // Delete all connections FROM this node
     sqlx::query("DELETE FROM connections WHERE from_node_id = $1")
         .bind(id)
@@ -277,11 +672,10 @@ pub async fn delete_node(
         .bind(id)
         .execute(&state.db)
         .await?;
+    uploads::delete_node_attachments(&state.db, id).await?;
 
     // Invalidate cache for the category
-    let cache_key = format!("graph_{}", node.category);
-    state.issue_graph_cache.invalidate(&cache_key).await;
-    state.issue_tree_cache.invalidate(&node.category).await;
+    state.invalidate_issue(&node.category).await;
 
     // Audit log the node deletion
     let user_id = Uuid::parse_str(&auth.0.sub)
@@ -306,6 +700,631 @@ pub async fn delete_node(
     Ok(Json(node))
 }
 
+/// How far a copy sits from the original, down and to the right, so both stay visible
+const CLONE_OFFSET: f64 = 40.0;
+
+/// First free "<base>_copy", "<base>_copy_2", ... semantic ID
+fn copy_semantic_id(base: &str, taken: &HashSet<String>) -> String {
+    let first = format!("{}_copy", base);
+    if !taken.contains(&first) {
+        return first;
+    }
+    (2..)
+        .map(|n| format!("{}_copy_{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("unbounded range always yields a free suffix")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloneNodeQuery {
+    /// Also copy the node's outgoing connections, pointing at the same targets
+    #[serde(default)]
+    pub with_connections: bool,
+}
+
+/// POST /api/nodes/:id/clone
+/// Copy a node into the same category (ADMIN only)
+///
+/// The copy is offset slightly from the original and gets a "_copy" suffix
+/// on its semantic ID. Nothing leads to it until it is connected.
+pub async fn clone_node(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Query(query): Query<CloneNodeQuery>,
+) -> ApiResult<Json<ClonedNode>> {
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
+
+    let mut tx = state.db.begin().await?;
+    let source = fetch_node_for_update(&mut tx, id).await?;
+
+    let semantic_id = match source.semantic_id.as_deref() {
+        Some(base) => {
+            // Wildcards in the base only widen the match; exact names are checked below
+            let taken: HashSet<String> = sqlx::query_scalar::<_, String>(
+                "SELECT semantic_id FROM nodes WHERE semantic_id LIKE $1 || '_copy%'",
+            )
+            .bind(base)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+            Some(copy_semantic_id(base, &taken))
+        }
+        None => None,
+    };
+
+    let node = sqlx::query_as::<_, Node>(
+        "INSERT INTO nodes (category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at"
+    )
+    .bind(&source.category)
+    .bind(&source.node_type)
+    .bind(&source.text)
+    .bind(&semantic_id)
+    .bind(&source.display_category)
+    .bind(source.position_x.map(|x| x + CLONE_OFFSET))
+    .bind(source.position_y.map(|y| y + CLONE_OFFSET))
+    .bind(&source.kb_url)
+    .bind(&source.doc_reference)
+    .bind(source.option_order)
+    .bind(source.answer_mode)
+    .bind(source.not_sure_node_id)
+    .bind(&source.subflow_category)
+    .bind(source.content.as_ref().map(sqlx::types::Json))
+    .bind(source.is_active)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let connections = if query.with_connections {
+        sqlx::query_as::<_, Connection>(
+            "INSERT INTO connections (from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active)
+             SELECT $2, to_node_id, label, order_index, weight, condition, unsure_route, is_active
+             FROM connections
+             WHERE from_node_id = $1
+             ORDER BY order_index, created_at
+             RETURNING id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at"
+        )
+        .bind(id)
+        .bind(node.id)
+        .fetch_all(&mut *tx)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    tx.commit().await?;
+
+    state.invalidate_issue(&node.category).await;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NodeCloned,
+        "node",
+        Some(&node.id.to_string()),
+        Some(json!({
+            "category": &node.category,
+            "source_node_id": id,
+            "semantic_id": &node.semantic_id,
+            "connections_copied": connections.len(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ClonedNode { node, connections }))
+}
+
+/// `base` named after `target` instead of `source`, when it was named after `source`
+fn renamed_for_category(base: &str, source: &str, target: &str) -> String {
+    match base.strip_prefix(source).and_then(|rest| rest.strip_prefix('_')) {
+        Some(rest) => format!("{}_{}", target, rest),
+        None => base.to_string(),
+    }
+}
+
+/// Semantic ID for a node copied from one category into another
+///
+/// IDs named after the source category are renamed after the target
+/// (`pump_check_fuse` becomes `fan_check_fuse`); taken ones get a copy suffix.
+fn transferred_semantic_id(base: &str, source: &str, target: &str, taken: &HashSet<String>) -> String {
+    let renamed = renamed_for_category(base, source, target);
+    if taken.contains(&renamed) {
+        copy_semantic_id(&renamed, taken)
+    } else {
+        renamed
+    }
+}
+
+/// POST /api/nodes/:id/subtree
+/// Copy or move a node and everything reachable from it into another category (ADMIN only)
+///
+/// The subtree follows answers (switched on or not) and "I'm not sure"
+/// options within the node's category. A copy gets new IDs with its answers
+/// rewired among the copies; answers leaving the subtree keep their targets,
+/// and conditions are copied as written. A move only changes the nodes'
+/// category, so answers into the subtree from the rest of the source issue
+/// keep working as cross-category links. The start node can be copied but
+/// not moved.
+pub async fn transfer_subtree(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<TransferSubtree>,
+) -> ApiResult<Json<SubtreeTransferred>> {
+    let target = req.target_category.trim().to_string();
+    let source = sqlx::query_scalar::<_, String>("SELECT category FROM nodes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Node not found"))?;
+
+    if target.is_empty() {
+        return Err(ApiError::validation(vec![(
+            "target_category".to_string(),
+            "Target category is required".to_string(),
+        )]));
+    }
+    if target == source {
+        return Err(ApiError::validation(vec![(
+            "target_category".to_string(),
+            "Target category must differ from the node's category".to_string(),
+        )]));
+    }
+
+    category_access::ensure_can_edit(&state.db, &auth, [source.as_str(), target.as_str()]).await?;
+
+    let target_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(&target)
+        .fetch_one(&state.db)
+        .await?;
+    if !target_exists {
+        return Err(ApiError::not_found(format!("Category '{}' not found", target)));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT c.from_node_id, c.to_node_id
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE f.category = $1 AND t.category = $1
+         UNION ALL
+         SELECT f.id, t.id FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE f.category = $1 AND t.category = $1",
+    )
+    .bind(&source)
+    .fetch_all(&mut *tx)
+    .await?;
+    let subtree = orphans::reachable([id], &edges);
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let (node_ids, connections) = match req.mode {
+        SubtreeMode::Move => {
+            let start = format!("{}_start", source);
+            let moves_start = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM nodes WHERE id = ANY($1) AND semantic_id = $2)",
+            )
+            .bind(&subtree)
+            .bind(&start)
+            .fetch_one(&mut *tx)
+            .await?;
+            if moves_start {
+                return Err(ApiError::bad_request(format!(
+                    "This subtree contains the start of '{}', which can't be moved out; copy it instead",
+                    source
+                )));
+            }
+
+            sqlx::query("UPDATE nodes SET category = $2, updated_at = NOW() WHERE id = ANY($1)")
+                .bind(&subtree)
+                .bind(&target)
+                .execute(&mut *tx)
+                .await?;
+            (subtree, 0)
+        }
+        SubtreeMode::Copy => {
+            let semantic_ids = sqlx::query_as::<_, (Uuid, Option<String>)>(
+                "SELECT id, semantic_id FROM nodes WHERE id = ANY($1)",
+            )
+            .bind(&subtree)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+
+            // Names the copies could clash with, including suffixed ones; wildcards
+            // in the names only widen the match
+            let patterns: Vec<String> = semantic_ids
+                .values()
+                .flatten()
+                .map(|base| format!("{}%", renamed_for_category(base, &source, &target)))
+                .collect();
+            let mut taken: HashSet<String> = sqlx::query_scalar::<_, String>(
+                "SELECT semantic_id FROM nodes WHERE semantic_id LIKE ANY($1)",
+            )
+            .bind(&patterns)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+            let new_ids: Vec<Uuid> = subtree.iter().map(|_| Uuid::new_v4()).collect();
+            let new_semantic_ids: Vec<Option<String>> = subtree
+                .iter()
+                .map(|old_id| {
+                    semantic_ids.get(old_id).cloned().flatten().map(|base| {
+                        let semantic_id = transferred_semantic_id(&base, &source, &target, &taken);
+                        taken.insert(semantic_id.clone());
+                        semantic_id
+                    })
+                })
+                .collect();
+
+            sqlx::query(
+                "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active)
+                 SELECT m.new_id, $4, n.node_type, n.text, m.semantic_id, n.display_category, n.position_x, n.position_y, n.kb_url, n.doc_reference, n.option_order, n.answer_mode, COALESCE(ns.new_id, n.not_sure_node_id), n.subflow_category, n.content, n.is_active
+                 FROM UNNEST($1::uuid[], $2::uuid[], $3::varchar[]) AS m(old_id, new_id, semantic_id)
+                 JOIN nodes n ON n.id = m.old_id
+                 LEFT JOIN UNNEST($1::uuid[], $2::uuid[]) AS ns(old_id, new_id) ON ns.old_id = n.not_sure_node_id",
+            )
+            .bind(&subtree)
+            .bind(&new_ids)
+            .bind(&new_semantic_ids)
+            .bind(&target)
+            .execute(&mut *tx)
+            .await?;
+
+            let copied = sqlx::query(
+                "INSERT INTO connections (from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active)
+                 SELECT f.new_id, COALESCE(t.new_id, c.to_node_id), c.label, c.order_index, c.weight, c.condition, c.unsure_route, c.is_active
+                 FROM connections c
+                 JOIN UNNEST($1::uuid[], $2::uuid[]) AS f(old_id, new_id) ON f.old_id = c.from_node_id
+                 LEFT JOIN UNNEST($1::uuid[], $2::uuid[]) AS t(old_id, new_id) ON t.old_id = c.to_node_id
+                 ORDER BY c.from_node_id, c.order_index, c.created_at",
+            )
+            .bind(&subtree)
+            .bind(&new_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            (new_ids, copied)
+        }
+    };
+
+    tx.commit().await?;
+
+    state.invalidate_issue(&source).await;
+    state.invalidate_issue(&target).await;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NodeSubtreeTransferred,
+        "node",
+        Some(&id.to_string()),
+        Some(json!({
+            "mode": req.mode,
+            "source_category": &source,
+            "target_category": &target,
+            "root_node_id": node_ids[0],
+            "nodes": node_ids.len(),
+            "connections": connections,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(SubtreeTransferred {
+        mode: req.mode,
+        source_category: source,
+        target_category: target,
+        root_node_id: node_ids[0],
+        node_ids,
+        connections,
+    }))
+}
+
+/// POST /api/nodes/:id/merge
+/// Merge a duplicate node into another (ADMIN only)
+///
+/// Answers leading to the duplicate are pointed at the target instead, as are
+/// "I'm not sure" options, entry points and notices that lead to it. The
+/// duplicate is then deactivated rather than deleted, so its history and past
+/// sessions stay readable. Both nodes must be in the same category and of the
+/// same type, and the category's start node can't be merged away.
+pub async fn merge_nodes(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<MergeNode>,
+) -> ApiResult<Json<NodesMerged>> {
+    let target_id = req.target_node_id;
+    if target_id == id {
+        return Err(ApiError::validation(vec![(
+            "target_node_id".to_string(),
+            "A node can't be merged into itself".to_string(),
+        )]));
+    }
+
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    // Lock both rows in a fixed order so concurrent merges can't deadlock
+    let mut tx = state.db.begin().await?;
+    let (source, target) = if id < target_id {
+        let source = fetch_node_for_update(&mut tx, id).await?;
+        (source, fetch_node_for_update(&mut tx, target_id).await)
+    } else {
+        let target = fetch_node_for_update(&mut tx, target_id).await;
+        (fetch_node_for_update(&mut tx, id).await?, target)
+    };
+    let target = target.map_err(|_| ApiError::not_found("Target node not found"))?;
+
+    let message = if target.category != source.category {
+        Some("Target node must be in the same category")
+    } else if target.node_type != source.node_type {
+        Some("Target node must be of the same type")
+    } else if !target.is_active {
+        Some("Target node is inactive")
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        return Err(ApiError::validation(vec![("target_node_id".to_string(), message.to_string())]));
+    }
+    if source.semantic_id.as_deref() == Some(format!("{}_start", source.category).as_str()) {
+        return Err(ApiError::bad_request(format!(
+            "The start node of '{}' can't be merged into another node",
+            source.category
+        )));
+    }
+
+    let connections_rewired = sqlx::query(
+        "UPDATE connections SET to_node_id = $2, updated_at = NOW() WHERE to_node_id = $1 AND from_node_id <> $2",
+    )
+    .bind(id)
+    .bind(target_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    // The target's own answers into the duplicate would become loops
+    sqlx::query("UPDATE connections SET is_active = false, updated_at = NOW() WHERE to_node_id = $1 AND from_node_id = $2")
+        .bind(id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "UPDATE nodes SET not_sure_node_id = CASE WHEN id = $2 THEN NULL ELSE $2 END, updated_at = NOW()
+         WHERE not_sure_node_id = $1",
+    )
+    .bind(id)
+    .bind(target_id)
+    .execute(&mut *tx)
+    .await?;
+    let entry_points = sqlx::query("UPDATE entry_points SET node_id = $2 WHERE node_id = $1")
+        .bind(id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    let notices = sqlx::query("UPDATE category_notices SET conclusion_node_id = $2 WHERE conclusion_node_id = $1")
+        .bind(id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let merged = sqlx::query_as::<_, Node>(
+        "UPDATE nodes SET is_active = false, updated_at = NOW() WHERE id = $1
+         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    node_revisions::record(&mut tx, id, user_id, &node_revisions::diff(&source, &merged), None).await?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&merged.category).await;
+    if entry_points > 0 {
+        state.questions_cache.clear().await;
+    }
+    if notices > 0 {
+        category_notices::reload(&state.db).await?;
+    }
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NodeMerged,
+        "node",
+        Some(&id.to_string()),
+        Some(json!({
+            "category": &merged.category,
+            "target_node_id": target_id,
+            "connections_rewired": connections_rewired,
+            "entry_points": entry_points,
+            "notices": notices,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(NodesMerged {
+        source: merged,
+        target_node_id: target_id,
+        connections_rewired,
+    }))
+}
+
+/// Maximum number of revisions returned by the history endpoint
+const HISTORY_MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct NodeHistoryQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/nodes/:id/history
+/// Field-level change history of a node, newest first
+pub async fn get_node_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<NodeHistoryQuery>,
+) -> ApiResult<Json<Vec<node_revisions::NodeRevision>>> {
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&state.db)
+        .await?;
+    if !exists {
+        return Err(ApiError::not_found("Node not found"));
+    }
+
+    let limit = query.limit.unwrap_or(100).clamp(1, HISTORY_MAX_LIMIT);
+    let revisions = node_revisions::list(&state.db, id, limit).await?;
+
+    Ok(Json(revisions))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevertRevisionQuery {
+    /// Revert even if the field was changed again after the revision
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// POST /api/nodes/:id/history/:revision_id/revert
+/// Set one field back to the value it had before a revision
+///
+/// Fails with 409 when the field has changed since the revision, unless
+/// `force=true`. The revert itself is recorded as a new revision.
+pub async fn revert_node_revision(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path((id, revision_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<RevertRevisionQuery>,
+) -> ApiResult<Json<Node>> {
+    let (field, old_value, new_value) = sqlx::query_as::<_, (String, serde_json::Value, serde_json::Value)>(
+        "SELECT field, old_value, new_value FROM node_revisions WHERE id = $1 AND node_id = $2",
+    )
+    .bind(revision_id)
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Revision not found"))?;
+
+    if !node_revisions::TRACKED_FIELDS.contains(&field.as_str()) {
+        return Err(ApiError::bad_request(format!("Field '{}' can no longer be reverted", field)));
+    }
+
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
+
+    let user_id = Uuid::parse_str(&auth.0.sub)
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let mut tx = state.db.begin().await?;
+    let before = fetch_node_for_update(&mut tx, id).await?;
+
+    let mut reverted = serde_json::to_value(&before).map_err(|e| ApiError::internal(e.to_string()))?;
+    let current_value = reverted.get(&field).cloned().unwrap_or(serde_json::Value::Null);
+    if current_value != new_value && !query.force {
+        return Err(ApiError::Conflict {
+            message: format!(
+                "Field '{}' has changed since this revision; pass force=true to revert anyway",
+                field
+            ),
+        });
+    }
+
+    reverted[field.as_str()] = old_value;
+    let mut reverted: Node = serde_json::from_value(reverted)
+        .map_err(|e| ApiError::internal(format!("Failed to apply revision: {}", e)))?;
+    // Questions can't keep knowledge base links from when they were conclusions
+    if !matches!(reverted.node_type, NodeType::Conclusion) && field == "node_type" {
+        reverted.kb_url = None;
+        reverted.doc_reference = None;
+    }
+    if !matches!(reverted.node_type, NodeType::Question) && field == "node_type" {
+        reverted.not_sure_node_id = None;
+    }
+    if !matches!(reverted.node_type, NodeType::Subflow) && field == "node_type" {
+        reverted.subflow_category = None;
+    }
+    validate_kb_fields(&reverted.node_type, reverted.kb_url.as_deref(), reverted.doc_reference.as_deref())?;
+    validate_not_sure_target(&mut tx, id, &reverted.node_type, reverted.not_sure_node_id).await?;
+    validate_subflow_category(&mut tx, &reverted.category, &reverted.node_type, reverted.subflow_category.as_deref()).await?;
+
+    let node = sqlx::query_as::<_, Node>(
+        "UPDATE nodes
+         SET text = $2, semantic_id = $3, node_type = $4, display_category = $5, kb_url = $6,
+             doc_reference = $7, option_order = $8, answer_mode = $9, not_sure_node_id = $10, is_active = $11,
+             subflow_category = $12, content = $13, updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at"
+    )
+    .bind(id)
+    .bind(&reverted.text)
+    .bind(&reverted.semantic_id)
+    .bind(&reverted.node_type)
+    .bind(&reverted.display_category)
+    .bind(&reverted.kb_url)
+    .bind(&reverted.doc_reference)
+    .bind(reverted.option_order)
+    .bind(reverted.answer_mode)
+    .bind(reverted.not_sure_node_id)
+    .bind(reverted.is_active)
+    .bind(&reverted.subflow_category)
+    .bind(reverted.content.as_ref().map(sqlx::types::Json))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let changes = node_revisions::diff(&before, &node);
+    node_revisions::record(&mut tx, id, user_id, &changes, Some(revision_id)).await?;
+    tx.commit().await?;
+
+    state.invalidate_issue(&node.category).await;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NodeRevisionReverted,
+        "node",
+        Some(&node.id.to_string()),
+        Some(json!({
+            "category": &node.category,
+            "revision_id": revision_id,
+            "field": &field,
+            "forced": query.force,
+            "changed_fields": changes.iter().map(|c| c.field).collect::<Vec<_>>(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(node))
+}
+
 /// GET /api/nodes/:id/with-connections
 /// Get a node with all its outgoing connections and target node details
 pub async fn get_node_with_connections(
@@ -314,7 +1333,7 @@ pub async fn get_node_with_connections(
 ) -> ApiResult<Json<NodeWithConnections>> {
     // Get the node
     let node = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE id = $1"
     )
@@ -338,6 +1357,13 @@ pub async fn get_node_with_connections(
             n.display_category as target_display_category,
             n.position_x as target_position_x,
             n.position_y as target_position_y,
+            n.kb_url as target_kb_url,
+            n.doc_reference as target_doc_reference,
+            n.option_order as "target_option_order: OptionOrder",
+            n.answer_mode as "target_answer_mode: AnswerMode",
+            n.not_sure_node_id as target_not_sure_node_id,
+            n.subflow_category as target_subflow_category,
+            n.content as "target_content: sqlx::types::Json<NodeContent>",
             n.is_active as target_is_active,
             n.created_at as target_created_at,
             n.updated_at as target_updated_at
@@ -364,6 +1390,8 @@ pub async fn get_node_with_connections(
                     node_type: match row.target_node_type.as_str() {
                         "question" => NodeType::Question,
                         "conclusion" => NodeType::Conclusion,
+                        "subflow" => NodeType::Subflow,
+                        "return" => NodeType::Return,
                         _ => NodeType::Question,
                     },
                     text: row.target_text,
@@ -371,6 +1399,13 @@ pub async fn get_node_with_connections(
                     display_category: row.target_display_category,
                     position_x: row.target_position_x,
                     position_y: row.target_position_y,
+                    kb_url: row.target_kb_url,
+                    doc_reference: row.target_doc_reference,
+                    option_order: row.target_option_order,
+                    answer_mode: row.target_answer_mode,
+                    not_sure_node_id: row.target_not_sure_node_id,
+                    subflow_category: row.target_subflow_category,
+                    content: row.target_content.map(|content| content.0),
                     is_active: row.target_is_active.unwrap_or(true),
                     created_at: row.target_created_at.unwrap_or_else(chrono::Utc::now),
                     updated_at: row.target_updated_at.unwrap_or_else(chrono::Utc::now),
@@ -384,3 +1419,34 @@ pub async fn get_node_with_connections(
         connections,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_pattern() {
+        assert_eq!(contains_pattern(" fuse "), "%fuse%");
+        assert_eq!(contains_pattern("100%_ok\\"), "%100\\%\\_ok\\\\%");
+    }
+
+    #[test]
+    fn test_copy_semantic_id() {
+        let mut taken = HashSet::new();
+        assert_eq!(copy_semantic_id("pump_start", &taken), "pump_start_copy");
+
+        taken.insert("pump_start_copy".to_string());
+        taken.insert("pump_start_copy_2".to_string());
+        assert_eq!(copy_semantic_id("pump_start", &taken), "pump_start_copy_3");
+    }
+
+    #[test]
+    fn test_transferred_semantic_id() {
+        let taken = HashSet::from(["fan_start".to_string(), "check_power".to_string()]);
+        assert_eq!(transferred_semantic_id("pump_check_fuse", "pump", "fan", &taken), "fan_check_fuse");
+        assert_eq!(transferred_semantic_id("pump_start", "pump", "fan", &taken), "fan_start_copy");
+        assert_eq!(transferred_semantic_id("check_power", "pump", "fan", &taken), "check_power_copy");
+        // Only a whole category name counts as the prefix
+        assert_eq!(transferred_semantic_id("pumpkin_spice", "pump", "fan", &taken), "pumpkin_spice");
+    }
+}
@@ -0,0 +1,254 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::routes::users::acting_user;
+use crate::utils::category_notices::{self, CategoryNotice, NOTICE_COLUMNS};
+use crate::utils::{audit, category_access};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Request to put a notice on a category
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CreateNoticeRequest {
+    pub category: String,
+    pub message: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub conclusion_node_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Request to change a notice; replaces its message, conclusion and window
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateNoticeRequest {
+    pub message: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub conclusion_node_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Query parameters for list_notices
+#[derive(Debug, Deserialize)]
+pub struct NoticesQuery {
+    pub category: Option<String>,
+    /// Include notices that have ended
+    #[serde(default)]
+    pub include_expired: bool,
+}
+
+async fn fetch_notice(state: &AppState, id: Uuid) -> ApiResult<CategoryNotice> {
+    sqlx::query_as::<_, CategoryNotice>(&format!("SELECT {} FROM category_notices WHERE id = $1", NOTICE_COLUMNS))
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Notice not found"))
+}
+
+/// Check the message and window, and that the suggested node is a conclusion
+async fn validate_notice(
+    state: &AppState,
+    message: &str,
+    conclusion_node_id: Option<Uuid>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> ApiResult<()> {
+    let mut errors = category_notices::validate(message, starts_at, ends_at);
+
+    if let Some(node_id) = conclusion_node_id {
+        let is_conclusion = sqlx::query_scalar::<_, bool>("SELECT node_type = 'conclusion' FROM nodes WHERE id = $1")
+            .bind(node_id)
+            .fetch_optional(&state.db)
+            .await?;
+        match is_conclusion {
+            None => errors.push(("conclusion_node_id".to_string(), "Node does not exist".to_string())),
+            Some(false) => errors.push(("conclusion_node_id".to_string(), "Must be a conclusion node".to_string())),
+            Some(true) => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::validation(errors))
+    }
+}
+
+/// GET /api/admin/notices
+/// List category notices (current and upcoming unless `include_expired` is set)
+pub async fn list_notices(
+    State(state): State<AppState>,
+    Query(params): Query<NoticesQuery>,
+) -> ApiResult<Json<Vec<CategoryNotice>>> {
+    let notices = sqlx::query_as::<_, CategoryNotice>(&format!(
+        "SELECT {} FROM category_notices
+         WHERE ($1::text IS NULL OR category = $1)
+           AND ($2 OR ends_at > NOW())
+         ORDER BY starts_at DESC, created_at DESC",
+        NOTICE_COLUMNS
+    ))
+    .bind(params.category.as_deref())
+    .bind(params.include_expired)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(notices))
+}
+
+/// POST /api/admin/notices
+/// Put a time-boxed notice on a category
+pub async fn create_notice(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<CreateNoticeRequest>,
+) -> ApiResult<Json<CategoryNotice>> {
+    let user_id = acting_user(&auth)?;
+
+    let category_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(&req.category)
+        .fetch_one(&state.db)
+        .await?;
+    if !category_exists {
+        return Err(ApiError::validation(vec![(
+            "category".to_string(),
+            "Category does not exist".to_string(),
+        )]));
+    }
+    category_access::ensure_can_edit(&state.db, &auth, [req.category.as_str()]).await?;
+    validate_notice(&state, &req.message, req.conclusion_node_id, req.starts_at, req.ends_at).await?;
+
+    let notice = sqlx::query_as::<_, CategoryNotice>(&format!(
+        "INSERT INTO category_notices (category, message, conclusion_node_id, starts_at, ends_at, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING {}",
+        NOTICE_COLUMNS
+    ))
+    .bind(&req.category)
+    .bind(req.message.trim())
+    .bind(req.conclusion_node_id)
+    .bind(req.starts_at)
+    .bind(req.ends_at)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    category_notices::reload(&state.db).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NoticeCreated,
+        "category_notice",
+        Some(&notice.id.to_string()),
+        Some(json!({
+            "category": &notice.category,
+            "starts_at": notice.starts_at,
+            "ends_at": notice.ends_at,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(notice))
+}
+
+/// PUT /api/admin/notices/:id
+/// Change a notice's message, suggested conclusion or window (end it early by moving `ends_at`)
+pub async fn update_notice(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateNoticeRequest>,
+) -> ApiResult<Json<CategoryNotice>> {
+    let user_id = acting_user(&auth)?;
+
+    let before = fetch_notice(&state, id).await?;
+    category_access::ensure_can_edit(&state.db, &auth, [before.category.as_str()]).await?;
+    validate_notice(&state, &req.message, req.conclusion_node_id, req.starts_at, req.ends_at).await?;
+
+    let notice = sqlx::query_as::<_, CategoryNotice>(&format!(
+        "UPDATE category_notices
+         SET message = $2, conclusion_node_id = $3, starts_at = $4, ends_at = $5, updated_at = NOW()
+         WHERE id = $1
+         RETURNING {}",
+        NOTICE_COLUMNS
+    ))
+    .bind(id)
+    .bind(req.message.trim())
+    .bind(req.conclusion_node_id)
+    .bind(req.starts_at)
+    .bind(req.ends_at)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Notice not found"))?;
+
+    category_notices::reload(&state.db).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NoticeUpdated,
+        "category_notice",
+        Some(&notice.id.to_string()),
+        Some(json!({
+            "category": &notice.category,
+            "before": { "starts_at": before.starts_at, "ends_at": before.ends_at },
+            "updates": &req,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(notice))
+}
+
+/// DELETE /api/admin/notices/:id
+/// Remove a notice; it stops showing immediately on this instance and within a minute elsewhere
+pub async fn delete_notice(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<CategoryNotice>> {
+    let user_id = acting_user(&auth)?;
+
+    let notice = fetch_notice(&state, id).await?;
+    category_access::ensure_can_edit(&state.db, &auth, [notice.category.as_str()]).await?;
+
+    sqlx::query("DELETE FROM category_notices WHERE id = $1")
+        .bind(id)
+        .execute(&state.db)
+        .await?;
+
+    category_notices::reload(&state.db).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::NoticeDeleted,
+        "category_notice",
+        Some(&notice.id.to_string()),
+        Some(json!({ "category": &notice.category, "message": &notice.message })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(notice))
+}
@@ -0,0 +1,344 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::models::{Permission, UserRole};
+use crate::routes::users::{acting_user, ensure_email_available, UserSummary, USER_COLUMNS};
+use crate::utils::{audit, service_tokens};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Maximum length of service account and token names
+const NAME_MAX_LENGTH: usize = 64;
+
+/// Domain of the placeholder email given to service accounts (reserved, never delivers)
+const SERVICE_EMAIL_DOMAIN: &str = "service.invalid";
+
+/// A service account with its tokens
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ServiceAccount {
+    #[serde(flatten)]
+    pub user: UserSummary,
+    pub tokens: Vec<ServiceTokenInfo>,
+}
+
+/// A service token without its secret
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ServiceTokenInfo {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// First characters of the token, to recognise it
+    pub token_prefix: String,
+    pub scopes: Vec<Permission>,
+    pub created_at: DateTime<Utc>,
+    /// Approximate (recorded about once a minute)
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A newly issued token; `token` is shown only in this response
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssuedServiceToken {
+    pub token: String,
+    pub info: ServiceTokenInfo,
+}
+
+/// Request to create a service account
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CreateServiceAccountRequest {
+    /// Lowercase letters, digits, `-` and `_` (e.g. `ci-import`)
+    pub name: String,
+}
+
+/// Request to issue a token for a service account
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CreateServiceTokenRequest {
+    /// What the token is for (e.g. "nightly export")
+    pub name: String,
+    pub scopes: Vec<Permission>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ServiceTokenRow {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    token_prefix: String,
+    scopes: Vec<String>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ServiceTokenRow> for ServiceTokenInfo {
+    fn from(row: ServiceTokenRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            name: row.name,
+            token_prefix: row.token_prefix,
+            scopes: row.scopes.iter().filter_map(|s| Permission::parse(s)).collect(),
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+const TOKEN_COLUMNS: &str = "id, user_id, name, token_prefix, scopes, created_at, last_used_at, revoked_at";
+
+fn validate_account_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > NAME_MAX_LENGTH {
+        return Err(format!("Must be 1-{} characters", NAME_MAX_LENGTH));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        return Err("Only lowercase letters, digits, '-' and '_' are allowed".to_string());
+    }
+    Ok(())
+}
+
+/// Scopes must be non-empty and exclude account/system administration
+fn validate_scopes(scopes: &[Permission]) -> Result<(), String> {
+    if scopes.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+    if let Some(denied) = scopes.iter().find(|p| !service_tokens::is_allowed_scope(**p)) {
+        return Err(format!("Service tokens cannot hold '{}'", denied.as_str()));
+    }
+    Ok(())
+}
+
+async fn fetch_service_account(state: &AppState, id: Uuid) -> ApiResult<UserSummary> {
+    sqlx::query_as::<_, UserSummary>(&format!(
+        "SELECT {} FROM users WHERE id = $1 AND is_service",
+        USER_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Service account not found"))
+}
+
+/// GET /api/admin/service-accounts
+/// List service accounts with their tokens (secrets are never returned)
+pub async fn list_service_accounts(State(state): State<AppState>) -> ApiResult<Json<Vec<ServiceAccount>>> {
+    let users = sqlx::query_as::<_, UserSummary>(&format!(
+        "SELECT {} FROM users WHERE is_service ORDER BY email",
+        USER_COLUMNS
+    ))
+    .fetch_all(&state.db)
+    .await?;
+
+    let rows = sqlx::query_as::<_, ServiceTokenRow>(&format!(
+        "SELECT {} FROM service_tokens WHERE user_id = ANY($1) ORDER BY created_at DESC",
+        TOKEN_COLUMNS
+    ))
+    .bind(users.iter().map(|u| u.id).collect::<Vec<_>>())
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut tokens: Vec<ServiceTokenInfo> = rows.into_iter().map(ServiceTokenInfo::from).collect();
+    let accounts = users
+        .into_iter()
+        .map(|user| {
+            let (mine, rest) = tokens.drain(..).partition(|t| t.user_id == user.id);
+            tokens = rest;
+            ServiceAccount { user, tokens: mine }
+        })
+        .collect();
+
+    Ok(Json(accounts))
+}
+
+/// POST /api/admin/service-accounts
+/// Create a service account (it has no password and cannot sign in)
+pub async fn create_service_account(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<CreateServiceAccountRequest>,
+) -> ApiResult<Json<ServiceAccount>> {
+    let user_id = acting_user(&auth)?;
+
+    validate_account_name(&req.name).map_err(|e| ApiError::validation(vec![("name".to_string(), e)]))?;
+    let email = format!("{}@{}", req.name, SERVICE_EMAIL_DOMAIN);
+    ensure_email_available(&state, &email, None).await?;
+
+    // "!" is not a valid Argon2 hash, so password checks always fail
+    let user = sqlx::query_as::<_, UserSummary>(&format!(
+        "INSERT INTO users (email, display_name, password_hash, role, is_active, is_service)
+         VALUES ($1, $2, '!', $3, true, true)
+         RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(&email)
+    .bind(&req.name)
+    .bind(UserRole::Viewer)
+    .fetch_one(&state.db)
+    .await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::ServiceAccountCreated,
+        "user",
+        Some(&user.id.to_string()),
+        Some(json!({ "email": &user.email })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("🤖 Service account created: {}", user.email);
+
+    Ok(Json(ServiceAccount { user, tokens: Vec::new() }))
+}
+
+/// POST /api/admin/service-accounts/:id/tokens
+/// Issue a non-expiring token with the given scopes
+pub async fn create_service_token(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateServiceTokenRequest>,
+) -> ApiResult<Json<IssuedServiceToken>> {
+    let user_id = acting_user(&auth)?;
+    let account = fetch_service_account(&state, id).await?;
+
+    let name = req.name.trim();
+    let mut errors = Vec::new();
+    if name.is_empty() || name.chars().count() > NAME_MAX_LENGTH {
+        errors.push(("name".to_string(), format!("Must be 1-{} characters", NAME_MAX_LENGTH)));
+    }
+    if let Err(e) = validate_scopes(&req.scopes) {
+        errors.push(("scopes".to_string(), e));
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+    if !account.is_active {
+        return Err(ApiError::bad_request("Service account is deactivated"));
+    }
+
+    let token = service_tokens::generate();
+    let mut scopes: Vec<&str> = req.scopes.iter().map(|p| p.as_str()).collect();
+    scopes.sort_unstable();
+    scopes.dedup();
+
+    let row = sqlx::query_as::<_, ServiceTokenRow>(&format!(
+        "INSERT INTO service_tokens (user_id, name, token_hash, token_prefix, scopes, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING {}",
+        TOKEN_COLUMNS
+    ))
+    .bind(account.id)
+    .bind(name)
+    .bind(crate::utils::password::hash_opaque_token(&token))
+    .bind(&token[..service_tokens::DISPLAY_PREFIX_LENGTH])
+    .bind(&scopes)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    service_tokens::reload(&state.db).await?;
+
+    let info = ServiceTokenInfo::from(row);
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::ServiceTokenIssued,
+        "service_token",
+        Some(&info.id.to_string()),
+        Some(json!({
+            "service_account": &account.email,
+            "name": &info.name,
+            "token_prefix": &info.token_prefix,
+            "scopes": &info.scopes,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(IssuedServiceToken { token, info }))
+}
+
+/// DELETE /api/admin/service-tokens/:id
+/// Revoke a token; it stops working immediately on this instance and within a minute elsewhere
+pub async fn revoke_service_token(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ServiceTokenInfo>> {
+    let user_id = acting_user(&auth)?;
+
+    let row = sqlx::query_as::<_, ServiceTokenRow>(&format!(
+        "UPDATE service_tokens
+         SET revoked_at = COALESCE(revoked_at, NOW()),
+             revoked_by = COALESCE(revoked_by, $2)
+         WHERE id = $1
+         RETURNING {}",
+        TOKEN_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Service token not found"))?;
+
+    service_tokens::reload(&state.db).await?;
+
+    let info = ServiceTokenInfo::from(row);
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::ServiceTokenRevoked,
+        "service_token",
+        Some(&info.id.to_string()),
+        Some(json!({
+            "user_id": info.user_id,
+            "name": &info.name,
+            "token_prefix": &info.token_prefix,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(info))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_scopes() {
+        assert!(validate_scopes(&[Permission::IssuesRead, Permission::IssuesWrite]).is_ok());
+        assert!(validate_scopes(&[]).is_err());
+        assert!(validate_scopes(&[Permission::IssuesRead, Permission::UsersManage]).is_err());
+        assert!(validate_scopes(&[Permission::SystemManage]).is_err());
+    }
+
+    #[test]
+    fn test_validate_account_name() {
+        assert!(validate_account_name("ci-import").is_ok());
+        assert!(validate_account_name("CI").is_err());
+        assert!(validate_account_name("ci@import").is_err());
+    }
+}
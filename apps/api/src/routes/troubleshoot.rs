@@ -1,67 +1,89 @@
+/// HTTP routes for troubleshooting sessions
+///
+/// Starting, answering, skipping, going back and reading a session's state
+/// are done by the [`engine`]; the handlers here turn requests into engine
+/// calls. Feedback, notes, photos, history and live updates live here.
+use crate::engine::{
+    self, SessionOrigin, StartSessionRequest, StartSessionResponse, SubmitAnswerRequest, SubmitAnswerResponse,
+};
 use crate::error::{ApiError, ApiResult};
-use crate::models::{Node, Connection, NodeType};
+use crate::models::{Connection, Node};
+use crate::routes::uploads::{upload_info, UploadInfo};
+use crate::uploads::{self, sanitize, NewUpload, UploadOwnerType};
+use crate::utils::devices;
+use crate::utils::session_events::{self, SessionEventType};
+use crate::utils::session_watch::{self, SessionChangeKind};
 use crate::AppState;
 use axum::{
-    extract::{Path, State},
-    http::HeaderMap,
+    extract::{Multipart, Path, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
 use serde::{Deserialize, Serialize};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::{Stream, StreamExt};
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Request to start a new troubleshooting session
+/// Whether the conclusion fixed the problem
 #[derive(Debug, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
-pub struct StartSessionRequest {
-    pub tech_identifier: Option<String>,
-    pub client_site: Option<String>,
-    pub category: Option<String>, // Optional: for direct category access
+pub struct ConclusionFeedbackRequest {
+    pub resolved: bool,
+    /// What happened, e.g. what actually fixed it
+    #[serde(default)]
+    #[ts(optional)]
+    pub comment: Option<String>,
 }
 
-/// Response when starting a session (NODE-GRAPH VERSION)
+/// Feedback as stored on the session
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
-pub struct StartSessionResponse {
+pub struct ConclusionFeedbackResponse {
     pub session_id: String,
-    pub node: Node,
-    pub options: Vec<NavigationOption>,
-}
-
-/// Navigation option (connection to next node)
-#[derive(Debug, Serialize, TS)]
-#[ts(export, export_to = "../../web/src/types/")]
-pub struct NavigationOption {
-    pub connection_id: Uuid,
-    pub label: String,
-    pub target_category: String,
-    pub display_category: Option<String>,
+    pub conclusion_node_id: Option<Uuid>,
+    pub resolved: bool,
+    pub comment: Option<String>,
+    pub submitted_at: String,
 }
 
-/// Request to submit an answer (NODE-GRAPH VERSION)
+/// Why the tech is giving up on a session
 #[derive(Debug, Deserialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
-pub struct SubmitAnswerRequest {
-    pub connection_id: Uuid,
+pub struct AbandonSessionRequest {
+    /// e.g. "equipment replaced", "escalated to vendor"
+    #[serde(default)]
+    #[ts(optional)]
+    pub reason: Option<String>,
 }
 
-/// Response after submitting an answer (NODE-GRAPH VERSION)
+/// An abandoned session
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
-pub struct SubmitAnswerResponse {
+pub struct AbandonSessionResponse {
     pub session_id: String,
-    pub node: Node,
-    pub options: Vec<NavigationOption>,
-    pub is_conclusion: bool,
-    pub conclusion_text: Option<String>,
+    pub reason: Option<String>,
+    pub abandoned_at: String,
 }
 
 /// A step in the troubleshooting session history
+///
+/// The question and answer as the tech saw them, plus the node and
+/// connection as they are now for linking back into the editor.
 #[derive(Debug, Serialize, TS)]
 #[ts(export, export_to = "../../web/src/types/")]
 pub struct HistoryStep {
-    pub node: Node,
-    pub connection: Connection,
+    #[serde(flatten)]
+    pub step: SessionStepSummary,
+    /// Reading entered on a measurement question
+    pub value: Option<f64>,
+    /// When the answer was given
+    pub answered_at: Option<String>,
+    /// The question as it is now; null once deleted
+    pub node: Option<Node>,
+    /// The answer followed as it is now; null for a skip or once deleted
+    pub connection: Option<Connection>,
 }
 
 /// Response containing session history
@@ -73,8 +95,114 @@ pub struct SessionHistoryResponse {
     pub completed: bool,
     pub steps: Vec<HistoryStep>,
     pub final_conclusion: Option<String>,
+    /// Photos taken along the way, oldest first
+    pub attachments: Vec<StepAttachment>,
+}
+
+/// A photo attached to a session step
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StepAttachment {
+    /// Index of the step's answer in the session history; a photo of the
+    /// question on screen gets the index its answer will have
+    pub step_index: i32,
+    /// Question the photo was taken at
+    pub node_id: Uuid,
+    pub upload: UploadInfo,
+}
+
+/// An answered step, as shown in breadcrumbs
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SessionStepSummary {
+    pub node_id: Uuid,
+    pub node_text: String,
+    /// Answer followed; absent when the tech skipped to a conclusion
+    #[serde(default)]
+    pub connection_id: Option<Uuid>,
+    #[serde(default)]
+    pub connection_label: Option<String>,
+    /// Conclusion the tech jumped to from this question
+    #[serde(default)]
+    pub skipped_to: Option<Uuid>,
+    /// Where "I'm not sure" led from this question
+    #[serde(default)]
+    pub not_sure_to: Option<Uuid>,
+    /// The tech's note on this step
+    #[serde(default)]
+    pub note: Option<String>,
+    /// The tech marked this answer as unsure
+    #[serde(default)]
+    pub unsure: bool,
+}
+
+/// Request to jump straight to a conclusion
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SkipToConclusionRequest {
+    /// Conclusion in the current question's category
+    pub node_id: Uuid,
+}
+
+/// A note on an answered step; blank removes it
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StepNoteRequest {
+    /// e.g. "intermittent, only when cold"
+    pub note: String,
 }
 
+/// A step's note as stored
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct StepNoteResponse {
+    pub session_id: String,
+    #[ts(type = "number")]
+    pub step_index: usize,
+    pub note: Option<String>,
+}
+
+/// A session picked up by resume code: its current state plus the answers so far
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ResumeSessionResponse {
+    #[serde(flatten)]
+    pub state: SubmitAnswerResponse,
+    pub resume_code: String,
+    pub steps: Vec<SessionStepSummary>,
+}
+
+/// Request to return to an earlier step
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct GotoStepRequest {
+    /// Zero-based index into the session history; that answer and all later ones are discarded
+    #[ts(type = "number")]
+    pub step_index: usize,
+}
+
+/// Response to a client heartbeat
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct HeartbeatResponse {
+    pub session_id: String,
+    pub last_activity_at: String,
+    /// Completed sessions ignore heartbeats; the client can stop pinging
+    pub completed: bool,
+}
+
+/// Maximum length of a feedback comment
+const FEEDBACK_COMMENT_MAX_LENGTH: usize = 2000;
+
+/// Maximum length of a note on a step
+const STEP_NOTE_MAX_LENGTH: usize = 1000;
+
+/// Maximum length of the reason given for abandoning a session
+const ABANDON_REASON_MAX_LENGTH: usize = 500;
+
+/// Most photos one session can have
+const MAX_SESSION_ATTACHMENTS: i64 = 20;
+
 /// POST /api/troubleshoot/start
 /// Start a new troubleshooting session (public) - NODE-GRAPH VERSION
 pub async fn start_session(
@@ -82,61 +210,11 @@ pub async fn start_session(
     headers: HeaderMap,
     Json(req): Json<StartSessionRequest>,
 ) -> ApiResult<Json<StartSessionResponse>> {
-    // Get the starting node based on category or default to global start
-    let root_node = if let Some(category) = &req.category {
-        // Direct category access: find the category's start node
-        let semantic_id = format!("{}_start", category);
-        sqlx::query_as::<_, Node>(
-            "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
-             FROM nodes
-             WHERE semantic_id = $1 AND is_active = true"
-        )
-        .bind(&semantic_id)
-        .fetch_optional(&state.db)
-        .await?
-        .ok_or_else(|| ApiError::not_found(format!("Issue category '{}' not found", category)))?
-    } else {
-        // No category specified: use global start node
-        sqlx::query_as::<_, Node>(
-            "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
-             FROM nodes
-             WHERE semantic_id = 'start' AND is_active = true"
-        )
-        .fetch_optional(&state.db)
-        .await?
-        .ok_or_else(|| ApiError::internal("Global start node not found. Please run ensure_global_start.sql"))?
-    };
-
-    // PERFORMANCE: Get connections with their target nodes in a single JOIN query (avoids N+1)
-    let options = sqlx::query!(
-        r#"
-        SELECT
-            c.id as connection_id,
-            c.label,
-            n.category as target_category,
-            n.display_category
-        FROM connections c
-        INNER JOIN nodes n ON c.to_node_id = n.id
-        WHERE c.from_node_id = $1
-          AND c.is_active = true
-          AND n.is_active = true
-        ORDER BY c.order_index ASC
-        "#,
-        root_node.id
-    )
-    .fetch_all(&state.db)
-    .await?
-    .into_iter()
-    .map(|row| NavigationOption {
-        connection_id: row.connection_id,
-        label: row.label,
-        target_category: row.target_category,
-        display_category: row.display_category,
-    })
-    .collect::<Vec<_>>();
-
-    // Generate session ID
-    let session_id = Uuid::new_v4().to_string();
+    // Kiosks identify themselves; a token that doesn't check out is refused outright
+    let device_id = devices::from_headers(&headers).map_err(ApiError::unauthorized)?;
+    if device_id.is_none() && state.settings.require_device_token() {
+        return Err(ApiError::unauthorized("A registered device token is required to start a session"));
+    }
 
     // Get user agent and IP for tracking
     let user_agent = headers
@@ -150,30 +228,8 @@ pub async fn start_session(
         .and_then(|v| v.to_str().ok())
         .map(|s| s.split(',').next().unwrap_or(s).trim().to_string());
 
-    // Hash IP address for privacy (simple MD5 for now)
-    let ip_hash = ip_address.map(|ip| format!("{:x}", md5::compute(ip.as_bytes())));
-
-    // Create session in database
-    let initial_steps = serde_json::json!([]);
-
-    sqlx::query(
-        "INSERT INTO sessions (session_id, started_at, steps, tech_identifier, client_site, user_agent, ip_hash, abandoned)
-         VALUES ($1, NOW(), $2, $3, $4, $5, $6, false)",
-    )
-    .bind(&session_id)
-    .bind(&initial_steps)
-    .bind(&req.tech_identifier)
-    .bind(&req.client_site)
-    .bind(&user_agent)
-    .bind(&ip_hash)
-    .execute(&state.db)
-    .await?;
-
-    Ok(Json(StartSessionResponse {
-        session_id,
-        node: root_node,
-        options,
-    }))
+    let origin = SessionOrigin { device_id, user_agent, ip_address };
+    engine::start(&state, req, origin).await.map(Json)
 }
 
 /// POST /api/troubleshoot/:session_id/answer
@@ -183,323 +239,508 @@ pub async fn submit_answer(
     Path(session_id): Path<String>,
     Json(req): Json<SubmitAnswerRequest>,
 ) -> ApiResult<Json<SubmitAnswerResponse>> {
-    // Verify session exists and get current state
-    let session = sqlx::query!(
-        "SELECT id, steps, completed_at FROM sessions WHERE session_id = $1",
-        session_id
+    engine::answer(&state, session_id, req).await.map(Json)
+}
+
+/// POST /api/troubleshoot/:session_id/follow-up
+/// Continue with the next queued answer of a multi-select question (public)
+///
+/// Only allowed once the current branch has reached a conclusion.
+pub async fn next_follow_up(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<SubmitAnswerResponse>> {
+    engine::follow_up(&state, session_id).await.map(Json)
+}
+
+/// POST /api/troubleshoot/:session_id/skip
+/// Jump from the current question straight to a conclusion in its category (public)
+///
+/// For techs who already know the fix. The skip is recorded as a step, so
+/// going back returns to the question it was made from.
+pub async fn skip_to_conclusion(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    Json(req): Json<SkipToConclusionRequest>,
+) -> ApiResult<Json<SubmitAnswerResponse>> {
+    engine::skip_to_conclusion(&state, session_id, req.node_id).await.map(Json)
+}
+
+/// GET /api/troubleshoot/:session_id
+/// Get current state of a session (public) - NODE-GRAPH VERSION
+pub async fn get_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<SubmitAnswerResponse>> {
+    engine::current(&state, session_id).await.map(Json)
+}
+
+/// GET /api/troubleshoot/resume/:code
+/// Continue an open session on another device (public)
+///
+/// Codes only resolve while the session is unfinished and was active recently
+/// (`resume_code_ttl_hours`, a day by default), which keeps the small code
+/// space hard to scan.
+pub async fn resume_session(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> ApiResult<Json<ResumeSessionResponse>> {
+    let code = engine::normalize_resume_code(&code);
+    if code.len() != engine::RESUME_CODE_LENGTH {
+        return Err(ApiError::validation(vec![(
+            "code".to_string(),
+            format!("Resume codes are {} characters", engine::RESUME_CODE_LENGTH),
+        )]));
+    }
+
+    let (session_id, steps) = sqlx::query_as::<_, (String, serde_json::Value)>(
+        "SELECT session_id, steps
+         FROM sessions
+         WHERE resume_code = $1
+           AND completed_at IS NULL
+           AND last_activity_at > NOW() - make_interval(hours => $2)"
     )
+    .bind(&code)
+    .bind(state.settings.resume_code_ttl_hours())
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Resume code not found or expired"))?;
+
+    let steps: Vec<SessionStepSummary> = serde_json::from_value(steps)
+        .map_err(|_| ApiError::internal("Invalid session data"))?;
+    let current = engine::current(&state, session_id).await?;
+
+    sqlx::query("UPDATE sessions SET last_activity_at = NOW() WHERE resume_code = $1")
+        .bind(&code)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Json(ResumeSessionResponse {
+        state: current,
+        resume_code: code,
+        steps,
+    }))
+}
+
+/// POST /api/troubleshoot/:session_id/heartbeat
+/// Mark an open session as still in use (public)
+///
+/// The client pings while the tech reads a step, so a slow session is not
+/// counted as abandoned. Abandonment is based on the last answer or heartbeat.
+pub async fn heartbeat(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<HeartbeatResponse>> {
+    let (last_activity_at, completed) = sqlx::query_as::<_, (chrono::DateTime<chrono::Utc>, bool)>(
+        "UPDATE sessions
+         SET last_activity_at = CASE WHEN completed_at IS NULL THEN NOW() ELSE last_activity_at END
+         WHERE session_id = $1
+         RETURNING last_activity_at, completed_at IS NOT NULL"
+    )
+    .bind(&session_id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| ApiError::not_found("Session not found"))?;
 
-    // Check if session is already completed
-    if session.completed_at.is_some() {
-        return Err(ApiError::bad_request("Session is already completed"));
+    Ok(Json(HeartbeatResponse {
+        session_id,
+        last_activity_at: last_activity_at.to_rfc3339(),
+        completed,
+    }))
+}
+
+/// POST /api/troubleshoot/:session_id/back
+/// Undo the last answer and return to the question it answered (public)
+///
+/// Going back from a conclusion reopens the session.
+pub async fn go_back(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<SubmitAnswerResponse>> {
+    engine::rewind(&state, session_id, None).await.map(Json)
+}
+
+/// POST /api/troubleshoot/:session_id/goto
+/// Return to the question answered at `step_index`, discarding it and every later answer (public)
+///
+/// Like going back, this reopens a concluded session.
+pub async fn goto_step(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    Json(req): Json<GotoStepRequest>,
+) -> ApiResult<Json<SubmitAnswerResponse>> {
+    engine::rewind(&state, session_id, Some(req.step_index)).await.map(Json)
+}
+
+/// POST /api/troubleshoot/:session_id/feedback
+/// Report whether the conclusion fixed the problem (public)
+///
+/// Only finished sessions take feedback; sending it again replaces the
+/// earlier answer, and reopening the session clears it.
+pub async fn submit_feedback(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    Json(req): Json<ConclusionFeedbackRequest>,
+) -> ApiResult<Json<ConclusionFeedbackResponse>> {
+    let comment = req.comment.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if comment.is_some_and(|c| c.chars().count() > FEEDBACK_COMMENT_MAX_LENGTH) {
+        return Err(ApiError::validation(vec![(
+            "comment".to_string(),
+            format!("Must be at most {} characters", FEEDBACK_COMMENT_MAX_LENGTH),
+        )]));
     }
 
-    // PERFORMANCE OPTIMIZATION: Get connection and both nodes in a single JOIN query
-    let result = sqlx::query!(
-        r#"
-        SELECT
-            c.id as connection_id,
-            c.from_node_id,
-            c.to_node_id,
-            c.label as connection_label,
-            c.order_index,
-            c.created_at as connection_created_at,
-            c.updated_at as connection_updated_at,
-            fn.id as from_id,
-            fn.category as from_category,
-            fn.node_type as "from_node_type: NodeType",
-            fn.text as from_text,
-            fn.semantic_id as from_semantic_id,
-            fn.display_category as from_display_category,
-            fn.position_x as from_position_x,
-            fn.position_y as from_position_y,
-            fn.is_active as from_is_active,
-            fn.created_at as from_created_at,
-            fn.updated_at as from_updated_at,
-            tn.id as to_id,
-            tn.category as to_category,
-            tn.node_type as "to_node_type: NodeType",
-            tn.text as to_text,
-            tn.semantic_id as to_semantic_id,
-            tn.display_category as to_display_category,
-            tn.position_x as to_position_x,
-            tn.position_y as to_position_y,
-            tn.is_active as to_is_active,
-            tn.created_at as to_created_at,
-            tn.updated_at as to_updated_at
-        FROM connections c
-        INNER JOIN nodes fn ON c.from_node_id = fn.id
-        INNER JOIN nodes tn ON c.to_node_id = tn.id
-        WHERE c.id = $1 AND c.is_active = true
-        "#,
-        req.connection_id
+    let (completed, conclusion_node_id, category) = sqlx::query_as::<_, (bool, Option<Uuid>, Option<String>)>(
+        "SELECT s.completed_at IS NOT NULL, s.conclusion_node_id, n.category
+         FROM sessions s
+         LEFT JOIN nodes n ON n.id = s.conclusion_node_id
+         WHERE s.session_id = $1"
     )
+    .bind(&session_id)
     .fetch_optional(&state.db)
     .await?
-    .ok_or_else(|| ApiError::not_found("Connection not found"))?;
-
-    // Reconstruct the connection and nodes from the joined result
-    let connection = Connection {
-        id: result.connection_id,
-        from_node_id: result.from_node_id,
-        to_node_id: result.to_node_id,
-        label: result.connection_label,
-        order_index: result.order_index.unwrap_or(0),
-        is_active: true,
-        created_at: result.connection_created_at.unwrap_or_default(),
-        updated_at: result.connection_updated_at.unwrap_or_default(),
-    };
+    .ok_or_else(|| ApiError::not_found("Session not found"))?;
 
-    let from_node = Node {
-        id: result.from_id,
-        category: result.from_category,
-        node_type: result.from_node_type,
-        text: result.from_text,
-        semantic_id: result.from_semantic_id,
-        display_category: result.from_display_category,
-        position_x: result.from_position_x,
-        position_y: result.from_position_y,
-        is_active: result.from_is_active.unwrap_or(true),
-        created_at: result.from_created_at.unwrap_or_default(),
-        updated_at: result.from_updated_at.unwrap_or_default(),
-    };
+    if !completed {
+        return Err(ApiError::bad_request("Feedback can only be given once the session reaches a conclusion"));
+    }
 
-    let next_node = Node {
-        id: result.to_id,
-        category: result.to_category,
-        node_type: result.to_node_type,
-        text: result.to_text,
-        semantic_id: result.to_semantic_id,
-        display_category: result.to_display_category,
-        position_x: result.to_position_x,
-        position_y: result.to_position_y,
-        is_active: result.to_is_active.unwrap_or(true),
-        created_at: result.to_created_at.unwrap_or_default(),
-        updated_at: result.to_updated_at.unwrap_or_default(),
-    };
+    let submitted_at = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>(
+        "UPDATE sessions
+         SET feedback_resolved = $1, feedback_comment = $2, feedback_at = NOW()
+         WHERE session_id = $3
+         RETURNING feedback_at"
+    )
+    .bind(req.resolved)
+    .bind(comment)
+    .bind(&session_id)
+    .fetch_one(&state.db)
+    .await?;
 
-    // Update session steps
-    let mut steps: Vec<serde_json::Value> = serde_json::from_value(session.steps.clone())
-        .unwrap_or_default();
+    session_events::record(
+        &session_id,
+        SessionEventType::Feedback,
+        category.as_deref(),
+        Some(serde_json::json!({
+            "conclusion_node_id": conclusion_node_id,
+            "resolved": req.resolved,
+        })),
+    );
+
+    Ok(Json(ConclusionFeedbackResponse {
+        session_id,
+        conclusion_node_id,
+        resolved: req.resolved,
+        comment: comment.map(str::to_string),
+        submitted_at: submitted_at.to_rfc3339(),
+    }))
+}
 
-    steps.push(serde_json::json!({
-        "node_id": from_node.id,
-        "node_text": from_node.text,
-        "connection_id": connection.id,
-        "connection_label": connection.label,
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-    }));
+/// POST /api/troubleshoot/:session_id/steps/:index/note
+/// Attach a note to an answered step, replacing any earlier one (public)
+///
+/// The note is kept in the step itself, so it goes away with the answer if
+/// the tech goes back past it.
+pub async fn set_step_note(
+    State(state): State<AppState>,
+    Path((session_id, step_index)): Path<(String, usize)>,
+    Json(req): Json<StepNoteRequest>,
+) -> ApiResult<Json<StepNoteResponse>> {
+    let note = Some(req.note.trim()).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > STEP_NOTE_MAX_LENGTH) {
+        return Err(ApiError::validation(vec![(
+            "note".to_string(),
+            format!("Must be at most {} characters", STEP_NOTE_MAX_LENGTH),
+        )]));
+    }
 
-    let steps_json = serde_json::to_value(&steps)?;
+    // Edit the one step in place so a concurrent answer isn't overwritten
+    let index = i32::try_from(step_index).unwrap_or(i32::MAX);
+    let updated = sqlx::query_scalar::<_, i32>(
+        "UPDATE sessions
+         SET steps = CASE WHEN $3::text IS NULL THEN steps #- ARRAY[$2::text, 'note']
+                          ELSE jsonb_set(steps, ARRAY[$2::text, 'note'], to_jsonb($3::text)) END
+         WHERE session_id = $1 AND jsonb_array_length(steps) > $2
+         RETURNING 1"
+    )
+    .bind(&session_id)
+    .bind(index)
+    .bind(note)
+    .fetch_optional(&state.db)
+    .await?;
 
-    // Check if this is a conclusion node
-    if matches!(next_node.node_type, NodeType::Conclusion) {
-        // Session is complete
-        sqlx::query(
-            "UPDATE sessions
-             SET steps = $1, final_conclusion = $2, completed_at = NOW(), abandoned = false
-             WHERE session_id = $3"
+    if updated.is_none() {
+        let step_count = sqlx::query_scalar::<_, i32>(
+            "SELECT COALESCE(jsonb_array_length(steps), 0)::int FROM sessions WHERE session_id = $1"
         )
-        .bind(&steps_json)
-        .bind(&next_node.text)
         .bind(&session_id)
-        .execute(&state.db)
-        .await?;
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Session not found"))?;
+        return Err(ApiError::not_found(format!(
+            "Step {} not found; the session has {} answered steps",
+            step_index, step_count
+        )));
+    }
 
-        return Ok(Json(SubmitAnswerResponse {
-            session_id,
-            node: next_node.clone(),
-            options: vec![],
-            is_conclusion: true,
-            conclusion_text: Some(next_node.text),
-        }));
+    Ok(Json(StepNoteResponse {
+        session_id,
+        step_index,
+        note: note.map(str::to_string),
+    }))
+}
+
+/// POST /api/troubleshoot/:session_id/abandon
+/// Give up on an open session, optionally saying why (public)
+///
+/// The session counts as abandoned straight away rather than after the idle
+/// timeout. Sending it again replaces the reason; answering or going back
+/// reopens the session.
+pub async fn abandon_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    Json(req): Json<AbandonSessionRequest>,
+) -> ApiResult<Json<AbandonSessionResponse>> {
+    let reason = req.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if reason.is_some_and(|r| r.chars().count() > ABANDON_REASON_MAX_LENGTH) {
+        return Err(ApiError::validation(vec![(
+            "reason".to_string(),
+            format!("Must be at most {} characters", ABANDON_REASON_MAX_LENGTH),
+        )]));
     }
 
-    // PERFORMANCE: Get connections with their target nodes in a single JOIN query (avoids N+1)
-    let options = sqlx::query!(
-        r#"
-        SELECT
-            c.id as connection_id,
-            c.label,
-            n.category as target_category,
-            n.display_category
-        FROM connections c
-        INNER JOIN nodes n ON c.to_node_id = n.id
-        WHERE c.from_node_id = $1
-          AND c.is_active = true
-          AND n.is_active = true
-        ORDER BY c.order_index ASC
-        "#,
-        next_node.id
-    )
-    .fetch_all(&state.db)
-    .await?
-    .into_iter()
-    .map(|row| NavigationOption {
-        connection_id: row.connection_id,
-        label: row.label,
-        target_category: row.target_category,
-        display_category: row.display_category,
-    })
-    .collect::<Vec<_>>();
-
-    // Update session
-    sqlx::query(
-        "UPDATE sessions SET steps = $1 WHERE session_id = $2"
-    )
-    .bind(&steps_json)
-    .bind(&session_id)
-    .execute(&state.db)
-    .await?;
+    let completed = sqlx::query_scalar::<_, bool>("SELECT completed_at IS NOT NULL FROM sessions WHERE session_id = $1")
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Session not found"))?;
+    let finished = || ApiError::bad_request("A session that reached a conclusion can't be abandoned");
+    if completed {
+        return Err(finished());
+    }
 
-    Ok(Json(SubmitAnswerResponse {
+    let (abandoned_at, step_count, category) =
+        sqlx::query_as::<_, (chrono::DateTime<chrono::Utc>, i32, Option<String>)>(
+            "UPDATE sessions
+             SET abandoned = true, abandoned_at = COALESCE(abandoned_at, NOW()), abandon_reason = $1
+             WHERE session_id = $2 AND completed_at IS NULL
+             RETURNING abandoned_at, COALESCE(jsonb_array_length(steps), 0)::int, (steps->0->>'category')::text"
+        )
+        .bind(reason)
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        // Finished since the check
+        .ok_or_else(finished)?;
+
+    let current = engine::current(&state, session_id.clone()).await?;
+    session_watch::publish(&state.db, SessionChangeKind::Abandoned, &session_id, current.node.id, step_count as usize);
+    session_events::record(
+        &session_id,
+        SessionEventType::Abandoned,
+        category.as_deref(),
+        Some(serde_json::json!({
+            "reason": reason,
+            "step_count": step_count,
+        })),
+    );
+
+    tracing::info!("🚪 Session {} abandoned after {} steps", session_id, step_count);
+
+    Ok(Json(AbandonSessionResponse {
         session_id,
-        node: next_node,
-        options,
-        is_conclusion: false,
-        conclusion_text: None,
+        reason: reason.map(str::to_string),
+        abandoned_at: abandoned_at.to_rfc3339(),
     }))
 }
 
-/// GET /api/troubleshoot/:session_id
-/// Get current state of a session (public) - NODE-GRAPH VERSION
-pub async fn get_session(
+/// POST /api/troubleshoot/:session_id/attachments
+/// Attach a photo to a step (public; multipart: `file`, optional `step_index`)
+///
+/// Without `step_index` the photo belongs to the question on screen. Photos
+/// go through the upload service like any other file, so they are stored on
+/// the configured backend with their metadata stripped.
+pub async fn upload_attachment(
     State(state): State<AppState>,
     Path(session_id): Path<String>,
-) -> ApiResult<Json<SubmitAnswerResponse>> {
-    // Get session
-    let session = sqlx::query!(
-        "SELECT steps, final_conclusion, completed_at FROM sessions WHERE session_id = $1",
-        session_id
+    mut multipart: Multipart,
+) -> ApiResult<(StatusCode, Json<StepAttachment>)> {
+    let service = uploads::service()?;
+
+    let mut file: Option<(String, Vec<u8>)> = None;
+    let mut step_index: Option<usize> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        match field.name().unwrap_or_default() {
+            "file" => {
+                let filename = field.file_name().unwrap_or("photo").to_string();
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
+                file = Some((filename, bytes.to_vec()));
+            }
+            "step_index" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::bad_request(format!("Invalid step_index: {}", e)))?;
+                step_index = Some(value.trim().parse().map_err(|_| {
+                    ApiError::validation(vec![("step_index".to_string(), "Must be a step number".to_string())])
+                })?);
+            }
+            _ => {}
+        }
+    }
+
+    let (filename, bytes) =
+        file.ok_or_else(|| ApiError::validation(vec![("file".to_string(), "A photo is required".to_string())]))?;
+    if !sanitize::detect_content_type(&bytes, &filename).is_some_and(sanitize::is_image) {
+        return Err(ApiError::validation(vec![(
+            "file".to_string(),
+            "Must be a photo (JPEG, PNG, GIF or WebP)".to_string(),
+        )]));
+    }
+
+    let (steps, attached) = sqlx::query_as::<_, (serde_json::Value, i64)>(
+        "SELECT s.steps, (SELECT COUNT(*) FROM session_attachments a WHERE a.session_id = s.session_id)
+         FROM sessions s
+         WHERE s.session_id = $1"
     )
+    .bind(&session_id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| ApiError::not_found("Session not found"))?;
 
-    // Parse steps to find current position
-    let steps: Vec<serde_json::Value> = serde_json::from_value(session.steps)
-        .unwrap_or_default();
+    if attached >= MAX_SESSION_ATTACHMENTS {
+        return Err(ApiError::bad_request(format!(
+            "A session can have at most {} photos",
+            MAX_SESSION_ATTACHMENTS
+        )));
+    }
 
-    // If no steps, return starting node
-    if steps.is_empty() {
-        let root_node = sqlx::query_as::<_, Node>(
-            "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
-             FROM nodes
-             WHERE semantic_id = 'start' AND is_active = true"
-        )
-        .fetch_one(&state.db)
-        .await?;
+    let steps: Vec<serde_json::Value> = serde_json::from_value(steps).unwrap_or_default();
+    let step_index = step_index.unwrap_or(steps.len());
+    let node_id = match steps.get(step_index) {
+        Some(step) => serde_json::from_value(step["node_id"].clone())
+            .map_err(|_| ApiError::internal("Invalid session data"))?,
+        None if step_index == steps.len() => {
+            engine::current(&state, session_id.clone()).await?.node.id
+        }
+        None => {
+            return Err(ApiError::validation(vec![(
+                "step_index".to_string(),
+                format!("Must be at most {}", steps.len()),
+            )]));
+        }
+    };
 
-        // PERFORMANCE: Get connections with their target nodes in a single JOIN query (avoids N+1)
-        let options = sqlx::query!(
-            r#"
-            SELECT
-                c.id as connection_id,
-                c.label,
-                n.category as target_category,
-                n.display_category
-            FROM connections c
-            INNER JOIN nodes n ON c.to_node_id = n.id
-            WHERE c.from_node_id = $1
-              AND c.is_active = true
-              AND n.is_active = true
-            ORDER BY c.order_index ASC
-            "#,
-            root_node.id
+    let upload = service
+        .store(
+            &state.db,
+            NewUpload {
+                filename,
+                bytes,
+                owner: Some((UploadOwnerType::Session, session_id.clone())),
+                uploaded_by: None,
+            },
         )
-        .fetch_all(&state.db)
-        .await?
-        .into_iter()
-        .map(|row| NavigationOption {
-            connection_id: row.connection_id,
-            label: row.label,
-            target_category: row.target_category,
-            display_category: row.display_category,
-        })
-        .collect::<Vec<_>>();
+        .await?;
 
-        return Ok(Json(SubmitAnswerResponse {
-            session_id,
-            node: root_node,
-            options,
-            is_conclusion: false,
-            conclusion_text: None,
-        }));
+    let linked = sqlx::query(
+        "INSERT INTO session_attachments (upload_id, session_id, step_index, node_id) VALUES ($1, $2, $3, $4)"
+    )
+    .bind(upload.id)
+    .bind(&session_id)
+    .bind(step_index as i32)
+    .bind(node_id)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = linked {
+        // Don't leave a photo behind that no step refers to
+        let _ = service.delete(&state.db, upload.id).await;
+        return Err(e.into());
     }
 
-    // Get last connection to determine current node
-    let last_step = &steps[steps.len() - 1];
-    let last_connection_id: Uuid = serde_json::from_value(last_step["connection_id"].clone())
-        .map_err(|_| ApiError::internal("Invalid session data"))?;
+    tracing::info!("📷 Photo {} attached to step {} of session {}", upload.id, step_index, session_id);
 
-    let last_connection = sqlx::query_as::<_, Connection>(
-        "SELECT id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at
-         FROM connections
-         WHERE id = $1"
-    )
-    .bind(last_connection_id)
-    .fetch_one(&state.db)
-    .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(StepAttachment {
+            step_index: step_index as i32,
+            node_id,
+            upload: upload_info(service, upload),
+        }),
+    ))
+}
 
-    // Get current node (target of last connection)
-    let current_node = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
-         FROM nodes
-         WHERE id = $1"
+/// Photos attached to a session's steps, oldest first
+async fn session_attachments(state: &AppState, session_id: &str) -> ApiResult<Vec<StepAttachment>> {
+    let links = sqlx::query_as::<_, (Uuid, i32, Uuid)>(
+        "SELECT upload_id, step_index, node_id FROM session_attachments WHERE session_id = $1"
     )
-    .bind(last_connection.to_node_id)
-    .fetch_one(&state.db)
+    .bind(session_id)
+    .fetch_all(&state.db)
     .await?;
-
-    // If current node is a conclusion, session should be marked complete
-    if matches!(current_node.node_type, NodeType::Conclusion) {
-        return Ok(Json(SubmitAnswerResponse {
-            session_id,
-            node: current_node.clone(),
-            options: vec![],
-            is_conclusion: true,
-            conclusion_text: Some(current_node.text),
-        }));
+    if links.is_empty() {
+        return Ok(Vec::new());
     }
 
-    // PERFORMANCE: Get connections with their target nodes in a single JOIN query (avoids N+1)
-    let options = sqlx::query!(
-        r#"
-        SELECT
-            c.id as connection_id,
-            c.label,
-            n.category as target_category,
-            n.display_category
-        FROM connections c
-        INNER JOIN nodes n ON c.to_node_id = n.id
-        WHERE c.from_node_id = $1
-          AND c.is_active = true
-          AND n.is_active = true
-        ORDER BY c.order_index ASC
-        "#,
-        current_node.id
-    )
-    .fetch_all(&state.db)
-    .await?
-    .into_iter()
-    .map(|row| NavigationOption {
-        connection_id: row.connection_id,
-        label: row.label,
-        target_category: row.target_category,
-        display_category: row.display_category,
-    })
-    .collect::<Vec<_>>();
-
-    Ok(Json(SubmitAnswerResponse {
-        session_id,
-        node: current_node,
-        options,
-        is_conclusion: false,
-        conclusion_text: None,
-    }))
+    let service = uploads::service()?;
+    let mut links: std::collections::HashMap<Uuid, (i32, Uuid)> = links
+        .into_iter()
+        .map(|(upload_id, step_index, node_id)| (upload_id, (step_index, node_id)))
+        .collect();
+
+    Ok(service
+        .list_for_owner(&state.db, UploadOwnerType::Session, session_id)
+        .await?
+        .into_iter()
+        .filter_map(|upload| {
+            let (step_index, node_id) = links.remove(&upload.id)?;
+            Some(StepAttachment {
+                step_index,
+                node_id,
+                upload: upload_info(service, upload),
+            })
+        })
+        .collect())
+}
+
+/// GET /api/troubleshoot/:session_id/events
+/// Stream changes to a session as server-sent events (public)
+///
+/// Sends an `answered`, `completed`, `rewound` or `abandoned` event (a `SessionChange`)
+/// whenever the session moves, so supervisors don't have to poll. A `lagged`
+/// event means changes were missed and the session should be fetched again.
+pub async fn watch_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, axum::Error>>>> {
+    // Subscribe first so nothing is missed between the check and the stream
+    let changes = session_watch::subscribe();
+
+    sqlx::query_scalar::<_, i32>("SELECT 1 FROM sessions WHERE session_id = $1")
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Session not found"))?;
+
+    let events = BroadcastStream::new(changes).filter_map(move |change| match change {
+        Ok(change) if change.session_id == session_id => {
+            Some(Event::default().event(change.kind.as_str()).json_data(&change))
+        }
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(missed)) => {
+            Some(Ok(Event::default().event("lagged").data(missed.to_string())))
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
 }
 
 /// GET /api/troubleshoot/:session_id/history
@@ -520,35 +761,38 @@ pub async fn get_session_history(
     // Parse steps
     let steps: Vec<serde_json::Value> = serde_json::from_value(session.steps)
         .unwrap_or_default();
-
-    // PERFORMANCE: Batch fetch all questions and answers to avoid N+1 queries (2 queries per step)
-    // Extract all unique IDs from steps
-    let question_ids: Vec<Uuid> = steps
-        .iter()
-        .filter_map(|step| serde_json::from_value(step["question_id"].clone()).ok())
-        .collect();
-    let answer_ids: Vec<Uuid> = steps
+    let recorded: Vec<(SessionStepSummary, &serde_json::Value)> = steps
         .iter()
-        .filter_map(|step| serde_json::from_value(step["answer_id"].clone()).ok())
+        .filter_map(|step| match serde_json::from_value::<SessionStepSummary>(step.clone()) {
+            Ok(summary) => Some((summary, step)),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable step in session {}: {}", session_id, e);
+                None
+            }
+        })
         .collect();
 
+    // PERFORMANCE: Batch fetch all nodes and connections to avoid N+1 queries (2 queries per step)
+    let node_ids: Vec<Uuid> = recorded.iter().map(|(summary, _)| summary.node_id).collect();
+    let connection_ids: Vec<Uuid> = recorded.iter().filter_map(|(summary, _)| summary.connection_id).collect();
+
     // Batch fetch all nodes in a single query
     let nodes = sqlx::query_as::<_, Node>(
-        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, is_active, created_at, updated_at
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
          FROM nodes
          WHERE id = ANY($1)"
     )
-    .bind(&question_ids)
+    .bind(&node_ids)
     .fetch_all(&state.db)
     .await?;
 
     // Batch fetch all connections in a single query
     let connections = sqlx::query_as::<_, Connection>(
-        "SELECT id, from_node_id, to_node_id, label, order_index, is_active, created_at, updated_at
+        "SELECT id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at, updated_at
          FROM connections
          WHERE id = ANY($1)"
     )
-    .bind(&answer_ids)
+    .bind(&connection_ids)
     .fetch_all(&state.db)
     .await?;
 
@@ -563,25 +807,19 @@ pub async fn get_session_history(
         .map(|c| (c.id, c))
         .collect();
 
-    // Build history using the HashMaps
-    let mut history = Vec::new();
-    for step in steps {
-        let question_id: Uuid = serde_json::from_value(step["question_id"].clone())
-            .map_err(|_| ApiError::internal("Invalid session data"))?;
-        let answer_id: Uuid = serde_json::from_value(step["answer_id"].clone())
-            .map_err(|_| ApiError::internal("Invalid session data"))?;
-
-        let node = node_map
-            .get(&question_id)
-            .ok_or_else(|| ApiError::internal("Node not found in batch"))?
-            .clone();
-        let connection = connection_map
-            .get(&answer_id)
-            .ok_or_else(|| ApiError::internal("Connection not found in batch"))?
-            .clone();
-
-        history.push(HistoryStep { node, connection });
-    }
+    // Build history from the recorded steps; nodes and connections may have been deleted since
+    let history = recorded
+        .into_iter()
+        .map(|(summary, step)| HistoryStep {
+            value: step["value"].as_f64(),
+            answered_at: step["timestamp"].as_str().map(str::to_string),
+            node: node_map.get(&summary.node_id).cloned(),
+            connection: summary.connection_id.and_then(|id| connection_map.get(&id).cloned()),
+            step: summary,
+        })
+        .collect::<Vec<_>>();
+
+    let attachments = session_attachments(&state, &session_id).await?;
 
     Ok(Json(SessionHistoryResponse {
         session_id,
@@ -589,28 +827,65 @@ pub async fn get_session_history(
         completed: session.completed_at.is_some(),
         steps: history,
         final_conclusion: session.final_conclusion,
+        attachments,
     }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::NOT_SURE_LABEL;
 
     #[test]
-    fn test_start_session_request() {
-        let req = StartSessionRequest {
-            tech_identifier: Some("Tech123".to_string()),
-            client_site: Some("Site A".to_string()),
-            category: None,
-        };
-        assert!(req.tech_identifier.is_some());
-    }
-
-    #[test]
-    fn test_submit_answer_request() {
-        let req = SubmitAnswerRequest {
-            connection_id: Uuid::new_v4(),
-        };
-        assert!(!req.connection_id.to_string().is_empty());
+    fn test_step_summary_note_and_unsure() {
+        let step = serde_json::json!({
+            "node_id": Uuid::new_v4(),
+            "node_text": "Does the pump start?",
+            "connection_id": Uuid::new_v4(),
+            "connection_label": "No",
+            "timestamp": "2025-10-24T10:00:00+00:00",
+        });
+        // Steps recorded before notes and unsure answers existed have neither
+        let summary: SessionStepSummary = serde_json::from_value(step.clone()).unwrap();
+        assert_eq!(summary.note, None);
+        assert!(!summary.unsure);
+
+        let mut step = step;
+        step["note"] = serde_json::json!("intermittent, only when cold");
+        step["unsure"] = serde_json::json!(true);
+        let summary: SessionStepSummary = serde_json::from_value(step).unwrap();
+        assert_eq!(summary.note.as_deref(), Some("intermittent, only when cold"));
+        assert!(summary.unsure);
+
+        // A skip has no answer
+        let conclusion = Uuid::new_v4();
+        let skip = serde_json::json!({
+            "node_id": Uuid::new_v4(),
+            "node_text": "Does the pump start?",
+            "connection_id": null,
+            "connection_label": null,
+            "skipped_to": conclusion,
+            "timestamp": "2025-10-24T10:00:00+00:00",
+        });
+        let summary: SessionStepSummary = serde_json::from_value(skip).unwrap();
+        assert_eq!(summary.connection_id, None);
+        assert_eq!(summary.skipped_to, Some(conclusion));
+
+        // So has "I'm not sure", which is always unsure
+        let sub_flow = Uuid::new_v4();
+        let not_sure = serde_json::json!({
+            "node_id": Uuid::new_v4(),
+            "node_text": "Does the pump start?",
+            "connection_id": null,
+            "connection_label": NOT_SURE_LABEL,
+            "not_sure_to": sub_flow,
+            "unsure": true,
+            "timestamp": "2025-10-24T10:00:00+00:00",
+        });
+        let summary: SessionStepSummary = serde_json::from_value(not_sure).unwrap();
+        assert_eq!(summary.connection_id, None);
+        assert_eq!(summary.skipped_to, None);
+        assert_eq!(summary.not_sure_to, Some(sub_flow));
+        assert!(summary.unsure);
     }
 }
@@ -0,0 +1,446 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::uploads::{self, NewUpload, Upload, UploadOwnerType, UploadService};
+use crate::utils::{audit, category_access};
+use crate::AppState;
+use axum::{
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Upload metadata with a short-lived download URL
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UploadInfo {
+    pub id: Uuid,
+    pub filename: String,
+    /// Detected from the file contents
+    pub content_type: String,
+    #[ts(type = "number")]
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub owner_type: Option<UploadOwnerType>,
+    pub owner_id: Option<String>,
+    pub uploaded_by: Option<Uuid>,
+    /// 'clean' (passed ClamAV) or 'not_scanned'
+    pub scan_status: String,
+    /// Signed download URL; request a fresh one after `url_expires_at`
+    pub url: String,
+    pub url_expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Build the API view of an upload, signing a fresh download URL
+pub(crate) fn upload_info(service: &UploadService, upload: Upload) -> UploadInfo {
+    let (url, url_expires_at) = service.signed_url(upload.id);
+    UploadInfo {
+        id: upload.id,
+        filename: upload.original_filename,
+        content_type: upload.content_type,
+        size_bytes: upload.size_bytes,
+        sha256: upload.sha256,
+        owner_type: upload.owner_type,
+        owner_id: upload.owner_id,
+        uploaded_by: upload.uploaded_by,
+        scan_status: upload.scan_status,
+        url,
+        url_expires_at,
+        created_at: upload.created_at,
+    }
+}
+
+/// Check that the node or session an upload is attached to exists
+pub(crate) async fn ensure_owner_exists(db: &PgPool, owner_type: UploadOwnerType, owner_id: &str) -> ApiResult<()> {
+    let exists = match owner_type {
+        UploadOwnerType::Node => {
+            let id = Uuid::parse_str(owner_id)
+                .map_err(|_| ApiError::validation(vec![("owner_id".to_string(), "Must be a node UUID".to_string())]))?;
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = $1)")
+                .bind(id)
+                .fetch_one(db)
+                .await?
+        }
+        UploadOwnerType::Session => {
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM sessions WHERE session_id = $1)")
+                .bind(owner_id)
+                .fetch_one(db)
+                .await?
+        }
+    };
+
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::not_found(format!("{} {} not found", owner_label(owner_type), owner_id)))
+    }
+}
+
+/// Node attachments are shown to technicians, so changing them takes edit access to the node's category
+async fn ensure_can_edit_owner(db: &PgPool, auth: &AuthUser, owner_type: UploadOwnerType, owner_id: &str) -> ApiResult<()> {
+    match owner_type {
+        UploadOwnerType::Node => {
+            let id = Uuid::parse_str(owner_id)
+                .map_err(|_| ApiError::validation(vec![("owner_id".to_string(), "Must be a node UUID".to_string())]))?;
+            category_access::ensure_can_edit_node(db, auth, id).await
+        }
+        UploadOwnerType::Session => Ok(()),
+    }
+}
+
+fn acting_user(auth: &AuthUser) -> ApiResult<Uuid> {
+    Uuid::parse_str(&auth.0.sub).map_err(|_| ApiError::internal("Invalid user ID in token"))
+}
+
+fn owner_label(owner_type: UploadOwnerType) -> &'static str {
+    match owner_type {
+        UploadOwnerType::Node => "Node",
+        UploadOwnerType::Session => "Session",
+    }
+}
+
+/// POST /api/v1/admin/uploads
+/// Upload a file (multipart: `file`, optional `owner_type` + `owner_id`)
+pub async fn create_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> ApiResult<(StatusCode, Json<UploadInfo>)> {
+    let service = uploads::service()?;
+    let user_id = acting_user(&auth)?;
+
+    let mut file: Option<(String, Vec<u8>)> = None;
+    let mut owner_type: Option<UploadOwnerType> = None;
+    let mut owner_id: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let filename = field.file_name().unwrap_or("upload").to_string();
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
+                file = Some((filename, bytes.to_vec()));
+            }
+            "owner_type" | "owner_id" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::bad_request(format!("Invalid {}: {}", name, e)))?;
+                if name == "owner_type" {
+                    owner_type = Some(serde_json::from_value(json!(value.trim())).map_err(|_| {
+                        ApiError::validation(vec![("owner_type".to_string(), "Must be 'node' or 'session'".to_string())])
+                    })?);
+                } else {
+                    owner_id = Some(value.trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (filename, bytes) =
+        file.ok_or_else(|| ApiError::validation(vec![("file".to_string(), "A file is required".to_string())]))?;
+
+    let owner = match (owner_type, owner_id) {
+        (Some(owner_type), Some(owner_id)) if !owner_id.is_empty() => {
+            ensure_owner_exists(&state.db, owner_type, &owner_id).await?;
+            ensure_can_edit_owner(&state.db, &auth, owner_type, &owner_id).await?;
+            Some((owner_type, owner_id))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(ApiError::validation(vec![(
+                "owner_id".to_string(),
+                "owner_type and owner_id must be given together".to_string(),
+            )]));
+        }
+    };
+
+    let upload = service
+        .store(
+            &state.db,
+            NewUpload {
+                filename,
+                bytes,
+                owner,
+                uploaded_by: Some(user_id),
+            },
+        )
+        .await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UploadCreated,
+        "upload",
+        Some(&upload.id.to_string()),
+        Some(json!({
+            "filename": upload.original_filename,
+            "content_type": upload.content_type,
+            "size_bytes": upload.size_bytes,
+            "owner_type": upload.owner_type,
+            "owner_id": upload.owner_id,
+            "scan_status": upload.scan_status,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!(
+        "📎 Upload stored: {} ({}, {} bytes, {})",
+        upload.id,
+        upload.content_type,
+        upload.size_bytes,
+        upload.scan_status
+    );
+
+    Ok((StatusCode::CREATED, Json(upload_info(service, upload))))
+}
+
+/// Query parameters for list_uploads
+#[derive(Debug, Deserialize)]
+pub struct ListUploadsQuery {
+    pub owner_type: UploadOwnerType,
+    pub owner_id: String,
+}
+
+/// GET /api/v1/admin/uploads?owner_type=node&owner_id=...
+/// List the uploads attached to a node or session
+pub async fn list_uploads(
+    State(state): State<AppState>,
+    Query(query): Query<ListUploadsQuery>,
+) -> ApiResult<Json<Vec<UploadInfo>>> {
+    let service = uploads::service()?;
+    let uploads = service.list_for_owner(&state.db, query.owner_type, &query.owner_id).await?;
+    Ok(Json(uploads.into_iter().map(|u| upload_info(service, u)).collect()))
+}
+
+/// GET /api/v1/admin/uploads/:id
+/// Upload metadata with a fresh signed URL
+pub async fn get_upload(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<UploadInfo>> {
+    let service = uploads::service()?;
+    let upload = service.find(&state.db, id).await?;
+    Ok(Json(upload_info(service, upload)))
+}
+
+/// DELETE /api/v1/admin/uploads/:id
+pub async fn delete_upload(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let service = uploads::service()?;
+    let user_id = acting_user(&auth)?;
+    let upload = service.find(&state.db, id).await?;
+    if let (Some(owner_type), Some(owner_id)) = (upload.owner_type, upload.owner_id.as_deref()) {
+        ensure_can_edit_owner(&state.db, &auth, owner_type, owner_id).await?;
+    }
+    service.delete(&state.db, id).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UploadDeleted,
+        "upload",
+        Some(&id.to_string()),
+        Some(json!({
+            "filename": upload.original_filename,
+            "owner_type": upload.owner_type,
+            "owner_id": upload.owner_id,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/nodes/:id/attachments
+/// Files attached to a node, oldest first
+pub async fn list_node_attachments(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Vec<UploadInfo>>> {
+    let service = uploads::service()?;
+    ensure_owner_exists(&state.db, UploadOwnerType::Node, &id.to_string()).await?;
+    let uploads = service.list_for_owner(&state.db, UploadOwnerType::Node, &id.to_string()).await?;
+    Ok(Json(uploads.into_iter().map(|u| upload_info(service, u)).collect()))
+}
+
+/// POST /api/v1/nodes/:id/attachments
+/// Attach a file to a node (multipart: `file`); technicians get it with the node
+pub async fn create_node_attachment(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> ApiResult<(StatusCode, Json<UploadInfo>)> {
+    let service = uploads::service()?;
+    let user_id = acting_user(&auth)?;
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
+
+    let mut file: Option<(String, Vec<u8>)> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            let filename = field.file_name().unwrap_or("attachment").to_string();
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
+            file = Some((filename, bytes.to_vec()));
+        }
+    }
+    let (filename, bytes) =
+        file.ok_or_else(|| ApiError::validation(vec![("file".to_string(), "A file is required".to_string())]))?;
+
+    let upload = service
+        .store(
+            &state.db,
+            NewUpload {
+                filename,
+                bytes,
+                owner: Some((UploadOwnerType::Node, id.to_string())),
+                uploaded_by: Some(user_id),
+            },
+        )
+        .await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UploadCreated,
+        "upload",
+        Some(&upload.id.to_string()),
+        Some(json!({
+            "filename": upload.original_filename,
+            "content_type": upload.content_type,
+            "size_bytes": upload.size_bytes,
+            "owner_type": upload.owner_type,
+            "owner_id": upload.owner_id,
+            "scan_status": upload.scan_status,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(upload_info(service, upload))))
+}
+
+/// DELETE /api/v1/nodes/:id/attachments/:upload_id
+pub async fn delete_node_attachment(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path((id, upload_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    let service = uploads::service()?;
+    let user_id = acting_user(&auth)?;
+    category_access::ensure_can_edit_node(&state.db, &auth, id).await?;
+
+    let upload = service.find(&state.db, upload_id).await?;
+    if upload.owner_type != Some(UploadOwnerType::Node) || upload.owner_id != Some(id.to_string()) {
+        return Err(ApiError::not_found("Attachment not found on this node"));
+    }
+    service.delete(&state.db, upload_id).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UploadDeleted,
+        "upload",
+        Some(&upload_id.to_string()),
+        Some(json!({
+            "filename": upload.original_filename,
+            "owner_type": upload.owner_type,
+            "owner_id": upload.owner_id,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove the files attached to a deleted node
+pub(crate) async fn delete_node_attachments(db: &PgPool, node_id: Uuid) -> ApiResult<()> {
+    let Ok(service) = uploads::service() else {
+        return Ok(());
+    };
+    for upload in service.list_for_owner(db, UploadOwnerType::Node, &node_id.to_string()).await? {
+        service.delete(db, upload.id).await?;
+    }
+    Ok(())
+}
+
+/// Signature parameters on a download URL
+#[derive(Debug, Deserialize)]
+pub struct SignedUrlQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// GET /api/v1/uploads/:id/content?expires=...&signature=...
+/// Download an upload through a signed URL (no login needed)
+pub async fn download_upload(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<SignedUrlQuery>,
+) -> ApiResult<Response> {
+    let service = uploads::service()?;
+    if !service.verify_signature(id, query.expires, &query.signature) {
+        return Err(ApiError::forbidden("Download link is invalid or has expired"));
+    }
+
+    let upload = service.find(&state.db, id).await?;
+    let bytes = service.read(&upload).await?;
+
+    // Images display inline; anything else downloads
+    let disposition = if uploads::sanitize::is_image(&upload.content_type) { "inline" } else { "attachment" };
+    let content_disposition = HeaderValue::from_str(&format!("{}; filename=\"{}\"", disposition, upload.original_filename))
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+    let content_type =
+        HeaderValue::from_str(&upload.content_type).unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"));
+
+    let mut response = Response::new(Body::from(bytes));
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::CONTENT_TYPE, content_type);
+    response_headers.insert(header::CONTENT_DISPOSITION, content_disposition);
+    response_headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    // Signed URLs are per-recipient; keep them out of shared caches
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, max-age=300"));
+    response_headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static("default-src 'none'; img-src 'self'; style-src 'unsafe-inline'; sandbox"),
+    );
+
+    Ok(response.into_response())
+}
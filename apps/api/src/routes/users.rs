@@ -0,0 +1,559 @@
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::models::{Permission, UserRole};
+use crate::utils::refresh_tokens::{self, RevokeReason};
+use crate::utils::{audit, category_access, password, permissions, service_tokens};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// User account as shown to admins (never includes the password hash)
+#[derive(Debug, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub role: UserRole,
+    pub is_active: bool,
+    /// Service accounts authenticate with service tokens and cannot sign in
+    pub is_service: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Query parameters for list_users
+#[derive(Debug, Deserialize)]
+pub struct ListUsersQuery {
+    /// Include deactivated accounts (default: false)
+    #[serde(default)]
+    pub include_inactive: bool,
+    pub role: Option<UserRole>,
+}
+
+/// Request to create a user account
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CreateUserRequest {
+    pub email: String,
+    pub password: String,
+    pub role: UserRole,
+}
+
+/// Request to update a user account
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateUserRequest {
+    #[ts(optional)]
+    pub email: Option<String>,
+    /// Reactivate (true) or deactivate (false) the account
+    #[ts(optional)]
+    pub is_active: Option<bool>,
+}
+
+/// Request to change a user's role
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct ChangeRoleRequest {
+    pub role: UserRole,
+}
+
+/// Permissions granted to a role
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RolePermissions {
+    pub role: UserRole,
+    pub permissions: Vec<Permission>,
+    /// False for ADMIN, which always holds every permission
+    pub editable: bool,
+}
+
+/// Request body for update_role_permissions
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateRolePermissionsRequest {
+    pub permissions: Vec<Permission>,
+}
+
+/// Issue categories a user may edit
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UserCategoryAccess {
+    pub user_id: Uuid,
+    /// False when the user may edit every category
+    pub restricted: bool,
+    /// Categories the user may edit (empty when unrestricted)
+    pub categories: Vec<String>,
+}
+
+/// Request body for update_user_categories; an empty list lifts the restriction
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateUserCategoriesRequest {
+    pub categories: Vec<String>,
+}
+
+fn role_permissions(role: UserRole) -> RolePermissions {
+    RolePermissions {
+        permissions: permissions::permissions_for(&role).into_iter().collect(),
+        editable: role != UserRole::Admin,
+        role,
+    }
+}
+
+pub(crate) const USER_COLUMNS: &str = "id, email, display_name, role, is_active, is_service, created_at, updated_at";
+
+/// Normalize and sanity-check an email address
+pub(crate) fn normalize_email(email: &str) -> Result<String, String> {
+    let email = email.trim().to_lowercase();
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        }
+        None => false,
+    };
+
+    if !valid || email.len() > 255 || email.chars().any(char::is_whitespace) {
+        return Err("Must be a valid email address".to_string());
+    }
+    Ok(email)
+}
+
+async fn fetch_user(state: &AppState, id: Uuid) -> ApiResult<UserSummary> {
+    sqlx::query_as::<_, UserSummary>(&format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS))
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User not found"))
+}
+
+pub(crate) async fn ensure_email_available(state: &AppState, email: &str, except: Option<Uuid>) -> ApiResult<()> {
+    let taken = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE LOWER(email) = $1 AND ($2::uuid IS NULL OR id <> $2))"
+    )
+    .bind(email)
+    .bind(except)
+    .fetch_one(&state.db)
+    .await?;
+
+    if taken {
+        return Err(ApiError::Conflict {
+            message: "A user with this email already exists".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Refuse changes that would leave no active administrator
+///
+/// Run it in the transaction making the change: it locks every active admin
+/// (in ID order, so concurrent demotions queue rather than deadlock), and a
+/// demotion waiting on the lock sees the other one's result.
+async fn ensure_other_active_admin(conn: &mut sqlx::PgConnection, id: Uuid) -> ApiResult<()> {
+    let admins = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM users WHERE role = 'ADMIN' AND is_active = true ORDER BY id FOR UPDATE"
+    )
+    .fetch_all(conn)
+    .await?;
+
+    if !admins.iter().any(|admin| *admin != id) {
+        return Err(ApiError::Conflict {
+            message: "At least one active administrator is required".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn acting_user(auth: &AuthUser) -> ApiResult<Uuid> {
+    Uuid::parse_str(&auth.0.sub).map_err(|_| ApiError::internal("Invalid user ID in token"))
+}
+
+/// GET /api/admin/users
+/// List user accounts
+pub async fn list_users(
+    State(state): State<AppState>,
+    Query(query): Query<ListUsersQuery>,
+) -> ApiResult<Json<Vec<UserSummary>>> {
+    use sqlx::QueryBuilder;
+    let mut builder = QueryBuilder::new(format!("SELECT {} FROM users WHERE 1=1", USER_COLUMNS));
+
+    if !query.include_inactive {
+        builder.push(" AND is_active = true");
+    }
+
+    if let Some(role) = &query.role {
+        builder.push(" AND role = ");
+        builder.push_bind(role.clone());
+    }
+
+    builder.push(" ORDER BY email ASC");
+
+    let users = builder
+        .build_query_as::<UserSummary>()
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(users))
+}
+
+/// GET /api/admin/users/:id
+/// Get a single user account
+pub async fn get_user(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<UserSummary>> {
+    Ok(Json(fetch_user(&state, id).await?))
+}
+
+/// POST /api/admin/users
+/// Create a user account
+pub async fn create_user(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Json(req): Json<CreateUserRequest>,
+) -> ApiResult<Json<UserSummary>> {
+    let email = normalize_email(&req.email);
+    let password = password::validate_password(&req.password);
+
+    let mut errors = Vec::new();
+    if let Err(e) = &email {
+        errors.push(("email".to_string(), e.clone()));
+    }
+    if let Err(e) = &password {
+        errors.push(("password".to_string(), e.clone()));
+    }
+    let (Ok(email), Ok(())) = (email, password) else {
+        return Err(ApiError::validation(errors));
+    };
+
+    ensure_email_available(&state, &email, None).await?;
+
+    let password_hash = password::hash_password(&req.password).map_err(ApiError::internal)?;
+
+    let user = sqlx::query_as::<_, UserSummary>(&format!(
+        "INSERT INTO users (email, password_hash, role, is_active)
+         VALUES ($1, $2, $3, true)
+         RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(&email)
+    .bind(&password_hash)
+    .bind(&req.role)
+    .fetch_one(&state.db)
+    .await?;
+
+    let user_id = acting_user(&auth)?;
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UserCreated,
+        "user",
+        Some(&user.id.to_string()),
+        Some(json!({
+            "email": &user.email,
+            "role": &user.role,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("👤 User created: {} ({:?})", user.email, user.role);
+
+    Ok(Json(user))
+}
+
+/// PUT /api/admin/users/:id
+/// Update a user's email or active flag
+pub async fn update_user(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateUserRequest>,
+) -> ApiResult<Json<UserSummary>> {
+    let user_id = acting_user(&auth)?;
+    let before = fetch_user(&state, id).await?;
+
+    let email = match &req.email {
+        Some(email) => {
+            let email = normalize_email(email)
+                .map_err(|e| ApiError::validation(vec![("email".to_string(), e)]))?;
+            ensure_email_available(&state, &email, Some(id)).await?;
+            Some(email)
+        }
+        None => None,
+    };
+
+    let mut tx = state.db.begin().await?;
+    if req.is_active == Some(false) {
+        if id == user_id {
+            return Err(ApiError::bad_request("You cannot deactivate your own account"));
+        }
+        if matches!(before.role, UserRole::Admin) {
+            ensure_other_active_admin(&mut tx, id).await?;
+        }
+    }
+
+    let user = sqlx::query_as::<_, UserSummary>(&format!(
+        "UPDATE users
+         SET email = COALESCE($2, email),
+             is_active = COALESCE($3, is_active),
+             updated_at = NOW()
+         WHERE id = $1
+         RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(id)
+    .bind(&email)
+    .bind(req.is_active)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    if before.is_active && !user.is_active {
+        refresh_tokens::revoke_all_for_user(&state.db, user.id, RevokeReason::Deactivated).await?;
+    }
+    if user.is_service && before.is_active != user.is_active {
+        service_tokens::reload(&state.db).await?;
+    }
+
+    let ip = audit::extract_ip_address(&headers);
+    let action = if before.is_active && !user.is_active {
+        audit::AuditAction::UserDeactivated
+    } else {
+        audit::AuditAction::UserUpdated
+    };
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        action,
+        "user",
+        Some(&user.id.to_string()),
+        Some(json!({
+            "email": &user.email,
+            "before": { "email": &before.email, "is_active": before.is_active },
+            "updates": &req,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(user))
+}
+
+/// PUT /api/admin/users/:id/role
+/// Change a user's role
+pub async fn change_user_role(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<ChangeRoleRequest>,
+) -> ApiResult<Json<UserSummary>> {
+    let user_id = acting_user(&auth)?;
+    let before = fetch_user(&state, id).await?;
+
+    let mut tx = state.db.begin().await?;
+    let demoting_admin = matches!(before.role, UserRole::Admin) && !matches!(req.role, UserRole::Admin);
+    if demoting_admin {
+        if id == user_id {
+            return Err(ApiError::bad_request("You cannot remove your own administrator role"));
+        }
+        ensure_other_active_admin(&mut tx, id).await?;
+    }
+
+    let user = sqlx::query_as::<_, UserSummary>(&format!(
+        "UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(id)
+    .bind(&req.role)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UserRoleChanged,
+        "user",
+        Some(&user.id.to_string()),
+        Some(json!({
+            "email": &user.email,
+            "from": &before.role,
+            "to": &user.role,
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("👤 Role changed for {}: {:?} -> {:?}", user.email, before.role, user.role);
+
+    Ok(Json(user))
+}
+
+/// GET /api/admin/roles/permissions
+/// Permissions currently granted to each role
+pub async fn list_role_permissions() -> ApiResult<Json<Vec<RolePermissions>>> {
+    Ok(Json(
+        [UserRole::Admin, UserRole::Editor, UserRole::Tech, UserRole::Viewer]
+            .into_iter()
+            .map(role_permissions)
+            .collect(),
+    ))
+}
+
+/// PUT /api/admin/roles/:role/permissions
+/// Replace the permissions granted to a role other than ADMIN
+pub async fn update_role_permissions(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(role): Path<UserRole>,
+    Json(req): Json<UpdateRolePermissionsRequest>,
+) -> ApiResult<Json<RolePermissions>> {
+    let user_id = acting_user(&auth)?;
+
+    if role == UserRole::Admin {
+        return Err(ApiError::bad_request("Administrators always hold every permission"));
+    }
+
+    let before = permissions::permissions_for(&role);
+    let after = req.permissions.into_iter().collect();
+    permissions::set_role_permissions(&state.db, &role, &after).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::RolePermissionsChanged,
+        "role",
+        Some(&format!("{:?}", role)),
+        Some(json!({
+            "granted": after.difference(&before).collect::<Vec<_>>(),
+            "revoked": before.difference(&after).collect::<Vec<_>>(),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("🔑 Permissions for {:?} set to {:?}", role, after);
+
+    Ok(Json(role_permissions(role)))
+}
+
+/// GET /api/admin/users/:id/categories
+/// Issue categories a user is restricted to editing
+pub async fn get_user_categories(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<UserCategoryAccess>> {
+    fetch_user(&state, id).await?;
+    let categories = category_access::editable_categories(&state.db, id).await?;
+
+    Ok(Json(UserCategoryAccess {
+        user_id: id,
+        restricted: categories.is_some(),
+        categories: categories.unwrap_or_default(),
+    }))
+}
+
+/// PUT /api/admin/users/:id/categories
+/// Restrict which issue categories a user may edit (empty list = all)
+pub async fn update_user_categories(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateUserCategoriesRequest>,
+) -> ApiResult<Json<UserCategoryAccess>> {
+    let user_id = acting_user(&auth)?;
+    let user = fetch_user(&state, id).await?;
+
+    let mut categories: Vec<String> = req
+        .categories
+        .iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    categories.sort();
+    categories.dedup();
+
+    let before = category_access::editable_categories(&state.db, id).await?;
+    category_access::set_editable_categories(&state.db, id, &categories, user_id).await?;
+
+    let ip = audit::extract_ip_address(&headers);
+
+    audit::log_event(
+        &state.db,
+        user_id,
+        audit::AuditAction::UserCategoryAccessChanged,
+        "user",
+        Some(&id.to_string()),
+        Some(json!({
+            "email": &user.email,
+            "from": before,
+            "to": (!categories.is_empty()).then_some(&categories),
+        })),
+        ip.as_deref(),
+    )
+    .await?;
+
+    tracing::info!("👤 Editable categories for {} set to {:?}", user.email, categories);
+
+    Ok(Json(UserCategoryAccess {
+        user_id: id,
+        restricted: !categories.is_empty(),
+        categories,
+    }))
+}
+
+/// DELETE /api/admin/users/:id
+/// Deactivate a user account (rows are kept for the audit trail)
+pub async fn deactivate_user(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<UserSummary>> {
+    update_user(
+        State(state),
+        Extension(auth),
+        headers,
+        Path(id),
+        Json(UpdateUserRequest {
+            email: None,
+            is_active: Some(false),
+        }),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(normalize_email("  Tech@Example.COM ").unwrap(), "tech@example.com");
+        assert!(normalize_email("no-at-sign.example.com").is_err());
+        assert!(normalize_email("@example.com").is_err());
+        assert!(normalize_email("tech@localhost").is_err());
+        assert!(normalize_email("te ch@example.com").is_err());
+    }
+}
@@ -0,0 +1,443 @@
+pub mod sanitize;
+pub mod scanner;
+pub mod storage;
+
+/// Upload service
+///
+/// Every feature that accepts files (node attachments, session photos) goes
+/// through `UploadService::store`:
+/// 1. size check, then content type detected from the bytes and checked
+///    against UPLOAD_ALLOWED_TYPES
+/// 2. images re-encoded to strip EXIF/GPS metadata
+/// 3. optional ClamAV scan (CLAMAV_ADDRESS)
+/// 4. written to the storage backend and indexed in the `uploads` table
+///
+/// Files are never served from a guessable path; callers hand out signed,
+/// expiring URLs from `signed_url`.
+use crate::error::{ApiError, ApiResult};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use scanner::{ClamAvScanner, ScanVerdict};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::OnceLock;
+use storage::Storage;
+use ts_rs::TS;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Largest accepted upload when UPLOAD_MAX_BYTES is not set (10 MiB)
+const DEFAULT_MAX_BYTES: usize = 10 * 1024 * 1024;
+/// Signed URL lifetime when UPLOAD_URL_TTL_SECONDS is not set
+const DEFAULT_URL_TTL_SECONDS: i64 = 900;
+/// Types accepted when UPLOAD_ALLOWED_TYPES is not set
+const DEFAULT_ALLOWED_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp", "application/pdf"];
+
+const UPLOAD_COLUMNS: &str =
+    "id, storage_key, original_filename, content_type, size_bytes, sha256, owner_type, owner_id, uploaded_by, scan_status, created_at";
+
+/// What an upload is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum UploadOwnerType {
+    Node,
+    Session,
+}
+
+/// A file attached to a node (a manual, a wiring diagram), as sent to technicians
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodeAttachment {
+    pub id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    #[ts(type = "number")]
+    pub size_bytes: i64,
+    /// Signed download URL; valid until `url_expires_at`
+    pub url: String,
+    pub url_expires_at: DateTime<Utc>,
+}
+
+/// A stored upload (row in `uploads`)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Upload {
+    pub id: Uuid,
+    pub storage_key: String,
+    pub original_filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub owner_type: Option<UploadOwnerType>,
+    pub owner_id: Option<String>,
+    pub uploaded_by: Option<Uuid>,
+    pub scan_status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A file to store
+#[derive(Debug)]
+pub struct NewUpload {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    pub owner: Option<(UploadOwnerType, String)>,
+    pub uploaded_by: Option<Uuid>,
+}
+
+/// Upload settings (from environment)
+#[derive(Debug, Clone)]
+pub struct UploadConfig {
+    pub max_bytes: usize,
+    pub allowed_types: Vec<String>,
+    pub url_ttl: Duration,
+    signing_secret: Vec<u8>,
+}
+
+impl UploadConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let max_bytes = std::env::var("UPLOAD_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_MAX_BYTES);
+
+        let allowed_types: Vec<String> = match std::env::var("UPLOAD_ALLOWED_TYPES") {
+            Ok(list) => list.split(',').map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()).collect(),
+            Err(_) => DEFAULT_ALLOWED_TYPES.iter().map(|t| t.to_string()).collect(),
+        };
+        if let Some(unknown) = allowed_types.iter().find(|t| !sanitize::KNOWN_TYPES.contains(&t.as_str())) {
+            return Err(format!(
+                "UPLOAD_ALLOWED_TYPES contains '{}'; supported types are {}",
+                unknown,
+                sanitize::KNOWN_TYPES.join(", ")
+            ));
+        }
+
+        let url_ttl = std::env::var("UPLOAD_URL_TTL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_URL_TTL_SECONDS);
+
+        let signing_secret = std::env::var("UPLOAD_SIGNING_SECRET")
+            .or_else(|_| std::env::var("JWT_SECRET"))
+            .map_err(|_| "UPLOAD_SIGNING_SECRET or JWT_SECRET must be set".to_string())?;
+
+        Ok(Self {
+            max_bytes,
+            allowed_types,
+            url_ttl: Duration::seconds(url_ttl),
+            signing_secret: signing_secret.into_bytes(),
+        })
+    }
+}
+
+/// Upload pipeline: validation, sanitizing, scanning, storage and URL signing
+#[derive(Debug)]
+pub struct UploadService {
+    pub config: UploadConfig,
+    pub storage: Storage,
+    pub scanner: Option<ClamAvScanner>,
+}
+
+static SERVICE: OnceLock<UploadService> = OnceLock::new();
+
+/// Configure the process-wide upload service (call once at startup)
+pub fn init() -> Result<&'static UploadService, String> {
+    if let Some(service) = SERVICE.get() {
+        return Ok(service);
+    }
+    let service = UploadService {
+        config: UploadConfig::from_env()?,
+        storage: Storage::from_env()?,
+        scanner: ClamAvScanner::from_env(),
+    };
+    Ok(SERVICE.get_or_init(|| service))
+}
+
+/// The upload service configured by `init`
+pub fn service() -> ApiResult<&'static UploadService> {
+    SERVICE.get().ok_or_else(|| ApiError::internal("Upload storage is not configured"))
+}
+
+/// Attachments of a node with fresh signed URLs, oldest first
+///
+/// Empty when upload storage isn't configured, so sessions never fail over
+/// reference material.
+pub async fn node_attachments(db: &PgPool, node_id: Uuid) -> ApiResult<Vec<NodeAttachment>> {
+    let Some(service) = SERVICE.get() else {
+        return Ok(Vec::new());
+    };
+    let uploads = service.list_for_owner(db, UploadOwnerType::Node, &node_id.to_string()).await?;
+    Ok(uploads
+        .into_iter()
+        .map(|upload| {
+            let (url, url_expires_at) = service.signed_url(upload.id);
+            NodeAttachment {
+                id: upload.id,
+                filename: upload.original_filename,
+                content_type: upload.content_type,
+                size_bytes: upload.size_bytes,
+                url,
+                url_expires_at,
+            }
+        })
+        .collect())
+}
+
+/// Keep only characters that are safe in a Content-Disposition filename
+pub fn sanitize_filename(filename: &str) -> String {
+    let base = filename.rsplit(['/', '\\']).next().unwrap_or("");
+    let cleaned: String = base
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') { c } else { '_' })
+        .collect::<String>()
+        .trim_matches(|c: char| c == '.' || c.is_whitespace())
+        .chars()
+        .take(200)
+        .collect();
+
+    if cleaned.is_empty() {
+        "upload".to_string()
+    } else {
+        cleaned
+    }
+}
+
+impl UploadService {
+    /// Validate, sanitize, scan and store a file
+    pub async fn store(&self, db: &PgPool, upload: NewUpload) -> ApiResult<Upload> {
+        if upload.bytes.is_empty() {
+            return Err(ApiError::validation(vec![("file".to_string(), "File is empty".to_string())]));
+        }
+        if upload.bytes.len() > self.config.max_bytes {
+            return Err(ApiError::validation(vec![(
+                "file".to_string(),
+                format!("File is larger than {} bytes", self.config.max_bytes),
+            )]));
+        }
+
+        let filename = sanitize_filename(&upload.filename);
+        let content_type = sanitize::detect_content_type(&upload.bytes, &filename)
+            .filter(|t| self.config.allowed_types.iter().any(|a| a == t))
+            .ok_or_else(|| {
+                ApiError::validation(vec![(
+                    "file".to_string(),
+                    format!("Unsupported file type; allowed: {}", self.config.allowed_types.join(", ")),
+                )])
+            })?;
+
+        let bytes = if sanitize::is_image(content_type) {
+            tokio::task::spawn_blocking(move || sanitize::strip_metadata(upload.bytes, content_type))
+                .await
+                .map_err(|e| ApiError::internal(format!("Image processing failed: {}", e)))?
+                .map_err(|e| ApiError::validation(vec![("file".to_string(), e)]))?
+        } else {
+            upload.bytes
+        };
+
+        let scan_status = match &self.scanner {
+            Some(scanner) => match scanner.scan(&bytes).await {
+                Ok(ScanVerdict::Clean) => "clean",
+                Ok(ScanVerdict::Infected(signature)) => {
+                    tracing::warn!("🦠 Upload '{}' rejected by ClamAV: {}", filename, signature);
+                    return Err(ApiError::validation(vec![(
+                        "file".to_string(),
+                        "File was rejected by the virus scanner".to_string(),
+                    )]));
+                }
+                Err(e) => {
+                    tracing::error!("❌ Virus scan unavailable, rejecting upload: {}", e);
+                    return Err(ApiError::internal("Virus scanning is unavailable; please try again later"));
+                }
+            },
+            None => "not_scanned",
+        };
+
+        let id = Uuid::new_v4();
+        let storage_key = format!(
+            "{}/{}.{}",
+            Utc::now().format("%Y/%m"),
+            id.simple(),
+            sanitize::extension_for(content_type)
+        );
+
+        self.storage.put(&storage_key, &bytes, content_type).await.map_err(|e| {
+            tracing::error!("❌ Failed to store upload: {}", e);
+            ApiError::internal("Failed to store upload")
+        })?;
+
+        let (owner_type, owner_id) = upload.owner.map_or((None, None), |(t, id)| (Some(t), Some(id)));
+        let inserted = sqlx::query_as::<_, Upload>(&format!(
+            "INSERT INTO uploads (id, storage_key, original_filename, content_type, size_bytes, sha256, owner_type, owner_id, uploaded_by, scan_status)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             RETURNING {}",
+            UPLOAD_COLUMNS
+        ))
+        .bind(id)
+        .bind(&storage_key)
+        .bind(&filename)
+        .bind(content_type)
+        .bind(bytes.len() as i64)
+        .bind(format!("{:x}", Sha256::digest(&bytes)))
+        .bind(owner_type)
+        .bind(owner_id)
+        .bind(upload.uploaded_by)
+        .bind(scan_status)
+        .fetch_one(db)
+        .await;
+
+        match inserted {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                // Don't leave an orphaned object behind
+                if let Err(cleanup) = self.storage.delete(&storage_key).await {
+                    tracing::warn!("⚠️  Failed to remove orphaned upload {}: {}", storage_key, cleanup);
+                }
+                Err(e.into())
+            }
+        }
+    }
+
+    pub async fn find(&self, db: &PgPool, id: Uuid) -> ApiResult<Upload> {
+        sqlx::query_as::<_, Upload>(&format!("SELECT {} FROM uploads WHERE id = $1", UPLOAD_COLUMNS))
+            .bind(id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Upload not found"))
+    }
+
+    /// Uploads attached to a node or session, oldest first
+    pub async fn list_for_owner(&self, db: &PgPool, owner_type: UploadOwnerType, owner_id: &str) -> ApiResult<Vec<Upload>> {
+        let uploads = sqlx::query_as::<_, Upload>(&format!(
+            "SELECT {} FROM uploads WHERE owner_type = $1 AND owner_id = $2 ORDER BY created_at ASC",
+            UPLOAD_COLUMNS
+        ))
+        .bind(owner_type)
+        .bind(owner_id)
+        .fetch_all(db)
+        .await?;
+        Ok(uploads)
+    }
+
+    /// Read an upload's contents from storage
+    pub async fn read(&self, upload: &Upload) -> ApiResult<Vec<u8>> {
+        self.storage
+            .get(&upload.storage_key)
+            .await
+            .map_err(|e| {
+                tracing::error!("❌ Failed to read upload {}: {}", upload.id, e);
+                ApiError::internal("Failed to read upload")
+            })?
+            .ok_or_else(|| ApiError::not_found("Upload content is missing"))
+    }
+
+    /// Delete an upload's row and contents
+    pub async fn delete(&self, db: &PgPool, id: Uuid) -> ApiResult<Upload> {
+        let upload = sqlx::query_as::<_, Upload>(&format!("DELETE FROM uploads WHERE id = $1 RETURNING {}", UPLOAD_COLUMNS))
+            .bind(id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Upload not found"))?;
+
+        if let Err(e) = self.storage.delete(&upload.storage_key).await {
+            tracing::warn!("⚠️  Upload {} removed from index but not from storage: {}", id, e);
+        }
+        Ok(upload)
+    }
+
+    fn signature(&self, id: Uuid, expires: i64) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.config.signing_secret).expect("HMAC accepts any key length");
+        mac.update(format!("upload:{}:{}", id, expires).as_bytes());
+        mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Expiring download URL for an upload
+    pub fn signed_url(&self, id: Uuid) -> (String, DateTime<Utc>) {
+        let expires_at = Utc::now() + self.config.url_ttl;
+        let expires = expires_at.timestamp();
+        (
+            format!("/api/v1/uploads/{}/content?expires={}&signature={}", id, expires, self.signature(id, expires)),
+            expires_at,
+        )
+    }
+
+    /// Check a download URL's signature and expiry
+    pub fn verify_signature(&self, id: Uuid, expires: i64, signature: &str) -> bool {
+        if expires < Utc::now().timestamp() {
+            return false;
+        }
+        let mut mac = HmacSha256::new_from_slice(&self.config.signing_secret).expect("HMAC accepts any key length");
+        mac.update(format!("upload:{}:{}", id, expires).as_bytes());
+
+        let Some(provided) = decode_hex(signature) else {
+            return false;
+        };
+        // Constant-time comparison
+        mac.verify_slice(&provided).is_ok()
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_service() -> UploadService {
+        UploadService {
+            config: UploadConfig {
+                max_bytes: 1024,
+                allowed_types: vec!["image/png".to_string()],
+                url_ttl: Duration::seconds(60),
+                signing_secret: b"test-signing-secret".to_vec(),
+            },
+            storage: Storage::Local(storage::LocalStorage::new(std::env::temp_dir())),
+            scanner: None,
+        }
+    }
+
+    #[test]
+    fn test_signed_url_roundtrip() {
+        let service = test_service();
+        let id = Uuid::new_v4();
+        let (url, expires_at) = service.signed_url(id);
+
+        let query = url.split_once('?').unwrap().1;
+        let params: std::collections::HashMap<_, _> = query.split('&').filter_map(|p| p.split_once('=')).collect();
+        let expires: i64 = params["expires"].parse().unwrap();
+        assert_eq!(expires, expires_at.timestamp());
+
+        assert!(service.verify_signature(id, expires, params["signature"]));
+        assert!(!service.verify_signature(Uuid::new_v4(), expires, params["signature"]));
+        assert!(!service.verify_signature(id, expires + 1, params["signature"]));
+        assert!(!service.verify_signature(id, expires, "zz"));
+    }
+
+    #[test]
+    fn test_expired_signature_rejected() {
+        let service = test_service();
+        let id = Uuid::new_v4();
+        let expires = Utc::now().timestamp() - 1;
+        let signature = service.signature(id, expires);
+        assert!(!service.verify_signature(id, expires, &signature));
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("C:\\photos\\pump \"A\".jpg"), "pump _A_.jpg");
+        assert_eq!(sanitize_filename("..."), "upload");
+    }
+}
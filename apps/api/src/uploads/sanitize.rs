@@ -0,0 +1,170 @@
+/// File type detection and image re-encoding
+///
+/// Types are detected from magic bytes; the client's Content-Type and file
+/// extension are never trusted. Images are decoded and encoded again, which
+/// drops EXIF (GPS position, camera serials), XMP and any trailing payload.
+/// The EXIF orientation is applied to the pixels first so photos taken on
+/// phones still display upright.
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Largest accepted image edge in pixels
+const MAX_IMAGE_DIMENSION: u32 = 12_000;
+/// Decoder memory ceiling (guards against decompression bombs)
+const MAX_DECODE_ALLOC_BYTES: u64 = 512 * 1024 * 1024;
+/// Quality for re-encoded JPEGs
+const JPEG_QUALITY: u8 = 90;
+
+/// Content types the upload service can recognise
+pub const KNOWN_TYPES: &[&str] = &[
+    "image/jpeg",
+    "image/png",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "text/plain",
+];
+
+/// Detect a file's content type from its leading bytes
+///
+/// Plain text is only recognised for `.txt`/`.log` files that are valid
+/// UTF-8 without control characters, since text has no magic number.
+pub fn detect_content_type(bytes: &[u8], filename: &str) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        return Some("image/png");
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if bytes.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+
+    let lower = filename.to_ascii_lowercase();
+    if (lower.ends_with(".txt") || lower.ends_with(".log"))
+        && std::str::from_utf8(bytes)
+            .map(|text| !text.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')))
+            .unwrap_or(false)
+    {
+        return Some("text/plain");
+    }
+
+    None
+}
+
+/// File extension used for stored objects of a content type
+pub fn extension_for(content_type: &str) -> &'static str {
+    match content_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+fn image_format(content_type: &str) -> Option<ImageFormat> {
+    match content_type {
+        "image/jpeg" => Some(ImageFormat::Jpeg),
+        "image/png" => Some(ImageFormat::Png),
+        "image/gif" => Some(ImageFormat::Gif),
+        "image/webp" => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// Whether a content type is an image that gets re-encoded
+pub fn is_image(content_type: &str) -> bool {
+    image_format(content_type).is_some()
+}
+
+/// Re-encode an image in its own format without metadata
+///
+/// Animated GIFs keep only their first frame. WebP is written lossless.
+/// Non-image content is returned unchanged.
+pub fn strip_metadata(bytes: Vec<u8>, content_type: &str) -> Result<Vec<u8>, String> {
+    let Some(format) = image_format(content_type) else {
+        return Ok(bytes);
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes.as_slice()), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| format!("Image could not be read: {}", e))?;
+    let orientation = decoder.orientation().map_err(|e| format!("Image could not be read: {}", e))?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| format!("Image could not be read: {}", e))?;
+    image.apply_orientation(orientation);
+
+    let mut out = Vec::with_capacity(bytes.len());
+    let encoded = match format {
+        ImageFormat::Jpeg => {
+            let image = if image.color().has_color() {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            } else {
+                DynamicImage::ImageLuma8(image.to_luma8())
+            };
+            JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY).encode_image(&image)
+        }
+        ImageFormat::WebP => DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(WebPEncoder::new_lossless(&mut out)),
+        ImageFormat::Gif => DynamicImage::ImageRgba8(image.to_rgba8()).write_to(&mut Cursor::new(&mut out), format),
+        _ => image.write_to(&mut Cursor::new(&mut out), format),
+    };
+    encoded.map_err(|e| format!("Image could not be re-encoded: {}", e))?;
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_png() -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 3, image::Rgb([200, 10, 10])));
+        let mut out = Vec::new();
+        image.write_to(&mut Cursor::new(&mut out), ImageFormat::Png).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_detect_content_type() {
+        assert_eq!(detect_content_type(&sample_png(), "photo.jpg"), Some("image/png"));
+        assert_eq!(detect_content_type(b"%PDF-1.7\n...", "manual.pdf"), Some("application/pdf"));
+        assert_eq!(detect_content_type(b"error 42 at boot\n", "printer.log"), Some("text/plain"));
+        assert_eq!(detect_content_type(b"error 42 at boot\n", "printer.exe"), None);
+        assert_eq!(detect_content_type(b"MZ\x90\x00", "photo.png"), None);
+    }
+
+    #[test]
+    fn test_strip_metadata_drops_trailing_payload() {
+        let mut png = sample_png();
+        png.extend_from_slice(b"<script>hidden</script>");
+
+        let cleaned = strip_metadata(png, "image/png").unwrap();
+        assert_eq!(detect_content_type(&cleaned, "x.png"), Some("image/png"));
+        assert!(!cleaned.windows(8).any(|w| w == b"<script>"));
+
+        let decoded = image::load_from_memory(&cleaned).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 3));
+    }
+
+    #[test]
+    fn test_strip_metadata_rejects_corrupt_images() {
+        let truncated = sample_png()[..20].to_vec();
+        assert!(strip_metadata(truncated, "image/png").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+/// ClamAV hook (clamd INSTREAM protocol)
+///
+/// When CLAMAV_ADDRESS is set every upload is streamed to clamd before it is
+/// stored. Scanning fails closed: if clamd is unreachable or errors, the
+/// upload is rejected rather than stored unscanned.
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Bytes sent per INSTREAM chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Connect + scan timeout when CLAMAV_TIMEOUT_SECONDS is not set
+const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+/// Outcome of a scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// Signature name reported by clamd
+    Infected(String),
+}
+
+/// Connection settings for clamd
+#[derive(Debug, Clone)]
+pub struct ClamAvScanner {
+    address: String,
+    timeout: Duration,
+}
+
+impl ClamAvScanner {
+    /// Scanner from CLAMAV_ADDRESS (host:port), or None when scanning is disabled
+    pub fn from_env() -> Option<Self> {
+        let address = std::env::var("CLAMAV_ADDRESS").ok().filter(|a| !a.trim().is_empty())?;
+        let timeout = std::env::var("CLAMAV_TIMEOUT_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+
+        Some(Self {
+            address: address.trim().to_string(),
+            timeout: Duration::from_secs(timeout),
+        })
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Stream bytes to clamd and interpret its reply
+    pub async fn scan(&self, bytes: &[u8]) -> Result<ScanVerdict, String> {
+        tokio::time::timeout(self.timeout, self.scan_inner(bytes))
+            .await
+            .map_err(|_| format!("ClamAV scan timed out after {}s", self.timeout.as_secs()))?
+    }
+
+    async fn scan_inner(&self, bytes: &[u8]) -> Result<ScanVerdict, String> {
+        let mut stream = TcpStream::connect(&self.address)
+            .await
+            .map_err(|e| format!("ClamAV unreachable at {}: {}", self.address, e))?;
+
+        let io_err = |e: std::io::Error| format!("ClamAV connection failed: {}", e);
+
+        stream.write_all(b"zINSTREAM\0").await.map_err(io_err)?;
+        for chunk in bytes.chunks(CHUNK_SIZE) {
+            stream.write_all(&(chunk.len() as u32).to_be_bytes()).await.map_err(io_err)?;
+            stream.write_all(chunk).await.map_err(io_err)?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await.map_err(io_err)?;
+        stream.flush().await.map_err(io_err)?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await.map_err(io_err)?;
+
+        parse_reply(&String::from_utf8_lossy(&reply))
+    }
+}
+
+/// Parse a clamd reply such as `stream: OK` or `stream: Eicar-Signature FOUND`
+pub fn parse_reply(reply: &str) -> Result<ScanVerdict, String> {
+    let reply = reply.trim_end_matches('\0').trim();
+    let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+
+    if result == "OK" {
+        Ok(ScanVerdict::Clean)
+    } else if let Some(signature) = result.strip_suffix("FOUND") {
+        Ok(ScanVerdict::Infected(signature.trim().to_string()))
+    } else {
+        Err(format!("ClamAV error: {}", result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(parse_reply("stream: OK\0"), Ok(ScanVerdict::Clean));
+        assert_eq!(
+            parse_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0"),
+            Ok(ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string()))
+        );
+        assert!(parse_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+}
@@ -0,0 +1,397 @@
+/// Storage backends for uploaded files
+///
+/// UPLOAD_STORAGE selects the backend: `local` (default) writes under
+/// UPLOAD_DIR; `s3` talks to S3 or an S3-compatible service (MinIO, R2) with
+/// SigV4-signed requests. Keys are generated by the upload service and only
+/// contain `[a-z0-9/._-]`, so they are safe as both paths and URL segments.
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Directory used when UPLOAD_DIR is not set
+const DEFAULT_UPLOAD_DIR: &str = "./uploads";
+/// Region used when S3_REGION is not set
+const DEFAULT_S3_REGION: &str = "us-east-1";
+
+/// Where upload contents are kept
+#[derive(Debug, Clone)]
+pub enum Storage {
+    Local(LocalStorage),
+    S3(S3Storage),
+}
+
+impl Storage {
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("UPLOAD_STORAGE").unwrap_or_else(|_| "local".to_string()).to_lowercase().as_str() {
+            "local" => Ok(Self::Local(LocalStorage::new(
+                std::env::var("UPLOAD_DIR").unwrap_or_else(|_| DEFAULT_UPLOAD_DIR.to_string()),
+            ))),
+            "s3" => S3Storage::from_env().map(Self::S3),
+            other => Err(format!("UPLOAD_STORAGE must be 'local' or 's3', got '{}'", other)),
+        }
+    }
+
+    /// Short description for startup logs
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Local(local) => format!("local ({})", local.root.display()),
+            Self::S3(s3) => format!("s3 ({}/{})", s3.endpoint, s3.bucket),
+        }
+    }
+
+    pub async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), String> {
+        check_key(key)?;
+        match self {
+            Self::Local(local) => local.put(key, bytes).await,
+            Self::S3(s3) => s3.put(key, bytes, content_type).await,
+        }
+    }
+
+    /// Read an object (None if it doesn't exist)
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        check_key(key)?;
+        match self {
+            Self::Local(local) => local.get(key).await,
+            Self::S3(s3) => s3.get(key).await,
+        }
+    }
+
+    /// Delete an object (missing objects are not an error)
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        check_key(key)?;
+        match self {
+            Self::Local(local) => local.delete(key).await,
+            Self::S3(s3) => s3.delete(key).await,
+        }
+    }
+}
+
+/// Reject keys that could escape the storage root
+fn check_key(key: &str) -> Result<(), String> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && key.split('/').all(|part| !part.is_empty() && part != "." && part != "..")
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '/' | '.' | '_' | '-'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid storage key: {}", key))
+    }
+}
+
+/// Files under a local directory
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(Path::new(key))
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        // Write then rename so readers never see a partial file
+        let tmp = path.with_extension("partial");
+        tokio::fs::write(&tmp, bytes)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| format!("Failed to move upload into place: {}", e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read upload {}: {}", key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to delete upload {}: {}", key, e)),
+        }
+    }
+}
+
+/// Objects in an S3 bucket (path-style requests)
+#[derive(Clone)]
+pub struct S3Storage {
+    client: reqwest::Client,
+    endpoint: String,
+    host: String,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+}
+
+impl std::fmt::Debug for S3Storage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Storage")
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    fn from_env() -> Result<Self, String> {
+        let required = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .ok_or_else(|| format!("{} must be set when UPLOAD_STORAGE=s3", name))
+        };
+
+        let bucket = required("S3_BUCKET")?;
+        let region = std::env::var("S3_REGION").unwrap_or_else(|_| DEFAULT_S3_REGION.to_string());
+        let endpoint = std::env::var("S3_ENDPOINT")
+            .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region))
+            .trim_end_matches('/')
+            .to_string();
+
+        let url = reqwest::Url::parse(&endpoint).map_err(|e| format!("S3_ENDPOINT is not a valid URL: {}", e))?;
+        if url.path() != "/" {
+            return Err("S3_ENDPOINT must not include a path".to_string());
+        }
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err("S3_ENDPOINT must include a host".to_string()),
+        };
+
+        let prefix = std::env::var("S3_PREFIX").unwrap_or_default().trim_matches('/').to_string();
+        if !prefix.is_empty() {
+            check_key(&prefix).map_err(|_| "S3_PREFIX may only contain lowercase letters, digits, '/', '.', '_' and '-'".to_string())?;
+        }
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            host,
+            bucket,
+            region,
+            prefix,
+            access_key: required("AWS_ACCESS_KEY_ID")?,
+            secret_key: required("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok().filter(|t| !t.is_empty()),
+        })
+    }
+
+    fn object_path(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            format!("/{}/{}", self.bucket, key)
+        } else {
+            format!("/{}/{}/{}", self.bucket, self.prefix, key)
+        }
+    }
+
+    /// Build a SigV4-signed request for an object
+    fn request(&self, method: reqwest::Method, key: &str, body: &[u8]) -> reqwest::RequestBuilder {
+        let path = self.object_path(key);
+        let now = Utc::now();
+        let payload_hash = format!("{:x}", Sha256::digest(body));
+        let authorization = sign_v4(
+            &SigningParams {
+                method: method.as_str(),
+                host: &self.host,
+                path: &path,
+                region: &self.region,
+                service: "s3",
+                access_key: &self.access_key,
+                secret_key: &self.secret_key,
+                session_token: self.session_token.as_deref(),
+                payload_hash: &payload_hash,
+            },
+            now,
+        );
+
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.endpoint, path))
+            .header("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string())
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization);
+        if let Some(token) = &self.session_token {
+            request = request.header("x-amz-security-token", token);
+        }
+        request
+    }
+
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), String> {
+        let response = self
+            .request(reqwest::Method::PUT, key, bytes)
+            .header("content-type", content_type)
+            .body(bytes.to_vec())
+            .send()
+            .await
+            .map_err(|e| format!("S3 upload failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("S3 upload failed with status {}", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self
+            .request(reqwest::Method::GET, key, b"")
+            .send()
+            .await
+            .map_err(|e| format!("S3 download failed: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("S3 download failed with status {}", response.status()));
+        }
+        response
+            .bytes()
+            .await
+            .map(|b| Some(b.to_vec()))
+            .map_err(|e| format!("S3 download failed: {}", e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self
+            .request(reqwest::Method::DELETE, key, b"")
+            .send()
+            .await
+            .map_err(|e| format!("S3 delete failed: {}", e))?;
+
+        // S3 answers 204 even for missing keys; some compatible stores use 404
+        if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(format!("S3 delete failed with status {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Inputs to an AWS Signature Version 4 header
+struct SigningParams<'a> {
+    method: &'a str,
+    host: &'a str,
+    /// Already URI-safe (keys are restricted by check_key)
+    path: &'a str,
+    region: &'a str,
+    service: &'a str,
+    access_key: &'a str,
+    secret_key: &'a str,
+    session_token: Option<&'a str>,
+    payload_hash: &'a str,
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Derive the SigV4 signing key for a date, region and service
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date);
+    let k_region = hmac_sha256(&k_date, region);
+    let k_service = hmac_sha256(&k_region, service);
+    hmac_sha256(&k_service, "aws4_request")
+}
+
+/// Authorization header value for a request without a query string
+fn sign_v4(params: &SigningParams<'_>, now: DateTime<Utc>) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers = vec![
+        ("host", params.host.to_string()),
+        ("x-amz-content-sha256", params.payload_hash.to_string()),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(token) = params.session_token {
+        headers.push(("x-amz-security-token", token.to_string()));
+    }
+
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        params.method, params.path, canonical_headers, signed_headers, params.payload_hash
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, params.region, params.service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+        amz_date,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+
+    let key = signing_key(params.secret_key, &date, params.region, params.service);
+    let signature: String = hmac_sha256(&key, &string_to_sign).iter().map(|b| format!("{:02x}", b)).collect();
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        params.access_key, scope, signed_headers, signature
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_key() {
+        assert!(check_key("2025/01/0b1c.jpg").is_ok());
+        assert!(check_key("../etc/passwd").is_err());
+        assert!(check_key("/abs.jpg").is_err());
+        assert!(check_key("a//b.jpg").is_err());
+        assert!(check_key("Photo.JPG").is_err());
+    }
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        // Example from the AWS SigV4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_roundtrip() {
+        let root = std::env::temp_dir().join(format!("uploads-test-{}", uuid::Uuid::new_v4()));
+        let storage = Storage::Local(LocalStorage::new(&root));
+
+        storage.put("2025/01/a.txt", b"hello", "text/plain").await.unwrap();
+        assert_eq!(storage.get("2025/01/a.txt").await.unwrap(), Some(b"hello".to_vec()));
+
+        storage.delete("2025/01/a.txt").await.unwrap();
+        assert_eq!(storage.get("2025/01/a.txt").await.unwrap(), None);
+        storage.delete("2025/01/a.txt").await.unwrap();
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
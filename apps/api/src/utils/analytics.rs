@@ -0,0 +1,341 @@
+/// Analytics event sinks
+///
+/// Session events recorded through `session_events::record` (session
+/// started, step answered, conclusion reached, feedback given, abandoned)
+/// are queued and handed in batches to every sink listed in ANALYTICS_SINKS:
+///
+/// - `postgres`: the partitioned `session_events` table (also enabled by
+///   SESSION_EVENTS_ENABLED=true)
+/// - `stdout`: one JSON object per line, for log shippers
+/// - `kafka`: a topic on ANALYTICS_KAFKA_BROKERS (built with `--features kafka`)
+/// - `nats`: `<subject>.<event>` on ANALYTICS_NATS_URL (built with `--features nats`)
+///
+/// Delivery never holds up a session. A sink that fails or times out loses
+/// that batch (logged), and events are dropped while the queue is full.
+use crate::utils::session_events::SessionEventType;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Events waiting for delivery before new ones are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// Most events handed to a sink at once
+const BATCH_SIZE: usize = 500;
+
+/// Timeout for one sink to take a batch
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default Kafka topic and NATS subject prefix
+#[cfg(any(feature = "kafka", feature = "nats"))]
+const DEFAULT_TOPIC: &str = "troubleshooting.analytics";
+
+/// One analytics event as delivered to sinks
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsEvent {
+    pub id: Uuid,
+    /// `session_started`, `step_answered`, `conclusion_reached`, `feedback_given` or `session_abandoned`
+    #[serde(rename = "event", serialize_with = "serialize_event_name")]
+    pub kind: SessionEventType,
+    pub session_id: String,
+    pub category: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub details: Option<JsonValue>,
+}
+
+fn serialize_event_name<S: serde::Serializer>(kind: &SessionEventType, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(kind.event_name())
+}
+
+impl AnalyticsEvent {
+    pub fn new(kind: SessionEventType, session_id: &str, category: Option<&str>, details: Option<JsonValue>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            session_id: session_id.to_string(),
+            category: category.map(str::to_string),
+            occurred_at: Utc::now(),
+            details,
+        }
+    }
+}
+
+/// Future returned by a sink delivering a batch
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Somewhere analytics events are delivered
+pub trait AnalyticsSink: Send + Sync {
+    /// Name used in ANALYTICS_SINKS and logs
+    fn name(&self) -> &'static str;
+
+    /// Deliver a batch of events, oldest first
+    fn send<'a>(&'a self, events: &'a [AnalyticsEvent]) -> SinkFuture<'a>;
+}
+
+/// Events stored in the `session_events` table
+pub struct PostgresSink {
+    db: PgPool,
+}
+
+impl AnalyticsSink for PostgresSink {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn send<'a>(&'a self, events: &'a [AnalyticsEvent]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let session_ids: Vec<&str> = events.iter().map(|e| e.session_id.as_str()).collect();
+            let event_types: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+            let categories: Vec<Option<&str>> = events.iter().map(|e| e.category.as_deref()).collect();
+            let details: Vec<Option<&JsonValue>> = events.iter().map(|e| e.details.as_ref()).collect();
+            let occurred_at: Vec<DateTime<Utc>> = events.iter().map(|e| e.occurred_at).collect();
+
+            sqlx::query(
+                "INSERT INTO session_events (session_id, event_type, category, details, occurred_at)
+                 SELECT * FROM UNNEST($1::varchar[], $2::varchar[], $3::varchar[], $4::jsonb[], $5::timestamptz[])",
+            )
+            .bind(&session_ids)
+            .bind(&event_types)
+            .bind(&categories)
+            .bind(&details)
+            .bind(&occurred_at)
+            .execute(&self.db)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Events written to stdout as JSON lines
+pub struct StdoutSink;
+
+impl AnalyticsSink for StdoutSink {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    fn send<'a>(&'a self, events: &'a [AnalyticsEvent]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let mut out = std::io::stdout().lock();
+            for event in events {
+                let line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+                writeln!(out, "{}", line).map_err(|e| e.to_string())?;
+            }
+            out.flush().map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Events produced to a Kafka topic, keyed by session so each session's
+/// events stay in order on one partition
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    producer: rdkafka::producer::FutureProducer,
+    topic: String,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    fn from_env() -> Result<Self, String> {
+        let brokers = std::env::var("ANALYTICS_KAFKA_BROKERS").map_err(|_| "ANALYTICS_KAFKA_BROKERS is not set".to_string())?;
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .set("message.timeout.ms", DELIVERY_TIMEOUT.as_millis().to_string())
+            .create()
+            .map_err(|e| e.to_string())?;
+        let topic = std::env::var("ANALYTICS_KAFKA_TOPIC").unwrap_or_else(|_| DEFAULT_TOPIC.to_string());
+        Ok(Self { producer, topic })
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl AnalyticsSink for KafkaSink {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    fn send<'a>(&'a self, events: &'a [AnalyticsEvent]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            // Queue the whole batch, then wait for the broker to acknowledge it
+            let mut deliveries = Vec::with_capacity(events.len());
+            for event in events {
+                let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+                let record = rdkafka::producer::FutureRecord::to(&self.topic).key(&event.session_id).payload(&payload);
+                deliveries.push(self.producer.send_result(record).map_err(|(e, _)| e.to_string())?);
+            }
+            for delivery in deliveries {
+                delivery
+                    .await
+                    .map_err(|_| "delivery cancelled".to_string())?
+                    .map_err(|(e, _)| e.to_string())?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Events published to NATS, one subject per event type
+#[cfg(feature = "nats")]
+pub struct NatsSink {
+    client: async_nats::Client,
+    subject: String,
+}
+
+#[cfg(feature = "nats")]
+impl NatsSink {
+    async fn from_env() -> Result<Self, String> {
+        let url = std::env::var("ANALYTICS_NATS_URL").map_err(|_| "ANALYTICS_NATS_URL is not set".to_string())?;
+        let client = async_nats::connect(url).await.map_err(|e| e.to_string())?;
+        let subject = std::env::var("ANALYTICS_NATS_SUBJECT").unwrap_or_else(|_| DEFAULT_TOPIC.to_string());
+        Ok(Self { client, subject })
+    }
+}
+
+#[cfg(feature = "nats")]
+impl AnalyticsSink for NatsSink {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    fn send<'a>(&'a self, events: &'a [AnalyticsEvent]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            for event in events {
+                let payload = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+                self.client
+                    .publish(format!("{}.{}", self.subject, event.kind.event_name()), payload.into())
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            self.client.flush().await.map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Sink names from ANALYTICS_SINKS (comma-separated, duplicates removed)
+fn parse_sink_names(list: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in list.split(',').map(|n| n.trim().to_ascii_lowercase()).filter(|n| !n.is_empty()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Build one sink by name
+async fn build_sink(name: &str, db: &PgPool) -> Result<Box<dyn AnalyticsSink>, String> {
+    match name {
+        "postgres" => Ok(Box::new(PostgresSink { db: db.clone() })),
+        "stdout" => Ok(Box::new(StdoutSink)),
+        #[cfg(feature = "kafka")]
+        "kafka" => Ok(Box::new(KafkaSink::from_env()?)),
+        #[cfg(not(feature = "kafka"))]
+        "kafka" => Err("this build doesn't include the `kafka` feature".to_string()),
+        #[cfg(feature = "nats")]
+        "nats" => Ok(Box::new(NatsSink::from_env().await?)),
+        #[cfg(not(feature = "nats"))]
+        "nats" => Err("this build doesn't include the `nats` feature".to_string()),
+        _ => Err("unknown sink (expected postgres, stdout, kafka or nats)".to_string()),
+    }
+}
+
+/// Sinks configured in the environment; ones that can't be set up are logged and skipped
+pub async fn sinks_from_env(db: &PgPool) -> Vec<Box<dyn AnalyticsSink>> {
+    let mut names = parse_sink_names(&std::env::var("ANALYTICS_SINKS").unwrap_or_default());
+    // SESSION_EVENTS_ENABLED predates the sinks and still turns on the table
+    if crate::utils::session_events::is_enabled() && !names.iter().any(|n| n == "postgres") {
+        names.insert(0, "postgres".to_string());
+    }
+
+    let mut sinks = Vec::with_capacity(names.len());
+    for name in names {
+        match build_sink(&name, db).await {
+            Ok(sink) => sinks.push(sink),
+            Err(e) => tracing::warn!("⚠️  Analytics sink '{}' disabled: {}", name, e),
+        }
+    }
+    sinks
+}
+
+fn queue() -> &'static OnceLock<mpsc::Sender<AnalyticsEvent>> {
+    static QUEUE: OnceLock<mpsc::Sender<AnalyticsEvent>> = OnceLock::new();
+    &QUEUE
+}
+
+/// Events dropped because the queue was full, reported by the delivery task
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+/// Queue an event for the sinks (does nothing when none are configured)
+pub fn emit(event: AnalyticsEvent) {
+    if let Some(queue) = queue().get() {
+        if queue.try_send(event).is_err() {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Start delivering queued events to `sinks`
+pub fn spawn(sinks: Vec<Box<dyn AnalyticsSink>>) {
+    if sinks.is_empty() {
+        return;
+    }
+    let (sender, mut receiver) = mpsc::channel::<AnalyticsEvent>(QUEUE_CAPACITY);
+    if queue().set(sender).is_err() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        while receiver.recv_many(&mut batch, BATCH_SIZE).await > 0 {
+            for sink in &sinks {
+                match tokio::time::timeout(DELIVERY_TIMEOUT, sink.send(&batch)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => tracing::warn!("⚠️  Analytics sink {} lost {} event(s): {}", sink.name(), batch.len(), e),
+                    Err(_) => tracing::warn!("⚠️  Analytics sink {} lost {} event(s): timed out", sink.name(), batch.len()),
+                }
+            }
+            batch.clear();
+
+            let dropped = DROPPED.swap(0, Ordering::Relaxed);
+            if dropped > 0 {
+                tracing::warn!("⚠️  Dropped {} analytics event(s) while the queue was full", dropped);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sink_names() {
+        assert_eq!(parse_sink_names(" Stdout, kafka,,stdout "), vec!["stdout", "kafka"]);
+        assert!(parse_sink_names("").is_empty());
+    }
+
+    #[test]
+    fn test_event_json() {
+        let event = AnalyticsEvent::new(
+            SessionEventType::Completed,
+            "abc",
+            Some("pump"),
+            Some(serde_json::json!({ "step_count": 3 })),
+        );
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "conclusion_reached");
+        assert_eq!(json["session_id"], "abc");
+        assert_eq!(json["category"], "pump");
+        assert_eq!(json["details"]["step_count"], 3);
+    }
+}
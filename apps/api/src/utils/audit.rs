@@ -17,14 +17,46 @@ pub enum AuditAction {
     IssueDeleted,
     IssueExported,
     IssuesImported,
+    IssueReviewed,
+    IssueRolloutUpdated,
+    IssueDraftCreated,
+    IssueDraftDiscarded,
+    IssueDraftPublished,
+    IssueRolledBack,
+    IssueArchived,
+    IssueUnarchived,
+    IssueSlugRenamed,
+    IssueSubmittedForApproval,
+    IssueApprovalWithdrawn,
+    IssueApproved,
+    IssueRejected,
+    IssueQuotaUpdated,
+    IssueLayoutNormalized,
+    IssueLaidOut,
+    IssueOrphansCleaned,
+    IssueInactivePurged,
 
     // Node/Connection management
     NodeCreated,
+    NodeCloned,
+    NodeSubtreeTransferred,
+    NodeMerged,
+    NodeRestored,
     NodeUpdated,
     NodeDeleted,
+    NodeRevisionReverted,
+    NodePositionsUpdated,
+    EntryPointCreated,
+    EntryPointUpdated,
+    EntryPointDeleted,
+    NoticeCreated,
+    NoticeUpdated,
+    NoticeDeleted,
     ConnectionCreated,
     ConnectionUpdated,
     ConnectionDeleted,
+    ConnectionRestored,
+    ConnectionWeightsRecomputed,
 
     // Category management
     CategoryRenamed,
@@ -32,10 +64,43 @@ pub enum AuditAction {
 
     // Session management
     SessionsDeleted,
+    SessionsArchived,
+    SessionsMerged,
+    SessionsExported,
+
+    // Uploads
+    UploadCreated,
+    UploadDeleted,
+
+    // User management
+    UserCreated,
+    UserUpdated,
+    UserRoleChanged,
+    RolePermissionsChanged,
+    UserCategoryAccessChanged,
+    UserDeactivated,
+
+    // System
+    LogLevelChanged,
+    JwtKeyRotated,
+    BrandingUpdated,
+    SettingChanged,
 
     // Authentication
     AdminLogin,
+    AdminLoginFailed,
+    AccountLocked,
     AdminLogout,
+    PasswordChanged,
+    ProfileUpdated,
+    ServiceAccountCreated,
+    ServiceTokenIssued,
+    ServiceTokenRevoked,
+    DeviceRegistered,
+    DeviceDisabled,
+    DeviceEnabled,
+    PasswordResetRequested,
+    PasswordReset,
 }
 
 impl AuditAction {
@@ -47,17 +112,76 @@ impl AuditAction {
             Self::IssueDeleted => "issue_deleted",
             Self::IssueExported => "issue_exported",
             Self::IssuesImported => "issues_imported",
+            Self::IssueReviewed => "issue_reviewed",
+            Self::IssueRolloutUpdated => "issue_rollout_updated",
+            Self::IssueDraftCreated => "issue_draft_created",
+            Self::IssueDraftDiscarded => "issue_draft_discarded",
+            Self::IssueDraftPublished => "issue_draft_published",
+            Self::IssueRolledBack => "issue_rolled_back",
+            Self::IssueArchived => "issue_archived",
+            Self::IssueUnarchived => "issue_unarchived",
+            Self::IssueSlugRenamed => "issue_slug_renamed",
+            Self::IssueSubmittedForApproval => "issue_submitted_for_approval",
+            Self::IssueApprovalWithdrawn => "issue_approval_withdrawn",
+            Self::IssueApproved => "issue_approved",
+            Self::IssueRejected => "issue_rejected",
+            Self::IssueQuotaUpdated => "issue_quota_updated",
+            Self::IssueLayoutNormalized => "issue_layout_normalized",
+            Self::IssueLaidOut => "issue_laid_out",
+            Self::IssueOrphansCleaned => "issue_orphans_cleaned",
+            Self::IssueInactivePurged => "issue_inactive_purged",
             Self::NodeCreated => "node_created",
+            Self::NodeCloned => "node_cloned",
+            Self::NodeSubtreeTransferred => "node_subtree_transferred",
+            Self::NodeMerged => "node_merged",
+            Self::NodeRestored => "node_restored",
             Self::NodeUpdated => "node_updated",
             Self::NodeDeleted => "node_deleted",
+            Self::NodeRevisionReverted => "node_revision_reverted",
+            Self::NodePositionsUpdated => "node_positions_updated",
+            Self::EntryPointCreated => "entry_point_created",
+            Self::EntryPointUpdated => "entry_point_updated",
+            Self::EntryPointDeleted => "entry_point_deleted",
+            Self::NoticeCreated => "notice_created",
+            Self::NoticeUpdated => "notice_updated",
+            Self::NoticeDeleted => "notice_deleted",
             Self::ConnectionCreated => "connection_created",
             Self::ConnectionUpdated => "connection_updated",
             Self::ConnectionDeleted => "connection_deleted",
+            Self::ConnectionRestored => "connection_restored",
+            Self::ConnectionWeightsRecomputed => "connection_weights_recomputed",
             Self::CategoryRenamed => "category_renamed",
             Self::CategoryDeleted => "category_deleted",
             Self::SessionsDeleted => "sessions_deleted",
+            Self::SessionsArchived => "sessions_archived",
+            Self::SessionsMerged => "sessions_merged",
+            Self::SessionsExported => "sessions_exported",
+            Self::UploadCreated => "upload_created",
+            Self::UploadDeleted => "upload_deleted",
+            Self::UserCreated => "user_created",
+            Self::UserUpdated => "user_updated",
+            Self::UserRoleChanged => "user_role_changed",
+            Self::RolePermissionsChanged => "role_permissions_changed",
+            Self::UserCategoryAccessChanged => "user_category_access_changed",
+            Self::UserDeactivated => "user_deactivated",
+            Self::LogLevelChanged => "log_level_changed",
+            Self::JwtKeyRotated => "jwt_key_rotated",
+            Self::BrandingUpdated => "branding_updated",
+            Self::SettingChanged => "setting_changed",
             Self::AdminLogin => "admin_login",
+            Self::AdminLoginFailed => "admin_login_failed",
+            Self::AccountLocked => "account_locked",
             Self::AdminLogout => "admin_logout",
+            Self::PasswordChanged => "password_changed",
+            Self::ProfileUpdated => "profile_updated",
+            Self::ServiceAccountCreated => "service_account_created",
+            Self::ServiceTokenIssued => "service_token_issued",
+            Self::ServiceTokenRevoked => "service_token_revoked",
+            Self::DeviceRegistered => "device_registered",
+            Self::DeviceDisabled => "device_disabled",
+            Self::DeviceEnabled => "device_enabled",
+            Self::PasswordResetRequested => "password_reset_requested",
+            Self::PasswordReset => "password_reset",
         }
     }
 }
@@ -117,8 +241,9 @@ pub async fn log_event(
 
 /// Extract IP address from HTTP headers
 ///
-/// Attempts to get the real client IP from various proxy headers,
-/// falling back to the direct connection IP.
+/// Reads the proxy headers, which `client_ip_middleware` has already
+/// rewritten to the resolved client IP (forwarded values are only honored
+/// from TRUSTED_PROXIES, otherwise the TCP peer address is used).
 pub fn extract_ip_address(headers: &axum::http::HeaderMap) -> Option<String> {
     // Try X-Forwarded-For first (most common proxy header)
     if let Some(forwarded_for) = headers.get("x-forwarded-for") {
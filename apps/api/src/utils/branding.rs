@@ -0,0 +1,366 @@
+/// Per-deployment branding
+///
+/// Product name, logo, support contact, feature switches and locales are kept
+/// in the single-row `branding` table and cached process-wide, so the public
+/// config endpoint never touches the database. Other instances pick up admin
+/// changes on their next refresh.
+use crate::utils::lint;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::{Arc, OnceLock, RwLock};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How often each instance re-reads branding
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// Product name used until an admin sets one
+pub const DEFAULT_PRODUCT_NAME: &str = "Equipment Troubleshooting";
+const PRODUCT_NAME_MAX_LENGTH: usize = 100;
+const SUPPORT_EMAIL_MAX_LENGTH: usize = 255;
+const SUPPORT_PHONE_MAX_LENGTH: usize = 50;
+const MAX_FEATURES: usize = 50;
+const FEATURE_MAX_LENGTH: usize = 50;
+const MAX_LOCALES: usize = 20;
+
+/// Deployment branding as served to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct PublicConfig {
+    pub product_name: String,
+    /// Absolute http(s) URL or a path on this host (e.g. `/uploads/logo.svg`)
+    pub logo_url: Option<String>,
+    pub support_email: Option<String>,
+    pub support_phone: Option<String>,
+    pub support_url: Option<String>,
+    /// Frontend feature switches that are turned on
+    pub features: Vec<String>,
+    /// Locales the install offers, most preferred first
+    pub locales: Vec<String>,
+    pub default_locale: String,
+}
+
+impl Default for PublicConfig {
+    fn default() -> Self {
+        Self {
+            product_name: DEFAULT_PRODUCT_NAME.to_string(),
+            logo_url: None,
+            support_email: None,
+            support_phone: None,
+            support_url: None,
+            features: vec![],
+            locales: vec!["en".to_string()],
+            default_locale: "en".to_string(),
+        }
+    }
+}
+
+/// Branding with its change stamp, for the admin settings page
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct BrandingSettings {
+    #[serde(flatten)]
+    pub config: PublicConfig,
+    /// Null while the defaults are in use
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Branding changes; omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateBrandingRequest {
+    #[ts(optional)]
+    pub product_name: Option<String>,
+    /// Empty string clears the logo
+    #[ts(optional)]
+    pub logo_url: Option<String>,
+    /// Empty string clears the support email
+    #[ts(optional)]
+    pub support_email: Option<String>,
+    /// Empty string clears the support phone number
+    #[ts(optional)]
+    pub support_phone: Option<String>,
+    /// Empty string clears the support link
+    #[ts(optional)]
+    pub support_url: Option<String>,
+    #[ts(optional)]
+    pub features: Option<Vec<String>>,
+    #[ts(optional)]
+    pub locales: Option<Vec<String>>,
+    #[ts(optional)]
+    pub default_locale: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct BrandingRow {
+    #[sqlx(flatten)]
+    config: PublicConfig,
+    updated_at: DateTime<Utc>,
+}
+
+/// Branding in effect and when it was last changed
+type Cached = (Arc<PublicConfig>, Option<DateTime<Utc>>);
+
+static CURRENT: OnceLock<RwLock<Cached>> = OnceLock::new();
+
+fn slot() -> &'static RwLock<Cached> {
+    CURRENT.get_or_init(|| RwLock::new((Arc::new(PublicConfig::default()), None)))
+}
+
+/// Branding currently in effect
+pub fn current() -> Arc<PublicConfig> {
+    slot().read().unwrap_or_else(|e| e.into_inner()).0.clone()
+}
+
+/// Branding with its last change time
+pub fn settings() -> BrandingSettings {
+    let (config, updated_at) = slot().read().unwrap_or_else(|e| e.into_inner()).clone();
+    BrandingSettings { config: (*config).clone(), updated_at }
+}
+
+/// Re-read branding from the database (defaults when no row exists)
+pub async fn reload(db: &PgPool) -> Result<(), sqlx::Error> {
+    let row = sqlx::query_as::<_, BrandingRow>(
+        "SELECT product_name, logo_url, support_email, support_phone, support_url, features, locales, default_locale, updated_at
+         FROM branding",
+    )
+    .fetch_optional(db)
+    .await?;
+
+    *slot().write().unwrap_or_else(|e| e.into_inner()) = match row {
+        Some(row) => (Arc::new(row.config), Some(row.updated_at)),
+        None => (Arc::new(PublicConfig::default()), None),
+    };
+    Ok(())
+}
+
+/// Apply a change on top of the current branding and validate the result
+///
+/// Returns the field errors when the merged branding is invalid.
+pub fn merge(base: &PublicConfig, req: &UpdateBrandingRequest) -> Result<PublicConfig, Vec<(String, String)>> {
+    let optional = |value: &Option<String>, current: &Option<String>| match value {
+        Some(v) if v.trim().is_empty() => None,
+        Some(v) => Some(v.trim().to_string()),
+        None => current.clone(),
+    };
+
+    let config = PublicConfig {
+        product_name: req
+            .product_name
+            .as_deref()
+            .map(|n| n.trim().to_string())
+            .unwrap_or_else(|| base.product_name.clone()),
+        logo_url: optional(&req.logo_url, &base.logo_url),
+        support_email: optional(&req.support_email, &base.support_email),
+        support_phone: optional(&req.support_phone, &base.support_phone),
+        support_url: optional(&req.support_url, &base.support_url),
+        features: req
+            .features
+            .as_ref()
+            .map(|features| {
+                let mut features: Vec<String> = features.iter().map(|f| f.trim().to_string()).collect();
+                features.sort();
+                features.dedup();
+                features
+            })
+            .unwrap_or_else(|| base.features.clone()),
+        locales: req
+            .locales
+            .as_ref()
+            .map(|locales| {
+                let mut seen = Vec::new();
+                for locale in locales.iter().map(|l| l.trim().to_string()) {
+                    if !seen.contains(&locale) {
+                        seen.push(locale);
+                    }
+                }
+                seen
+            })
+            .unwrap_or_else(|| base.locales.clone()),
+        default_locale: req
+            .default_locale
+            .as_deref()
+            .map(|l| l.trim().to_string())
+            .unwrap_or_else(|| base.default_locale.clone()),
+    };
+
+    let errors = validate(&config);
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate(config: &PublicConfig) -> Vec<(String, String)> {
+    let mut errors = Vec::new();
+    let mut error = |field: &str, message: String| errors.push((field.to_string(), message));
+
+    let name_length = config.product_name.chars().count();
+    if name_length == 0 || name_length > PRODUCT_NAME_MAX_LENGTH {
+        error("product_name", format!("Must be 1-{} characters", PRODUCT_NAME_MAX_LENGTH));
+    }
+
+    if let Some(logo_url) = &config.logo_url {
+        let local_path = logo_url.starts_with('/') && !logo_url.starts_with("//");
+        if !local_path && !lint::is_http_url(logo_url) {
+            error("logo_url", "Must be an http or https URL or a path starting with /".to_string());
+        }
+    }
+
+    if let Some(email) = &config.support_email {
+        let valid = email.len() <= SUPPORT_EMAIL_MAX_LENGTH
+            && email.split_once('@').is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'))
+            && !email.chars().any(char::is_whitespace);
+        if !valid {
+            error("support_email", "Must be a valid email address".to_string());
+        }
+    }
+
+    if config.support_phone.as_ref().is_some_and(|p| p.chars().count() > SUPPORT_PHONE_MAX_LENGTH) {
+        error("support_phone", format!("Must be at most {} characters", SUPPORT_PHONE_MAX_LENGTH));
+    }
+
+    if config.support_url.as_deref().is_some_and(|u| !lint::is_http_url(u)) {
+        error("support_url", "Must be an http or https URL".to_string());
+    }
+
+    if config.features.len() > MAX_FEATURES {
+        error("features", format!("At most {} features", MAX_FEATURES));
+    }
+    if let Some(feature) = config.features.iter().find(|f| !is_feature_name(f)) {
+        error(
+            "features",
+            format!("'{}' must be 1-{} lowercase letters, digits or '_'", feature, FEATURE_MAX_LENGTH),
+        );
+    }
+
+    if config.locales.is_empty() || config.locales.len() > MAX_LOCALES {
+        error("locales", format!("Must list 1-{} locales", MAX_LOCALES));
+    }
+    if let Some(locale) = config.locales.iter().find(|l| !is_locale_tag(l)) {
+        error("locales", format!("'{}' is not a language tag such as 'en' or 'pt-BR'", locale));
+    }
+    if !config.locales.contains(&config.default_locale) {
+        error("default_locale", "Must be one of the locales".to_string());
+    }
+
+    errors
+}
+
+fn is_feature_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= FEATURE_MAX_LENGTH
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Language tag: a 2-3 letter language, then optional 2-8 character subtags
+fn is_locale_tag(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let language_ok = parts
+        .next()
+        .is_some_and(|l| (2..=3).contains(&l.len()) && l.chars().all(|c| c.is_ascii_alphabetic()));
+    language_ok && parts.all(|p| (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Store new branding and make it current on this instance
+pub async fn save(db: &PgPool, config: &PublicConfig, updated_by: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO branding (id, product_name, logo_url, support_email, support_phone, support_url, features, locales, default_locale, updated_by, updated_at)
+         VALUES (true, $1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+         ON CONFLICT (id) DO UPDATE SET
+            product_name = EXCLUDED.product_name,
+            logo_url = EXCLUDED.logo_url,
+            support_email = EXCLUDED.support_email,
+            support_phone = EXCLUDED.support_phone,
+            support_url = EXCLUDED.support_url,
+            features = EXCLUDED.features,
+            locales = EXCLUDED.locales,
+            default_locale = EXCLUDED.default_locale,
+            updated_by = EXCLUDED.updated_by,
+            updated_at = NOW()",
+    )
+    .bind(&config.product_name)
+    .bind(&config.logo_url)
+    .bind(&config.support_email)
+    .bind(&config.support_phone)
+    .bind(&config.support_url)
+    .bind(&config.features)
+    .bind(&config.locales)
+    .bind(&config.default_locale)
+    .bind(updated_by)
+    .execute(db)
+    .await?;
+
+    reload(db).await
+}
+
+/// Periodically re-read branding so every instance serves the same config
+pub fn spawn_refresh(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = reload(&db).await {
+                tracing::warn!("Failed to refresh branding: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_and_validate() {
+        let base = PublicConfig::default();
+        let merged = merge(
+            &base,
+            &UpdateBrandingRequest {
+                product_name: Some("  Acme Field Service ".to_string()),
+                logo_url: Some("/uploads/logo.svg".to_string()),
+                support_email: Some("help@acme.example".to_string()),
+                features: Some(vec!["resume_codes".to_string(), "resume_codes".to_string()]),
+                locales: Some(vec!["en".to_string(), "pt-BR".to_string()]),
+                default_locale: Some("pt-BR".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(merged.product_name, "Acme Field Service");
+        assert_eq!(merged.features, vec!["resume_codes"]);
+        assert_eq!(merged.default_locale, "pt-BR");
+
+        // Empty strings clear optional fields; omitted ones are kept
+        let cleared = merge(&merged, &UpdateBrandingRequest { logo_url: Some(String::new()), ..Default::default() }).unwrap();
+        assert_eq!(cleared.logo_url, None);
+        assert_eq!(cleared.support_email.as_deref(), Some("help@acme.example"));
+
+        let errors = merge(
+            &base,
+            &UpdateBrandingRequest {
+                product_name: Some(" ".to_string()),
+                logo_url: Some("//evil.example/logo.png".to_string()),
+                support_email: Some("nobody".to_string()),
+                features: Some(vec!["Dark Mode".to_string()]),
+                default_locale: Some("fr".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|(f, _)| f.as_str()).collect();
+        assert_eq!(fields, vec!["product_name", "logo_url", "support_email", "features", "default_locale"]);
+    }
+
+    #[test]
+    fn test_locale_tags() {
+        assert!(is_locale_tag("en"));
+        assert!(is_locale_tag("pt-BR"));
+        assert!(is_locale_tag("zh-Hant-TW"));
+        assert!(!is_locale_tag("english"));
+        assert!(!is_locale_tag("en_US"));
+        assert!(!is_locale_tag(""));
+    }
+}
@@ -0,0 +1,167 @@
+/// Cache warming for the busiest issue categories (optional)
+///
+/// When CACHE_WARMING_ENABLED=true, the questions and graph caches are filled
+/// on startup and again after bulk invalidations (imports, bulk toggles) for
+/// the categories with the most sessions in the recent window, so the first
+/// technicians after a deploy don't pay for cold caches.
+use crate::engine;
+use crate::routes::issues;
+use crate::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Categories warmed when CACHE_WARMING_CATEGORIES is not set
+const DEFAULT_TOP_CATEGORIES: i64 = 10;
+/// Session window used to rank categories when CACHE_WARMING_WINDOW_DAYS is not set
+const DEFAULT_WINDOW_DAYS: i32 = 7;
+/// Never warm more graphs than the graph cache holds
+const MAX_TOP_CATEGORIES: i64 = 50;
+
+/// Cache warming settings (from environment)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmingConfig {
+    pub enabled: bool,
+    pub top_categories: i64,
+    pub window_days: i32,
+}
+
+impl WarmingConfig {
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("CACHE_WARMING_ENABLED")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+        let top_categories = std::env::var("CACHE_WARMING_CATEGORIES")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_TOP_CATEGORIES)
+            .clamp(1, MAX_TOP_CATEGORIES);
+        let window_days = std::env::var("CACHE_WARMING_WINDOW_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|d| *d > 0)
+            .unwrap_or(DEFAULT_WINDOW_DAYS);
+
+        Self {
+            enabled,
+            top_categories,
+            window_days,
+        }
+    }
+}
+
+/// Process-wide warming settings
+pub fn config() -> &'static WarmingConfig {
+    static CONFIG: OnceLock<WarmingConfig> = OnceLock::new();
+    CONFIG.get_or_init(WarmingConfig::from_env)
+}
+
+/// Outcome of one warming pass
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WarmingReport {
+    pub categories: Vec<String>,
+    pub graphs_warmed: usize,
+    pub questions_warmed: usize,
+}
+
+/// Active categories ranked by sessions started in the last `window_days`
+pub async fn top_categories(state: &AppState, limit: i64, window_days: i32) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT (steps->0->>'category')::text AS category
+         FROM sessions
+         WHERE started_at > NOW() - make_interval(days => $1)
+           AND jsonb_array_length(steps) > 0
+           AND steps->0->>'category' IS NOT NULL
+           AND EXISTS (
+               SELECT 1 FROM nodes
+               WHERE nodes.category = steps->0->>'category' AND nodes.is_active = true
+           )
+         GROUP BY 1
+         ORDER BY COUNT(*) DESC, 1 ASC
+         LIMIT $2"
+    )
+    .bind(window_days)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+}
+
+/// Fill the questions and graph caches for the busiest categories
+///
+/// The global start question is always warmed. Categories that fail to load
+/// are logged and skipped; warming never fails a request.
+pub async fn warm(state: &AppState, config: &WarmingConfig) -> Result<WarmingReport, sqlx::Error> {
+    let categories = top_categories(state, config.top_categories, config.window_days).await?;
+    let mut report = WarmingReport::default();
+
+    match engine::load_start_question(state, "start").await {
+        Ok(Some(_)) => report.questions_warmed += 1,
+        Ok(None) => {}
+        Err(e) => tracing::warn!("⚠️  Cache warming: global start question failed: {:?}", e),
+    }
+
+    for category in &categories {
+        match engine::load_start_question(state, &format!("{}_start", category)).await {
+            Ok(Some(_)) => report.questions_warmed += 1,
+            Ok(None) => {}
+            Err(e) => tracing::warn!("⚠️  Cache warming: start question for {} failed: {:?}", category, e),
+        }
+
+        match issues::load_issue_graph(state, category).await {
+            Ok(_) => report.graphs_warmed += 1,
+            Err(e) => tracing::warn!("⚠️  Cache warming: graph for {} failed: {:?}", category, e),
+        }
+    }
+
+    report.categories = categories;
+    Ok(report)
+}
+
+/// Warm caches in the background if warming is enabled
+///
+/// Overlapping requests (e.g. several imports in a row) are collapsed: while
+/// a pass is running, further calls are ignored.
+pub fn schedule(state: AppState, reason: &'static str) {
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+
+    let config = *config();
+    if !config.enabled || RUNNING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let started = Instant::now();
+        match warm(&state, &config).await {
+            Ok(report) => tracing::info!(
+                "🔥 Cache warmed after {}: {} graphs, {} start questions in {}ms",
+                reason,
+                report.graphs_warmed,
+                report.questions_warmed,
+                started.elapsed().as_millis()
+            ),
+            Err(e) => tracing::warn!("⚠️  Cache warming after {} failed: {}", reason, e),
+        }
+        RUNNING.store(false, Ordering::Release);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults() {
+        std::env::remove_var("CACHE_WARMING_ENABLED");
+        std::env::remove_var("CACHE_WARMING_CATEGORIES");
+        std::env::remove_var("CACHE_WARMING_WINDOW_DAYS");
+
+        assert_eq!(
+            WarmingConfig::from_env(),
+            WarmingConfig {
+                enabled: false,
+                top_categories: DEFAULT_TOP_CATEGORIES,
+                window_days: DEFAULT_WINDOW_DAYS,
+            }
+        );
+    }
+}
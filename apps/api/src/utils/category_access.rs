@@ -0,0 +1,190 @@
+/// Per-user category edit restrictions
+///
+/// Maintenance teams own different equipment lines. A user with no rows in
+/// `user_category_access` may edit every category their permissions allow;
+/// a user with rows may only edit those categories. Reads are not affected.
+/// A connection belongs to the category of the node it starts from, and an
+/// issue's draft to the issue. Editors may only edit drafts, which go live
+/// once someone with `issues:approve` approves them.
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::models::UserRole;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Categories a user is restricted to, or None when unrestricted
+pub async fn editable_categories(db: &PgPool, user_id: Uuid) -> Result<Option<Vec<String>>, sqlx::Error> {
+    let categories = sqlx::query_scalar::<_, String>(
+        "SELECT category FROM user_category_access WHERE user_id = $1 ORDER BY category",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok((!categories.is_empty()).then_some(categories))
+}
+
+/// True when a restriction list allows editing the category
+pub fn is_allowed(restriction: Option<&[String]>, category: &str) -> bool {
+    restriction.is_none_or(|categories| categories.iter().any(|c| c == category))
+}
+
+/// Categories from `categories` the user may not edit (deduplicated, in input order)
+pub async fn denied_categories<'a>(
+    db: &PgPool,
+    auth: &AuthUser,
+    categories: impl IntoIterator<Item = &'a str>,
+) -> ApiResult<Vec<&'a str>> {
+    Ok(denials(db, auth, categories, false).await?.into_iter().map(|(category, _)| category).collect())
+}
+
+/// Denied categories, each flagged when only an editor's draft-only rule refused it
+async fn denials<'a>(
+    db: &PgPool,
+    auth: &AuthUser,
+    categories: impl IntoIterator<Item = &'a str>,
+    drafting: bool,
+) -> ApiResult<Vec<(&'a str, bool)>> {
+    let user_id = Uuid::parse_str(&auth.0.sub).map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let restriction = editable_categories(db, user_id).await?;
+    let categories: Vec<&str> = categories.into_iter().collect();
+    // Editors change published issues only through drafts submitted for review
+    let drafts_only = auth.0.role == UserRole::Editor && !drafting;
+
+    // A draft may be edited by whoever may edit the issue it belongs to
+    let drafts: HashMap<String, String> = if restriction.is_some() || drafts_only {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT draft_category, category FROM issue_drafts WHERE draft_category = ANY($1)",
+        )
+        .bind(&categories)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut denied: Vec<(&str, bool)> = Vec::new();
+    for category in categories {
+        let draft_owner = drafts.get(category);
+        let owner = draft_owner.map_or(category, String::as_str);
+        if denied.iter().any(|(c, _)| *c == category) {
+            continue;
+        }
+        if !is_allowed(restriction.as_deref(), owner) {
+            denied.push((category, false));
+        } else if drafts_only && draft_owner.is_none() {
+            denied.push((category, true));
+        }
+    }
+    Ok(denied)
+}
+
+/// 403 naming the denied categories
+fn forbidden(denied: &[(&str, bool)]) -> ApiError {
+    let names = denied.iter().map(|(c, _)| format!("'{}'", c)).collect::<Vec<_>>().join(", ");
+    if denied.iter().all(|(_, needs_draft)| *needs_draft) {
+        ApiError::forbidden(format!(
+            "Editors can't change category {} directly; edit its draft and submit it for approval",
+            names
+        ))
+    } else {
+        ApiError::forbidden(format!("You are not allowed to edit category {}", names))
+    }
+}
+
+/// Fail with 403 unless the user may edit every listed category
+///
+/// Editors may only edit drafts; see `ensure_can_draft`.
+pub async fn ensure_can_edit<'a>(
+    db: &PgPool,
+    auth: &AuthUser,
+    categories: impl IntoIterator<Item = &'a str>,
+) -> ApiResult<()> {
+    let denied = denials(db, auth, categories, false).await?;
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(forbidden(&denied))
+    }
+}
+
+/// Fail with 403 unless the user may work on a draft of the issue: start,
+/// discard or submit it for review (editors included)
+pub async fn ensure_can_draft(db: &PgPool, auth: &AuthUser, category: &str) -> ApiResult<()> {
+    let denied = denials(db, auth, [category], true).await?;
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(forbidden(&denied))
+    }
+}
+
+/// Fail with 403 unless the user may edit the node's category (404 if the node is missing)
+pub async fn ensure_can_edit_node(db: &PgPool, auth: &AuthUser, node_id: Uuid) -> ApiResult<()> {
+    let category = sqlx::query_scalar::<_, String>("SELECT category FROM nodes WHERE id = $1")
+        .bind(node_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Node not found"))?;
+
+    ensure_can_edit(db, auth, [category.as_str()]).await
+}
+
+/// Fail with 403 unless the user may edit the category the connection starts in
+pub async fn ensure_can_edit_connection(db: &PgPool, auth: &AuthUser, connection_id: Uuid) -> ApiResult<()> {
+    let category = sqlx::query_scalar::<_, String>(
+        "SELECT n.category FROM connections c JOIN nodes n ON n.id = c.from_node_id WHERE c.id = $1",
+    )
+    .bind(connection_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Connection not found"))?;
+
+    ensure_can_edit(db, auth, [category.as_str()]).await
+}
+
+/// Replace a user's restriction list (empty removes the restriction)
+pub async fn set_editable_categories(
+    db: &PgPool,
+    user_id: Uuid,
+    categories: &[String],
+    granted_by: Uuid,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM user_category_access WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO user_category_access (user_id, category, granted_by)
+         SELECT $1, category, $3 FROM unnest($2::varchar[]) AS category
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(categories)
+    .bind(granted_by)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_allowed() {
+        let restriction = vec!["printer".to_string(), "scanner".to_string()];
+
+        assert!(is_allowed(None, "printer"));
+        assert!(is_allowed(Some(&restriction), "scanner"));
+        assert!(!is_allowed(Some(&restriction), "copier"));
+        assert!(!is_allowed(Some(&restriction), "Printer"));
+    }
+}
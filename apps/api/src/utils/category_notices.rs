@@ -0,0 +1,149 @@
+/// Time-boxed category notices
+///
+/// Admins can put a temporary message on a category ("Known firmware bug this
+/// week — skip to the controller reset"), optionally pointing at a
+/// conclusion. While a notice is active every session response at a node in
+/// that category carries it; outside its window it simply isn't returned, so
+/// notices expire without anyone cleaning them up.
+///
+/// Notices that haven't ended are kept in memory so session responses never
+/// query for them; admin changes reload immediately and a background refresh
+/// picks up other instances.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Maximum length of a notice message
+pub const MESSAGE_MAX_LENGTH: usize = 1000;
+
+/// How often notices are re-read
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A temporary message on a category
+#[derive(Debug, Clone, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CategoryNotice {
+    pub id: Uuid,
+    pub category: String,
+    pub message: String,
+    /// Conclusion the notice suggests skipping to
+    pub conclusion_node_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CategoryNotice {
+    /// Whether the notice shows at this moment
+    pub fn is_active_at(&self, at: DateTime<Utc>) -> bool {
+        self.starts_at <= at && at < self.ends_at
+    }
+}
+
+pub const NOTICE_COLUMNS: &str =
+    "id, category, message, conclusion_node_id, starts_at, ends_at, created_at, updated_at";
+
+static NOTICES: OnceLock<RwLock<Vec<CategoryNotice>>> = OnceLock::new();
+
+fn notices() -> &'static RwLock<Vec<CategoryNotice>> {
+    NOTICES.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Check a notice's message and window
+pub fn validate(message: &str, starts_at: DateTime<Utc>, ends_at: DateTime<Utc>) -> Vec<(String, String)> {
+    let mut errors = Vec::new();
+    if message.trim().is_empty() || message.trim().chars().count() > MESSAGE_MAX_LENGTH {
+        errors.push(("message".to_string(), format!("Must be 1-{} characters", MESSAGE_MAX_LENGTH)));
+    }
+    if ends_at <= starts_at {
+        errors.push(("ends_at".to_string(), "Must be after starts_at".to_string()));
+    }
+    errors
+}
+
+/// Notices currently showing for a category, soonest-ending first
+pub fn active(category: &str) -> Vec<CategoryNotice> {
+    let now = Utc::now();
+    notices()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|n| n.category == category && n.is_active_at(now))
+        .cloned()
+        .collect()
+}
+
+/// Re-read notices that haven't ended yet
+pub async fn reload(db: &PgPool) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query_as::<_, CategoryNotice>(&format!(
+        "SELECT {} FROM category_notices WHERE ends_at > NOW() ORDER BY ends_at, created_at",
+        NOTICE_COLUMNS
+    ))
+    .fetch_all(db)
+    .await?;
+
+    *notices().write().unwrap_or_else(|e| e.into_inner()) = rows;
+    Ok(())
+}
+
+/// Periodically re-read notices so every instance shows the same ones
+pub fn spawn_refresh(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = reload(&db).await {
+                tracing::warn!("Failed to refresh category notices: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn notice(category: &str, starts_at: DateTime<Utc>, ends_at: DateTime<Utc>) -> CategoryNotice {
+        CategoryNotice {
+            id: Uuid::new_v4(),
+            category: category.to_string(),
+            message: "Known firmware bug".to_string(),
+            conclusion_node_id: None,
+            starts_at,
+            ends_at,
+            created_at: starts_at,
+            updated_at: starts_at,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let now = Utc::now();
+        assert!(validate("Skip to the reset", now, now + Duration::hours(1)).is_empty());
+        assert_eq!(validate("  ", now, now + Duration::hours(1))[0].0, "message");
+        assert_eq!(validate(&"x".repeat(MESSAGE_MAX_LENGTH + 1), now, now + Duration::hours(1)).len(), 1);
+        assert_eq!(validate("Skip", now, now)[0].0, "ends_at");
+    }
+
+    #[test]
+    fn test_active_window() {
+        let now = Utc::now();
+        let current = notice("notice-test", now - Duration::hours(1), now + Duration::hours(1));
+        let upcoming = notice("notice-test", now + Duration::hours(1), now + Duration::hours(2));
+        let ended = notice("notice-test", now - Duration::hours(2), now - Duration::seconds(1));
+        let other = notice("notice-other", now - Duration::hours(1), now + Duration::hours(1));
+        assert!(current.is_active_at(now));
+        assert!(!current.is_active_at(current.ends_at));
+
+        notices().write().unwrap().extend([current.clone(), upcoming, ended, other]);
+        let shown: Vec<Uuid> = active("notice-test").iter().map(|n| n.id).collect();
+        assert_eq!(shown, vec![current.id]);
+    }
+}
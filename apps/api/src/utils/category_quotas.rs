@@ -0,0 +1,141 @@
+/// Per-category daily session quotas
+///
+/// Categories that lead to expensive field dispatches can be given a daily
+/// limit on new sessions, with an optional warning threshold below it. Each
+/// start is counted against the category's row for the current UTC day in
+/// `category_quota_usage`, and only while the count is under the limit, so
+/// the limit holds across instances. Sessions refused once it's reached are
+/// counted there too. Past the warning threshold sessions still start; the
+/// crossing is logged and shows in stats.
+///
+/// The quota applies to the category a session is started on (directly or
+/// through an entry point); sessions that reach it from the global start
+/// question aren't stopped midway.
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use ts_rs::TS;
+
+/// Largest daily limit that can be set
+pub const DAILY_LIMIT_MAX: i32 = 1_000_000;
+
+/// A category's quota with today's use
+#[derive(Debug, Clone, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct CategoryQuota {
+    pub category: String,
+    /// New sessions allowed per UTC day
+    pub daily_limit: i32,
+    /// Sessions per day after which ops are warned
+    pub warn_at: Option<i32>,
+    /// Sessions started today
+    pub sessions_today: i32,
+    /// Sessions refused today because the limit was reached
+    pub rejected_today: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of counting a new session against its category's quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaClaim {
+    /// The category has no quota
+    Unlimited,
+    /// Counted; `warn` is set for the session that reaches the warning threshold
+    Allowed { sessions: i32, daily_limit: i32, warn: bool },
+    /// The limit for today has been reached
+    Refused { daily_limit: i32 },
+}
+
+/// Check a quota's limit and warning threshold
+pub fn validate(daily_limit: i32, warn_at: Option<i32>) -> Vec<(String, String)> {
+    let mut errors = Vec::new();
+    if !(1..=DAILY_LIMIT_MAX).contains(&daily_limit) {
+        errors.push(("daily_limit".to_string(), format!("Must be between 1 and {}", DAILY_LIMIT_MAX)));
+    }
+    if warn_at.is_some_and(|warn_at| warn_at < 1 || warn_at > daily_limit) {
+        errors.push(("warn_at".to_string(), "Must be between 1 and the daily limit".to_string()));
+    }
+    errors
+}
+
+/// Seconds until quotas reset at the next UTC midnight
+pub fn seconds_until_reset(now: DateTime<Utc>) -> u64 {
+    let midnight = (now.date_naive() + Duration::days(1)).and_time(chrono::NaiveTime::MIN).and_utc();
+    (midnight - now).num_seconds().max(1) as u64
+}
+
+/// Count a new session against `category`'s quota for today
+pub async fn claim(db: &PgPool, category: &str) -> Result<QuotaClaim, sqlx::Error> {
+    let quota = sqlx::query_as::<_, (i32, Option<i32>)>("SELECT daily_limit, warn_at FROM category_quotas WHERE category = $1")
+        .bind(category)
+        .fetch_optional(db)
+        .await?;
+    let Some((daily_limit, warn_at)) = quota else {
+        return Ok(QuotaClaim::Unlimited);
+    };
+
+    let sessions = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO category_quota_usage (category, day, sessions)
+         VALUES ($1, (NOW() AT TIME ZONE 'UTC')::date, 1)
+         ON CONFLICT (category, day) DO UPDATE SET sessions = category_quota_usage.sessions + 1
+         WHERE category_quota_usage.sessions < $2
+         RETURNING sessions",
+    )
+    .bind(category)
+    .bind(daily_limit)
+    .fetch_optional(db)
+    .await?;
+
+    match sessions {
+        Some(sessions) => Ok(QuotaClaim::Allowed { sessions, daily_limit, warn: warn_at == Some(sessions) }),
+        None => {
+            sqlx::query(
+                "UPDATE category_quota_usage SET rejected = rejected + 1
+                 WHERE category = $1 AND day = (NOW() AT TIME ZONE 'UTC')::date",
+            )
+            .bind(category)
+            .execute(db)
+            .await?;
+            Ok(QuotaClaim::Refused { daily_limit })
+        }
+    }
+}
+
+/// Quotas with today's use, for one category or all of them
+pub async fn list(db: &PgPool, category: Option<&str>) -> Result<Vec<CategoryQuota>, sqlx::Error> {
+    sqlx::query_as::<_, CategoryQuota>(
+        "SELECT q.category, q.daily_limit, q.warn_at,
+                COALESCE(u.sessions, 0) AS sessions_today, COALESCE(u.rejected, 0) AS rejected_today,
+                q.updated_at
+         FROM category_quotas q
+         LEFT JOIN category_quota_usage u ON u.category = q.category AND u.day = (NOW() AT TIME ZONE 'UTC')::date
+         WHERE $1::text IS NULL OR q.category = $1
+         ORDER BY q.category",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(50, Some(40)).is_empty());
+        assert!(validate(50, None).is_empty());
+        assert_eq!(validate(0, None)[0].0, "daily_limit");
+        assert_eq!(validate(50, Some(51))[0].0, "warn_at");
+        assert_eq!(validate(50, Some(0))[0].0, "warn_at");
+    }
+
+    #[test]
+    fn test_seconds_until_reset() {
+        let evening = Utc.with_ymd_and_hms(2025, 10, 24, 23, 0, 0).unwrap();
+        assert_eq!(seconds_until_reset(evening), 3600);
+        let midnight = Utc.with_ymd_and_hms(2025, 10, 24, 0, 0, 0).unwrap();
+        assert_eq!(seconds_until_reset(midnight), 86_400);
+    }
+}
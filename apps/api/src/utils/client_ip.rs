@@ -0,0 +1,179 @@
+/// Client IP resolution behind trusted proxies
+///
+/// Forwarded headers (X-Forwarded-For, X-Real-IP, X-Forwarded-Proto) are only
+/// honored when the TCP peer is in TRUSTED_PROXIES. Anyone else could set them
+/// to dodge rate limits or forge audit log IPs.
+use axum::http::HeaderMap;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+/// Proxies trusted when TRUSTED_PROXIES is not set (loopback only)
+const DEFAULT_TRUSTED_PROXIES: &str = "127.0.0.1/32,::1/128";
+
+/// An IP network in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Parse "10.0.0.0/8", "fd00::/8" or a bare address (treated as a single host)
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (value.parse::<IpAddr>().ok()?, None),
+        };
+
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max_prefix);
+        if prefix > max_prefix {
+            return None;
+        }
+
+        Some(Self { network: addr, prefix })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(*ip) & mask
+            }
+            // IPv4-mapped IPv6 peers (::ffff:a.b.c.d) match IPv4 ranges
+            (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+                .to_ipv4_mapped()
+                .map(|v4| self.contains(&IpAddr::V4(v4)))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+/// Set of trusted proxy networks
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<Cidr>,
+}
+
+impl TrustedProxies {
+    /// Parse a comma-separated CIDR list, skipping (and logging) invalid entries
+    pub fn parse(list: &str) -> Self {
+        let networks = list
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let cidr = Cidr::parse(entry);
+                if cidr.is_none() {
+                    tracing::warn!("⚠️  Ignoring invalid TRUSTED_PROXIES entry: {}", entry);
+                }
+                cidr
+            })
+            .collect();
+
+        Self { networks }
+    }
+
+    pub fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(ip))
+    }
+}
+
+/// Process-wide trusted proxies (from TRUSTED_PROXIES)
+pub fn trusted_proxies() -> &'static TrustedProxies {
+    static TRUSTED: OnceLock<TrustedProxies> = OnceLock::new();
+    TRUSTED.get_or_init(|| {
+        let list = std::env::var("TRUSTED_PROXIES")
+            .unwrap_or_else(|_| DEFAULT_TRUSTED_PROXIES.to_string());
+        TrustedProxies::parse(&list)
+    })
+}
+
+/// Determine the real client IP for a request
+///
+/// Forwarded headers are ignored unless `peer` is trusted. X-Forwarded-For is
+/// walked right to left, skipping trusted hops, so a client cannot prepend a
+/// fake address. Without a known peer (e.g. in tests) headers are not trusted.
+pub fn resolve_client_ip(peer: Option<IpAddr>, headers: &HeaderMap, trusted: &TrustedProxies) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted.is_trusted(&peer) {
+        return Some(peer);
+    }
+
+    if let Some(forwarded_for) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
+        let hops: Vec<IpAddr> = forwarded_for
+            .split(',')
+            .filter_map(|hop| hop.trim().parse::<IpAddr>().ok())
+            .collect();
+
+        if let Some(client) = hops.iter().rev().find(|ip| !trusted.is_trusted(ip)) {
+            return Some(*client);
+        }
+        // Every hop is one of our proxies; the left-most is the closest we get to the client
+        if let Some(first) = hops.first() {
+            return Some(*first);
+        }
+    }
+
+    if let Some(real_ip) = headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<IpAddr>().ok())
+    {
+        return Some(real_ip);
+    }
+
+    Some(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let net = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains(&ip("10.1.2.3")));
+        assert!(!net.contains(&ip("11.0.0.1")));
+        assert!(net.contains(&ip("::ffff:10.0.0.1")));
+
+        let v6 = Cidr::parse("fd00::/8").unwrap();
+        assert!(v6.contains(&ip("fd12::1")));
+        assert!(!v6.contains(&ip("fe80::1")));
+
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains(&ip("8.8.8.8")));
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+        assert!(Cidr::parse("not-an-ip").is_none());
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_forwarded_headers() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8");
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.2.3.4".parse().unwrap());
+
+        let resolved = resolve_client_ip(Some(ip("203.0.113.9")), &headers, &trusted);
+        assert_eq!(resolved, Some(ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn test_trusted_peer_skips_trusted_hops() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8");
+        let mut headers = HeaderMap::new();
+        // Client tried to spoof 1.1.1.1; the real client is 198.51.100.7
+        headers.insert("x-forwarded-for", "1.1.1.1, 198.51.100.7, 10.0.0.2".parse().unwrap());
+
+        let resolved = resolve_client_ip(Some(ip("10.0.0.1")), &headers, &trusted);
+        assert_eq!(resolved, Some(ip("198.51.100.7")));
+    }
+}
@@ -0,0 +1,175 @@
+/// Conditions on connections
+///
+/// A condition is an expression over the session's variables (earlier answers
+/// by semantic ID, captured values). Conditions using the reading (`value`)
+/// make the question a measurement question; others hide their answer unless
+/// they hold.
+use crate::utils::expression::{Expr, Value, Variables, IMPLICIT_VARIABLE};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed connection condition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    expr: Expr,
+}
+
+impl Condition {
+    /// Whether the condition holds for these session variables
+    pub fn matches(&self, variables: &Variables) -> bool {
+        self.expr.eval(variables)
+    }
+
+    /// Whether the condition looks at a measurement reading
+    pub fn uses_reading(&self) -> bool {
+        self.expr.variables().contains(IMPLICIT_VARIABLE)
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self { expr: s.parse()? })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+/// Validate and canonicalize a condition for storage
+///
+/// Blank input clears the condition.
+pub fn normalize(condition: &str) -> Result<Option<String>, String> {
+    if condition.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(condition.parse::<Condition>()?.to_string()))
+}
+
+/// Whether a stored condition is a reading range
+pub fn uses_reading(condition: Option<&str>) -> bool {
+    condition
+        .and_then(|c| c.parse::<Condition>().ok())
+        .is_some_and(|c| c.uses_reading())
+}
+
+/// Whether an answer is offered given the session so far
+///
+/// Reading ranges are always offered (the reading decides between them);
+/// other conditions must hold. Stored conditions that no longer parse hide
+/// their answer.
+pub fn available(condition: Option<&str>, variables: &Variables) -> bool {
+    match condition.map(str::parse::<Condition>) {
+        None => true,
+        Some(Ok(condition)) => condition.uses_reading() || condition.matches(variables),
+        Some(Err(_)) => false,
+    }
+}
+
+/// Pick the connection a reading routes to
+///
+/// `options` are a node's active connections in display order. The first
+/// connection whose condition matches the reading and session variables wins;
+/// otherwise the first connection without a condition is the fallback.
+/// Stored conditions that no longer parse never match.
+pub fn route<T: Copy>(options: &[(T, Option<String>)], variables: &Variables, value: f64) -> Option<T> {
+    let mut variables = variables.clone();
+    variables.insert(IMPLICIT_VARIABLE.to_string(), Value::Number(value));
+
+    options
+        .iter()
+        .find(|(_, condition)| {
+            condition
+                .as_deref()
+                .and_then(|c| c.parse::<Condition>().ok())
+                .is_some_and(|c| c.matches(&variables))
+        })
+        .or_else(|| options.iter().find(|(_, condition)| condition.is_none()))
+        .map(|(id, _)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(value: f64) -> Variables {
+        Variables::from([(IMPLICIT_VARIABLE.to_string(), Value::Number(value))])
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        let c: Condition = ">= 11.5 and < 13.8".parse().unwrap();
+        assert!(c.uses_reading());
+        assert!(c.matches(&reading(11.5)));
+        assert!(c.matches(&reading(12.0)));
+        assert!(!c.matches(&reading(13.8)));
+        assert!(!c.matches(&reading(-1.0)));
+
+        let c: Condition = "<=0&&!=-5".parse().unwrap();
+        assert!(c.matches(&reading(0.0)));
+        assert!(!c.matches(&reading(-5.0)));
+
+        assert!("between 1 and 2".parse::<Condition>().is_err());
+        assert!("> abc".parse::<Condition>().is_err());
+        assert!("> 1 and".parse::<Condition>().is_err());
+        assert!("> inf".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(" >=11.50 AND <13.8 ").unwrap().as_deref(), Some(">= 11.5 and < 13.8"));
+        assert_eq!(normalize("==0").unwrap().as_deref(), Some("= 0"));
+        assert_eq!(normalize("  ").unwrap(), None);
+        assert!(normalize("12").is_err());
+        assert_eq!(
+            normalize("pump_start=='No' && > 12").unwrap().as_deref(),
+            Some("pump_start = \"No\" and > 12")
+        );
+    }
+
+    #[test]
+    fn test_available() {
+        let variables = Variables::from([("pump_start".to_string(), Value::Text("No".to_string()))]);
+        assert!(available(None, &variables));
+        assert!(available(Some("pump_start = 'no'"), &variables));
+        assert!(!available(Some("pump_start = 'Yes'"), &variables));
+        assert!(!available(Some("breaker = 'Tripped'"), &variables));
+        // Reading ranges are settled by the reading, not up front
+        assert!(available(Some("> 12"), &variables));
+        assert!(!available(Some("not a condition ("), &variables));
+
+        assert!(uses_reading(Some("pump_start = 'No' and value > 3")));
+        assert!(!uses_reading(Some("pump_start = 'No'")));
+        assert!(!uses_reading(None));
+    }
+
+    #[test]
+    fn test_route() {
+        let options = vec![
+            (1, Some("< 11.5".to_string())),
+            (2, Some(">= 11.5 and < 13.8".to_string())),
+            (3, None),
+        ];
+        let none = Variables::new();
+        assert_eq!(route(&options, &none, 10.0), Some(1));
+        assert_eq!(route(&options, &none, 12.6), Some(2));
+        assert_eq!(route(&options, &none, 14.2), Some(3));
+
+        // Without a fallback an uncovered reading has nowhere to go
+        assert_eq!(route(&options[..2], &none, 14.2), None);
+
+        // Earlier answers can narrow a range
+        let options = vec![
+            (1, Some("charger = 'Yes' and < 13".to_string())),
+            (2, Some("< 12".to_string())),
+            (3, None),
+        ];
+        let charging = Variables::from([("charger".to_string(), Value::Text("Yes".to_string()))]);
+        assert_eq!(route(&options, &charging, 12.5), Some(1));
+        assert_eq!(route(&options, &none, 12.5), Some(3));
+    }
+}
@@ -0,0 +1,252 @@
+/// Rich node content (markdown, pictures, further reading)
+///
+/// Content is sanitized before it is stored: raw HTML is dropped and links or
+/// images that aren't http(s), `mailto:` or a site path lose their destination.
+use crate::error::{ApiError, ApiResult};
+use crate::models::{ContentImage, ContentLink, NodeContent};
+use crate::utils::lint;
+
+/// Longest accepted markdown body, in characters
+pub const MARKDOWN_MAX_LENGTH: usize = 20_000;
+/// Most images or links one node can carry
+pub const MAX_ENTRIES: usize = 20;
+/// Longest alt text, caption or link label, in characters
+pub const LABEL_MAX_LENGTH: usize = 500;
+
+/// Whether a link destination is safe to hand to a browser
+fn is_safe_link(url: &str) -> bool {
+    let url = url.trim();
+    if url.starts_with('#') || (url.starts_with('/') && !url.starts_with("//")) {
+        return !url.chars().any(char::is_whitespace);
+    }
+    lint::is_http_url(url) || (url.starts_with("mailto:") && url.len() > "mailto:".len() && !url.contains(char::is_whitespace))
+}
+
+/// Whether an image source is an http(s) URL or a path on this site (e.g. an upload)
+fn is_image_source(url: &str) -> bool {
+    let url = url.trim();
+    lint::is_http_url(url) || (url.starts_with('/') && !url.starts_with("//") && !url.contains(char::is_whitespace))
+}
+
+/// Drop raw HTML tags and comments, keeping safe autolinks
+fn strip_html(markdown: &str) -> String {
+    let mut cleaned = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find('<') {
+        cleaned.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        if let Some(comment) = after.strip_prefix("!--") {
+            // An unterminated comment hides the rest of the text from renderers too
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let is_tag = after.starts_with(|c: char| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        match after.find('>') {
+            Some(end) if is_tag => {
+                let inner = &after[..end];
+                if inner.contains(':') && is_safe_link(inner) {
+                    cleaned.push('<');
+                    cleaned.push_str(inner);
+                    cleaned.push('>');
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                cleaned.push_str("&lt;");
+                rest = after;
+            }
+        }
+    }
+
+    cleaned.push_str(rest);
+    cleaned
+}
+
+/// Byte index of the `)` closing a link destination, allowing balanced parentheses inside
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Remove the destination of inline links and images that aren't safe
+fn strip_unsafe_destinations(markdown: &str) -> String {
+    let mut cleaned = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find("](") {
+        cleaned.push_str(&rest[..start + 1]);
+        let after = &rest[start + 2..];
+        let Some(end) = closing_paren(after) else {
+            cleaned.push('(');
+            rest = after;
+            continue;
+        };
+
+        // `(url "title")` or `(<url>)`
+        let target = after[..end].split_whitespace().next().unwrap_or("");
+        let target = target.trim_start_matches('<').trim_end_matches('>');
+        if is_safe_link(target) {
+            cleaned.push('(');
+            cleaned.push_str(&after[..=end]);
+        }
+        rest = &after[end + 1..];
+    }
+
+    cleaned.push_str(rest);
+    cleaned
+}
+
+/// Clean markdown for storage: control characters, raw HTML and unsafe destinations removed
+pub fn sanitize_markdown(markdown: &str) -> String {
+    let markdown: String = markdown
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect();
+    strip_unsafe_destinations(&strip_html(&markdown)).trim().to_string()
+}
+
+fn check_label(errors: &mut Vec<(String, String)>, pointer: String, label: &str) {
+    if label.chars().count() > LABEL_MAX_LENGTH {
+        errors.push((pointer, format!("Must be at most {} characters", LABEL_MAX_LENGTH)));
+    }
+}
+
+/// Sanitize content for storage
+///
+/// Errors are `(pointer, message)` pairs relative to the content object
+/// (`/images/0/url`) so callers can place them in their own payload. Empty
+/// content comes back as `None`.
+pub fn sanitize(content: NodeContent) -> Result<Option<NodeContent>, Vec<(String, String)>> {
+    let mut errors = Vec::new();
+
+    let markdown = sanitize_markdown(&content.markdown);
+    if markdown.chars().count() > MARKDOWN_MAX_LENGTH {
+        errors.push(("/markdown".to_string(), format!("Must be at most {} characters", MARKDOWN_MAX_LENGTH)));
+    }
+
+    if content.images.len() > MAX_ENTRIES {
+        errors.push(("/images".to_string(), format!("At most {} images are allowed", MAX_ENTRIES)));
+    }
+    let images: Vec<ContentImage> = content
+        .images
+        .into_iter()
+        .enumerate()
+        .map(|(index, image)| {
+            let url = image.url.trim().to_string();
+            if !is_image_source(&url) {
+                errors.push((format!("/images/{}/url", index), "Must be an http(s) URL or a path on this site".to_string()));
+            }
+            let alt = image.alt.trim().to_string();
+            check_label(&mut errors, format!("/images/{}/alt", index), &alt);
+            let caption = image.caption.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+            if let Some(caption) = &caption {
+                check_label(&mut errors, format!("/images/{}/caption", index), caption);
+            }
+            ContentImage { url, alt, caption }
+        })
+        .collect();
+
+    if content.links.len() > MAX_ENTRIES {
+        errors.push(("/links".to_string(), format!("At most {} links are allowed", MAX_ENTRIES)));
+    }
+    let links: Vec<ContentLink> = content
+        .links
+        .into_iter()
+        .enumerate()
+        .map(|(index, link)| {
+            let url = link.url.trim().to_string();
+            if !lint::is_http_url(&url) {
+                errors.push((format!("/links/{}/url", index), "Must be an http or https URL".to_string()));
+            }
+            let label = link.label.trim().to_string();
+            if label.is_empty() {
+                errors.push((format!("/links/{}/label", index), "Link label is required".to_string()));
+            }
+            check_label(&mut errors, format!("/links/{}/label", index), &label);
+            ContentLink { url, label }
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if markdown.is_empty() && images.is_empty() && links.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(NodeContent { markdown, images, links }))
+}
+
+/// Sanitize optional request content, reporting errors under `pointer` (e.g. `/content`)
+pub fn sanitize_at(content: Option<NodeContent>, pointer: &str) -> ApiResult<Option<NodeContent>> {
+    match content {
+        Some(content) => sanitize(content).map_err(|errors| {
+            ApiError::validation_at(
+                errors
+                    .into_iter()
+                    .map(|(relative, message)| (format!("{}{}", pointer, relative), message))
+                    .collect(),
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_markdown() {
+        assert_eq!(
+            sanitize_markdown("1. Unplug the pump\n2. Open the <b>fuse</b> box<script>alert(1)</script>"),
+            "1. Unplug the pump\n2. Open the fuse boxalert(1)"
+        );
+        assert_eq!(sanitize_markdown("See <https://example.com/manual> <!-- draft -->"), "See <https://example.com/manual>");
+        assert_eq!(sanitize_markdown("<javascript:alert(1)>"), "");
+        assert_eq!(sanitize_markdown("Voltage < 12 V"), "Voltage &lt; 12 V");
+        assert_eq!(
+            sanitize_markdown("[manual](https://example.com \"Manual\") and [bad](javascript:alert(1))"),
+            "[manual](https://example.com \"Manual\") and [bad]"
+        );
+        assert_eq!(sanitize_markdown("![fuse](/api/v1/uploads/1/download)"), "![fuse](/api/v1/uploads/1/download)");
+        assert_eq!(sanitize_markdown("![x](//evil.example/x.png)"), "![x]");
+        assert_eq!(sanitize_markdown("Unclosed [link](http"), "Unclosed [link](http");
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let content = NodeContent {
+            markdown: "  Replace the **fuse**  ".to_string(),
+            images: vec![
+                ContentImage { url: "/api/v1/uploads/abc/download".to_string(), alt: "Fuse box".to_string(), caption: Some(" ".to_string()) },
+            ],
+            links: vec![ContentLink { url: "https://example.com/manual".to_string(), label: "Manual".to_string() }],
+        };
+        let sanitized = sanitize(content).unwrap().unwrap();
+        assert_eq!(sanitized.markdown, "Replace the **fuse**");
+        assert_eq!(sanitized.images[0].caption, None);
+
+        let errors = sanitize(NodeContent {
+            markdown: String::new(),
+            images: vec![ContentImage { url: "javascript:alert(1)".to_string(), alt: String::new(), caption: None }],
+            links: vec![ContentLink { url: "ftp://example.com".to_string(), label: String::new() }],
+        })
+        .unwrap_err();
+        let pointers: Vec<&str> = errors.iter().map(|(pointer, _)| pointer.as_str()).collect();
+        assert_eq!(pointers, vec!["/images/0/url", "/links/0/url", "/links/0/label"]);
+
+        assert_eq!(sanitize(NodeContent { markdown: "<br>".to_string(), images: vec![], links: vec![] }).unwrap(), None);
+    }
+}
@@ -0,0 +1,160 @@
+/// Loop detection for answers
+///
+/// An answer from A to B closes a loop when B already leads back to A, so a
+/// tech following it could go round the same questions forever. Only loops
+/// within A's category count; the graph is its active answers plus the
+/// questions' "I'm not sure" options.
+use sqlx::PgPool;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+/// Longest node label shown in a loop
+const LABEL_MAX_CHARS: usize = 40;
+
+/// Shortest path from `from` to `to` over (from, to) edges, both ends included
+pub fn find_path(edges: &[(Uuid, Uuid)], from: Uuid, to: Uuid) -> Option<Vec<Uuid>> {
+    if from == to {
+        return Some(vec![from]);
+    }
+
+    let mut children: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for &(a, b) in edges {
+        children.entry(a).or_default().push(b);
+    }
+
+    let mut came_from: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        for &next in children.get(&node).into_iter().flatten() {
+            if next == from || came_from.contains_key(&next) {
+                continue;
+            }
+            came_from.insert(next, node);
+            if next == to {
+                let mut path = vec![to];
+                let mut at = to;
+                while let Some(&prev) = came_from.get(&at) {
+                    path.push(prev);
+                    at = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+/// The loop an answer from `from` to `to` would close, as node labels that
+/// start and end at `from` (None when it closes none)
+///
+/// `replacing` is an existing answer being retargeted; it is left out of the
+/// graph so its old target doesn't count.
+pub async fn loop_closed_by(
+    db: &PgPool,
+    from: Uuid,
+    to: Uuid,
+    replacing: Option<Uuid>,
+) -> Result<Option<Vec<String>>, sqlx::Error> {
+    let same_category = sqlx::query_scalar::<_, bool>(
+        "SELECT f.category IS NOT DISTINCT FROM t.category FROM nodes f, nodes t WHERE f.id = $1 AND t.id = $2",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_optional(db)
+    .await?
+    .unwrap_or(false);
+    if !same_category {
+        return Ok(None);
+    }
+
+    let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT c.from_node_id, c.to_node_id
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE f.category = (SELECT category FROM nodes WHERE id = $1)
+           AND t.category = f.category
+           AND c.is_active = true
+           AND c.id IS DISTINCT FROM $2
+         UNION ALL
+         SELECT f.id, t.id
+         FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE f.category = (SELECT category FROM nodes WHERE id = $1)
+           AND t.category = f.category
+           AND f.node_type = 'question'",
+    )
+    .bind(from)
+    .bind(replacing)
+    .fetch_all(db)
+    .await?;
+
+    let Some(path) = find_path(&edges, to, from) else {
+        return Ok(None);
+    };
+    let mut cycle = vec![from];
+    cycle.extend(path);
+
+    let labels: HashMap<Uuid, String> = sqlx::query_as::<_, (Uuid, Option<String>, String)>(
+        "SELECT id, semantic_id, text FROM nodes WHERE id = ANY($1)",
+    )
+    .bind(&cycle)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|(id, semantic_id, text)| (id, semantic_id.unwrap_or_else(|| truncate(&text))))
+    .collect();
+
+    Ok(Some(
+        cycle
+            .iter()
+            .map(|id| labels.get(id).cloned().unwrap_or_else(|| id.to_string()))
+            .collect(),
+    ))
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= LABEL_MAX_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(LABEL_MAX_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_path_shortest() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        // 0 -> 1 -> 2 -> 3 and a shortcut 0 -> 3
+        let edges = vec![(ids[0], ids[1]), (ids[1], ids[2]), (ids[2], ids[3]), (ids[0], ids[3])];
+
+        assert_eq!(find_path(&edges, ids[0], ids[3]), Some(vec![ids[0], ids[3]]));
+        assert_eq!(find_path(&edges, ids[1], ids[3]), Some(vec![ids[1], ids[2], ids[3]]));
+        assert_eq!(find_path(&edges, ids[3], ids[0]), None);
+        assert_eq!(find_path(&edges, ids[2], ids[2]), Some(vec![ids[2]]));
+    }
+
+    #[test]
+    fn test_find_path_survives_existing_loops() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        // 0 <-> 1 already loops; 2 is out of reach
+        let edges = vec![(ids[0], ids[1]), (ids[1], ids[0])];
+
+        assert_eq!(find_path(&edges, ids[0], ids[2]), None);
+        assert_eq!(find_path(&edges, ids[1], ids[0]), Some(vec![ids[1], ids[0]]));
+    }
+
+    #[test]
+    fn test_truncate_long_labels() {
+        assert_eq!(truncate("Check the fuse"), "Check the fuse");
+        let long = "Is the pump motor making a grinding noise when started?";
+        let cut = truncate(long);
+        assert!(cut.ends_with('…'));
+        assert!(cut.chars().count() <= LABEL_MAX_CHARS);
+    }
+}
@@ -0,0 +1,143 @@
+/// Kiosk devices
+///
+/// Kiosks share one network identity, so the public troubleshooting API can't
+/// tell them apart by IP. Each kiosk is registered by an admin and sends the
+/// token it was issued in the `X-Device-Token` header: the rate limiter then
+/// counts its requests on their own, and sessions it starts record which
+/// device they came from. Whether `start_session` insists on a token is the
+/// `require_device_token` setting.
+///
+/// Like service tokens, enabled devices are kept in memory (keyed by digest)
+/// so checking a token never touches the database; admin changes reload
+/// immediately and a background refresh picks up other instances.
+use crate::utils::password;
+use axum::http::HeaderMap;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Header kiosks send their token in
+pub const HEADER: &str = "x-device-token";
+
+/// Prefix that tells device tokens apart from other tokens
+pub const TOKEN_PREFIX: &str = "dev_";
+
+/// Characters of a token kept for display ("dev_" plus 8 hex digits)
+pub const DISPLAY_PREFIX_LENGTH: usize = 12;
+
+/// How often devices are re-read and last-seen times are written back
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+static DEVICES: OnceLock<RwLock<HashMap<String, Uuid>>> = OnceLock::new();
+static SEEN: OnceLock<Mutex<HashSet<Uuid>>> = OnceLock::new();
+
+fn devices() -> &'static RwLock<HashMap<String, Uuid>> {
+    DEVICES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn seen() -> &'static Mutex<HashSet<Uuid>> {
+    SEEN.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Generate a new token (the plaintext is only returned once)
+pub fn generate() -> String {
+    format!("{}{}", TOKEN_PREFIX, password::generate_opaque_token())
+}
+
+/// Enabled device a token belongs to, recording that it was seen
+pub fn lookup(token: &str) -> Option<Uuid> {
+    if !token.starts_with(TOKEN_PREFIX) {
+        return None;
+    }
+    let id = *devices()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&password::hash_opaque_token(token))?;
+
+    seen().lock().unwrap_or_else(|e| e.into_inner()).insert(id);
+    Some(id)
+}
+
+/// Device identified by a request's headers
+///
+/// `Ok(None)` when no token was sent; `Err` when one was sent but isn't an
+/// enabled device, so a disabled kiosk is refused rather than treated as anonymous.
+pub fn from_headers(headers: &HeaderMap) -> Result<Option<Uuid>, &'static str> {
+    match headers.get(HEADER) {
+        None => Ok(None),
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|token| lookup(token.trim()))
+            .map(Some)
+            .ok_or("Unknown or disabled device token"),
+    }
+}
+
+/// Re-read all enabled devices from the database
+pub async fn reload(db: &PgPool) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, Uuid)>("SELECT token_hash, id FROM devices WHERE disabled_at IS NULL")
+        .fetch_all(db)
+        .await?;
+
+    *devices().write().unwrap_or_else(|e| e.into_inner()) = rows.into_iter().collect();
+    Ok(())
+}
+
+/// Write back which devices were seen since the last flush (accurate to the refresh interval)
+async fn flush_seen(db: &PgPool) -> Result<(), sqlx::Error> {
+    let ids: Vec<Uuid> = seen().lock().unwrap_or_else(|e| e.into_inner()).drain().collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE devices SET last_seen_at = NOW() WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Periodically record activity and re-read devices so every instance converges on disabled ones
+pub fn spawn_refresh(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = flush_seen(&db).await {
+                tracing::warn!("Failed to record device activity: {}", e);
+            }
+            if let Err(e) = reload(&db).await {
+                tracing::warn!("Failed to refresh devices: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_tokens_and_headers() {
+        let token = generate();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+
+        let id = Uuid::new_v4();
+        devices().write().unwrap().insert(password::hash_opaque_token(&token), id);
+        assert_eq!(lookup(&token), Some(id));
+        assert_eq!(lookup("svc_not_a_device"), None);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(from_headers(&headers), Ok(None));
+        headers.insert(HEADER, HeaderValue::from_str(&format!(" {} ", token)).unwrap());
+        assert_eq!(from_headers(&headers), Ok(Some(id)));
+        headers.insert(HEADER, HeaderValue::from_static("dev_unknown"));
+        assert!(from_headers(&headers).is_err());
+    }
+}
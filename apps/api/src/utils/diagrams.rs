@@ -0,0 +1,164 @@
+/// Issue graphs as Graphviz DOT or Mermaid flowcharts
+///
+/// Node shapes follow the editor and answers are the edge labels, in the order
+/// technicians see them.
+use crate::routes::issues::{ConnectionExportData, IssueExportData, NodeExportData};
+
+/// Connections in display order (by source, then order index)
+fn ordered_connections(data: &IssueExportData) -> Vec<&ConnectionExportData> {
+    let mut connections: Vec<&ConnectionExportData> = data.connections.iter().collect();
+    connections.sort_by_key(|c| (c.from_node_index, c.order_index));
+    connections
+}
+
+/// Text shown on a node
+fn node_label(node: &NodeExportData) -> String {
+    match (node.node_type.as_str(), &node.subflow_category) {
+        ("subflow", Some(category)) => format!("{}\n(runs {})", node.text, category),
+        ("return", _) => format!("Return: {}", node.text),
+        _ => node.text.clone(),
+    }
+}
+
+/// Graphviz DOT digraph of an issue
+pub fn to_dot(data: &IssueExportData) -> String {
+    let mut dot = format!("digraph \"{}\" {{\n", dot_escape(&data.issue.category));
+    dot.push_str("  rankdir=TB;\n");
+    dot.push_str("  node [fontname=\"Helvetica\"];\n");
+    dot.push_str("  edge [fontname=\"Helvetica\", fontsize=10];\n");
+
+    for (i, node) in data.nodes.iter().enumerate() {
+        let shape = match node.node_type.as_str() {
+            "conclusion" => "shape=box, style=\"rounded,filled\", fillcolor=\"#e8f5e9\"",
+            "subflow" => "shape=component",
+            "return" => "shape=oval",
+            _ => "shape=box, style=rounded",
+        };
+        dot.push_str(&format!("  n{} [label=\"{}\", {}];\n", i, dot_escape(&node_label(node)), shape));
+    }
+    for connection in ordered_connections(data) {
+        dot.push_str(&format!(
+            "  n{} -> n{} [label=\"{}\"];\n",
+            connection.from_node_index,
+            connection.to_node_index,
+            dot_escape(&connection.label)
+        ));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Mermaid flowchart of an issue
+pub fn to_mermaid(data: &IssueExportData) -> String {
+    let mut mermaid = String::from("flowchart TD\n");
+
+    for (i, node) in data.nodes.iter().enumerate() {
+        let label = mermaid_escape(&node_label(node));
+        let shape = match node.node_type.as_str() {
+            "conclusion" => format!("([\"{}\"])", label),
+            "subflow" => format!("[[\"{}\"]]", label),
+            "return" => format!("((\"{}\"))", label),
+            _ => format!("[\"{}\"]", label),
+        };
+        mermaid.push_str(&format!("    n{}{}\n", i, shape));
+    }
+    for connection in ordered_connections(data) {
+        mermaid.push_str(&format!(
+            "    n{} -->|\"{}\"| n{}\n",
+            connection.from_node_index,
+            mermaid_escape(&connection.label),
+            connection.to_node_index
+        ));
+    }
+
+    mermaid
+}
+
+/// Quoted Mermaid text: quotes, pipes and angle brackets as entities, line breaks as `<br/>`
+fn mermaid_escape(text: &str) -> String {
+    text.replace('&', "#amp;")
+        .replace('"', "#quot;")
+        .replace('|', "#124;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', "<br/>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::issues::IssueImportMetadata;
+
+    fn node(node_type: &str, text: &str) -> NodeExportData {
+        NodeExportData {
+            node_type: node_type.to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn connection(from: usize, to: usize, label: &str, order_index: i32) -> ConnectionExportData {
+        ConnectionExportData {
+            from_node_index: from,
+            to_node_index: to,
+            label: label.to_string(),
+            order_index,
+            condition: None,
+            unsure_route: false,
+        }
+    }
+
+    fn pump() -> IssueExportData {
+        IssueExportData {
+            issue: IssueImportMetadata {
+                name: "Pump".to_string(),
+                category: "pump".to_string(),
+                display_category: None,
+                root_question_text: "Is the \"main\" breaker on?".to_string(),
+                is_active: None,
+                start_order_index: None,
+                description: None,
+                equipment_models: None,
+                tags: None,
+            },
+            nodes: vec![
+                node("question", "Is the \"main\" breaker on?"),
+                node("conclusion", "Reset the breaker"),
+                node("conclusion", "Pressure < 10 | call support"),
+            ],
+            connections: vec![connection(0, 2, "Yes", 1), connection(0, 1, "No", 0)],
+        }
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = to_dot(&pump());
+        assert!(dot.starts_with("digraph \"pump\" {\n"));
+        assert!(dot.contains("  n0 [label=\"Is the \\\"main\\\" breaker on?\", shape=box, style=rounded];\n"));
+        assert!(dot.contains("  n1 [label=\"Reset the breaker\", shape=box, style=\"rounded,filled\""));
+        // Answers in the order technicians see them
+        assert!(dot.contains("  n0 -> n1 [label=\"No\"];\n  n0 -> n2 [label=\"Yes\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mermaid = to_mermaid(&pump());
+        assert!(mermaid.starts_with("flowchart TD\n    n0[\"Is the #quot;main#quot; breaker on?\"]\n"));
+        assert!(mermaid.contains("    n2([\"Pressure #lt; 10 #124; call support\"])\n"));
+        assert!(mermaid.contains("    n0 -->|\"No\"| n1\n    n0 -->|\"Yes\"| n2\n"));
+    }
+
+    #[test]
+    fn test_subflow_label() {
+        let mut subflow = node("subflow", "Check wiring");
+        subflow.subflow_category = Some("wiring".to_string());
+        assert_eq!(node_label(&subflow), "Check wiring\n(runs wiring)");
+        assert_eq!(node_label(&node("return", "fixed")), "Return: fixed");
+    }
+}
@@ -0,0 +1,103 @@
+/// In-memory ring buffer of recent API errors
+///
+/// Keeps the last N error responses so admins can see recent failures from
+/// the dashboard without shell access to the server logs.
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use ts_rs::TS;
+
+/// Number of errors kept when RECENT_ERRORS_CAPACITY is not set
+const DEFAULT_CAPACITY: usize = 200;
+
+/// A single recorded error response
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RecentError {
+    pub timestamp: String,
+    pub method: String,
+    /// Matched route pattern (e.g. /api/v1/admin/nodes/:id) when available
+    pub route: String,
+    pub status: u16,
+    /// ApiError type, absent for errors not produced by an ApiError
+    pub error_code: Option<String>,
+    pub correlation_id: String,
+}
+
+/// Fixed-capacity buffer that drops the oldest entry when full
+pub struct ErrorBuffer {
+    entries: Mutex<VecDeque<RecentError>>,
+    capacity: usize,
+}
+
+impl ErrorBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, error: RecentError) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(error);
+    }
+
+    /// Most recent errors first
+    pub fn recent(&self, limit: usize) -> Vec<RecentError> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+static BUFFER: OnceLock<ErrorBuffer> = OnceLock::new();
+
+/// Process-wide error buffer (capacity from RECENT_ERRORS_CAPACITY)
+pub fn global() -> &'static ErrorBuffer {
+    BUFFER.get_or_init(|| {
+        let capacity = std::env::var("RECENT_ERRORS_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        ErrorBuffer::new(capacity)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(status: u16) -> RecentError {
+        RecentError {
+            timestamp: "2025-10-24T00:00:00Z".to_string(),
+            method: "GET".to_string(),
+            route: "/api/v1/admin/sessions".to_string(),
+            status,
+            error_code: None,
+            correlation_id: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest() {
+        let buffer = ErrorBuffer::new(2);
+        buffer.push(error(400));
+        buffer.push(error(404));
+        buffer.push(error(500));
+
+        let recent = buffer.recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].status, 500);
+        assert_eq!(recent[1].status, 404);
+    }
+}
@@ -0,0 +1,505 @@
+/// Small boolean expression language used for connection conditions
+///
+/// Comparisons (`<`, `<=`, `>`, `>=`, `=`, `!=`) between variables and literals,
+/// combined with `and`, `or`, `not` and parentheses. A comparison with no
+/// left-hand side compares `value`. Strings compare case-insensitively.
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Variable implied by a comparison without a left-hand side
+pub const IMPLICIT_VARIABLE: &str = "value";
+
+/// Deepest nesting of parentheses and `not` accepted
+const MAX_DEPTH: usize = 32;
+
+/// A variable's value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            Self::Text(s) => s.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+            Self::Bool(_) => None,
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        match self {
+            Self::Number(n) => *n != 0.0,
+            Self::Text(s) => !s.is_empty(),
+            Self::Bool(b) => *b,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Text(s) => write!(f, "{}", s),
+            Self::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Variables an expression is evaluated against
+pub type Variables = HashMap<String, Value>;
+
+/// Whether `name` can be used as a variable in expressions
+///
+/// Stricter than what the parser accepts: ASCII letters, digits and
+/// underscores, not starting with a digit, and not a keyword or `value`.
+pub fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !["and", "or", "not", "true", "false", IMPLICIT_VARIABLE]
+            .iter()
+            .any(|keyword| name.eq_ignore_ascii_case(keyword))
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "=",
+            Self::Ne => "!=",
+        }
+    }
+
+    fn holds(&self, left: &Value, right: &Value) -> bool {
+        if let (Some(l), Some(r)) = (left.as_number(), right.as_number()) {
+            return match self {
+                Self::Lt => l < r,
+                Self::Le => l <= r,
+                Self::Gt => l > r,
+                Self::Ge => l >= r,
+                Self::Eq => l == r,
+                Self::Ne => l != r,
+            };
+        }
+
+        let equal = match (left, right) {
+            (Value::Text(l), Value::Text(r)) => l.trim().eq_ignore_ascii_case(r.trim()),
+            (Value::Bool(l), Value::Bool(r)) => l == r,
+            _ => false,
+        };
+        match self {
+            Self::Eq => equal,
+            Self::Ne => !equal,
+            _ => false,
+        }
+    }
+}
+
+/// Side of a comparison
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Literal(Value),
+    Variable(String),
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, variables: &'a Variables) -> Option<&'a Value> {
+        match self {
+            Self::Literal(value) => Some(value),
+            Self::Variable(name) => variables.get(name),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(Value::Number(n)) => write!(f, "{}", n),
+            Self::Literal(Value::Text(s)) if s.contains('"') => write!(f, "'{}'", s),
+            Self::Literal(Value::Text(s)) => write!(f, "\"{}\"", s),
+            Self::Literal(Value::Bool(b)) => write!(f, "{}", b),
+            Self::Variable(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Compare(Operand, Comparison, Operand),
+    Truthy(Operand),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    /// Evaluate against a set of variables
+    pub fn eval(&self, variables: &Variables) -> bool {
+        match self {
+            Self::Compare(left, cmp, right) => match (left.resolve(variables), right.resolve(variables)) {
+                (Some(l), Some(r)) => cmp.holds(l, r),
+                _ => false,
+            },
+            Self::Truthy(operand) => operand.resolve(variables).is_some_and(Value::is_truthy),
+            Self::Not(inner) => !inner.eval(variables),
+            Self::And(parts) => parts.iter().all(|p| p.eval(variables)),
+            Self::Or(parts) => parts.iter().any(|p| p.eval(variables)),
+        }
+    }
+
+    /// Names of the variables the expression reads
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut names = BTreeSet::new();
+        self.collect_variables(&mut names);
+        names
+    }
+
+    fn collect_variables<'a>(&'a self, names: &mut BTreeSet<&'a str>) {
+        let mut add = |operand: &'a Operand| {
+            if let Operand::Variable(name) = operand {
+                names.insert(name.as_str());
+            }
+        };
+        match self {
+            Self::Compare(left, _, right) => {
+                add(left);
+                add(right);
+            }
+            Self::Truthy(operand) => add(operand),
+            Self::Not(inner) => inner.collect_variables(names),
+            Self::And(parts) | Self::Or(parts) => parts.iter().for_each(|p| p.collect_variables(names)),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compare(Operand::Variable(name), cmp, right @ Operand::Literal(Value::Number(_)))
+                if name == IMPLICIT_VARIABLE =>
+            {
+                write!(f, "{} {}", cmp.as_str(), right)
+            }
+            Self::Compare(left, cmp, right) => write!(f, "{} {} {}", left, cmp.as_str(), right),
+            Self::Truthy(operand) => write!(f, "{}", operand),
+            Self::Not(inner) => match inner.as_ref() {
+                Self::And(_) | Self::Or(_) => write!(f, "not ({})", inner),
+                _ => write!(f, "not {}", inner),
+            },
+            Self::And(parts) => {
+                let parts: Vec<String> = parts
+                    .iter()
+                    .map(|p| match p {
+                        Self::Or(_) => format!("({})", p),
+                        _ => p.to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", parts.join(" and "))
+            }
+            Self::Or(parts) => {
+                let parts: Vec<String> = parts.iter().map(|p| p.to_string()).collect();
+                write!(f, "{}", parts.join(" or "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Word(String),
+    Compare(Comparison),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+        let (token, width) = match (c, two.as_str()) {
+            (_, "<=") => (Token::Compare(Comparison::Le), 2),
+            (_, ">=") => (Token::Compare(Comparison::Ge), 2),
+            (_, "!=") => (Token::Compare(Comparison::Ne), 2),
+            (_, "==") => (Token::Compare(Comparison::Eq), 2),
+            (_, "&&") => (Token::And, 2),
+            (_, "||") => (Token::Or, 2),
+            ('<', _) => (Token::Compare(Comparison::Lt), 1),
+            ('>', _) => (Token::Compare(Comparison::Gt), 1),
+            ('=', _) => (Token::Compare(Comparison::Eq), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            ('"' | '\'', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| "Unterminated string".to_string())?;
+                (Token::Text(chars[i + 1..i + 1 + end].iter().collect()), end + 2)
+            }
+            _ if c.is_ascii_digit() || c == '.' || (c == '-' && next.is_some_and(|n| n.is_ascii_digit() || n == '.')) => {
+                let len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|ch| ch.is_ascii_digit() || **ch == '.')
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let number = text
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite())
+                    .ok_or_else(|| format!("'{}' is not a number", text))?;
+                (Token::Number(number), len)
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_alphanumeric() || **ch == '_' || **ch == '.')
+                    .count();
+                let word: String = chars[i..i + len].iter().collect();
+                let token = match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                };
+                (token, len)
+            }
+            _ => return Err(format!("Unexpected character '{}'", c)),
+        };
+
+        tokens.push(token);
+        i += width;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut parts = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            parts.push(self.and()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Expr::Or(parts) })
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut parts = vec![self.unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            parts.push(self.unary()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Expr::And(parts) })
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+
+        let expr = match self.peek() {
+            Some(Token::Not) => {
+                self.advance();
+                Expr::Not(Box::new(self.unary()?))
+            }
+            Some(Token::Open) => {
+                self.advance();
+                let inner = self.or()?;
+                if self.advance() != Some(Token::Close) {
+                    return Err("Missing ')'".to_string());
+                }
+                inner
+            }
+            _ => self.comparison()?,
+        };
+
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        // A leading operator compares the implicit variable against a number
+        if let Some(Token::Compare(cmp)) = self.peek() {
+            let cmp = *cmp;
+            self.advance();
+            return match self.advance() {
+                Some(Token::Number(n)) => Ok(Expr::Compare(
+                    Operand::Variable(IMPLICIT_VARIABLE.to_string()),
+                    cmp,
+                    Operand::Literal(Value::Number(n)),
+                )),
+                _ => Err(format!("Expected a number after '{}'", cmp.as_str())),
+            };
+        }
+
+        let left = self.operand()?;
+        match self.peek() {
+            Some(Token::Compare(cmp)) => {
+                let cmp = *cmp;
+                self.advance();
+                Ok(Expr::Compare(left, cmp, self.operand()?))
+            }
+            _ if matches!(left, Operand::Literal(_)) => Err(format!("Expected a comparison after {}", left)),
+            _ => Ok(Expr::Truthy(left)),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.advance() {
+            Some(Token::Number(n)) => Ok(Operand::Literal(Value::Number(n))),
+            Some(Token::Text(s)) => Ok(Operand::Literal(Value::Text(s))),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("true") => Ok(Operand::Literal(Value::Bool(true))),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("false") => Ok(Operand::Literal(Value::Bool(false))),
+            Some(Token::Word(w)) => Ok(Operand::Variable(w)),
+            Some(_) => Err("Expected a number, string or variable".to_string()),
+            None => Err("Expression ends too early".to_string()),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(s)?, position: 0, depth: 0 };
+        if parser.tokens.is_empty() {
+            return Err("Expression is empty".to_string());
+        }
+
+        let expr = parser.or()?;
+        if parser.position < parser.tokens.len() {
+            return Err("Unexpected text after the end of the expression".to_string());
+        }
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, Value)]) -> Variables {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_eval() {
+        let v = vars(&[
+            ("pump_start", Value::Text("No".to_string())),
+            ("voltage", Value::Number(11.8)),
+            ("reading", Value::Text("12".to_string())),
+        ]);
+
+        let e: Expr = "pump_start = \"no\" and voltage < 12".parse().unwrap();
+        assert!(e.eval(&v));
+        let e: Expr = "pump_start == 'Yes' || (voltage >= 11 && !(voltage > 13))".parse().unwrap();
+        assert!(e.eval(&v));
+        let e: Expr = "reading > 11.5".parse().unwrap();
+        assert!(e.eval(&v));
+
+        // Unset variables fail comparisons and are falsy
+        let e: Expr = "breaker = 'Tripped'".parse().unwrap();
+        assert!(!e.eval(&v));
+        let e: Expr = "not breaker".parse().unwrap();
+        assert!(e.eval(&v));
+
+        // Strings only compare for equality
+        let e: Expr = "pump_start > 'A'".parse().unwrap();
+        assert!(!e.eval(&v));
+        let e: Expr = "pump_start != 'Yes'".parse().unwrap();
+        assert!(e.eval(&v));
+    }
+
+    #[test]
+    fn test_implicit_variable() {
+        let e: Expr = ">= 11.5 and < 13.8".parse().unwrap();
+        assert_eq!(e.variables().into_iter().collect::<Vec<_>>(), vec![IMPLICIT_VARIABLE]);
+        assert!(e.eval(&vars(&[(IMPLICIT_VARIABLE, Value::Number(12.0))])));
+        assert!(!e.eval(&vars(&[(IMPLICIT_VARIABLE, Value::Number(13.8))])));
+        assert!(!e.eval(&Variables::new()));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for (input, canonical) in [
+            ("value >= 11.50 AND value<13.8", ">= 11.5 and < 13.8"),
+            ("a == 1 || b && !c", "a = 1 or b and not c"),
+            ("(a or b) and not (c and d)", "(a or b) and not (c and d)"),
+            ("label = 'say \"hi\"'", "label = 'say \"hi\"'"),
+        ] {
+            let e: Expr = input.parse().unwrap();
+            assert_eq!(e.to_string(), canonical);
+            assert_eq!(canonical.parse::<Expr>().unwrap(), e);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in ["", "12", "a =", "(a", "a b", "'open", "a # b", "1..2 > 0", "> inf"] {
+            assert!(input.parse::<Expr>().is_err(), "{:?} should not parse", input);
+        }
+        assert!("(".repeat(MAX_DEPTH + 1).parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn test_variable_names() {
+        for name in ["serial", "error_code", "_x", "Panel2"] {
+            assert!(is_variable_name(name), "{:?} should be allowed", name);
+        }
+        for name in ["", "2fast", "panel.voltage", "error-code", "AND", "value", "true", "sérial"] {
+            assert!(!is_variable_name(name), "{:?} should be rejected", name);
+        }
+    }
+}
@@ -0,0 +1,616 @@
+/// Graph validation for an issue
+///
+/// Checks that every path through a category can end: each question has an
+/// answer leading somewhere live, no answer points at a node that is switched
+/// off, and every node can be reached from the issue's start (or from an
+/// entry point or another category). A question's "I'm not sure" option
+/// counts as a way on, but not as an answer. A sub-flow node must run another
+/// category that has an active start, and needs an answer to continue with
+/// afterwards. Used before an issue is activated;
+/// nodes of the category itself count as active since activation turns them
+/// all on.
+///
+/// It also warns about things that are allowed but usually mistakes:
+/// conclusions and returns with answers (never shown), two answers
+/// on one question with the same label, and loops. Warnings don't stop an
+/// issue being activated.
+use crate::engine::NOT_SURE_LABEL;
+use crate::models::NodeType;
+use crate::utils::cycles;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet, VecDeque};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What is wrong with part of a graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum GraphProblemKind {
+    /// A question with no answer leading to an active node
+    DeadEnd,
+    /// An active answer pointing at a node that is switched off
+    InactiveTarget,
+    /// A node no path reaches
+    Unreachable,
+    /// A conclusion or return with answers of its own, which are never shown
+    ConclusionWithAnswers,
+    /// Two answers on one question with the same label
+    DuplicateLabel,
+    /// Answers that lead round in a loop
+    Cycle,
+    /// A sub-flow node whose category can't be run
+    MissingSubflow,
+}
+
+impl GraphProblemKind {
+    pub fn severity(self) -> GraphProblemSeverity {
+        match self {
+            GraphProblemKind::DeadEnd
+            | GraphProblemKind::InactiveTarget
+            | GraphProblemKind::Unreachable
+            | GraphProblemKind::MissingSubflow => GraphProblemSeverity::Error,
+            GraphProblemKind::ConclusionWithAnswers | GraphProblemKind::DuplicateLabel | GraphProblemKind::Cycle => {
+                GraphProblemSeverity::Warning
+            }
+        }
+    }
+}
+
+/// Whether a problem stops the issue being activated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum GraphProblemSeverity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a graph
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct GraphProblem {
+    pub kind: GraphProblemKind,
+    pub severity: GraphProblemSeverity,
+    pub node_id: Uuid,
+    /// The answer at fault, for problems with a connection
+    pub connection_id: Option<Uuid>,
+    pub message: String,
+}
+
+/// Everything wrong with an issue's graph
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct GraphValidationReport {
+    pub category: String,
+    /// The issue's start node
+    pub root_node_id: Uuid,
+    pub problems: Vec<GraphProblem>,
+}
+
+impl GraphProblem {
+    fn new(kind: GraphProblemKind, node_id: Uuid, connection_id: Option<Uuid>, message: String) -> Self {
+        Self { kind, severity: kind.severity(), node_id, connection_id, message }
+    }
+}
+
+impl GraphValidationReport {
+    /// No errors (warnings are allowed)
+    pub fn is_valid(&self) -> bool {
+        !self.problems.iter().any(|p| p.severity == GraphProblemSeverity::Error)
+    }
+
+    /// One-line summary of the errors, e.g. for bulk results
+    pub fn summary(&self) -> String {
+        let count = |kind| self.problems.iter().filter(|p| p.kind == kind).count();
+        let parts: Vec<String> = [
+            (GraphProblemKind::DeadEnd, "dead-end question(s)"),
+            (GraphProblemKind::InactiveTarget, "answer(s) leading to inactive nodes"),
+            (GraphProblemKind::Unreachable, "unreachable node(s)"),
+            (GraphProblemKind::MissingSubflow, "sub-flow(s) that can't be run"),
+        ]
+        .into_iter()
+        .filter_map(|(kind, what)| Some(count(kind)).filter(|n| *n > 0).map(|n| format!("{} {}", n, what)))
+        .collect();
+        format!("This issue has {}", parts.join(", "))
+    }
+}
+
+/// A node of the category being checked
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    pub id: Uuid,
+    pub node_type: NodeType,
+    pub text: String,
+    pub semantic_id: Option<String>,
+    /// The category a sub-flow node runs
+    pub subflow_category: Option<String>,
+}
+
+impl GraphNode {
+    /// How the node is named in messages: "text (semantic_id)"
+    fn describe(&self) -> String {
+        format!("{} ({})", self.text, self.semantic_id.as_deref().unwrap_or("no ID"))
+    }
+}
+
+/// An active connection, or an "I'm not sure" option, leaving a node of the category
+#[derive(Debug, Clone)]
+pub struct GraphEdge {
+    /// The connection; None for an "I'm not sure" option
+    pub id: Option<Uuid>,
+    pub from: Uuid,
+    pub to: Uuid,
+    pub label: String,
+    /// Whether the target will be active once the issue is
+    pub target_active: bool,
+}
+
+/// Check a category's graph
+///
+/// `nodes` are the category's nodes with its start node first; `entries` are
+/// nodes sessions can also start from or arrive at from other categories;
+/// `runnable` are the categories sub-flow nodes can run (those with an
+/// active start).
+pub fn validate(
+    category: &str,
+    nodes: &[GraphNode],
+    edges: &[GraphEdge],
+    entries: &HashSet<Uuid>,
+    runnable: &HashSet<String>,
+) -> GraphValidationReport {
+    let mut problems = Vec::new();
+    let by_id: HashMap<Uuid, &GraphNode> = nodes.iter().map(|n| (n.id, n)).collect();
+    let mut outgoing: HashMap<Uuid, Vec<&GraphEdge>> = HashMap::new();
+    for edge in edges {
+        outgoing.entry(edge.from).or_default().push(edge);
+    }
+
+    for node in nodes {
+        let edges = outgoing.get(&node.id).map(Vec::as_slice).unwrap_or_default();
+        for edge in edges.iter().filter(|e| !e.target_active) {
+            problems.push(GraphProblem::new(
+                GraphProblemKind::InactiveTarget,
+                node.id,
+                edge.id,
+                format!("Answer \"{}\" on {} leads to an inactive node", edge.label, node.describe()),
+            ));
+        }
+        let continues = edges.iter().any(|e| e.id.is_some() && e.target_active);
+        if matches!(node.node_type, NodeType::Question) && !continues {
+            problems.push(GraphProblem::new(
+                GraphProblemKind::DeadEnd,
+                node.id,
+                None,
+                format!(
+                    "{} has no answer leading to an active node; add one or change it to a conclusion",
+                    node.describe()
+                ),
+            ));
+        }
+        if matches!(node.node_type, NodeType::Subflow) {
+            if !continues {
+                problems.push(GraphProblem::new(
+                    GraphProblemKind::DeadEnd,
+                    node.id,
+                    None,
+                    format!("Sub-flow {} has no answer leading to an active node to continue with", node.describe()),
+                ));
+            }
+            match node.subflow_category.as_deref() {
+                Some(runs) if runs == category => problems.push(GraphProblem::new(
+                    GraphProblemKind::MissingSubflow,
+                    node.id,
+                    None,
+                    format!("Sub-flow {} runs its own issue", node.describe()),
+                )),
+                Some(runs) if !runnable.contains(runs) => problems.push(GraphProblem::new(
+                    GraphProblemKind::MissingSubflow,
+                    node.id,
+                    None,
+                    format!("Sub-flow {} runs '{}', which has no active start", node.describe(), runs),
+                )),
+                Some(_) => {}
+                None => problems.push(GraphProblem::new(
+                    GraphProblemKind::MissingSubflow,
+                    node.id,
+                    None,
+                    format!("Sub-flow {} doesn't say which issue it runs", node.describe()),
+                )),
+            }
+        }
+
+        let answers: Vec<&&GraphEdge> = edges.iter().filter(|e| e.id.is_some()).collect();
+        if node.node_type.ends_session() && !answers.is_empty() {
+            let (what, ends) = match node.node_type {
+                NodeType::Return => ("Return", "sessions leave the sub-flow at a return"),
+                _ => ("Conclusion", "sessions end at a conclusion"),
+            };
+            problems.push(GraphProblem::new(
+                GraphProblemKind::ConclusionWithAnswers,
+                node.id,
+                answers[0].id,
+                format!(
+                    "{} {} has {} answer(s); {}, so they are never shown",
+                    what,
+                    node.describe(),
+                    answers.len(),
+                    ends
+                ),
+            ));
+        }
+
+        // Labels are compared the way a tech reads them
+        let mut labels: HashMap<String, usize> = HashMap::new();
+        for edge in &answers {
+            let seen = labels.entry(edge.label.trim().to_lowercase()).or_default();
+            *seen += 1;
+            if *seen == 2 {
+                problems.push(GraphProblem::new(
+                    GraphProblemKind::DuplicateLabel,
+                    node.id,
+                    edge.id,
+                    format!("{} has more than one answer labelled \"{}\"", node.describe(), edge.label.trim()),
+                ));
+            }
+        }
+    }
+
+    // Walk from the start and every other way in, staying inside the category
+    let mut reached: HashSet<Uuid> = HashSet::new();
+    let mut queue: VecDeque<Uuid> = nodes
+        .first()
+        .map(|root| root.id)
+        .into_iter()
+        .chain(entries.iter().copied().filter(|id| by_id.contains_key(id)))
+        .collect();
+    while let Some(id) = queue.pop_front() {
+        if !reached.insert(id) {
+            continue;
+        }
+        for edge in outgoing.get(&id).into_iter().flatten() {
+            if edge.target_active && by_id.contains_key(&edge.to) {
+                queue.push_back(edge.to);
+            }
+        }
+    }
+    for node in nodes.iter().filter(|n| !reached.contains(&n.id)) {
+        let what = match node.node_type {
+            NodeType::Question => "Question",
+            NodeType::Conclusion => "Conclusion",
+            NodeType::Subflow => "Sub-flow",
+            NodeType::Return => "Return",
+        };
+        problems.push(GraphProblem::new(
+            GraphProblemKind::Unreachable,
+            node.id,
+            None,
+            format!("{} {} can't be reached from the start of the issue", what, node.describe()),
+        ));
+    }
+
+    // One warning per loop, showing a way round it
+    let inside: Vec<(Uuid, Uuid)> = edges
+        .iter()
+        .filter(|e| e.target_active && by_id.contains_key(&e.to))
+        .map(|e| (e.from, e.to))
+        .collect();
+    for component in loops(nodes, &inside) {
+        let first = component[0];
+        let members: HashSet<Uuid> = component.iter().copied().collect();
+        let within: Vec<(Uuid, Uuid)> = inside
+            .iter()
+            .copied()
+            .filter(|(from, to)| members.contains(from) && members.contains(to))
+            .collect();
+        let Some(&(_, next)) = within.iter().find(|(from, _)| *from == first) else {
+            continue;
+        };
+        let mut path = vec![first];
+        path.extend(cycles::find_path(&within, next, first).unwrap_or_default());
+        let route: Vec<String> = path.iter().map(|id| by_id[id].describe()).collect();
+        problems.push(GraphProblem::new(
+            GraphProblemKind::Cycle,
+            first,
+            None,
+            format!("Answers loop back: {}", route.join(" → ")),
+        ));
+    }
+
+    GraphValidationReport {
+        category: category.to_string(),
+        root_node_id: nodes.first().map(|n| n.id).unwrap_or_default(),
+        problems,
+    }
+}
+
+/// Groups of nodes that lead round to each other, each in `nodes` order
+///
+/// Strongly connected components with more than one node, or a node with an
+/// answer leading back to itself (Kosaraju, iteratively).
+fn loops(nodes: &[GraphNode], edges: &[(Uuid, Uuid)]) -> Vec<Vec<Uuid>> {
+    let index: HashMap<Uuid, usize> = nodes.iter().enumerate().map(|(i, n)| (n.id, i)).collect();
+    let mut forward: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    let mut backward: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    let mut self_loop = vec![false; nodes.len()];
+    for (from, to) in edges {
+        let (Some(&a), Some(&b)) = (index.get(from), index.get(to)) else {
+            continue;
+        };
+        forward[a].push(b);
+        backward[b].push(a);
+        self_loop[a] |= a == b;
+    }
+
+    // Finish order over the forward edges
+    let mut visited = vec![false; nodes.len()];
+    let mut order = Vec::with_capacity(nodes.len());
+    for start in 0..nodes.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![(start, 0)];
+        while let Some((node, next)) = stack.pop() {
+            if let Some(&child) = forward[node].get(next) {
+                stack.push((node, next + 1));
+                if !visited[child] {
+                    visited[child] = true;
+                    stack.push((child, 0));
+                }
+            } else {
+                order.push(node);
+            }
+        }
+    }
+
+    // Components over the reversed edges, latest finished first
+    let mut component = vec![usize::MAX; nodes.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for &start in order.iter().rev() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        let id = groups.len();
+        let mut members = Vec::new();
+        let mut stack = vec![start];
+        component[start] = id;
+        while let Some(node) = stack.pop() {
+            members.push(node);
+            for &parent in &backward[node] {
+                if component[parent] == usize::MAX {
+                    component[parent] = id;
+                    stack.push(parent);
+                }
+            }
+        }
+        groups.push(members);
+    }
+
+    let mut loops: Vec<Vec<usize>> = groups
+        .into_iter()
+        .filter(|members| members.len() > 1 || self_loop[members[0]])
+        .map(|mut members| {
+            members.sort_unstable();
+            members
+        })
+        .collect();
+    loops.sort_unstable_by_key(|members| members[0]);
+    loops
+        .into_iter()
+        .map(|members| members.into_iter().map(|i| nodes[i].id).collect())
+        .collect()
+}
+
+/// Load a category's graph and check it (None when the category has no nodes)
+pub async fn validate_category(db: &PgPool, category: &str) -> Result<Option<GraphValidationReport>, sqlx::Error> {
+    let nodes = sqlx::query_as::<_, (Uuid, NodeType, String, Option<String>, Option<String>)>(
+        "SELECT id, node_type, text, semantic_id, subflow_category FROM nodes WHERE category = $1 ORDER BY created_at ASC",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|(id, node_type, text, semantic_id, subflow_category)| GraphNode { id, node_type, text, semantic_id, subflow_category })
+    .collect::<Vec<_>>();
+    if nodes.is_empty() {
+        return Ok(None);
+    }
+
+    let edges = sqlx::query_as::<_, (Option<Uuid>, Uuid, Uuid, String, bool)>(
+        "SELECT c.id, c.from_node_id, c.to_node_id, c.label, t.category = $1 OR t.is_active
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE f.category = $1 AND c.is_active = true
+         UNION ALL
+         SELECT NULL, f.id, t.id, $2, t.category = $1 OR t.is_active
+         FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE f.category = $1 AND f.node_type = 'question'",
+    )
+    .bind(category)
+    .bind(NOT_SURE_LABEL)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|(id, from, to, label, target_active)| GraphEdge { id, from, to, label, target_active })
+    .collect::<Vec<_>>();
+
+    // Other ways in: entry points and answers in other categories
+    let entries = sqlx::query_scalar::<_, Uuid>(
+        "SELECT e.node_id FROM entry_points e
+         JOIN nodes n ON n.id = e.node_id
+         WHERE n.category = $1 AND e.is_active = true
+         UNION
+         SELECT c.to_node_id FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE t.category = $1 AND f.category <> $1 AND f.is_active = true
+         UNION
+         SELECT t.id FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE t.category = $1 AND f.category <> $1 AND f.is_active = true",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    // Categories this one's sub-flow nodes run that have an active start
+    let runnable = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT s.category FROM nodes n
+         JOIN nodes s ON s.category = n.subflow_category AND s.semantic_id = n.subflow_category || '_start'
+         WHERE n.category = $1 AND s.is_active = true",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    Ok(Some(validate(category, &nodes, &edges, &entries, &runnable)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(node_type: NodeType, text: &str) -> GraphNode {
+        GraphNode { id: Uuid::new_v4(), node_type, text: text.to_string(), semantic_id: None, subflow_category: None }
+    }
+
+    fn edge(from: &GraphNode, to: Uuid, target_active: bool) -> GraphEdge {
+        GraphEdge { id: Some(Uuid::new_v4()), from: from.id, to, label: "Yes".to_string(), target_active }
+    }
+
+    fn kinds(report: &GraphValidationReport) -> Vec<(GraphProblemKind, Uuid)> {
+        report.problems.iter().map(|p| (p.kind, p.node_id)).collect()
+    }
+
+    #[test]
+    fn test_valid_graph() {
+        let start = node(NodeType::Question, "Start?");
+        let fix = node(NodeType::Conclusion, "Replace fuse");
+        let edges = [edge(&start, fix.id, true)];
+        let report = validate("pump", &[start.clone(), fix], &edges, &HashSet::new(), &HashSet::new());
+        assert!(report.is_valid());
+        assert_eq!(report.root_node_id, start.id);
+    }
+
+    #[test]
+    fn test_dead_ends_and_inactive_targets() {
+        let start = node(NodeType::Question, "Start?");
+        let stuck = node(NodeType::Question, "Pump on?");
+        let elsewhere = Uuid::new_v4();
+        let edges = [edge(&start, stuck.id, true), edge(&stuck, elsewhere, false)];
+        let report = validate("pump", &[start, stuck.clone()], &edges, &HashSet::new(), &HashSet::new());
+
+        assert_eq!(
+            kinds(&report),
+            vec![(GraphProblemKind::InactiveTarget, stuck.id), (GraphProblemKind::DeadEnd, stuck.id)]
+        );
+        assert_eq!(report.problems[0].connection_id, edges[1].id);
+        assert!(report.summary().contains("1 dead-end question(s)"));
+    }
+
+    #[test]
+    fn test_unreachable_nodes() {
+        let start = node(NodeType::Question, "Start?");
+        let fix = node(NodeType::Conclusion, "Replace fuse");
+        let orphan = node(NodeType::Conclusion, "Call support");
+        let entered = node(NodeType::Conclusion, "Reset breaker");
+        let edges = [edge(&start, fix.id, true)];
+        let nodes = [start, fix, orphan.clone(), entered.clone()];
+        let report = validate("pump", &nodes, &edges, &HashSet::from([entered.id]), &HashSet::new());
+
+        assert_eq!(kinds(&report), vec![(GraphProblemKind::Unreachable, orphan.id)]);
+        assert!(report.problems[0].message.starts_with("Conclusion Call support"));
+    }
+
+    #[test]
+    fn test_not_sure_option_reaches_but_does_not_answer() {
+        let start = node(NodeType::Question, "Start?");
+        let escalate = node(NodeType::Conclusion, "Call support");
+        let not_sure = GraphEdge { id: None, ..edge(&start, escalate.id, true) };
+        let report = validate("pump", &[start.clone(), escalate], &[not_sure], &HashSet::new(), &HashSet::new());
+
+        // The escalation is reachable, but the question still needs a real answer
+        assert_eq!(kinds(&report), vec![(GraphProblemKind::DeadEnd, start.id)]);
+    }
+
+    #[test]
+    fn test_warnings_do_not_invalidate() {
+        let start = node(NodeType::Question, "Start?");
+        let fix = node(NodeType::Conclusion, "Replace fuse");
+        let edges = [
+            edge(&start, fix.id, true),
+            GraphEdge { label: " yes".to_string(), ..edge(&start, fix.id, true) },
+            edge(&fix, start.id, true),
+        ];
+        let report = validate("pump", &[start.clone(), fix.clone()], &edges, &HashSet::new(), &HashSet::new());
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (GraphProblemKind::DuplicateLabel, start.id),
+                (GraphProblemKind::ConclusionWithAnswers, fix.id),
+                (GraphProblemKind::Cycle, start.id),
+            ]
+        );
+        assert_eq!(report.problems[0].connection_id, edges[1].id);
+        assert!(report.problems.iter().all(|p| p.severity == GraphProblemSeverity::Warning));
+        assert!(report.is_valid());
+        assert_eq!(report.problems[2].message, "Answers loop back: Start? (no ID) → Replace fuse (no ID) → Start? (no ID)");
+    }
+
+    #[test]
+    fn test_subflows() {
+        let start = node(NodeType::Question, "Start?");
+        let power = GraphNode { subflow_category: Some("power".to_string()), ..node(NodeType::Subflow, "Check power") };
+        let missing = GraphNode { subflow_category: Some("wiring".to_string()), ..node(NodeType::Subflow, "Check wiring") };
+        let fix = node(NodeType::Conclusion, "Replace fuse");
+        let done = node(NodeType::Return, "Powered");
+        let edges = [
+            edge(&start, power.id, true),
+            GraphEdge { label: "No".to_string(), ..edge(&start, missing.id, true) },
+            edge(&power, fix.id, true),
+            GraphEdge { label: "Maybe".to_string(), ..edge(&start, done.id, true) },
+            edge(&done, fix.id, true),
+        ];
+        let nodes = [start, power, missing.clone(), fix, done.clone()];
+        let report = validate("pump", &nodes, &edges, &HashSet::new(), &HashSet::from(["power".to_string()]));
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (GraphProblemKind::DeadEnd, missing.id),
+                (GraphProblemKind::MissingSubflow, missing.id),
+                (GraphProblemKind::ConclusionWithAnswers, done.id),
+            ]
+        );
+        assert!(report.problems[1].message.contains("runs 'wiring', which has no active start"));
+        assert!(report.problems[2].message.starts_with("Return Powered"));
+    }
+
+    #[test]
+    fn test_loops_reported_once_each() {
+        let nodes: Vec<GraphNode> = (0..6).map(|i| node(NodeType::Question, &format!("Q{}", i))).collect();
+        let ids: Vec<Uuid> = nodes.iter().map(|n| n.id).collect();
+        // 1 -> 2 -> 3 -> 1 and 4 -> 4; 0 and 5 aren't on a loop
+        let edges: Vec<(Uuid, Uuid)> = vec![
+            (ids[0], ids[1]),
+            (ids[1], ids[2]),
+            (ids[2], ids[3]),
+            (ids[3], ids[1]),
+            (ids[3], ids[4]),
+            (ids[4], ids[4]),
+            (ids[4], ids[5]),
+        ];
+
+        assert_eq!(loops(&nodes, &edges), vec![vec![ids[1], ids[2], ids[3]], vec![ids[4]]]);
+        assert!(loops(&nodes, &edges[..3]).is_empty());
+    }
+}
@@ -0,0 +1,423 @@
+/// Import adapters for decision trees authored in other tools
+///
+/// Each adapter converts an external export (OPML from XMind and other
+/// outliners, shape data CSV from Lucidchart, our own flat CSV) into
+/// `IssueExportData`, which then goes through the regular import pipeline.
+use crate::routes::issues::{
+    ConnectionExportData, IssueExportData, IssueImportMetadata, NodeExportData,
+};
+use crate::utils::issue_csv::CsvAdapter;
+use std::collections::{HashMap, HashSet};
+
+/// Issue the converted tree is imported as
+#[derive(Debug, Clone)]
+pub struct AdapterTarget {
+    pub category: String,
+    /// Issue name; adapters fall back to the document title, then the category
+    pub name: Option<String>,
+    pub display_category: Option<String>,
+}
+
+/// Converts one external export format into importable issue data
+pub trait ImportAdapter: Send + Sync {
+    /// Format name accepted by `?format=`
+    fn format(&self) -> &'static str;
+
+    /// Convert a document into a single issue, or explain why it can't be
+    fn convert(&self, input: &str, target: &AdapterTarget) -> Result<IssueExportData, String>;
+}
+
+/// All registered adapters
+pub fn adapters() -> Vec<Box<dyn ImportAdapter>> {
+    vec![Box::new(OpmlAdapter), Box::new(LucidchartCsvAdapter), Box::new(CsvAdapter)]
+}
+
+/// Look up an adapter by format name (case-insensitive)
+pub fn adapter_for(format: &str) -> Option<Box<dyn ImportAdapter>> {
+    adapters()
+        .into_iter()
+        .find(|adapter| adapter.format().eq_ignore_ascii_case(format.trim()))
+}
+
+/// Accumulates nodes and labelled connections while walking a source document
+#[derive(Default)]
+pub(crate) struct TreeBuilder {
+    nodes: Vec<String>,
+    connections: Vec<ConnectionExportData>,
+}
+
+impl TreeBuilder {
+    pub(crate) fn add_node(&mut self, text: String) -> usize {
+        self.nodes.push(text);
+        self.nodes.len() - 1
+    }
+
+    pub(crate) fn connect(&mut self, from: usize, to: usize, label: String) {
+        let order_index = self
+            .connections
+            .iter()
+            .filter(|c| c.from_node_index == from)
+            .count() as i32;
+
+        self.connections.push(ConnectionExportData {
+            from_node_index: from,
+            to_node_index: to,
+            label,
+            order_index,
+            condition: None,
+            unsure_route: false,
+        });
+    }
+
+    /// Nodes with outgoing connections become questions, the rest conclusions
+    pub(crate) fn finish(self, root: usize, name: String, target: &AdapterTarget) -> IssueExportData {
+        let has_children: HashSet<usize> =
+            self.connections.iter().map(|c| c.from_node_index).collect();
+
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, text)| NodeExportData {
+                node_type: if has_children.contains(&i) { "question" } else { "conclusion" }.to_string(),
+                text: text.clone(),
+                semantic_id: (i == root).then(|| format!("{}_start", target.category)),
+                position_x: None,
+                position_y: None,
+                kb_url: None,
+                doc_reference: None,
+                option_order: Default::default(),
+                answer_mode: Default::default(),
+                not_sure_node_index: None,
+                subflow_category: None,
+                content: None,
+            })
+            .collect();
+
+        IssueExportData {
+            issue: IssueImportMetadata {
+                name,
+                category: target.category.clone(),
+                display_category: target.display_category.clone(),
+                root_question_text: self.nodes[root].clone(),
+                is_active: None,
+                start_order_index: None,
+                description: None,
+                equipment_models: None,
+                tags: None,
+            },
+            nodes,
+            connections: self.connections,
+        }
+    }
+}
+
+/// OPML outlines (XMind, OmniOutliner, Workflowy, ...)
+///
+/// The body must contain a single top-level outline, the root question.
+/// A child outline is an answer: either it carries a `label` attribute and is
+/// itself the next step, or its text is the answer and its only child is the
+/// next step. Outlines without children are conclusions.
+pub struct OpmlAdapter;
+
+impl ImportAdapter for OpmlAdapter {
+    fn format(&self) -> &'static str {
+        "opml"
+    }
+
+    fn convert(&self, input: &str, target: &AdapterTarget) -> Result<IssueExportData, String> {
+        let doc = roxmltree::Document::parse(input).map_err(|e| format!("Invalid OPML: {}", e))?;
+        let opml = doc.root_element();
+        if !opml.has_tag_name("opml") {
+            return Err("Invalid OPML: root element must be <opml>".to_string());
+        }
+
+        let title = opml
+            .children()
+            .find(|n| n.has_tag_name("head"))
+            .and_then(|head| head.children().find(|n| n.has_tag_name("title")))
+            .and_then(|title| title.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        let body = opml
+            .children()
+            .find(|n| n.has_tag_name("body"))
+            .ok_or("Invalid OPML: missing <body>")?;
+
+        let roots: Vec<_> = body.children().filter(is_outline).collect();
+        let root = match roots.as_slice() {
+            [root] => *root,
+            [] => return Err("OPML body has no outlines".to_string()),
+            _ => {
+                return Err(format!(
+                    "OPML body must have exactly one top-level outline (the root question), found {}",
+                    roots.len()
+                ))
+            }
+        };
+
+        let mut builder = TreeBuilder::default();
+        let root_index = add_outline(&mut builder, root)?;
+
+        let name = target
+            .name
+            .clone()
+            .or(title)
+            .unwrap_or_else(|| target.category.clone());
+
+        Ok(builder.finish(root_index, name, target))
+    }
+}
+
+fn is_outline(node: &roxmltree::Node) -> bool {
+    node.has_tag_name("outline")
+}
+
+fn outline_text(outline: roxmltree::Node) -> Result<String, String> {
+    outline
+        .attribute("text")
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            let pos = outline.document().text_pos_at(outline.range().start);
+            format!("Outline at line {} has no text", pos.row)
+        })
+}
+
+fn add_outline(builder: &mut TreeBuilder, outline: roxmltree::Node) -> Result<usize, String> {
+    let index = builder.add_node(outline_text(outline)?);
+
+    for answer in outline.children().filter(is_outline) {
+        let (label, next) = match answer.attribute("label").map(str::trim) {
+            Some(label) if !label.is_empty() => (label.to_string(), answer),
+            _ => {
+                let mut steps = answer.children().filter(is_outline);
+                match (steps.next(), steps.next()) {
+                    (Some(next), None) => (outline_text(answer)?, next),
+                    _ => {
+                        return Err(format!(
+                            "Answer '{}' needs a label attribute or exactly one child outline for the next step",
+                            outline_text(answer)?
+                        ))
+                    }
+                }
+            }
+        };
+
+        let next_index = add_outline(builder, next)?;
+        builder.connect(index, next_index, label);
+    }
+
+    Ok(index)
+}
+
+/// Lucidchart "shape data" CSV export
+///
+/// Shape rows become nodes (their `Text Area 1`), line rows (`Line Source` and
+/// `Line Destination` set) become connections labelled with the line text.
+/// Shapes without any lines, such as title boxes, are ignored. The root is the
+/// one shape that no line points to.
+pub struct LucidchartCsvAdapter;
+
+/// Lucidchart rows that describe the document rather than a shape
+const LUCIDCHART_META_ROWS: &[&str] = &["document", "page"];
+
+impl ImportAdapter for LucidchartCsvAdapter {
+    fn format(&self) -> &'static str {
+        "lucidchart-csv"
+    }
+
+    fn convert(&self, input: &str, target: &AdapterTarget) -> Result<IssueExportData, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(input.as_bytes());
+
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Invalid CSV: {}", e))?
+            .clone();
+        let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+
+        let id_col = column("Id").ok_or("CSV is missing the 'Id' column")?;
+        let text_col = column("Text Area 1").ok_or("CSV is missing the 'Text Area 1' column")?;
+        let name_col = column("Name");
+        let source_col = column("Line Source");
+        let dest_col = column("Line Destination");
+
+        let mut shapes: Vec<(String, String)> = Vec::new();
+        let mut lines: Vec<(String, String, String, String)> = Vec::new();
+
+        for (row, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| format!("Invalid CSV at row {}: {}", row + 2, e))?;
+            let get = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("").to_string();
+
+            let id = get(Some(id_col));
+            let text = get(Some(text_col));
+            let source = get(source_col);
+            let dest = get(dest_col);
+
+            if !source.is_empty() && !dest.is_empty() {
+                lines.push((id, source, dest, text));
+            } else if id.is_empty()
+                || text.is_empty()
+                || LUCIDCHART_META_ROWS.contains(&get(name_col).to_lowercase().as_str())
+            {
+                continue;
+            } else {
+                shapes.push((id, text));
+            }
+        }
+
+        let shape_ids: HashSet<&str> = shapes.iter().map(|(id, _)| id.as_str()).collect();
+        for (line_id, source, dest, text) in &lines {
+            if !shape_ids.contains(source.as_str()) || !shape_ids.contains(dest.as_str()) {
+                return Err(format!("Line {} connects to a shape without text or that does not exist", line_id));
+            }
+            if text.is_empty() {
+                return Err(format!("Line {} has no label; every answer needs text", line_id));
+            }
+        }
+
+        let linked: HashSet<&str> = lines
+            .iter()
+            .flat_map(|(_, source, dest, _)| [source.as_str(), dest.as_str()])
+            .collect();
+        let targets: HashSet<&str> = lines.iter().map(|(_, _, dest, _)| dest.as_str()).collect();
+
+        let mut builder = TreeBuilder::default();
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        let mut roots = Vec::new();
+
+        for (id, text) in &shapes {
+            // A lone shape is a single-question tree; otherwise skip unconnected decoration
+            if shapes.len() > 1 && !linked.contains(id.as_str()) {
+                continue;
+            }
+            let index = builder.add_node(text.clone());
+            indices.insert(id.as_str(), index);
+            if !targets.contains(id.as_str()) {
+                roots.push(index);
+            }
+        }
+
+        let root = match roots.as_slice() {
+            [root] => *root,
+            [] if builder.nodes.is_empty() => return Err("CSV contains no shapes with text".to_string()),
+            [] => return Err("Every shape has an incoming line, so there is no root question".to_string()),
+            _ => {
+                return Err(format!(
+                    "Expected one root shape (no incoming lines), found {}",
+                    roots.len()
+                ))
+            }
+        };
+
+        for (_, source, dest, text) in &lines {
+            builder.connect(indices[source.as_str()], indices[dest.as_str()], text.clone());
+        }
+
+        let name = target.name.clone().unwrap_or_else(|| target.category.clone());
+        Ok(builder.finish(root, name, target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> AdapterTarget {
+        AdapterTarget {
+            category: "pump".to_string(),
+            name: None,
+            display_category: None,
+        }
+    }
+
+    #[test]
+    fn test_adapter_lookup() {
+        assert_eq!(adapter_for("OPML").unwrap().format(), "opml");
+        assert_eq!(adapter_for("lucidchart-csv").unwrap().format(), "lucidchart-csv");
+        assert_eq!(adapter_for(" CSV ").unwrap().format(), "csv");
+        assert!(adapter_for("visio").is_none());
+    }
+
+    #[test]
+    fn test_opml_answer_styles() {
+        let opml = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Pump Troubleshooting</title></head>
+  <body>
+    <outline text="Is the pump running?">
+      <outline text="Yes">
+        <outline text="Check the discharge valve"/>
+      </outline>
+      <outline text="Is it plugged in?" label="No">
+        <outline text="Yes"><outline text="Replace the motor"/></outline>
+        <outline text="Plug it in" label="No"/>
+      </outline>
+    </outline>
+  </body>
+</opml>"#;
+
+        let data = OpmlAdapter.convert(opml, &target()).unwrap();
+
+        assert_eq!(data.issue.name, "Pump Troubleshooting");
+        assert_eq!(data.issue.root_question_text, "Is the pump running?");
+        assert_eq!(data.nodes.len(), 5);
+        assert_eq!(data.nodes[0].semantic_id.as_deref(), Some("pump_start"));
+        assert_eq!(data.nodes[0].node_type, "question");
+        assert_eq!(data.nodes[1].node_type, "conclusion");
+
+        let labels: Vec<(&str, i32)> = data
+            .connections
+            .iter()
+            .filter(|c| c.from_node_index == 0)
+            .map(|c| (c.label.as_str(), c.order_index))
+            .collect();
+        assert_eq!(labels, vec![("Yes", 0), ("No", 1)]);
+    }
+
+    #[test]
+    fn test_opml_rejects_ambiguous_answers() {
+        let opml = r#"<opml><body><outline text="Q?"><outline text="Yes"/></outline></body></opml>"#;
+        assert!(OpmlAdapter.convert(opml, &target()).is_err());
+
+        let two_roots = r#"<opml><body><outline text="A"/><outline text="B"/></body></opml>"#;
+        assert!(OpmlAdapter.convert(two_roots, &target()).is_err());
+    }
+
+    #[test]
+    fn test_lucidchart_csv() {
+        let csv = "Id,Name,Shape Library,Page ID,Line Source,Line Destination,Text Area 1\n\
+                   1,Document,,,,,Pump\n\
+                   2,Page,,,,,Page 1\n\
+                   3,Process,Flowchart,2,,,Is the pump running?\n\
+                   4,Process,Flowchart,2,,,Check the valve\n\
+                   5,Process,Flowchart,2,,,Replace the motor\n\
+                   6,Text,Standard,2,,,Legend\n\
+                   7,Line,,2,3,4,Yes\n\
+                   8,Line,,2,3,5,No\n";
+
+        let data = LucidchartCsvAdapter.convert(csv, &target()).unwrap();
+
+        assert_eq!(data.issue.name, "pump");
+        assert_eq!(data.nodes.len(), 3);
+        assert_eq!(data.issue.root_question_text, "Is the pump running?");
+        assert_eq!(data.nodes[0].semantic_id.as_deref(), Some("pump_start"));
+        assert_eq!(data.connections.len(), 2);
+        assert_eq!(data.connections[1].label, "No");
+        assert_eq!(data.connections[1].order_index, 1);
+    }
+
+    #[test]
+    fn test_lucidchart_csv_requires_labels() {
+        let csv = "Id,Name,Line Source,Line Destination,Text Area 1\n\
+                   1,Process,,,Question?\n\
+                   2,Process,,,Answer\n\
+                   3,Line,1,2,\n";
+        assert!(LucidchartCsvAdapter.convert(csv, &target()).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+/// Approval of issue drafts before publishing
+///
+/// Editors submit a draft for review; someone holding `issues:approve` approves
+/// it (publishing the draft) or rejects it with a comment. A draft that changed
+/// since it was submitted has to be submitted again before it can be approved.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Longest submission or decision comment
+pub const COMMENT_MAX_LENGTH: usize = 5_000;
+
+/// Where a submission stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum ApprovalStatus {
+    /// Waiting for a reviewer
+    Pending,
+    /// Approved and published
+    Approved,
+    /// Sent back with a comment
+    Rejected,
+    /// Taken back by the submitter, or the draft was discarded
+    Withdrawn,
+}
+
+/// A draft submitted for review
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueApproval {
+    pub id: Uuid,
+    pub category: String,
+    pub status: ApprovalStatus,
+    /// Submitter's note to the reviewer
+    pub comment: Option<String>,
+    pub submitted_by: Option<Uuid>,
+    /// Email of the submitter (null once the account is gone)
+    pub submitted_by_email: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+    pub decided_by: Option<Uuid>,
+    /// Email of the reviewer (null until decided or once the account is gone)
+    pub decided_by_email: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    /// Reviewer's reason for the decision
+    pub decision_comment: Option<String>,
+    /// Version the approved draft was published as
+    pub version: Option<i32>,
+}
+
+const APPROVAL_COLUMNS: &str = "a.id, a.category, a.status, a.comment, a.submitted_by, s.email AS submitted_by_email,
+     a.submitted_at, a.decided_by, d.email AS decided_by_email, a.decided_at, a.decision_comment, a.version";
+
+const APPROVAL_JOINS: &str = "issue_approvals a
+     LEFT JOIN users s ON s.id = a.submitted_by
+     LEFT JOIN users d ON d.id = a.decided_by";
+
+/// Trimmed comment, with blank treated as none
+pub fn normalize_comment(comment: Option<&str>) -> Result<Option<String>, String> {
+    match comment.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(comment) if comment.chars().count() > COMMENT_MAX_LENGTH => {
+            Err(format!("Must be at most {} characters", COMMENT_MAX_LENGTH))
+        }
+        comment => Ok(comment.map(str::to_string)),
+    }
+}
+
+/// Hash of a draft's nodes and the answers leaving them, ignoring layout and bookkeeping columns
+pub async fn fingerprint(conn: &mut PgConnection, draft_category: &str) -> Result<String, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT md5(
+            COALESCE((
+                SELECT jsonb_agg(to_jsonb(n) - 'position_x' - 'position_y' - 'updated_at' - 'last_reviewed_at' - 'last_reviewed_by'
+                                 ORDER BY n.id)::text
+                FROM nodes n WHERE n.category = $1
+            ), '')
+            || COALESCE((
+                SELECT jsonb_agg(to_jsonb(c) - 'updated_at' ORDER BY c.id)::text
+                FROM connections c JOIN nodes n ON n.id = c.from_node_id
+                WHERE n.category = $1
+            ), '')
+         )",
+    )
+    .bind(draft_category)
+    .fetch_one(conn)
+    .await
+}
+
+/// The issue's pending submission and the draft fingerprint it was submitted with
+pub async fn pending(conn: &mut PgConnection, category: &str) -> Result<Option<(IssueApproval, String)>, sqlx::Error> {
+    let Some((id, fingerprint)) = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, draft_fingerprint FROM issue_approvals WHERE category = $1 AND status = 'pending'",
+    )
+    .bind(category)
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some((get(conn, id).await?, fingerprint)))
+}
+
+/// One submission
+pub async fn get(conn: &mut PgConnection, id: Uuid) -> Result<IssueApproval, sqlx::Error> {
+    sqlx::query_as::<_, IssueApproval>(&format!("SELECT {} FROM {} WHERE a.id = $1", APPROVAL_COLUMNS, APPROVAL_JOINS))
+        .bind(id)
+        .fetch_one(conn)
+        .await
+}
+
+/// Submissions newest first, optionally for one issue and/or in one status
+pub async fn list(db: &PgPool, category: Option<&str>, status: Option<ApprovalStatus>) -> Result<Vec<IssueApproval>, sqlx::Error> {
+    sqlx::query_as::<_, IssueApproval>(&format!(
+        "SELECT {} FROM {}
+         WHERE ($1::varchar IS NULL OR a.category = $1)
+           AND ($2::varchar IS NULL OR a.status = $2)
+         ORDER BY a.submitted_at DESC",
+        APPROVAL_COLUMNS, APPROVAL_JOINS
+    ))
+    .bind(category)
+    .bind(status)
+    .fetch_all(db)
+    .await
+}
+
+/// Submit the issue's draft for approval; None when a submission is already pending
+pub async fn submit(
+    conn: &mut PgConnection,
+    category: &str,
+    draft_category: &str,
+    comment: Option<&str>,
+    submitted_by: Uuid,
+) -> Result<Option<IssueApproval>, sqlx::Error> {
+    let fingerprint = fingerprint(conn, draft_category).await?;
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO issue_approvals (category, comment, draft_fingerprint, submitted_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (category) WHERE status = 'pending' DO NOTHING
+         RETURNING id",
+    )
+    .bind(category)
+    .bind(comment)
+    .bind(&fingerprint)
+    .bind(submitted_by)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match id {
+        Some(id) => Ok(Some(get(conn, id).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Close a pending submission; None when it was no longer pending
+pub async fn close(
+    conn: &mut PgConnection,
+    id: Uuid,
+    status: ApprovalStatus,
+    closed_by: Uuid,
+    comment: Option<&str>,
+    version: Option<i32>,
+) -> Result<Option<IssueApproval>, sqlx::Error> {
+    let closed = sqlx::query(
+        "UPDATE issue_approvals
+         SET status = $2, decided_by = $3, decided_at = NOW(), decision_comment = $4, version = $5
+         WHERE id = $1 AND status = 'pending'",
+    )
+    .bind(id)
+    .bind(status)
+    .bind(closed_by)
+    .bind(comment)
+    .bind(version)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    if closed == 0 {
+        return Ok(None);
+    }
+    Ok(Some(get(conn, id).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_comment() {
+        assert_eq!(normalize_comment(None).unwrap(), None);
+        assert_eq!(normalize_comment(Some("  ")).unwrap(), None);
+        assert_eq!(
+            normalize_comment(Some(" Step 4 needs a lockout warning ")).unwrap().as_deref(),
+            Some("Step 4 needs a lockout warning")
+        );
+        assert!(normalize_comment(Some(&"c".repeat(COMMENT_MAX_LENGTH + 1))).is_err());
+    }
+
+    #[test]
+    fn test_review_status_serializes_lowercase() {
+        assert_eq!(serde_json::to_value(ApprovalStatus::Withdrawn).unwrap(), "withdrawn");
+        let status: ApprovalStatus = serde_json::from_value(serde_json::json!("pending")).unwrap();
+        assert_eq!(status, ApprovalStatus::Pending);
+    }
+}
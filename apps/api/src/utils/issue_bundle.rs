@@ -0,0 +1,250 @@
+/// ZIP bundles of an issue and its attachments
+///
+/// A bundle holds `issue.json` (the JSON export), `manifest.json` (attachment
+/// list with SHA-256s) and the files under `attachments/`. Reading verifies the
+/// checksums and caps how much is inflated.
+use crate::routes::issues::IssueExportData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bundle layout version written to the manifest
+pub const FORMAT_VERSION: u32 = 1;
+/// Largest bundle accepted for import (request body limit)
+pub const MAX_BUNDLE_BYTES: usize = 100 * 1024 * 1024;
+/// Most attachments in one bundle
+pub const MAX_ATTACHMENTS: usize = 500;
+/// Largest `issue.json` or `manifest.json` inflated
+const MAX_JSON_BYTES: u64 = 20 * 1024 * 1024;
+
+const ISSUE_FILE: &str = "issue.json";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// An attachment listed in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleAttachment {
+    /// Index of the node in `issue.json` the file is attached to
+    pub node_index: usize,
+    pub filename: String,
+    pub content_type: String,
+    /// Path of the file in the archive
+    pub path: String,
+    /// Hex SHA-256 of the file
+    pub sha256: String,
+}
+
+/// `manifest.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub category: String,
+    pub name: String,
+    pub attachments: Vec<BundleAttachment>,
+}
+
+/// A file to attach to a node, as written to or read from a bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentFile {
+    pub node_index: usize,
+    pub filename: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A bundle's contents
+#[derive(Debug)]
+pub struct IssueBundle {
+    pub data: IssueExportData,
+    pub exported_at: DateTime<Utc>,
+    pub attachments: Vec<AttachmentFile>,
+}
+
+/// Write an exported issue and its attachments as a ZIP archive
+pub fn write_bundle(data: &IssueExportData, attachments: &[AttachmentFile]) -> Result<Vec<u8>, String> {
+    let write_err = |e: zip::result::ZipError| format!("Failed to write bundle: {}", e);
+    let io_err = |e: std::io::Error| format!("Failed to write bundle: {}", e);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Images and PDFs are compressed already
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+    let mut listed = Vec::with_capacity(attachments.len());
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (n, attachment) in attachments.iter().enumerate() {
+        let path = format!("attachments/{}/{}-{}", attachment.node_index, n + 1, attachment.filename);
+        zip.start_file(path.as_str(), stored).map_err(write_err)?;
+        zip.write_all(&attachment.bytes).map_err(io_err)?;
+        listed.push(BundleAttachment {
+            node_index: attachment.node_index,
+            filename: attachment.filename.clone(),
+            content_type: attachment.content_type.clone(),
+            path,
+            sha256: format!("{:x}", Sha256::digest(&attachment.bytes)),
+        });
+    }
+
+    let manifest = BundleManifest {
+        format_version: FORMAT_VERSION,
+        exported_at: Utc::now(),
+        category: data.issue.category.clone(),
+        name: data.issue.name.clone(),
+        attachments: listed,
+    };
+    let json_err = |e: serde_json::Error| format!("Failed to write bundle: {}", e);
+    zip.start_file(MANIFEST_FILE, deflated).map_err(write_err)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(json_err)?).map_err(io_err)?;
+    zip.start_file(ISSUE_FILE, deflated).map_err(write_err)?;
+    zip.write_all(&serde_json::to_vec_pretty(data).map_err(json_err)?).map_err(io_err)?;
+
+    Ok(zip.finish().map_err(write_err)?.into_inner())
+}
+
+/// Read a bundle, rejecting attachments larger than `max_attachment_bytes`
+pub fn read_bundle(bytes: &[u8], max_attachment_bytes: usize) -> Result<IssueBundle, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("Not a ZIP archive: {}", e))?;
+
+    let manifest: BundleManifest = serde_json::from_slice(&read_entry(&mut archive, MANIFEST_FILE, MAX_JSON_BYTES)?)
+        .map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?;
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported bundle version {} (expected {})",
+            manifest.format_version, FORMAT_VERSION
+        ));
+    }
+    if manifest.attachments.len() > MAX_ATTACHMENTS {
+        return Err(format!("Bundle has more than {} attachments", MAX_ATTACHMENTS));
+    }
+
+    let data: IssueExportData = serde_json::from_slice(&read_entry(&mut archive, ISSUE_FILE, MAX_JSON_BYTES)?)
+        .map_err(|e| format!("Invalid {}: {}", ISSUE_FILE, e))?;
+
+    let mut attachments = Vec::with_capacity(manifest.attachments.len());
+    for listed in manifest.attachments {
+        if listed.node_index >= data.nodes.len() {
+            return Err(format!(
+                "Attachment '{}' belongs to node {}, but the issue has {} nodes",
+                listed.path,
+                listed.node_index,
+                data.nodes.len()
+            ));
+        }
+        let bytes = read_entry(&mut archive, &listed.path, max_attachment_bytes as u64)?;
+        if format!("{:x}", Sha256::digest(&bytes)) != listed.sha256.to_lowercase() {
+            return Err(format!("Attachment '{}' does not match its checksum", listed.path));
+        }
+        attachments.push(AttachmentFile {
+            node_index: listed.node_index,
+            filename: listed.filename,
+            content_type: listed.content_type,
+            bytes,
+        });
+    }
+
+    Ok(IssueBundle {
+        data,
+        exported_at: manifest.exported_at,
+        attachments,
+    })
+}
+
+/// Inflate one file, failing once it grows past `limit` bytes
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str, limit: u64) -> Result<Vec<u8>, String> {
+    let file = archive.by_name(name).map_err(|_| format!("Bundle is missing '{}'", name))?;
+    let mut bytes = Vec::new();
+    file.take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read '{}': {}", name, e))?;
+    if bytes.len() as u64 > limit {
+        return Err(format!("'{}' is larger than {} bytes", name, limit));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::issues::{ConnectionExportData, IssueImportMetadata, NodeExportData};
+
+    fn node(node_type: &str, text: &str, semantic_id: Option<&str>) -> NodeExportData {
+        NodeExportData {
+            node_type: node_type.to_string(),
+            text: text.to_string(),
+            semantic_id: semantic_id.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn pump() -> IssueExportData {
+        IssueExportData {
+            issue: IssueImportMetadata {
+                name: "Pump won't start".to_string(),
+                category: "pump".to_string(),
+                display_category: None,
+                root_question_text: "Is the breaker on?".to_string(),
+                is_active: Some(true),
+                start_order_index: None,
+                description: Some("Centrifugal pumps".to_string()),
+                equipment_models: None,
+                tags: Some(vec!["electrical".to_string()]),
+            },
+            nodes: vec![
+                node("question", "Is the breaker on?", Some("pump_start")),
+                node("conclusion", "Reset the breaker", None),
+            ],
+            connections: vec![ConnectionExportData {
+                from_node_index: 0,
+                to_node_index: 1,
+                label: "No".to_string(),
+                order_index: 0,
+                condition: None,
+                unsure_route: false,
+            }],
+        }
+    }
+
+    fn wiring_diagram() -> AttachmentFile {
+        AttachmentFile {
+            node_index: 1,
+            filename: "wiring.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            bytes: b"%PDF-1.4 wiring".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let attachments = vec![wiring_diagram()];
+        let bytes = write_bundle(&pump(), &attachments).unwrap();
+        let bundle = read_bundle(&bytes, 1024).unwrap();
+
+        assert_eq!(bundle.data.issue.name, "Pump won't start");
+        assert_eq!(bundle.data.issue.tags, Some(vec!["electrical".to_string()]));
+        assert_eq!(bundle.data.nodes.len(), 2);
+        assert_eq!(bundle.data.connections[0].label, "No");
+        assert_eq!(bundle.attachments, attachments);
+    }
+
+    #[test]
+    fn test_oversized_attachment_rejected() {
+        let bytes = write_bundle(&pump(), &[wiring_diagram()]).unwrap();
+        let err = read_bundle(&bytes, 4).unwrap_err();
+        assert!(err.contains("larger than 4 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_attachment_for_missing_node_rejected() {
+        let mut attachment = wiring_diagram();
+        attachment.node_index = 7;
+        let bytes = write_bundle(&pump(), &[attachment]).unwrap();
+        let err = read_bundle(&bytes, 1024).unwrap_err();
+        assert!(err.contains("belongs to node 7"), "{}", err);
+    }
+
+    #[test]
+    fn test_not_a_zip() {
+        assert!(read_bundle(b"{\"issue\": {}}", 1024).unwrap_err().starts_with("Not a ZIP archive"));
+    }
+}
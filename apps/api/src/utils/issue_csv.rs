@@ -0,0 +1,340 @@
+/// Flat CSV format for issue trees
+///
+/// One row per answer: `node_id, type, text, answer_label, target`. Carries the
+/// tree only. Cells that spreadsheets would run as formulas are prefixed with
+/// `'` on export and unprefixed on import.
+use crate::routes::issues::{ConnectionExportData, IssueExportData, NodeExportData};
+use crate::utils::import_adapters::{AdapterTarget, ImportAdapter, TreeBuilder};
+use std::collections::{HashMap, HashSet};
+
+/// Column headers, in order
+pub const COLUMNS: [&str; 5] = ["node_id", "type", "text", "answer_label", "target"];
+
+/// Node types the format can carry
+const NODE_TYPES: [&str; 2] = ["question", "conclusion"];
+
+/// Leading characters that make spreadsheets treat a cell as a formula
+const FORMULA_TRIGGERS: [char; 4] = ['=', '+', '-', '@'];
+
+/// Whether a cell needs (or, read back, carries) the `'` guard: it starts with
+/// a formula trigger once any apostrophes in front are skipped, so text that
+/// really starts with `'=` survives the round trip too
+fn is_guarded(cell: &str) -> bool {
+    cell.trim_start_matches('\'').starts_with(FORMULA_TRIGGERS)
+}
+
+/// A cell as written: `'` in front of anything a spreadsheet would evaluate
+fn guard_cell(cell: &str) -> String {
+    if is_guarded(cell) {
+        format!("'{}", cell)
+    } else {
+        cell.to_string()
+    }
+}
+
+/// A cell as read: the guard added by `guard_cell` removed
+fn unguard_cell(cell: &str) -> &str {
+    match cell.strip_prefix('\'') {
+        Some(rest) if is_guarded(cell) => rest,
+        _ => cell,
+    }
+}
+
+/// Write an exported issue as CSV, start question first
+///
+/// Fails when the issue uses nodes the format can't describe.
+pub fn to_csv(data: &IssueExportData) -> Result<String, String> {
+    if let Some(node) = data.nodes.iter().find(|n| !NODE_TYPES.contains(&n.node_type.as_str())) {
+        return Err(format!(
+            "Node '{}' is a {} node, which CSV can't describe; export as JSON instead",
+            node.text, node.node_type
+        ));
+    }
+
+    let ids = node_ids(&data.nodes);
+    let start = data
+        .nodes
+        .iter()
+        .position(|n| n.semantic_id.as_deref().is_some_and(|s| s.ends_with("_start")))
+        .unwrap_or(0);
+    let order = std::iter::once(start).chain((0..data.nodes.len()).filter(|&i| i != start));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let write_err = |e: csv::Error| format!("Failed to write CSV: {}", e);
+    writer.write_record(COLUMNS).map_err(write_err)?;
+    for index in order {
+        let node = &data.nodes[index];
+        let mut answers: Vec<&ConnectionExportData> =
+            data.connections.iter().filter(|c| c.from_node_index == index).collect();
+        answers.sort_by_key(|c| c.order_index);
+
+        if answers.is_empty() {
+            writer
+                .write_record([ids[index].as_str(), &node.node_type, &node.text, "", ""].map(guard_cell))
+                .map_err(write_err)?;
+        }
+        for answer in answers {
+            writer
+                .write_record(
+                    [
+                        ids[index].as_str(),
+                        &node.node_type,
+                        &node.text,
+                        &answer.label,
+                        ids[answer.to_node_index].as_str(),
+                    ]
+                    .map(guard_cell),
+                )
+                .map_err(write_err)?;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(|e| format!("Failed to write CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+/// Row IDs for nodes: their semantic ID when unique, else `n1`, `n2`, ...
+fn node_ids(nodes: &[NodeExportData]) -> Vec<String> {
+    let taken: HashSet<&str> = nodes.iter().filter_map(|n| n.semantic_id.as_deref()).collect();
+    let mut used = HashSet::new();
+    let mut counter = 0;
+    nodes
+        .iter()
+        .map(|node| match node.semantic_id.as_deref() {
+            Some(semantic_id) if used.insert(semantic_id.to_string()) => semantic_id.to_string(),
+            _ => loop {
+                counter += 1;
+                let id = format!("n{}", counter);
+                if !taken.contains(id.as_str()) && used.insert(id.clone()) {
+                    break id;
+                }
+            },
+        })
+        .collect()
+}
+
+/// A node as described by the CSV rows carrying its ID
+struct CsvNode {
+    /// 1-based row number (counting the header) of the node's first row
+    row: usize,
+    node_type: Option<String>,
+    text: String,
+}
+
+/// The flat format written by `to_csv`
+pub struct CsvAdapter;
+
+impl ImportAdapter for CsvAdapter {
+    fn format(&self) -> &'static str {
+        "csv"
+    }
+
+    fn convert(&self, input: &str, target: &AdapterTarget) -> Result<IssueExportData, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(input.as_bytes());
+
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Invalid CSV: {}", e))?
+            .clone();
+        let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+        let required = |name: &str| column(name).ok_or_else(|| format!("CSV is missing the '{}' column", name));
+
+        let id_col = required("node_id")?;
+        let text_col = required("text")?;
+        let label_col = required("answer_label")?;
+        let target_col = required("target")?;
+        let type_col = column("type");
+
+        let mut order: Vec<String> = Vec::new();
+        let mut nodes: HashMap<String, CsvNode> = HashMap::new();
+        // (row, from, label, to)
+        let mut answers: Vec<(usize, String, String, String)> = Vec::new();
+
+        for (i, record) in reader.records().enumerate() {
+            let row = i + 2;
+            let record = record.map_err(|e| format!("Invalid CSV at row {}: {}", row, e))?;
+            let get = |col: Option<usize>| unguard_cell(col.and_then(|c| record.get(c)).unwrap_or("")).to_string();
+
+            let id = get(Some(id_col));
+            let text = get(Some(text_col));
+            let label = get(Some(label_col));
+            let to = get(Some(target_col));
+            let node_type = Some(get(type_col).to_lowercase()).filter(|t| !t.is_empty());
+
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            if id.is_empty() {
+                return Err(format!("Row {} has no node_id", row));
+            }
+            if let Some(node_type) = &node_type {
+                if !NODE_TYPES.contains(&node_type.as_str()) {
+                    return Err(format!(
+                        "Row {}: type must be one of {}, not '{}'",
+                        row,
+                        NODE_TYPES.join(", "),
+                        node_type
+                    ));
+                }
+            }
+
+            match nodes.get_mut(&id) {
+                None => {
+                    order.push(id.clone());
+                    nodes.insert(id.clone(), CsvNode { row, node_type, text });
+                }
+                Some(node) => {
+                    if !text.is_empty() && !node.text.is_empty() && text != node.text {
+                        return Err(format!(
+                            "Row {}: node '{}' already has different text on row {}",
+                            row, id, node.row
+                        ));
+                    }
+                    if node_type.is_some() && node.node_type.is_some() && node_type != node.node_type {
+                        return Err(format!(
+                            "Row {}: node '{}' already has a different type on row {}",
+                            row, id, node.row
+                        ));
+                    }
+                    if node.text.is_empty() {
+                        node.text = text;
+                    }
+                    node.node_type = node.node_type.take().or(node_type);
+                }
+            }
+
+            match (label.is_empty(), to.is_empty()) {
+                (true, true) => {}
+                (false, false) => answers.push((row, id, label, to)),
+                (true, false) => return Err(format!("Row {}: the answer to '{}' needs an answer_label", row, to)),
+                (false, true) => return Err(format!("Row {}: answer '{}' needs a target", row, label)),
+            }
+        }
+
+        if order.is_empty() {
+            return Err("CSV contains no nodes".to_string());
+        }
+        for id in &order {
+            let node = &nodes[id];
+            if node.text.is_empty() {
+                return Err(format!("Row {}: node '{}' has no text", node.row, id));
+            }
+        }
+
+        let mut builder = TreeBuilder::default();
+        let indices: HashMap<&str, usize> = order
+            .iter()
+            .map(|id| (id.as_str(), builder.add_node(nodes[id].text.clone())))
+            .collect();
+        for (row, from, label, to) in &answers {
+            let to_index = *indices
+                .get(to.as_str())
+                .ok_or_else(|| format!("Row {}: target '{}' is not a node_id in the CSV", row, to))?;
+            builder.connect(indices[from.as_str()], to_index, label.clone());
+        }
+
+        let name = target.name.clone().unwrap_or_else(|| target.category.clone());
+        let mut data = builder.finish(0, name, target);
+
+        // Explicit types win over the shape of the tree
+        for (node, id) in data.nodes.iter_mut().zip(&order) {
+            if let Some(node_type) = &nodes[id].node_type {
+                node.node_type = node_type.clone();
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> AdapterTarget {
+        AdapterTarget {
+            category: "pump".to_string(),
+            name: Some("Pump won't start".to_string()),
+            display_category: None,
+        }
+    }
+
+    const PUMP_CSV: &str = "node_id,type,text,answer_label,target\n\
+        pump_start,question,Is the breaker on?,Yes,motor\n\
+        pump_start,question,Is the breaker on?,No,reset\n\
+        motor,question,\"Does the motor hum, but not turn?\",Yes,capacitor\n\
+        motor,,,No,capacitor\n\
+        reset,conclusion,Reset the breaker,,\n\
+        capacitor,conclusion,Replace the start capacitor,,\n";
+
+    #[test]
+    fn test_convert() {
+        let data = CsvAdapter.convert(PUMP_CSV, &target()).unwrap();
+
+        assert_eq!(data.issue.name, "Pump won't start");
+        assert_eq!(data.issue.root_question_text, "Is the breaker on?");
+        let texts: Vec<&str> = data.nodes.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            ["Is the breaker on?", "Does the motor hum, but not turn?", "Reset the breaker", "Replace the start capacitor"]
+        );
+        assert_eq!(data.nodes[0].semantic_id.as_deref(), Some("pump_start"));
+        assert_eq!(data.nodes[2].node_type, "conclusion");
+        assert_eq!(data.connections.len(), 4);
+        assert_eq!(
+            (data.connections[1].from_node_index, data.connections[1].to_node_index, data.connections[1].label.as_str()),
+            (0, 2, "No")
+        );
+        assert_eq!(data.connections[1].order_index, 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = CsvAdapter.convert(PUMP_CSV, &target()).unwrap();
+        let csv = to_csv(&data).unwrap();
+
+        assert!(csv.starts_with("node_id,type,text,answer_label,target\npump_start,question,Is the breaker on?,Yes,n1\n"));
+        let again = CsvAdapter.convert(&csv, &target()).unwrap();
+        assert_eq!(again.nodes.len(), data.nodes.len());
+        assert_eq!(again.connections.len(), data.connections.len());
+        assert_eq!(again.nodes[1].text, "Does the motor hum, but not turn?");
+    }
+
+    #[test]
+    fn test_formula_cells_guarded() {
+        let csv = "node_id,type,text,answer_label,target\n\
+            start,question,=HYPERLINK(\"http://x\"),-5 psi,done\n\
+            done,conclusion,'=literal,,\n";
+        let data = CsvAdapter.convert(csv, &target()).unwrap();
+        assert_eq!(data.nodes[0].text, "=HYPERLINK(\"http://x\")");
+        assert_eq!(data.nodes[1].text, "=literal");
+
+        let exported = to_csv(&data).unwrap();
+        assert!(exported.contains(",\"'=HYPERLINK(\"\"http://x\"\")\",'-5 psi,"), "{}", exported);
+        assert!(exported.contains(",'=literal,"), "{}", exported);
+
+        let again = CsvAdapter.convert(&exported, &target()).unwrap();
+        assert_eq!(again.nodes[0].text, data.nodes[0].text);
+        assert_eq!(again.connections[0].label, "-5 psi");
+        assert_eq!(again.nodes[1].text, "=literal");
+
+        assert_eq!(guard_cell("'=x"), "''=x");
+        assert_eq!(unguard_cell("''=x"), "'=x");
+        assert_eq!(unguard_cell("'plain"), "'plain");
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        let convert = |csv: &str| CsvAdapter.convert(csv, &target()).unwrap_err();
+
+        assert!(convert("id,text\n").contains("'node_id'"));
+        assert!(convert("node_id,text,answer_label,target\na,Start?,Yes,b\n").contains("target 'b'"));
+        assert!(convert("node_id,text,answer_label,target\na,Start?,Yes,\n").contains("needs a target"));
+        assert!(convert("node_id,text,answer_label,target\na,Start?,Yes,b\nb,,,\n").contains("'b' has no text"));
+        assert!(convert("node_id,text,answer_label,target\na,Start?,Yes,b\na,Begin?,No,b\nb,Done,,\n")
+            .contains("different text"));
+        assert!(convert("node_id,type,text,answer_label,target\na,subflow,Start?,,\n").contains("type must be"));
+    }
+}
@@ -0,0 +1,511 @@
+/// Draft versions of issue graphs
+///
+/// A draft copies an issue into `{category}_draft`, which sessions can't reach.
+/// Publishing writes every copy back over its origin (`draft_origin_id`), so
+/// published IDs stay put and sessions, history and attachments keep working.
+use crate::error::{ApiError, ApiResult};
+use crate::models::Node;
+use crate::utils::issue_versions::{self, VersionSource};
+use crate::utils::{node_revisions, semantic_ids};
+use serde::Serialize;
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An issue's unpublished draft
+#[derive(Debug, Serialize, sqlx::FromRow, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueDraft {
+    pub category: String,
+    /// Category holding the draft graph; open it in the editor to make changes
+    pub draft_category: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[ts(type = "number")]
+    pub node_count: i64,
+}
+
+/// What publishing a draft changed in the issue
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct DraftPublished {
+    pub category: String,
+    /// Version the published graph was recorded as
+    pub version: i32,
+    /// Published nodes the draft changed
+    #[ts(type = "number")]
+    pub nodes_updated: u64,
+    #[ts(type = "number")]
+    pub nodes_added: u64,
+    /// Published nodes the draft no longer has, now switched off
+    #[ts(type = "number")]
+    pub nodes_deactivated: u64,
+    /// Published answers rewritten from the draft
+    #[ts(type = "number")]
+    pub connections_updated: u64,
+    #[ts(type = "number")]
+    pub connections_added: u64,
+    #[ts(type = "number")]
+    pub connections_deactivated: u64,
+}
+
+/// A published answer (`o`) differs from its draft copy (`d`, endpoints mapped through `f`/`t`)
+const CONNECTION_CHANGED: &str =
+    "(o.from_node_id, o.to_node_id, o.label, o.order_index, o.weight, o.condition, o.unsure_route, o.is_active)
+     IS DISTINCT FROM
+     (COALESCE(f.live_id, d.from_node_id), COALESCE(t.live_id, d.to_node_id), d.label, d.order_index, d.weight, d.condition, d.unsure_route, d.is_active)";
+
+/// Category a draft of `category` is kept in
+pub fn draft_category_for(category: &str) -> String {
+    format!("{}_draft", category)
+}
+
+/// Semantic ID a draft node takes when published: the draft's start becomes the issue's start
+fn published_semantic_id(semantic_id: &str, category: &str, draft_category: &str) -> String {
+    if semantic_id == format!("{}_start", draft_category) {
+        format!("{}_start", category)
+    } else {
+        semantic_id.to_string()
+    }
+}
+
+/// The issue's draft, if it has one
+pub async fn find(conn: &mut PgConnection, category: &str) -> Result<Option<IssueDraft>, sqlx::Error> {
+    sqlx::query_as::<_, IssueDraft>(
+        "SELECT d.category, d.draft_category, d.created_by, d.created_at,
+                (SELECT COUNT(*) FROM nodes n WHERE n.category = d.draft_category) AS node_count
+         FROM issue_drafts d
+         WHERE d.category = $1",
+    )
+    .bind(category)
+    .fetch_optional(conn)
+    .await
+}
+
+/// The published category `category` is a draft of, if it is one
+pub async fn draft_of(conn: &mut PgConnection, category: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>("SELECT category FROM issue_drafts WHERE draft_category = $1")
+        .bind(category)
+        .fetch_optional(conn)
+        .await
+}
+
+/// Copy an issue's nodes and answers into a new draft category
+///
+/// The caller checks the issue exists and has no draft yet.
+pub async fn create(conn: &mut PgConnection, category: &str, created_by: Uuid) -> ApiResult<IssueDraft> {
+    let draft_category = draft_category_for(category);
+    let taken = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE category = $1)")
+        .bind(&draft_category)
+        .fetch_one(&mut *conn)
+        .await?;
+    if taken {
+        return Err(ApiError::Conflict {
+            message: format!("Category '{}' already exists, so the draft can't be kept there", draft_category),
+        });
+    }
+
+    let nodes = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        "SELECT id, semantic_id FROM nodes WHERE category = $1 ORDER BY created_at, id",
+    )
+    .bind(category)
+    .fetch_all(&mut *conn)
+    .await?;
+    let old_ids: Vec<Uuid> = nodes.iter().map(|(id, _)| *id).collect();
+    let new_ids: Vec<Uuid> = nodes.iter().map(|_| Uuid::new_v4()).collect();
+    let start = format!("{}_start", category);
+    let semantic_ids: Vec<Option<String>> = nodes
+        .iter()
+        .map(|(_, semantic_id)| {
+            semantic_id.as_ref().map(|s| if *s == start { format!("{}_start", draft_category) } else { s.clone() })
+        })
+        .collect();
+
+    sqlx::query(
+        "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, draft_origin_id, created_at)
+         SELECT m.new_id, $4, n.node_type, n.text, m.semantic_id, n.display_category, n.position_x, n.position_y, n.kb_url, n.doc_reference, n.option_order, n.answer_mode, COALESCE(ns.new_id, n.not_sure_node_id), n.subflow_category, n.content, n.is_active, n.id, n.created_at
+         FROM UNNEST($1::uuid[], $2::uuid[], $3::varchar[]) AS m(old_id, new_id, semantic_id)
+         JOIN nodes n ON n.id = m.old_id
+         LEFT JOIN UNNEST($1::uuid[], $2::uuid[]) AS ns(old_id, new_id) ON ns.old_id = n.not_sure_node_id",
+    )
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .bind(&semantic_ids)
+    .bind(&draft_category)
+    .execute(&mut *conn)
+    .await?;
+
+    // Answers leaving the issue keep their targets
+    sqlx::query(
+        "INSERT INTO connections (from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, draft_origin_id)
+         SELECT f.new_id, COALESCE(t.new_id, c.to_node_id), c.label, c.order_index, c.weight, c.condition, c.unsure_route, c.is_active, c.id
+         FROM connections c
+         JOIN UNNEST($1::uuid[], $2::uuid[]) AS f(old_id, new_id) ON f.old_id = c.from_node_id
+         LEFT JOIN UNNEST($1::uuid[], $2::uuid[]) AS t(old_id, new_id) ON t.old_id = c.to_node_id
+         ORDER BY c.from_node_id, c.order_index, c.created_at",
+    )
+    .bind(&old_ids)
+    .bind(&new_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query("INSERT INTO issue_drafts (category, draft_category, created_by) VALUES ($1, $2, $3)")
+        .bind(category)
+        .bind(&draft_category)
+        .bind(created_by)
+        .execute(&mut *conn)
+        .await?;
+
+    find(conn, category)
+        .await?
+        .ok_or_else(|| ApiError::internal("Draft vanished while it was created"))
+}
+
+/// Delete an issue's draft with its nodes and answers
+///
+/// Returns the deleted draft nodes (so their attachments can be removed once
+/// the transaction commits), or None when the issue had no draft.
+pub async fn discard(conn: &mut PgConnection, category: &str) -> Result<Option<Vec<Uuid>>, sqlx::Error> {
+    let Some(draft_category) = sqlx::query_scalar::<_, String>(
+        "DELETE FROM issue_drafts WHERE category = $1 RETURNING draft_category",
+    )
+    .bind(category)
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "DELETE FROM connections
+         WHERE from_node_id IN (SELECT id FROM nodes WHERE category = $1)
+            OR to_node_id IN (SELECT id FROM nodes WHERE category = $1)",
+    )
+    .bind(&draft_category)
+    .execute(&mut *conn)
+    .await?;
+    let node_ids = sqlx::query_scalar::<_, Uuid>("DELETE FROM nodes WHERE category = $1 RETURNING id")
+        .bind(&draft_category)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    Ok(Some(node_ids))
+}
+
+/// Write an issue's draft over its published graph and delete the draft
+///
+/// Every published node that changes gets a revision by `published_by`, and
+/// the published graph is recorded as the issue's next version. The caller
+/// checks the draft exists and validates its graph.
+pub async fn publish(
+    conn: &mut PgConnection,
+    category: &str,
+    draft_category: &str,
+    published_by: Uuid,
+) -> ApiResult<DraftPublished> {
+    // Draft nodes with the published node each replaces (None: added in the draft)
+    let draft_nodes = sqlx::query_as::<_, (Uuid, Option<Uuid>, Option<String>)>(
+        "SELECT d.id, o.id, d.semantic_id
+         FROM nodes d
+         LEFT JOIN nodes o ON o.id = d.draft_origin_id AND o.category = $2
+         WHERE d.category = $1
+         ORDER BY d.created_at, d.id",
+    )
+    .bind(draft_category)
+    .bind(category)
+    .fetch_all(&mut *conn)
+    .await?;
+    if draft_nodes.is_empty() {
+        return Err(ApiError::bad_request("The draft has no nodes; discard it instead"));
+    }
+
+    let semantic_ids: Vec<Option<String>> = draft_nodes
+        .iter()
+        .map(|(_, _, semantic_id)| semantic_id.as_deref().map(|s| published_semantic_id(s, category, draft_category)))
+        .collect();
+    let start = format!("{}_start", category);
+    if !semantic_ids.iter().any(|s| s.as_deref() == Some(start.as_str())) {
+        return Err(ApiError::bad_request(format!(
+            "The draft has no start node ({}_start)",
+            draft_category
+        )));
+    }
+    let repeated = semantic_ids::find_duplicates(semantic_ids.iter().map(Option::as_deref));
+    if !repeated.is_empty() {
+        return Err(ApiError::validation(
+            repeated
+                .iter()
+                .map(|&i| {
+                    (
+                        "semantic_id".to_string(),
+                        format!(
+                            "Draft node {} would repeat semantic ID '{}'",
+                            draft_nodes[i].0,
+                            semantic_ids[i].as_deref().unwrap_or_default()
+                        ),
+                    )
+                })
+                .collect(),
+        ));
+    }
+
+    issue_versions::record_baseline(conn, category, published_by).await?;
+
+    let draft_ids: Vec<Uuid> = draft_nodes.iter().map(|(id, _, _)| *id).collect();
+    let live_ids: Vec<Uuid> = draft_nodes.iter().map(|(id, origin, _)| origin.unwrap_or(*id)).collect();
+    let replaced: Vec<Uuid> = draft_nodes.iter().filter(|(_, origin, _)| origin.is_some()).map(|(id, _, _)| *id).collect();
+    let nodes_added = (draft_nodes.len() - replaced.len()) as u64;
+
+    // Draft answers with the published answer each replaces, read before any node moves
+    let draft_connections = sqlx::query_as::<_, (Uuid, Option<Uuid>)>(
+        "SELECT c.id, o.id
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id AND f.category = $1
+         LEFT JOIN connections o ON o.id = c.draft_origin_id
+             AND o.from_node_id IN (SELECT id FROM nodes WHERE category = $2)",
+    )
+    .bind(draft_category)
+    .bind(category)
+    .fetch_all(&mut *conn)
+    .await?;
+    let published_connections = sqlx::query_scalar::<_, Uuid>(
+        "SELECT c.id FROM connections c JOIN nodes f ON f.id = c.from_node_id WHERE f.category = $1 FOR UPDATE OF c",
+    )
+    .bind(category)
+    .fetch_all(&mut *conn)
+    .await?;
+    let connection_draft_ids: Vec<Uuid> = draft_connections.iter().map(|(id, _)| *id).collect();
+    let connection_live_ids: Vec<Uuid> = draft_connections.iter().map(|(id, origin)| origin.unwrap_or(*id)).collect();
+    let kept_connections: HashSet<Uuid> = draft_connections.iter().filter_map(|(_, origin)| *origin).collect();
+    let connections_added: Vec<Uuid> = draft_connections.iter().filter(|(_, origin)| origin.is_none()).map(|(id, _)| *id).collect();
+    let connections_dropped: Vec<Uuid> = published_connections.iter().copied().filter(|id| !kept_connections.contains(id)).collect();
+
+    let before = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE category = $1
+         FOR UPDATE",
+    )
+    .bind(category)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Unique semantic IDs and "I'm not sure" answers are checked row by row, so
+    // first free the ones that move to another record or go away
+    let published_ids: HashSet<&str> = semantic_ids.iter().flatten().map(String::as_str).collect();
+    let final_semantic_ids: HashMap<Uuid, Option<&str>> =
+        live_ids.iter().copied().zip(semantic_ids.iter().map(Option::as_deref)).collect();
+    let freed: Vec<Uuid> = before
+        .iter()
+        .filter(|node| {
+            node.semantic_id.as_deref().is_some_and(|current| {
+                published_ids.contains(current) && final_semantic_ids.get(&node.id).copied().flatten() != Some(current)
+            })
+        })
+        .map(|node| node.id)
+        .collect();
+    sqlx::query("UPDATE nodes SET semantic_id = NULL WHERE id = ANY($1)")
+        .bind(&freed)
+        .execute(&mut *conn)
+        .await?;
+
+    let connections_updated = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*)
+         FROM UNNEST($1::uuid[], $2::uuid[]) AS m(draft_id, live_id)
+         JOIN connections d ON d.id = m.draft_id
+         JOIN connections o ON o.id = m.live_id
+         LEFT JOIN UNNEST($3::uuid[], $4::uuid[]) AS f(draft_id, live_id) ON f.draft_id = d.from_node_id
+         LEFT JOIN UNNEST($3::uuid[], $4::uuid[]) AS t(draft_id, live_id) ON t.draft_id = d.to_node_id
+         WHERE m.live_id <> m.draft_id AND {}",
+        CONNECTION_CHANGED
+    ))
+    .bind(&connection_draft_ids)
+    .bind(&connection_live_ids)
+    .bind(&draft_ids)
+    .bind(&live_ids)
+    .fetch_one(&mut *conn)
+    .await? as u64;
+    sqlx::query(
+        "UPDATE connections o SET unsure_route = false
+         WHERE o.id = ANY($5) AND o.unsure_route AND NOT EXISTS (
+             SELECT 1
+             FROM UNNEST($1::uuid[], $2::uuid[]) AS m(draft_id, live_id)
+             JOIN connections d ON d.id = m.draft_id
+             LEFT JOIN UNNEST($3::uuid[], $4::uuid[]) AS f(draft_id, live_id) ON f.draft_id = d.from_node_id
+             WHERE m.live_id = o.id AND d.unsure_route AND COALESCE(f.live_id, d.from_node_id) = o.from_node_id)",
+    )
+    .bind(&connection_draft_ids)
+    .bind(&connection_live_ids)
+    .bind(&draft_ids)
+    .bind(&live_ids)
+    .bind(&published_connections)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "UPDATE nodes o SET
+            node_type = d.node_type, text = d.text, semantic_id = m.semantic_id, display_category = d.display_category,
+            position_x = d.position_x, position_y = d.position_y, kb_url = d.kb_url, doc_reference = d.doc_reference,
+            option_order = d.option_order, answer_mode = d.answer_mode, not_sure_node_id = COALESCE(ns.live_id, d.not_sure_node_id),
+            subflow_category = d.subflow_category, content = d.content, is_active = d.is_active
+         FROM UNNEST($1::uuid[], $2::uuid[], $3::varchar[]) AS m(draft_id, live_id, semantic_id)
+         JOIN nodes d ON d.id = m.draft_id
+         LEFT JOIN UNNEST($1::uuid[], $2::uuid[]) AS ns(draft_id, live_id) ON ns.draft_id = d.not_sure_node_id
+         WHERE o.id = m.live_id AND m.live_id <> m.draft_id
+           AND (o.node_type, o.text, o.semantic_id, o.display_category, o.position_x, o.position_y, o.kb_url, o.doc_reference,
+                o.option_order, o.answer_mode, o.not_sure_node_id, o.subflow_category, o.content, o.is_active)
+               IS DISTINCT FROM
+               (d.node_type, d.text, m.semantic_id, d.display_category, d.position_x, d.position_y, d.kb_url, d.doc_reference,
+                d.option_order, d.answer_mode, COALESCE(ns.live_id, d.not_sure_node_id), d.subflow_category, d.content, d.is_active)",
+    )
+    .bind(&draft_ids)
+    .bind(&live_ids)
+    .bind(&semantic_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "UPDATE nodes d SET
+            category = $4, semantic_id = m.semantic_id, draft_origin_id = NULL,
+            not_sure_node_id = COALESCE(
+                (SELECT ns.live_id FROM UNNEST($1::uuid[], $2::uuid[]) AS ns(draft_id, live_id) WHERE ns.draft_id = d.not_sure_node_id),
+                d.not_sure_node_id)
+         FROM UNNEST($1::uuid[], $2::uuid[], $3::varchar[]) AS m(draft_id, live_id, semantic_id)
+         WHERE d.id = m.draft_id AND m.live_id = m.draft_id",
+    )
+    .bind(&draft_ids)
+    .bind(&live_ids)
+    .bind(&semantic_ids)
+    .bind(category)
+    .execute(&mut *conn)
+    .await?;
+
+    // Published nodes the draft dropped are switched off
+    let nodes_deactivated = sqlx::query(
+        "UPDATE nodes SET is_active = false WHERE category = $1 AND NOT (id = ANY($2)) AND is_active",
+    )
+    .bind(category)
+    .bind(&live_ids)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    sqlx::query(&format!(
+        "UPDATE connections o SET
+            from_node_id = COALESCE(f.live_id, d.from_node_id), to_node_id = COALESCE(t.live_id, d.to_node_id),
+            label = d.label, order_index = d.order_index, weight = d.weight, condition = d.condition,
+            unsure_route = d.unsure_route, is_active = d.is_active
+         FROM UNNEST($1::uuid[], $2::uuid[]) AS m(draft_id, live_id)
+         JOIN connections d ON d.id = m.draft_id
+         LEFT JOIN UNNEST($3::uuid[], $4::uuid[]) AS f(draft_id, live_id) ON f.draft_id = d.from_node_id
+         LEFT JOIN UNNEST($3::uuid[], $4::uuid[]) AS t(draft_id, live_id) ON t.draft_id = d.to_node_id
+         WHERE o.id = m.live_id AND m.live_id <> m.draft_id AND {}",
+        CONNECTION_CHANGED
+    ))
+    .bind(&connection_draft_ids)
+    .bind(&connection_live_ids)
+    .bind(&draft_ids)
+    .bind(&live_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "UPDATE connections c SET
+            from_node_id = COALESCE((SELECT m.live_id FROM UNNEST($2::uuid[], $3::uuid[]) AS m(draft_id, live_id) WHERE m.draft_id = c.from_node_id), c.from_node_id),
+            to_node_id = COALESCE((SELECT m.live_id FROM UNNEST($2::uuid[], $3::uuid[]) AS m(draft_id, live_id) WHERE m.draft_id = c.to_node_id), c.to_node_id),
+            draft_origin_id = NULL
+         WHERE c.id = ANY($1)",
+    )
+    .bind(&connections_added)
+    .bind(&draft_ids)
+    .bind(&live_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    let connections_deactivated = sqlx::query("UPDATE connections SET is_active = false WHERE id = ANY($1) AND is_active")
+        .bind(&connections_dropped)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    // The copies written back are no longer needed. Point anything else still
+    // referring to a node copy at its published node before the copies go.
+    let replacing: Vec<Uuid> = draft_connections.iter().filter(|(_, origin)| origin.is_some()).map(|(id, _)| *id).collect();
+    sqlx::query("DELETE FROM connections WHERE id = ANY($1)")
+        .bind(&replacing)
+        .execute(&mut *conn)
+        .await?;
+    let (copy_ids, origin_ids): (Vec<Uuid>, Vec<Uuid>) = draft_nodes
+        .iter()
+        .filter_map(|(id, origin, _)| origin.map(|origin| (*id, origin)))
+        .unzip();
+    for statement in [
+        "UPDATE connections c SET to_node_id = m.origin FROM UNNEST($1::uuid[], $2::uuid[]) AS m(copy, origin) WHERE c.to_node_id = m.copy",
+        "UPDATE connections c SET from_node_id = m.origin FROM UNNEST($1::uuid[], $2::uuid[]) AS m(copy, origin) WHERE c.from_node_id = m.copy",
+        "UPDATE nodes n SET not_sure_node_id = m.origin FROM UNNEST($1::uuid[], $2::uuid[]) AS m(copy, origin) WHERE n.not_sure_node_id = m.copy",
+        "UPDATE entry_points e SET node_id = m.origin FROM UNNEST($1::uuid[], $2::uuid[]) AS m(copy, origin) WHERE e.node_id = m.copy",
+        "UPDATE category_notices c SET conclusion_node_id = m.origin FROM UNNEST($1::uuid[], $2::uuid[]) AS m(copy, origin) WHERE c.conclusion_node_id = m.copy",
+        "UPDATE uploads u SET owner_id = m.origin::text FROM UNNEST($1::uuid[], $2::uuid[]) AS m(copy, origin) WHERE u.owner_type = 'node' AND u.owner_id = m.copy::text",
+    ] {
+        sqlx::query(statement).bind(&copy_ids).bind(&origin_ids).execute(&mut *conn).await?;
+    }
+    sqlx::query("DELETE FROM nodes WHERE id = ANY($1)")
+        .bind(&replaced)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM issue_drafts WHERE category = $1")
+        .bind(category)
+        .execute(&mut *conn)
+        .await?;
+
+    let after: HashMap<Uuid, Node> = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE id = ANY($1)",
+    )
+    .bind(before.iter().map(|node| node.id).collect::<Vec<_>>())
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|node| (node.id, node))
+    .collect();
+
+    let kept: HashSet<Uuid> = live_ids.iter().copied().collect();
+    let mut nodes_updated = 0;
+    for node in &before {
+        let Some(published) = after.get(&node.id) else {
+            continue;
+        };
+        let changes = node_revisions::diff(node, published);
+        if !changes.is_empty() && kept.contains(&node.id) {
+            nodes_updated += 1;
+        }
+        node_revisions::record(conn, node.id, published_by, &changes, None).await?;
+    }
+
+    let version = issue_versions::record(conn, category, VersionSource::Publish, None, published_by).await?;
+
+    Ok(DraftPublished {
+        category: category.to_string(),
+        version,
+        nodes_updated,
+        nodes_added,
+        nodes_deactivated,
+        connections_updated,
+        connections_added: connections_added.len() as u64,
+        connections_deactivated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_semantic_id() {
+        assert_eq!(published_semantic_id("pump_draft_start", "pump", "pump_draft"), "pump_start");
+        assert_eq!(published_semantic_id("pump_check_fuse", "pump", "pump_draft"), "pump_check_fuse");
+        assert_eq!(draft_category_for("pump"), "pump_draft");
+    }
+}
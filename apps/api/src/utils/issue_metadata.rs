@@ -0,0 +1,114 @@
+/// Issue metadata (name, description, owner, models, tags)
+///
+/// Checks return plain messages so both the issue endpoints and the import can
+/// report them against their own fields.
+use std::collections::HashSet;
+
+/// Longest issue name (and start question answer label)
+pub const NAME_MAX_LENGTH: usize = 255;
+/// Longest description
+pub const DESCRIPTION_MAX_LENGTH: usize = 10_000;
+/// Most tags on one issue
+pub const MAX_TAGS: usize = 20;
+/// Longest tag
+pub const TAG_MAX_LENGTH: usize = 50;
+/// Most equipment models on one issue
+pub const MAX_EQUIPMENT_MODELS: usize = 100;
+/// Longest equipment model
+pub const EQUIPMENT_MODEL_MAX_LENGTH: usize = 100;
+
+/// Trimmed issue name
+pub fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Must not be empty".to_string());
+    }
+    if name.chars().count() > NAME_MAX_LENGTH {
+        return Err(format!("Must be at most {} characters", NAME_MAX_LENGTH));
+    }
+    Ok(name.to_string())
+}
+
+/// Trimmed description, with blank treated as none
+pub fn normalize_description(description: Option<&str>) -> Result<Option<String>, String> {
+    match description.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(description) if description.chars().count() > DESCRIPTION_MAX_LENGTH => {
+            Err(format!("Must be at most {} characters", DESCRIPTION_MAX_LENGTH))
+        }
+        description => Ok(description.map(str::to_string)),
+    }
+}
+
+/// Trimmed, lowercase tags without blanks or repeats, in the order given
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    normalize_list(tags.iter().map(|tag| tag.trim().to_lowercase()), MAX_TAGS, TAG_MAX_LENGTH, "tags")
+}
+
+/// Trimmed equipment models without blanks or repeats (ignoring case), in the order given
+pub fn normalize_equipment_models(models: &[String]) -> Result<Vec<String>, String> {
+    normalize_list(
+        models.iter().map(|model| model.trim().to_string()),
+        MAX_EQUIPMENT_MODELS,
+        EQUIPMENT_MODEL_MAX_LENGTH,
+        "equipment models",
+    )
+}
+
+fn normalize_list(
+    values: impl Iterator<Item = String>,
+    max_items: usize,
+    max_length: usize,
+    what: &str,
+) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    let mut list = Vec::new();
+    for value in values.filter(|value| !value.is_empty()) {
+        if value.chars().count() > max_length {
+            return Err(format!("'{}' is longer than {} characters", value, max_length));
+        }
+        if seen.insert(value.to_lowercase()) {
+            list.push(value);
+        }
+    }
+    if list.len() > max_items {
+        return Err(format!("At most {} {} allowed", max_items, what));
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        assert_eq!(
+            normalize_tags(&strings(&[" Seasonal ", "pumps", "", "seasonal", "PUMPS"])).unwrap(),
+            vec!["seasonal", "pumps"]
+        );
+        let many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("tag{}", i)).collect();
+        assert!(normalize_tags(&many).is_err());
+        assert!(normalize_tags(&["t".repeat(TAG_MAX_LENGTH + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_normalize_equipment_models() {
+        assert_eq!(
+            normalize_equipment_models(&strings(&["AX-200 ", "ax-200", " ", "BX-10"])).unwrap(),
+            vec!["AX-200", "BX-10"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_name_and_description() {
+        assert_eq!(normalize_name("  Pump won't start ").unwrap(), "Pump won't start");
+        assert!(normalize_name("   ").is_err());
+        assert_eq!(normalize_description(Some("  ")).unwrap(), None);
+        assert_eq!(normalize_description(Some(" Winter units ")).unwrap().as_deref(), Some("Winter units"));
+        assert!(normalize_description(Some(&"d".repeat(DESCRIPTION_MAX_LENGTH + 1))).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+/// Scheduled issue activation
+///
+/// A background job flips an issue's active flag as its `active_from`/
+/// `active_until` boundaries pass. Each boundary is claimed with a conditional
+/// update, so only one instance acts and manual toggles aren't undone.
+use crate::engine;
+use crate::routes::issues;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often boundaries are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Check a schedule's window
+pub fn validate(active_from: Option<DateTime<Utc>>, active_until: Option<DateTime<Utc>>) -> Vec<(String, String)> {
+    match (active_from, active_until) {
+        (Some(from), Some(until)) if until <= from => {
+            vec![("active_until".to_string(), "Must be after active_from".to_string())]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Activate or deactivate issues whose window boundary has passed
+///
+/// Issues whose graph doesn't validate are left inactive with a warning, just
+/// as an unforced toggle would refuse them. Returns how many issues changed.
+pub async fn apply_due(state: &AppState) -> Result<usize, sqlx::Error> {
+    let due = sqlx::query_as::<_, (String, bool)>(
+        "UPDATE issues SET schedule_applied = wanted.active
+         FROM (
+            SELECT category,
+                   (active_from IS NULL OR active_from <= NOW()) AND (active_until IS NULL OR active_until > NOW()) AS active
+            FROM issues
+            WHERE active_from IS NOT NULL OR active_until IS NOT NULL
+         ) wanted
+         WHERE issues.category = wanted.category
+           AND issues.schedule_applied IS DISTINCT FROM wanted.active
+         RETURNING issues.category, wanted.active",
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut changed = 0;
+    for (category, active) in due {
+        let root = sqlx::query_as::<_, (Uuid, Option<bool>)>(
+            "SELECT id, is_active FROM nodes WHERE category = $1 ORDER BY created_at ASC LIMIT 1",
+        )
+        .bind(&category)
+        .fetch_optional(&state.db)
+        .await?;
+        let Some((root_id, is_active)) = root else {
+            continue;
+        };
+
+        // Caches built while the issue was inside (or outside) its window are stale either way
+        if is_active.unwrap_or(true) == active {
+            state.invalidate_issue(&category).await;
+            continue;
+        }
+        if active {
+            match engine::validate(state, &category).await {
+                Ok(report) if report.is_valid() => {}
+                Ok(_) => {
+                    tracing::warn!("⚠️  Scheduled activation of issue '{}' skipped: its graph has errors", category);
+                    state.invalidate_issue(&category).await;
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️  Scheduled activation of issue '{}' skipped: {:?}", category, e);
+                    continue;
+                }
+            }
+        }
+
+        match issues::set_issue_active(state, &category, root_id, active).await {
+            Ok(()) => changed += 1,
+            Err(e) => tracing::warn!("⚠️  Failed to apply schedule to issue '{}': {:?}", category, e),
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Check issue schedules every minute
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match apply_due(&state).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("📅 Applied schedules to {} issues", n),
+                Err(e) => tracing::warn!("⚠️  Failed to apply issue schedules: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_validate() {
+        let now = Utc::now();
+        assert!(validate(None, None).is_empty());
+        assert!(validate(Some(now), None).is_empty());
+        assert!(validate(Some(now), Some(now + Duration::days(90))).is_empty());
+        assert_eq!(validate(Some(now), Some(now)).len(), 1);
+    }
+}
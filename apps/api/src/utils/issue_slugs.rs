@@ -0,0 +1,179 @@
+/// Renaming an issue's category slug
+///
+/// Everything that refers to an issue by category name (start semantic ID,
+/// sub-flows, metadata, versions, sessions, ...) is moved in one go.
+use crate::utils::node_revisions::{self, FieldChange};
+use crate::utils::semantic_ids;
+use serde_json::json;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Longest slug, leaving room for the `_start` suffix of the start question's semantic ID
+pub const MAX_LENGTH: usize = semantic_ids::MAX_LENGTH - "_start".len();
+
+/// What a rename touched
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RenameCounts {
+    /// Nodes moved into the new category
+    pub nodes: u64,
+    /// Sub-flow nodes (in any issue) now running the new category
+    pub subflows: u64,
+    /// Sessions whose recorded steps named the old category
+    pub sessions: u64,
+}
+
+/// Trimmed slug: letters, digits, underscores and hyphens
+pub fn normalize(slug: &str) -> Result<String, String> {
+    let slug = slug.trim();
+    if slug.is_empty() {
+        return Err("Must not be empty".to_string());
+    }
+    if slug.chars().count() > MAX_LENGTH {
+        return Err(format!("Must be at most {} characters", MAX_LENGTH));
+    }
+    if !slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("Use only letters, digits, underscores and hyphens".to_string());
+    }
+    Ok(slug.to_string())
+}
+
+/// Move everything keyed by category `from` to `to`
+///
+/// `to` must be unused. Quota usage left over from an earlier issue with the
+/// new slug is dropped so the renamed issue keeps its own counts.
+pub async fn rename(conn: &mut PgConnection, from: &str, to: &str, renamed_by: Uuid) -> Result<RenameCounts, sqlx::Error> {
+    let start = sqlx::query_scalar::<_, Uuid>(
+        "UPDATE nodes SET semantic_id = $2 || '_start'
+         WHERE category = $1 AND semantic_id = $1 || '_start'
+         RETURNING id",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let nodes = sqlx::query("UPDATE nodes SET category = $2 WHERE category = $1")
+        .bind(from)
+        .bind(to)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    if let Some(start) = start {
+        let change = FieldChange {
+            field: "semantic_id",
+            old_value: json!(format!("{}_start", from)),
+            new_value: json!(format!("{}_start", to)),
+        };
+        node_revisions::record(conn, start, renamed_by, &[change], None).await?;
+    }
+
+    let subflow_ids = sqlx::query_scalar::<_, Uuid>(
+        "UPDATE nodes SET subflow_category = $2 WHERE subflow_category = $1 RETURNING id",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(&mut *conn)
+    .await?;
+    let change = FieldChange {
+        field: "subflow_category",
+        old_value: json!(from),
+        new_value: json!(to),
+    };
+    for id in &subflow_ids {
+        node_revisions::record(conn, *id, renamed_by, std::slice::from_ref(&change), None).await?;
+    }
+
+    sqlx::query("DELETE FROM category_quota_usage WHERE category = $1")
+        .bind(to)
+        .execute(&mut *conn)
+        .await?;
+    // Plain category columns; edit access granted ahead of time for the new slug is kept
+    for statement in [
+        "UPDATE issues SET category = $2 WHERE category = $1",
+        "UPDATE issue_archives SET category = $2 WHERE category = $1",
+        "UPDATE issue_approvals SET category = $2 WHERE category = $1",
+        "UPDATE issue_rollouts SET category = $2 WHERE category = $1",
+        "UPDATE issue_rollouts SET candidate_category = $2 WHERE candidate_category = $1",
+        "UPDATE session_rollout_assignments SET category = $2 WHERE category = $1",
+        "UPDATE session_rollout_assignments SET served_category = $2 WHERE served_category = $1",
+        "INSERT INTO user_category_access (user_id, category, granted_by, granted_at)
+         SELECT user_id, $2, granted_by, granted_at FROM user_category_access WHERE category = $1
+         ON CONFLICT (user_id, category) DO NOTHING",
+        "DELETE FROM user_category_access WHERE category = $1",
+        "UPDATE category_notices SET category = $2 WHERE category = $1",
+        "UPDATE category_quotas SET category = $2 WHERE category = $1",
+        "UPDATE category_quota_usage SET category = $2 WHERE category = $1",
+        "UPDATE deleted_records SET category = $2 WHERE category = $1",
+        "UPDATE session_events SET category = $2 WHERE category = $1",
+        "UPDATE session_archive SET category = $2 WHERE category = $1",
+    ] {
+        sqlx::query(statement).bind(from).bind(to).execute(&mut *conn).await?;
+    }
+
+    // Version snapshots are restored as rows by rollback, so their nodes move too
+    sqlx::query(
+        "UPDATE issue_versions SET
+            category = CASE WHEN category = $1 THEN $2 ELSE category END,
+            nodes = (
+                SELECT COALESCE(jsonb_agg(
+                    n
+                    || CASE WHEN n->>'category' = $1 THEN jsonb_build_object('category', $2) ELSE '{}' END
+                    || CASE WHEN n->>'category' = $1 AND n->>'semantic_id' = $1 || '_start'
+                            THEN jsonb_build_object('semantic_id', $2 || '_start') ELSE '{}' END
+                    || CASE WHEN n->>'subflow_category' = $1 THEN jsonb_build_object('subflow_category', $2) ELSE '{}' END
+                    ORDER BY ord), '[]')
+                FROM jsonb_array_elements(nodes) WITH ORDINALITY AS e(n, ord)
+            )
+         WHERE category = $1 OR nodes @> jsonb_build_array(jsonb_build_object('subflow_category', $1))",
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *conn)
+    .await?;
+
+    // Steps recorded with a category (older sessions), and sub-flows entered along the way
+    let sessions = sqlx::query(
+        "UPDATE sessions SET steps = (
+            SELECT jsonb_agg(
+                s
+                || CASE WHEN s->>'category' = $1 THEN jsonb_build_object('category', $2) ELSE '{}' END
+                || CASE WHEN s->'subflows' @> jsonb_build_array(jsonb_build_object('category', $1)) THEN
+                        jsonb_build_object('subflows', (
+                            SELECT jsonb_agg(
+                                CASE WHEN f->>'category' = $1 THEN f || jsonb_build_object('category', $2) ELSE f END
+                                ORDER BY f_ord)
+                            FROM jsonb_array_elements(s->'subflows') WITH ORDINALITY AS g(f, f_ord)
+                        ))
+                   ELSE '{}' END
+                ORDER BY ord)
+            FROM jsonb_array_elements(steps) WITH ORDINALITY AS e(s, ord)
+         )
+         WHERE steps @> jsonb_build_array(jsonb_build_object('category', $1))
+            OR steps @> jsonb_build_array(jsonb_build_object('subflows', jsonb_build_array(jsonb_build_object('category', $1))))",
+    )
+    .bind(from)
+    .bind(to)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    Ok(RenameCounts {
+        nodes,
+        subflows: subflow_ids.len() as u64,
+        sessions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  boiler-lockout_2 ").unwrap(), "boiler-lockout_2");
+        assert!(normalize("  ").is_err());
+        assert!(normalize("boiler lockout").is_err());
+        assert!(normalize("pümp").is_err());
+        assert!(normalize(&"b".repeat(MAX_LENGTH)).is_ok());
+        assert!(normalize(&"b".repeat(MAX_LENGTH + 1)).is_err());
+    }
+}
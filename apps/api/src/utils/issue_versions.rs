@@ -0,0 +1,530 @@
+/// Issue version history
+///
+/// Each draft publish records the issue's rows as a numbered version. Versions
+/// can be compared, and a rollback writes a version back over the issue's
+/// records by ID (recording a new version itself).
+use crate::error::{ApiError, ApiResult};
+use crate::models::Node;
+use crate::utils::node_revisions::{self, FieldChange, TRACKED_FIELDS};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Connection fields compared between versions
+const CONNECTION_FIELDS: &[&str] = &[
+    "from_node_id",
+    "to_node_id",
+    "label",
+    "order_index",
+    "weight",
+    "condition",
+    "unsure_route",
+    "is_active",
+];
+
+/// A live node (`o`) differs from its row in the version (`s`), once moved back into category $1
+const NODE_CHANGED: &str =
+    "(o.category, o.node_type, o.text, o.semantic_id, o.display_category, o.position_x, o.position_y, o.kb_url, o.doc_reference,
+      o.option_order, o.answer_mode, o.not_sure_node_id, o.subflow_category, o.content, o.is_active)
+     IS DISTINCT FROM
+     ($1, s.node_type, s.text, s.semantic_id, s.display_category, s.position_x, s.position_y, s.kb_url, s.doc_reference,
+      s.option_order, s.answer_mode, (SELECT ns.id FROM nodes ns WHERE ns.id = s.not_sure_node_id), s.subflow_category, s.content, s.is_active)";
+
+/// A live connection (`o`) differs from its row in the version (`s`)
+const CONNECTION_CHANGED: &str =
+    "(o.from_node_id, o.to_node_id, o.label, o.order_index, o.weight, o.condition, o.unsure_route, o.is_active)
+     IS DISTINCT FROM
+     (s.from_node_id, s.to_node_id, s.label, s.order_index, s.weight, s.condition, s.unsure_route, s.is_active)";
+
+/// Why a version was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// The graph as it was before the issue's first publish
+    Baseline,
+    Publish,
+    Rollback,
+}
+
+impl VersionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VersionSource::Baseline => "baseline",
+            VersionSource::Publish => "publish",
+            VersionSource::Rollback => "rollback",
+        }
+    }
+}
+
+/// A recorded version, as listed in an issue's history
+#[derive(Debug, Serialize, sqlx::FromRow, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueVersion {
+    pub category: String,
+    pub version: i32,
+    /// baseline, publish or rollback
+    pub source: String,
+    /// Version a rollback restored
+    pub restored_version: Option<i32>,
+    pub node_count: i32,
+    pub connection_count: i32,
+    pub created_by: Option<Uuid>,
+    /// Email of the publisher (null once the account is gone)
+    pub created_by_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A field that differs between two versions of a record
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct VersionFieldChange {
+    pub field: String,
+    #[ts(type = "unknown")]
+    pub old_value: Value,
+    #[ts(type = "unknown")]
+    pub new_value: Value,
+}
+
+/// A node added, removed or changed between two versions
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct VersionNodeDiff {
+    pub id: Uuid,
+    pub semantic_id: Option<String>,
+    pub text: String,
+    /// Changed fields (empty for added and removed nodes)
+    pub changes: Vec<VersionFieldChange>,
+}
+
+/// A connection added, removed or changed between two versions
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct VersionConnectionDiff {
+    pub id: Uuid,
+    pub from_node_id: Uuid,
+    pub to_node_id: Uuid,
+    pub label: String,
+    /// Changed fields (empty for added and removed connections)
+    pub changes: Vec<VersionFieldChange>,
+}
+
+/// Structural differences between two versions of an issue
+///
+/// Layout is left out, like in node history.
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueVersionDiff {
+    pub category: String,
+    pub from_version: i32,
+    pub to_version: i32,
+    pub nodes_added: Vec<VersionNodeDiff>,
+    pub nodes_removed: Vec<VersionNodeDiff>,
+    pub nodes_changed: Vec<VersionNodeDiff>,
+    pub connections_added: Vec<VersionConnectionDiff>,
+    pub connections_removed: Vec<VersionConnectionDiff>,
+    pub connections_changed: Vec<VersionConnectionDiff>,
+}
+
+/// What a rollback changed in the issue
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct IssueRolledBack {
+    pub category: String,
+    pub restored_version: i32,
+    /// Version the rollback was recorded as
+    pub version: i32,
+    #[ts(type = "number")]
+    pub nodes_updated: u64,
+    /// Nodes deleted since the version, created again with their old IDs
+    #[ts(type = "number")]
+    pub nodes_recreated: u64,
+    /// Nodes the version doesn't have, now switched off
+    #[ts(type = "number")]
+    pub nodes_deactivated: u64,
+    #[ts(type = "number")]
+    pub connections_updated: u64,
+    #[ts(type = "number")]
+    pub connections_recreated: u64,
+    #[ts(type = "number")]
+    pub connections_deactivated: u64,
+}
+
+/// Record the issue's current nodes and connections as its next version
+pub async fn record(
+    conn: &mut PgConnection,
+    category: &str,
+    source: VersionSource,
+    restored_version: Option<i32>,
+    created_by: Uuid,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar::<_, i32>(
+        "INSERT INTO issue_versions (category, version, source, restored_version, nodes, connections, node_count, connection_count, created_by)
+         SELECT $1, COALESCE((SELECT MAX(version) FROM issue_versions WHERE category = $1), 0) + 1, $2, $3,
+                n.rows, c.rows, n.count, c.count, $4
+         FROM (SELECT COALESCE(jsonb_agg(to_jsonb(n) ORDER BY n.created_at, n.id), '[]'::jsonb) AS rows, COUNT(*)::int AS count
+               FROM nodes n WHERE n.category = $1) n,
+              (SELECT COALESCE(jsonb_agg(to_jsonb(c) ORDER BY c.from_node_id, c.order_index, c.created_at, c.id), '[]'::jsonb) AS rows,
+                      COUNT(*)::int AS count
+               FROM connections c JOIN nodes f ON f.id = c.from_node_id WHERE f.category = $1) c
+         RETURNING version",
+    )
+    .bind(category)
+    .bind(source.as_str())
+    .bind(restored_version)
+    .bind(created_by)
+    .fetch_one(conn)
+    .await
+}
+
+/// Record the graph a publish is about to replace, if the issue has no versions yet
+pub async fn record_baseline(conn: &mut PgConnection, category: &str, created_by: Uuid) -> Result<(), sqlx::Error> {
+    let recorded = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM issue_versions WHERE category = $1)")
+        .bind(category)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !recorded {
+        record(conn, category, VersionSource::Baseline, None, created_by).await?;
+    }
+    Ok(())
+}
+
+/// The issue's versions, newest first
+pub async fn list(conn: &mut PgConnection, category: &str) -> Result<Vec<IssueVersion>, sqlx::Error> {
+    sqlx::query_as::<_, IssueVersion>(
+        "SELECT v.category, v.version, v.source, v.restored_version, v.node_count, v.connection_count,
+                v.created_by, u.email AS created_by_email, v.created_at
+         FROM issue_versions v
+         LEFT JOIN users u ON u.id = v.created_by
+         WHERE v.category = $1
+         ORDER BY v.version DESC",
+    )
+    .bind(category)
+    .fetch_all(conn)
+    .await
+}
+
+/// A version's node and connection rows
+async fn rows(conn: &mut PgConnection, category: &str, version: i32) -> ApiResult<(Vec<Value>, Vec<Value>)> {
+    let (nodes, connections) = sqlx::query_as::<_, (sqlx::types::Json<Vec<Value>>, sqlx::types::Json<Vec<Value>>)>(
+        "SELECT nodes, connections FROM issue_versions WHERE category = $1 AND version = $2",
+    )
+    .bind(category)
+    .bind(version)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Version {} of this issue not found", version)))?;
+
+    Ok((nodes.0, connections.0))
+}
+
+/// Rows of one table compared between two versions, matched by `id`
+struct RowDiff<'a> {
+    added: Vec<&'a Value>,
+    removed: Vec<&'a Value>,
+    /// Rows whose compared fields differ, as they are in the later version
+    changed: Vec<(&'a Value, Vec<FieldChange>)>,
+}
+
+/// Compare two versions of a table's rows on the listed fields
+fn diff_rows<'a>(before: &'a [Value], after: &'a [Value], fields: &[&'static str]) -> RowDiff<'a> {
+    let id = |row: &'a Value| row.get("id").and_then(Value::as_str);
+    let before_by_id: HashMap<&str, &Value> = before.iter().filter_map(|row| Some((id(row)?, row))).collect();
+    let after_ids: HashSet<&str> = after.iter().filter_map(id).collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for row in after {
+        match id(row).and_then(|id| before_by_id.get(id)) {
+            None => added.push(row),
+            Some(old) => {
+                let changes = node_revisions::diff_fields(old, row, fields);
+                if !changes.is_empty() {
+                    changed.push((row, changes));
+                }
+            }
+        }
+    }
+    let removed = before
+        .iter()
+        .filter(|row| id(row).is_some_and(|id| !after_ids.contains(id)))
+        .collect();
+
+    RowDiff { added, removed, changed }
+}
+
+fn field_changes(changes: Vec<FieldChange>) -> Vec<VersionFieldChange> {
+    changes
+        .into_iter()
+        .map(|c| VersionFieldChange { field: c.field.to_string(), old_value: c.old_value, new_value: c.new_value })
+        .collect()
+}
+
+fn uuid_field(row: &Value, field: &str) -> Uuid {
+    row.get(field).and_then(Value::as_str).and_then(|s| s.parse().ok()).unwrap_or_default()
+}
+
+fn string_field(row: &Value, field: &str) -> Option<String> {
+    row.get(field).and_then(Value::as_str).map(str::to_string)
+}
+
+fn node_diff(row: &Value, changes: Vec<FieldChange>) -> VersionNodeDiff {
+    VersionNodeDiff {
+        id: uuid_field(row, "id"),
+        semantic_id: string_field(row, "semantic_id"),
+        text: string_field(row, "text").unwrap_or_default(),
+        changes: field_changes(changes),
+    }
+}
+
+fn connection_diff(row: &Value, changes: Vec<FieldChange>) -> VersionConnectionDiff {
+    VersionConnectionDiff {
+        id: uuid_field(row, "id"),
+        from_node_id: uuid_field(row, "from_node_id"),
+        to_node_id: uuid_field(row, "to_node_id"),
+        label: string_field(row, "label").unwrap_or_default(),
+        changes: field_changes(changes),
+    }
+}
+
+/// Compare version `from` of an issue with version `to`
+pub async fn diff(conn: &mut PgConnection, category: &str, from: i32, to: i32) -> ApiResult<IssueVersionDiff> {
+    let (before_nodes, before_connections) = rows(&mut *conn, category, from).await?;
+    let (after_nodes, after_connections) = rows(conn, category, to).await?;
+
+    let nodes = diff_rows(&before_nodes, &after_nodes, TRACKED_FIELDS);
+    let connections = diff_rows(&before_connections, &after_connections, CONNECTION_FIELDS);
+
+    Ok(IssueVersionDiff {
+        category: category.to_string(),
+        from_version: from,
+        to_version: to,
+        nodes_added: nodes.added.into_iter().map(|row| node_diff(row, Vec::new())).collect(),
+        nodes_removed: nodes.removed.into_iter().map(|row| node_diff(row, Vec::new())).collect(),
+        nodes_changed: nodes.changed.into_iter().map(|(row, changes)| node_diff(row, changes)).collect(),
+        connections_added: connections.added.into_iter().map(|row| connection_diff(row, Vec::new())).collect(),
+        connections_removed: connections.removed.into_iter().map(|row| connection_diff(row, Vec::new())).collect(),
+        connections_changed: connections
+            .changed
+            .into_iter()
+            .map(|(row, changes)| connection_diff(row, changes))
+            .collect(),
+    })
+}
+
+/// Write version `version` over the issue's graph and record the result as a new version
+///
+/// Nodes and connections keep their IDs: ones deleted since the version are
+/// created again, ones added since are switched off. Every node that changes
+/// gets a revision by `rolled_back_by`. The caller checks the issue has no
+/// draft, since the draft's copies point at the records rolled back.
+pub async fn rollback(
+    conn: &mut PgConnection,
+    category: &str,
+    version: i32,
+    rolled_back_by: Uuid,
+) -> ApiResult<IssueRolledBack> {
+    let (nodes, connections) = rows(&mut *conn, category, version).await?;
+    let restored_ids: HashSet<Uuid> = nodes.iter().map(|row| uuid_field(row, "id")).collect();
+    let nodes = sqlx::types::Json(nodes);
+    let connections = sqlx::types::Json(connections);
+
+    let before = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE category = $1 OR id IN (SELECT id FROM jsonb_populate_recordset(NULL::nodes, $2))
+         FOR UPDATE",
+    )
+    .bind(category)
+    .bind(&nodes)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Unique semantic IDs are checked row by row, so first free the ones
+    // another node takes back
+    sqlx::query(
+        "UPDATE nodes o SET semantic_id = NULL
+         FROM jsonb_populate_recordset(NULL::nodes, $2) s
+         WHERE o.category = $1 AND o.semantic_id = s.semantic_id AND o.id <> s.id",
+    )
+    .bind(category)
+    .bind(&nodes)
+    .execute(&mut *conn)
+    .await?;
+
+    // "I'm not sure" targets are filled in by the update below, once every node exists
+    let nodes_recreated = sqlx::query(
+        "INSERT INTO nodes (id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, subflow_category, content, is_active, created_at)
+         SELECT s.id, $1, s.node_type, s.text, s.semantic_id, s.display_category, s.position_x, s.position_y, s.kb_url, s.doc_reference, s.option_order, s.answer_mode, s.subflow_category, s.content, s.is_active, s.created_at
+         FROM jsonb_populate_recordset(NULL::nodes, $2) s
+         WHERE NOT EXISTS (SELECT 1 FROM nodes n WHERE n.id = s.id)",
+    )
+    .bind(category)
+    .bind(&nodes)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    sqlx::query(&format!(
+        "UPDATE nodes o SET
+            category = $1, node_type = s.node_type, text = s.text, semantic_id = s.semantic_id, display_category = s.display_category,
+            position_x = s.position_x, position_y = s.position_y, kb_url = s.kb_url, doc_reference = s.doc_reference,
+            option_order = s.option_order, answer_mode = s.answer_mode,
+            not_sure_node_id = (SELECT ns.id FROM nodes ns WHERE ns.id = s.not_sure_node_id),
+            subflow_category = s.subflow_category, content = s.content, is_active = s.is_active
+         FROM jsonb_populate_recordset(NULL::nodes, $2) s
+         WHERE o.id = s.id AND {}",
+        NODE_CHANGED
+    ))
+    .bind(category)
+    .bind(&nodes)
+    .execute(&mut *conn)
+    .await?;
+
+    let nodes_deactivated = sqlx::query(
+        "UPDATE nodes SET is_active = false
+         WHERE category = $1 AND is_active AND id NOT IN (SELECT id FROM jsonb_populate_recordset(NULL::nodes, $2))",
+    )
+    .bind(category)
+    .bind(&nodes)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    // Connections whose target has since been deleted from another issue can't come back
+    let restorable = "EXISTS (SELECT 1 FROM nodes t WHERE t.id = s.to_node_id)";
+    let connections_updated = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*)
+         FROM jsonb_populate_recordset(NULL::connections, $1) s
+         JOIN connections o ON o.id = s.id
+         WHERE {} AND {}",
+        restorable, CONNECTION_CHANGED
+    ))
+    .bind(&connections)
+    .fetch_one(&mut *conn)
+    .await? as u64;
+
+    // One unsure route per question is checked row by row too
+    sqlx::query(
+        "UPDATE connections o SET unsure_route = false
+         WHERE o.unsure_route
+           AND o.from_node_id IN (SELECT id FROM nodes WHERE category = $2)
+           AND NOT EXISTS (
+               SELECT 1 FROM jsonb_populate_recordset(NULL::connections, $1) s
+               WHERE s.id = o.id AND s.unsure_route AND s.from_node_id = o.from_node_id)",
+    )
+    .bind(&connections)
+    .bind(category)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(&format!(
+        "UPDATE connections o SET
+            from_node_id = s.from_node_id, to_node_id = s.to_node_id, label = s.label, order_index = s.order_index,
+            weight = s.weight, condition = s.condition, unsure_route = s.unsure_route, is_active = s.is_active
+         FROM jsonb_populate_recordset(NULL::connections, $1) s
+         WHERE o.id = s.id AND {} AND {}",
+        restorable, CONNECTION_CHANGED
+    ))
+    .bind(&connections)
+    .execute(&mut *conn)
+    .await?;
+
+    let connections_recreated = sqlx::query(&format!(
+        "INSERT INTO connections (id, from_node_id, to_node_id, label, order_index, weight, condition, unsure_route, is_active, created_at)
+         SELECT s.id, s.from_node_id, s.to_node_id, s.label, s.order_index, s.weight, s.condition, s.unsure_route, s.is_active, s.created_at
+         FROM jsonb_populate_recordset(NULL::connections, $1) s
+         WHERE NOT EXISTS (SELECT 1 FROM connections c WHERE c.id = s.id) AND {}",
+        restorable
+    ))
+    .bind(&connections)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    let connections_deactivated = sqlx::query(
+        "UPDATE connections SET is_active = false
+         WHERE is_active
+           AND from_node_id IN (SELECT id FROM nodes WHERE category = $2)
+           AND id NOT IN (SELECT id FROM jsonb_populate_recordset(NULL::connections, $1))",
+    )
+    .bind(&connections)
+    .bind(category)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    let after: HashMap<Uuid, Node> = sqlx::query_as::<_, Node>(
+        "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+         FROM nodes
+         WHERE id = ANY($1)",
+    )
+    .bind(before.iter().map(|node| node.id).collect::<Vec<_>>())
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|node| (node.id, node))
+    .collect();
+
+    let mut nodes_updated = 0;
+    for node in &before {
+        let Some(restored) = after.get(&node.id) else {
+            continue;
+        };
+        let changes = node_revisions::diff(node, restored);
+        if !changes.is_empty() && restored_ids.contains(&node.id) {
+            nodes_updated += 1;
+        }
+        node_revisions::record(conn, node.id, rolled_back_by, &changes, None).await?;
+    }
+
+    let recorded = record(conn, category, VersionSource::Rollback, Some(version), rolled_back_by).await?;
+
+    Ok(IssueRolledBack {
+        category: category.to_string(),
+        restored_version: version,
+        version: recorded,
+        nodes_updated,
+        nodes_recreated,
+        nodes_deactivated,
+        connections_updated,
+        connections_recreated,
+        connections_deactivated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_rows() {
+        let before = vec![
+            json!({"id": "a", "label": "Yes", "order_index": 0, "weight": null}),
+            json!({"id": "b", "label": "No", "order_index": 1, "weight": null}),
+        ];
+        let after = vec![
+            json!({"id": "a", "label": "Yes", "order_index": 0, "weight": null, "updated_at": "later"}),
+            json!({"id": "b", "label": "Nope", "order_index": 1, "weight": 2.0}),
+            json!({"id": "c", "label": "Maybe", "order_index": 2, "weight": null}),
+        ];
+
+        let diff = diff_rows(&before, &after, CONNECTION_FIELDS);
+        assert_eq!(diff.added, vec![&after[2]]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0["id"], "b");
+        assert_eq!(
+            diff.changed[0].1.iter().map(|c| c.field).collect::<Vec<_>>(),
+            vec!["label", "weight"]
+        );
+
+        let diff = diff_rows(&after, &before, CONNECTION_FIELDS);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec![&after[2]]);
+        assert_eq!(diff.changed.len(), 1);
+    }
+}
@@ -1,7 +1,8 @@
 use crate::error::{ApiError, ApiResult};
 use crate::models::UserRole;
+use crate::utils::jwt_keys;
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Header, Validation};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
@@ -36,28 +37,12 @@ impl Claims {
         }
     }
 
-    /// Create new claims for a user with default expiration from env (fallback: 24 hours)
-    pub fn new(user_id: Uuid, email: String, role: UserRole) -> Self {
-        let expiration_hours = std::env::var("JWT_EXPIRATION_HOURS")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(24);
-
-        Self::new_with_expiration(user_id, email, role, expiration_hours * 60)
-    }
-
     /// Check if token has expired
     pub fn is_expired(&self) -> bool {
         Utc::now().timestamp() > self.exp
     }
 }
 
-/// Generate JWT token for user with default expiration
-pub fn generate_token(user_id: Uuid, email: String, role: UserRole) -> ApiResult<String> {
-    let claims = Claims::new(user_id, email, role);
-    encode_claims(&claims)
-}
-
 /// Generate JWT token for user with custom expiration in minutes
 pub fn generate_token_with_expiration(
     user_id: Uuid,
@@ -69,17 +54,18 @@ pub fn generate_token_with_expiration(
     encode_claims(&claims)
 }
 
-/// Internal function to encode claims into a JWT token
+/// Internal function to encode claims into a JWT token (signed by the current keyset key)
 fn encode_claims(claims: &Claims) -> ApiResult<String> {
-    let secret = std::env::var("JWT_SECRET")
-        .map_err(|_| ApiError::internal("JWT_SECRET not configured"))?;
-
-    let token = encode(
-        &Header::default(),
-        claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(|e| {
+    let key = jwt_keys::signing_key().map_err(ApiError::internal)?;
+    let encoding = key
+        .encoding
+        .as_ref()
+        .ok_or_else(|| ApiError::internal("JWT signing key cannot sign"))?;
+
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+
+    let token = encode(&header, claims, encoding).map_err(|e| {
         tracing::error!("Failed to generate JWT: {}", e);
         ApiError::internal("Failed to generate authentication token")
     })?;
@@ -88,20 +74,26 @@ fn encode_claims(claims: &Claims) -> ApiResult<String> {
 }
 
 /// Verify and decode JWT token
+///
+/// The `kid` header selects the key; tokens without one are checked against
+/// the environment secrets.
 pub fn verify_token(token: &str) -> ApiResult<Claims> {
-    let secret = std::env::var("JWT_SECRET")
-        .map_err(|_| ApiError::internal("JWT_SECRET not configured"))?;
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::default(),
-    )
-    .map_err(|e| {
-        tracing::debug!("JWT verification failed: {}", e);
+    let header = decode_header(token).map_err(|e| {
+        tracing::debug!("JWT header invalid: {}", e);
         ApiError::unauthorized("Invalid or expired token")
     })?;
 
+    let keys = jwt_keys::verification_keys(header.kid.as_deref()).map_err(ApiError::internal)?;
+
+    let token_data = keys
+        .iter()
+        .filter(|key| key.algorithm == header.alg)
+        .find_map(|key| decode::<Claims>(token, &key.decoding, &Validation::new(key.algorithm)).ok())
+        .ok_or_else(|| {
+            tracing::debug!("JWT verification failed (kid {:?})", header.kid);
+            ApiError::unauthorized("Invalid or expired token")
+        })?;
+
     let claims = token_data.claims;
 
     // Check if token is expired (extra safety check)
@@ -139,7 +131,7 @@ mod tests {
         let email = "test@example.com".to_string();
         let role = UserRole::Admin;
 
-        let claims = Claims::new(user_id, email.clone(), role.clone());
+        let claims = Claims::new_with_expiration(user_id, email.clone(), role.clone(), 15);
 
         assert_eq!(claims.sub, user_id.to_string());
         assert_eq!(claims.email, email);
@@ -0,0 +1,459 @@
+/// JWT signing keyset
+///
+/// Tokens carry a `kid` header naming the key that signed them, so several
+/// keys can verify at once and secrets can be rotated without signing
+/// everyone out:
+/// - `JWT_SECRET` (HS256). Secrets listed in `JWT_PREVIOUS_SECRETS` keep
+///   verifying tokens after the environment secret is replaced.
+/// - An optional RSA pair (`JWT_RSA_PRIVATE_KEY_FILE` + `JWT_RSA_PUBLIC_KEY_FILE`,
+///   RS256) that signs instead of `JWT_SECRET`; retired public keys go in
+///   `JWT_RSA_PREVIOUS_PUBLIC_KEY_FILES`.
+/// - Keys rotated through the admin API: fresh random secrets, stored
+///   encrypted under `JWT_KEY_ENCRYPTION_KEY` (not `JWT_SECRET`). A new key
+///   starts signing after `ACTIVATION_DELAY`, once every instance has loaded
+///   it, and the key it replaces verifies for `JWT_KEY_RETENTION_HOURS`.
+///
+/// Tokens without a `kid` (issued before keysets) are checked against the
+/// environment HMAC secrets.
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Nonce};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::{Arc, OnceLock, RwLock};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Rotated keys sign only after this delay, so every instance knows them first
+pub const ACTIVATION_DELAY_SECONDS: i64 = 120;
+
+/// How often rotated keys are re-read from the database
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Default verification window for a replaced key (JWT_KEY_RETENTION_HOURS)
+const DEFAULT_RETENTION_HOURS: i64 = 24;
+
+/// Length of a rotated HMAC secret in bytes
+const ROTATED_SECRET_LENGTH: usize = 32;
+
+/// AES-GCM nonce length; stored in front of the ciphertext
+const NONCE_LENGTH: usize = 12;
+
+/// Where a key comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum JwtKeySource {
+    /// JWT_SECRET
+    Env,
+    /// JWT_PREVIOUS_SECRETS (verify only)
+    EnvPrevious,
+    /// JWT_RSA_PRIVATE_KEY_FILE / JWT_RSA_PUBLIC_KEY_FILE
+    Rsa,
+    /// JWT_RSA_PREVIOUS_PUBLIC_KEY_FILES (verify only)
+    RsaPrevious,
+    /// Created by the rotation endpoint
+    Rotated,
+}
+
+/// A key that can verify (and possibly sign) tokens
+#[derive(Clone)]
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub source: JwtKeySource,
+    pub encoding: Option<EncodingKey>,
+    pub decoding: DecodingKey,
+    pub activates_at: Option<DateTime<Utc>>,
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+impl JwtKey {
+    fn hmac(kid: String, secret: &[u8], source: JwtKeySource) -> Self {
+        Self {
+            kid,
+            algorithm: Algorithm::HS256,
+            source,
+            encoding: (source != JwtKeySource::EnvPrevious).then(|| EncodingKey::from_secret(secret)),
+            decoding: DecodingKey::from_secret(secret),
+            activates_at: None,
+            retired_at: None,
+        }
+    }
+
+    /// Signing candidate at `now` (active and not retired)
+    fn can_sign_at(&self, now: DateTime<Utc>) -> bool {
+        self.encoding.is_some()
+            && self.activates_at.is_none_or(|at| at <= now)
+            && self.retired_at.is_none_or(|at| at > now)
+    }
+
+    /// Still accepted for verification at `now`
+    fn can_verify_at(&self, now: DateTime<Utc>) -> bool {
+        self.retired_at.is_none_or(|at| at + retention() > now)
+    }
+}
+
+/// A key as shown to administrators (never includes key material)
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct JwtKeyInfo {
+    pub kid: String,
+    pub algorithm: String,
+    pub source: JwtKeySource,
+    /// True for the key currently signing new tokens
+    pub signing: bool,
+    pub activates_at: Option<DateTime<Utc>>,
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+static ENV_KEYS: OnceLock<RwLock<Option<Arc<Vec<JwtKey>>>>> = OnceLock::new();
+static ROTATED_KEYS: OnceLock<RwLock<Arc<Vec<JwtKey>>>> = OnceLock::new();
+
+fn env_slot() -> &'static RwLock<Option<Arc<Vec<JwtKey>>>> {
+    ENV_KEYS.get_or_init(|| RwLock::new(None))
+}
+
+fn rotated_slot() -> &'static RwLock<Arc<Vec<JwtKey>>> {
+    ROTATED_KEYS.get_or_init(|| RwLock::new(Arc::new(Vec::new())))
+}
+
+fn retention() -> Duration {
+    let hours = std::env::var("JWT_KEY_RETENTION_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(DEFAULT_RETENTION_HOURS);
+    Duration::hours(hours)
+}
+
+/// Short public id for key material (a digest, never the material itself)
+fn key_id(prefix: &str, material: &[u8]) -> String {
+    let digest = Sha256::new().chain_update(b"jwt-kid:").chain_update(material).finalize();
+    format!("{}-{}", prefix, digest.iter().take(6).map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+/// Cipher for stored key material, keyed by JWT_KEY_ENCRYPTION_KEY
+///
+/// `None` when the variable is unset or equals JWT_SECRET (a leaked signing
+/// secret must not unlock the rotated keys).
+fn storage_cipher() -> Option<Aes256Gcm> {
+    let key = std::env::var("JWT_KEY_ENCRYPTION_KEY").ok().filter(|k| !k.trim().is_empty())?;
+    if std::env::var("JWT_SECRET").is_ok_and(|secret| secret == key) {
+        return None;
+    }
+    Some(cipher_for(key.as_bytes()))
+}
+
+fn cipher_for(key: &[u8]) -> Aes256Gcm {
+    let digest = Sha256::new().chain_update(b"jwt-key-encryption:").chain_update(key).finalize();
+    Aes256Gcm::new_from_slice(&digest).expect("SHA-256 digest is a valid AES-256 key")
+}
+
+/// True when rotated keys can be stored (JWT_KEY_ENCRYPTION_KEY is set)
+pub fn rotation_configured() -> bool {
+    storage_cipher().is_some()
+}
+
+/// Encrypt a rotated secret (nonce followed by ciphertext); the kid is bound as associated data
+fn seal_secret(cipher: &Aes256Gcm, kid: &str, secret: &[u8]) -> Vec<u8> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: secret, aad: kid.as_bytes() })
+        .expect("AES-GCM encryption of a short secret cannot fail");
+    [&nonce[..], &ciphertext].concat()
+}
+
+/// Decrypt a stored secret; `None` if it was sealed under another key or for another kid
+fn open_secret(cipher: &Aes256Gcm, kid: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() <= NONCE_LENGTH {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    let nonce: [u8; NONCE_LENGTH] = nonce.try_into().ok()?;
+    cipher
+        .decrypt(&Nonce::from(nonce), Payload { msg: ciphertext, aad: kid.as_bytes() })
+        .ok()
+}
+
+fn read_file(var: &str, path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path.trim()).map_err(|e| format!("{}: cannot read {}: {}", var, path.trim(), e))
+}
+
+fn rsa_public_key(var: &str, path: &str, source: JwtKeySource) -> Result<JwtKey, String> {
+    let pem = read_file(var, path)?;
+    Ok(JwtKey {
+        kid: key_id("rsa", &pem),
+        algorithm: Algorithm::RS256,
+        source,
+        encoding: None,
+        decoding: DecodingKey::from_rsa_pem(&pem).map_err(|e| format!("{}: invalid RSA public key: {}", var, e))?,
+        activates_at: None,
+        retired_at: None,
+    })
+}
+
+/// Build the environment keys (JWT_SECRET, previous secrets, RSA files)
+fn load_env_keys() -> Result<Vec<JwtKey>, String> {
+    let secret = std::env::var("JWT_SECRET").map_err(|_| "JWT_SECRET not configured".to_string())?;
+    let mut keys = vec![JwtKey::hmac(key_id("env", secret.as_bytes()), secret.as_bytes(), JwtKeySource::Env)];
+
+    if let Ok(previous) = std::env::var("JWT_PREVIOUS_SECRETS") {
+        for old in previous.split(',').map(str::trim).filter(|s| !s.is_empty() && *s != secret) {
+            keys.push(JwtKey::hmac(key_id("env", old.as_bytes()), old.as_bytes(), JwtKeySource::EnvPrevious));
+        }
+    }
+
+    let private_file = std::env::var("JWT_RSA_PRIVATE_KEY_FILE").ok().filter(|v| !v.trim().is_empty());
+    let public_file = std::env::var("JWT_RSA_PUBLIC_KEY_FILE").ok().filter(|v| !v.trim().is_empty());
+    match (private_file, public_file) {
+        (Some(private_file), Some(public_file)) => {
+            let private_pem = read_file("JWT_RSA_PRIVATE_KEY_FILE", &private_file)?;
+            let mut key = rsa_public_key("JWT_RSA_PUBLIC_KEY_FILE", &public_file, JwtKeySource::Rsa)?;
+            key.encoding = Some(
+                EncodingKey::from_rsa_pem(&private_pem)
+                    .map_err(|e| format!("JWT_RSA_PRIVATE_KEY_FILE: invalid RSA private key: {}", e))?,
+            );
+            keys.push(key);
+        }
+        (None, None) => {}
+        _ => return Err("JWT_RSA_PRIVATE_KEY_FILE and JWT_RSA_PUBLIC_KEY_FILE must be set together".to_string()),
+    }
+
+    if let Ok(files) = std::env::var("JWT_RSA_PREVIOUS_PUBLIC_KEY_FILES") {
+        for file in files.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            keys.push(rsa_public_key("JWT_RSA_PREVIOUS_PUBLIC_KEY_FILES", file, JwtKeySource::RsaPrevious)?);
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Environment keys, built on first use
+fn env_keys() -> Result<Arc<Vec<JwtKey>>, String> {
+    if let Some(keys) = env_slot().read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Ok(keys.clone());
+    }
+
+    let keys = Arc::new(load_env_keys()?);
+    *env_slot().write().unwrap_or_else(|e| e.into_inner()) = Some(keys.clone());
+    Ok(keys)
+}
+
+/// Validate the environment key configuration at startup
+pub fn init() -> Result<usize, String> {
+    env_keys().map(|keys| keys.len())
+}
+
+/// True when an RSA key pair signs tokens (rotation is then done through the key files)
+pub fn rsa_signing() -> bool {
+    env_keys().is_ok_and(|keys| keys.iter().any(|k| k.source == JwtKeySource::Rsa))
+}
+
+/// Key that signs tokens at `now`: the RSA pair, else the newest active rotated key, else JWT_SECRET
+fn signing_key_from(env: &[JwtKey], rotated: &[JwtKey], now: DateTime<Utc>) -> Option<JwtKey> {
+    env.iter()
+        .find(|k| k.source == JwtKeySource::Rsa)
+        .or_else(|| {
+            rotated
+                .iter()
+                .filter(|k| k.can_sign_at(now))
+                .max_by_key(|k| k.activates_at)
+        })
+        .or_else(|| env.iter().find(|k| k.source == JwtKeySource::Env))
+        .cloned()
+}
+
+/// Key to sign new tokens with
+pub fn signing_key() -> Result<JwtKey, String> {
+    let env = env_keys()?;
+    let rotated = rotated_slot().read().unwrap_or_else(|e| e.into_inner()).clone();
+    signing_key_from(&env, &rotated, Utc::now()).ok_or_else(|| "No JWT signing key available".to_string())
+}
+
+/// Keys that may have signed a token with this `kid` (env HMAC secrets when absent)
+pub fn verification_keys(kid: Option<&str>) -> Result<Vec<JwtKey>, String> {
+    let env = env_keys()?;
+    let now = Utc::now();
+
+    let keys = match kid {
+        Some(kid) => {
+            let rotated = rotated_slot().read().unwrap_or_else(|e| e.into_inner()).clone();
+            env.iter()
+                .chain(rotated.iter())
+                .filter(|k| k.kid == kid && k.can_verify_at(now))
+                .cloned()
+                .collect()
+        }
+        None => env
+            .iter()
+            .filter(|k| matches!(k.source, JwtKeySource::Env | JwtKeySource::EnvPrevious))
+            .cloned()
+            .collect(),
+    };
+    Ok(keys)
+}
+
+/// Re-read rotated keys from the database
+pub async fn reload(db: &PgPool) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, Vec<u8>, DateTime<Utc>, Option<DateTime<Utc>>)>(
+        "SELECT kid, encrypted_secret, activates_at, retired_at
+         FROM jwt_signing_keys
+         WHERE encrypted_secret IS NOT NULL
+           AND (retired_at IS NULL OR retired_at > NOW() - make_interval(hours => $1))",
+    )
+    .bind(retention().num_hours() as i32)
+    .fetch_all(db)
+    .await?;
+
+    let Some(cipher) = storage_cipher() else {
+        if !rows.is_empty() {
+            tracing::warn!("JWT_KEY_ENCRYPTION_KEY is not set; ignoring {} rotated JWT keys", rows.len());
+        }
+        return Ok(());
+    };
+
+    let keys = rows
+        .into_iter()
+        .filter_map(|(kid, sealed, activates_at, retired_at)| {
+            let Some(secret) = open_secret(&cipher, &kid, &sealed) else {
+                tracing::warn!("Cannot decrypt rotated JWT key {} (JWT_KEY_ENCRYPTION_KEY changed?)", kid);
+                return None;
+            };
+            let mut key = JwtKey::hmac(kid, &secret, JwtKeySource::Rotated);
+            key.activates_at = Some(activates_at);
+            key.retired_at = retired_at;
+            Some(key)
+        })
+        .collect();
+
+    *rotated_slot().write().unwrap_or_else(|e| e.into_inner()) = Arc::new(keys);
+    Ok(())
+}
+
+/// Create a new signing key; it takes over after `ACTIVATION_DELAY_SECONDS`
+///
+/// Callers check `rotation_configured` first; without an encryption key this
+/// is a no-op that returns `None`.
+pub async fn rotate(db: &PgPool, created_by: Uuid) -> Result<Option<(String, DateTime<Utc>)>, sqlx::Error> {
+    let Some(cipher) = storage_cipher() else {
+        return Ok(None);
+    };
+    let kid = format!("rot-{}", &crate::utils::password::generate_opaque_token()[..16]);
+    let activates_at = Utc::now() + Duration::seconds(ACTIVATION_DELAY_SECONDS);
+
+    let mut secret = [0u8; ROTATED_SECRET_LENGTH];
+    OsRng.fill_bytes(&mut secret);
+    let sealed = seal_secret(&cipher, &kid, &secret);
+
+    let mut tx = db.begin().await?;
+    // Keys still waiting to activate are superseded right away; active ones retire on handover
+    sqlx::query(
+        "UPDATE jwt_signing_keys
+         SET retired_at = CASE WHEN activates_at > NOW() THEN NOW() ELSE $1 END
+         WHERE retired_at IS NULL",
+    )
+    .bind(activates_at)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("INSERT INTO jwt_signing_keys (kid, encrypted_secret, activates_at, created_by) VALUES ($1, $2, $3, $4)")
+        .bind(&kid)
+        .bind(&sealed)
+        .bind(activates_at)
+        .bind(created_by)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    reload(db).await?;
+    Ok(Some((kid, activates_at)))
+}
+
+/// All keys currently accepted, for the admin overview
+pub fn key_infos() -> Result<Vec<JwtKeyInfo>, String> {
+    let env = env_keys()?;
+    let rotated = rotated_slot().read().unwrap_or_else(|e| e.into_inner()).clone();
+    let now = Utc::now();
+    let signing = signing_key_from(&env, &rotated, now).map(|k| k.kid);
+
+    Ok(env
+        .iter()
+        .chain(rotated.iter())
+        .filter(|k| k.can_verify_at(now))
+        .map(|k| JwtKeyInfo {
+            kid: k.kid.clone(),
+            algorithm: format!("{:?}", k.algorithm),
+            source: k.source,
+            signing: signing.as_deref() == Some(k.kid.as_str()),
+            activates_at: k.activates_at,
+            retired_at: k.retired_at,
+        })
+        .collect())
+}
+
+/// Periodically re-read rotated keys so every instance signs with the same key
+pub fn spawn_refresh(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = reload(&db).await {
+                tracing::warn!("Failed to refresh JWT signing keys: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotated(kid: &str, activates_in: i64, retired_in: Option<i64>) -> JwtKey {
+        let now = Utc::now();
+        let mut key = JwtKey::hmac(kid.to_string(), b"derived", JwtKeySource::Rotated);
+        key.activates_at = Some(now + Duration::seconds(activates_in));
+        key.retired_at = retired_in.map(|s| now + Duration::seconds(s));
+        key
+    }
+
+    #[test]
+    fn test_signing_key_precedence() {
+        let env = vec![JwtKey::hmac("env-a".to_string(), b"secret", JwtKeySource::Env)];
+        let now = Utc::now();
+
+        // Nothing rotated yet: JWT_SECRET signs
+        assert_eq!(signing_key_from(&env, &[], now).unwrap().kid, "env-a");
+
+        // A pending key does not sign until it activates; the key it replaces still does
+        let keys = vec![rotated("rot-old", -3600, Some(60)), rotated("rot-new", 60, None)];
+        assert_eq!(signing_key_from(&env, &keys, now).unwrap().kid, "rot-old");
+        assert_eq!(signing_key_from(&env, &keys, now + Duration::seconds(61)).unwrap().kid, "rot-new");
+    }
+
+    #[test]
+    fn test_previous_secrets_only_verify() {
+        let key = JwtKey::hmac("env-b".to_string(), b"old", JwtKeySource::EnvPrevious);
+        assert!(key.encoding.is_none());
+        assert!(!key.can_sign_at(Utc::now()));
+        assert!(key.can_verify_at(Utc::now()));
+    }
+
+    #[test]
+    fn test_sealed_secrets_need_the_key_and_kid() {
+        let cipher = cipher_for(b"storage-key");
+        let sealed = seal_secret(&cipher, "rot-1", b"random material");
+
+        assert_eq!(open_secret(&cipher, "rot-1", &sealed).as_deref(), Some(&b"random material"[..]));
+        assert_eq!(open_secret(&cipher_for(b"other-key"), "rot-1", &sealed), None);
+        assert_eq!(open_secret(&cipher, "rot-2", &sealed), None);
+        assert_eq!(open_secret(&cipher, "rot-1", &sealed[..NONCE_LENGTH]), None);
+
+        // A fresh nonce each time
+        assert_ne!(sealed, seal_secret(&cipher, "rot-1", b"random material"));
+        assert_ne!(key_id("env", b"secret"), key_id("env", b"other"));
+    }
+}
@@ -0,0 +1,443 @@
+/// Graph layout clean-up
+///
+/// Imported and API-created nodes often have no position or sit on top of
+/// each other, and the editor then draws them in a pile. Normalizing a
+/// category snaps every node to the editor grid, clamps it to the canvas,
+/// moves overlapping nodes aside and puts unpositioned nodes in rows below
+/// the rest. Nodes that are already apart keep their arrangement.
+///
+/// A category can also be laid out from scratch in layers, top to bottom
+/// from the issue's start. Imports do that when no node has a position.
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet, VecDeque};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Grid the editor snaps to
+pub const GRID: f64 = 25.0;
+
+/// Space a node takes on the canvas (the editor draws nodes 250px wide)
+pub const NODE_WIDTH: f64 = 250.0;
+pub const NODE_HEIGHT: f64 = 100.0;
+
+/// Space kept between neighbouring nodes
+pub const GAP: f64 = 50.0;
+
+/// Canvas bounds; positions are clamped to 0..=CANVAS_SIZE - node size
+pub const CANVAS_SIZE: f64 = 20_000.0;
+
+/// What laying out or normalizing a category changed
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LayoutResult {
+    pub category: String,
+    /// Nodes in the category
+    #[ts(type = "number")]
+    pub nodes: usize,
+    /// Nodes whose position changed
+    #[ts(type = "number")]
+    pub moved: usize,
+    /// Nodes that had no position before
+    #[ts(type = "number")]
+    pub placed: usize,
+}
+
+/// A node's current position, if it has one
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutNode {
+    pub id: Uuid,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+}
+
+fn snap(value: f64) -> f64 {
+    (value / GRID).round() * GRID
+}
+
+fn overlaps(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < NODE_WIDTH + GAP && (a.1 - b.1).abs() < NODE_HEIGHT + GAP
+}
+
+/// First spot from `start` rightwards, then row by row, that clears every placed node
+fn free_spot(start: (f64, f64), placed: &[(f64, f64)]) -> (f64, f64) {
+    let (mut x, mut y) = start;
+    // Past the bottom of the canvas nothing is placed, so the search always ends
+    while let Some(&(blocker_x, _)) = placed.iter().find(|&&p| overlaps((x, y), p)) {
+        x = blocker_x + NODE_WIDTH + GAP;
+        if x > CANVAS_SIZE - NODE_WIDTH {
+            x = 0.0;
+            y += NODE_HEIGHT + GAP;
+        }
+    }
+    (x, y)
+}
+
+/// Normalized position of every node, in input order
+///
+/// Nodes are settled in order, so earlier nodes keep their spot and later
+/// ones are moved out of their way.
+pub fn normalize(nodes: &[LayoutNode]) -> Vec<(Uuid, f64, f64)> {
+    let mut placed: Vec<(f64, f64)> = Vec::with_capacity(nodes.len());
+    let mut positions: Vec<Option<(f64, f64)>> = vec![None; nodes.len()];
+
+    for (i, node) in nodes.iter().enumerate() {
+        if let (Some(x), Some(y)) = (node.x, node.y) {
+            let x = snap(x.clamp(0.0, CANVAS_SIZE - NODE_WIDTH));
+            let y = snap(y.clamp(0.0, CANVAS_SIZE - NODE_HEIGHT));
+            let spot = free_spot((x, y), &placed);
+            placed.push(spot);
+            positions[i] = Some(spot);
+        }
+    }
+
+    // Unpositioned nodes go in rows below everything else
+    let below = placed
+        .iter()
+        .map(|&(_, y)| y + NODE_HEIGHT + GAP)
+        .fold(0.0, f64::max);
+    for position in positions.iter_mut().filter(|p| p.is_none()) {
+        let spot = free_spot((0.0, snap(below)), &placed);
+        placed.push(spot);
+        *position = Some(spot);
+    }
+
+    nodes
+        .iter()
+        .zip(positions)
+        .map(|(node, position)| {
+            let (x, y) = position.unwrap_or_default();
+            (node.id, x, y)
+        })
+        .collect()
+}
+
+/// Space between neighbouring nodes in a layered layout (matches the editor's default grid)
+pub const LAYER_SPACING_X: f64 = 350.0;
+pub const LAYER_SPACING_Y: f64 = 200.0;
+
+/// Barycenter passes (down and up) when ordering layers
+const ORDERING_SWEEPS: usize = 4;
+
+/// Layered (Sugiyama-style) layout: answers flow downwards, layer by layer
+///
+/// `nodes` come in priority order (the issue's start first); `edges` are
+/// (from, to) pairs. Loops are broken by ignoring answers that lead back up
+/// the graph, each node goes one layer below its deepest parent, and each
+/// layer is ordered to keep children under their parents. Returns positions
+/// in input order.
+pub fn layered(nodes: &[Uuid], edges: &[(Uuid, Uuid)]) -> Vec<(Uuid, f64, f64)> {
+    let index: HashMap<Uuid, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for (from, to) in edges {
+        if let (Some(&from), Some(&to)) = (index.get(from), index.get(to)) {
+            if from != to && !children[from].contains(&to) {
+                children[from].push(to);
+            }
+        }
+    }
+
+    // Drop answers that close a loop, walking from the start, then other roots
+    let has_parent: HashSet<usize> = children.iter().flatten().copied().collect();
+    let starts = (0..nodes.len().min(1))
+        .chain((0..nodes.len()).filter(|i| !has_parent.contains(i)))
+        .chain(0..nodes.len());
+    let mut state = vec![0u8; nodes.len()]; // 0 unvisited, 1 on the path, 2 done
+    let mut forward: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for start in starts {
+        if state[start] != 0 {
+            continue;
+        }
+        state[start] = 1;
+        let mut stack = vec![(start, 0)];
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            match children[node].get(*next).copied() {
+                Some(child) => {
+                    *next += 1;
+                    match state[child] {
+                        0 => {
+                            forward[node].push(child);
+                            state[child] = 1;
+                            stack.push((child, 0));
+                        }
+                        2 => forward[node].push(child),
+                        _ => {} // back to a node on the path: a loop
+                    }
+                }
+                None => {
+                    state[node] = 2;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    // Longest-path layering in topological order
+    let mut incoming = vec![0usize; nodes.len()];
+    for &child in forward.iter().flatten() {
+        incoming[child] += 1;
+    }
+    let mut layer = vec![0usize; nodes.len()];
+    let mut ready: VecDeque<usize> = (0..nodes.len()).filter(|&i| incoming[i] == 0).collect();
+    while let Some(node) = ready.pop_front() {
+        for &child in &forward[node] {
+            layer[child] = layer[child].max(layer[node] + 1);
+            incoming[child] -= 1;
+            if incoming[child] == 0 {
+                ready.push_back(child);
+            }
+        }
+    }
+
+    let depth = layer.iter().max().map_or(0, |deepest| deepest + 1);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); depth];
+    for (node, &l) in layer.iter().enumerate() {
+        layers[l].push(node);
+    }
+    let mut parents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for (node, kids) in forward.iter().enumerate() {
+        for &child in kids {
+            parents[child].push(node);
+        }
+    }
+
+    // Barycenter ordering: each node moves towards the average position of its neighbours
+    let mut offset = vec![0.0f64; nodes.len()];
+    let place = |layers: &[Vec<usize>], offset: &mut [f64]| {
+        for nodes in layers {
+            let middle = (nodes.len() as f64 - 1.0) / 2.0;
+            for (i, &node) in nodes.iter().enumerate() {
+                offset[node] = i as f64 - middle;
+            }
+        }
+    };
+    place(&layers, &mut offset);
+    for sweep in 0..ORDERING_SWEEPS {
+        let downwards = sweep % 2 == 0;
+        let order: Vec<usize> = if downwards { (1..depth).collect() } else { (0..depth.saturating_sub(1)).rev().collect() };
+        for l in order {
+            let neighbours = if downwards { &parents } else { &forward };
+            let mut keyed: Vec<(f64, usize)> = layers[l]
+                .iter()
+                .map(|&node| {
+                    let around = &neighbours[node];
+                    let key = if around.is_empty() {
+                        offset[node]
+                    } else {
+                        around.iter().map(|&n| offset[n]).sum::<f64>() / around.len() as f64
+                    };
+                    (key, node)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[l] = keyed.into_iter().map(|(_, node)| node).collect();
+            place(&layers[l..=l], &mut offset);
+        }
+    }
+
+    let widest = layers.iter().map(Vec::len).max().unwrap_or(0) as f64;
+    let mut positions = vec![(0.0, 0.0); nodes.len()];
+    for (l, members) in layers.iter().enumerate() {
+        for &node in members {
+            let x = (offset[node] + (widest - 1.0) / 2.0) * LAYER_SPACING_X;
+            positions[node] = (snap(x), l as f64 * LAYER_SPACING_Y);
+        }
+    }
+    nodes.iter().zip(positions).map(|(id, (x, y))| (*id, x, y)).collect()
+}
+
+/// A category's nodes, the issue's start first, then by creation
+async fn load_nodes(db: &PgPool, category: &str) -> Result<Vec<LayoutNode>, sqlx::Error> {
+    Ok(sqlx::query_as::<_, (Uuid, Option<f64>, Option<f64>)>(
+        "SELECT id, position_x, position_y FROM nodes
+         WHERE category = $1
+         ORDER BY semantic_id IS NOT DISTINCT FROM $1 || '_start' DESC, created_at, id",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|(id, x, y)| LayoutNode { id, x, y })
+    .collect())
+}
+
+/// Save the positions that changed
+async fn save(
+    db: &PgPool,
+    category: &str,
+    nodes: &[LayoutNode],
+    positions: Vec<(Uuid, f64, f64)>,
+) -> Result<LayoutResult, sqlx::Error> {
+    let mut ids = Vec::new();
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let mut placed = 0;
+    for (node, (id, x, y)) in nodes.iter().zip(positions) {
+        if node.x != Some(x) || node.y != Some(y) {
+            if node.x.is_none() || node.y.is_none() {
+                placed += 1;
+            }
+            ids.push(id);
+            xs.push(x);
+            ys.push(y);
+        }
+    }
+
+    if !ids.is_empty() {
+        sqlx::query(
+            "UPDATE nodes n SET position_x = p.x, position_y = p.y
+             FROM UNNEST($1::uuid[], $2::float8[], $3::float8[]) AS p(id, x, y)
+             WHERE n.id = p.id",
+        )
+        .bind(&ids)
+        .bind(&xs)
+        .bind(&ys)
+        .execute(db)
+        .await?;
+    }
+
+    Ok(LayoutResult {
+        category: category.to_string(),
+        nodes: nodes.len(),
+        moved: ids.len(),
+        placed,
+    })
+}
+
+/// Normalize the layout of one category and save the positions that changed
+pub async fn normalize_category(db: &PgPool, category: &str) -> Result<LayoutResult, sqlx::Error> {
+    let nodes = load_nodes(db, category).await?;
+    let positions = normalize(&nodes);
+    save(db, category, &nodes, positions).await
+}
+
+/// Lay out a whole category in layers and save the positions
+pub async fn layer_category(db: &PgPool, category: &str) -> Result<LayoutResult, sqlx::Error> {
+    let nodes = load_nodes(db, category).await?;
+    // Answers and "I'm not sure" options within the category
+    let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT c.from_node_id, c.to_node_id
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE f.category = $1 AND t.category = $1
+         UNION ALL
+         SELECT f.id, f.not_sure_node_id
+         FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE f.category = $1 AND t.category = $1
+         ORDER BY 1, 2",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?;
+
+    let ids: Vec<Uuid> = nodes.iter().map(|n| n.id).collect();
+    let positions = layered(&ids, &edges);
+    save(db, category, &nodes, positions).await
+}
+
+/// Tidy a freshly imported category: lay it out from scratch when no node
+/// has a position, otherwise just normalize it
+pub async fn tidy_category(db: &PgPool, category: &str) -> Result<LayoutResult, sqlx::Error> {
+    let nodes = load_nodes(db, category).await?;
+    if nodes.iter().all(|n| n.x.is_none() || n.y.is_none()) {
+        layer_category(db, category).await
+    } else {
+        let positions = normalize(&nodes);
+        save(db, category, &nodes, positions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f64, y: f64) -> LayoutNode {
+        LayoutNode { id: Uuid::new_v4(), x: Some(x), y: Some(y) }
+    }
+
+    fn unplaced() -> LayoutNode {
+        LayoutNode { id: Uuid::new_v4(), x: None, y: None }
+    }
+
+    fn spots(nodes: &[LayoutNode]) -> Vec<(f64, f64)> {
+        normalize(nodes).into_iter().map(|(_, x, y)| (x, y)).collect()
+    }
+
+    #[test]
+    fn test_snaps_and_clamps() {
+        let nodes = [at(12.0, 38.0), at(-400.0, 600.0), at(50_000.0, 90_000.0)];
+        assert_eq!(
+            spots(&nodes),
+            vec![(0.0, 50.0), (0.0, 600.0), (CANVAS_SIZE - NODE_WIDTH, CANVAS_SIZE - NODE_HEIGHT)]
+        );
+    }
+
+    #[test]
+    fn test_resolves_overlaps() {
+        // The second and third nodes sit on the first
+        let nodes = [at(100.0, 100.0), at(110.0, 90.0), at(100.0, 100.0)];
+        let result = spots(&nodes);
+        assert_eq!(result[0], (100.0, 100.0));
+        assert_eq!(result[1], (400.0, 100.0));
+        assert_eq!(result[2], (700.0, 100.0));
+    }
+
+    #[test]
+    fn test_places_unpositioned_nodes_below() {
+        let nodes = [unplaced(), at(0.0, 0.0), unplaced(), at(350.0, 200.0)];
+        let result = spots(&nodes);
+        assert_eq!(result[1], (0.0, 0.0));
+        assert_eq!(result[3], (350.0, 200.0));
+        assert_eq!(result[0], (0.0, 350.0));
+        assert_eq!(result[2], (300.0, 350.0));
+    }
+
+    #[test]
+    fn test_well_laid_out_graph_is_unchanged() {
+        let nodes = [at(0.0, 0.0), at(350.0, 0.0), at(0.0, 200.0)];
+        assert_eq!(spots(&nodes), vec![(0.0, 0.0), (350.0, 0.0), (0.0, 200.0)]);
+    }
+
+    fn layered_spots(nodes: &[Uuid], edges: &[(Uuid, Uuid)]) -> Vec<(f64, f64)> {
+        layered(nodes, edges).into_iter().map(|(_, x, y)| (x, y)).collect()
+    }
+
+    #[test]
+    fn test_layered_tree() {
+        // 0 -> 1, 0 -> 2, 1 -> 3
+        let n: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let edges = [(n[0], n[1]), (n[0], n[2]), (n[1], n[3])];
+        let result = layered_spots(&n, &edges);
+
+        assert_eq!(result[0], (175.0, 0.0));
+        assert_eq!(result[1], (0.0, 200.0));
+        assert_eq!(result[2], (350.0, 200.0));
+        assert_eq!(result[3], (175.0, 400.0));
+    }
+
+    #[test]
+    fn test_layered_loops_and_long_answers() {
+        // 0 -> 1 -> 2 -> 0 (loop back to the start), 0 -> 2 (skips a layer)
+        let n: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let edges = [(n[0], n[1]), (n[1], n[2]), (n[2], n[0]), (n[0], n[2])];
+        let layers: Vec<f64> = layered_spots(&n, &edges).into_iter().map(|(_, y)| y).collect();
+
+        assert_eq!(layers, vec![0.0, 200.0, 400.0]);
+    }
+
+    #[test]
+    fn test_layered_orders_children_under_parents() {
+        // Two parents whose children are listed crosswise: 0 -> {a, b}, a -> d, b -> c
+        let n: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let (root, a, b, c, d) = (n[0], n[1], n[2], n[3], n[4]);
+        let edges = [(root, a), (root, b), (a, d), (b, c)];
+        let result = layered_spots(&n, &edges);
+
+        assert!(result[1].0 < result[2].0);
+        // d sits under a, c under b, so the answers don't cross
+        assert!(result[4].0 < result[3].0);
+    }
+}
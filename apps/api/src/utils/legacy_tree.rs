@@ -0,0 +1,315 @@
+/// Converting the legacy question/answer tree
+///
+/// Each answer of the legacy `start` question becomes an issue holding every
+/// question reachable from it; answers with conclusion text become conclusion
+/// nodes. A question reached from two issues is copied into both.
+use crate::routes::issues::{ConnectionExportData, IssueExportData, IssueImportMetadata, NodeExportData};
+use crate::utils::{issue_slugs, semantic_ids};
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Semantic ID of the legacy question every issue hung off
+pub const START_SEMANTIC_ID: &str = "start";
+
+/// A row of the legacy `questions` table
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LegacyQuestion {
+    pub id: Uuid,
+    pub semantic_id: String,
+    pub text: String,
+    pub category: Option<String>,
+    pub is_active: bool,
+}
+
+/// A row of the legacy `answers` table
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LegacyAnswer {
+    pub question_id: Uuid,
+    pub label: String,
+    pub next_question_id: Option<Uuid>,
+    pub conclusion_text: Option<String>,
+    pub order_index: i32,
+    pub is_active: bool,
+}
+
+/// One issue converted from the legacy tree
+#[derive(Debug)]
+pub struct ConvertedIssue {
+    pub data: IssueExportData,
+    pub questions: usize,
+    pub conclusions: usize,
+    /// Parts of the issue's legacy rows that were left out or need a look
+    pub warnings: Vec<String>,
+}
+
+/// The legacy tree as issues
+#[derive(Debug)]
+pub struct Conversion {
+    pub issues: Vec<ConvertedIssue>,
+    /// Legacy rows that didn't make it into any issue
+    pub warnings: Vec<String>,
+}
+
+/// Convert legacy questions and answers into importable issues
+///
+/// Fails only when there is no start question to hang issues off.
+pub fn convert(questions: &[LegacyQuestion], answers: &[LegacyAnswer]) -> Result<Conversion, String> {
+    let by_id: HashMap<Uuid, &LegacyQuestion> = questions.iter().map(|q| (q.id, q)).collect();
+    let mut answers_of: HashMap<Uuid, Vec<&LegacyAnswer>> = HashMap::new();
+    for answer in answers {
+        answers_of.entry(answer.question_id).or_default().push(answer);
+    }
+    for list in answers_of.values_mut() {
+        list.sort_by_key(|a| a.order_index);
+    }
+
+    let start = questions
+        .iter()
+        .find(|q| q.semantic_id.trim() == START_SEMANTIC_ID)
+        .ok_or_else(|| format!("No legacy question has the semantic ID '{}'", START_SEMANTIC_ID))?;
+
+    let mut warnings = Vec::new();
+    let mut issues = Vec::new();
+    let mut categories = HashSet::new();
+    let mut reached = HashSet::from([start.id]);
+    for answer in answers_of.get(&start.id).into_iter().flatten() {
+        if !answer.is_active {
+            warnings.push(format!("Start answer '{}' is switched off; skipped", answer.label));
+            continue;
+        }
+        let Some(root) = answer.next_question_id.and_then(|id| by_id.get(&id)) else {
+            warnings.push(format!("Start answer '{}' doesn't lead to a question; skipped", answer.label));
+            continue;
+        };
+
+        let base = root
+            .category
+            .as_deref()
+            .and_then(|c| issue_slugs::normalize(c).ok())
+            .filter(|c| c != "root")
+            .unwrap_or_else(|| semantic_ids::slugify(&answer.label));
+        let category = semantic_ids::first_free(&base, &categories);
+        categories.insert(category.clone());
+
+        let issue = convert_issue(answer, root, &category, &by_id, &answers_of);
+        reached.extend(issue.1);
+        issues.push(issue.0);
+    }
+
+    let unreached = questions.iter().filter(|q| !reached.contains(&q.id)).count();
+    if unreached > 0 {
+        warnings.push(format!(
+            "{} question(s) can't be reached from the start question and were not converted",
+            unreached
+        ));
+    }
+
+    Ok(Conversion { issues, warnings })
+}
+
+/// The issue under one start answer, and the legacy questions it took in
+fn convert_issue(
+    start_answer: &LegacyAnswer,
+    root: &LegacyQuestion,
+    category: &str,
+    by_id: &HashMap<Uuid, &LegacyQuestion>,
+    answers_of: &HashMap<Uuid, Vec<&LegacyAnswer>>,
+) -> (ConvertedIssue, HashSet<Uuid>) {
+    let root_semantic_id = format!("{}_start", category);
+    let mut taken = HashSet::from([root_semantic_id.clone()]);
+    let mut nodes = vec![node("question", &root.text, Some(root_semantic_id))];
+    let mut connections = Vec::new();
+    let mut warnings = Vec::new();
+    let mut conclusions = 0;
+    let mut skipped_inactive = 0;
+
+    let mut index_of = HashMap::from([(root.id, 0)]);
+    let mut queue = VecDeque::from([root.id]);
+    while let Some(question_id) = queue.pop_front() {
+        let from = index_of[&question_id];
+        let mut answered = false;
+        for answer in answers_of.get(&question_id).into_iter().flatten() {
+            if !answer.is_active {
+                skipped_inactive += 1;
+                continue;
+            }
+            let conclusion = answer.conclusion_text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+            let to = match (answer.next_question_id, conclusion) {
+                (Some(next_id), _) => match by_id.get(&next_id) {
+                    Some(next) => *index_of.entry(next_id).or_insert_with(|| {
+                        let semantic_id = semantic_ids::first_free(next.semantic_id.trim(), &taken);
+                        taken.insert(semantic_id.clone());
+                        nodes.push(node("question", &next.text, Some(semantic_id)));
+                        queue.push_back(next_id);
+                        nodes.len() - 1
+                    }),
+                    None => {
+                        warnings.push(format!("Answer '{}' leads to a missing question; skipped", answer.label));
+                        continue;
+                    }
+                },
+                (None, Some(text)) => {
+                    conclusions += 1;
+                    nodes.push(node("conclusion", text, None));
+                    nodes.len() - 1
+                }
+                (None, None) => {
+                    warnings.push(format!("Answer '{}' has neither a next question nor a conclusion; skipped", answer.label));
+                    continue;
+                }
+            };
+            answered = true;
+            connections.push(ConnectionExportData {
+                from_node_index: from,
+                to_node_index: to,
+                label: answer.label.clone(),
+                order_index: answer.order_index,
+                condition: None,
+                unsure_route: false,
+            });
+        }
+        if !answered {
+            warnings.push(format!("Question '{}' has no answers and will be a dead end", nodes[from].text));
+        }
+    }
+    if skipped_inactive > 0 {
+        warnings.push(format!("{} switched-off answer(s) were left out", skipped_inactive));
+    }
+
+    let issue = ConvertedIssue {
+        data: IssueExportData {
+            issue: IssueImportMetadata {
+                name: start_answer.label.trim().to_string(),
+                category: category.to_string(),
+                display_category: Some(start_answer.label.trim().to_string()),
+                root_question_text: root.text.clone(),
+                is_active: Some(root.is_active),
+                start_order_index: Some(start_answer.order_index),
+                description: None,
+                equipment_models: None,
+                tags: None,
+            },
+            nodes,
+            connections,
+        },
+        questions: index_of.len(),
+        conclusions,
+        warnings,
+    };
+    (issue, index_of.into_keys().collect())
+}
+
+fn node(node_type: &str, text: &str, semantic_id: Option<String>) -> NodeExportData {
+    NodeExportData {
+        node_type: node_type.to_string(),
+        text: text.trim().to_string(),
+        semantic_id,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(semantic_id: &str, text: &str, category: &str) -> LegacyQuestion {
+        LegacyQuestion {
+            id: Uuid::new_v4(),
+            semantic_id: semantic_id.to_string(),
+            text: text.to_string(),
+            category: Some(category.to_string()),
+            is_active: true,
+        }
+    }
+
+    fn answer(question: &LegacyQuestion, label: &str, next: Option<&LegacyQuestion>, conclusion: Option<&str>, order_index: i32) -> LegacyAnswer {
+        LegacyAnswer {
+            question_id: question.id,
+            label: label.to_string(),
+            next_question_id: next.map(|q| q.id),
+            conclusion_text: conclusion.map(str::to_string),
+            order_index,
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let start = question("start", "What are you having trouble with?", "root");
+        let brush = question("brush_check", "What's the issue?", "brush");
+        let breaker = question("breaker_check", "Is the circuit breaker tripped?", "electrical");
+        let orphan = question("old_check", "Unused question", "brush");
+        let questions = vec![start.clone(), brush.clone(), breaker.clone(), orphan];
+        let answers = vec![
+            answer(&start, "Brush", Some(&brush), None, 0),
+            answer(&brush, "Not spinning", Some(&breaker), None, 0),
+            answer(&brush, "Worn", None, Some("Replace the brush"), 1),
+            answer(&breaker, "Yes", None, Some("Reset the breaker"), 0),
+        ];
+
+        let conversion = convert(&questions, &answers).unwrap();
+        assert_eq!(conversion.issues.len(), 1);
+        assert_eq!(
+            conversion.warnings,
+            ["1 question(s) can't be reached from the start question and were not converted"]
+        );
+
+        let issue = &conversion.issues[0];
+        assert_eq!(issue.data.issue.category, "brush");
+        assert_eq!(issue.data.issue.name, "Brush");
+        assert_eq!(issue.data.issue.root_question_text, "What's the issue?");
+        assert_eq!((issue.questions, issue.conclusions), (2, 2));
+        let semantic_ids: Vec<Option<&str>> = issue.data.nodes.iter().map(|n| n.semantic_id.as_deref()).collect();
+        assert_eq!(semantic_ids, [Some("brush_start"), Some("breaker_check"), None, None]);
+        let edges: Vec<(usize, usize, &str)> = issue
+            .data
+            .connections
+            .iter()
+            .map(|c| (c.from_node_index, c.to_node_index, c.label.as_str()))
+            .collect();
+        assert_eq!(edges, [(0, 1, "Not spinning"), (0, 2, "Worn"), (1, 3, "Yes")]);
+        assert!(issue.warnings.is_empty(), "{:?}", issue.warnings);
+    }
+
+    #[test]
+    fn test_shared_questions_are_copied() {
+        let start = question("start", "What are you having trouble with?", "root");
+        let a = question("a_check", "A?", "");
+        let b = question("b_check", "B?", "");
+        let shared = question("shared", "Is it plugged in?", "electrical");
+        let questions = vec![start.clone(), a.clone(), b.clone(), shared.clone()];
+        let answers = vec![
+            answer(&start, "Pump A", Some(&a), None, 0),
+            answer(&start, "Pump A", Some(&b), None, 1),
+            answer(&a, "Dead", Some(&shared), None, 0),
+            answer(&b, "Dead", Some(&shared), None, 0),
+            answer(&shared, "No", None, Some("Plug it in"), 0),
+        ];
+
+        let conversion = convert(&questions, &answers).unwrap();
+        let categories: Vec<&str> = conversion.issues.iter().map(|i| i.data.issue.category.as_str()).collect();
+        assert_eq!(categories, ["pump_a", "pump_a_2"]);
+        assert!(conversion.issues.iter().all(|i| i.questions == 2 && i.conclusions == 1));
+        assert!(conversion.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_cycles_and_dead_ends() {
+        let start = question("start", "Start", "root");
+        let loop_q = question("loop", "Try again?", "general");
+        let mut off = answer(&loop_q, "Give up", None, Some("Call support"), 1);
+        off.is_active = false;
+        let questions = vec![start.clone(), loop_q.clone()];
+        let answers = vec![answer(&start, "General", Some(&loop_q), None, 0), answer(&loop_q, "Yes", Some(&loop_q), None, 0), off];
+
+        let issue = &convert(&questions, &answers).unwrap().issues[0];
+        assert_eq!(issue.data.nodes.len(), 1);
+        assert_eq!(issue.data.connections[0].to_node_index, 0);
+        assert_eq!(issue.warnings, ["1 switched-off answer(s) were left out"]);
+    }
+
+    #[test]
+    fn test_missing_start() {
+        assert!(convert(&[question("brush_check", "What's the issue?", "brush")], &[]).is_err());
+    }
+}
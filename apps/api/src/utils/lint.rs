@@ -0,0 +1,337 @@
+/// Content lint rules for node text
+///
+/// Runs a configurable set of style checks over nodes so content reviews
+/// can start from a report instead of reading every tree by hand.
+use crate::models::{Node, NodeType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Time allowed for each knowledge base link check
+const LINK_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Links checked in parallel
+const LINK_CHECK_CONCURRENCY: usize = 8;
+
+/// Configurable lint rules (every field falls back to a sensible default)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(default)]
+pub struct LintRules {
+    /// Minimum number of characters in node text
+    pub min_length: usize,
+    /// Maximum number of characters in node text
+    pub max_length: usize,
+    /// Words that must not appear in node text (case-insensitive)
+    pub forbidden_words: Vec<String>,
+    /// Question nodes must end with a question mark
+    pub require_question_mark: bool,
+    /// Flag conclusions written entirely in capital letters
+    pub flag_all_caps_conclusions: bool,
+    /// Send a HEAD request to every conclusion kb_url and flag broken links
+    pub check_kb_links: bool,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        Self {
+            min_length: 3,
+            max_length: 500,
+            forbidden_words: vec![],
+            require_question_mark: true,
+            flag_all_caps_conclusions: true,
+            check_kb_links: true,
+        }
+    }
+}
+
+/// Severity of a lint finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+/// A single rule violation on a node
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct LintIssue {
+    pub rule: String,
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+/// All findings for one node
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodeLintResult {
+    pub node_id: Uuid,
+    pub category: String,
+    pub semantic_id: Option<String>,
+    pub node_type: NodeType,
+    pub text: String,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintIssue {
+    fn new(rule: &str, severity: LintSeverity, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.to_string(),
+            severity,
+            message: message.into(),
+        }
+    }
+}
+
+/// Run all enabled rules against a single node
+pub fn lint_node(node: &Node, rules: &LintRules) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let text = node.text.trim();
+    let length = text.chars().count();
+
+    if length < rules.min_length {
+        issues.push(LintIssue::new(
+            "min_length",
+            LintSeverity::Error,
+            format!("Text is {} characters, minimum is {}", length, rules.min_length),
+        ));
+    }
+
+    if length > rules.max_length {
+        issues.push(LintIssue::new(
+            "max_length",
+            LintSeverity::Warning,
+            format!("Text is {} characters, maximum is {}", length, rules.max_length),
+        ));
+    }
+
+    let lowered = text.to_lowercase();
+    for word in &rules.forbidden_words {
+        let needle = word.trim().to_lowercase();
+        if needle.is_empty() {
+            continue;
+        }
+        let found = lowered
+            .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+            .any(|token| token == needle);
+        if found {
+            issues.push(LintIssue::new(
+                "forbidden_word",
+                LintSeverity::Error,
+                format!("Text contains forbidden word '{}'", word.trim()),
+            ));
+        }
+    }
+
+    match node.node_type {
+        NodeType::Question => {
+            if rules.require_question_mark && !text.is_empty() && !text.ends_with('?') {
+                issues.push(LintIssue::new(
+                    "question_mark",
+                    LintSeverity::Warning,
+                    "Question text should end with a question mark",
+                ));
+            }
+        }
+        NodeType::Conclusion => {
+            if rules.flag_all_caps_conclusions && is_all_caps(text) {
+                issues.push(LintIssue::new(
+                    "all_caps",
+                    LintSeverity::Warning,
+                    "Conclusion is written in all capital letters",
+                ));
+            }
+            if let Some(url) = &node.kb_url {
+                if !is_http_url(url) {
+                    issues.push(LintIssue::new(
+                        "kb_url",
+                        LintSeverity::Error,
+                        "Knowledge base link is not a valid http(s) URL",
+                    ));
+                }
+            }
+        }
+        NodeType::Subflow | NodeType::Return => {}
+    }
+
+    issues
+}
+
+/// Lint a batch of nodes, returning only nodes that have findings
+///
+/// When `check_kb_links` is on, conclusion knowledge base links are also
+/// requested to confirm they still respond.
+pub async fn lint_nodes(nodes: &[Node], rules: &LintRules) -> Vec<NodeLintResult> {
+    let mut link_issues = if rules.check_kb_links {
+        check_kb_links(nodes).await
+    } else {
+        HashMap::new()
+    };
+
+    nodes
+        .iter()
+        .filter_map(|node| {
+            let mut issues = lint_node(node, rules);
+            issues.extend(link_issues.remove(&node.id));
+            if issues.is_empty() {
+                return None;
+            }
+            Some(NodeLintResult {
+                node_id: node.id,
+                category: node.category.clone(),
+                semantic_id: node.semantic_id.clone(),
+                node_type: node.node_type.clone(),
+                text: node.text.clone(),
+                issues,
+            })
+        })
+        .collect()
+}
+
+/// True for absolute http/https URLs with a host
+pub fn is_http_url(value: &str) -> bool {
+    reqwest::Url::parse(value.trim())
+        .map(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+        .unwrap_or(false)
+}
+
+/// HEAD-check every valid conclusion kb_url, returning a finding per broken link
+///
+/// Each distinct URL is requested once. Servers that reject HEAD are retried
+/// with GET before the link is reported.
+async fn check_kb_links(nodes: &[Node]) -> HashMap<Uuid, LintIssue> {
+    let mut urls: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for node in nodes {
+        if let Some(url) = node.kb_url.as_deref().filter(|u| is_http_url(u)) {
+            urls.entry(url.trim().to_string()).or_default().push(node.id);
+        }
+    }
+
+    let client = match reqwest::Client::builder().timeout(LINK_CHECK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("⚠️  Could not build HTTP client for link checks: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    let limiter = Arc::new(tokio::sync::Semaphore::new(LINK_CHECK_CONCURRENCY));
+    let mut checks = tokio::task::JoinSet::new();
+    for (url, node_ids) in urls {
+        let client = client.clone();
+        let limiter = limiter.clone();
+        checks.spawn(async move {
+            let _permit = limiter.acquire_owned().await;
+            (check_link(&client, &url).await, node_ids)
+        });
+    }
+
+    let mut findings = HashMap::new();
+    while let Some(result) = checks.join_next().await {
+        if let Ok((Err(message), node_ids)) = result {
+            for node_id in node_ids {
+                findings.insert(
+                    node_id,
+                    LintIssue::new("kb_link", LintSeverity::Error, message.clone()),
+                );
+            }
+        }
+    }
+    findings
+}
+
+async fn check_link(client: &reqwest::Client, url: &str) -> Result<(), String> {
+    let mut response = client.head(url).send().await;
+
+    if let Ok(r) = &response {
+        if matches!(r.status().as_u16(), 403 | 405 | 501) {
+            response = client.get(url).send().await;
+        }
+    }
+
+    match response {
+        Ok(r) if r.status().is_success() => Ok(()),
+        Ok(r) => Err(format!("Knowledge base link returned HTTP {}", r.status().as_u16())),
+        Err(e) if e.is_timeout() => Err("Knowledge base link timed out".to_string()),
+        Err(_) => Err("Knowledge base link is unreachable".to_string()),
+    }
+}
+
+/// True when the text has at least two letters and none of them are lowercase
+fn is_all_caps(text: &str) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() >= 2 && letters.iter().all(|c| !c.is_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn node(node_type: NodeType, text: &str) -> Node {
+        Node {
+            id: Uuid::new_v4(),
+            category: "brush".to_string(),
+            node_type,
+            text: text.to_string(),
+            semantic_id: None,
+            display_category: None,
+            position_x: None,
+            position_y: None,
+            kb_url: None,
+            doc_reference: None,
+            option_order: Default::default(),
+            answer_mode: Default::default(),
+            not_sure_node_id: None,
+            subflow_category: None,
+            content: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_question_mark_rule() {
+        let rules = LintRules::default();
+        let issues = lint_node(&node(NodeType::Question, "Is the brush worn"), &rules);
+        assert!(issues.iter().any(|i| i.rule == "question_mark"));
+
+        let issues = lint_node(&node(NodeType::Question, "Is the brush worn?"), &rules);
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_all_caps_and_forbidden_words() {
+        let rules = LintRules {
+            forbidden_words: vec!["just".to_string()],
+            ..LintRules::default()
+        };
+
+        let issues = lint_node(&node(NodeType::Conclusion, "REPLACE THE BRUSH"), &rules);
+        assert!(issues.iter().any(|i| i.rule == "all_caps"));
+
+        let issues = lint_node(&node(NodeType::Conclusion, "Just replace the brush"), &rules);
+        assert!(issues.iter().any(|i| i.rule == "forbidden_word"));
+
+        let issues = lint_node(&node(NodeType::Conclusion, "Adjust the brush"), &rules);
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_kb_url_format() {
+        assert!(is_http_url("https://docs.example.com/manual#brush"));
+        assert!(is_http_url("http://intranet/kb/123"));
+        assert!(!is_http_url("ftp://files.example.com/manual.pdf"));
+        assert!(!is_http_url("/kb/123"));
+
+        let mut conclusion = node(NodeType::Conclusion, "Replace the brush");
+        conclusion.kb_url = Some("not a url".to_string());
+        let issues = lint_node(&conclusion, &LintRules::default());
+        assert!(issues.iter().any(|i| i.rule == "kb_url"));
+    }
+}
@@ -0,0 +1,157 @@
+/// Runtime-adjustable tracing filter
+///
+/// The subscriber is installed with a reloadable `EnvFilter` so admins can turn
+/// up logging for specific modules while debugging production issues. Every
+/// override reverts to the startup filter (RUST_LOG) after a timeout.
+use chrono::{DateTime, Utc};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Filter used when RUST_LOG is not set
+const DEFAULT_FILTER: &str = "info";
+
+struct LogLevelState {
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// Filter the process started with; overrides revert to this
+    startup_filter: String,
+    current_filter: String,
+    revert_at: Option<DateTime<Utc>>,
+    /// Bumped on every change so stale revert timers do nothing
+    generation: u64,
+}
+
+static STATE: OnceLock<Mutex<LogLevelState>> = OnceLock::new();
+
+/// Snapshot of the active filter
+#[derive(Debug, Clone)]
+pub struct LogLevelStatus {
+    pub startup_filter: String,
+    pub current_filter: String,
+    pub revert_at: Option<DateTime<Utc>>,
+}
+
+/// Install the global tracing subscriber with a reloadable filter
+pub fn init() {
+    let startup_filter = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|f| EnvFilter::try_new(f).is_ok())
+        .unwrap_or_else(|| DEFAULT_FILTER.to_string());
+
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(&startup_filter));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let _ = STATE.set(Mutex::new(LogLevelState {
+        handle,
+        current_filter: startup_filter.clone(),
+        startup_filter,
+        revert_at: None,
+        generation: 0,
+    }));
+}
+
+/// Check that a filter string parses as `EnvFilter` directives
+pub fn validate_filter(filter: &str) -> Result<(), String> {
+    if filter.trim().is_empty() {
+        return Err("Filter cannot be empty".to_string());
+    }
+    EnvFilter::try_new(filter)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid filter directives: {}", e))
+}
+
+fn state() -> Result<&'static Mutex<LogLevelState>, String> {
+    STATE
+        .get()
+        .ok_or_else(|| "Runtime log level control is not initialized".to_string())
+}
+
+fn snapshot(state: &LogLevelState) -> LogLevelStatus {
+    LogLevelStatus {
+        startup_filter: state.startup_filter.clone(),
+        current_filter: state.current_filter.clone(),
+        revert_at: state.revert_at,
+    }
+}
+
+/// Current filter and pending revert time
+pub fn status() -> Result<LogLevelStatus, String> {
+    let state = state()?.lock().map_err(|_| "Log level state poisoned".to_string())?;
+    Ok(snapshot(&state))
+}
+
+/// Apply a new filter, reverting to the startup filter after `revert_after`
+pub fn set_filter(filter: &str, revert_after: Duration) -> Result<LogLevelStatus, String> {
+    validate_filter(filter)?;
+
+    let generation = {
+        let mut state = state()?.lock().map_err(|_| "Log level state poisoned".to_string())?;
+        state
+            .handle
+            .reload(EnvFilter::new(filter))
+            .map_err(|e| format!("Failed to reload filter: {}", e))?;
+
+        state.current_filter = filter.to_string();
+        state.revert_at = chrono::Duration::from_std(revert_after)
+            .ok()
+            .map(|d| Utc::now() + d);
+        state.generation += 1;
+        state.generation
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(revert_after).await;
+        if let Ok(Some(status)) = revert_if_current(generation) {
+            tracing::info!("🔁 Log filter reverted to '{}' after timeout", status.startup_filter);
+        }
+    });
+
+    status()
+}
+
+/// Restore the startup filter immediately
+pub fn reset() -> Result<LogLevelStatus, String> {
+    let mut state = state()?.lock().map_err(|_| "Log level state poisoned".to_string())?;
+    restore(&mut state)?;
+    Ok(snapshot(&state))
+}
+
+/// Revert only if no newer change was made since `generation`
+fn revert_if_current(generation: u64) -> Result<Option<LogLevelStatus>, String> {
+    let mut state = state()?.lock().map_err(|_| "Log level state poisoned".to_string())?;
+    if state.generation != generation {
+        return Ok(None);
+    }
+    restore(&mut state)?;
+    Ok(Some(snapshot(&state)))
+}
+
+fn restore(state: &mut LogLevelState) -> Result<(), String> {
+    state
+        .handle
+        .reload(EnvFilter::new(&state.startup_filter))
+        .map_err(|e| format!("Failed to reload filter: {}", e))?;
+    state.current_filter = state.startup_filter.clone();
+    state.revert_at = None;
+    state.generation += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_filter() {
+        assert!(validate_filter("info,equipment_troubleshooting::routes=debug").is_ok());
+        assert!(validate_filter("warn").is_ok());
+        assert!(validate_filter("").is_err());
+        assert!(validate_filter("routes=notalevel").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+/// Account lockout and per-IP login throttling
+///
+/// Consecutive wrong passwords lock the account for a cooldown. Independently,
+/// an IP with too many failures in the window is refused before any password
+/// check, which stops one client spraying guesses across many accounts. Both
+/// sit on top of the generic request rate limiter.
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Consecutive failures before an account locks (LOGIN_MAX_FAILURES)
+const DEFAULT_MAX_FAILURES: i32 = 5;
+/// Lockout cooldown in minutes (LOGIN_LOCKOUT_MINUTES)
+const DEFAULT_LOCKOUT_MINUTES: i32 = 15;
+/// Failures per IP allowed within the window (LOGIN_IP_MAX_FAILURES)
+const DEFAULT_IP_MAX_FAILURES: i64 = 20;
+/// Per-IP window in minutes (LOGIN_IP_WINDOW_MINUTES)
+const DEFAULT_IP_WINDOW_MINUTES: i32 = 15;
+
+/// Lockout settings (from environment)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutConfig {
+    pub max_failures: i32,
+    pub lockout_minutes: i32,
+    pub ip_max_failures: i64,
+    pub ip_window_minutes: i32,
+}
+
+impl LockoutConfig {
+    pub fn from_env() -> Self {
+        fn env_positive<T: std::str::FromStr + PartialOrd + Default>(name: &str, default: T) -> T {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<T>().ok())
+                .filter(|v| *v > T::default())
+                .unwrap_or(default)
+        }
+
+        Self {
+            max_failures: env_positive("LOGIN_MAX_FAILURES", DEFAULT_MAX_FAILURES),
+            lockout_minutes: env_positive("LOGIN_LOCKOUT_MINUTES", DEFAULT_LOCKOUT_MINUTES),
+            ip_max_failures: env_positive("LOGIN_IP_MAX_FAILURES", DEFAULT_IP_MAX_FAILURES),
+            ip_window_minutes: env_positive("LOGIN_IP_WINDOW_MINUTES", DEFAULT_IP_WINDOW_MINUTES),
+        }
+    }
+}
+
+/// Process-wide lockout settings
+pub fn config() -> &'static LockoutConfig {
+    static CONFIG: OnceLock<LockoutConfig> = OnceLock::new();
+    CONFIG.get_or_init(LockoutConfig::from_env)
+}
+
+/// Seconds until `until`, rounded up and never below one
+pub fn seconds_until(until: DateTime<Utc>) -> u64 {
+    let millis = (until - Utc::now()).num_milliseconds().max(1) as u64;
+    millis.div_ceil(1000)
+}
+
+/// When an IP over its failure budget may try again (None = not throttled)
+pub async fn ip_blocked_until(db: &PgPool, ip: &str, config: &LockoutConfig) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    // The oldest failure that still counts decides when the window frees up
+    sqlx::query_scalar::<_, DateTime<Utc>>(
+        "SELECT attempted_at + make_interval(mins => $2)
+         FROM failed_logins
+         WHERE ip_address = $1 AND attempted_at > NOW() - make_interval(mins => $2)
+         ORDER BY attempted_at DESC
+         OFFSET ($3 - 1)
+         LIMIT 1"
+    )
+    .bind(ip)
+    .bind(config.ip_window_minutes)
+    .bind(config.ip_max_failures)
+    .fetch_optional(db)
+    .await
+}
+
+/// Result of recording a wrong password for an existing account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailureOutcome {
+    /// Consecutive failures including this one
+    pub failed_count: i32,
+    /// Set when this failure locked the account
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// Record a failed login for rate limiting and, for known accounts, lockout
+pub async fn record_failure(
+    db: &PgPool,
+    email: &str,
+    user_id: Option<Uuid>,
+    ip: Option<&str>,
+    config: &LockoutConfig,
+) -> Result<Option<FailureOutcome>, sqlx::Error> {
+    sqlx::query("INSERT INTO failed_logins (email, user_id, ip_address) VALUES ($1, $2, $3)")
+        .bind(email.chars().take(255).collect::<String>())
+        .bind(user_id)
+        .bind(ip)
+        .execute(db)
+        .await?;
+
+    let Some(user_id) = user_id else {
+        return Ok(None);
+    };
+
+    // SET expressions see the old row, so both columns agree on whether this failure locks
+    let (failed_count, locked_until, locked_now) = sqlx::query_as::<_, (i32, Option<DateTime<Utc>>, bool)>(
+        "UPDATE users SET
+             failed_login_count = CASE WHEN failed_login_count + 1 >= $2 THEN 0 ELSE failed_login_count + 1 END,
+             locked_until = CASE WHEN failed_login_count + 1 >= $2
+                                 THEN NOW() + make_interval(mins => $3)
+                                 ELSE locked_until END,
+             last_failed_login_at = NOW()
+         WHERE id = $1
+         RETURNING
+             CASE WHEN failed_login_count = 0 AND locked_until > NOW() THEN $2 ELSE failed_login_count END,
+             locked_until,
+             (failed_login_count = 0 AND locked_until > NOW())"
+    )
+    .bind(user_id)
+    .bind(config.max_failures)
+    .bind(config.lockout_minutes)
+    .fetch_one(db)
+    .await?;
+
+    Ok(Some(FailureOutcome {
+        failed_count,
+        locked_until: if locked_now { locked_until } else { None },
+    }))
+}
+
+/// Current lock on an account, if it hasn't expired
+pub async fn locked_until(db: &PgPool, user_id: Uuid) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        "SELECT locked_until FROM users WHERE id = $1 AND locked_until > NOW()"
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map(Option::flatten)
+}
+
+/// Clear failure counters after a successful login or password reset
+pub async fn clear_failures<'e, E>(executor: E, user_id: Uuid) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query(
+        "UPDATE users SET failed_login_count = 0, locked_until = NULL
+         WHERE id = $1 AND (failed_login_count <> 0 OR locked_until IS NOT NULL)"
+    )
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Delete failed attempts older than a day (well past any throttling window)
+pub async fn purge_old_failures(db: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM failed_logins WHERE attempted_at < NOW() - INTERVAL '1 day'")
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_config_defaults() {
+        std::env::remove_var("LOGIN_MAX_FAILURES");
+        std::env::remove_var("LOGIN_LOCKOUT_MINUTES");
+        std::env::remove_var("LOGIN_IP_MAX_FAILURES");
+        std::env::remove_var("LOGIN_IP_WINDOW_MINUTES");
+
+        assert_eq!(
+            LockoutConfig::from_env(),
+            LockoutConfig {
+                max_failures: DEFAULT_MAX_FAILURES,
+                lockout_minutes: DEFAULT_LOCKOUT_MINUTES,
+                ip_max_failures: DEFAULT_IP_MAX_FAILURES,
+                ip_window_minutes: DEFAULT_IP_WINDOW_MINUTES,
+            }
+        );
+    }
+
+    #[test]
+    fn test_seconds_until_rounds_up() {
+        assert_eq!(seconds_until(Utc::now() - Duration::seconds(5)), 1);
+        let secs = seconds_until(Utc::now() + Duration::milliseconds(90_500));
+        assert!((90..=91).contains(&secs));
+    }
+}
@@ -1,3 +1,52 @@
+pub mod analytics;
 pub mod audit;
+pub mod branding;
 pub mod cache;
+pub mod cache_warming;
+pub mod category_access;
+pub mod category_notices;
+pub mod category_quotas;
+pub mod client_ip;
+pub mod conditions;
+pub mod content;
+pub mod cycles;
+pub mod devices;
+pub mod diagrams;
+pub mod error_buffer;
+pub mod expression;
+pub mod graph_validation;
+pub mod import_adapters;
+pub mod issue_drafts;
+pub mod issue_metadata;
+pub mod issue_approvals;
+pub mod issue_bundle;
+pub mod issue_csv;
+pub mod issue_schedules;
+pub mod issue_slugs;
+pub mod issue_versions;
 pub mod jwt;
+pub mod jwt_keys;
+pub mod layout;
+pub mod legacy_tree;
+pub mod lint;
+pub mod log_level;
+pub mod login_guard;
+pub mod node_revisions;
+pub mod orphans;
+pub mod password;
+pub mod permissions;
+pub mod pool_metrics;
+pub mod refresh_tokens;
+pub mod remaining_depth;
+pub mod rollout;
+pub mod semantic_ids;
+pub mod service_tokens;
+pub mod session_archive;
+pub mod session_events;
+pub mod session_expiry;
+pub mod session_watch;
+pub mod settings;
+pub mod siem;
+pub mod static_cache;
+pub mod telemetry;
+pub mod template;
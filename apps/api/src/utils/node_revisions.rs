@@ -0,0 +1,185 @@
+/// Field-level node change history
+///
+/// Node updates record one `node_revisions` row per changed field with the
+/// old and new value as JSON, so any single field can be reverted later.
+/// Layout (position_x/position_y) is not tracked; the editor moves nodes
+/// constantly and those changes would drown out content edits.
+use crate::models::Node;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgConnection;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Node fields with history, as named in the JSON representation and the `nodes` table
+pub const TRACKED_FIELDS: &[&str] = &[
+    "text",
+    "semantic_id",
+    "node_type",
+    "display_category",
+    "kb_url",
+    "doc_reference",
+    "option_order",
+    "answer_mode",
+    "not_sure_node_id",
+    "subflow_category",
+    "content",
+    "is_active",
+];
+
+/// One changed field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+/// A recorded change, as listed in a node's history
+#[derive(Debug, Serialize, TS, sqlx::FromRow)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct NodeRevision {
+    pub id: Uuid,
+    pub node_id: Uuid,
+    pub field: String,
+    #[ts(type = "unknown")]
+    pub old_value: Value,
+    #[ts(type = "unknown")]
+    pub new_value: Value,
+    pub changed_by: Option<Uuid>,
+    /// Email of the editor (null once the account is gone)
+    pub changed_by_email: Option<String>,
+    pub changed_at: DateTime<Utc>,
+    /// Revision this change undid, for reverts
+    pub reverted_revision_id: Option<Uuid>,
+}
+
+/// Tracked fields that differ between two versions of a node
+pub fn diff(before: &Node, after: &Node) -> Vec<FieldChange> {
+    let (Ok(before), Ok(after)) = (serde_json::to_value(before), serde_json::to_value(after)) else {
+        return Vec::new();
+    };
+
+    diff_fields(&before, &after, TRACKED_FIELDS)
+}
+
+/// Listed fields that differ between two JSON objects (a missing field counts as null)
+pub fn diff_fields(before: &Value, after: &Value, fields: &[&'static str]) -> Vec<FieldChange> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let old_value = before.get(*field).cloned().unwrap_or(Value::Null);
+            let new_value = after.get(*field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then_some(FieldChange { field, old_value, new_value })
+        })
+        .collect()
+}
+
+/// Store changes made to a node by one edit
+pub async fn record(
+    conn: &mut PgConnection,
+    node_id: Uuid,
+    changed_by: Uuid,
+    changes: &[FieldChange],
+    reverted_revision_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let fields: Vec<&str> = changes.iter().map(|c| c.field).collect();
+    let old_values: Vec<Value> = changes.iter().map(|c| c.old_value.clone()).collect();
+    let new_values: Vec<Value> = changes.iter().map(|c| c.new_value.clone()).collect();
+
+    sqlx::query(
+        "INSERT INTO node_revisions (node_id, field, old_value, new_value, changed_by, reverted_revision_id)
+         SELECT $1, field, old_value, new_value, $5, $6
+         FROM unnest($2::varchar[], $3::jsonb[], $4::jsonb[]) AS r(field, old_value, new_value)",
+    )
+    .bind(node_id)
+    .bind(&fields)
+    .bind(&old_values)
+    .bind(&new_values)
+    .bind(changed_by)
+    .bind(reverted_revision_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// History of a node, newest first
+pub async fn list(db: &sqlx::PgPool, node_id: Uuid, limit: i64) -> Result<Vec<NodeRevision>, sqlx::Error> {
+    sqlx::query_as::<_, NodeRevision>(
+        "SELECT r.id, r.node_id, r.field, r.old_value, r.new_value, r.changed_by, u.email AS changed_by_email,
+                r.changed_at, r.reverted_revision_id
+         FROM node_revisions r
+         LEFT JOIN users u ON u.id = r.changed_by
+         WHERE r.node_id = $1
+         ORDER BY r.changed_at DESC, r.id
+         LIMIT $2",
+    )
+    .bind(node_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AnswerMode, NodeType, OptionOrder};
+    use serde_json::json;
+
+    fn node() -> Node {
+        let now = Utc::now();
+        Node {
+            id: Uuid::new_v4(),
+            category: "printer".to_string(),
+            node_type: NodeType::Question,
+            text: "Is it on?".to_string(),
+            semantic_id: None,
+            display_category: None,
+            position_x: Some(0.0),
+            position_y: Some(0.0),
+            kb_url: None,
+            doc_reference: None,
+            option_order: OptionOrder::OrderIndex,
+            answer_mode: AnswerMode::Single,
+            not_sure_node_id: None,
+            subflow_category: None,
+            content: None,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_diff_tracks_content_not_layout() {
+        let before = node();
+        let mut after = before.clone();
+        after.text = "Is the power LED on?".to_string();
+        after.semantic_id = Some("power_check".to_string());
+        after.position_x = Some(250.0);
+        after.updated_at = before.updated_at + chrono::Duration::seconds(5);
+
+        assert_eq!(
+            diff(&before, &after),
+            vec![
+                FieldChange {
+                    field: "text",
+                    old_value: json!("Is it on?"),
+                    new_value: json!("Is the power LED on?"),
+                },
+                FieldChange {
+                    field: "semantic_id",
+                    old_value: Value::Null,
+                    new_value: json!("power_check"),
+                },
+            ]
+        );
+        assert!(diff(&before, &before.clone()).is_empty());
+    }
+}
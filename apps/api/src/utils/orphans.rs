@@ -0,0 +1,161 @@
+/// Stranded nodes in an issue
+///
+/// A node is stranded when nothing leads to it: no answer or "I'm not sure"
+/// option on the way from the issue's start, no entry point and no answer
+/// from another category. Answers count whether or not they are switched on,
+/// so switching one off doesn't make its target look stranded. Editing the
+/// graph leaves these behind; the cleanup endpoint lists them and switches
+/// them off or deletes them.
+use crate::models::NodeType;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet, VecDeque};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A node nothing leads to
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct OrphanNode {
+    pub id: Uuid,
+    pub node_type: NodeType,
+    pub text: String,
+    pub semantic_id: Option<String>,
+    pub is_active: bool,
+}
+
+/// An issue's stranded nodes
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct OrphanReport {
+    pub category: String,
+    /// The issue's start node
+    pub root_node_id: Uuid,
+    /// In creation order
+    pub nodes: Vec<OrphanNode>,
+}
+
+/// Every node some path from `roots` reaches, roots included, in visiting order
+pub fn reachable(roots: impl IntoIterator<Item = Uuid>, edges: &[(Uuid, Uuid)]) -> Vec<Uuid> {
+    let mut children: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for &(from, to) in edges {
+        children.entry(from).or_default().push(to);
+    }
+
+    let mut seen: HashSet<Uuid> = HashSet::new();
+    let mut order = Vec::new();
+    let mut queue: VecDeque<Uuid> = roots.into_iter().collect();
+    while let Some(id) = queue.pop_front() {
+        if seen.insert(id) {
+            order.push(id);
+            queue.extend(children.get(&id).into_iter().flatten().copied());
+        }
+    }
+    order
+}
+
+/// Nodes of `nodes` that no path from `root` or `entries` reaches, in order
+pub fn unreachable(root: Uuid, nodes: &[Uuid], edges: &[(Uuid, Uuid)], entries: &HashSet<Uuid>) -> Vec<Uuid> {
+    let reached: HashSet<Uuid> = reachable(std::iter::once(root).chain(entries.iter().copied()), edges)
+        .into_iter()
+        .collect();
+
+    nodes.iter().copied().filter(|id| !reached.contains(id)).collect()
+}
+
+/// Find a category's stranded nodes (None when the category has no nodes)
+pub async fn find(db: &PgPool, category: &str) -> Result<Option<OrphanReport>, sqlx::Error> {
+    // The start node first, then the rest as created
+    let nodes = sqlx::query_as::<_, (Uuid, NodeType, String, Option<String>, bool)>(
+        "SELECT id, node_type, text, semantic_id, is_active FROM nodes
+         WHERE category = $1
+         ORDER BY semantic_id IS NOT DISTINCT FROM $1 || '_start' DESC, created_at, id",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?;
+    let Some(root_node_id) = nodes.first().map(|(id, ..)| *id) else {
+        return Ok(None);
+    };
+
+    let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT c.from_node_id, c.to_node_id
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         WHERE f.category = $1
+         UNION ALL
+         SELECT id, not_sure_node_id FROM nodes
+         WHERE category = $1 AND not_sure_node_id IS NOT NULL",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?;
+
+    // Other ways in: entry points and answers in other categories
+    let entries = sqlx::query_scalar::<_, Uuid>(
+        "SELECT e.node_id FROM entry_points e
+         JOIN nodes n ON n.id = e.node_id
+         WHERE n.category = $1
+         UNION
+         SELECT c.to_node_id FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE t.category = $1 AND f.category <> $1
+         UNION
+         SELECT t.id FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE t.category = $1 AND f.category <> $1",
+    )
+    .bind(category)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    let ids: Vec<Uuid> = nodes.iter().map(|(id, ..)| *id).collect();
+    let stranded: HashSet<Uuid> = unreachable(root_node_id, &ids, &edges, &entries).into_iter().collect();
+
+    Ok(Some(OrphanReport {
+        category: category.to_string(),
+        root_node_id,
+        nodes: nodes
+            .into_iter()
+            .filter(|(id, ..)| stranded.contains(id))
+            .map(|(id, node_type, text, semantic_id, is_active)| OrphanNode { id, node_type, text, semantic_id, is_active })
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreachable() {
+        let ids: Vec<Uuid> = (0..6).map(|_| Uuid::new_v4()).collect();
+        // 0 -> 1 -> 2; 3 is an entry point leading to 4; 5 -> 2 leads in but nothing reaches 5
+        let edges = vec![(ids[0], ids[1]), (ids[1], ids[2]), (ids[3], ids[4]), (ids[5], ids[2])];
+        let entries = HashSet::from([ids[3]]);
+
+        assert_eq!(unreachable(ids[0], &ids, &edges, &entries), vec![ids[5]]);
+        assert_eq!(unreachable(ids[0], &ids, &edges, &HashSet::new()), vec![ids[3], ids[4], ids[5]]);
+    }
+
+    #[test]
+    fn test_reachable_in_visiting_order() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        // 1 -> 2 -> 3 -> 1 loops; 0 leads into it; 4 is apart
+        let edges = vec![(ids[0], ids[1]), (ids[1], ids[2]), (ids[2], ids[3]), (ids[3], ids[1]), (ids[4], ids[2])];
+
+        assert_eq!(reachable([ids[1]], &edges), vec![ids[1], ids[2], ids[3]]);
+        assert_eq!(reachable([ids[0]], &edges), vec![ids[0], ids[1], ids[2], ids[3]]);
+    }
+
+    #[test]
+    fn test_unreachable_loops_back_to_start() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let edges = vec![(ids[0], ids[1]), (ids[1], ids[0]), (ids[2], ids[2])];
+
+        assert_eq!(unreachable(ids[0], &ids, &edges, &HashSet::new()), vec![ids[2]]);
+    }
+}
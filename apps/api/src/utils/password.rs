@@ -0,0 +1,99 @@
+/// Password hashing, policy and opaque tokens
+///
+/// Passwords are hashed with Argon2 (same parameters as the hash_password
+/// tool). Opaque tokens (password reset, refresh) are random and only their
+/// SHA-256 digest is stored, so a leaked table cannot be replayed.
+use argon2::{
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        PasswordHasher, SaltString,
+    },
+    Argon2, PasswordHash, PasswordVerifier,
+};
+use sha2::{Digest, Sha256};
+
+/// Minimum password length
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Hash a password with Argon2
+pub fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash password: {}", e))
+}
+
+/// Check a password against a stored Argon2 hash
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+/// Validate a password against the account policy
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+    if password.trim().is_empty() {
+        return Err("Password cannot be only whitespace".to_string());
+    }
+    Ok(())
+}
+
+/// Generate a random opaque token (64 hex characters)
+pub fn generate_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Random code drawn uniformly from `alphabet` (at most 256 symbols)
+pub fn generate_code(alphabet: &[u8], length: usize) -> String {
+    // Reject bytes past the last full multiple of the alphabet size to avoid modulo bias
+    let limit = 256 - 256 % alphabet.len();
+    let mut code = String::with_capacity(length);
+    let mut byte = [0u8; 1];
+    while code.len() < length {
+        OsRng.fill_bytes(&mut byte);
+        if (byte[0] as usize) < limit {
+            code.push(alphabet[byte[0] as usize % alphabet.len()] as char);
+        }
+    }
+    code
+}
+
+/// Digest stored in place of an opaque token
+pub fn hash_opaque_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("wrong horse", &hash));
+        assert!(!verify_password("correct horse", "not-a-hash"));
+    }
+
+    #[test]
+    fn test_validate_password() {
+        assert!(validate_password("short").is_err());
+        assert!(validate_password("        ").is_err());
+        assert!(validate_password("correct horse").is_ok());
+    }
+
+    #[test]
+    fn test_opaque_tokens() {
+        let token = generate_opaque_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_opaque_token());
+        assert_eq!(hash_opaque_token(&token), hash_opaque_token(&format!(" {} ", token)));
+        assert_eq!(hash_opaque_token(&token).len(), 64);
+    }
+}
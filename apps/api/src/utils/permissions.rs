@@ -0,0 +1,173 @@
+/// Role → permission grants
+///
+/// Grants are read from `role_permissions` at startup and kept in memory so
+/// `require_permission` never touches the database. Edits through the API
+/// reload the map immediately; a background refresh picks up changes made
+/// by other instances.
+use crate::models::{Permission, UserRole};
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// How often grants are re-read from the database
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+static GRANTS: OnceLock<RwLock<HashMap<UserRole, BTreeSet<Permission>>>> = OnceLock::new();
+
+fn grants() -> &'static RwLock<HashMap<UserRole, BTreeSet<Permission>>> {
+    GRANTS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Grants used until the database has been read (mirrors the migration seed)
+pub fn default_permissions(role: &UserRole) -> BTreeSet<Permission> {
+    match role {
+        UserRole::Admin => Permission::ALL.into_iter().collect(),
+        UserRole::Viewer => [Permission::IssuesRead, Permission::SessionsRead].into_iter().collect(),
+        UserRole::Tech => BTreeSet::new(),
+        UserRole::Editor => [
+            Permission::IssuesRead,
+            Permission::IssuesWrite,
+            Permission::SessionsRead,
+            Permission::UploadsRead,
+            Permission::UploadsWrite,
+        ]
+        .into_iter()
+        .collect(),
+    }
+}
+
+/// Effective permissions of a role; ADMIN always has all of them
+pub fn permissions_for(role: &UserRole) -> BTreeSet<Permission> {
+    if *role == UserRole::Admin {
+        return Permission::ALL.into_iter().collect();
+    }
+
+    let map = grants().read().unwrap_or_else(|e| e.into_inner());
+    map.get(role).cloned().unwrap_or_else(|| default_permissions(role))
+}
+
+/// True when the role holds the permission
+pub fn has_permission(role: &UserRole, permission: Permission) -> bool {
+    permissions_for(role).contains(&permission)
+}
+
+/// Build the grant map from `(role, permission)` rows
+///
+/// Roles without rows end up with no permissions. Unknown permission
+/// strings (e.g. left behind by a newer release) are ignored.
+fn build_map(rows: Vec<(UserRole, String)>) -> HashMap<UserRole, BTreeSet<Permission>> {
+    let mut map: HashMap<UserRole, BTreeSet<Permission>> =
+        [UserRole::Viewer, UserRole::Tech, UserRole::Editor].into_iter().map(|r| (r, BTreeSet::new())).collect();
+
+    for (role, permission) in rows {
+        match Permission::parse(&permission) {
+            Some(permission) => {
+                map.entry(role).or_default().insert(permission);
+            }
+            None => tracing::warn!("Ignoring unknown permission '{}' granted to {:?}", permission, role),
+        }
+    }
+
+    map
+}
+
+/// Re-read all grants from the database
+pub async fn reload(db: &PgPool) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query_as::<_, (UserRole, String)>("SELECT role, permission FROM role_permissions")
+        .fetch_all(db)
+        .await?;
+
+    *grants().write().unwrap_or_else(|e| e.into_inner()) = build_map(rows);
+    Ok(())
+}
+
+/// Replace a role's grants and reload the in-memory map
+pub async fn set_role_permissions(
+    db: &PgPool,
+    role: &UserRole,
+    permissions: &BTreeSet<Permission>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM role_permissions WHERE role = $1")
+        .bind(role)
+        .execute(&mut *tx)
+        .await?;
+
+    let names: Vec<&str> = permissions.iter().map(|p| p.as_str()).collect();
+    sqlx::query("INSERT INTO role_permissions (role, permission) SELECT $1, unnest($2::varchar[])")
+        .bind(role)
+        .bind(&names)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    reload(db).await
+}
+
+/// Periodically re-read grants so every instance converges on edits
+pub fn spawn_refresh(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = reload(&db).await {
+                tracing::warn!("Failed to refresh role permissions: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_has_every_permission() {
+        let admin = permissions_for(&UserRole::Admin);
+        assert_eq!(admin.len(), Permission::ALL.len());
+        assert!(has_permission(&UserRole::Admin, Permission::UsersManage));
+    }
+
+    #[test]
+    fn test_default_permissions_are_read_only() {
+        let defaults = default_permissions(&UserRole::Viewer);
+        assert!(defaults.contains(&Permission::IssuesRead));
+        assert!(defaults.contains(&Permission::SessionsRead));
+        assert!(!defaults.contains(&Permission::IssuesWrite));
+        assert!(!defaults.contains(&Permission::UsersManage));
+
+        assert!(default_permissions(&UserRole::Tech).is_empty());
+    }
+
+    #[test]
+    fn test_build_map_ignores_unknown_and_empties_missing_roles() {
+        let map = build_map(vec![
+            (UserRole::Viewer, "audit:read".to_string()),
+            (UserRole::Viewer, "rockets:launch".to_string()),
+        ]);
+
+        assert_eq!(map[&UserRole::Viewer], [Permission::AuditRead].into_iter().collect());
+        assert!(map[&UserRole::Tech].is_empty());
+        assert!(map[&UserRole::Editor].is_empty());
+    }
+
+    #[test]
+    fn test_editor_defaults_can_write_but_not_approve() {
+        let defaults = default_permissions(&UserRole::Editor);
+        assert!(defaults.contains(&Permission::IssuesWrite));
+        assert!(!defaults.contains(&Permission::IssuesApprove));
+        assert!(!defaults.contains(&Permission::UsersManage));
+    }
+
+    #[test]
+    fn test_permission_strings_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+            assert_eq!(serde_json::to_value(permission).unwrap(), permission.as_str());
+        }
+        assert_eq!(Permission::parse("issues:admin"), None);
+    }
+}
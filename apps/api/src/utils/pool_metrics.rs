@@ -0,0 +1,303 @@
+/// Database connection pool sizing and saturation monitoring
+///
+/// Pool limits and the prepared statement cache are configured from the
+/// environment (DB_MAX_CONNECTIONS and friends). The pool is opened at
+/// DB_MAX_CONNECTIONS, and the `db_max_connections` runtime setting can
+/// lower that while running (see `PoolLimit`).
+/// A background sampler measures how long it takes to acquire a connection
+/// and warns when the pool is saturated, so exhaustion shows up in the logs
+/// and on the performance dashboard before users notice.
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Requests that failed because no connection became available in time
+static ACQUIRE_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+/// Number of samples where every connection was checked out
+static SATURATION_EVENTS: AtomicU64 = AtomicU64::new(0);
+/// Sum and count of sampled acquire waits (microseconds)
+static WAIT_TOTAL_MICROS: AtomicU64 = AtomicU64::new(0);
+static WAIT_SAMPLES: AtomicU64 = AtomicU64::new(0);
+/// Longest sampled acquire wait (microseconds)
+static WAIT_MAX_MICROS: AtomicU64 = AtomicU64::new(0);
+
+/// Connection pool settings
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout: Duration,
+    pub idle_timeout: Duration,
+    /// Acquire waits above this are logged as slow
+    pub slow_acquire_threshold: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 20,
+            min_connections: 2,
+            acquire_timeout: Duration::from_secs(3),
+            idle_timeout: Duration::from_secs(600),
+            slow_acquire_threshold: Duration::from_millis(250),
+        }
+    }
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok())
+}
+
+impl PoolConfig {
+    /// Load pool settings from the environment, falling back to defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let max_connections = env_u64("DB_MAX_CONNECTIONS")
+            .map(|v| v as u32)
+            .filter(|v| *v > 0)
+            .unwrap_or(defaults.max_connections);
+
+        let min_connections = env_u64("DB_MIN_CONNECTIONS")
+            .map(|v| v as u32)
+            .unwrap_or(defaults.min_connections)
+            .min(max_connections);
+
+        Self {
+            max_connections,
+            min_connections,
+            acquire_timeout: env_u64("DB_ACQUIRE_TIMEOUT_SECS")
+                .filter(|v| *v > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.acquire_timeout),
+            idle_timeout: env_u64("DB_IDLE_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.idle_timeout),
+            slow_acquire_threshold: env_u64("DB_SLOW_ACQUIRE_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.slow_acquire_threshold),
+        }
+    }
+}
+
+/// Reads the current limit
+type LimitFn = Box<dyn Fn() -> u32 + Send + Sync>;
+
+/// Runtime limit on the pool's connections, below the DB_MAX_CONNECTIONS ceiling
+///
+/// sqlx can't resize an open pool, so the limit is applied as connections are
+/// released: while the pool holds more than the limit, a released connection
+/// is closed instead of kept. A burst can still open connections up to the
+/// ceiling; they are closed again as it passes.
+#[derive(Clone, Default)]
+pub struct PoolLimit {
+    source: Arc<OnceLock<(PgPool, LimitFn)>>,
+}
+
+impl PoolLimit {
+    /// Start applying `limit` (read on every release) to `pool`
+    pub fn follow(&self, pool: PgPool, limit: impl Fn() -> u32 + Send + Sync + 'static) {
+        let _ = self.source.set((pool, Box::new(limit)));
+    }
+
+    /// Current limit, or `ceiling` until the limit is followed
+    pub fn current(&self, ceiling: u32) -> u32 {
+        match self.source.get() {
+            Some((_, limit)) => effective_limit(limit(), ceiling),
+            None => ceiling,
+        }
+    }
+
+    /// Whether a released connection goes back to the pool (`after_release` hook)
+    pub fn keep_released(&self) -> bool {
+        match self.source.get() {
+            Some((pool, limit)) => {
+                let ceiling = pool.options().get_max_connections();
+                keep_released(pool.size(), effective_limit(limit(), ceiling))
+            }
+            None => true,
+        }
+    }
+}
+
+/// A requested limit within what the pool was opened with
+fn effective_limit(requested: u32, ceiling: u32) -> u32 {
+    requested.clamp(1, ceiling.max(1))
+}
+
+/// Keep a released connection unless the pool (counting it) is over the limit
+fn keep_released(size: u32, limit: u32) -> bool {
+    size <= limit
+}
+
+/// Prepared statement cache size when caching is enabled (sqlx default)
+const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 100;
+
+/// True when the URL points at a transaction-mode pooler (PgBouncer/Supavisor),
+/// which cannot share prepared statements between clients
+pub fn is_transaction_pooler(database_url: &str) -> bool {
+    let url = database_url.to_lowercase();
+    let (without_query, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    // Supabase pooler hosts, and 6543 is the conventional PgBouncer/Supavisor port
+    let host_part = without_query.rsplit('@').next().unwrap_or(without_query);
+    let host_and_port = host_part.split('/').next().unwrap_or(host_part);
+
+    host_and_port.contains("pooler.")
+        || host_and_port.ends_with(":6543")
+        || query.split('&').any(|p| p == "pgbouncer=true")
+}
+
+/// Statement cache capacity for this deployment
+///
+/// DB_STATEMENT_CACHE=enabled|disabled forces the choice; the default (auto)
+/// disables caching only when the URL looks like a transaction-mode pooler.
+pub fn statement_cache_capacity(database_url: &str) -> usize {
+    let capacity = std::env::var("DB_STATEMENT_CACHE_CAPACITY")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_STATEMENT_CACHE_CAPACITY);
+
+    let mode = std::env::var("DB_STATEMENT_CACHE").unwrap_or_else(|_| "auto".to_string());
+    match mode.to_lowercase().as_str() {
+        "enabled" | "true" | "on" => capacity,
+        "disabled" | "false" | "off" => 0,
+        _ => {
+            if is_transaction_pooler(database_url) {
+                0
+            } else {
+                capacity
+            }
+        }
+    }
+}
+
+/// Snapshot of pool health counters
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+    pub acquire_timeouts: u64,
+    pub saturation_events: u64,
+    pub avg_acquire_wait_ms: f64,
+    pub max_acquire_wait_ms: f64,
+}
+
+/// Count a request that gave up waiting for a connection
+pub fn record_acquire_timeout() {
+    let total = ACQUIRE_TIMEOUTS.fetch_add(1, Ordering::Relaxed) + 1;
+    tracing::warn!("🚨 Database pool acquire timed out ({} total since startup)", total);
+}
+
+fn record_wait(wait: Duration) {
+    let micros = wait.as_micros() as u64;
+    WAIT_TOTAL_MICROS.fetch_add(micros, Ordering::Relaxed);
+    WAIT_SAMPLES.fetch_add(1, Ordering::Relaxed);
+    WAIT_MAX_MICROS.fetch_max(micros, Ordering::Relaxed);
+}
+
+/// Current pool health counters
+pub fn stats() -> PoolStats {
+    let samples = WAIT_SAMPLES.load(Ordering::Relaxed);
+    let total = WAIT_TOTAL_MICROS.load(Ordering::Relaxed);
+
+    PoolStats {
+        acquire_timeouts: ACQUIRE_TIMEOUTS.load(Ordering::Relaxed),
+        saturation_events: SATURATION_EVENTS.load(Ordering::Relaxed),
+        avg_acquire_wait_ms: if samples == 0 {
+            0.0
+        } else {
+            total as f64 / samples as f64 / 1000.0
+        },
+        max_acquire_wait_ms: WAIT_MAX_MICROS.load(Ordering::Relaxed) as f64 / 1000.0,
+    }
+}
+
+/// True when every connection the pool may open is checked out
+fn is_saturated(size: u32, idle: usize, max_connections: u32) -> bool {
+    size >= max_connections && idle == 0
+}
+
+/// Spawn the background sampler (every 15 seconds)
+pub fn spawn_monitor(db: PgPool, config: PoolConfig, limit: PoolLimit) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(15));
+        let mut was_saturated = false;
+
+        loop {
+            interval.tick().await;
+
+            let max_connections = limit.current(config.max_connections);
+            let saturated = is_saturated(db.size(), db.num_idle(), max_connections);
+            if saturated {
+                SATURATION_EVENTS.fetch_add(1, Ordering::Relaxed);
+                if !was_saturated {
+                    tracing::warn!(
+                        "🚨 Database pool saturated: all {} connections in use",
+                        max_connections
+                    );
+                }
+            } else if was_saturated {
+                tracing::info!("✅ Database pool recovered from saturation");
+            }
+            was_saturated = saturated;
+
+            // Probe how long a request would currently wait for a connection
+            let start = Instant::now();
+            match db.acquire().await {
+                Ok(conn) => {
+                    let wait = start.elapsed();
+                    drop(conn);
+                    record_wait(wait);
+                    if wait > config.slow_acquire_threshold {
+                        tracing::warn!("⚠️  Slow database pool acquire: {}ms", wait.as_millis());
+                    }
+                }
+                Err(sqlx::Error::PoolTimedOut) => record_acquire_timeout(),
+                Err(e) => tracing::debug!("Pool monitor probe failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_saturated() {
+        assert!(is_saturated(20, 0, 20));
+        assert!(!is_saturated(20, 1, 20));
+        assert!(!is_saturated(10, 0, 20));
+    }
+
+    #[test]
+    fn test_runtime_limit() {
+        assert_eq!(effective_limit(10, 20), 10);
+        assert_eq!(effective_limit(50, 20), 20);
+        assert_eq!(effective_limit(0, 20), 1);
+
+        assert!(keep_released(10, 10));
+        assert!(!keep_released(11, 10));
+        assert_eq!(PoolLimit::default().current(20), 20);
+        assert!(PoolLimit::default().keep_released());
+    }
+
+    #[test]
+    fn test_is_transaction_pooler() {
+        assert!(is_transaction_pooler(
+            "postgres://user:pw@aws-0-us-east-1.pooler.supabase.com:6543/postgres"
+        ));
+        assert!(is_transaction_pooler("postgres://user:pw@db.internal:6543/app"));
+        assert!(is_transaction_pooler("postgres://user:pw@db.internal/app?pgbouncer=true"));
+        assert!(!is_transaction_pooler("postgres://user:pw@localhost:5432/app"));
+        assert!(!is_transaction_pooler("postgres://postgres@localhost/equip?host=/tmp/pg"));
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = PoolConfig::default();
+        assert_eq!(config.max_connections, 20);
+        assert_eq!(config.min_connections, 2);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(3));
+    }
+}
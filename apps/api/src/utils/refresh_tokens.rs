@@ -0,0 +1,248 @@
+/// Server-side refresh tokens with rotation
+///
+/// Login returns a short-lived access JWT plus an opaque refresh token. Every
+/// refresh consumes the presented token and issues its replacement in the same
+/// family (one family per signed-in device). A rotated token that shows up
+/// again was copied, so the whole family is revoked.
+use crate::utils::password;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Lifetime of access tokens issued alongside refresh tokens
+pub const ACCESS_TOKEN_MINUTES: i64 = 15;
+
+/// Refresh token lifetime with "stay signed in" (REFRESH_TOKEN_TTL_DAYS)
+const DEFAULT_REMEMBER_ME_DAYS: i64 = 30;
+/// Refresh token lifetime without "stay signed in" (REFRESH_TOKEN_SESSION_HOURS)
+const DEFAULT_SESSION_HOURS: i64 = 12;
+
+/// A rotated token replayed within this window is treated as a benign race
+/// (two tabs refreshing at once) rather than theft
+const ROTATION_GRACE_SECONDS: i64 = 30;
+
+/// Why a token or family stopped being valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevokeReason {
+    Rotated,
+    Logout,
+    ReuseDetected,
+    PasswordChanged,
+    Revoked,
+    Deactivated,
+}
+
+impl RevokeReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Rotated => "rotated",
+            Self::Logout => "logout",
+            Self::ReuseDetected => "reuse_detected",
+            Self::PasswordChanged => "password_changed",
+            Self::Revoked => "revoked",
+            Self::Deactivated => "deactivated",
+        }
+    }
+}
+
+/// Refresh token lifetime for a sign-in
+pub fn lifetime(remember_me: bool) -> Duration {
+    fn env_i64(name: &str) -> Option<i64> {
+        std::env::var(name).ok().and_then(|v| v.parse::<i64>().ok()).filter(|v| *v > 0)
+    }
+
+    if remember_me {
+        Duration::days(env_i64("REFRESH_TOKEN_TTL_DAYS").unwrap_or(DEFAULT_REMEMBER_ME_DAYS))
+    } else {
+        Duration::hours(env_i64("REFRESH_TOKEN_SESSION_HOURS").unwrap_or(DEFAULT_SESSION_HOURS))
+    }
+}
+
+/// Device details recorded with each token
+#[derive(Debug, Clone, Default)]
+pub struct DeviceInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// A newly issued refresh token (the plaintext is only ever returned here)
+#[derive(Debug, Clone)]
+pub struct IssuedToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Result of presenting a refresh token
+#[derive(Debug)]
+pub enum RotateOutcome {
+    Rotated { user_id: Uuid, token: IssuedToken },
+    /// Unknown, expired or revoked
+    Invalid,
+    /// A rotated token was replayed; its family has been revoked
+    ReuseDetected { user_id: Uuid, family_id: Uuid },
+}
+
+async fn insert_token<'e, E>(
+    executor: E,
+    user_id: Uuid,
+    family_id: Uuid,
+    remember_me: bool,
+    device: &DeviceInfo,
+) -> Result<(Uuid, IssuedToken), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let token = password::generate_opaque_token();
+    let expires_at = Utc::now() + lifetime(remember_me);
+
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO refresh_tokens (user_id, family_id, token_hash, remember_me, user_agent, ip_address, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         RETURNING id"
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .bind(password::hash_opaque_token(&token))
+    .bind(remember_me)
+    .bind(device.user_agent.as_deref().map(|ua| ua.chars().take(500).collect::<String>()))
+    .bind(device.ip_address.as_deref())
+    .bind(expires_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok((id, IssuedToken { token, expires_at }))
+}
+
+/// Start a new token family for a sign-in
+pub async fn issue(db: &PgPool, user_id: Uuid, remember_me: bool, device: &DeviceInfo) -> Result<IssuedToken, sqlx::Error> {
+    let (_, issued) = insert_token(db, user_id, Uuid::new_v4(), remember_me, device).await?;
+    Ok(issued)
+}
+
+/// Exchange a refresh token for its replacement
+pub async fn rotate(db: &PgPool, presented: &str, device: &DeviceInfo) -> Result<RotateOutcome, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let row = sqlx::query_as::<_, (Uuid, Uuid, Uuid, bool, DateTime<Utc>, Option<DateTime<Utc>>, Option<String>)>(
+        "SELECT id, user_id, family_id, remember_me, expires_at, revoked_at, revoked_reason
+         FROM refresh_tokens
+         WHERE token_hash = $1
+         FOR UPDATE"
+    )
+    .bind(password::hash_opaque_token(presented))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((id, user_id, family_id, remember_me, expires_at, revoked_at, revoked_reason)) = row else {
+        return Ok(RotateOutcome::Invalid);
+    };
+
+    if let Some(revoked_at) = revoked_at {
+        let replayed_rotation = revoked_reason.as_deref() == Some(RevokeReason::Rotated.as_str());
+        if replayed_rotation && revoked_at < Utc::now() - Duration::seconds(ROTATION_GRACE_SECONDS) {
+            revoke_family_in(&mut *tx, user_id, family_id, RevokeReason::ReuseDetected).await?;
+            tx.commit().await?;
+            return Ok(RotateOutcome::ReuseDetected { user_id, family_id });
+        }
+        return Ok(RotateOutcome::Invalid);
+    }
+
+    if expires_at <= Utc::now() {
+        return Ok(RotateOutcome::Invalid);
+    }
+
+    let (new_id, token) = insert_token(&mut *tx, user_id, family_id, remember_me, device).await?;
+
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW(), revoked_reason = $2, replaced_by = $3 WHERE id = $1"
+    )
+    .bind(id)
+    .bind(RevokeReason::Rotated.as_str())
+    .bind(new_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(RotateOutcome::Rotated { user_id, token })
+}
+
+async fn revoke_family_in<'e, E>(executor: E, user_id: Uuid, family_id: Uuid, reason: RevokeReason) -> Result<u64, sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let result = sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW(), revoked_reason = $3
+         WHERE user_id = $1 AND family_id = $2 AND revoked_at IS NULL"
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .bind(reason.as_str())
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Revoke one device's token family (returns the number of tokens revoked)
+pub async fn revoke_family(db: &PgPool, user_id: Uuid, family_id: Uuid, reason: RevokeReason) -> Result<u64, sqlx::Error> {
+    revoke_family_in(db, user_id, family_id, reason).await
+}
+
+/// Revoke the family a presented token belongs to (logout)
+///
+/// Returns the owning user, or None for unknown tokens.
+pub async fn revoke_token_family(db: &PgPool, presented: &str, reason: RevokeReason) -> Result<Option<Uuid>, sqlx::Error> {
+    let owner = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT user_id, family_id FROM refresh_tokens WHERE token_hash = $1"
+    )
+    .bind(password::hash_opaque_token(presented))
+    .fetch_optional(db)
+    .await?;
+
+    let Some((user_id, family_id)) = owner else {
+        return Ok(None);
+    };
+
+    revoke_family_in(db, user_id, family_id, reason).await?;
+    Ok(Some(user_id))
+}
+
+/// Revoke every refresh token a user holds (all devices)
+pub async fn revoke_all_for_user<'e, E>(executor: E, user_id: Uuid, reason: RevokeReason) -> Result<u64, sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let result = sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW(), revoked_reason = $2
+         WHERE user_id = $1 AND revoked_at IS NULL"
+    )
+    .bind(user_id)
+    .bind(reason.as_str())
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Delete tokens that expired more than a week ago (kept briefly for reuse detection)
+pub async fn purge_expired(db: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at < NOW() - INTERVAL '7 days'")
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifetime_defaults() {
+        std::env::remove_var("REFRESH_TOKEN_TTL_DAYS");
+        std::env::remove_var("REFRESH_TOKEN_SESSION_HOURS");
+
+        assert_eq!(lifetime(true), Duration::days(DEFAULT_REMEMBER_ME_DAYS));
+        assert_eq!(lifetime(false), Duration::hours(DEFAULT_SESSION_HOURS));
+        assert!(lifetime(false) > Duration::minutes(ACCESS_TOKEN_MINUTES));
+    }
+}
@@ -0,0 +1,169 @@
+/// Estimated questions left before a conclusion
+///
+/// Works out, for every active node, the fewest and most questions a tech
+/// may still answer before reaching an active conclusion. Conditions on
+/// answers are ignored, so these are bounds on the graph rather than a
+/// prediction for one session. Loops are walked at most once: an answer that
+/// leads back to a question already on the path doesn't add to the maximum.
+/// A sub-flow's returns count as its ends, and a sub-flow node counts as one
+/// question on the way through the issue that runs it.
+///
+/// Results are cached per category in `AppState::remaining_depth_cache` as
+/// plain `(min, max)` pairs.
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Fewest and most questions left before a conclusion (0 at a conclusion)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct RemainingDepth {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl From<(u32, u32)> for RemainingDepth {
+    fn from((min, max): (u32, u32)) -> Self {
+        Self { min, max }
+    }
+}
+
+/// Depths of every node that can reach a conclusion, keyed by node
+///
+/// `edges` are (from, to) pairs; nodes that can't reach a conclusion are
+/// left out.
+pub fn compute(edges: &[(Uuid, Uuid)], conclusions: &HashSet<Uuid>) -> HashMap<Uuid, (u32, u32)> {
+    let mut children: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    let mut parents: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for &(from, to) in edges {
+        // Reaching a conclusion ends the session, so its own answers don't count
+        if conclusions.contains(&from) {
+            continue;
+        }
+        children.entry(from).or_default().push(to);
+        parents.entry(to).or_default().push(from);
+    }
+
+    // Fewest: breadth-first outwards from every conclusion
+    let mut min: HashMap<Uuid, u32> = conclusions.iter().map(|&id| (id, 0)).collect();
+    let mut queue: VecDeque<Uuid> = conclusions.iter().copied().collect();
+    while let Some(node) = queue.pop_front() {
+        let depth = min[&node] + 1;
+        for &parent in parents.get(&node).into_iter().flatten() {
+            if let Entry::Vacant(entry) = min.entry(parent) {
+                entry.insert(depth);
+                queue.push_back(parent);
+            }
+        }
+    }
+
+    // Most: longest path, ignoring answers that loop back onto the path
+    let mut max: HashMap<Uuid, Option<u32>> = HashMap::new();
+    let mut on_path = HashSet::new();
+    for &node in min.keys() {
+        longest(node, &children, conclusions, &mut max, &mut on_path);
+    }
+
+    min.into_iter()
+        .map(|(id, fewest)| (id, (fewest, max.get(&id).copied().flatten().unwrap_or(fewest).max(fewest))))
+        .collect()
+}
+
+fn longest(
+    node: Uuid,
+    children: &HashMap<Uuid, Vec<Uuid>>,
+    conclusions: &HashSet<Uuid>,
+    memo: &mut HashMap<Uuid, Option<u32>>,
+    on_path: &mut HashSet<Uuid>,
+) -> Option<u32> {
+    if conclusions.contains(&node) {
+        return Some(0);
+    }
+    if let Some(&known) = memo.get(&node) {
+        return known;
+    }
+    if !on_path.insert(node) {
+        return None;
+    }
+
+    let mut best = None;
+    for &child in children.get(&node).into_iter().flatten() {
+        if let Some(depth) = longest(child, children, conclusions, memo, on_path) {
+            best = best.max(Some(depth + 1));
+        }
+    }
+
+    on_path.remove(&node);
+    memo.insert(node, best);
+    best
+}
+
+/// Depths of the active nodes in one category
+pub async fn load_category(db: &PgPool, category: &str) -> Result<HashMap<Uuid, (u32, u32)>, sqlx::Error> {
+    let nodes = sqlx::query_as::<_, (Uuid, String, bool)>(
+        "SELECT id, category, node_type IN ('conclusion', 'return') FROM nodes WHERE is_active = true",
+    )
+    .fetch_all(db)
+    .await?;
+    // Answers (and "I'm not sure") can lead into other categories, so the whole active graph is walked
+    let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT c.from_node_id, c.to_node_id
+         FROM connections c
+         JOIN nodes f ON f.id = c.from_node_id
+         JOIN nodes t ON t.id = c.to_node_id
+         WHERE c.is_active = true AND f.is_active = true AND t.is_active = true
+         UNION ALL
+         SELECT f.id, t.id
+         FROM nodes f
+         JOIN nodes t ON t.id = f.not_sure_node_id
+         WHERE f.is_active = true AND t.is_active = true",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let conclusions = nodes.iter().filter(|(_, _, is_conclusion)| *is_conclusion).map(|(id, _, _)| *id).collect();
+    let in_category: HashSet<Uuid> = nodes.iter().filter(|(_, c, _)| c == category).map(|(id, _, _)| *id).collect();
+
+    Ok(compute(&edges, &conclusions)
+        .into_iter()
+        .filter(|(id, _)| in_category.contains(id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    #[test]
+    fn test_branching_depths() {
+        // 0 -> 1 -> 2 -> C3, 0 -> C4, 1 -> C3
+        let n = ids(5);
+        let edges = [(n[0], n[1]), (n[1], n[2]), (n[2], n[3]), (n[0], n[4]), (n[1], n[3])];
+        let conclusions = HashSet::from([n[3], n[4]]);
+        let depths = compute(&edges, &conclusions);
+
+        assert_eq!(depths[&n[0]], (1, 3));
+        assert_eq!(depths[&n[1]], (1, 2));
+        assert_eq!(depths[&n[2]], (1, 1));
+        assert_eq!(depths[&n[3]], (0, 0));
+    }
+
+    #[test]
+    fn test_loops_and_dead_ends() {
+        // 0 -> 1 -> 0 (loop), 1 -> C2; 3 leads nowhere
+        let n = ids(4);
+        let edges = [(n[0], n[1]), (n[1], n[0]), (n[1], n[2])];
+        let conclusions = HashSet::from([n[2]]);
+        let depths = compute(&edges, &conclusions);
+
+        assert_eq!(depths[&n[0]], (2, 2));
+        assert_eq!(depths[&n[1]], (1, 1));
+        assert!(!depths.contains_key(&n[3]));
+    }
+}
@@ -0,0 +1,91 @@
+/// Percentage rollout routing for revised issue graphs
+///
+/// A revised graph is built in a candidate category. While a rollout is
+/// active, new sessions entering the current category are bucketed by
+/// session ID and a fixed share is sent to the candidate's root node instead.
+use crate::models::Node;
+use sqlx::PgPool;
+
+/// Stable 0-99 bucket for a session, so a session always gets the same arm
+pub fn bucket(session_id: &str) -> u8 {
+    let digest = md5::compute(session_id.as_bytes());
+    let value = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    (value % 100) as u8
+}
+
+/// Route a session entering `category` according to its active rollout
+///
+/// Returns the candidate's root node when the session falls in the rollout
+/// share, or None to keep the current graph. Either way the assignment is
+/// recorded so completion stats can be compared per version.
+pub async fn route_session(
+    db: &PgPool,
+    session_id: &str,
+    category: &str,
+) -> Result<Option<Node>, sqlx::Error> {
+    let rollout = sqlx::query_as::<_, (String, i16)>(
+        "SELECT candidate_category, percentage FROM issue_rollouts WHERE category = $1"
+    )
+    .bind(category)
+    .fetch_optional(db)
+    .await?;
+
+    let Some((candidate_category, percentage)) = rollout else {
+        return Ok(None);
+    };
+
+    let candidate_root = if (bucket(session_id) as i16) < percentage {
+        sqlx::query_as::<_, Node>(
+            "SELECT id, category, node_type, text, semantic_id, display_category, position_x, position_y, kb_url, doc_reference, option_order, answer_mode, not_sure_node_id, subflow_category, content, is_active, created_at, updated_at
+             FROM nodes
+             WHERE semantic_id = $1 AND is_active = true"
+        )
+        .bind(format!("{}_start", candidate_category))
+        .fetch_optional(db)
+        .await?
+    } else {
+        None
+    };
+
+    let (variant, served_category) = match &candidate_root {
+        Some(_) => ("candidate", candidate_category.as_str()),
+        None => ("current", category),
+    };
+
+    sqlx::query(
+        "INSERT INTO session_rollout_assignments (session_id, category, variant, served_category)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (session_id, category) DO NOTHING"
+    )
+    .bind(session_id)
+    .bind(category)
+    .bind(variant)
+    .bind(served_category)
+    .execute(db)
+    .await?;
+
+    Ok(candidate_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_is_stable_and_in_range() {
+        let id = "7f7c2f1e-1234-4c1b-9a55-0d2b9b7c1e11";
+        assert_eq!(bucket(id), bucket(id));
+
+        for i in 0..500 {
+            assert!(bucket(&format!("session-{}", i)) < 100);
+        }
+    }
+
+    #[test]
+    fn test_bucket_spreads_sessions() {
+        let below_half = (0..1000)
+            .filter(|i| bucket(&format!("session-{}", i)) < 50)
+            .count();
+        assert!((400..600).contains(&below_half));
+    }
+}
@@ -0,0 +1,156 @@
+/// Semantic IDs
+///
+/// Readable node keys, unique within a category. Sessions start from
+/// `{category}_start` and conditions read earlier answers by semantic ID.
+use crate::error::{ApiError, ApiResult};
+use std::collections::HashSet;
+
+/// Longest semantic ID the column holds
+pub const MAX_LENGTH: usize = 255;
+/// Longest slug generated from node text
+const SLUG_MAX_LENGTH: usize = 50;
+/// Unique index on (category, semantic_id)
+const UNIQUE_INDEX: &str = "idx_nodes_category_semantic_id";
+
+/// Trimmed semantic ID, with blank treated as none
+pub fn normalize(semantic_id: Option<&str>) -> Option<String> {
+    semantic_id.map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+/// Check a semantic ID's length, reporting errors under `field`
+pub fn validate(field: &str, semantic_id: Option<&str>) -> ApiResult<()> {
+    match semantic_id {
+        Some(semantic_id) if semantic_id.chars().count() > MAX_LENGTH => Err(ApiError::validation(vec![(
+            field.to_string(),
+            format!("Must be at most {} characters", MAX_LENGTH),
+        )])),
+        _ => Ok(()),
+    }
+}
+
+/// Lowercase slug of node text: letters and digits joined by underscores
+///
+/// "Is the pump ON?" becomes `is_the_pump_on`. Text without any letters or
+/// digits gives `node`.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for word in text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let separator = usize::from(!slug.is_empty());
+        if slug.len() + separator + word.len() > SLUG_MAX_LENGTH {
+            if slug.is_empty() {
+                slug.push_str(&word[..SLUG_MAX_LENGTH]);
+            }
+            break;
+        }
+        if separator == 1 {
+            slug.push('_');
+        }
+        slug.push_str(word);
+    }
+
+    if slug.is_empty() {
+        "node".to_string()
+    } else {
+        slug.to_ascii_lowercase()
+    }
+}
+
+/// First free "<base>", "<base>_2", "<base>_3", ... semantic ID
+pub fn first_free(base: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}_{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("unbounded range always yields a free suffix")
+}
+
+/// A free semantic ID in `category` derived from `text`, ignoring node `except`
+pub async fn generate(
+    conn: &mut sqlx::PgConnection,
+    category: &str,
+    text: &str,
+    except: Option<uuid::Uuid>,
+) -> ApiResult<String> {
+    let base = slugify(text);
+    // Wildcards in the base only widen the match; exact names are checked by first_free
+    let taken: HashSet<String> = sqlx::query_scalar::<_, String>(
+        "SELECT semantic_id FROM nodes
+         WHERE category = $1 AND semantic_id LIKE $2 || '%' AND id IS DISTINCT FROM $3",
+    )
+    .bind(category)
+    .bind(&base)
+    .bind(except)
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .collect();
+
+    Ok(first_free(&base, &taken))
+}
+
+/// Indexes of entries that repeat an earlier semantic ID (blank ones are ignored)
+pub fn find_duplicates<'a>(semantic_ids: impl IntoIterator<Item = Option<&'a str>>) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    semantic_ids
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, semantic_id)| {
+            let semantic_id = semantic_id.map(str::trim).filter(|s| !s.is_empty())?;
+            (!seen.insert(semantic_id)).then_some(index)
+        })
+        .collect()
+}
+
+/// Turn a write refused by the per-category unique index into a validation error on `field`
+pub fn map_taken(field: &str, semantic_id: Option<&str>, e: sqlx::Error) -> ApiError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.constraint() == Some(UNIQUE_INDEX) => ApiError::validation(vec![(
+            field.to_string(),
+            format!(
+                "'{}' is already used by another node in this category",
+                semantic_id.unwrap_or_default()
+            ),
+        )]),
+        _ => e.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Is the pump ON?"), "is_the_pump_on");
+        assert_eq!(slugify("  Check fuse #3 -- replace (if blown) "), "check_fuse_3_replace_if_blown");
+        assert_eq!(slugify("¿Qué pasa?"), "qu_pasa");
+        assert_eq!(slugify("!!!"), "node");
+
+        let long = slugify("Disconnect the mains supply and wait at least five minutes before opening");
+        assert_eq!(long, "disconnect_the_mains_supply_and_wait_at_least_five");
+        assert!(long.len() <= SLUG_MAX_LENGTH);
+        assert_eq!(slugify(&"x".repeat(80)).len(), SLUG_MAX_LENGTH);
+    }
+
+    #[test]
+    fn test_first_free() {
+        let mut taken = HashSet::new();
+        assert_eq!(first_free("check_power", &taken), "check_power");
+        taken.insert("check_power".to_string());
+        taken.insert("check_power_2".to_string());
+        assert_eq!(first_free("check_power", &taken), "check_power_3");
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let ids = [Some("start"), None, Some("check"), Some(" start "), Some(""), Some(""), Some("check")];
+        assert_eq!(find_duplicates(ids), vec![3, 6]);
+        assert!(normalize(Some("  ")).is_none());
+        assert_eq!(normalize(Some(" pump_start ")).as_deref(), Some("pump_start"));
+    }
+}
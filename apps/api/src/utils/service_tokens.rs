@@ -0,0 +1,185 @@
+/// Service account tokens
+///
+/// Service accounts (users with `is_service`) authenticate automation such as
+/// CI import/export jobs. Their tokens never expire, carry their own
+/// permission scopes and stay valid until revoked or the account is
+/// deactivated. Like role grants, active tokens are kept in memory (keyed by
+/// digest) so `require_permission` never touches the database; API changes
+/// reload immediately and a background refresh picks up other instances.
+use crate::models::{Permission, UserRole};
+use crate::utils::jwt::Claims;
+use crate::utils::password;
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Prefix that tells service tokens apart from JWTs
+pub const TOKEN_PREFIX: &str = "svc_";
+
+/// Characters of a token kept for display ("svc_" plus 8 hex digits)
+pub const DISPLAY_PREFIX_LENGTH: usize = 12;
+
+/// How often tokens are re-read and usage is written back
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An active token as seen by the middleware
+#[derive(Debug, Clone)]
+pub struct ServiceGrant {
+    pub token_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub scopes: BTreeSet<Permission>,
+}
+
+impl ServiceGrant {
+    /// Claims handed to handlers, so audit entries name the service account
+    pub fn claims(&self) -> Claims {
+        Claims {
+            sub: self.user_id.to_string(),
+            email: self.email.clone(),
+            role: self.role.clone(),
+            iat: 0,
+            exp: i64::MAX,
+        }
+    }
+}
+
+static TOKENS: OnceLock<RwLock<HashMap<String, ServiceGrant>>> = OnceLock::new();
+static USED: OnceLock<Mutex<HashSet<Uuid>>> = OnceLock::new();
+
+fn tokens() -> &'static RwLock<HashMap<String, ServiceGrant>> {
+    TOKENS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn used() -> &'static Mutex<HashSet<Uuid>> {
+    USED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Scopes a service token may carry; account and system administration and
+/// approving reviewed changes stay interactive
+pub fn is_allowed_scope(permission: Permission) -> bool {
+    !matches!(permission, Permission::UsersManage | Permission::SystemManage | Permission::IssuesApprove)
+}
+
+/// True when a bearer token is a service token rather than a JWT
+pub fn is_service_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+/// Generate a new token (the plaintext is only returned once)
+pub fn generate() -> String {
+    format!("{}{}", TOKEN_PREFIX, password::generate_opaque_token())
+}
+
+/// Grant of an active token, recording that it was used
+pub fn lookup(token: &str) -> Option<ServiceGrant> {
+    let grant = tokens()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&password::hash_opaque_token(token))
+        .cloned()?;
+
+    used().lock().unwrap_or_else(|e| e.into_inner()).insert(grant.token_id);
+    Some(grant)
+}
+
+/// Row of an active token joined with its account
+type TokenRow = (String, Uuid, Uuid, String, UserRole, Vec<String>);
+
+/// Build the digest → grant map; unknown or disallowed scopes are ignored
+fn build_map(rows: Vec<TokenRow>) -> HashMap<String, ServiceGrant> {
+    rows.into_iter()
+        .map(|(token_hash, token_id, user_id, email, role, scopes)| {
+            let scopes = scopes
+                .iter()
+                .filter_map(|s| Permission::parse(s))
+                .filter(|p| is_allowed_scope(*p))
+                .collect();
+            (token_hash, ServiceGrant { token_id, user_id, email, role, scopes })
+        })
+        .collect()
+}
+
+/// Re-read all active tokens from the database
+pub async fn reload(db: &PgPool) -> Result<(), sqlx::Error> {
+    let rows = sqlx::query_as::<_, TokenRow>(
+        "SELECT t.token_hash, t.id, u.id, u.email, u.role, t.scopes
+         FROM service_tokens t
+         JOIN users u ON u.id = t.user_id
+         WHERE t.revoked_at IS NULL AND u.is_active AND u.is_service",
+    )
+    .fetch_all(db)
+    .await?;
+
+    *tokens().write().unwrap_or_else(|e| e.into_inner()) = build_map(rows);
+    Ok(())
+}
+
+/// Write back which tokens were used since the last flush (accurate to the refresh interval)
+async fn flush_usage(db: &PgPool) -> Result<(), sqlx::Error> {
+    let ids: Vec<Uuid> = used().lock().unwrap_or_else(|e| e.into_inner()).drain().collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE service_tokens SET last_used_at = NOW() WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Periodically record usage and re-read tokens so every instance converges on revocations
+pub fn spawn_refresh(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = flush_usage(&db).await {
+                tracing::warn!("Failed to record service token usage: {}", e);
+            }
+            if let Err(e) = reload(&db).await {
+                tracing::warn!("Failed to refresh service tokens: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_are_recognised() {
+        let token = generate();
+        assert!(is_service_token(&token));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+        assert!(!is_service_token("eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.e30.sig"));
+    }
+
+    #[test]
+    fn test_build_map_drops_disallowed_scopes() {
+        let user_id = Uuid::new_v4();
+        let map = build_map(vec![(
+            "digest".to_string(),
+            Uuid::new_v4(),
+            user_id,
+            "ci@service.invalid".to_string(),
+            UserRole::Viewer,
+            vec![
+                "issues:write".to_string(),
+                "users:manage".to_string(),
+                "issues:approve".to_string(),
+                "rockets:launch".to_string(),
+            ],
+        )]);
+
+        let grant = &map["digest"];
+        assert_eq!(grant.scopes, [Permission::IssuesWrite].into_iter().collect());
+        assert_eq!(grant.claims().sub, user_id.to_string());
+    }
+}
@@ -0,0 +1,171 @@
+/// Session archival
+///
+/// Sessions older than `SESSION_ARCHIVE_AFTER_DAYS` are moved from `sessions`
+/// into `session_archive` in batches. The steps JSON is gzip-compressed; the
+/// columns the dashboard aggregates on (status, conclusion, category, step
+/// count, feedback) are kept uncompressed so stats still include archived sessions.
+use chrono::{DateTime, Duration, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sqlx::PgPool;
+use std::io::{Read, Write};
+use uuid::Uuid;
+
+/// Sessions moved per transaction
+const BATCH_SIZE: i64 = 500;
+
+/// Days after which sessions are archived automatically (None when disabled)
+pub fn archive_after_days() -> Option<i64> {
+    std::env::var("SESSION_ARCHIVE_AFTER_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+}
+
+/// Gzip the steps JSON for storage
+pub fn compress_steps(steps: &serde_json::Value) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(steps)?)?;
+    encoder.finish()
+}
+
+/// Inverse of `compress_steps`
+pub fn decompress_steps(data: &[u8]) -> std::io::Result<serde_json::Value> {
+    let mut json = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// A session as read for archiving
+#[derive(sqlx::FromRow)]
+struct SessionRow {
+    session_id: String,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    abandoned: bool,
+    last_activity_at: DateTime<Utc>,
+    final_conclusion: Option<String>,
+    category: Option<String>,
+    steps: serde_json::Value,
+    tech_identifier: Option<String>,
+    client_site: Option<String>,
+    entry_point: Option<String>,
+    conclusion_node_id: Option<Uuid>,
+    feedback_resolved: Option<bool>,
+    feedback_comment: Option<String>,
+    feedback_at: Option<DateTime<Utc>>,
+    abandoned_at: Option<DateTime<Utc>>,
+    abandon_reason: Option<String>,
+    equipment_id: Option<String>,
+    variables: serde_json::Value,
+    device_id: Option<Uuid>,
+    start_node_id: Option<Uuid>,
+}
+
+/// Move one batch of finished or long-idle sessions started before `cutoff`
+async fn archive_batch(db: &PgPool, cutoff: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    // Open sessions idle since before the cutoff are archived as abandoned
+    let rows = sqlx::query_as::<_, SessionRow>(
+        "SELECT session_id, started_at, completed_at,
+                abandoned OR completed_at IS NULL AS abandoned,
+                last_activity_at, final_conclusion, (steps->0->>'category')::text AS category, steps,
+                tech_identifier, client_site, entry_point,
+                conclusion_node_id, feedback_resolved, feedback_comment, feedback_at,
+                abandoned_at, abandon_reason, equipment_id, variables, device_id, start_node_id
+         FROM sessions
+         WHERE started_at < $1 AND (completed_at IS NOT NULL OR last_activity_at < $1)
+         ORDER BY started_at
+         LIMIT $2
+         FOR UPDATE SKIP LOCKED",
+    )
+    .bind(cutoff)
+    .bind(BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let mut ids = Vec::with_capacity(rows.len());
+    for row in rows {
+        let step_count = row.steps.as_array().map_or(0, |s| s.len()) as i32;
+        let steps_gz = compress_steps(&row.steps).map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO session_archive
+                (session_id, started_at, completed_at, abandoned, last_activity_at, final_conclusion,
+                 category, step_count, tech_identifier, client_site, entry_point, steps_gz,
+                 conclusion_node_id, feedback_resolved, feedback_comment, feedback_at,
+                 abandoned_at, abandon_reason, equipment_id, variables, device_id, start_node_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                     $19, $20, $21, $22)
+             ON CONFLICT (session_id) DO NOTHING",
+        )
+        .bind(&row.session_id)
+        .bind(row.started_at)
+        .bind(row.completed_at)
+        .bind(row.abandoned)
+        .bind(row.last_activity_at)
+        .bind(row.final_conclusion)
+        .bind(row.category)
+        .bind(step_count)
+        .bind(row.tech_identifier)
+        .bind(row.client_site)
+        .bind(row.entry_point)
+        .bind(steps_gz)
+        .bind(row.conclusion_node_id)
+        .bind(row.feedback_resolved)
+        .bind(row.feedback_comment)
+        .bind(row.feedback_at)
+        .bind(row.abandoned_at)
+        .bind(row.abandon_reason)
+        .bind(row.equipment_id)
+        .bind(row.variables)
+        .bind(row.device_id)
+        .bind(row.start_node_id)
+        .execute(&mut *tx)
+        .await?;
+
+        ids.push(row.session_id);
+    }
+
+    sqlx::query("DELETE FROM sessions WHERE session_id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(ids.len())
+}
+
+/// Archive every session started more than `days` ago that is finished or idle since then
+pub async fn archive_older_than(db: &PgPool, days: i64) -> Result<u64, sqlx::Error> {
+    let cutoff = Utc::now() - Duration::days(days);
+    let mut total = 0u64;
+
+    loop {
+        let moved = archive_batch(db, cutoff).await?;
+        total += moved as u64;
+        if (moved as i64) < BATCH_SIZE {
+            return Ok(total);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_round_trip_compressed() {
+        let steps = serde_json::json!([
+            { "node_id": "00000000-0000-0000-0000-00000000000a", "connection_label": "Pump" },
+            { "node_id": "00000000-0000-0000-0000-00000000000b", "connection_label": "No" },
+        ]);
+        let compressed = compress_steps(&steps).unwrap();
+        assert_eq!(decompress_steps(&compressed).unwrap(), steps);
+        assert!(decompress_steps(b"not gzip").is_err());
+    }
+}
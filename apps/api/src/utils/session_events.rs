@@ -0,0 +1,102 @@
+/// Session lifecycle event stream
+///
+/// Session starts, answered steps, completions, abandonments and feedback
+/// are handed to the analytics sinks (see `analytics`). With the `postgres`
+/// sink (or SESSION_EVENTS_ENABLED=true) they're written to the partitioned
+/// `session_events` table. Admin actions stay in `audit_logs`; this stream
+/// is high-volume.
+use crate::utils::analytics::{self, AnalyticsEvent};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Session lifecycle event types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEventType {
+    Started,
+    StepAnswered,
+    Completed,
+    Abandoned,
+    Feedback,
+}
+
+impl SessionEventType {
+    /// Value stored in `session_events.event_type`
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Started => "started",
+            Self::StepAnswered => "step_answered",
+            Self::Completed => "completed",
+            Self::Abandoned => "abandoned",
+            Self::Feedback => "feedback",
+        }
+    }
+
+    /// Name the analytics sinks deliver the event under
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Started => "session_started",
+            Self::StepAnswered => "step_answered",
+            Self::Completed => "conclusion_reached",
+            Self::Abandoned => "session_abandoned",
+            Self::Feedback => "feedback_given",
+        }
+    }
+}
+
+/// Whether session events are recorded (read once from SESSION_EVENTS_ENABLED)
+pub fn is_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        std::env::var("SESSION_EVENTS_ENABLED")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false)
+    })
+}
+
+/// Record a session event without blocking the request
+///
+/// The event is queued for the analytics sinks; delivery failures are logged
+/// and never surface to the technician using the session.
+pub fn record(
+    session_id: &str,
+    event: SessionEventType,
+    category: Option<&str>,
+    details: Option<JsonValue>,
+) {
+    analytics::emit(AnalyticsEvent::new(event, session_id, category, details));
+}
+
+/// Keep monthly partitions created ahead of time (checked daily)
+pub fn spawn_partition_maintenance(db: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(24 * 3600));
+        loop {
+            interval.tick().await;
+            match sqlx::query("SELECT ensure_session_event_partitions(2)")
+                .execute(&db)
+                .await
+            {
+                Ok(_) => tracing::debug!("🗂️  Session event partitions checked"),
+                Err(e) => tracing::warn!("⚠️  Failed to create session event partitions: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_strings() {
+        assert_eq!(SessionEventType::Started.as_str(), "started");
+        assert_eq!(SessionEventType::StepAnswered.as_str(), "step_answered");
+        assert_eq!(SessionEventType::Completed.as_str(), "completed");
+        assert_eq!(SessionEventType::Abandoned.as_str(), "abandoned");
+        assert_eq!(SessionEventType::Feedback.as_str(), "feedback");
+        assert_eq!(SessionEventType::Completed.event_name(), "conclusion_reached");
+        assert_eq!(SessionEventType::Feedback.event_name(), "feedback_given");
+    }
+}
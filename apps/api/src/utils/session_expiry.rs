@@ -0,0 +1,44 @@
+/// Idle session expiry
+///
+/// Open sessions with no answer or heartbeat for `session_idle_minutes` are
+/// marked abandoned (with `abandoned_at` set) by a background job in
+/// `main.rs`, so stats and session filters read the stored flag instead of
+/// each working out "idle for too long" on their own. Answering or going
+/// back reopens an expired session just like one the tech abandoned
+/// explicitly.
+///
+/// Every instance runs the job; the update only touches rows still open, so
+/// a session is expired (and its event recorded) once.
+use crate::utils::session_events::{self, SessionEventType};
+use sqlx::PgPool;
+
+/// Mark open sessions idle for at least `idle_minutes` as abandoned
+///
+/// Returns how many sessions were expired.
+pub async fn expire_idle(db: &PgPool, idle_minutes: i32) -> Result<usize, sqlx::Error> {
+    let expired = sqlx::query_as::<_, (String, i32, Option<String>)>(
+        "UPDATE sessions
+         SET abandoned = true, abandoned_at = NOW()
+         WHERE completed_at IS NULL
+           AND abandoned = false
+           AND last_activity_at <= NOW() - make_interval(mins => $1)
+         RETURNING session_id, COALESCE(jsonb_array_length(steps), 0)::int, (steps->0->>'category')::text",
+    )
+    .bind(idle_minutes)
+    .fetch_all(db)
+    .await?;
+
+    for (session_id, step_count, category) in &expired {
+        session_events::record(
+            session_id,
+            SessionEventType::Abandoned,
+            category.as_deref(),
+            Some(serde_json::json!({
+                "idle_minutes": idle_minutes,
+                "step_count": step_count,
+            })),
+        );
+    }
+
+    Ok(expired.len())
+}
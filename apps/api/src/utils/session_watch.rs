@@ -0,0 +1,167 @@
+/// Live session changes for supervisors
+///
+/// Answers, completions, rewinds and abandonments are announced with Postgres `NOTIFY`, so a
+/// supervisor connected to any instance hears about changes made on another.
+/// Each instance keeps one listening connection and fans the notifications
+/// out to its subscribers over a broadcast channel.
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Postgres channel the changes are sent on
+const CHANNEL: &str = "session_changes";
+
+/// Changes buffered per subscriber before it starts missing them
+const BUFFER: usize = 256;
+
+/// Wait before reconnecting after the listening connection fails
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// What happened to a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../web/src/types/")]
+pub enum SessionChangeKind {
+    /// An answer was recorded and the session moved on
+    Answered,
+    /// The session reached a conclusion
+    Completed,
+    /// Answers were undone by going back or returning to an earlier step
+    Rewound,
+    /// The tech gave up on the session
+    Abandoned,
+}
+
+impl SessionChangeKind {
+    /// Name used as the SSE event type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Answered => "answered",
+            Self::Completed => "completed",
+            Self::Rewound => "rewound",
+            Self::Abandoned => "abandoned",
+        }
+    }
+}
+
+/// A change to a session; fetch the session for its full state
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SessionChange {
+    pub session_id: String,
+    pub kind: SessionChangeKind,
+    /// Question (or conclusion) the session is now at
+    pub node_id: Uuid,
+    #[ts(type = "number")]
+    pub step_count: usize,
+    pub at: String,
+}
+
+fn sender() -> &'static broadcast::Sender<SessionChange> {
+    static SENDER: OnceLock<broadcast::Sender<SessionChange>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(BUFFER).0)
+}
+
+/// Receive every session change from now on
+pub fn subscribe() -> broadcast::Receiver<SessionChange> {
+    sender().subscribe()
+}
+
+/// Announce a change without blocking the request
+///
+/// Failures are logged; the tech's answer has already been saved.
+pub fn publish(db: &PgPool, kind: SessionChangeKind, session_id: &str, node_id: Uuid, step_count: usize) {
+    let change = SessionChange {
+        session_id: session_id.to_string(),
+        kind,
+        node_id,
+        step_count,
+        at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let db = db.clone();
+    tokio::spawn(async move {
+        let payload = match serde_json::to_string(&change) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!("Failed to encode session change: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(CHANNEL)
+            .bind(payload)
+            .execute(&db)
+            .await
+        {
+            tracing::warn!("Failed to publish change to session {}: {}", change.session_id, e);
+        }
+    });
+}
+
+/// Listen for changes from every instance and pass them to local subscribers
+pub fn spawn_listener(db: PgPool) {
+    tokio::spawn(async move {
+        loop {
+            let mut listener = match PgListener::connect_with(&db).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::warn!("Failed to connect session change listener: {}", e);
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            if let Err(e) = listener.listen(CHANNEL).await {
+                tracing::warn!("Failed to listen for session changes: {}", e);
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+
+            // recv() reconnects by itself; an error means that failed too
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => match serde_json::from_str::<SessionChange>(notification.payload()) {
+                        // No subscribers is not an error
+                        Ok(change) => {
+                            let _ = sender().send(change);
+                        }
+                        Err(e) => tracing::warn!("Ignoring malformed session change: {}", e),
+                    },
+                    Err(e) => {
+                        tracing::warn!("Session change listener failed: {}", e);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_payload() {
+        let change = SessionChange {
+            session_id: "abc".to_string(),
+            kind: SessionChangeKind::Completed,
+            node_id: Uuid::nil(),
+            step_count: 3,
+            at: "2025-10-24T00:00:00+00:00".to_string(),
+        };
+        let payload = serde_json::to_value(&change).unwrap();
+        assert_eq!(payload["kind"], "completed");
+        assert_eq!(SessionChangeKind::Completed.as_str(), "completed");
+
+        let parsed: SessionChange = serde_json::from_value(payload).unwrap();
+        assert_eq!(parsed.kind, SessionChangeKind::Completed);
+        assert_eq!(parsed.step_count, 3);
+    }
+}
@@ -0,0 +1,368 @@
+/// Runtime settings
+///
+/// Timeouts, limits and retention knobs that admins can change without
+/// editing the environment or restarting. Each setting has a default (taken
+/// from its environment variable where there is one) that applies until an
+/// override is stored in the `settings` table. Overrides are cached in
+/// `AppState` and re-read periodically, so other instances follow within a
+/// minute.
+///
+/// Branding has its own endpoint (`/api/v1/admin/branding`).
+use crate::utils::{login_guard, pool_metrics, session_archive};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How often each instance re-reads overrides
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A setting admins can override
+pub struct SettingDefinition {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub min: i64,
+    pub max: i64,
+    /// Value used without an override
+    pub default: fn() -> i64,
+}
+
+/// Every known setting
+pub const DEFINITIONS: &[SettingDefinition] = &[
+    SettingDefinition {
+        key: "login_max_failures",
+        description: "Consecutive wrong passwords before an account locks",
+        min: 1,
+        max: 100,
+        default: || login_guard::config().max_failures as i64,
+    },
+    SettingDefinition {
+        key: "login_lockout_minutes",
+        description: "Minutes a locked account stays locked",
+        min: 1,
+        max: 1440,
+        default: || login_guard::config().lockout_minutes as i64,
+    },
+    SettingDefinition {
+        key: "login_ip_max_failures",
+        description: "Failed logins allowed from one IP within the window",
+        min: 1,
+        max: 10_000,
+        default: || login_guard::config().ip_max_failures,
+    },
+    SettingDefinition {
+        key: "login_ip_window_minutes",
+        description: "Window for counting failed logins per IP",
+        min: 1,
+        max: 1440,
+        default: || login_guard::config().ip_window_minutes as i64,
+    },
+    SettingDefinition {
+        key: "duplicate_session_window_minutes",
+        description: "Warn when a session starts on equipment that had a session opened this many minutes earlier (0 turns the warning off)",
+        min: 0,
+        max: 1440,
+        default: || DEFAULT_DUPLICATE_SESSION_WINDOW_MINUTES,
+    },
+    SettingDefinition {
+        key: "require_device_token",
+        description: "Only registered kiosks (sending a device token) can start sessions (1 = required, 0 = optional)",
+        min: 0,
+        max: 1,
+        default: || 0,
+    },
+    SettingDefinition {
+        key: "resume_code_ttl_hours",
+        description: "Hours an idle session's resume code keeps working",
+        min: 1,
+        max: 720,
+        default: || DEFAULT_RESUME_CODE_TTL_HOURS,
+    },
+    SettingDefinition {
+        key: "session_idle_minutes",
+        description: "Minutes without an answer or heartbeat before an open session is marked abandoned",
+        min: 5,
+        max: 10_080,
+        default: || DEFAULT_SESSION_IDLE_MINUTES,
+    },
+    SettingDefinition {
+        key: "session_archive_after_days",
+        description: "Archive sessions older than this many days (0 turns automatic archiving off)",
+        min: 0,
+        max: 3650,
+        default: || session_archive::archive_after_days().unwrap_or(0),
+    },
+    SettingDefinition {
+        key: "db_max_connections",
+        description: "Database connections the pool keeps open, up to DB_MAX_CONNECTIONS; lowering it closes connections as they're released",
+        min: 1,
+        max: 10_000,
+        default: || pool_metrics::PoolConfig::from_env().max_connections as i64,
+    },
+];
+
+/// Resume code lifetime without an override
+const DEFAULT_RESUME_CODE_TTL_HOURS: i64 = 24;
+/// Idle period before a session is abandoned, without an override
+const DEFAULT_SESSION_IDLE_MINUTES: i64 = 60;
+/// Duplicate session window without an override
+const DEFAULT_DUPLICATE_SESSION_WINDOW_MINUTES: i64 = 15;
+
+/// Look up a setting by key
+pub fn definition(key: &str) -> Option<&'static SettingDefinition> {
+    DEFINITIONS.iter().find(|d| d.key == key)
+}
+
+/// Check a submitted value against a setting's range
+pub fn validate(definition: &SettingDefinition, value: &serde_json::Value) -> Result<i64, String> {
+    let value = value
+        .as_i64()
+        .ok_or_else(|| "Must be a whole number".to_string())?;
+    if value < definition.min || value > definition.max {
+        return Err(format!("Must be between {} and {}", definition.min, definition.max));
+    }
+    Ok(value)
+}
+
+/// A setting with its effective value, for the admin settings page
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct SettingInfo {
+    pub key: String,
+    pub description: String,
+    #[ts(type = "number")]
+    pub value: i64,
+    #[ts(type = "number")]
+    pub default_value: i64,
+    #[ts(type = "number")]
+    pub min: i64,
+    #[ts(type = "number")]
+    pub max: i64,
+    /// Whether an admin override is in effect
+    pub overridden: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_by: Option<Uuid>,
+}
+
+/// Request to override a setting
+#[derive(Debug, Deserialize, TS)]
+#[ts(export, export_to = "../../web/src/types/")]
+pub struct UpdateSettingRequest {
+    #[ts(type = "number")]
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+struct Override {
+    value: i64,
+    updated_at: DateTime<Utc>,
+    updated_by: Option<Uuid>,
+}
+
+/// Describe a setting given its override (value, when and by whom), if any
+pub fn info(definition: &SettingDefinition, current: Option<(i64, DateTime<Utc>, Option<Uuid>)>) -> SettingInfo {
+    let default_value = (definition.default)();
+    SettingInfo {
+        key: definition.key.to_string(),
+        description: definition.description.to_string(),
+        value: current.map(|(value, _, _)| value).unwrap_or(default_value),
+        default_value,
+        min: definition.min,
+        max: definition.max,
+        overridden: current.is_some(),
+        updated_at: current.map(|(_, updated_at, _)| updated_at),
+        updated_by: current.and_then(|(_, _, updated_by)| updated_by),
+    }
+}
+
+/// Cached setting overrides with typed accessors
+///
+/// Lives in the library's `AppState`, so its methods only deal in plain
+/// types that both the library and the binary agree on.
+#[allow(dead_code)] // Constructed by the library; the binary reaches it through AppState
+#[derive(Clone, Default)]
+pub struct Settings {
+    overrides: Arc<RwLock<HashMap<String, Override>>>,
+}
+
+#[allow(dead_code)] // Called on the library's instance; see the struct
+impl Settings {
+    /// Effective value of a defined setting
+    fn get(&self, key: &str) -> i64 {
+        self.override_of(key)
+            .map(|(value, _, _)| value)
+            .or_else(|| definition(key).map(|d| (d.default)()))
+            .expect("setting is defined")
+    }
+
+    /// The stored override of a setting: value, when and by whom
+    pub fn override_of(&self, key: &str) -> Option<(i64, DateTime<Utc>, Option<Uuid>)> {
+        self.overrides
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .map(|o| (o.value, o.updated_at, o.updated_by))
+    }
+
+    /// Consecutive wrong passwords before an account locks
+    pub fn login_max_failures(&self) -> i32 {
+        self.get("login_max_failures") as i32
+    }
+
+    /// Minutes a locked account stays locked
+    pub fn login_lockout_minutes(&self) -> i32 {
+        self.get("login_lockout_minutes") as i32
+    }
+
+    /// Failed logins allowed from one IP within the window
+    pub fn login_ip_max_failures(&self) -> i64 {
+        self.get("login_ip_max_failures")
+    }
+
+    /// Window in minutes for counting failed logins per IP
+    pub fn login_ip_window_minutes(&self) -> i32 {
+        self.get("login_ip_window_minutes") as i32
+    }
+
+    /// Minutes after a session starts during which another session on the
+    /// same equipment is flagged (None when disabled)
+    pub fn duplicate_session_window_minutes(&self) -> Option<i32> {
+        Some(self.get("duplicate_session_window_minutes") as i32).filter(|minutes| *minutes > 0)
+    }
+
+    /// Whether starting a session requires a registered device token
+    pub fn require_device_token(&self) -> bool {
+        self.get("require_device_token") != 0
+    }
+
+    /// Hours an idle session's resume code keeps working
+    pub fn resume_code_ttl_hours(&self) -> i32 {
+        self.get("resume_code_ttl_hours") as i32
+    }
+
+    /// Minutes without activity before an open session is marked abandoned
+    pub fn session_idle_minutes(&self) -> i32 {
+        self.get("session_idle_minutes") as i32
+    }
+
+    /// Days after which sessions are archived automatically (None when disabled)
+    pub fn session_archive_after_days(&self) -> Option<i64> {
+        Some(self.get("session_archive_after_days")).filter(|days| *days > 0)
+    }
+
+    /// Database connections the pool keeps open (capped by the pool itself)
+    pub fn db_max_connections(&self) -> u32 {
+        self.get("db_max_connections") as u32
+    }
+
+    /// Re-read overrides from the database
+    ///
+    /// Rows for unknown keys and values outside the current range are ignored,
+    /// so removing or narrowing a setting never breaks startup.
+    pub async fn reload(&self, db: &PgPool) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, serde_json::Value, DateTime<Utc>, Option<Uuid>)>(
+            "SELECT key, value, updated_at, updated_by FROM settings",
+        )
+        .fetch_all(db)
+        .await?;
+
+        let overrides = rows
+            .into_iter()
+            .filter_map(|(key, value, updated_at, updated_by)| {
+                let value = validate(definition(&key)?, &value).ok()?;
+                Some((key, Override { value, updated_at, updated_by }))
+            })
+            .collect();
+
+        *self.overrides.write().unwrap_or_else(|e| e.into_inner()) = overrides;
+        Ok(())
+    }
+
+    /// Store an override (already validated) and apply it on this instance
+    pub async fn set(&self, db: &PgPool, key: &str, value: i64, updated_by: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_by, updated_at)
+             VALUES ($1, $2, $3, NOW())
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by, updated_at = NOW()",
+        )
+        .bind(key)
+        .bind(serde_json::json!(value))
+        .bind(updated_by)
+        .execute(db)
+        .await?;
+
+        self.reload(db).await
+    }
+
+    /// Remove an override; returns whether there was one
+    pub async fn reset(&self, db: &PgPool, key: &str) -> Result<bool, sqlx::Error> {
+        let removed = sqlx::query("DELETE FROM settings WHERE key = $1")
+            .bind(key)
+            .execute(db)
+            .await?
+            .rows_affected()
+            > 0;
+
+        self.reload(db).await?;
+        Ok(removed)
+    }
+
+    /// Periodically re-read overrides so every instance applies the same values
+    pub fn spawn_refresh(&self, db: PgPool) {
+        let settings = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = settings.reload(&db).await {
+                    tracing::warn!("Failed to refresh settings: {}", e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definitions() {
+        let mut keys: Vec<&str> = DEFINITIONS.iter().map(|d| d.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), DEFINITIONS.len(), "setting keys must be unique");
+
+        // Defaults must be valid values, or an untouched install would be out of range
+        for d in DEFINITIONS {
+            assert!(validate(d, &serde_json::json!((d.default)())).is_ok(), "{} default out of range", d.key);
+        }
+    }
+
+    #[test]
+    fn test_validate_and_overrides() {
+        let ttl = definition("resume_code_ttl_hours").unwrap();
+        assert_eq!(validate(ttl, &serde_json::json!(48)), Ok(48));
+        assert!(validate(ttl, &serde_json::json!(0)).is_err());
+        assert!(validate(ttl, &serde_json::json!(1.5)).is_err());
+        assert!(validate(ttl, &serde_json::json!("48")).is_err());
+        assert!(definition("no_such_setting").is_none());
+
+        let settings = Settings::default();
+        assert_eq!(settings.resume_code_ttl_hours(), DEFAULT_RESUME_CODE_TTL_HOURS as i32);
+        settings.overrides.write().unwrap().insert(
+            "resume_code_ttl_hours".to_string(),
+            Override { value: 48, updated_at: Utc::now(), updated_by: None },
+        );
+        assert_eq!(settings.resume_code_ttl_hours(), 48);
+
+        let info = info(ttl, settings.override_of("resume_code_ttl_hours"));
+        assert!(info.overridden);
+        assert_eq!(info.value, 48);
+        assert_eq!(info.default_value, DEFAULT_RESUME_CODE_TTL_HOURS);
+    }
+}
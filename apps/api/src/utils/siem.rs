@@ -0,0 +1,477 @@
+/// Continuous forwarding of audit entries to a SIEM
+///
+/// When SIEM_ENDPOINT is set, a background task sends new audit log entries
+/// to the security team's collector: as JSON batches POSTed to an
+/// `http(s)://` endpoint, or as RFC 5424 messages over TCP to a
+/// `syslog://host:port` endpoint. Login, logout and password events are
+/// only included with SIEM_INCLUDE_AUTH_EVENTS=true, and session lifecycle
+/// events (when SESSION_EVENTS_ENABLED records them) with
+/// SIEM_INCLUDE_SESSION_EVENTS=true.
+///
+/// Each stream's position is checkpointed in `siem_cursors` after every
+/// delivered batch, so a restart resumes where it stopped and a failed
+/// delivery is retried rather than skipped. The first run sends the existing
+/// history. With several instances, the cursor row lock lets only one of them
+/// forward a stream at a time.
+use crate::utils::audit::AuditAction;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Entries younger than this are left for the next pass, so a transaction
+/// that commits late with an earlier timestamp isn't skipped by the cursor
+const SETTLE_DELAY_SECONDS: f64 = 5.0;
+
+/// Delivery attempts per batch before giving up until the next interval
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry; doubled after each failure
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Timeout for one delivery attempt
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest batch accepted from SIEM_BATCH_SIZE
+const MAX_BATCH_SIZE: i64 = 1000;
+
+/// Syslog APP-NAME
+const APP_NAME: &str = "equipment-troubleshooting";
+
+/// Syslog priority: facility 13 (log audit), severity 6 (informational)
+const SYSLOG_PRIORITY: u8 = 13 * 8 + 6;
+
+/// Audit actions that are authentication events
+const AUTH_ACTIONS: &[AuditAction] = &[
+    AuditAction::AdminLogin,
+    AuditAction::AdminLoginFailed,
+    AuditAction::AccountLocked,
+    AuditAction::AdminLogout,
+    AuditAction::PasswordChanged,
+    AuditAction::PasswordResetRequested,
+    AuditAction::PasswordReset,
+];
+
+/// Where events are delivered
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transport {
+    /// POST a JSON array of events per batch
+    Http { url: String, authorization: Option<String> },
+    /// RFC 5424 messages over TCP with octet-counting framing (RFC 6587)
+    Syslog { address: String },
+}
+
+impl Transport {
+    fn parse(endpoint: &str, authorization: Option<String>) -> Result<Self, String> {
+        if let Some(address) = endpoint.strip_prefix("syslog://") {
+            let address = address.trim_end_matches('/');
+            match address.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(Self::Syslog {
+                    address: address.to_string(),
+                }),
+                _ => Err("syslog endpoints must be syslog://host:port".to_string()),
+            }
+        } else if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            Ok(Self::Http {
+                url: endpoint.to_string(),
+                authorization,
+            })
+        } else {
+            Err("SIEM_ENDPOINT must start with http://, https:// or syslog://".to_string())
+        }
+    }
+}
+
+/// SIEM forwarding configuration loaded from the environment
+#[derive(Debug, Clone)]
+pub struct SiemConfig {
+    pub transport: Transport,
+    pub interval: Duration,
+    pub batch_size: i64,
+    pub include_auth_events: bool,
+    pub include_session_events: bool,
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false)
+}
+
+impl SiemConfig {
+    /// Returns None unless SIEM_ENDPOINT is set (and valid)
+    pub fn from_env() -> Option<Self> {
+        let endpoint = std::env::var("SIEM_ENDPOINT").ok().filter(|e| !e.trim().is_empty())?;
+        let transport = match Transport::parse(endpoint.trim(), std::env::var("SIEM_HTTP_AUTHORIZATION").ok()) {
+            Ok(transport) => transport,
+            Err(e) => {
+                tracing::warn!("⚠️  Invalid SIEM_ENDPOINT ({}); SIEM forwarding disabled", e);
+                return None;
+            }
+        };
+
+        let interval_seconds = std::env::var("SIEM_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|s| *s > 0)
+            .unwrap_or(10);
+        let batch_size = std::env::var("SIEM_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(200)
+            .min(MAX_BATCH_SIZE);
+
+        Some(Self {
+            transport,
+            interval: Duration::from_secs(interval_seconds),
+            batch_size,
+            include_auth_events: env_flag("SIEM_INCLUDE_AUTH_EVENTS"),
+            include_session_events: env_flag("SIEM_INCLUDE_SESSION_EVENTS"),
+        })
+    }
+
+    fn streams(&self) -> Vec<Stream> {
+        let mut streams = vec![Stream::Audit];
+        if self.include_session_events {
+            streams.push(Stream::SessionEvents);
+        }
+        streams
+    }
+}
+
+/// A forwarded source table
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stream {
+    Audit,
+    SessionEvents,
+}
+
+impl Stream {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Audit => "audit",
+            Self::SessionEvents => "session_events",
+        }
+    }
+}
+
+/// One forwarded entry
+#[derive(Debug, Serialize)]
+pub struct SiemEvent {
+    pub stream: &'static str,
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub action: String,
+    pub user_id: Option<Uuid>,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Why a batch wasn't forwarded
+#[derive(Debug)]
+enum ForwardError {
+    Database(sqlx::Error),
+    Delivery(String),
+}
+
+impl From<sqlx::Error> for ForwardError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl std::fmt::Display for ForwardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "database error: {}", e),
+            Self::Delivery(e) => write!(f, "delivery failed: {}", e),
+        }
+    }
+}
+
+/// Frame an event as an RFC 5424 message with an octet count prefix
+fn syslog_frame(event: &SiemEvent, hostname: &str) -> Result<String, serde_json::Error> {
+    // MSGID is limited to 32 printable ASCII characters
+    let msg_id: String = event
+        .action
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(32)
+        .collect();
+    let message = format!(
+        "<{}>1 {} {} {} - {} - {}",
+        SYSLOG_PRIORITY,
+        event.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        hostname,
+        APP_NAME,
+        if msg_id.is_empty() { "-" } else { &msg_id },
+        serde_json::to_string(event)?,
+    );
+    Ok(format!("{} {}", message.len(), message))
+}
+
+/// Entries after the cursor, oldest first
+async fn fetch_batch(
+    tx: &mut sqlx::PgConnection,
+    config: &SiemConfig,
+    stream: Stream,
+    after: Option<(DateTime<Utc>, String)>,
+) -> Result<Vec<SiemEvent>, sqlx::Error> {
+    let (after_at, after_id) = after.unzip();
+
+    match stream {
+        Stream::Audit => {
+            let auth_actions: Vec<&str> = AUTH_ACTIONS.iter().map(|a| a.as_str()).collect();
+            let rows = sqlx::query_as::<
+                _,
+                (Uuid, DateTime<Utc>, Uuid, String, String, Option<String>, Option<serde_json::Value>, Option<String>),
+            >(
+                "SELECT id, created_at, user_id, action, resource_type, resource_id, details, ip_address
+                 FROM audit_logs
+                 WHERE created_at < NOW() - make_interval(secs => $1)
+                   AND ($2::timestamptz IS NULL OR (created_at, id) > ($2, $3::uuid))
+                   AND ($4 OR action <> ALL($5))
+                 ORDER BY created_at, id
+                 LIMIT $6",
+            )
+            .bind(SETTLE_DELAY_SECONDS)
+            .bind(after_at)
+            .bind(after_id)
+            .bind(config.include_auth_events)
+            .bind(&auth_actions)
+            .bind(config.batch_size)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(id, at, user_id, action, resource_type, resource_id, details, ip_address)| SiemEvent {
+                    stream: stream.name(),
+                    id: id.to_string(),
+                    timestamp: at,
+                    action,
+                    user_id: Some(user_id),
+                    resource_type,
+                    resource_id,
+                    ip_address,
+                    details,
+                })
+                .collect())
+        }
+        Stream::SessionEvents => {
+            let rows = sqlx::query_as::<_, (i64, DateTime<Utc>, String, String, Option<String>, Option<serde_json::Value>)>(
+                "SELECT id, occurred_at, session_id, event_type, category, details
+                 FROM session_events
+                 WHERE occurred_at < NOW() - make_interval(secs => $1)
+                   AND ($2::timestamptz IS NULL OR (occurred_at, id) > ($2, $3::bigint))
+                 ORDER BY occurred_at, id
+                 LIMIT $4",
+            )
+            .bind(SETTLE_DELAY_SECONDS)
+            .bind(after_at)
+            .bind(after_id)
+            .bind(config.batch_size)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(id, at, session_id, event_type, category, details)| SiemEvent {
+                    stream: stream.name(),
+                    id: id.to_string(),
+                    timestamp: at,
+                    action: format!("session_{}", event_type),
+                    user_id: None,
+                    resource_type: "session".to_string(),
+                    resource_id: Some(session_id),
+                    ip_address: None,
+                    details: Some(serde_json::json!({ "category": category, "details": details })),
+                })
+                .collect())
+        }
+    }
+}
+
+/// Send a batch once
+async fn deliver(client: &reqwest::Client, transport: &Transport, events: &[SiemEvent]) -> Result<(), String> {
+    match transport {
+        Transport::Http { url, authorization } => {
+            let mut request = client.post(url).timeout(DELIVERY_TIMEOUT).json(events);
+            if let Some(authorization) = authorization {
+                request = request.header(reqwest::header::AUTHORIZATION, authorization);
+            }
+            let response = request.send().await.map_err(|e| e.to_string())?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("endpoint returned {}", response.status()))
+            }
+        }
+        Transport::Syslog { address } => {
+            let hostname = std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string());
+            let mut payload = String::new();
+            for event in events {
+                payload.push_str(&syslog_frame(event, &hostname).map_err(|e| e.to_string())?);
+            }
+
+            let send = async {
+                let mut stream = tokio::net::TcpStream::connect(address).await?;
+                stream.write_all(payload.as_bytes()).await?;
+                stream.flush().await
+            };
+            tokio::time::timeout(DELIVERY_TIMEOUT, send)
+                .await
+                .map_err(|_| "timed out".to_string())?
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Send a batch, backing off between failed attempts
+async fn deliver_with_retries(
+    client: &reqwest::Client,
+    transport: &Transport,
+    events: &[SiemEvent],
+) -> Result<(), String> {
+    let mut delay = RETRY_BASE_DELAY;
+    let mut attempt = 1;
+    loop {
+        match deliver(client, transport, events).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                tracing::debug!("SIEM delivery attempt {} failed: {}", attempt, e);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Forward the next batch of a stream; returns how many entries were delivered
+async fn forward_batch(
+    db: &PgPool,
+    client: &reqwest::Client,
+    config: &SiemConfig,
+    stream: Stream,
+) -> Result<usize, ForwardError> {
+    sqlx::query("INSERT INTO siem_cursors (stream) VALUES ($1) ON CONFLICT (stream) DO NOTHING")
+        .bind(stream.name())
+        .execute(db)
+        .await?;
+
+    let mut tx = db.begin().await?;
+
+    // Another instance holding the row is already forwarding this stream
+    let Some((last_at, last_id)) = sqlx::query_as::<_, (Option<DateTime<Utc>>, Option<String>)>(
+        "SELECT last_at, last_id FROM siem_cursors WHERE stream = $1 FOR UPDATE SKIP LOCKED",
+    )
+    .bind(stream.name())
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(0);
+    };
+
+    let after = last_at.zip(last_id);
+    let events = fetch_batch(&mut tx, config, stream, after).await?;
+    let Some(last) = events.last() else {
+        return Ok(0);
+    };
+
+    deliver_with_retries(client, &config.transport, &events)
+        .await
+        .map_err(ForwardError::Delivery)?;
+
+    sqlx::query("UPDATE siem_cursors SET last_at = $2, last_id = $3, updated_at = NOW() WHERE stream = $1")
+        .bind(stream.name())
+        .bind(last.timestamp)
+        .bind(&last.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(events.len())
+}
+
+/// Forward everything pending on a stream
+async fn forward(db: &PgPool, client: &reqwest::Client, config: &SiemConfig, stream: Stream) -> Result<usize, ForwardError> {
+    let mut total = 0;
+    loop {
+        let sent = forward_batch(db, client, config, stream).await?;
+        total += sent;
+        if (sent as i64) < config.batch_size {
+            return Ok(total);
+        }
+    }
+}
+
+/// Spawn the forwarding task
+pub fn spawn(db: PgPool, config: SiemConfig) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut interval = tokio::time::interval(config.interval);
+
+        loop {
+            interval.tick().await;
+
+            for stream in config.streams() {
+                match forward(&db, &client, &config, stream).await {
+                    Ok(0) => {}
+                    Ok(sent) => tracing::debug!("🛡️ Forwarded {} {} entries to SIEM", sent, stream.name()),
+                    // The cursor didn't move, so the entries are retried next interval
+                    Err(e) => tracing::warn!("SIEM forwarding of {} failed: {}", stream.name(), e),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transport() {
+        assert_eq!(
+            Transport::parse("syslog://siem.internal:6514", None),
+            Ok(Transport::Syslog { address: "siem.internal:6514".to_string() })
+        );
+        assert!(Transport::parse("syslog://siem.internal", None).is_err());
+        assert!(Transport::parse("syslog://:514", None).is_err());
+        assert_eq!(
+            Transport::parse("https://siem.example.com/ingest", Some("Splunk abc".to_string())),
+            Ok(Transport::Http {
+                url: "https://siem.example.com/ingest".to_string(),
+                authorization: Some("Splunk abc".to_string()),
+            })
+        );
+        assert!(Transport::parse("ftp://siem.example.com", None).is_err());
+    }
+
+    #[test]
+    fn test_syslog_frame() {
+        let event = SiemEvent {
+            stream: "audit",
+            id: "1".to_string(),
+            timestamp: DateTime::parse_from_rfc3339("2025-10-24T10:00:00Z").unwrap().with_timezone(&Utc),
+            action: "admin_login".to_string(),
+            user_id: None,
+            resource_type: "user".to_string(),
+            resource_id: None,
+            ip_address: Some("10.0.0.1".to_string()),
+            details: None,
+        };
+        let frame = syslog_frame(&event, "api-1").unwrap();
+        let (length, message) = frame.split_once(' ').unwrap();
+        assert_eq!(length.parse::<usize>().unwrap(), message.len());
+        assert!(message.starts_with("<110>1 2025-10-24T10:00:00.000Z api-1 equipment-troubleshooting - admin_login - {"));
+        assert!(message.ends_with('}'));
+    }
+}
@@ -0,0 +1,50 @@
+/// Far-future caching for content-hashed files (`assets/index-B3xk9_aQ.js`)
+pub const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Revalidate on every load (`index.html` and unhashed files), so kiosks pick
+/// up a new build after a deploy
+pub const NO_CACHE: &str = "no-cache";
+
+/// Shortest and longest hash Vite/Rollup produce in filenames
+const HASH_MIN_LENGTH: usize = 8;
+const HASH_MAX_LENGTH: usize = 20;
+
+/// True when the filename carries a content hash (`name-HASH.ext` or `name.HASH.ext`)
+pub fn is_fingerprinted(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let Some((stem, _extension)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+    let Some(hash) = stem.rsplit(['-', '.']).next().filter(|h| h.len() < stem.len()) else {
+        return false;
+    };
+
+    (HASH_MIN_LENGTH..=HASH_MAX_LENGTH).contains(&hash.len())
+        && hash.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        // Plain words ("component-overview.js") are not hashes
+        && hash.chars().any(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+}
+
+/// Cache-Control value for a file served from the SPA build
+pub fn cache_control(path: &str) -> &'static str {
+    if path.ends_with(".html") || !is_fingerprinted(path) {
+        NO_CACHE
+    } else {
+        IMMUTABLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_control_by_filename() {
+        assert_eq!(cache_control("/assets/index-B3xk9_aQ.js"), IMMUTABLE);
+        assert_eq!(cache_control("/assets/vendor.4f2a91c0.css"), IMMUTABLE);
+        assert_eq!(cache_control("/index.html"), NO_CACHE);
+        assert_eq!(cache_control("/favicon.svg"), NO_CACHE);
+        assert_eq!(cache_control("/assets/component-overview.js"), NO_CACHE);
+        assert_eq!(cache_control("/assets/a-b.js"), NO_CACHE);
+    }
+}
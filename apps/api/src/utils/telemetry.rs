@@ -0,0 +1,202 @@
+/// Anonymous usage telemetry (strictly opt-in)
+///
+/// When TELEMETRY_ENABLED=true and TELEMETRY_ENDPOINT is set, a background task
+/// periodically posts aggregate fleet-health numbers (version, session counts,
+/// error rates). Node text, session steps, identifiers and IPs are never sent.
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Requests observed since the last report
+static REQUESTS_TOTAL: AtomicU64 = AtomicU64::new(0);
+/// Responses with a 5xx status since the last report
+static SERVER_ERRORS_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Telemetry configuration loaded from the environment
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    pub endpoint: String,
+    pub interval: Duration,
+    pub install_id: String,
+}
+
+impl TelemetryConfig {
+    /// Returns None unless telemetry has been explicitly enabled
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("TELEMETRY_ENABLED")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        if !enabled {
+            return None;
+        }
+
+        let endpoint = match std::env::var("TELEMETRY_ENDPOINT") {
+            Ok(endpoint) if !endpoint.is_empty() => endpoint,
+            _ => {
+                tracing::warn!("⚠️  TELEMETRY_ENABLED is set but TELEMETRY_ENDPOINT is missing; telemetry disabled");
+                return None;
+            }
+        };
+
+        let interval_hours = std::env::var("TELEMETRY_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|h| *h > 0)
+            .unwrap_or(24);
+
+        // A random ID per process unless the operator pins one; it identifies nothing about the site
+        let install_id = std::env::var("TELEMETRY_INSTALL_ID")
+            .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string());
+
+        Some(Self {
+            endpoint,
+            interval: Duration::from_secs(interval_hours * 3600),
+            install_id,
+        })
+    }
+}
+
+/// Aggregate report sent to the telemetry endpoint
+#[derive(Debug, Serialize)]
+pub struct TelemetryReport {
+    pub install_id: String,
+    pub version: &'static str,
+    pub environment: Option<String>,
+    pub uptime_seconds: u64,
+    pub interval_seconds: u64,
+    pub sessions_started: i64,
+    pub sessions_completed: i64,
+    pub sessions_abandoned: i64,
+    pub requests_total: u64,
+    pub server_errors_total: u64,
+    pub error_rate: f64,
+}
+
+/// Count a response toward the request/error totals
+pub fn record_response(status: axum::http::StatusCode) {
+    REQUESTS_TOTAL.fetch_add(1, Ordering::Relaxed);
+    if status.is_server_error() {
+        SERVER_ERRORS_TOTAL.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Fraction of requests that failed with a server error
+fn error_rate(requests: u64, errors: u64) -> f64 {
+    if requests == 0 {
+        0.0
+    } else {
+        errors as f64 / requests as f64
+    }
+}
+
+/// Build a report for the last interval and reset the request counters
+async fn collect_report(
+    db: &PgPool,
+    config: &TelemetryConfig,
+    started: Instant,
+) -> Result<TelemetryReport, sqlx::Error> {
+    let (sessions_started, sessions_completed, sessions_abandoned) =
+        sqlx::query_as::<_, (i64, i64, i64)>(
+            "SELECT
+                COUNT(*),
+                COUNT(*) FILTER (WHERE completed_at IS NOT NULL),
+                COUNT(*) FILTER (WHERE abandoned = true)
+             FROM sessions
+             WHERE started_at >= NOW() - make_interval(secs => $1)",
+        )
+        .bind(config.interval.as_secs() as f64)
+        .fetch_one(db)
+        .await?;
+
+    let requests_total = REQUESTS_TOTAL.swap(0, Ordering::Relaxed);
+    let server_errors_total = SERVER_ERRORS_TOTAL.swap(0, Ordering::Relaxed);
+
+    Ok(TelemetryReport {
+        install_id: config.install_id.clone(),
+        version: env!("CARGO_PKG_VERSION"),
+        environment: std::env::var("ENVIRONMENT").ok(),
+        uptime_seconds: started.elapsed().as_secs(),
+        interval_seconds: config.interval.as_secs(),
+        sessions_started,
+        sessions_completed,
+        sessions_abandoned,
+        requests_total,
+        server_errors_total,
+        error_rate: error_rate(requests_total, server_errors_total),
+    })
+}
+
+/// Spawn the periodic reporting task
+pub fn spawn(db: PgPool, config: TelemetryConfig) {
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let client = reqwest::Client::new();
+        let mut interval = tokio::time::interval(config.interval);
+        // The first tick fires immediately; skip it so the first report covers a full interval
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let report = match collect_report(&db, &config, started).await {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::debug!("Telemetry collection failed: {}", e);
+                    continue;
+                }
+            };
+
+            match client
+                .post(&config.endpoint)
+                .timeout(Duration::from_secs(10))
+                .json(&report)
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    tracing::debug!("📡 Telemetry report sent");
+                }
+                Ok(response) => {
+                    tracing::debug!("Telemetry endpoint returned {}", response.status());
+                }
+                Err(e) => {
+                    tracing::debug!("Telemetry report failed: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rate() {
+        assert_eq!(error_rate(0, 0), 0.0);
+        assert_eq!(error_rate(200, 50), 0.25);
+    }
+
+    #[test]
+    fn test_report_contains_no_content_fields() {
+        let report = TelemetryReport {
+            install_id: "install".to_string(),
+            version: "2.0.0",
+            environment: None,
+            uptime_seconds: 10,
+            interval_seconds: 86400,
+            sessions_started: 5,
+            sessions_completed: 4,
+            sessions_abandoned: 1,
+            requests_total: 100,
+            server_errors_total: 1,
+            error_rate: 0.01,
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+        assert!(!keys.iter().any(|k| k.contains("text") || k.contains("ip") || k.contains("tech")));
+    }
+}
@@ -0,0 +1,52 @@
+/// Placeholders in node text
+///
+/// `{{serial}}` renders as the session variable; unset variables are left as
+/// written so a missing value is visible.
+use crate::utils::expression::Variables;
+
+/// Replace `{{name}}` placeholders with the session's variables
+pub fn render(text: &str, variables: &Variables) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + length].trim();
+        let end = start + 2 + length + 2;
+
+        rendered.push_str(&rest[..start]);
+        match variables.get(name) {
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => rendered.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::expression::Value;
+
+    #[test]
+    fn test_render() {
+        let variables = Variables::from([
+            ("serial".to_string(), Value::Text("SN-1042".to_string())),
+            ("battery".to_string(), Value::Number(11.5)),
+        ]);
+
+        assert_eq!(
+            render("Check unit {{serial}} ({{ battery }} V)", &variables),
+            "Check unit SN-1042 (11.5 V)"
+        );
+        assert_eq!(render("Code {{error_code}} on {{serial}}", &variables), "Code {{error_code}} on SN-1042");
+        assert_eq!(render("No placeholders", &variables), "No placeholders");
+        assert_eq!(render("Unclosed {{serial", &variables), "Unclosed {{serial");
+        assert_eq!(render("{{serial}}{{serial}}", &variables), "SN-1042SN-1042");
+    }
+}
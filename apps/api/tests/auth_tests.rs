@@ -1,7 +1,7 @@
 mod common;
 
 use equipment_troubleshooting::models::UserRole;
-use equipment_troubleshooting::utils::jwt::{generate_token, verify_token, extract_token};
+use equipment_troubleshooting::utils::jwt::{generate_token_with_expiration, verify_token, extract_token};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -14,7 +14,7 @@ async fn test_generate_and_verify_token() {
     let role = UserRole::Admin;
 
     // Generate token
-    let token = generate_token(user_id, email.clone(), role.clone())
+    let token = generate_token_with_expiration(user_id, email.clone(), role.clone(), 15)
         .expect("Failed to generate token");
 
     assert!(!token.is_empty());
@@ -54,7 +54,7 @@ pub async fn create_test_user(pool: &PgPool, email: &str, role: UserRole) -> Uui
 
 /// Generate JWT token for test user
 pub fn generate_test_token(user_id: Uuid, email: &str, role: UserRole) -> String {
-    equipment_troubleshooting::utils::jwt::generate_token(user_id, email.to_string(), role)
+    equipment_troubleshooting::utils::jwt::generate_token_with_expiration(user_id, email.to_string(), role, 15)
         .expect("Failed to generate test token")
 }
 
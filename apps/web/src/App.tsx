@@ -1,6 +1,7 @@
 import { BrowserRouter, Routes, Route, Navigate } from 'react-router-dom';
-import { lazy, Suspense } from 'react';
+import { lazy, Suspense, useEffect } from 'react';
 import { ErrorBoundary } from './components/ErrorBoundary';
+import { configAPI } from './lib/api';
 
 // Lazy load page components for code splitting
 const LandingPage = lazy(() => import('./pages/LandingPage'));
@@ -34,6 +35,13 @@ function ProtectedRoute({ children }: { children: React.ReactNode }) {
 }
 
 function App() {
+  // Show the deployment's product name in the tab; keep the default title if config can't load
+  useEffect(() => {
+    configAPI.getPublic()
+      .then((config) => { document.title = config.product_name; })
+      .catch(() => {});
+  }, []);
+
   return (
     <ErrorBoundary>
       <BrowserRouter>
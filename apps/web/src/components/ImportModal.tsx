@@ -170,6 +170,19 @@ const ImportModal = memo(function ImportModal({ isOpen, onClose, onSuccess }: Im
                     {result.errors.map((error, idx: number) => (
                       <div key={idx} className="text-sm text-red-700">
                         <strong>{error.category}</strong>: {error.error}
+                        {error.details.length > 0 ? (
+                          <ul className="mt-1 ml-4 list-disc text-xs">
+                            {error.details.map((detail, i: number) => (
+                              <li key={i}>{detail}</li>
+                            ))}
+                          </ul>
+                        ) : error.fields.length > 0 && (
+                          <ul className="mt-1 ml-4 list-disc text-xs">
+                            {error.fields.map((field, i: number) => (
+                              <li key={i}>{field.field}: {field.message}</li>
+                            ))}
+                          </ul>
+                        )}
                       </div>
                     ))}
                   </div>
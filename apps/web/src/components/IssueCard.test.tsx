@@ -19,6 +19,14 @@ const mockIssue: Issue = {
   question_count: 10n,
   created_at: '2024-01-01T00:00:00Z',
   updated_at: '2024-01-01T00:00:00Z',
+  archived_at: null,
+  description: null,
+  owner_id: null,
+  owner_email: null,
+  equipment_models: [],
+  tags: [],
+  active_from: null,
+  active_until: null,
 };
 
 describe('IssueCard', () => {
@@ -37,6 +45,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -70,6 +79,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -92,6 +102,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -114,6 +125,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -139,6 +151,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -164,6 +177,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -190,6 +204,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
@@ -217,6 +232,7 @@ describe('IssueCard', () => {
         onEdit={onEdit}
         onDelete={onDelete}
         onExport={onExport}
+        onArchive={vi.fn()}
       />
     );
 
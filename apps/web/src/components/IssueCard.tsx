@@ -9,10 +9,13 @@ interface IssueCardProps {
   onEdit: (category: string) => void;
   onDelete: (category: string, deleteSessions: boolean) => Promise<void>;
   onExport: (category: string) => Promise<void>;
+  onArchive: (category: string, archive: boolean) => Promise<void>;
 }
 
-const IssueCard = memo(function IssueCard({ issue, onToggle, onTest, onEdit, onDelete, onExport }: IssueCardProps) {
+const IssueCard = memo(function IssueCard({ issue, onToggle, onTest, onEdit, onDelete, onExport, onArchive }: IssueCardProps) {
   const [toggling, setToggling] = useState(false);
+  const [archiving, setArchiving] = useState(false);
+  const archived = issue.archived_at !== null;
   const [showDeleteModal, setShowDeleteModal] = useState(false);
 
   const handleToggle = async () => {
@@ -24,6 +27,15 @@ const IssueCard = memo(function IssueCard({ issue, onToggle, onTest, onEdit, onD
     }
   };
 
+  const handleArchive = async () => {
+    setArchiving(true);
+    try {
+      await onArchive(issue.category, !archived);
+    } finally {
+      setArchiving(false);
+    }
+  };
+
   const handleDeleteClick = () => {
     setShowDeleteModal(true);
   };
@@ -45,6 +57,14 @@ const IssueCard = memo(function IssueCard({ issue, onToggle, onTest, onEdit, onD
                 {issue.display_category}
               </span>
             )}
+            {archived && (
+              <span
+                className="inline-block bg-amber-100 text-amber-700 px-3 py-1 rounded text-[0.85em] font-medium"
+                title="Hidden from technicians; sessions and the tree are kept"
+              >
+                Archived
+              </span>
+            )}
           </div>
           <p className="text-gray-500 text-[0.9em] mt-2">
             {Number(issue.question_count)} question{Number(issue.question_count) !== 1 ? 's' : ''} in this decision tree
@@ -96,6 +116,16 @@ const IssueCard = memo(function IssueCard({ issue, onToggle, onTest, onEdit, onD
         >
           📤 Export
         </button>
+        <button
+          onClick={handleArchive}
+          disabled={archiving}
+          className={`px-3 py-[6px] text-[0.9em] rounded-md bg-[#f59e0b] text-white border-none transition-transform duration-200 hover:-translate-y-0.5 ${
+            archiving ? 'opacity-50 cursor-not-allowed' : 'cursor-pointer'
+          }`}
+          aria-label={`${archived ? 'Unarchive' : 'Archive'} ${issue.name} issue`}
+        >
+          📦 {archived ? 'Unarchive' : 'Archive'}
+        </button>
         <button
           onClick={handleDeleteClick}
           className="px-3 py-[6px] text-[0.9em] rounded-md bg-[#f44336] text-white border-none transition-transform duration-200 hover:-translate-y-0.5 cursor-pointer"
@@ -14,6 +14,10 @@ interface IssueMetadataHeaderProps {
   onSaveMetadata: () => void;
   onCreateNode: () => void;
   onSaveLayout: () => void;
+  onTidyLayout: () => void;
+  onAutoLayout: () => void;
+  onValidate: () => void;
+  onCleanUp: () => void;
   onClose: () => void;
   hasLayoutChanges: boolean;
 }
@@ -31,6 +35,10 @@ export const IssueMetadataHeader = memo(function IssueMetadataHeader({
   onSaveMetadata,
   onCreateNode,
   onSaveLayout,
+  onTidyLayout,
+  onAutoLayout,
+  onValidate,
+  onCleanUp,
   onClose,
   hasLayoutChanges,
 }: IssueMetadataHeaderProps) {
@@ -83,6 +91,42 @@ export const IssueMetadataHeader = memo(function IssueMetadataHeader({
           >
             ➕ New Node
           </button>
+          <button
+            onClick={onValidate}
+            disabled={loading}
+            className="px-4 py-2 rounded-md bg-gray-200 text-gray-700 border-none cursor-pointer transition-transform duration-200 hover:-translate-y-0.5 font-medium text-sm whitespace-nowrap"
+            aria-label="Check graph"
+            title="List dead ends, unreachable nodes, loops and other problems"
+          >
+            🩺 Check Graph
+          </button>
+          <button
+            onClick={onCleanUp}
+            disabled={loading}
+            className="px-4 py-2 rounded-md bg-gray-200 text-gray-700 border-none cursor-pointer transition-transform duration-200 hover:-translate-y-0.5 font-medium text-sm whitespace-nowrap"
+            aria-label="Clean up stranded nodes"
+            title="Find nodes nothing leads to and switch them off"
+          >
+            🧹 Clean Up
+          </button>
+          <button
+            onClick={onAutoLayout}
+            disabled={loading}
+            className="px-4 py-2 rounded-md bg-gray-200 text-gray-700 border-none cursor-pointer transition-transform duration-200 hover:-translate-y-0.5 font-medium text-sm whitespace-nowrap"
+            aria-label="Auto-layout graph"
+            title="Arrange all nodes in layers from the start question"
+          >
+            🪜 Auto Layout
+          </button>
+          <button
+            onClick={onTidyLayout}
+            disabled={loading}
+            className="px-4 py-2 rounded-md bg-gray-200 text-gray-700 border-none cursor-pointer transition-transform duration-200 hover:-translate-y-0.5 font-medium text-sm whitespace-nowrap"
+            aria-label="Tidy graph layout"
+            title="Snap nodes to the grid, separate overlapping nodes and place unpositioned ones"
+          >
+            📐 Tidy Layout
+          </button>
           <button
             onClick={onSaveLayout}
             disabled={loading}
@@ -0,0 +1,36 @@
+import { memo } from 'react';
+import { apiUrl } from '../lib/api';
+import type { NodeAttachment } from '../types/NodeAttachment';
+
+interface NodeAttachmentListProps {
+  attachments: NodeAttachment[];
+}
+
+const formatSize = (bytes: number) =>
+  bytes >= 1024 * 1024 ? `${(bytes / (1024 * 1024)).toFixed(1)} MB` : `${Math.max(1, Math.round(bytes / 1024))} KB`;
+
+/** Reference files attached to a node (manuals, wiring diagrams); links are signed and expire */
+export const NodeAttachmentList = memo(function NodeAttachmentList({ attachments }: NodeAttachmentListProps) {
+  if (attachments.length === 0) return null;
+
+  return (
+    <div className="mt-4">
+      <h4 className="text-sm font-semibold text-gray-700 mb-2">Reference files</h4>
+      <ul className="space-y-1 text-sm">
+        {attachments.map((attachment) => (
+          <li key={attachment.id}>
+            <a
+              href={apiUrl(attachment.url)}
+              target="_blank"
+              rel="noopener noreferrer"
+              className="underline text-[#667eea] hover:text-[#5568d3]"
+            >
+              {attachment.content_type.startsWith('image/') ? '🖼️' : '📄'} {attachment.filename}
+            </a>{' '}
+            <span className="text-gray-500">({formatSize(attachment.size_bytes)})</span>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+});
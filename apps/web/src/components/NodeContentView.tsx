@@ -0,0 +1,49 @@
+import { memo } from 'react';
+import type { NodeContent } from '../types';
+
+interface NodeContentViewProps {
+  content: NodeContent | null | undefined;
+}
+
+/**
+ * Rich node content: instructions, pictures and further reading.
+ * The server has already sanitized it; markdown is shown as written,
+ * keeping line breaks so numbered steps stay readable.
+ */
+export const NodeContentView = memo(function NodeContentView({ content }: NodeContentViewProps) {
+  if (!content) return null;
+
+  return (
+    <div className="mt-4 space-y-4">
+      {content.markdown && (
+        <div className="text-gray-800 whitespace-pre-wrap leading-relaxed">{content.markdown}</div>
+      )}
+
+      {content.images.map((image, index) => (
+        <figure key={`${image.url}-${index}`}>
+          <img src={image.url} alt={image.alt} className="max-w-full rounded-lg border border-gray-200" />
+          {image.caption && (
+            <figcaption className="text-sm text-gray-600 mt-1">{image.caption}</figcaption>
+          )}
+        </figure>
+      ))}
+
+      {content.links.length > 0 && (
+        <ul className="text-sm space-y-1">
+          {content.links.map((link, index) => (
+            <li key={`${link.url}-${index}`}>
+              <a
+                href={link.url}
+                target="_blank"
+                rel="noopener noreferrer"
+                className="font-semibold underline text-[#667eea] hover:text-[#5568d3]"
+              >
+                🔗 {link.label}
+              </a>
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+});
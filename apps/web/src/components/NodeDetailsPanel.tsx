@@ -1,6 +1,7 @@
-import { memo } from 'react';
-import { nodesAPI } from '../lib/api';
-import type { Node } from '../types';
+import { memo, useEffect, useState } from 'react';
+import { nodesAPI, uploadsAPI } from '../lib/api';
+import type { Node, NodeType } from '../types';
+import type { UploadInfo } from '../types/UploadInfo';
 import { getErrorMessage } from '../lib/errorUtils';
 
 interface NodeDetailsPanelProps {
@@ -10,6 +11,9 @@ interface NodeDetailsPanelProps {
   hasUnsavedChanges: boolean;
   onEditingTextChange: (text: string) => void;
   onSave: () => void;
+  onDuplicate: (nodeId: string) => void;
+  onTransferSubtree: (nodeId: string) => void;
+  onMerge: (nodeId: string) => void;
   onDelete: (nodeId: string) => void;
   onClose: () => void;
   onNodeTypeChange: () => Promise<void>;
@@ -23,15 +27,84 @@ export const NodeDetailsPanel = memo(function NodeDetailsPanel({
   hasUnsavedChanges,
   onEditingTextChange,
   onSave,
+  onDuplicate,
+  onTransferSubtree,
+  onMerge,
   onDelete,
   onClose,
   onNodeTypeChange,
   setError,
 }: NodeDetailsPanelProps) {
-  const handleNodeTypeChange = async (newType: 'Question' | 'Conclusion') => {
+  const TEXT_LABELS: Record<NodeType, string> = {
+    Question: 'Question Text',
+    Conclusion: 'Conclusion Text',
+    Subflow: 'Sub-flow Name',
+    Return: 'Outcome (matches an answer on the sub-flow node)',
+  };
+
+  // Markdown instructions are saved on their own, keeping the node's images and links
+  const [editingMarkdown, setEditingMarkdown] = useState('');
+  useEffect(() => {
+    setEditingMarkdown(selectedNode?.content?.markdown ?? '');
+  }, [selectedNode?.id, selectedNode?.content?.markdown]);
+
+  const handleSaveInstructions = async () => {
+    if (!selectedNode) return;
+    const content = selectedNode.content ?? { markdown: '', images: [], links: [] };
+    try {
+      await nodesAPI.update(selectedNode.id, { content: { ...content, markdown: editingMarkdown } });
+      await onNodeTypeChange();
+    } catch (err: unknown) {
+      setError(`Failed to save instructions: ${getErrorMessage(err)}`);
+    }
+  };
+
+  // Reference files technicians get with this node
+  const [attachments, setAttachments] = useState<UploadInfo[]>([]);
+  useEffect(() => {
+    if (!selectedNode) return;
+    let cancelled = false;
+    uploadsAPI
+      .listNodeAttachments(selectedNode.id)
+      .then((list) => {
+        if (!cancelled) setAttachments(list);
+      })
+      .catch((err: unknown) => setError(`Failed to load attachments: ${getErrorMessage(err)}`));
+    return () => {
+      cancelled = true;
+    };
+  }, [selectedNode?.id, setError]);
+
+  const handleAttach = async (file: File | undefined) => {
+    if (!selectedNode || !file) return;
+    try {
+      const upload = await uploadsAPI.attachToNode(selectedNode.id, file);
+      setAttachments((list) => [...list, upload]);
+    } catch (err: unknown) {
+      setError(`Failed to attach file: ${getErrorMessage(err)}`);
+    }
+  };
+
+  const handleDetach = async (upload: UploadInfo) => {
+    if (!selectedNode || !confirm(`Remove ${upload.filename} from this node?`)) return;
+    try {
+      await uploadsAPI.detachFromNode(selectedNode.id, upload.id);
+      setAttachments((list) => list.filter((a) => a.id !== upload.id));
+    } catch (err: unknown) {
+      setError(`Failed to remove attachment: ${getErrorMessage(err)}`);
+    }
+  };
+
+  const handleNodeTypeChange = async (newType: NodeType) => {
     if (!selectedNode || selectedNode.node_type === newType) return;
+    // Sub-flow nodes run another issue, so they need one named up front
+    let subflowCategory: string | undefined;
+    if (newType === 'Subflow') {
+      subflowCategory = prompt('Which issue category should this sub-flow run?')?.trim();
+      if (!subflowCategory) return;
+    }
     try {
-      await nodesAPI.update(selectedNode.id, { node_type: newType });
+      await nodesAPI.update(selectedNode.id, { node_type: newType, subflow_category: subflowCategory });
       await onNodeTypeChange();
     } catch (err: unknown) {
       setError(`Failed to change node type: ${getErrorMessage(err)}`);
@@ -85,12 +158,39 @@ export const NodeDetailsPanel = memo(function NodeDetailsPanel({
                   🎯 Conclusion
                 </button>
               </div>
+              <div className="flex gap-3 mt-2">
+                <button
+                  onClick={() => handleNodeTypeChange('Subflow')}
+                  className={`flex-1 px-4 py-2 rounded-md border-2 font-medium transition-all ${
+                    selectedNode.node_type === 'Subflow'
+                      ? 'border-purple-500 bg-purple-100 text-purple-800'
+                      : 'border-gray-300 bg-white text-gray-600 hover:border-purple-300'
+                  }`}
+                >
+                  🔁 Sub-flow
+                </button>
+                <button
+                  onClick={() => handleNodeTypeChange('Return')}
+                  className={`flex-1 px-4 py-2 rounded-md border-2 font-medium transition-all ${
+                    selectedNode.node_type === 'Return'
+                      ? 'border-orange-500 bg-orange-100 text-orange-800'
+                      : 'border-gray-300 bg-white text-gray-600 hover:border-orange-300'
+                  }`}
+                >
+                  ↩️ Return
+                </button>
+              </div>
+              {selectedNode.node_type === 'Subflow' && (
+                <p className="text-xs text-gray-600 mt-2">
+                  Runs <span className="font-mono">{selectedNode.subflow_category}</span>, then continues with this node&apos;s answers.
+                </p>
+              )}
             </div>
 
             {/* Node Text */}
             <div className="mb-4">
               <label className="block text-sm font-medium text-gray-700 mb-2">
-                {selectedNode.node_type === 'Question' ? 'Question Text' : 'Conclusion Text'}
+                {TEXT_LABELS[selectedNode.node_type]}
               </label>
               <textarea
                 value={editingText}
@@ -108,6 +208,65 @@ export const NodeDetailsPanel = memo(function NodeDetailsPanel({
               </p>
             </div>
 
+            {/* Rich Content */}
+            <div className="mb-4">
+              <label className="block text-sm font-medium text-gray-700 mb-2">
+                Instructions (markdown, optional)
+              </label>
+              <textarea
+                value={editingMarkdown}
+                onChange={(e) => setEditingMarkdown(e.target.value)}
+                rows={6}
+                className="w-full px-3 py-2 border border-gray-300 rounded-md resize-y font-mono text-sm"
+                placeholder={'1. Switch off the pump\n2. ...'}
+              />
+              {selectedNode.content && (
+                <p className="text-xs text-gray-500 mt-1">
+                  {selectedNode.content.images.length} image(s), {selectedNode.content.links.length} link(s)
+                </p>
+              )}
+              {editingMarkdown !== (selectedNode.content?.markdown ?? '') && (
+                <button
+                  onClick={handleSaveInstructions}
+                  className="mt-2 w-full px-3 py-2 rounded-md bg-gray-100 text-gray-700 font-medium hover:bg-gray-200"
+                >
+                  Save Instructions
+                </button>
+              )}
+            </div>
+
+            {/* Attachments */}
+            <div className="mb-4">
+              <label className="block text-sm font-medium text-gray-700 mb-2">
+                Reference Files
+              </label>
+              {attachments.length > 0 && (
+                <ul className="mb-2 space-y-1 text-sm">
+                  {attachments.map((upload) => (
+                    <li key={upload.id} className="flex items-center justify-between gap-2">
+                      <span className="truncate text-gray-800" title={upload.filename}>{upload.filename}</span>
+                      <button
+                        onClick={() => handleDetach(upload)}
+                        className="text-red-500 hover:text-red-700 text-xs font-medium"
+                        aria-label={`Remove ${upload.filename}`}
+                      >
+                        Remove
+                      </button>
+                    </li>
+                  ))}
+                </ul>
+              )}
+              <input
+                type="file"
+                accept="application/pdf,image/*"
+                onChange={(e) => {
+                  handleAttach(e.target.files?.[0]);
+                  e.target.value = '';
+                }}
+                className="w-full text-sm text-gray-600"
+              />
+            </div>
+
             {/* Node Metadata */}
             <div className="mb-4 p-3 bg-gray-50 border border-gray-200 rounded-md">
               <div className="text-xs space-y-1">
@@ -140,6 +299,30 @@ export const NodeDetailsPanel = memo(function NodeDetailsPanel({
               </div>
             )}
 
+            {/* Duplicate Button */}
+            <button
+              onClick={() => onDuplicate(selectedNode.id)}
+              className="w-full mb-2 px-3 py-2 rounded-md bg-gray-100 text-gray-700 font-medium hover:bg-gray-200"
+            >
+              Duplicate Node
+            </button>
+
+            {/* Subtree Button */}
+            <button
+              onClick={() => onTransferSubtree(selectedNode.id)}
+              className="w-full mb-2 px-3 py-2 rounded-md bg-gray-100 text-gray-700 font-medium hover:bg-gray-200"
+            >
+              Copy/Move Subtree…
+            </button>
+
+            {/* Merge Button */}
+            <button
+              onClick={() => onMerge(selectedNode.id)}
+              className="w-full mb-2 px-3 py-2 rounded-md bg-gray-100 text-gray-700 font-medium hover:bg-gray-200"
+            >
+              Merge Into Another Node…
+            </button>
+
             {/* Delete Button */}
             <button
               onClick={() => onDelete(selectedNode.id)}
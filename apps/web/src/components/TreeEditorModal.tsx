@@ -12,13 +12,13 @@ import ReactFlow, {
 } from 'reactflow';
 import 'reactflow/dist/style.css';
 import { issuesAPI, nodesAPI, connectionsAPI } from '../lib/api';
-import type { IssueGraph, UpdateNode, UpdateConnection } from '../types';
+import type { IssueGraph, NodePosition, NodeType, UpdateNode, UpdateConnection } from '../types';
 import { AccessibleAlert } from './AccessibleAlert';
 import { AccessibleConfirm } from './AccessibleConfirm';
 import { NodeDetailsPanel } from './NodeDetailsPanel';
 import { ConnectionDetailsPanel } from './ConnectionDetailsPanel';
 import { IssueMetadataHeader } from './IssueMetadataHeader';
-import { getErrorMessage } from '../lib/errorUtils';
+import { getErrorMessage, isAxiosError } from '../lib/errorUtils';
 import { logger } from '../lib/logger';
 
 interface TreeEditorModalProps {
@@ -28,6 +28,31 @@ interface TreeEditorModalProps {
   onSave?: () => void; // Optional - kept for backward compatibility but not used
 }
 
+const NODE_ICONS: Record<NodeType, string> = {
+  Question: '❓ ',
+  Conclusion: '🎯 ',
+  Subflow: '🔁 ',
+  Return: '↩️ ',
+};
+
+// Positions of the nodes that have moved since the graph was loaded
+function changedPositions(
+  nodes: IssueGraph['nodes'],
+  positions: Record<string, { x: number; y: number }>
+): NodePosition[] {
+  return nodes.flatMap((node) => {
+    const pos = positions[node.id];
+    if (!pos || (node.position_x === pos.x && node.position_y === pos.y)) return [];
+    return [{ id: node.id, position_x: pos.x, position_y: pos.y }];
+  });
+}
+
+// The server refuses answers that loop back (409); ask before keeping one anyway
+function confirmLoop(err: unknown): boolean {
+  if (!isAxiosError(err) || err.response?.status !== 409) return false;
+  return confirm(`${getErrorMessage(err)}\n\nTechnicians could go round this loop forever. Keep it anyway?`);
+}
+
 export default function TreeEditorModal({ category, issueName, onClose }: TreeEditorModalProps) {
   const [flowNodes, setFlowNodes, onFlowNodesChange] = useNodesState([]);
   const [flowEdges, setFlowEdges, onFlowEdgesChange] = useEdgesState([]);
@@ -132,15 +157,15 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
           label: (
             <div className="p-2">
               <div className="font-semibold text-sm">
-                {node.node_type === 'Conclusion' ? '🎯 ' : '❓ '}
+                {NODE_ICONS[node.node_type]}
                 {node.text.length > 60 ? node.text.substring(0, 60) + '...' : node.text}
               </div>
             </div>
           )
         },
         style: {
-          background: node.node_type === 'Conclusion' ? '#dcfce7' : '#fff',
-          border: '2px solid ' + (node.node_type === 'Conclusion' ? '#16a34a' : '#667eea'),
+          background: node.node_type === 'Conclusion' ? '#dcfce7' : node.node_type === 'Subflow' ? '#f3e8ff' : '#fff',
+          border: '2px solid ' + (node.node_type === 'Conclusion' ? '#16a34a' : node.node_type === 'Subflow' ? '#9333ea' : '#667eea'),
           borderRadius: '8px',
           width: 250,
         },
@@ -303,25 +328,23 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
         });
 
         // Update positions in database first
-        for (const node of graphData.nodes) {
-          const pos = nodePositions[node.id];
-          if (pos && (node.position_x !== pos.x || node.position_y !== pos.y)) {
-            await nodesAPI.update(node.id, {
-              position_x: pos.x,
-              position_y: pos.y,
-            });
-          }
-        }
+        await nodesAPI.updatePositions(changedPositions(graphData.nodes, nodePositions));
 
         // Count existing connections from source node for order_index
         const existingConnections = graphData.connections.filter(c => c.from_node_id === params.source);
 
-        await connectionsAPI.create({
+        const connection = {
           from_node_id: params.source,
           to_node_id: params.target,
           label: label.trim(),
           order_index: existingConnections.length,
-        });
+        };
+        try {
+          await connectionsAPI.create(connection);
+        } catch (err: unknown) {
+          if (!confirmLoop(err)) throw err;
+          await connectionsAPI.create(connection, true);
+        }
 
         // Save positions to localStorage as backup
         const layoutKey = `graph_layout_${category}`;
@@ -397,7 +420,12 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
       }
 
       if (Object.keys(connUpdates).length > 0) {
-        await connectionsAPI.update(selectedConnection.id, connUpdates);
+        try {
+          await connectionsAPI.update(selectedConnection.id, connUpdates);
+        } catch (err: unknown) {
+          if (!confirmLoop(err)) throw err;
+          await connectionsAPI.update(selectedConnection.id, connUpdates, true);
+        }
         await loadGraph();
         setHasUnsavedNodeChanges(false);
         setHasChanges(false);
@@ -430,6 +458,73 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
     });
   };
 
+  // Duplicate node, optionally with its answers
+  const handleDuplicateNode = async (nodeId: string) => {
+    const withConnections = confirm('Also copy this node\'s answers? (Cancel to copy the node alone)');
+    try {
+      const { node } = await nodesAPI.clone(nodeId, withConnections);
+      await loadGraph();
+      setSelectedNodeId(node.id);
+    } catch (err: unknown) {
+      setError(`Failed to duplicate node: ${getErrorMessage(err)}`);
+      logger.error('Failed to duplicate node', { nodeId, error: getErrorMessage(err) });
+    }
+  };
+
+  // Copy or move a node and everything after it into another issue
+  const handleTransferSubtree = async (nodeId: string) => {
+    const targetCategory = prompt('Copy or move this node and everything after it into which category?');
+    if (!targetCategory || targetCategory.trim() === '') return;
+    const mode = confirm(`Copy into "${targetCategory}"? (Cancel to move instead)`) ? 'copy' : 'move';
+
+    try {
+      const result = await nodesAPI.transferSubtree(nodeId, { target_category: targetCategory.trim(), mode });
+      await loadGraph();
+      if (mode === 'move') {
+        setSelectedNodeId(null);
+        setOpenPanel('none');
+      }
+      setAlertDialog({
+        isOpen: true,
+        title: mode === 'copy' ? 'Subtree Copied' : 'Subtree Moved',
+        message: `${result.node_ids.length} node(s) ${mode === 'copy' ? 'copied' : 'moved'} to "${result.target_category}".`,
+        type: 'success',
+      });
+    } catch (err: unknown) {
+      setError(`Failed to ${mode} subtree: ${getErrorMessage(err)}`);
+      logger.error('Failed to transfer subtree', { nodeId, targetCategory, mode, error: getErrorMessage(err) });
+    }
+  };
+
+  // Fold a duplicate into another node; its incoming answers move to the target
+  const handleMergeNode = async (nodeId: string) => {
+    const answer = prompt('Merge this node into which node? Enter its semantic ID or the start of its ID');
+    const key = answer?.trim();
+    if (!key) return;
+    const target = graphData?.nodes.find(
+      n => n.id !== nodeId && (n.semantic_id === key || n.id.startsWith(key.toLowerCase()))
+    );
+    if (!target) {
+      setError(`No other node matches "${key}"`);
+      return;
+    }
+
+    try {
+      const result = await nodesAPI.merge(nodeId, target.id);
+      await loadGraph();
+      setSelectedNodeId(target.id);
+      setAlertDialog({
+        isOpen: true,
+        title: 'Nodes Merged',
+        message: `${result.connections_rewired} answer(s) now lead to "${target.text}". The duplicate was deactivated.`,
+        type: 'success',
+      });
+    } catch (err: unknown) {
+      setError(`Failed to merge nodes: ${getErrorMessage(err)}`);
+      logger.error('Failed to merge nodes', { nodeId, targetId: target.id, error: getErrorMessage(err) });
+    }
+  };
+
   // Delete node
   const handleDeleteNode = async (nodeId: string) => {
     const node = graphData?.nodes.find(n => n.id === nodeId);
@@ -464,13 +559,6 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
     const text = prompt(`Enter ${nodeType} text:`);
     if (!text || text.trim() === '') return;
 
-    // Auto-generate semantic_id from text (hidden from user)
-    const semanticId = text
-      .toLowerCase()
-      .replace(/[^a-z0-9\s]/g, '')
-      .replace(/\s+/g, '_')
-      .substring(0, 50);
-
     try {
       // Save current node positions before creating new node
       if (graphData) {
@@ -483,15 +571,7 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
         });
 
         // Update positions in database first
-        for (const node of graphData.nodes) {
-          const pos = nodePositions[node.id];
-          if (pos && (node.position_x !== pos.x || node.position_y !== pos.y)) {
-            await nodesAPI.update(node.id, {
-              position_x: pos.x,
-              position_y: pos.y,
-            });
-          }
-        }
+        await nodesAPI.updatePositions(changedPositions(graphData.nodes, nodePositions));
 
         // Save positions to localStorage as backup
         const layoutKey = `graph_layout_${category}`;
@@ -502,7 +582,9 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
         category,
         node_type: nodeType,
         text,
-        semantic_id: semanticId || null,
+        // The server derives a semantic_id from the text that is free in this category
+        semantic_id: null,
+        generate_semantic_id: true,
         display_category: null,
         position_x: null,
         position_y: null,
@@ -550,16 +632,8 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
         };
       });
 
-      // Update node positions in the database
-      for (const node of graphData.nodes) {
-        const pos = nodePositions[node.id];
-        if (pos) {
-          await nodesAPI.update(node.id, {
-            position_x: pos.x,
-            position_y: pos.y,
-          });
-        }
-      }
+      // Update node positions in the database in one request
+      await nodesAPI.updatePositions(changedPositions(graphData.nodes, nodePositions));
 
       // Save layout positions to localStorage as backup
       const layoutKey = `graph_layout_${category}`;
@@ -581,6 +655,106 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
     }
   };
 
+  // Server-side layout; both replace unsaved moves
+  const applyServerLayout = async (mode: 'tidy' | 'auto') => {
+    try {
+      setError(null);
+      const result = mode === 'auto'
+        ? await issuesAPI.autoLayout(category)
+        : await issuesAPI.normalizeLayout(category);
+      localStorage.removeItem(`graph_layout_${category}`);
+      await loadGraph();
+      setHasChanges(false);
+      setAlertDialog({
+        isOpen: true,
+        title: mode === 'auto' ? 'Layout applied' : 'Layout tidied',
+        message: result.moved === 0
+          ? 'No nodes needed to move.'
+          : `${result.moved} node(s) moved (${result.placed} had no position).`,
+        type: 'success',
+      });
+    } catch (err: unknown) {
+      setError(`Failed to lay out graph: ${getErrorMessage(err)}`);
+      logger.error('Failed to apply server layout', { category, mode, error: getErrorMessage(err) });
+    }
+  };
+
+  const handleTidyLayout = () => applyServerLayout('tidy');
+
+  // Auto-layout rearranges every node, so ask first
+  const confirmAutoLayout = () => {
+    setConfirmDialog({
+      isOpen: true,
+      title: 'Auto layout',
+      message: 'Rearrange every node in layers from the start question? Your current arrangement will be replaced.',
+      variant: 'default',
+      onConfirm: () => applyServerLayout('auto'),
+    });
+  };
+
+  const handleValidate = async () => {
+    try {
+      setError(null);
+      const report = await issuesAPI.validate(category);
+      const errors = report.problems.filter(problem => problem.severity === 'error');
+      const warnings = report.problems.filter(problem => problem.severity === 'warning');
+      const list = (title: string, problems: typeof report.problems) =>
+        problems.length === 0 ? [] : [`${title}:`, ...problems.map(problem => `• ${problem.message}`)];
+      setAlertDialog({
+        isOpen: true,
+        title: errors.length > 0 ? 'Graph has errors' : 'Graph check',
+        message: report.problems.length === 0
+          ? 'No problems found.'
+          : [...list('Errors (block activation)', errors), ...list('Warnings', warnings)].join('\n'),
+        type: errors.length > 0 ? 'error' : warnings.length > 0 ? 'info' : 'success',
+      });
+    } catch (err: unknown) {
+      setError(`Failed to check graph: ${getErrorMessage(err)}`);
+      logger.error('Failed to validate graph', { category, error: getErrorMessage(err) });
+    }
+  };
+
+  // Stranded nodes are switched off rather than deleted, so they can be linked up again
+  const handleCleanUp = async () => {
+    try {
+      setError(null);
+      const report = await issuesAPI.orphans(category);
+      const active = report.nodes.filter(node => node.is_active);
+      if (active.length === 0) {
+        setAlertDialog({
+          isOpen: true,
+          title: 'Clean up',
+          message: report.nodes.length === 0
+            ? 'Every node can be reached.'
+            : `${report.nodes.length} stranded node(s), all already switched off.`,
+          type: 'success',
+        });
+        return;
+      }
+      setConfirmDialog({
+        isOpen: true,
+        title: 'Switch off stranded nodes',
+        message: `Nothing leads to these nodes:\n${active.map(node => `• ${node.text}`).join('\n')}\n\nSwitch them off?`,
+        variant: 'default',
+        onConfirm: async () => {
+          try {
+            await issuesAPI.cleanupOrphans(category, {
+              action: 'deactivate',
+              node_ids: active.map(node => node.id),
+            });
+            await loadGraph();
+          } catch (err: unknown) {
+            setError(`Failed to clean up nodes: ${getErrorMessage(err)}`);
+            logger.error('Failed to clean up stranded nodes', { category, error: getErrorMessage(err) });
+          }
+        },
+      });
+    } catch (err: unknown) {
+      setError(`Failed to find stranded nodes: ${getErrorMessage(err)}`);
+      logger.error('Failed to list stranded nodes', { category, error: getErrorMessage(err) });
+    }
+  };
+
   if (loading) {
     return (
       <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
@@ -613,6 +787,10 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
         onSaveMetadata={handleSaveIssue}
         onCreateNode={handleCreateNode}
         onSaveLayout={handleSave}
+        onTidyLayout={handleTidyLayout}
+        onAutoLayout={confirmAutoLayout}
+        onValidate={handleValidate}
+        onCleanUp={handleCleanUp}
         onClose={handleClose}
         hasLayoutChanges={hasChanges}
       />
@@ -637,6 +815,9 @@ export default function TreeEditorModal({ category, issueName, onClose }: TreeEd
             setHasUnsavedNodeChanges(true);
           }}
           onSave={handleSaveNode}
+          onDuplicate={handleDuplicateNode}
+          onTransferSubtree={handleTransferSubtree}
+          onMerge={handleMergeNode}
           onDelete={handleDeleteNode}
           onClose={() => setOpenPanel('none')}
           onNodeTypeChange={loadGraph}
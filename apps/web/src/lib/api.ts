@@ -1,15 +1,67 @@
 import axios from 'axios';
 import type { LoginRequest, LoginResponse, UserInfo } from '../types';
+import type { BrandingSettings } from '../types/BrandingSettings';
+import type { CategoryNotice } from '../types/CategoryNotice';
+import type { ChangePasswordRequest } from '../types/ChangePasswordRequest';
+import type { CreateEntryPointRequest } from '../types/CreateEntryPointRequest';
+import type { CreateNoticeRequest } from '../types/CreateNoticeRequest';
+import type { CreateServiceAccountRequest } from '../types/CreateServiceAccountRequest';
+import type { CreateServiceTokenRequest } from '../types/CreateServiceTokenRequest';
+import type { DeviceInfo } from '../types/DeviceInfo';
+import type { DeviceSession } from '../types/DeviceSession';
+import type { EntryPoint } from '../types/EntryPoint';
+import type { ForgotPasswordRequest } from '../types/ForgotPasswordRequest';
+import type { IssuedDeviceToken } from '../types/IssuedDeviceToken';
+import type { IssuedServiceToken } from '../types/IssuedServiceToken';
+import type { JwtKeyInfo } from '../types/JwtKeyInfo';
+import type { JwtKeyRotationResponse } from '../types/JwtKeyRotationResponse';
+import type { LegacyMigrationReport } from '../types/LegacyMigrationReport';
+import type { NodeRevision } from '../types/NodeRevision';
+import type { PublicConfig } from '../types/PublicConfig';
+import type { RegisterDeviceRequest } from '../types/RegisterDeviceRequest';
+import type { ResetPasswordRequest } from '../types/ResetPasswordRequest';
+import type { RolePermissions } from '../types/RolePermissions';
+import type { ServiceAccount } from '../types/ServiceAccount';
+import type { ServiceTokenInfo } from '../types/ServiceTokenInfo';
+import type { SessionChange } from '../types/SessionChange';
+import type { SettingInfo } from '../types/SettingInfo';
+import type { UpdateBrandingRequest } from '../types/UpdateBrandingRequest';
+import type { UpdateEntryPointRequest } from '../types/UpdateEntryPointRequest';
+import type { UpdateNoticeRequest } from '../types/UpdateNoticeRequest';
+import type { UpdateProfileRequest } from '../types/UpdateProfileRequest';
+import type { UpdateRolePermissionsRequest } from '../types/UpdateRolePermissionsRequest';
+import type { UpdateUserCategoriesRequest } from '../types/UpdateUserCategoriesRequest';
+import type { UserCategoryAccess } from '../types/UserCategoryAccess';
+import type { UserRole } from '../types/UserRole';
+import type { UploadInfo } from '../types/UploadInfo';
+import type { UploadOwnerType } from '../types/UploadOwnerType';
 import type {
   StartSessionRequest,
   StartSessionResponse,
   SubmitAnswerRequest,
   SubmitAnswerResponse,
+  HeartbeatResponse,
+  GotoStepRequest,
+  ConclusionFeedbackRequest,
+  ConclusionFeedbackResponse,
+  AbandonSessionRequest,
+  AbandonSessionResponse,
+  SkipToConclusionRequest,
+  StepNoteRequest,
+  StepNoteResponse,
+  ResumeSessionResponse,
   SessionHistoryResponse,
+  StepAttachment,
   SessionsListResponse,
   DashboardStats,
   AuditLogsResponse,
   DeleteSessionsResponse,
+  ArchiveSessionsRequest,
+  ArchiveSessionsResponse,
+  ArchivedSession,
+  SessionDetail,
+  MergeSessionsRequest,
+  MergeSessionsResponse,
   CategoryListResponse,
   RenameCategoryRequest,
   CategoryUpdateResponse,
@@ -20,11 +72,42 @@ import type {
   UpdateIssueRequest,
   IssueExportData,
   ImportResult,
+  BundleImportResult,
+  BulkToggleRequest,
+  BulkToggleResult,
+  BulkIssueRequest,
+  BulkIssueResult,
+  RecomputeWeightsResult,
+  LayoutResult,
+  IssueChecksum,
+  GraphValidationReport,
+  OrphanReport,
+  OrphanCleanupRequest,
+  OrphanCleanupResult,
+  PurgeInactiveResult,
+  IssueDraft,
+  DraftPublished,
+  IssueVersion,
+  IssueVersionDiff,
+  IssueRolledBack,
+  IssueArchive,
+  IssueSlugRenamed,
+  ApprovalStatus,
+  IssueApproval,
+  ApprovalPublished,
+  SimulateRequest,
+  SimulationResult,
 } from '../types/issues';
 import type {
   Node,
   CreateNode,
   UpdateNode,
+  NodePosition,
+  NodePositionsUpdated,
+  ClonedNode,
+  TransferSubtree,
+  SubtreeTransferred,
+  NodesMerged,
   Connection,
   CreateConnection,
   UpdateConnection,
@@ -53,6 +136,9 @@ const getApiBaseUrl = () => {
 
 const API_BASE_URL = getApiBaseUrl();
 
+// Absolute URL for a server path such as a signed download link
+export const apiUrl = (path: string) => `${API_BASE_URL}${path}`;
+
 const api = axios.create({
   baseURL: API_BASE_URL,
   headers: {
@@ -66,16 +152,69 @@ api.interceptors.request.use((config) => {
   if (token) {
     config.headers.Authorization = `Bearer ${token}`;
   }
+  // Set once on a registered kiosk
+  const deviceToken = localStorage.getItem('device_token');
+  if (deviceToken) {
+    config.headers['X-Device-Token'] = deviceToken;
+  }
   return config;
 });
 
-// Response interceptor to handle errors
+export function storeSession(response: LoginResponse): void {
+  localStorage.setItem('token', response.token);
+  localStorage.setItem('refresh_token', response.refresh_token);
+  localStorage.setItem('user', JSON.stringify(response.user));
+}
+
+export function clearSession(): void {
+  localStorage.removeItem('token');
+  localStorage.removeItem('refresh_token');
+  localStorage.removeItem('user');
+}
+
+// Refresh tokens are single use, so concurrent 401s share one refresh request
+let refreshInFlight: Promise<string> | null = null;
+
+async function refreshAccessToken(): Promise<string> {
+  const refreshToken = localStorage.getItem('refresh_token');
+  if (!refreshToken) {
+    throw new Error('No refresh token');
+  }
+
+  try {
+    const { data } = await axios.post<LoginResponse>(`${API_BASE_URL}/api/v1/auth/refresh`, {
+      refresh_token: refreshToken,
+    });
+    storeSession(data);
+    return data.token;
+  } catch (err) {
+    // Another tab may have rotated the token first; use its result
+    const latest = localStorage.getItem('refresh_token');
+    if (latest && latest !== refreshToken) {
+      return localStorage.getItem('token') ?? Promise.reject(err);
+    }
+    throw err;
+  }
+}
+
+// Response interceptor: refresh the access token once on 401, otherwise sign out
 api.interceptors.response.use(
   (response) => response,
-  (error) => {
-    if (error.response?.status === 401) {
-      localStorage.removeItem('token');
-      window.location.href = '/login';
+  async (error) => {
+    const original = error.config;
+    if (error.response?.status === 401 && original && !original._retried) {
+      original._retried = true;
+      try {
+        refreshInFlight = refreshInFlight ?? refreshAccessToken().finally(() => {
+          refreshInFlight = null;
+        });
+        const token = await refreshInFlight;
+        original.headers.Authorization = `Bearer ${token}`;
+        return api(original);
+      } catch {
+        clearSession();
+        window.location.href = '/login';
+      }
     }
     return Promise.reject(error);
   }
@@ -91,6 +230,49 @@ export const authAPI = {
     const { data } = await api.get<UserInfo>('/api/v1/auth/me');
     return data;
   },
+
+  updateMe: async (req: UpdateProfileRequest): Promise<UserInfo> => {
+    const { data } = await api.patch<UserInfo>('/api/v1/auth/me', req);
+    return data;
+  },
+
+  logout: async (): Promise<void> => {
+    const refreshToken = localStorage.getItem('refresh_token');
+    if (refreshToken) {
+      await api.post('/api/v1/auth/logout', { refresh_token: refreshToken });
+    }
+  },
+
+  listDeviceSessions: async (): Promise<DeviceSession[]> => {
+    const { data } = await api.get<DeviceSession[]>('/api/v1/auth/sessions');
+    return data;
+  },
+
+  revokeDeviceSession: async (id: string): Promise<void> => {
+    await api.delete(`/api/v1/auth/sessions/${id}`);
+  },
+
+  changePassword: async (req: ChangePasswordRequest): Promise<{ message: string }> => {
+    const { data } = await api.post<{ message: string }>('/api/v1/auth/change-password', req);
+    return data;
+  },
+
+  forgotPassword: async (req: ForgotPasswordRequest): Promise<{ message: string }> => {
+    const { data } = await api.post<{ message: string }>('/api/v1/auth/forgot-password', req);
+    return data;
+  },
+
+  resetPassword: async (req: ResetPasswordRequest): Promise<{ message: string }> => {
+    const { data } = await api.post<{ message: string }>('/api/v1/auth/reset-password', req);
+    return data;
+  },
+};
+
+export const configAPI = {
+  getPublic: async (): Promise<PublicConfig> => {
+    const { data } = await api.get<PublicConfig>('/api/v1/config/public');
+    return data;
+  },
 };
 
 export const troubleshootAPI = {
@@ -112,10 +294,85 @@ export const troubleshootAPI = {
     return data;
   },
 
+  resume: async (code: string): Promise<ResumeSessionResponse> => {
+    const { data } = await api.get<ResumeSessionResponse>(`/api/v1/troubleshoot/resume/${encodeURIComponent(code)}`);
+    return data;
+  },
+
   getHistory: async (sessionId: string): Promise<SessionHistoryResponse> => {
     const { data } = await api.get<SessionHistoryResponse>(`/api/v1/troubleshoot/${sessionId}/history`);
     return data;
   },
+
+  heartbeat: async (sessionId: string): Promise<HeartbeatResponse> => {
+    const { data } = await api.post<HeartbeatResponse>(`/api/v1/troubleshoot/${sessionId}/heartbeat`);
+    return data;
+  },
+
+  goBack: async (sessionId: string): Promise<SubmitAnswerResponse> => {
+    const { data } = await api.post<SubmitAnswerResponse>(`/api/v1/troubleshoot/${sessionId}/back`);
+    return data;
+  },
+
+  followUp: async (sessionId: string): Promise<SubmitAnswerResponse> => {
+    const { data } = await api.post<SubmitAnswerResponse>(`/api/v1/troubleshoot/${sessionId}/follow-up`);
+    return data;
+  },
+
+  skipToConclusion: async (sessionId: string, nodeId: string): Promise<SubmitAnswerResponse> => {
+    const request: SkipToConclusionRequest = { node_id: nodeId };
+    const { data } = await api.post<SubmitAnswerResponse>(`/api/v1/troubleshoot/${sessionId}/skip`, request);
+    return data;
+  },
+
+  gotoStep: async (sessionId: string, stepIndex: number): Promise<SubmitAnswerResponse> => {
+    const request: GotoStepRequest = { step_index: stepIndex };
+    const { data } = await api.post<SubmitAnswerResponse>(`/api/v1/troubleshoot/${sessionId}/goto`, request);
+    return data;
+  },
+
+  submitFeedback: async (sessionId: string, resolved: boolean, comment?: string): Promise<ConclusionFeedbackResponse> => {
+    const request: ConclusionFeedbackRequest = { resolved, comment };
+    const { data } = await api.post<ConclusionFeedbackResponse>(`/api/v1/troubleshoot/${sessionId}/feedback`, request);
+    return data;
+  },
+
+  // An empty note removes it
+  setStepNote: async (sessionId: string, stepIndex: number, note: string): Promise<StepNoteResponse> => {
+    const request: StepNoteRequest = { note };
+    const { data } = await api.post<StepNoteResponse>(`/api/v1/troubleshoot/${sessionId}/steps/${stepIndex}/note`, request);
+    return data;
+  },
+
+  abandon: async (sessionId: string, reason?: string): Promise<AbandonSessionResponse> => {
+    const request: AbandonSessionRequest = { reason };
+    const { data } = await api.post<AbandonSessionResponse>(`/api/v1/troubleshoot/${sessionId}/abandon`, request);
+    return data;
+  },
+
+  // Without stepIndex the photo belongs to the question on screen
+  uploadAttachment: async (sessionId: string, file: File, stepIndex?: number): Promise<StepAttachment> => {
+    const form = new FormData();
+    form.append('file', file);
+    if (stepIndex !== undefined) {
+      form.append('step_index', String(stepIndex));
+    }
+    const { data } = await api.post<StepAttachment>(`/api/v1/troubleshoot/${sessionId}/attachments`, form, {
+      headers: { 'Content-Type': 'multipart/form-data' },
+    });
+    return data;
+  },
+
+  // Calls onChange whenever the session moves; close the returned source to stop.
+  // A 'lagged' event means changes were missed, so the session is refetched.
+  watchSession: (sessionId: string, onChange: (change: SessionChange | null) => void): EventSource => {
+    const source = new EventSource(`${API_BASE_URL}/api/v1/troubleshoot/${sessionId}/events`);
+    for (const kind of ['answered', 'completed', 'rewound', 'abandoned']) {
+      source.addEventListener(kind, (event) => onChange(JSON.parse((event as MessageEvent).data) as SessionChange));
+    }
+    source.addEventListener('lagged', () => onChange(null));
+    return source;
+  },
 };
 
 export const adminAPI = {
@@ -144,6 +401,28 @@ export const adminAPI = {
     return data;
   },
 
+  archiveSessions: async (olderThanDays: number): Promise<ArchiveSessionsResponse> => {
+    const request: ArchiveSessionsRequest = { older_than_days: olderThanDays };
+    const { data } = await api.post<ArchiveSessionsResponse>('/api/v1/admin/sessions/archive', request);
+    return data;
+  },
+
+  mergeSessions: async (sessionId: string, sourceSessionId: string): Promise<MergeSessionsResponse> => {
+    const request: MergeSessionsRequest = { source_session_id: sourceSessionId };
+    const { data } = await api.post<MergeSessionsResponse>(`/api/v1/admin/sessions/${encodeURIComponent(sessionId)}/merge`, request);
+    return data;
+  },
+
+  getSession: async (sessionId: string): Promise<SessionDetail> => {
+    const { data } = await api.get<SessionDetail>(`/api/v1/admin/sessions/${encodeURIComponent(sessionId)}`);
+    return data;
+  },
+
+  getArchivedSession: async (sessionId: string): Promise<ArchivedSession> => {
+    const { data } = await api.get<ArchivedSession>(`/api/v1/admin/sessions/archive/${encodeURIComponent(sessionId)}`);
+    return data;
+  },
+
   getCategories: async (): Promise<CategoryListResponse> => {
     const { data } = await api.get<CategoryListResponse>('/api/v1/admin/categories');
     return data;
@@ -159,17 +438,131 @@ export const adminAPI = {
     const { data } = await api.delete<CategoryUpdateResponse>(`/api/v1/admin/categories/${encodeURIComponent(name)}`);
     return data;
   },
+
+  getRolePermissions: async (): Promise<RolePermissions[]> => {
+    const { data } = await api.get<RolePermissions[]>('/api/v1/admin/roles/permissions');
+    return data;
+  },
+
+  updateRolePermissions: async (role: UserRole, request: UpdateRolePermissionsRequest): Promise<RolePermissions> => {
+    const { data } = await api.put<RolePermissions>(`/api/v1/admin/roles/${role}/permissions`, request);
+    return data;
+  },
+
+  getUserCategories: async (userId: string): Promise<UserCategoryAccess> => {
+    const { data } = await api.get<UserCategoryAccess>(`/api/v1/admin/users/${userId}/categories`);
+    return data;
+  },
+
+  updateUserCategories: async (userId: string, request: UpdateUserCategoriesRequest): Promise<UserCategoryAccess> => {
+    const { data } = await api.put<UserCategoryAccess>(`/api/v1/admin/users/${userId}/categories`, request);
+    return data;
+  },
+
+  listServiceAccounts: async (): Promise<ServiceAccount[]> => {
+    const { data } = await api.get<ServiceAccount[]>('/api/v1/admin/service-accounts');
+    return data;
+  },
+
+  createServiceAccount: async (request: CreateServiceAccountRequest): Promise<ServiceAccount> => {
+    const { data } = await api.post<ServiceAccount>('/api/v1/admin/service-accounts', request);
+    return data;
+  },
+
+  createServiceToken: async (accountId: string, request: CreateServiceTokenRequest): Promise<IssuedServiceToken> => {
+    const { data } = await api.post<IssuedServiceToken>(`/api/v1/admin/service-accounts/${accountId}/tokens`, request);
+    return data;
+  },
+
+  revokeServiceToken: async (tokenId: string): Promise<ServiceTokenInfo> => {
+    const { data } = await api.delete<ServiceTokenInfo>(`/api/v1/admin/service-tokens/${tokenId}`);
+    return data;
+  },
+
+  listDevices: async (): Promise<DeviceInfo[]> => {
+    const { data } = await api.get<DeviceInfo[]>('/api/v1/admin/devices');
+    return data;
+  },
+
+  registerDevice: async (request: RegisterDeviceRequest): Promise<IssuedDeviceToken> => {
+    const { data } = await api.post<IssuedDeviceToken>('/api/v1/admin/devices', request);
+    return data;
+  },
+
+  setDeviceDisabled: async (deviceId: string, disabled: boolean): Promise<DeviceInfo> => {
+    const { data } = await api.post<DeviceInfo>(`/api/v1/admin/devices/${deviceId}/${disabled ? 'disable' : 'enable'}`);
+    return data;
+  },
+
+  listJwtKeys: async (): Promise<JwtKeyInfo[]> => {
+    const { data } = await api.get<JwtKeyInfo[]>('/api/v1/admin/jwt-keys');
+    return data;
+  },
+
+  rotateJwtKey: async (): Promise<JwtKeyRotationResponse> => {
+    const { data } = await api.post<JwtKeyRotationResponse>('/api/v1/admin/jwt-keys/rotate');
+    return data;
+  },
+
+  getBranding: async (): Promise<BrandingSettings> => {
+    const { data } = await api.get<BrandingSettings>('/api/v1/admin/branding');
+    return data;
+  },
+
+  updateBranding: async (request: UpdateBrandingRequest): Promise<BrandingSettings> => {
+    const { data } = await api.put<BrandingSettings>('/api/v1/admin/branding', request);
+    return data;
+  },
+
+  listSettings: async (): Promise<SettingInfo[]> => {
+    const { data } = await api.get<SettingInfo[]>('/api/v1/admin/settings');
+    return data;
+  },
+
+  updateSetting: async (key: string, value: number): Promise<SettingInfo> => {
+    const { data } = await api.put<SettingInfo>(`/api/v1/admin/settings/${key}`, { value });
+    return data;
+  },
+
+  resetSetting: async (key: string): Promise<SettingInfo> => {
+    const { data } = await api.delete<SettingInfo>(`/api/v1/admin/settings/${key}`);
+    return data;
+  },
+
+  migrateLegacyTree: async (dryRun: boolean): Promise<LegacyMigrationReport> => {
+    const { data } = await api.post<LegacyMigrationReport>('/api/v1/admin/migrate/legacy', { dry_run: dryRun });
+    return data;
+  },
 };
 
+/** Nodes requested per chunk when loading an issue graph */
+const GRAPH_CHUNK_SIZE = 500;
+
 export const issuesAPI = {
-  list: async (): Promise<Issue[]> => {
-    const { data } = await api.get<Issue[]>('/api/v1/admin/issues');
+  list: async (params?: {
+    archived?: boolean;
+    tag?: string;
+    equipment_model?: string;
+    owner_id?: string;
+  }): Promise<Issue[]> => {
+    const { data } = await api.get<Issue[]>('/api/v1/admin/issues', { params });
     return data;
   },
 
   getGraph: async (category: string): Promise<IssueGraph> => {
-    const { data } = await api.get<IssueGraph>(`/api/v1/admin/issues/${category}/graph`);
-    return data;
+    // Fetch the graph in node chunks and stitch them together so very large
+    // categories stay under proxy response size limits
+    const graph: IssueGraph = { category, nodes: [], connections: [], next_cursor: null };
+    let cursor: string | null = null;
+    do {
+      const params = new URLSearchParams({ limit: String(GRAPH_CHUNK_SIZE) });
+      if (cursor) params.set('cursor', cursor);
+      const { data } = await api.get<IssueGraph>(`/api/v1/admin/issues/${category}/graph?${params}`);
+      graph.nodes.push(...data.nodes);
+      graph.connections.push(...data.connections);
+      cursor = data.next_cursor;
+    } while (cursor);
+    return graph;
   },
 
   create: async (request: CreateIssueRequest): Promise<Issue> => {
@@ -188,6 +581,158 @@ export const issuesAPI = {
     return data;
   },
 
+  bulkToggle: async (request: BulkToggleRequest): Promise<BulkToggleResult> => {
+    const { data } = await api.patch<BulkToggleResult>('/api/v1/admin/issues/bulk-toggle', request);
+    return data;
+  },
+
+  bulk: async (request: BulkIssueRequest): Promise<BulkIssueResult> => {
+    const { data } = await api.post<BulkIssueResult>('/api/v1/admin/issues/bulk', request);
+    return data;
+  },
+
+  recomputeWeights: async (
+    category: string,
+    params?: { window_days?: number; min_samples?: number }
+  ): Promise<RecomputeWeightsResult> => {
+    const { data } = await api.post<RecomputeWeightsResult>(
+      `/api/v1/admin/issues/${category}/connection-weights/recompute`,
+      null,
+      { params }
+    );
+    return data;
+  },
+
+  autoLayout: async (category: string): Promise<LayoutResult> => {
+    const { data } = await api.post<LayoutResult>(`/api/v1/admin/issues/${category}/layout`);
+    return data;
+  },
+
+  normalizeLayout: async (category: string): Promise<LayoutResult> => {
+    const { data } = await api.post<LayoutResult>(`/api/v1/admin/issues/${category}/layout/normalize`);
+    return data;
+  },
+
+  validate: async (category: string): Promise<GraphValidationReport> => {
+    const { data } = await api.get<GraphValidationReport>(`/api/v1/admin/issues/${category}/validate`);
+    return data;
+  },
+
+  orphans: async (category: string): Promise<OrphanReport> => {
+    const { data } = await api.get<OrphanReport>(`/api/v1/admin/issues/${category}/orphans`);
+    return data;
+  },
+
+  cleanupOrphans: async (category: string, request: OrphanCleanupRequest): Promise<OrphanCleanupResult> => {
+    const { data } = await api.post<OrphanCleanupResult>(`/api/v1/admin/issues/${category}/orphans/cleanup`, request);
+    return data;
+  },
+
+  // Permanently delete nodes and answers switched off more than olderThanDays ago
+  purgeInactive: async (category: string, olderThanDays: number): Promise<PurgeInactiveResult> => {
+    const { data } = await api.post<PurgeInactiveResult>(`/api/v1/admin/issues/${category}/purge`, {
+      older_than_days: olderThanDays,
+    });
+    return data;
+  },
+
+  getDraft: async (category: string): Promise<IssueDraft> => {
+    const { data } = await api.get<IssueDraft>(`/api/v1/admin/issues/${category}/draft`);
+    return data;
+  },
+
+  createDraft: async (category: string): Promise<IssueDraft> => {
+    const { data } = await api.post<IssueDraft>(`/api/v1/admin/issues/${category}/draft`);
+    return data;
+  },
+
+  discardDraft: async (category: string): Promise<void> => {
+    await api.delete(`/api/v1/admin/issues/${category}/draft`);
+  },
+
+  publishDraft: async (category: string, force = false): Promise<DraftPublished> => {
+    const { data } = await api.post<DraftPublished>(`/api/v1/admin/issues/${category}/publish`, null, {
+      params: { force },
+    });
+    return data;
+  },
+
+  listApprovals: async (params?: { category?: string; status?: ApprovalStatus }): Promise<IssueApproval[]> => {
+    const { data } = await api.get<IssueApproval[]>('/api/v1/admin/issues/approvals', { params });
+    return data;
+  },
+
+  submitForApproval: async (category: string, comment?: string): Promise<IssueApproval> => {
+    const { data } = await api.post<IssueApproval>(`/api/v1/admin/issues/${category}/approval`, { comment });
+    return data;
+  },
+
+  withdrawApproval: async (category: string): Promise<IssueApproval> => {
+    const { data } = await api.delete<IssueApproval>(`/api/v1/admin/issues/${category}/approval`);
+    return data;
+  },
+
+  approve: async (category: string, comment?: string, force = false): Promise<ApprovalPublished> => {
+    const { data } = await api.post<ApprovalPublished>(
+      `/api/v1/admin/issues/${category}/approval/approve`,
+      { comment },
+      { params: { force } }
+    );
+    return data;
+  },
+
+  reject: async (category: string, comment: string): Promise<IssueApproval> => {
+    const { data } = await api.post<IssueApproval>(`/api/v1/admin/issues/${category}/approval/reject`, { comment });
+    return data;
+  },
+
+  archive: async (category: string): Promise<IssueArchive> => {
+    const { data } = await api.post<IssueArchive>(`/api/v1/admin/issues/${category}/archive`);
+    return data;
+  },
+
+  unarchive: async (category: string): Promise<void> => {
+    await api.post(`/api/v1/admin/issues/${category}/unarchive`);
+  },
+
+  renameSlug: async (category: string, newCategory: string): Promise<IssueSlugRenamed> => {
+    const { data } = await api.put<IssueSlugRenamed>(`/api/v1/admin/issues/${category}/rename-slug`, {
+      new_category: newCategory,
+    });
+    return data;
+  },
+
+  listVersions: async (category: string): Promise<IssueVersion[]> => {
+    const { data } = await api.get<IssueVersion[]>(`/api/v1/admin/issues/${category}/versions`);
+    return data;
+  },
+
+  diffVersions: async (category: string, from: number, to: number): Promise<IssueVersionDiff> => {
+    const { data } = await api.get<IssueVersionDiff>(`/api/v1/admin/issues/${category}/versions/diff`, {
+      params: { from, to },
+    });
+    return data;
+  },
+
+  rollbackVersion: async (category: string, version: number): Promise<IssueRolledBack> => {
+    const { data } = await api.post<IssueRolledBack>(
+      `/api/v1/admin/issues/${category}/versions/${version}/rollback`
+    );
+    return data;
+  },
+
+  checksum: async (category: string, ignoreLayout = false): Promise<IssueChecksum> => {
+    const { data } = await api.get<IssueChecksum>(`/api/v1/admin/issues/${category}/checksum`, {
+      params: ignoreLayout ? { ignore_layout: true } : undefined,
+    });
+    return data;
+  },
+
+  simulate: async (category: string, request: SimulateRequest): Promise<SimulationResult> => {
+    const { data } = await api.post<SimulationResult>(`/api/v1/admin/issues/${category}/simulate`, request);
+    return data;
+  },
+
   delete: async (category: string, deleteSessions?: boolean): Promise<void> => {
     const params = deleteSessions ? '?delete_sessions=true' : '';
     await api.delete(`/api/v1/admin/issues/${category}${params}`);
@@ -198,24 +743,83 @@ export const issuesAPI = {
     return data;
   },
 
+  exportCsv: async (category: string): Promise<string> => {
+    const { data } = await api.get<string>(`/api/v1/admin/issues/${category}/export`, {
+      params: { format: 'csv' },
+      responseType: 'text',
+    });
+    return data;
+  },
+
+  exportDiagram: async (category: string, format: 'dot' | 'mermaid'): Promise<string> => {
+    const { data } = await api.get<string>(`/api/v1/admin/issues/${category}/export`, {
+      params: { format },
+      responseType: 'text',
+    });
+    return data;
+  },
+
+  exportBundle: async (category: string): Promise<Blob> => {
+    const { data } = await api.get<Blob>(`/api/v1/admin/issues/${category}/export`, {
+      params: { format: 'zip' },
+      responseType: 'blob',
+    });
+    return data;
+  },
+
   exportAll: async (): Promise<IssueExportData[]> => {
     const { data } = await api.get<IssueExportData[]>('/api/v1/admin/issues/export-all');
     return data;
   },
 
-  importIssues: async (issues: IssueExportData[]): Promise<ImportResult> => {
-    const { data } = await api.post<ImportResult>('/api/v1/admin/issues/import', issues);
+  importIssues: async (
+    issues: IssueExportData[],
+    duplicates: 'reject' | 'dedupe' = 'reject',
+    mode: 'skip' | 'overwrite' | 'merge' = 'skip'
+  ): Promise<ImportResult> => {
+    const { data } = await api.post<ImportResult>('/api/v1/admin/issues/import', issues, {
+      params: { duplicates, mode },
+    });
+    return data;
+  },
+
+  importBundle: async (
+    bundle: Blob,
+    duplicates: 'reject' | 'dedupe' = 'reject',
+    mode: 'skip' | 'overwrite' | 'merge' = 'skip'
+  ): Promise<BundleImportResult> => {
+    const { data } = await api.post<BundleImportResult>('/api/v1/admin/issues/import/bundle', bundle, {
+      params: { duplicates, mode },
+      headers: { 'Content-Type': 'application/zip' },
+    });
+    return data;
+  },
+
+  importExternal: async (
+    format: 'opml' | 'lucidchart-csv' | 'csv',
+    category: string,
+    content: string,
+    name?: string
+  ): Promise<ImportResult> => {
+    const { data } = await api.post<ImportResult>('/api/v1/admin/issues/import/external', content, {
+      params: { format, category, name },
+      headers: { 'Content-Type': 'text/plain' },
+    });
     return data;
   },
 };
 
 export const nodesAPI = {
-  list: async (category?: string, nodeType?: string): Promise<Node[]> => {
-    const params = new URLSearchParams();
-    if (category) params.append('category', category);
-    if (nodeType) params.append('node_type', nodeType);
-
-    const { data } = await api.get<Node[]>(`/api/v1/nodes?${params.toString()}`);
+  // Active nodes unless include_inactive or is_active says otherwise
+  list: async (params?: {
+    category?: string;
+    node_type?: string;
+    semantic_id?: string;
+    is_active?: boolean;
+    include_inactive?: boolean;
+    search?: string;
+  }): Promise<Node[]> => {
+    const { data } = await api.get<Node[]>('/api/v1/nodes', { params });
     return data;
   },
 
@@ -239,34 +843,182 @@ export const nodesAPI = {
     return data;
   },
 
+  updatePositions: async (positions: NodePosition[]): Promise<NodePositionsUpdated> => {
+    const { data } = await api.patch<NodePositionsUpdated>('/api/v1/nodes/positions', positions);
+    return data;
+  },
+
+  // Copies land next to the original; withConnections also copies its answers
+  clone: async (id: string, withConnections = false): Promise<ClonedNode> => {
+    const { data } = await api.post<ClonedNode>(`/api/v1/nodes/${id}/clone`, undefined, {
+      params: withConnections ? { with_connections: true } : undefined,
+    });
+    return data;
+  },
+
+  // Copy or move the node and everything reachable from it into another category
+  transferSubtree: async (id: string, request: TransferSubtree): Promise<SubtreeTransferred> => {
+    const { data } = await api.post<SubtreeTransferred>(`/api/v1/nodes/${id}/subtree`, request);
+    return data;
+  },
+
+  // Point the node's incoming answers at the target and deactivate it
+  merge: async (id: string, targetNodeId: string): Promise<NodesMerged> => {
+    const { data } = await api.post<NodesMerged>(`/api/v1/nodes/${id}/merge`, { target_node_id: targetNodeId });
+    return data;
+  },
+
+  restore: async (id: string): Promise<Node> => {
+    const { data } = await api.post<Node>(`/api/v1/nodes/${id}/restore`);
+    return data;
+  },
+
   delete: async (id: string): Promise<void> => {
     await api.delete(`/api/v1/nodes/${id}`);
   },
+
+  getHistory: async (id: string, limit?: number): Promise<NodeRevision[]> => {
+    const params = limit ? `?limit=${limit}` : '';
+    const { data } = await api.get<NodeRevision[]>(`/api/v1/nodes/${id}/history${params}`);
+    return data;
+  },
+
+  revertRevision: async (id: string, revisionId: string, force = false): Promise<Node> => {
+    const params = force ? '?force=true' : '';
+    const { data } = await api.post<Node>(`/api/v1/nodes/${id}/history/${revisionId}/revert${params}`);
+    return data;
+  },
 };
 
 export const connectionsAPI = {
-  list: async (fromNodeId?: string, toNodeId?: string): Promise<Connection[]> => {
-    const params = new URLSearchParams();
-    if (fromNodeId) params.append('from_node_id', fromNodeId);
-    if (toNodeId) params.append('to_node_id', toNodeId);
-
-    const { data} = await api.get<Connection[]>(`/api/v1/connections?${params.toString()}`);
+  // Active connections unless include_inactive or is_active says otherwise
+  list: async (params?: {
+    from_node_id?: string;
+    to_node_id?: string;
+    is_active?: boolean;
+    include_inactive?: boolean;
+    search?: string;
+  }): Promise<Connection[]> => {
+    const { data } = await api.get<Connection[]>('/api/v1/connections', { params });
     return data;
   },
 
-  create: async (connection: CreateConnection): Promise<Connection> => {
-    const { data } = await api.post<Connection>('/api/v1/connections', connection);
+  // Answers that loop back to an earlier question are refused (409) unless allowCycle is set
+  create: async (connection: CreateConnection, allowCycle = false): Promise<Connection> => {
+    const { data } = await api.post<Connection>('/api/v1/connections', connection, {
+      params: allowCycle ? { allow_cycle: true } : undefined,
+    });
     return data;
   },
 
-  update: async (id: string, updates: UpdateConnection): Promise<Connection> => {
-    const { data } = await api.put<Connection>(`/api/v1/connections/${id}`, updates);
+  update: async (id: string, updates: UpdateConnection, allowCycle = false): Promise<Connection> => {
+    const { data } = await api.put<Connection>(`/api/v1/connections/${id}`, updates, {
+      params: allowCycle ? { allow_cycle: true } : undefined,
+    });
     return data;
   },
 
   delete: async (id: string): Promise<void> => {
     await api.delete(`/api/v1/connections/${id}`);
   },
+
+  restore: async (id: string): Promise<Connection> => {
+    const { data } = await api.post<Connection>(`/api/v1/connections/${id}/restore`);
+    return data;
+  },
+};
+
+export const entryPointsAPI = {
+  list: async (): Promise<EntryPoint[]> => {
+    const { data } = await api.get<EntryPoint[]>('/api/v1/admin/entry-points');
+    return data;
+  },
+
+  create: async (req: CreateEntryPointRequest): Promise<EntryPoint> => {
+    const { data } = await api.post<EntryPoint>('/api/v1/admin/entry-points', req);
+    return data;
+  },
+
+  update: async (name: string, req: UpdateEntryPointRequest): Promise<EntryPoint> => {
+    const { data } = await api.put<EntryPoint>(`/api/v1/admin/entry-points/${encodeURIComponent(name)}`, req);
+    return data;
+  },
+
+  delete: async (name: string): Promise<void> => {
+    await api.delete(`/api/v1/admin/entry-points/${encodeURIComponent(name)}`);
+  },
+};
+
+export const noticesAPI = {
+  list: async (params?: { category?: string; include_expired?: boolean }): Promise<CategoryNotice[]> => {
+    const { data } = await api.get<CategoryNotice[]>('/api/v1/admin/notices', { params });
+    return data;
+  },
+
+  create: async (req: CreateNoticeRequest): Promise<CategoryNotice> => {
+    const { data } = await api.post<CategoryNotice>('/api/v1/admin/notices', req);
+    return data;
+  },
+
+  update: async (id: string, req: UpdateNoticeRequest): Promise<CategoryNotice> => {
+    const { data } = await api.put<CategoryNotice>(`/api/v1/admin/notices/${id}`, req);
+    return data;
+  },
+
+  delete: async (id: string): Promise<void> => {
+    await api.delete(`/api/v1/admin/notices/${id}`);
+  },
+};
+
+export const uploadsAPI = {
+  upload: async (file: File, owner?: { type: UploadOwnerType; id: string }): Promise<UploadInfo> => {
+    const form = new FormData();
+    form.append('file', file);
+    if (owner) {
+      form.append('owner_type', owner.type);
+      form.append('owner_id', owner.id);
+    }
+    // Override the JSON default; the browser fills in the multipart boundary
+    const { data } = await api.post<UploadInfo>('/api/v1/admin/uploads', form, {
+      headers: { 'Content-Type': 'multipart/form-data' },
+    });
+    return data;
+  },
+
+  list: async (ownerType: UploadOwnerType, ownerId: string): Promise<UploadInfo[]> => {
+    const params = new URLSearchParams({ owner_type: ownerType, owner_id: ownerId });
+    const { data } = await api.get<UploadInfo[]>(`/api/v1/admin/uploads?${params.toString()}`);
+    return data;
+  },
+
+  // Returns a fresh signed download URL
+  get: async (id: string): Promise<UploadInfo> => {
+    const { data } = await api.get<UploadInfo>(`/api/v1/admin/uploads/${id}`);
+    return data;
+  },
+
+  delete: async (id: string): Promise<void> => {
+    await api.delete(`/api/v1/admin/uploads/${id}`);
+  },
+
+  // Reference files technicians get with a node (manuals, wiring diagrams)
+  listNodeAttachments: async (nodeId: string): Promise<UploadInfo[]> => {
+    const { data } = await api.get<UploadInfo[]>(`/api/v1/nodes/${nodeId}/attachments`);
+    return data;
+  },
+
+  attachToNode: async (nodeId: string, file: File): Promise<UploadInfo> => {
+    const form = new FormData();
+    form.append('file', file);
+    const { data } = await api.post<UploadInfo>(`/api/v1/nodes/${nodeId}/attachments`, form, {
+      headers: { 'Content-Type': 'multipart/form-data' },
+    });
+    return data;
+  },
+
+  detachFromNode: async (nodeId: string, uploadId: string): Promise<void> => {
+    await api.delete(`/api/v1/nodes/${nodeId}/attachments/${uploadId}`);
+  },
 };
 
 export default api;
@@ -71,8 +71,8 @@ describe('errorUtils', () => {
             type: 'ValidationError',
             data: {
               fields: [
-                { field: 'email', message: 'Invalid email format' },
-                { field: 'password', message: 'Password too short' },
+                { field: 'email', message: 'Invalid email format', pointer: null },
+                { field: 'password', message: 'Password too short', pointer: null },
               ],
             },
           },
@@ -34,6 +34,7 @@ export function getErrorMessage(error: unknown): string {
         case 'InternalError':
         case 'BadRequest':
         case 'Conflict':
+        case 'TooManyRequests':
           return apiError.data.message;
         case 'ValidationError':
           return `Validation error: ${apiError.data.fields.map(f => f.message).join(', ')}`;
@@ -21,6 +21,16 @@ vi.mock('../lib/api', () => ({
   authAPI: {
     login: vi.fn(),
   },
+  storeSession: (response: { token: string; refresh_token: string; user: unknown }) => {
+    localStorage.setItem('token', response.token);
+    localStorage.setItem('refresh_token', response.refresh_token);
+    localStorage.setItem('user', JSON.stringify(response.user));
+  },
+  clearSession: () => {
+    localStorage.removeItem('token');
+    localStorage.removeItem('refresh_token');
+    localStorage.removeItem('user');
+  },
 }));
 
 import { authAPI } from '../lib/api';
@@ -100,7 +110,9 @@ describe('AdminLoginPage', () => {
     const user = userEvent.setup();
     const mockResponse = {
       token: 'test-token',
-      user: { id: '1', email: 'admin@example.com', role: 'Admin' as UserRole },
+      refresh_token: 'test-refresh-token',
+      refresh_expires_at: '2030-01-01T00:00:00Z',
+      user: { id: '1', email: 'admin@example.com', display_name: null, role: 'Admin' as UserRole, permissions: [] },
     };
 
     vi.mocked(authAPI.login).mockResolvedValue(mockResponse);
@@ -131,7 +143,9 @@ describe('AdminLoginPage', () => {
     const user = userEvent.setup();
     const mockResponse = {
       token: 'test-jwt-token',
-      user: { id: '1', email: 'admin@example.com', role: 'Admin' as UserRole },
+      refresh_token: 'test-refresh-token',
+      refresh_expires_at: '2030-01-01T00:00:00Z',
+      user: { id: '1', email: 'admin@example.com', display_name: null, role: 'Admin' as UserRole, permissions: [] },
     };
 
     vi.mocked(authAPI.login).mockResolvedValue(mockResponse);
@@ -152,6 +166,7 @@ describe('AdminLoginPage', () => {
 
     await waitFor(() => {
       expect(localStorage.getItem('token')).toBe('test-jwt-token');
+      expect(localStorage.getItem('refresh_token')).toBe('test-refresh-token');
       expect(mockNavigate).toHaveBeenCalledWith('/admin');
     });
   });
@@ -260,7 +275,9 @@ describe('AdminLoginPage', () => {
     // Second submission should clear error before making request
     const mockSuccess = {
       token: 'new-token',
-      user: { id: '1', email: 'admin@example.com', role: 'Admin' as UserRole },
+      refresh_token: 'test-refresh-token',
+      refresh_expires_at: '2030-01-01T00:00:00Z',
+      user: { id: '1', email: 'admin@example.com', display_name: null, role: 'Admin' as UserRole, permissions: [] },
     };
     vi.mocked(authAPI.login).mockResolvedValueOnce(mockSuccess);
 
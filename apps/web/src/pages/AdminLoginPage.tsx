@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react';
 import { useNavigate } from 'react-router-dom';
-import { authAPI } from '../lib/api';
+import { authAPI, clearSession, storeSession } from '../lib/api';
 import { getErrorMessage } from '../lib/errorUtils';
 
 export default function AdminLoginPage() {
@@ -21,9 +21,8 @@ export default function AdminLoginPage() {
           navigate('/admin', { replace: true });
         })
         .catch(() => {
-          // Token is invalid, remove it
-          localStorage.removeItem('token');
-          localStorage.removeItem('user');
+          // Session could not be refreshed, remove it
+          clearSession();
         });
     }
   }, [navigate]);
@@ -39,8 +38,7 @@ export default function AdminLoginPage() {
         password,
         remember_me: rememberMe
       });
-      localStorage.setItem('token', response.token);
-      localStorage.setItem('user', JSON.stringify(response.user));
+      storeSession(response);
       navigate('/admin');
     } catch (err: unknown) {
       setError(getErrorMessage(err) || 'Invalid email or password');
@@ -26,12 +26,12 @@ import { useLocation } from 'react-router-dom';
 
 const mockHistory: HistoryStep[] = [
   {
-    node: { id: '1', text: 'Is the device powered on?', category: 'test', node_type: 'Question', semantic_id: null, display_category: null, position_x: null, position_y: null, is_active: true, created_at: '2024-01-01', updated_at: '2024-01-01' },
-    connection: { id: '1', from_node_id: '1', to_node_id: '2', label: 'No', order_index: 0, is_active: true, created_at: '2024-01-01', updated_at: '2024-01-01' },
+    node_id: '1', node_text: 'Is the device powered on?', connection_id: '1', connection_label: 'No',
+    skipped_to: null, not_sure_to: null, note: null, unsure: false, value: null, answered_at: '2024-01-01T00:00:00Z', node: null, connection: null,
   },
   {
-    node: { id: '2', text: 'Is the power cable connected?', category: 'test', node_type: 'Question', semantic_id: null, display_category: null, position_x: null, position_y: null, is_active: true, created_at: '2024-01-01', updated_at: '2024-01-01' },
-    connection: { id: '2', from_node_id: '2', to_node_id: '3', label: 'Yes', order_index: 0, is_active: true, created_at: '2024-01-01', updated_at: '2024-01-01' },
+    node_id: '2', node_text: 'Is the power cable connected?', connection_id: '2', connection_label: 'Yes',
+    skipped_to: null, not_sure_to: null, note: null, unsure: false, value: null, answered_at: '2024-01-01T00:00:01Z', node: null, connection: null,
   },
 ];
 
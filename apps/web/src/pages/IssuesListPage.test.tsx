@@ -18,6 +18,10 @@ vi.mock('react-router-dom', async () => {
 
 // Mock the API
 vi.mock('../lib/api', () => ({
+  authAPI: {
+    logout: vi.fn(),
+  },
+  clearSession: vi.fn(),
   issuesAPI: {
     list: vi.fn(),
     toggle: vi.fn(),
@@ -49,6 +53,14 @@ const mockIssues: Issue[] = [
     question_count: 10n,
     created_at: '2024-01-01T00:00:00Z',
     updated_at: '2024-01-01T00:00:00Z',
+    archived_at: null,
+    description: null,
+    owner_id: null,
+    owner_email: null,
+    equipment_models: [],
+    tags: [],
+    active_from: null,
+    active_until: null,
   },
   {
     id: '2',
@@ -60,6 +72,14 @@ const mockIssues: Issue[] = [
     question_count: 5n,
     created_at: '2024-01-02T00:00:00Z',
     updated_at: '2024-01-02T00:00:00Z',
+    archived_at: null,
+    description: null,
+    owner_id: null,
+    owner_email: null,
+    equipment_models: [],
+    tags: [],
+    active_from: null,
+    active_until: null,
   },
 ];
 
@@ -134,6 +154,14 @@ describe('IssuesListPage', () => {
         question_count: 1n,
         created_at: '2024-01-03T00:00:00Z',
         updated_at: '2024-01-03T00:00:00Z',
+        archived_at: null,
+        description: null,
+        owner_id: null,
+        owner_email: null,
+        equipment_models: [],
+        tags: [],
+        active_from: null,
+        active_until: null,
       },
     ];
 
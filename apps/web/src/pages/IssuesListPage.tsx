@@ -1,7 +1,8 @@
 import { useState, useEffect, useCallback } from 'react';
 import { useNavigate } from 'react-router-dom';
-import { issuesAPI } from '../lib/api';
+import { authAPI, clearSession, issuesAPI } from '../lib/api';
 import type { Issue } from '../types/issues';
+import type { ApiError } from '../types';
 import IssueCard from '../components/IssueCard';
 import TreeEditorModal from '../components/TreeEditorModal';
 import CreateIssueModal from '../components/CreateIssueModal';
@@ -64,14 +65,18 @@ export default function IssuesListPage() {
     } catch (err: unknown) {
       // Check if this is an axios error with validation data
       if (err && typeof err === 'object' && 'response' in err) {
-        const axiosErr = err as { response?: { data?: { error?: { type?: string; data?: { fields?: { incomplete_nodes?: string }; message?: string } } } } };
-        const validationError = axiosErr.response?.data?.error;
-        if (validationError?.type === 'validation' && validationError?.data?.fields?.incomplete_nodes) {
-          const message = validationError.data.fields.incomplete_nodes;
+        const axiosErr = err as { response?: { data?: { error?: ApiError } } };
+        const apiError = axiosErr.response?.data?.error;
+        if (apiError?.type === 'InvalidGraph') {
+          const { message, report } = apiError.data;
+          const problems = report.problems
+            .filter(problem => problem.severity === 'error')
+            .map(problem => `• ${problem.message}`)
+            .join('\n');
           setConfirmDialog({
             isOpen: true,
             title: 'Validation Warning',
-            message: `${message}\n\nDo you want to activate this issue anyway?`,
+            message: `${message}:\n${problems}\n\nDo you want to activate this issue anyway?`,
             onConfirm: () => {
               // Retry with force=true
               handleToggle(category, true);
@@ -111,6 +116,24 @@ export default function IssuesListPage() {
     }
   }, []);
 
+  const handleArchive = useCallback(async (category: string, archive: boolean) => {
+    setError(null);
+    try {
+      let archivedAt: string | null = null;
+      if (archive) {
+        archivedAt = (await issuesAPI.archive(category)).archived_at;
+      } else {
+        await issuesAPI.unarchive(category);
+      }
+      setIssues(prevIssues => prevIssues.map(issue =>
+        issue.category === category ? { ...issue, archived_at: archivedAt } : issue
+      ));
+    } catch (err: unknown) {
+      setError(getErrorMessage(err) || `Failed to ${archive ? 'archive' : 'unarchive'} issue. Please try again.`);
+      logger.error('Failed to change issue archival', { category, archive, error: getErrorMessage(err) });
+    }
+  }, []);
+
   const handleCreateNew = useCallback(() => {
     setShowCreateModal(true);
   }, []);
@@ -162,9 +185,13 @@ export default function IssuesListPage() {
     loadIssues(); // Reload issues after import
   }, []);
 
-  const handleLogout = useCallback(() => {
-    localStorage.removeItem('token');
-    localStorage.removeItem('user');
+  const handleLogout = useCallback(async () => {
+    try {
+      await authAPI.logout();
+    } catch (err: unknown) {
+      logger.error('Failed to revoke session on logout', { error: getErrorMessage(err) });
+    }
+    clearSession();
     navigate('/login');
   }, [navigate]);
 
@@ -274,6 +301,7 @@ export default function IssuesListPage() {
                 onEdit={handleEdit}
                 onDelete={handleDelete}
                 onExport={handleExportSingle}
+                onArchive={handleArchive}
               />
             ))}
           </div>
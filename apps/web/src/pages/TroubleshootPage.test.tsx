@@ -21,6 +21,11 @@ vi.mock('../lib/api', () => ({
   troubleshootAPI: {
     startSession: vi.fn(),
     submitAnswer: vi.fn(),
+    heartbeat: vi.fn().mockResolvedValue({}),
+    goBack: vi.fn(),
+    gotoStep: vi.fn(),
+    followUp: vi.fn(),
+    resume: vi.fn(),
   },
 }));
 
@@ -40,6 +45,8 @@ const mockQuestionNode: Node = {
   display_category: 'Hardware',
   position_x: 100,
   position_y: 200,
+  kb_url: null,
+  doc_reference: null,
   is_active: true,
   created_at: '2024-01-01T00:00:00Z',
   updated_at: '2024-01-01T00:00:00Z',
@@ -49,12 +56,14 @@ const mockOptions: NavigationOption[] = [
   {
     connection_id: 'conn-1',
     label: 'Yes',
+    condition: null,
     target_category: 'hardware',
     display_category: 'Hardware',
   },
   {
     connection_id: 'conn-2',
     label: 'No',
+    condition: null,
     target_category: 'hardware',
     display_category: 'Hardware',
   },
@@ -69,6 +78,8 @@ const mockConclusionNode: Node = {
   display_category: 'Hardware',
   position_x: null,
   position_y: null,
+  kb_url: null,
+  doc_reference: null,
   is_active: true,
   created_at: '2024-01-01T00:00:00Z',
   updated_at: '2024-01-01T00:00:00Z',
@@ -84,6 +95,7 @@ describe('TroubleshootPage', () => {
   it('should start session on mount', async () => {
     const mockResponse = {
       session_id: 'session-123',
+      resume_code: 'ABC234',
       node: mockQuestionNode,
       options: mockOptions,
     };
@@ -110,6 +122,7 @@ describe('TroubleshootPage', () => {
   it('should display question text when session loads', async () => {
     const mockResponse = {
       session_id: 'session-123',
+      resume_code: 'ABC234',
       node: mockQuestionNode,
       options: mockOptions,
     };
@@ -132,6 +145,7 @@ describe('TroubleshootPage', () => {
   it('should display navigation options', async () => {
     const mockResponse = {
       session_id: 'session-123',
+      resume_code: 'ABC234',
       node: mockQuestionNode,
       options: mockOptions,
     };
@@ -407,6 +421,7 @@ describe('TroubleshootPage', () => {
     const user = userEvent.setup();
     const startResponse = {
       session_id: 'session-123',
+      resume_code: 'ABC234',
       node: mockConclusionNode,
       options: [],
     };
@@ -448,6 +463,7 @@ describe('TroubleshootPage', () => {
   it('should disable Next button when no option is selected', async () => {
     const mockResponse = {
       session_id: 'session-123',
+      resume_code: 'ABC234',
       node: mockQuestionNode,
       options: mockOptions,
     };
@@ -473,6 +489,7 @@ describe('TroubleshootPage', () => {
   it('should start session with category from URL params', async () => {
     const mockResponse = {
       session_id: 'session-123',
+      resume_code: 'ABC234',
       node: mockQuestionNode,
       options: mockOptions,
     };
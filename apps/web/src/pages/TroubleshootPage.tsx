@@ -2,35 +2,65 @@ import { useState, useEffect, useCallback } from 'react';
 import { useNavigate, useParams } from 'react-router-dom';
 import { troubleshootAPI } from '../lib/api';
 import type { Node, NavigationOption } from '../types';
-import { getErrorMessage } from '../lib/errorUtils';
+import type { NodeAttachment } from '../types/NodeAttachment';
+import type { RemainingDepth } from '../types/RemainingDepth';
+import { getErrorMessage, isAxiosError } from '../lib/errorUtils';
 import { logger } from '../lib/logger';
+import { NodeContentView } from '../components/NodeContentView';
+import { NodeAttachmentList } from '../components/NodeAttachmentList';
 
 interface HistoryStep {
   nodeText: string;
   optionLabel: string;
   nodeId: string;
   connectionId: string;
-  node: Node;
-  options: NavigationOption[];
+  /** Reading entered on a measurement question */
+  value?: string;
+  /** Answers picked on a multi-select question */
+  connectionIds?: string[];
 }
 
+/** How often an open session reports that the tech is still on the page */
+const HEARTBEAT_INTERVAL_MS = 30_000;
+
 export default function TroubleshootPage() {
   const navigate = useNavigate();
   const { category } = useParams<{ category?: string }>();
   const [sessionId, setSessionId] = useState<string | null>(null);
   const [currentNode, setCurrentNode] = useState<Node | null>(null);
+  const [attachments, setAttachments] = useState<NodeAttachment[]>([]);
   const [options, setOptions] = useState<NavigationOption[]>([]);
   const [selectedOption, setSelectedOption] = useState<string>('');
+  const [reading, setReading] = useState('');
+  const [selectedOptions, setSelectedOptions] = useState<string[]>([]);
+  const [followUpsRemaining, setFollowUpsRemaining] = useState(0);
+  const [remaining, setRemaining] = useState<RemainingDepth | null>(null);
   const [history, setHistory] = useState<HistoryStep[]>([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [categoryFilter, setCategoryFilter] = useState<string>('all');
+  const [resumeCode, setResumeCode] = useState<string | null>(null);
+  const [resumeInput, setResumeInput] = useState('');
 
   // Detect if we're at the global issue selection screen
   const isIssueSelection = currentNode?.semantic_id === 'start';
 
-  // Detect if we're at a conclusion node
-  const isConclusion = currentNode?.node_type === 'Conclusion';
+  // Detect if we're at a conclusion node (a sub-flow run on its own ends at a return)
+  const isConclusion = currentNode?.node_type === 'Conclusion' || currentNode?.node_type === 'Return';
+
+  // Measurement questions route on a numeric reading instead of a chosen option
+  const isMeasurement = options.some(opt => opt.condition);
+  // Checklist questions take several answers at once
+  const isMultiSelect = !isMeasurement && !!currentNode?.answer_mode && currentNode.answer_mode !== 'single';
+  const canSubmit = isMeasurement
+    ? reading.trim() !== '' && Number.isFinite(Number(reading))
+    : isMultiSelect ? selectedOptions.length > 0 : !!selectedOption;
+
+  const toggleOption = (connectionId: string) => {
+    setSelectedOptions((selected) => selected.includes(connectionId)
+      ? selected.filter((id) => id !== connectionId)
+      : [...selected, connectionId]);
+  };
 
   // Get unique categories from options
   const availableCategories = Array.from(
@@ -52,12 +82,21 @@ export default function TroubleshootPage() {
         category: category || null,
       });
       setSessionId(response.session_id);
+      setResumeCode(response.resume_code);
       setCurrentNode(response.node);
+      setAttachments(response.attachments);
       setOptions(response.options);
+      setFollowUpsRemaining(0);
+      setRemaining(null);
       setHistory([]);
       setSelectedOption('');
+      setReading('');
+      setSelectedOptions([]);
     } catch (err) {
-      setError('Failed to start session. Please try again.');
+      // A category that has used up its daily sessions says so
+      setError(isAxiosError(err) && err.response?.status === 429
+        ? getErrorMessage(err)
+        : 'Failed to start session. Please try again.');
       logger.error('Failed to start troubleshooting session', { category, error: getErrorMessage(err) });
     } finally {
       setLoading(false);
@@ -69,35 +108,78 @@ export default function TroubleshootPage() {
     startNewSession();
   }, [startNewSession]);
 
+  // Ping while the tech is reading so a slow session isn't counted as abandoned
+  useEffect(() => {
+    if (!sessionId || isConclusion) return;
+
+    const interval = setInterval(() => {
+      if (document.visibilityState !== 'visible') return;
+      troubleshootAPI.heartbeat(sessionId).catch((err) => {
+        logger.warn('Session heartbeat failed', { sessionId, error: getErrorMessage(err) });
+      });
+    }, HEARTBEAT_INTERVAL_MS);
+
+    return () => clearInterval(interval);
+  }, [sessionId, isConclusion]);
+
   const submitAnswer = async () => {
-    if (!sessionId || !selectedOption) return;
+    if (!sessionId || !currentNode || !canSubmit) return;
 
     setLoading(true);
     setError(null);
     try {
-      const response = await troubleshootAPI.submitAnswer(sessionId, {
-        connection_id: selectedOption,
-      });
+      const response = await troubleshootAPI.submitAnswer(
+        sessionId,
+        isMeasurement
+          ? { node_id: currentNode.id, value: Number(reading) }
+          : isMultiSelect
+            ? { connection_ids: selectedOptions }
+            : { connection_id: selectedOption },
+      );
 
       // Add current node/option to history (with full state for back navigation)
       const selectedOptionObj = options.find((opt) => opt.connection_id === selectedOption);
-      if (currentNode && selectedOptionObj) {
+      if (isMeasurement) {
+        setHistory([...history, {
+          nodeText: currentNode.text,
+          optionLabel: reading.trim(),
+          nodeId: currentNode.id,
+          connectionId: '',
+          value: reading.trim(),
+        }]);
+      } else if (isMultiSelect) {
+        setHistory([...history, {
+          nodeText: currentNode.text,
+          optionLabel: options
+            .filter((opt) => selectedOptions.includes(opt.connection_id))
+            .map((opt) => opt.label)
+            .join(', '),
+          nodeId: currentNode.id,
+          connectionId: '',
+          connectionIds: selectedOptions,
+        }]);
+      } else if (selectedOptionObj) {
         setHistory([...history, {
           nodeText: currentNode.text,
           optionLabel: selectedOptionObj.label,
           nodeId: currentNode.id,
           connectionId: selectedOption,
-          node: currentNode,
-          options: options,
         }]);
       }
 
       // Always update to next node (whether question or conclusion)
       setCurrentNode(response.node);
+      setAttachments(response.attachments);
       setOptions(response.options);
+      setFollowUpsRemaining(response.follow_ups_remaining);
+      setRemaining(response.remaining);
       setSelectedOption('');
+      setReading('');
+      setSelectedOptions([]);
     } catch (err) {
-      setError('Failed to submit answer. Please try again.');
+      setError(isMeasurement
+        ? 'That reading could not be matched to a next step. Please check the value and try again.'
+        : 'Failed to submit answer. Please try again.');
       logger.error('Failed to submit answer', {
         sessionId,
         connectionId: selectedOption,
@@ -108,19 +190,170 @@ export default function TroubleshootPage() {
     }
   };
 
-  const goBack = () => {
-    if (history.length === 0) return;
+  // Move on to the next answer picked on a queue-mode checklist question
+  const continueFollowUp = async () => {
+    if (!sessionId) return;
+
+    setLoading(true);
+    setError(null);
+    try {
+      const response = await troubleshootAPI.followUp(sessionId);
+      setHistory([...history, {
+        nodeText: 'Checklist',
+        optionLabel: 'Next selected answer',
+        nodeId: currentNode?.id ?? '',
+        connectionId: '',
+      }]);
+      setCurrentNode(response.node);
+      setAttachments(response.attachments);
+      setOptions(response.options);
+      setFollowUpsRemaining(response.follow_ups_remaining);
+      setRemaining(response.remaining);
+    } catch (err) {
+      setError('Failed to continue. Please try again.');
+      logger.error('Failed to continue with next selected answer', {
+        sessionId,
+        error: getErrorMessage(err)
+      });
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  // Follow the question's "I'm not sure" option to its sub-flow or escalation
+  const answerNotSure = async () => {
+    if (!sessionId || !currentNode) return;
+
+    setLoading(true);
+    setError(null);
+    try {
+      const response = await troubleshootAPI.submitAnswer(sessionId, { not_sure: true });
+      setHistory([...history, {
+        nodeText: currentNode.text,
+        optionLabel: "I'm not sure",
+        nodeId: currentNode.id,
+        connectionId: '',
+      }]);
+      setCurrentNode(response.node);
+      setAttachments(response.attachments);
+      setOptions(response.options);
+      setFollowUpsRemaining(response.follow_ups_remaining);
+      setRemaining(response.remaining);
+      setSelectedOption('');
+      setReading('');
+      setSelectedOptions([]);
+    } catch (err) {
+      setError('Failed to submit answer. Please try again.');
+      logger.error('Failed to submit "not sure"', {
+        sessionId,
+        error: getErrorMessage(err)
+      });
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const goBack = async () => {
+    if (!sessionId || history.length === 0) return;
 
     // Get the previous step from history
     const previousStep = history[history.length - 1];
 
-    // Restore the previous node and options
-    setCurrentNode(previousStep.node);
-    setOptions(previousStep.options);
-    setSelectedOption(previousStep.connectionId);
+    setLoading(true);
+    setError(null);
+    try {
+      // Undo the answer on the server so the session record matches what the tech sees
+      const response = await troubleshootAPI.goBack(sessionId);
 
-    // Remove the last step from history
-    setHistory(history.slice(0, -1));
+      // Restore the previous node and options
+      setCurrentNode(response.node);
+      setAttachments(response.attachments);
+      setOptions(response.options);
+      setFollowUpsRemaining(response.follow_ups_remaining);
+      setRemaining(response.remaining);
+      setSelectedOption(previousStep.connectionId);
+      setReading(previousStep.value ?? '');
+      setSelectedOptions(previousStep.connectionIds ?? []);
+
+      // Remove the last step from history
+      setHistory(history.slice(0, -1));
+    } catch (err) {
+      setError('Failed to go back. Please try again.');
+      logger.error('Failed to go back', {
+        sessionId,
+        error: getErrorMessage(err)
+      });
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const returnToStep = async (index: number) => {
+    if (!sessionId || index >= history.length) return;
+
+    const step = history[index];
+
+    setLoading(true);
+    setError(null);
+    try {
+      // Discard this answer and every later one on the server
+      const response = await troubleshootAPI.gotoStep(sessionId, index);
+
+      setCurrentNode(response.node);
+      setAttachments(response.attachments);
+      setOptions(response.options);
+      setFollowUpsRemaining(response.follow_ups_remaining);
+      setRemaining(response.remaining);
+      setSelectedOption(step.connectionId);
+      setReading(step.value ?? '');
+      setSelectedOptions(step.connectionIds ?? []);
+      setHistory(history.slice(0, index));
+    } catch (err) {
+      setError('Failed to return to that step. Please try again.');
+      logger.error('Failed to return to step', {
+        sessionId,
+        stepIndex: index,
+        error: getErrorMessage(err)
+      });
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const resumeSession = async (e: React.FormEvent) => {
+    e.preventDefault();
+    const code = resumeInput.trim();
+    if (!code) return;
+
+    setLoading(true);
+    setError(null);
+    try {
+      // Pick up a session started on another device
+      const response = await troubleshootAPI.resume(code);
+
+      setSessionId(response.session_id);
+      setResumeCode(response.resume_code);
+      setCurrentNode(response.node);
+      setAttachments(response.attachments);
+      setOptions(response.options);
+      setFollowUpsRemaining(response.follow_ups_remaining);
+      setRemaining(response.remaining);
+      setSelectedOption('');
+      setReading('');
+      setSelectedOptions([]);
+      setHistory(response.steps.map((step) => ({
+        nodeText: step.node_text,
+        optionLabel: step.connection_label ?? 'Skipped to conclusion',
+        nodeId: step.node_id,
+        connectionId: step.connection_id ?? '',
+      })));
+      setResumeInput('');
+    } catch (err) {
+      setError('Resume code not found or expired.');
+      logger.error('Failed to resume session', { error: getErrorMessage(err) });
+    } finally {
+      setLoading(false);
+    }
   };
 
   if (loading && !currentNode) {
@@ -242,8 +475,13 @@ export default function TroubleshootPage() {
                       </svg>
                     </div>
                     <h2 className="text-3xl font-bold text-gray-800 mb-2">
-                      Troubleshooting Complete
+                      {followUpsRemaining > 0 ? 'Step Complete' : 'Troubleshooting Complete'}
                     </h2>
+                    {followUpsRemaining > 0 && (
+                      <p className="text-gray-600">
+                        {followUpsRemaining} more selected answer{followUpsRemaining === 1 ? '' : 's'} to check
+                      </p>
+                    )}
                   </div>
 
                   <div className="bg-green-50 border-2 border-green-200 rounded-xl p-6 mb-6">
@@ -251,9 +489,39 @@ export default function TroubleshootPage() {
                     <p className="text-gray-800 text-lg leading-relaxed">
                       {currentNode?.text}
                     </p>
+                    <NodeContentView content={currentNode?.content} />
+                    <NodeAttachmentList attachments={attachments} />
+                    {(currentNode?.kb_url || currentNode?.doc_reference) && (
+                      <div className="mt-4 pt-4 border-t border-green-200 text-sm text-green-900">
+                        {currentNode.kb_url && (
+                          <a
+                            href={currentNode.kb_url}
+                            target="_blank"
+                            rel="noopener noreferrer"
+                            className="font-semibold underline hover:text-green-700"
+                          >
+                            📖 Open repair manual
+                          </a>
+                        )}
+                        {currentNode.doc_reference && (
+                          <span className={currentNode.kb_url ? 'ml-3' : ''}>
+                            Reference: {currentNode.doc_reference}
+                          </span>
+                        )}
+                      </div>
+                    )}
                   </div>
 
                   <div className="flex gap-3 flex-wrap">
+                    {followUpsRemaining > 0 && (
+                      <button
+                        onClick={continueFollowUp}
+                        disabled={loading}
+                        className="w-full bg-[#667eea] hover:bg-[#5568d3] text-white text-lg font-semibold py-4 px-8 rounded-lg transition-all duration-200 disabled:opacity-50 hover:-translate-y-0.5 hover:shadow-lg"
+                      >
+                        {loading ? 'Loading...' : 'Continue With Next Answer'}
+                      </button>
+                    )}
                     {history.length > 0 && (
                       <button
                         onClick={goBack}
@@ -282,37 +550,94 @@ export default function TroubleshootPage() {
                   <div className="mb-6">
                     <span className="text-sm text-gray-500 font-medium">
                       Question {history.length + 1}
+                      {remaining && (
+                        <>
+                          {' · '}
+                          {remaining.min === remaining.max
+                            ? `${remaining.min} left`
+                            : `${remaining.min}–${remaining.max} left`}
+                        </>
+                      )}
                     </span>
                   </div>
 
                   <h2 className="text-2xl font-bold text-gray-800 mb-6">
                     {currentNode?.text}
                   </h2>
+                  {(currentNode?.content || attachments.length > 0) && (
+                    <div className="mb-6">
+                      <NodeContentView content={currentNode?.content} />
+                      <NodeAttachmentList attachments={attachments} />
+                    </div>
+                  )}
 
+                  {isMeasurement ? (
+                  <div className="mb-8">
+                    <input
+                      type="number"
+                      inputMode="decimal"
+                      step="any"
+                      value={reading}
+                      onChange={(e) => setReading(e.target.value)}
+                      onKeyDown={(e) => e.key === 'Enter' && submitAnswer()}
+                      placeholder="Enter reading"
+                      aria-label="Measurement reading"
+                      className="w-full p-4 border-2 border-gray-200 rounded-lg text-lg focus:border-[#667eea] focus:outline-none"
+                    />
+                    <ul className="mt-3 text-sm text-gray-500 space-y-1">
+                      {options.map((option) => (
+                        <li key={option.connection_id}>
+                          {option.condition ?? 'otherwise'}: {option.label}
+                        </li>
+                      ))}
+                    </ul>
+                  </div>
+                  ) : (
                   <div className="space-y-3 mb-8">
                     {options.map((option) => (
                       <label
                         key={option.connection_id}
                         className="flex items-center p-4 border-2 border-gray-200 rounded-lg cursor-pointer transition-all hover:border-[#667eea] hover:bg-gray-50"
                         style={{
-                          borderColor: selectedOption === option.connection_id ? '#667eea' : '',
-                          backgroundColor: selectedOption === option.connection_id ? '#f0f4ff' : '',
+                          borderColor: selectedOption === option.connection_id || selectedOptions.includes(option.connection_id) ? '#667eea' : '',
+                          backgroundColor: selectedOption === option.connection_id || selectedOptions.includes(option.connection_id) ? '#f0f4ff' : '',
                         }}
                       >
-                        <input
-                          type="radio"
-                          name="option"
-                          value={option.connection_id}
-                          checked={selectedOption === option.connection_id}
-                          onChange={(e) => setSelectedOption(e.target.value)}
-                          className="w-5 h-5 text-[#667eea] focus:ring-[#667eea] focus:ring-2"
-                        />
+                        {isMultiSelect ? (
+                          <input
+                            type="checkbox"
+                            value={option.connection_id}
+                            checked={selectedOptions.includes(option.connection_id)}
+                            onChange={() => toggleOption(option.connection_id)}
+                            className="w-5 h-5 text-[#667eea] focus:ring-[#667eea] focus:ring-2"
+                          />
+                        ) : (
+                          <input
+                            type="radio"
+                            name="option"
+                            value={option.connection_id}
+                            checked={selectedOption === option.connection_id}
+                            onChange={(e) => setSelectedOption(e.target.value)}
+                            className="w-5 h-5 text-[#667eea] focus:ring-[#667eea] focus:ring-2"
+                          />
+                        )}
                         <span className="ml-3 text-gray-700 font-medium">
                           {option.label}
                         </span>
                       </label>
                     ))}
                   </div>
+                  )}
+
+                  {currentNode.not_sure_node_id && (
+                    <button
+                      onClick={answerNotSure}
+                      disabled={loading}
+                      className="w-full mb-3 text-[#667eea] hover:text-[#5568d3] font-medium py-2 disabled:opacity-50 disabled:cursor-not-allowed"
+                    >
+                      I'm not sure
+                    </button>
+                  )}
 
                   <div className="flex gap-2">
                     {history.length > 0 && (
@@ -339,7 +664,7 @@ export default function TroubleshootPage() {
                     )}
                     <button
                       onClick={submitAnswer}
-                      disabled={!selectedOption || loading}
+                      disabled={!canSubmit || loading}
                       className={`${history.length > 0 ? 'flex-[0.8]' : 'w-full'} bg-[#667eea] hover:bg-[#5568d3] text-white text-lg font-semibold py-4 px-8 rounded-lg transition-all duration-200 disabled:opacity-50 disabled:cursor-not-allowed hover:-translate-y-0.5 hover:shadow-lg`}
                     >
                       {loading ? 'Loading...' : 'Next'}
@@ -364,18 +689,49 @@ export default function TroubleshootPage() {
               ) : (
                 <div className="space-y-4">
                   {history.map((step, index) => (
-                    <div key={index} className="border-l-4 border-[#667eea] pl-3">
+                    <button
+                      key={index}
+                      onClick={() => returnToStep(index)}
+                      disabled={loading}
+                      title="Return to this question"
+                      className="block w-full text-left border-l-4 border-[#667eea] pl-3 hover:bg-[#f0f4ff] rounded-r transition-colors duration-200 disabled:cursor-not-allowed"
+                    >
                       <div className="text-sm font-semibold text-gray-700">
                         {step.nodeText}
                       </div>
                       <div className="text-sm text-gray-600 mt-1">
                         → {step.optionLabel}
                       </div>
-                    </div>
+                    </button>
                   ))}
                 </div>
               )}
 
+              {resumeCode && !isConclusion && (
+                <div className="mt-6 p-3 bg-gray-50 rounded-lg text-sm text-gray-600">
+                  Continue on another device with code{' '}
+                  <span className="font-mono font-bold text-gray-800 tracking-widest">{resumeCode}</span>
+                </div>
+              )}
+
+              <form onSubmit={resumeSession} className="mt-4 flex gap-2">
+                <input
+                  value={resumeInput}
+                  onChange={(e) => setResumeInput(e.target.value)}
+                  placeholder="Resume code"
+                  maxLength={8}
+                  aria-label="Resume code"
+                  className="flex-1 min-w-0 px-3 py-2 border-2 border-gray-300 rounded-lg font-mono uppercase focus:border-[#667eea] focus:outline-none"
+                />
+                <button
+                  type="submit"
+                  disabled={!resumeInput.trim() || loading}
+                  className="px-4 py-2 bg-[#667eea] hover:bg-[#5568d3] text-white font-semibold rounded-lg transition-colors duration-200 disabled:opacity-50"
+                >
+                  Resume
+                </button>
+              </form>
+
               <button
                 onClick={startNewSession}
                 className="w-full mt-6 bg-gray-200 hover:bg-gray-300 text-gray-700 font-semibold py-3 px-4 rounded-lg transition-colors duration-200"
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why the tech is giving up on a session
+ */
+export type AbandonSessionRequest = { 
+/**
+ * e.g. "equipment replaced", "escalated to vendor"
+ */
+reason?: string, };
\ No newline at end of file
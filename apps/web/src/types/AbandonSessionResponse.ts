@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An abandoned session
+ */
+export type AbandonSessionResponse = { session_id: string, reason: string | null, abandoned_at: string, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to approve or reject a submitted draft
+ */
+export type ApprovalDecisionRequest = { 
+/**
+ * Reason for the decision; required when rejecting
+ */
+comment?: string, };
\ No newline at end of file
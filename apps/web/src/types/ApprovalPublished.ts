@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DraftPublished } from "./DraftPublished";
+import type { IssueApproval } from "./IssueApproval";
+
+/**
+ * An approved submission and the publish it triggered
+ */
+export type ApprovalPublished = { approval: IssueApproval, published: DraftPublished, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to archive old sessions
+ */
+export type ArchiveSessionsRequest = { 
+/**
+ * Archive sessions started more than this many days ago (finished or idle since)
+ */
+older_than_days: number, };
\ No newline at end of file
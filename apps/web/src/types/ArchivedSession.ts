@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An archived session with its decompressed steps
+ */
+export type ArchivedSession = { session_id: string, started_at: string, completed_at: string | null, abandoned: boolean, abandon_reason: string | null, last_activity_at: string, final_conclusion: string | null, category: string | null, step_count: number, tech_identifier: string | null, client_site: string | null, entry_point: string | null, equipment_id: string | null, variables: Record<string, unknown>, device_id: string | null, start_node_id: string | null, archived_at: string, steps: unknown, };
\ No newline at end of file
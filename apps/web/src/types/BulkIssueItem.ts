@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BulkIssueStatus } from "./BulkIssueStatus";
+import type { GraphProblem } from "./GraphProblem";
+
+export type BulkIssueItem = { category: string, status: BulkIssueStatus, error: string | null, 
+/**
+ * Graph problems that stopped the issue being enabled
+ */
+problems: Array<GraphProblem>, };
\ No newline at end of file
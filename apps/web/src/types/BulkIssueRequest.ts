@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BulkIssueAction } from "./BulkIssueAction";
+
+/**
+ * Request to apply one action to several issues
+ */
+export type BulkIssueRequest = { action: BulkIssueAction, categories: Array<string>, 
+/**
+ * Target state for `toggle`
+ */
+is_active?: boolean, 
+/**
+ * Skip the graph validation when enabling
+ */
+force: boolean, 
+/**
+ * Also delete the issues' sessions (`delete` only)
+ */
+delete_sessions: boolean, };
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BulkIssueAction } from "./BulkIssueAction";
+import type { BulkIssueItem } from "./BulkIssueItem";
+
+export type BulkIssueResult = { action: BulkIssueAction, updated: number, failed: number, results: Array<BulkIssueItem>, };
\ No newline at end of file
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to enable or disable several issues at once
+ */
+export type BulkToggleRequest = { categories: Array<string>, 
+/**
+ * Target state for every listed category
+ */
+is_active: boolean, 
+/**
+ * Skip the graph validation when enabling
+ */
+force: boolean, };
\ No newline at end of file
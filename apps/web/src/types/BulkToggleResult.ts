@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BulkIssueItem } from "./BulkIssueItem";
+
+export type BulkToggleResult = { is_active: boolean, updated: number, failed: number, results: Array<BulkIssueItem>, };
\ No newline at end of file
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportError } from "./ImportError";
+import type { ImportSuccess } from "./ImportSuccess";
+
+/**
+ * Result of importing a bundle: the issue import plus its attachments
+ */
+export type BundleImportResult = { 
+/**
+ * Attachments stored on the imported nodes
+ */
+attachments_count: number, 
+/**
+ * Attachments that were rejected (the issue import stands)
+ */
+attachment_errors: Array<string>, success: Array<ImportSuccess>, errors: Array<ImportError>, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ManifestCategory } from "./ManifestCategory";
+
+/**
+ * Active categories with their content versions
+ */
+export type CategoryManifest = { 
+/**
+ * Hash over every category's version; unchanged means nothing to refresh
+ */
+version: string, generated_at: string, categories: Array<ManifestCategory>, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A temporary message on a category
+ */
+export type CategoryNotice = { id: string, category: string, message: string, 
+/**
+ * Conclusion the notice suggests skipping to
+ */
+conclusion_node_id: string | null, starts_at: string, ends_at: string, created_at: string, updated_at: string, };
\ No newline at end of file
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A category's quota with today's use
+ */
+export type CategoryQuota = { category: string, 
+/**
+ * New sessions allowed per UTC day
+ */
+daily_limit: number, 
+/**
+ * Sessions per day after which ops are warned
+ */
+warn_at: number | null, 
+/**
+ * Sessions started today
+ */
+sessions_today: number, 
+/**
+ * Sessions refused today because the limit was reached
+ */
+rejected_today: number, updated_at: string, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UserRole } from "./UserRole";
+
+/**
+ * Request to change a user's role
+ */
+export type ChangeRoleRequest = { role: UserRole, };
\ No newline at end of file
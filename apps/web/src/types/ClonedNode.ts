@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Connection } from "./Connection";
+import type { Node } from "./Node";
+
+/**
+ * A copied node and the answers copied along with it
+ */
+export type ClonedNode = { node: Node, 
+/**
+ * Empty unless the clone asked for the outgoing connections
+ */
+connections: Array<Connection>, };
\ No newline at end of file
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How often a conclusion fixed the problem, according to tech feedback
+ */
+export type ConclusionEffectiveness = { node_id: string, conclusion: string, category: string, 
+/**
+ * Sessions that ended at this conclusion
+ */
+completed: number, feedback_count: number, resolved_count: number, 
+/**
+ * Share of feedback saying the problem was fixed, from 0 to 1
+ */
+effectiveness: number, };
\ No newline at end of file
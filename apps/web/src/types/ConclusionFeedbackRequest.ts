@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether the conclusion fixed the problem
+ */
+export type ConclusionFeedbackRequest = { resolved: boolean, 
+/**
+ * What happened, e.g. what actually fixed it
+ */
+comment?: string, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Feedback as stored on the session
+ */
+export type ConclusionFeedbackResponse = { session_id: string, conclusion_node_id: string | null, resolved: boolean, comment: string | null, submitted_at: string, };
\ No newline at end of file
@@ -1,3 +1,17 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Connection = { id: string, from_node_id: string, to_node_id: string, label: string, order_index: number, is_active: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
+export type Connection = { id: string, from_node_id: string, to_node_id: string, label: string, order_index: number, 
+/**
+ * Ordering weight used when the source node orders options by weight
+ */
+weight: number | null, 
+/**
+ * When this answer applies: a reading range on a measurement question
+ * (e.g. ">= 11.5 and < 13.8") and/or earlier answers (e.g. `pump_start = "No"`)
+ */
+condition: string | null, 
+/**
+ * The question's conservative branch: followed instead of the picked
+ * answer when the tech marks it unsure
+ */
+unsure_route: boolean, is_active: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
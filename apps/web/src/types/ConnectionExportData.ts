@@ -11,4 +11,12 @@ from_node_index: number,
 /**
  * Index in nodes array (not UUID)
  */
-to_node_index: number, label: string, order_index: number, };
\ No newline at end of file
+to_node_index: number, label: string, order_index: number, 
+/**
+ * When this answer applies (reading range and/or earlier answers)
+ */
+condition: string | null, 
+/**
+ * Conservative branch for unsure answers
+ */
+unsure_route: boolean, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A picture in a node's content
+ */
+export type ContentImage = { 
+/**
+ * http(s) URL or a path on this site, such as an upload
+ */
+url: string, alt: string, caption: string | null, };
\ No newline at end of file
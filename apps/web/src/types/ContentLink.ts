@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Further reading linked from a node's content
+ */
+export type ContentLink = { url: string, label: string, };
\ No newline at end of file
@@ -1,3 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type CreateConnection = { from_node_id: string, to_node_id: string, label: string, order_index: number, };
\ No newline at end of file
+export type CreateConnection = { from_node_id: string, to_node_id: string, label: string, order_index: number, 
+/**
+ * When this answer applies (reading range and/or earlier answers)
+ */
+condition?: string, 
+/**
+ * Send unsure answers to this question down this connection
+ */
+unsure_route?: boolean, };
\ No newline at end of file
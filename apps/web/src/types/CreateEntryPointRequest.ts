@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to create an entry point
+ */
+export type CreateEntryPointRequest = { 
+/**
+ * Lowercase letters, digits, `-` and `_`
+ */
+name: string, node_id: string, description?: string, };
\ No newline at end of file
@@ -3,4 +3,12 @@
 /**
  * Request to create a new issue
  */
-export type CreateIssueRequest = { name: string, category: string, display_category: string | null, root_question_text: string, };
\ No newline at end of file
+export type CreateIssueRequest = { name: string, category: string, display_category: string | null, root_question_text: string, description?: string, owner_id?: string, equipment_models?: Array<string>, tags?: Array<string>, 
+/**
+ * Start of the window technicians see the issue in
+ */
+active_from?: string, 
+/**
+ * End of the window technicians see the issue in
+ */
+active_until?: string, };
\ No newline at end of file
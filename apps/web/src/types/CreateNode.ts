@@ -1,4 +1,17 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NodeContent } from "./NodeContent";
 import type { NodeType } from "./NodeType";
 
-export type CreateNode = { category: string, node_type: NodeType, text: string, semantic_id: string | null, display_category: string | null, position_x: number | null, position_y: number | null, };
\ No newline at end of file
+export type CreateNode = { category: string, node_type: NodeType, text: string, 
+/**
+ * Unique within the category; blank means none
+ */
+semantic_id: string | null, 
+/**
+ * Derive a free semantic ID from the text when `semantic_id` is blank
+ */
+generate_semantic_id?: boolean, display_category: string | null, position_x: number | null, position_y: number | null, kb_url?: string, doc_reference?: string, 
+/**
+ * The category a sub-flow node runs (required for sub-flow nodes)
+ */
+subflow_category?: string, content?: NodeContent, };
\ No newline at end of file
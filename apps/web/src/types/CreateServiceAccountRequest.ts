@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to create a service account
+ */
+export type CreateServiceAccountRequest = { 
+/**
+ * Lowercase letters, digits, `-` and `_` (e.g. `ci-import`)
+ */
+name: string, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+
+/**
+ * Request to issue a token for a service account
+ */
+export type CreateServiceTokenRequest = { 
+/**
+ * What the token is for (e.g. "nightly export")
+ */
+name: string, scopes: Array<Permission>, };
\ No newline at end of file
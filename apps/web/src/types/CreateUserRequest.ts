@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UserRole } from "./UserRole";
+
+/**
+ * Request to create a user account
+ */
+export type CreateUserRequest = { email: string, password: string, role: UserRole, };
\ No newline at end of file
@@ -1,8 +1,23 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryQuota } from "./CategoryQuota";
 import type { CategoryStats } from "./CategoryStats";
+import type { ConclusionEffectiveness } from "./ConclusionEffectiveness";
 import type { ConclusionStats } from "./ConclusionStats";
+import type { UnsureQuestionStats } from "./UnsureQuestionStats";
 
 /**
  * Dashboard statistics response
  */
-export type DashboardStats = { total_sessions: number, completed_sessions: number, abandoned_sessions: number, active_sessions: number, avg_steps_to_completion: number, most_common_conclusions: Array<ConclusionStats>, sessions_by_category: Array<CategoryStats>, };
\ No newline at end of file
+export type DashboardStats = { total_sessions: number, completed_sessions: number, abandoned_sessions: number, active_sessions: number, avg_steps_to_completion: number, most_common_conclusions: Array<ConclusionStats>, sessions_by_category: Array<CategoryStats>, 
+/**
+ * Conclusions with tech feedback, least effective first
+ */
+conclusion_effectiveness: Array<ConclusionEffectiveness>, 
+/**
+ * Questions techs most often answer unsure, highest share first
+ */
+unsure_questions: Array<UnsureQuestionStats>, 
+/**
+ * Daily session quotas with today's use (not affected by the date filter)
+ */
+category_quotas: Array<CategoryQuota>, };
\ No newline at end of file
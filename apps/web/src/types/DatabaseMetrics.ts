@@ -1,3 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type DatabaseMetrics = { pool_size: number, active_connections: number, idle_connections: number, };
\ No newline at end of file
+export type DatabaseMetrics = { pool_size: number, max_connections: number, active_connections: number, idle_connections: number, 
+/**
+ * Requests that failed waiting for a connection since startup
+ */
+acquire_timeouts: number, 
+/**
+ * Monitor samples where every connection was in use
+ */
+saturation_events: number, avg_acquire_wait_ms: number, max_acquire_wait_ms: number, };
\ No newline at end of file
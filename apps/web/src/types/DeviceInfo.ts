@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A registered kiosk without its token
+ */
+export type DeviceInfo = { id: string, name: string, 
+/**
+ * First characters of the token, to recognise it
+ */
+token_prefix: string, created_at: string, 
+/**
+ * Approximate (recorded about once a minute)
+ */
+last_seen_at: string | null, disabled_at: string | null, 
+/**
+ * Sessions started from this device
+ */
+session_count: number, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A signed-in device (one refresh token family)
+ */
+export type DeviceSession = { id: string, user_agent: string | null, ip_address: string | null, remember_me: boolean, signed_in_at: string, last_refreshed_at: string, expires_at: string, };
\ No newline at end of file
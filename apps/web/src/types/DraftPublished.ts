@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What publishing a draft changed in the issue
+ */
+export type DraftPublished = { category: string, 
+/**
+ * Version the published graph was recorded as
+ */
+version: number, 
+/**
+ * Published nodes the draft changed
+ */
+nodes_updated: number, nodes_added: number, 
+/**
+ * Published nodes the draft no longer has, now switched off
+ */
+nodes_deactivated: number, 
+/**
+ * Published answers rewritten from the draft
+ */
+connections_updated: number, connections_added: number, connections_deactivated: number, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An open session on the same equipment
+ */
+export type DuplicateSession = { session_id: string, 
+/**
+ * Code for continuing that session on this device
+ */
+resume_code: string | null, started_at: string, tech_identifier: string | null, };
\ No newline at end of file
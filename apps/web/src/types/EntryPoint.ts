@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Named alternative start node
+ */
+export type EntryPoint = { name: string, node_id: string, 
+/**
+ * Category and text of the start node, for display
+ */
+node_category: string, node_text: string, description: string | null, is_active: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
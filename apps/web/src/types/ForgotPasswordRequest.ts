@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request a password reset link
+ */
+export type ForgotPasswordRequest = { email: string, };
\ No newline at end of file
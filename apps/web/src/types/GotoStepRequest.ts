@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to return to an earlier step
+ */
+export type GotoStepRequest = { 
+/**
+ * Zero-based index into the session history; that answer and all later ones are discarded
+ */
+step_index: number, };
\ No newline at end of file
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphProblemKind } from "./GraphProblemKind";
+import type { GraphProblemSeverity } from "./GraphProblemSeverity";
+
+/**
+ * One problem found in a graph
+ */
+export type GraphProblem = { kind: GraphProblemKind, severity: GraphProblemSeverity, node_id: string, 
+/**
+ * The answer at fault, for problems with a connection
+ */
+connection_id: string | null, message: string, };
\ No newline at end of file
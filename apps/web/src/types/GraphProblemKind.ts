@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GraphProblemKind = "dead_end" | "inactive_target" | "unreachable" | "conclusion_with_answers" | "duplicate_label" | "cycle" | "missing_subflow";
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphProblem } from "./GraphProblem";
+
+/**
+ * Everything wrong with an issue's graph
+ */
+export type GraphValidationReport = { category: string, 
+/**
+ * The issue's start node
+ */
+root_node_id: string, problems: Array<GraphProblem>, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response to a client heartbeat
+ */
+export type HeartbeatResponse = { session_id: string, last_activity_at: string, 
+/**
+ * Completed sessions ignore heartbeats; the client can stop pinging
+ */
+completed: boolean, };
\ No newline at end of file
@@ -4,5 +4,44 @@ import type { Node } from "./Node";
 
 /**
  * A step in the troubleshooting session history
+ *
+ * The question and answer as the tech saw them, plus the node and
+ * connection as they are now for linking back into the editor.
  */
-export type HistoryStep = { node: Node, connection: Connection, };
\ No newline at end of file
+export type HistoryStep = { 
+/**
+ * Reading entered on a measurement question
+ */
+value: number | null, 
+/**
+ * When the answer was given
+ */
+answered_at: string | null, 
+/**
+ * The question as it is now; null once deleted
+ */
+node: Node | null, 
+/**
+ * The answer followed as it is now; null for a skip or once deleted
+ */
+connection: Connection | null, node_id: string, node_text: string, 
+/**
+ * Answer followed; absent when the tech skipped to a conclusion
+ */
+connection_id: string | null, connection_label: string | null, 
+/**
+ * Conclusion the tech jumped to from this question
+ */
+skipped_to: string | null, 
+/**
+ * Where "I'm not sure" led from this question
+ */
+not_sure_to: string | null, 
+/**
+ * The tech's note on this step
+ */
+note: string | null, 
+/**
+ * The tech marked this answer as unsure
+ */
+unsure: boolean, };
\ No newline at end of file
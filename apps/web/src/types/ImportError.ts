@@ -1,6 +1,15 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ValidationField } from "./ValidationField";
 
 /**
  * Error during import
  */
-export type ImportError = { category: string, error: string, };
\ No newline at end of file
+export type ImportError = { category: string, error: string, 
+/**
+ * Itemized problems (e.g. each duplicate connection), empty when not applicable
+ */
+details: Array<string>, 
+/**
+ * Offending elements, with JSON pointers into the imported array (e.g. `/0/connections/3`)
+ */
+fields: Array<ValidationField>, };
\ No newline at end of file
@@ -3,4 +3,25 @@
 /**
  * Successfully imported issue
  */
-export type ImportSuccess = { category: string, name: string, nodes_count: number, connections_count: number, };
\ No newline at end of file
+export type ImportSuccess = { category: string, name: string, nodes_count: number, connections_count: number, 
+/**
+ * Duplicate connections dropped (only with `duplicates=dedupe`)
+ */
+duplicates_removed: number, 
+/**
+ * Nodes of the existing issue that were replaced (only with `mode=overwrite`)
+ */
+nodes_replaced: number, 
+/**
+ * Entry points of the replaced issue whose start question has no counterpart
+ * (same semantic ID) in the import, and were removed with it
+ */
+entry_points_dropped: Array<string>, 
+/**
+ * Change history entries of the replaced nodes, removed with them
+ */
+revisions_dropped: number, 
+/**
+ * Nodes already in the issue under the same semantic ID, left as they were (only with `mode=merge`)
+ */
+nodes_matched: number, };
\ No newline at end of file
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Connection } from "./Connection";
+import type { Node } from "./Node";
+import type { Tombstone } from "./Tombstone";
+
+/**
+ * Changes to one issue category since a point in time
+ *
+ * Unlike `IssueExportData` this keeps UUIDs so the target can match records;
+ * seed the target with a snapshot (no `since`) before applying increments.
+ */
+export type IncrementalExportData = { category: string, since: string | null, 
+/**
+ * Database time of the export; pass as `since` on the next sync
+ */
+exported_at: string, 
+/**
+ * Nodes created or modified after `since` (including deactivated ones)
+ */
+nodes: Array<Node>, 
+/**
+ * Connections created or modified after `since`
+ */
+connections: Array<Connection>, 
+/**
+ * Records deleted after `since`
+ */
+deleted: Array<Tombstone>, };
\ No newline at end of file
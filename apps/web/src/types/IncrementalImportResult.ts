@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of applying an incremental import
+ */
+export type IncrementalImportResult = { category: string, nodes_upserted: number, connections_upserted: number, nodes_deleted: number, connections_deleted: number, };
\ No newline at end of file
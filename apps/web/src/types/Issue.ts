@@ -3,4 +3,28 @@
 /**
  * Issue represents a top-level troubleshooting category
  */
-export type Issue = { id: string, name: string, category: string, display_category: string | null, root_question_id: string, is_active: boolean, question_count: bigint, created_at: string, updated_at: string, };
\ No newline at end of file
+export type Issue = { id: string, name: string, category: string, display_category: string | null, root_question_id: string, is_active: boolean, question_count: bigint, created_at: string, updated_at: string, 
+/**
+ * When the issue was archived (hidden from technicians); null while it's live
+ */
+archived_at: string | null, description: string | null, 
+/**
+ * User responsible for the issue's content
+ */
+owner_id: string | null, 
+/**
+ * Email of the owner (null once the account is gone)
+ */
+owner_email: string | null, 
+/**
+ * Equipment models the issue applies to
+ */
+equipment_models: Array<string>, tags: Array<string>, 
+/**
+ * Hidden from technicians before this time
+ */
+active_from: string | null, 
+/**
+ * Hidden from technicians from this time on
+ */
+active_until: string | null, };
\ No newline at end of file
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApprovalStatus } from "./ApprovalStatus";
+
+/**
+ * A draft submitted for review
+ */
+export type IssueApproval = { id: string, category: string, status: ApprovalStatus, 
+/**
+ * Submitter's note to the reviewer
+ */
+comment: string | null, submitted_by: string | null, 
+/**
+ * Email of the submitter (null once the account is gone)
+ */
+submitted_by_email: string | null, submitted_at: string, decided_by: string | null, 
+/**
+ * Email of the reviewer (null until decided or once the account is gone)
+ */
+decided_by_email: string | null, decided_at: string | null, 
+/**
+ * Reviewer's reason for the decision
+ */
+decision_comment: string | null, 
+/**
+ * Version the approved draft was published as
+ */
+version: number | null, };
\ No newline at end of file
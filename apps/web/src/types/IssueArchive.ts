@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An archived issue
+ */
+export type IssueArchive = { category: string, archived_by: string | null, archived_at: string, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Deterministic content hash of a category, for sync drift detection
+ */
+export type IssueChecksum = { category: string, algorithm: string, 
+/**
+ * Hash over nodes_checksum and connections_checksum
+ */
+checksum: string, nodes_checksum: string, connections_checksum: string, node_count: number, connection_count: number, ignore_layout: boolean, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An issue's unpublished draft
+ */
+export type IssueDraft = { category: string, 
+/**
+ * Category holding the draft graph; open it in the editor to make changes
+ */
+draft_category: string, created_by: string | null, created_at: string, node_count: number, };
\ No newline at end of file
@@ -5,4 +5,8 @@ import type { Node } from "./Node";
 /**
  * Complete graph for an issue category
  */
-export type IssueGraph = { category: string, nodes: Array<Node>, connections: Array<Connection>, };
\ No newline at end of file
+export type IssueGraph = { category: string, nodes: Array<Node>, connections: Array<Connection>, 
+/**
+ * Continuation token for the next chunk (null on the last chunk and for unpaginated requests)
+ */
+next_cursor: string | null, };
\ No newline at end of file
@@ -3,4 +3,16 @@
 /**
  * Issue metadata for import (without generated fields)
  */
-export type IssueImportMetadata = { name: string, category: string, display_category: string | null, root_question_text: string, };
\ No newline at end of file
+export type IssueImportMetadata = { 
+/**
+ * Issue name, as shown on the start question's answer
+ */
+name: string, category: string, display_category: string | null, root_question_text: string, 
+/**
+ * Whether the issue is switched on (imported as active when absent)
+ */
+is_active?: boolean, 
+/**
+ * Position among the start question's answers (after the existing ones when absent)
+ */
+start_order_index?: number, description?: string, equipment_models?: Array<string>, tags?: Array<string>, };
\ No newline at end of file
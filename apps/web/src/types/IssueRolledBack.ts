@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a rollback changed in the issue
+ */
+export type IssueRolledBack = { category: string, restored_version: number, 
+/**
+ * Version the rollback was recorded as
+ */
+version: number, nodes_updated: number, 
+/**
+ * Nodes deleted since the version, created again with their old IDs
+ */
+nodes_recreated: number, 
+/**
+ * Nodes the version doesn't have, now switched off
+ */
+nodes_deactivated: number, connections_updated: number, connections_recreated: number, connections_deactivated: number, };
\ No newline at end of file
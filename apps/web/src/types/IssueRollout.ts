@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Active rollout configuration
+ */
+export type IssueRollout = { category: string, candidate_category: string, percentage: number, started_at: string, updated_at: string, };
\ No newline at end of file
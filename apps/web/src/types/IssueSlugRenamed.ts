@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Issue } from "./Issue";
+
+/**
+ * Response after renaming an issue's category slug
+ */
+export type IssueSlugRenamed = { previous_category: string, issue: Issue, 
+/**
+ * Nodes moved into the new category
+ */
+nodes_updated: number, 
+/**
+ * Sub-flow nodes in any issue now pointing at the new category
+ */
+subflows_updated: number, 
+/**
+ * Sessions whose recorded steps were moved to the new category
+ */
+sessions_updated: number, };
\ No newline at end of file
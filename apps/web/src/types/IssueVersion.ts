@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A recorded version, as listed in an issue's history
+ */
+export type IssueVersion = { category: string, version: number, 
+/**
+ * baseline, publish or rollback
+ */
+source: string, 
+/**
+ * Version a rollback restored
+ */
+restored_version: number | null, node_count: number, connection_count: number, created_by: string | null, 
+/**
+ * Email of the publisher (null once the account is gone)
+ */
+created_by_email: string | null, created_at: string, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VersionConnectionDiff } from "./VersionConnectionDiff";
+import type { VersionNodeDiff } from "./VersionNodeDiff";
+
+/**
+ * Structural differences between two versions of an issue
+ *
+ * Layout is left out, like in node history.
+ */
+export type IssueVersionDiff = { category: string, from_version: number, to_version: number, nodes_added: Array<VersionNodeDiff>, nodes_removed: Array<VersionNodeDiff>, nodes_changed: Array<VersionNodeDiff>, connections_added: Array<VersionConnectionDiff>, connections_removed: Array<VersionConnectionDiff>, connections_changed: Array<VersionConnectionDiff>, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeviceInfo } from "./DeviceInfo";
+
+/**
+ * A newly registered device; `token` is shown only in this response
+ */
+export type IssuedDeviceToken = { token: string, device: DeviceInfo, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ServiceTokenInfo } from "./ServiceTokenInfo";
+
+/**
+ * A newly issued token; `token` is shown only in this response
+ */
+export type IssuedServiceToken = { token: string, info: ServiceTokenInfo, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JwtKeySource } from "./JwtKeySource";
+
+/**
+ * A key as shown to administrators (never includes key material)
+ */
+export type JwtKeyInfo = { kid: string, algorithm: string, source: JwtKeySource, 
+/**
+ * True for the key currently signing new tokens
+ */
+signing: boolean, activates_at: string | null, retired_at: string | null, };
\ No newline at end of file
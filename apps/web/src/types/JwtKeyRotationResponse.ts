@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JwtKeyInfo } from "./JwtKeyInfo";
+
+/**
+ * Rotation result: the new key signs from `activates_at`, older keys keep verifying
+ */
+export type JwtKeyRotationResponse = { kid: string, activates_at: string, keys: Array<JwtKeyInfo>, };
\ No newline at end of file
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What laying out or normalizing a category changed
+ */
+export type LayoutResult = { category: string, 
+/**
+ * Nodes in the category
+ */
+nodes: number, 
+/**
+ * Nodes whose position changed
+ */
+moved: number, 
+/**
+ * Nodes that had no position before
+ */
+placed: number, };
\ No newline at end of file
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LegacyIssueStatus } from "./LegacyIssueStatus";
+
+/**
+ * One issue converted from the legacy tree
+ */
+export type LegacyIssueReport = { category: string, name: string, status: LegacyIssueStatus, 
+/**
+ * Question nodes (legacy questions)
+ */
+questions: number, 
+/**
+ * Conclusion nodes (legacy answers with conclusion text)
+ */
+conclusions: number, connections: number, error: string | null, 
+/**
+ * Legacy rows left out of the issue or worth a look
+ */
+warnings: Array<string>, };
\ No newline at end of file
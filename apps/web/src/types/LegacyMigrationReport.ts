@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LegacyIssueReport } from "./LegacyIssueReport";
+
+/**
+ * Result of converting the legacy tree
+ */
+export type LegacyMigrationReport = { dry_run: boolean, legacy_questions: number, legacy_answers: number, issues: Array<LegacyIssueReport>, 
+/**
+ * Legacy rows that didn't make it into any issue
+ */
+warnings: Array<string>, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LegacyMigrationRequest = { 
+/**
+ * Report what would be created without writing anything
+ */
+dry_run: boolean, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintSeverity } from "./LintSeverity";
+
+/**
+ * A single rule violation on a node
+ */
+export type LintIssue = { rule: string, severity: LintSeverity, message: string, };
\ No newline at end of file
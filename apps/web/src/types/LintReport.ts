@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintRules } from "./LintRules";
+import type { NodeLintResult } from "./NodeLintResult";
+
+/**
+ * Per-node lint report
+ */
+export type LintReport = { rules: LintRules, nodes_checked: number, issues_found: number, nodes: Array<NodeLintResult>, };
\ No newline at end of file
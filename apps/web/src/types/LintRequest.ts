@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintRules } from "./LintRules";
+
+export type LintRequest = { 
+/**
+ * Restrict the report to a single issue category
+ */
+category?: string, 
+/**
+ * Include inactive nodes in the report
+ */
+include_inactive: boolean, 
+/**
+ * Rule overrides (defaults are used for omitted fields)
+ */
+rules?: LintRules, };
\ No newline at end of file
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LintRules = { 
+/**
+ * Minimum number of characters in node text
+ */
+min_length: number, 
+/**
+ * Maximum number of characters in node text
+ */
+max_length: number, 
+/**
+ * Words that must not appear in node text (case-insensitive)
+ */
+forbidden_words: Array<string>, 
+/**
+ * Question nodes must end with a question mark
+ */
+require_question_mark: boolean, 
+/**
+ * Flag conclusions written entirely in capital letters
+ */
+flag_all_caps_conclusions: boolean, 
+/**
+ * Send a HEAD request to every conclusion kb_url and flag broken links
+ */
+check_kb_links: boolean, };
\ No newline at end of file
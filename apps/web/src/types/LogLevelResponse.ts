@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Active tracing filter
+ */
+export type LogLevelResponse = { startup_filter: string, current_filter: string, revert_at: string | null, };
\ No newline at end of file
@@ -5,6 +5,6 @@
  */
 export type LoginRequest = { email: string, password: string, 
 /**
- * If true, token will not expire. If false, token expires in 15 minutes.
+ * If true, the refresh token lasts 30 days; otherwise 12 hours
  */
 remember_me: boolean, };
\ No newline at end of file
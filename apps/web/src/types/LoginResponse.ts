@@ -2,6 +2,14 @@
 import type { UserInfo } from "./UserInfo";
 
 /**
- * Login response with JWT token and user info
+ * Login response with access/refresh tokens and user info
  */
-export type LoginResponse = { token: string, user: UserInfo, };
\ No newline at end of file
+export type LoginResponse = { 
+/**
+ * Short-lived access JWT (15 minutes)
+ */
+token: string, 
+/**
+ * Opaque refresh token; single use, exchanged at /auth/refresh
+ */
+refresh_token: string, refresh_expires_at: string, user: UserInfo, };
\ No newline at end of file
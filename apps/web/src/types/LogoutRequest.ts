@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Logout request payload
+ */
+export type LogoutRequest = { refresh_token: string, };
\ No newline at end of file
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One category in the kiosk manifest
+ */
+export type ManifestCategory = { category: string, name: string, display_category: string | null, 
+/**
+ * Content hash (editor layout excluded); changes whenever the category's
+ * questions or answers do
+ */
+version: string, 
+/**
+ * Latest edit or deletion in the category
+ */
+last_modified: string, node_count: number, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Merge a duplicate node into another
+ */
+export type MergeNode = { 
+/**
+ * The node that takes over the duplicate's incoming answers
+ */
+target_node_id: string, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to merge another session into this one
+ */
+export type MergeSessionsRequest = { 
+/**
+ * Session whose steps move over; it is deleted afterwards
+ */
+source_session_id: string, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of merging two sessions
+ */
+export type MergeSessionsResponse = { session_id: string, merged_from: string, step_count: number, completed: boolean, };
\ No newline at end of file
@@ -3,4 +3,10 @@
 /**
  * Navigation option (connection to next node)
  */
-export type NavigationOption = { connection_id: string, label: string, target_category: string, display_category: string | null, };
\ No newline at end of file
+export type NavigationOption = { connection_id: string, label: string, 
+/**
+ * Reading range this option covers; when a question's options have
+ * conditions the client asks for a number instead of showing buttons.
+ * Conditions on earlier answers are settled server-side and not sent.
+ */
+condition: string | null, target_category: string, display_category: string | null, };
\ No newline at end of file
@@ -1,4 +1,29 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnswerMode } from "./AnswerMode";
+import type { NodeContent } from "./NodeContent";
 import type { NodeType } from "./NodeType";
+import type { OptionOrder } from "./OptionOrder";
 
-export type Node = { id: string, category: string, node_type: NodeType, text: string, semantic_id: string | null, display_category: string | null, position_x: number | null, position_y: number | null, is_active: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
+export type Node = { id: string, category: string, node_type: NodeType, text: string, semantic_id: string | null, display_category: string | null, position_x: number | null, position_y: number | null, 
+/**
+ * Knowledge base / repair manual link (conclusions only)
+ */
+kb_url: string | null, 
+/**
+ * Manual section or document number (conclusions only)
+ */
+doc_reference: string | null, option_order: OptionOrder, answer_mode: AnswerMode, 
+/**
+ * Where the question's "I'm not sure" option leads: the first question
+ * of a diagnostic sub-flow or an escalation conclusion; no option is
+ * offered when unset
+ */
+not_sure_node_id: string | null, 
+/**
+ * The category a sub-flow node runs (sub-flow nodes only)
+ */
+subflow_category: string | null, 
+/**
+ * Step-by-step instructions, pictures and links shown with `text`
+ */
+content: NodeContent | null, is_active: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file attached to a node (a manual, a wiring diagram), as sent to technicians
+ */
+export type NodeAttachment = { id: string, filename: string, content_type: string, size_bytes: number, 
+/**
+ * Signed download URL; valid until `url_expires_at`
+ */
+url: string, url_expires_at: string, };
\ No newline at end of file
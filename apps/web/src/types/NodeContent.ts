@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContentImage } from "./ContentImage";
+import type { ContentLink } from "./ContentLink";
+
+/**
+ * Rich content of a node (sanitized by `utils::content` before it is stored)
+ */
+export type NodeContent = { 
+/**
+ * Markdown without raw HTML
+ */
+markdown: string, images: Array<ContentImage>, links: Array<ContentLink>, };
\ No newline at end of file
@@ -1,6 +1,18 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnswerMode } from "./AnswerMode";
+import type { NodeContent } from "./NodeContent";
+import type { OptionOrder } from "./OptionOrder";
 
 /**
  * Node data for export (with index references instead of UUIDs)
  */
-export type NodeExportData = { node_type: string, text: string, semantic_id: string | null, position_x: number | null, position_y: number | null, };
\ No newline at end of file
+export type NodeExportData = { node_type: string, text: string, semantic_id: string | null, position_x: number | null, position_y: number | null, kb_url: string | null, doc_reference: string | null, option_order: OptionOrder, answer_mode: AnswerMode, 
+/**
+ * Index in nodes array of where "I'm not sure" leads (targets in other
+ * categories are not exported, like answers into them)
+ */
+not_sure_node_index?: number, 
+/**
+ * The category a sub-flow node runs
+ */
+subflow_category?: string, content?: NodeContent, };
\ No newline at end of file
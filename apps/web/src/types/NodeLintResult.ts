@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintIssue } from "./LintIssue";
+import type { NodeType } from "./NodeType";
+
+/**
+ * All findings for one node
+ */
+export type NodeLintResult = { node_id: string, category: string, semantic_id: string | null, node_type: NodeType, text: string, issues: Array<LintIssue>, };
\ No newline at end of file
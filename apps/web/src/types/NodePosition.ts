@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One node's place in a saved graph layout
+ */
+export type NodePosition = { id: string, position_x: number, position_y: number, };
\ No newline at end of file
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A recorded change, as listed in a node's history
+ */
+export type NodeRevision = { id: string, node_id: string, field: string, old_value: unknown, new_value: unknown, changed_by: string | null, 
+/**
+ * Email of the editor (null once the account is gone)
+ */
+changed_by_email: string | null, changed_at: string, 
+/**
+ * Revision this change undid, for reverts
+ */
+reverted_revision_id: string | null, };
\ No newline at end of file
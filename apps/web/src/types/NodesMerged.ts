@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Node } from "./Node";
+
+export type NodesMerged = { 
+/**
+ * The duplicate, now inactive
+ */
+source: Node, target_node_id: string, 
+/**
+ * Answers that now lead to the target
+ */
+connections_rewired: number, };
\ No newline at end of file
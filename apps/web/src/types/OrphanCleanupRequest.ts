@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrphanAction } from "./OrphanAction";
+
+/**
+ * Request to clean up an issue's stranded nodes
+ */
+export type OrphanCleanupRequest = { action: OrphanAction, 
+/**
+ * Only these nodes (each must be stranded); all of them when omitted
+ */
+node_ids?: Array<string>, };
\ No newline at end of file
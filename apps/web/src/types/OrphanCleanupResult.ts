@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrphanAction } from "./OrphanAction";
+
+/**
+ * Stranded nodes that were cleaned up
+ */
+export type OrphanCleanupResult = { category: string, action: OrphanAction, 
+/**
+ * Nodes switched off or deleted (already inactive ones are left out when deactivating)
+ */
+node_ids: Array<string>, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NodeType } from "./NodeType";
+
+/**
+ * A node nothing leads to
+ */
+export type OrphanNode = { id: string, node_type: NodeType, text: string, semantic_id: string | null, is_active: boolean, };
\ No newline at end of file
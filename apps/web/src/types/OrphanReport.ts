@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrphanNode } from "./OrphanNode";
+
+/**
+ * An issue's stranded nodes
+ */
+export type OrphanReport = { category: string, 
+/**
+ * The issue's start node
+ */
+root_node_id: string, 
+/**
+ * In creation order
+ */
+nodes: Array<OrphanNode>, };
\ No newline at end of file
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Fine-grained permission granted to roles (`resource:action`)
+ *
+ * ADMIN always holds every permission; grants for the other roles live in
+ * the `role_permissions` table.
+ */
+export type Permission = "issues:read" | "issues:write" | "issues:approve" | "sessions:read" | "sessions:delete" | "stats:read" | "audit:read" | "uploads:read" | "uploads:write" | "users:manage" | "system:manage";
\ No newline at end of file
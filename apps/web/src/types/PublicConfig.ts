@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Deployment branding as served to the frontend
+ */
+export type PublicConfig = { product_name: string, 
+/**
+ * Absolute http(s) URL or a path on this host (e.g. `/uploads/logo.svg`)
+ */
+logo_url: string | null, support_email: string | null, support_phone: string | null, support_url: string | null, 
+/**
+ * Frontend feature switches that are turned on
+ */
+features: Array<string>, 
+/**
+ * Locales the install offers, most preferred first
+ */
+locales: Array<string>, default_locale: string, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to purge an issue's long-inactive nodes and answers
+ */
+export type PurgeInactiveRequest = { 
+/**
+ * Delete records switched off more than this many days ago
+ */
+older_than_days: number, };
\ No newline at end of file
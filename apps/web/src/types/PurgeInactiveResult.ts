@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Nodes and answers deleted by a purge
+ */
+export type PurgeInactiveResult = { category: string, node_ids: Array<string>, 
+/**
+ * Answers deleted, including those of the purged nodes
+ */
+connections: number, };
\ No newline at end of file
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A single recorded error response
+ */
+export type RecentError = { timestamp: string, method: string, 
+/**
+ * Matched route pattern (e.g. /api/v1/admin/nodes/:id) when available
+ */
+route: string, status: number, 
+/**
+ * ApiError type, absent for errors not produced by an ApiError
+ */
+error_code: string | null, correlation_id: string, };
\ No newline at end of file
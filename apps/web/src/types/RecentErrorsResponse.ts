@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RecentError } from "./RecentError";
+
+/**
+ * Recent API errors, newest first
+ */
+export type RecentErrorsResponse = { errors: Array<RecentError>, capacity: number, };
\ No newline at end of file
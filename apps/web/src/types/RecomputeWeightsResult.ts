@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of recomputing connection weights for a category
+ */
+export type RecomputeWeightsResult = { category: string, window_days: number, min_samples: number, nodes_updated: number, connections_updated: number, };
\ No newline at end of file
@@ -3,4 +3,4 @@
 /**
  * Refresh token request payload
  */
-export type RefreshRequest = { token: string, };
\ No newline at end of file
+export type RefreshRequest = { refresh_token: string, };
\ No newline at end of file
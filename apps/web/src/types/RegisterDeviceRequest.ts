@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to register a kiosk
+ */
+export type RegisterDeviceRequest = { 
+/**
+ * Where the kiosk is (e.g. "Plant 2 line 4")
+ */
+name: string, };
\ No newline at end of file
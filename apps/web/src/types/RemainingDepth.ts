@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Fewest and most questions left before a conclusion (0 at a conclusion)
+ */
+export type RemainingDepth = { min: number, max: number, };
\ No newline at end of file
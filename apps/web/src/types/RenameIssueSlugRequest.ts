@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to rename an issue's category slug
+ */
+export type RenameIssueSlugRequest = { 
+/**
+ * New category key: letters, digits, underscores and hyphens
+ */
+new_category: string, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Set a new password with a reset token
+ */
+export type ResetPasswordRequest = { token: string, new_password: string, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response after marking an issue as reviewed
+ */
+export type ReviewIssueResponse = { category: string, nodes_reviewed: number, reviewed_at: string, };
\ No newline at end of file
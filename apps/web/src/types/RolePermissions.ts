@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+import type { UserRole } from "./UserRole";
+
+/**
+ * Permissions granted to a role
+ */
+export type RolePermissions = { role: UserRole, permissions: Array<Permission>, 
+/**
+ * False for ADMIN, which always holds every permission
+ */
+editable: boolean, };
\ No newline at end of file
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IssueRollout } from "./IssueRollout";
+import type { RolloutVariantStats } from "./RolloutVariantStats";
+
+/**
+ * Rollout configuration with per-version stats
+ */
+export type RolloutStatus = { rollout: IssueRollout, variants: Array<RolloutVariantStats>, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Completion stats for one rollout arm
+ */
+export type RolloutVariantStats = { 
+/**
+ * "current" or "candidate"
+ */
+variant: string, served_category: string, sessions: bigint, completed: bigint, completion_rate: number, };
\ No newline at end of file
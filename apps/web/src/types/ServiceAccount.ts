@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ServiceTokenInfo } from "./ServiceTokenInfo";
+import type { UserRole } from "./UserRole";
+
+/**
+ * A service account with its tokens
+ */
+export type ServiceAccount = { tokens: Array<ServiceTokenInfo>, id: string, email: string, display_name: string | null, role: UserRole, is_active: boolean, 
+/**
+ * Service accounts authenticate with service tokens and cannot sign in
+ */
+is_service: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+
+/**
+ * A service token without its secret
+ */
+export type ServiceTokenInfo = { id: string, user_id: string, name: string, 
+/**
+ * First characters of the token, to recognise it
+ */
+token_prefix: string, scopes: Array<Permission>, created_at: string, 
+/**
+ * Approximate (recorded about once a minute)
+ */
+last_used_at: string | null, revoked_at: string | null, };
\ No newline at end of file
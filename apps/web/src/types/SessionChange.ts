@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionChangeKind } from "./SessionChangeKind";
+
+/**
+ * A change to a session; fetch the session for its full state
+ */
+export type SessionChange = { session_id: string, kind: SessionChangeKind, 
+/**
+ * Question (or conclusion) the session is now at
+ */
+node_id: string, step_count: number, at: string, };
\ No newline at end of file
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A session with its steps, including any notes the tech left on them
+ */
+export type SessionDetail = { entry_point: string | null, steps: unknown, session_id: string, started_at: string, completed_at: string | null, abandoned: boolean, 
+/**
+ * Why the tech gave up, when they abandoned the session themselves
+ */
+abandon_reason: string | null, 
+/**
+ * Last answer or heartbeat from the client
+ */
+last_activity_at: string, tech_identifier: string | null, client_site: string | null, 
+/**
+ * Asset tag or serial of the unit worked on
+ */
+equipment_id: string | null, 
+/**
+ * Kiosk the session was started from
+ */
+device_id: string | null, final_conclusion: string | null, step_count: number, 
+/**
+ * Whether the tech said the conclusion fixed the problem
+ */
+feedback_resolved: boolean | null, feedback_comment: string | null, };
\ No newline at end of file
@@ -1,7 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { HistoryStep } from "./HistoryStep";
+import type { StepAttachment } from "./StepAttachment";
 
 /**
  * Response containing session history
  */
-export type SessionHistoryResponse = { session_id: string, started_at: string, completed: boolean, steps: Array<HistoryStep>, final_conclusion: string | null, };
\ No newline at end of file
+export type SessionHistoryResponse = { session_id: string, started_at: string, completed: boolean, steps: Array<HistoryStep>, final_conclusion: string | null, 
+/**
+ * Photos taken along the way, oldest first
+ */
+attachments: Array<StepAttachment>, };
\ No newline at end of file
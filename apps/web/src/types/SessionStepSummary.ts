@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An answered step, as shown in breadcrumbs
+ */
+export type SessionStepSummary = { node_id: string, node_text: string, 
+/**
+ * Answer followed; absent when the tech skipped to a conclusion
+ */
+connection_id: string | null, connection_label: string | null, 
+/**
+ * Conclusion the tech jumped to from this question
+ */
+skipped_to: string | null, 
+/**
+ * Where "I'm not sure" led from this question
+ */
+not_sure_to: string | null, 
+/**
+ * The tech's note on this step
+ */
+note: string | null, 
+/**
+ * The tech marked this answer as unsure
+ */
+unsure: boolean, };
\ No newline at end of file
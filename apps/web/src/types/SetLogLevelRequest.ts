@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request body for changing the tracing filter
+ */
+export type SetLogLevelRequest = { 
+/**
+ * `EnvFilter` directives, e.g. "info,equipment_troubleshooting::routes=debug"
+ */
+filter: string, 
+/**
+ * Minutes until the filter reverts to the startup value (default 30)
+ */
+duration_minutes?: number, };
\ No newline at end of file
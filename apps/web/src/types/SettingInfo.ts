@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A setting with its effective value, for the admin settings page
+ */
+export type SettingInfo = { key: string, description: string, value: number, default_value: number, min: number, max: number, 
+/**
+ * Whether an admin override is in effect
+ */
+overridden: boolean, updated_at: string | null, updated_by: string | null, };
\ No newline at end of file
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request for simulate_issue: a scripted walk through a category
+ */
+export type SimulateRequest = { 
+/**
+ * Answer labels to pick in order (exact match first, then case-insensitive);
+ * at a question taking a measurement, a number is the reading
+ */
+answers: Array<string>, 
+/**
+ * Conclusion text the walk must end at; sets `passed`
+ */
+expect_conclusion?: string, 
+/**
+ * Values a session would have captured, for conditions and placeholders
+ */
+variables?: { [key: string]: string }, };
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SimulationOutcome = "concluded" | "needs_more_answers" | "unmatched_answer" | "extra_answers";
\ No newline at end of file
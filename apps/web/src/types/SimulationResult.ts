@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Node } from "./Node";
+import type { SimulationOutcome } from "./SimulationOutcome";
+import type { SimulationStep } from "./SimulationStep";
+
+/**
+ * Result of simulate_issue
+ */
+export type SimulationResult = { category: string, outcome: SimulationOutcome, steps: Array<SimulationStep>, 
+/**
+ * Node the walk stopped at, with its placeholders filled in
+ */
+final_node: Node, 
+/**
+ * Conclusion text when the walk stopped at a conclusion
+ */
+conclusion: string | null, 
+/**
+ * Options offered at the final node given the answers so far (empty at a conclusion)
+ */
+available_answers: Array<string>, 
+/**
+ * The answer that matched no option, for `unmatched_answer`
+ */
+unmatched_answer: string | null, 
+/**
+ * Whether the conclusion equals `expect_conclusion` (trimmed, case-insensitive); null when not given
+ */
+passed: boolean | null, };
\ No newline at end of file
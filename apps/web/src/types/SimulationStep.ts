@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One answered question in a simulated walk
+ */
+export type SimulationStep = { node_id: string, node_text: string, semantic_id: string | null, category: string, connection_id: string, 
+/**
+ * Label of the connection taken
+ */
+answer: string, 
+/**
+ * Reading given, at a question taking a measurement
+ */
+reading: number | null, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to jump straight to a conclusion
+ */
+export type SkipToConclusionRequest = { 
+/**
+ * Conclusion in the current question's category
+ */
+node_id: string, };
\ No newline at end of file
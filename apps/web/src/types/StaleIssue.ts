@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A category that has not been reviewed within the review interval
+ */
+export type StaleIssue = { category: string, node_count: number, 
+/**
+ * Nodes in this category that have never been reviewed
+ */
+unreviewed_nodes: number, 
+/**
+ * Oldest review timestamp across the category's nodes
+ */
+last_reviewed_at: string | null, days_since_review: number | null, };
\ No newline at end of file
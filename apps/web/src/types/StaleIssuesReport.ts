@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StaleIssue } from "./StaleIssue";
+
+/**
+ * Stale content report
+ */
+export type StaleIssuesReport = { max_age_days: number, issues: Array<StaleIssue>, };
\ No newline at end of file
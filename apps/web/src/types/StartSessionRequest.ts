@@ -3,4 +3,16 @@
 /**
  * Request to start a new troubleshooting session
  */
-export type StartSessionRequest = { tech_identifier: string | null, client_site: string | null, category: string | null, };
\ No newline at end of file
+export type StartSessionRequest = { tech_identifier: string | null, client_site: string | null, category: string | null, 
+/**
+ * Named entry point to start from instead of the global start node
+ */
+entry_point?: string, 
+/**
+ * Asset tag or serial of the unit being worked on
+ */
+equipment_id?: string, 
+/**
+ * Values captured up front, such as a serial number
+ */
+variables?: { [key: string]: string }, };
\ No newline at end of file
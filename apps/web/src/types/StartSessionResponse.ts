@@ -1,8 +1,28 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryNotice } from "./CategoryNotice";
+import type { DuplicateSession } from "./DuplicateSession";
 import type { NavigationOption } from "./NavigationOption";
 import type { Node } from "./Node";
+import type { NodeAttachment } from "./NodeAttachment";
 
 /**
  * Response when starting a session (NODE-GRAPH VERSION)
  */
-export type StartSessionResponse = { session_id: string, node: Node, options: Array<NavigationOption>, };
\ No newline at end of file
+export type StartSessionResponse = { session_id: string, 
+/**
+ * Short code for continuing the session on another device
+ */
+resume_code: string, node: Node, options: Array<NavigationOption>, 
+/**
+ * Another open session on the same equipment started shortly before
+ * this one; the tech may want to continue it instead
+ */
+duplicate_of: DuplicateSession | null, 
+/**
+ * Active notices on the start node's category
+ */
+notices: Array<CategoryNotice>, 
+/**
+ * Reference files attached to the start node
+ */
+attachments: Array<NodeAttachment>, };
\ No newline at end of file
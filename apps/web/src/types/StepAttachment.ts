@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UploadInfo } from "./UploadInfo";
+
+/**
+ * A photo attached to a session step
+ */
+export type StepAttachment = { 
+/**
+ * Index of the step's answer in the session history; a photo of the
+ * question on screen gets the index its answer will have
+ */
+step_index: number, 
+/**
+ * Question the photo was taken at
+ */
+node_id: string, upload: UploadInfo, };
\ No newline at end of file
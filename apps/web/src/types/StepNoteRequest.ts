@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A note on an answered step; blank removes it
+ */
+export type StepNoteRequest = { 
+/**
+ * e.g. "intermittent, only when cold"
+ */
+note: string, };
\ No newline at end of file
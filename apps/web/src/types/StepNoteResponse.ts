@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A step's note as stored
+ */
+export type StepNoteResponse = { session_id: string, step_index: number, note: string | null, };
\ No newline at end of file
@@ -3,4 +3,36 @@
 /**
  * Request to submit an answer (NODE-GRAPH VERSION)
  */
-export type SubmitAnswerRequest = { connection_id: string, };
\ No newline at end of file
+export type SubmitAnswerRequest = { 
+/**
+ * The chosen answer (omit when submitting a measurement)
+ */
+connection_id?: string, 
+/**
+ * Measurement question being answered (required with `value`)
+ */
+node_id?: string, 
+/**
+ * Numeric reading; routed to the first connection whose condition matches
+ */
+value?: number, 
+/**
+ * Several answers on a multi-select question
+ */
+connection_ids?: Array<string>, 
+/**
+ * Values captured at this question, such as an error code; node text
+ * shows them as `{{name}}` and conditions can test them. A blank value
+ * clears the variable.
+ */
+variables?: { [key: string]: string }, 
+/**
+ * The tech isn't confident in this answer; recorded on the step, and
+ * sent down the question's conservative branch if it has one
+ */
+unsure?: boolean, 
+/**
+ * The tech can't answer at all: go where the current question's
+ * "I'm not sure" option leads (sent instead of an answer)
+ */
+not_sure?: boolean, };
\ No newline at end of file
@@ -1,8 +1,39 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryNotice } from "./CategoryNotice";
 import type { NavigationOption } from "./NavigationOption";
 import type { Node } from "./Node";
+import type { NodeAttachment } from "./NodeAttachment";
+import type { RemainingDepth } from "./RemainingDepth";
 
 /**
  * Response after submitting an answer (NODE-GRAPH VERSION)
  */
-export type SubmitAnswerResponse = { session_id: string, node: Node, options: Array<NavigationOption>, is_conclusion: boolean, conclusion_text: string | null, };
\ No newline at end of file
+export type SubmitAnswerResponse = { session_id: string, node: Node, options: Array<NavigationOption>, is_conclusion: boolean, conclusion_text: string | null, 
+/**
+ * Repair manual link for the conclusion, if one is set
+ */
+kb_url: string | null, 
+/**
+ * Manual section or document number for the conclusion
+ */
+doc_reference: string | null, 
+/**
+ * Answers picked on a queue-mode question that are still to be followed;
+ * at a conclusion the session stays open until these are worked through
+ */
+follow_ups_remaining: number, 
+/**
+ * Active notices on the current node's category (e.g. a known issue
+ * this week with a conclusion to skip to)
+ */
+notices: Array<CategoryNotice>, 
+/**
+ * Reference files attached to the current node, such as a manual or wiring diagram
+ */
+attachments: Array<NodeAttachment>, 
+/**
+ * Fewest and most questions left before a conclusion (0 at a
+ * conclusion), for a progress indicator; null when no conclusion can be
+ * reached from here
+ */
+remaining: RemainingDepth | null, };
\ No newline at end of file
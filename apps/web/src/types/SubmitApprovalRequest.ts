@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to submit an issue's draft for approval
+ */
+export type SubmitApprovalRequest = { 
+/**
+ * Note to the reviewer
+ */
+comment?: string, };
\ No newline at end of file
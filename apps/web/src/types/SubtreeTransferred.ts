@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SubtreeMode } from "./SubtreeMode";
+
+export type SubtreeTransferred = { mode: SubtreeMode, source_category: string, target_category: string, 
+/**
+ * The subtree's root in the target category (a new node when copied)
+ */
+root_node_id: string, 
+/**
+ * The subtree's nodes in the target category, root first
+ */
+node_ids: Array<string>, 
+/**
+ * Answers copied along with the nodes (0 when moved)
+ */
+connections: number, };
\ No newline at end of file
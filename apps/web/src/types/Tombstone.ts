@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A hard-deleted node or connection
+ */
+export type Tombstone = { 
+/**
+ * "node" or "connection"
+ */
+entity_type: string, entity_id: string, deleted_at: string, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SubtreeMode } from "./SubtreeMode";
+
+/**
+ * Copy or move a node and everything reachable from it into another category
+ */
+export type TransferSubtree = { target_category: string, mode: SubtreeMode, };
\ No newline at end of file
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How often techs were unsure answering a question
+ *
+ * Counted from live sessions only; archived steps are compressed.
+ */
+export type UnsureQuestionStats = { node_id: string, question: string, category: string, answers: number, unsure_count: number, 
+/**
+ * Share of answers marked unsure, from 0 to 1
+ */
+unsure_rate: number, };
\ No newline at end of file
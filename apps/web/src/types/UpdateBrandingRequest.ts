@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Branding changes; omitted fields are left unchanged
+ */
+export type UpdateBrandingRequest = { product_name?: string, 
+/**
+ * Empty string clears the logo
+ */
+logo_url?: string, 
+/**
+ * Empty string clears the support email
+ */
+support_email?: string, 
+/**
+ * Empty string clears the support phone number
+ */
+support_phone?: string, 
+/**
+ * Empty string clears the support link
+ */
+support_url?: string, features?: Array<string>, locales?: Array<string>, default_locale?: string, };
\ No newline at end of file
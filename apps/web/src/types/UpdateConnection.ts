@@ -1,3 +1,19 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type UpdateConnection = { to_node_id?: string, label?: string, order_index?: number, is_active?: boolean, };
\ No newline at end of file
+export type UpdateConnection = { to_node_id?: string, label?: string, order_index?: number, 
+/**
+ * Manual ordering weight (must be >= 0)
+ */
+weight?: number, 
+/**
+ * Remove the weight (ignored when `weight` is set)
+ */
+clear_weight?: boolean, 
+/**
+ * When this answer applies (empty string clears it)
+ */
+condition?: string, 
+/**
+ * Send unsure answers to this question down this connection
+ */
+unsure_route?: boolean, is_active?: boolean, };
\ No newline at end of file
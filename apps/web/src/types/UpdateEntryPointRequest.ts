@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to update an entry point; omitted fields are left unchanged
+ */
+export type UpdateEntryPointRequest = { node_id?: string, 
+/**
+ * Empty string clears the description
+ */
+description?: string, is_active?: boolean, };
\ No newline at end of file
@@ -3,4 +3,28 @@
 /**
  * Request to update issue metadata
  */
-export type UpdateIssueRequest = { name: string | null, display_category: string | null, is_active: boolean | null, };
\ No newline at end of file
+export type UpdateIssueRequest = { name: string | null, display_category: string | null, is_active: boolean | null, 
+/**
+ * Empty string clears it
+ */
+description?: string, 
+/**
+ * User ID; empty string clears it
+ */
+owner_id?: string, 
+/**
+ * Replaces the list
+ */
+equipment_models?: Array<string>, 
+/**
+ * Replaces the list
+ */
+tags?: Array<string>, 
+/**
+ * RFC 3339 timestamp; empty string clears it
+ */
+active_from?: string, 
+/**
+ * RFC 3339 timestamp; empty string clears it
+ */
+active_until?: string, };
\ No newline at end of file
@@ -1,4 +1,39 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnswerMode } from "./AnswerMode";
+import type { NodeContent } from "./NodeContent";
 import type { NodeType } from "./NodeType";
+import type { OptionOrder } from "./OptionOrder";
 
-export type UpdateNode = { text?: string, semantic_id?: string, node_type?: NodeType, display_category?: string, position_x?: number, position_y?: number, is_active?: boolean, };
\ No newline at end of file
+export type UpdateNode = { text?: string, 
+/**
+ * Unique within the category; empty string clears it
+ */
+semantic_id?: string, 
+/**
+ * Derive a free semantic ID from the (new) text; ignored when `semantic_id` is set
+ */
+generate_semantic_id?: boolean, node_type?: NodeType, display_category?: string, position_x?: number, position_y?: number, 
+/**
+ * Empty string clears the link
+ */
+kb_url?: string, 
+/**
+ * Empty string clears the reference
+ */
+doc_reference?: string, option_order?: OptionOrder, answer_mode?: AnswerMode, 
+/**
+ * Where "I'm not sure" leads (questions only)
+ */
+not_sure_node_id?: string, 
+/**
+ * Remove the "I'm not sure" option (ignored when `not_sure_node_id` is set)
+ */
+clear_not_sure?: boolean, 
+/**
+ * The category a sub-flow node runs (sub-flow nodes only)
+ */
+subflow_category?: string, 
+/**
+ * Replaces the node's rich content; empty content clears it
+ */
+content?: NodeContent, is_active?: boolean, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to change a notice; replaces its message, conclusion and window
+ */
+export type UpdateNoticeRequest = { message: string, conclusion_node_id?: string, starts_at: string, ends_at: string, };
\ No newline at end of file
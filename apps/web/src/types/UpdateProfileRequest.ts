@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Update the caller's own profile; omitted fields are left unchanged
+ *
+ * Changing the email or password requires `current_password`.
+ */
+export type UpdateProfileRequest = { 
+/**
+ * Empty string clears the display name
+ */
+display_name?: string, email?: string, current_password?: string, new_password?: string, };
\ No newline at end of file
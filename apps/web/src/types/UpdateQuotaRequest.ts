@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to set a category's daily session quota
+ */
+export type UpdateQuotaRequest = { 
+/**
+ * New sessions allowed per UTC day
+ */
+daily_limit: number, 
+/**
+ * Sessions per day after which ops are warned (at most the limit)
+ */
+warn_at?: number, };
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
+
+/**
+ * Request body for update_role_permissions
+ */
+export type UpdateRolePermissionsRequest = { permissions: Array<Permission>, };
\ No newline at end of file
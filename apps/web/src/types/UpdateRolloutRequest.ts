@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to start or adjust a percentage rollout
+ */
+export type UpdateRolloutRequest = { 
+/**
+ * Category holding the revised graph
+ */
+candidate_category: string, 
+/**
+ * Share of new sessions (0-100) routed to the candidate
+ */
+percentage: number, };
\ No newline at end of file
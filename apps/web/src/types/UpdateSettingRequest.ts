@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to override a setting
+ */
+export type UpdateSettingRequest = { value: number, };
\ No newline at end of file
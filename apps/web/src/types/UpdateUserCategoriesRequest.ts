@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request body for update_user_categories; an empty list lifts the restriction
+ */
+export type UpdateUserCategoriesRequest = { categories: Array<string>, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to update a user account
+ */
+export type UpdateUserRequest = { email?: string, 
+/**
+ * Reactivate (true) or deactivate (false) the account
+ */
+is_active?: boolean, };
\ No newline at end of file
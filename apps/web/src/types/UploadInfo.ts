@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UploadOwnerType } from "./UploadOwnerType";
+
+/**
+ * Upload metadata with a short-lived download URL
+ */
+export type UploadInfo = { id: string, filename: string, 
+/**
+ * Detected from the file contents
+ */
+content_type: string, size_bytes: number, sha256: string, owner_type: UploadOwnerType | null, owner_id: string | null, uploaded_by: string | null, 
+/**
+ * 'clean' (passed ClamAV) or 'not_scanned'
+ */
+scan_status: string, 
+/**
+ * Signed download URL; request a fresh one after `url_expires_at`
+ */
+url: string, url_expires_at: string, created_at: string, };
\ No newline at end of file
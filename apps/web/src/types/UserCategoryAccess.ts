@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Issue categories a user may edit
+ */
+export type UserCategoryAccess = { user_id: string, 
+/**
+ * False when the user may edit every category
+ */
+restricted: boolean, 
+/**
+ * Categories the user may edit (empty when unrestricted)
+ */
+categories: Array<string>, };
\ No newline at end of file
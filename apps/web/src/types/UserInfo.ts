@@ -1,7 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Permission } from "./Permission";
 import type { UserRole } from "./UserRole";
 
 /**
  * User information returned in login response
  */
-export type UserInfo = { id: string, email: string, role: UserRole, };
\ No newline at end of file
+export type UserInfo = { id: string, email: string, display_name: string | null, role: UserRole, 
+/**
+ * Effective permissions of the role, for hiding unavailable admin screens
+ */
+permissions: Array<Permission>, };
\ No newline at end of file
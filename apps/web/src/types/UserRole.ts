@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type UserRole = "Admin" | "Viewer" | "Tech";
\ No newline at end of file
+export type UserRole = "Admin" | "Viewer" | "Tech" | "Editor";
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UserRole } from "./UserRole";
+
+/**
+ * User account as shown to admins (never includes the password hash)
+ */
+export type UserSummary = { id: string, email: string, display_name: string | null, role: UserRole, is_active: boolean, 
+/**
+ * Service accounts authenticate with service tokens and cannot sign in
+ */
+is_service: boolean, created_at: string, updated_at: string, };
\ No newline at end of file
@@ -1,3 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type ValidationField = { field: string, message: string, };
\ No newline at end of file
+export type ValidationField = { field: string, message: string, 
+/**
+ * JSON pointer (RFC 6901) to the offending element of a nested payload,
+ * e.g. `/connections/3/label`
+ */
+pointer: string | null, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VersionFieldChange } from "./VersionFieldChange";
+
+/**
+ * A connection added, removed or changed between two versions
+ */
+export type VersionConnectionDiff = { id: string, from_node_id: string, to_node_id: string, label: string, 
+/**
+ * Changed fields (empty for added and removed connections)
+ */
+changes: Array<VersionFieldChange>, };
\ No newline at end of file
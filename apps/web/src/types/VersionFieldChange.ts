@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A field that differs between two versions of a record
+ */
+export type VersionFieldChange = { field: string, old_value: unknown, new_value: unknown, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VersionFieldChange } from "./VersionFieldChange";
+
+/**
+ * A node added, removed or changed between two versions
+ */
+export type VersionNodeDiff = { id: string, semantic_id: string | null, text: string, 
+/**
+ * Changed fields (empty for added and removed nodes)
+ */
+changes: Array<VersionFieldChange>, };
\ No newline at end of file
@@ -2,8 +2,12 @@
 export * from './Answer';
 export * from './AnswerDestination';
 export * from './ApiError';
+export * from './BrandingSettings';
 export * from './Claims';
+export * from './ClonedNode';
 export * from './Connection';
+export * from './ContentImage';
+export * from './ContentLink';
 export * from './ConnectionWithTarget';
 export * from './CreateAnswer';
 export * from './CreateConnection';
@@ -12,18 +16,58 @@ export * from './CreateQuestion';
 export * from './ErrorResponse';
 export * from './LoginRequest';
 export * from './LoginResponse';
+export * from './MergeNode';
 export * from './Node';
+export * from './NodeContent';
+export * from './NodePosition';
+export * from './NodePositionsUpdated';
 export * from './NodeType';
 export * from './NodeWithConnections';
+export * from './NodesMerged';
+export * from './PublicConfig';
 export * from './Question';
 export * from './QuestionWithAnswers';
 export * from './RefreshRequest';
+export * from './SettingInfo';
+export * from './SubtreeMode';
+export * from './SubtreeTransferred';
+export * from './TransferSubtree';
 export * from './UpdateAnswer';
+export * from './UpdateBrandingRequest';
 export * from './UpdateConnection';
 export * from './UpdateNode';
 export * from './UpdateQuestion';
+export * from './UpdateSettingRequest';
 export * from './UserInfo';
 export * from './UserRole';
+export * from './Permission';
+export * from './RolePermissions';
+export * from './UpdateRolePermissionsRequest';
+export * from './UserCategoryAccess';
+export * from './UpdateUserCategoriesRequest';
 export * from './ValidationField';
 export * from './IssueGraph';
 export * from './NavigationOption';
+export * from './NodeRevision';
+export * from './UpdateProfileRequest';
+export * from './EntryPoint';
+export * from './CreateEntryPointRequest';
+export * from './UpdateEntryPointRequest';
+export * from './CategoryNotice';
+export * from './CreateNoticeRequest';
+export * from './UpdateNoticeRequest';
+export * from './ServiceAccount';
+export * from './ServiceTokenInfo';
+export * from './IssuedServiceToken';
+export * from './CreateServiceAccountRequest';
+export * from './CreateServiceTokenRequest';
+export * from './DeviceInfo';
+export * from './IssuedDeviceToken';
+export * from './RegisterDeviceRequest';
+export * from './JwtKeyInfo';
+export * from './JwtKeySource';
+export * from './JwtKeyRotationResponse';
+export * from './LegacyMigrationRequest';
+export * from './LegacyMigrationReport';
+export * from './LegacyIssueReport';
+export * from './LegacyIssueStatus';
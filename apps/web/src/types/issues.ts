@@ -12,4 +12,49 @@ export * from './NodeExportData';
 export * from './ConnectionExportData';
 export * from './ImportResult';
 export * from './ImportSuccess';
+export * from './BundleImportResult';
 export * from './ImportError';
+export * from './BulkToggleRequest';
+export * from './BulkToggleResult';
+export * from './BulkIssueAction';
+export * from './BulkIssueRequest';
+export * from './BulkIssueResult';
+export * from './BulkIssueItem';
+export * from './BulkIssueStatus';
+export * from './GraphValidationReport';
+export * from './GraphProblem';
+export * from './GraphProblemKind';
+export * from './GraphProblemSeverity';
+export * from './OrphanNode';
+export * from './OrphanReport';
+export * from './OrphanAction';
+export * from './OrphanCleanupRequest';
+export * from './OrphanCleanupResult';
+export * from './PurgeInactiveRequest';
+export * from './PurgeInactiveResult';
+export * from './IssueDraft';
+export * from './DraftPublished';
+export * from './IssueVersion';
+export * from './IssueVersionDiff';
+export * from './VersionNodeDiff';
+export * from './VersionConnectionDiff';
+export * from './VersionFieldChange';
+export * from './IssueRolledBack';
+export * from './IssueArchive';
+export * from './RenameIssueSlugRequest';
+export * from './IssueSlugRenamed';
+export * from './ApprovalStatus';
+export * from './IssueApproval';
+export * from './SubmitApprovalRequest';
+export * from './ApprovalDecisionRequest';
+export * from './ApprovalPublished';
+export * from './RecomputeWeightsResult';
+export * from './IssueChecksum';
+export * from './CategoryManifest';
+export * from './ManifestCategory';
+export * from './SimulateRequest';
+export * from './SimulationOutcome';
+export * from './SimulationStep';
+export * from './SimulationResult';
+export * from './UpdateQuotaRequest';
+export * from './LayoutResult';
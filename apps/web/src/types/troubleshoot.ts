@@ -1,19 +1,44 @@
 // Barrel export for troubleshooting-related types
 export * from './StartSessionRequest';
 export * from './StartSessionResponse';
+export * from './DuplicateSession';
 export * from './SubmitAnswerRequest';
 export * from './SubmitAnswerResponse';
+export * from './RemainingDepth';
+export * from './HeartbeatResponse';
+export * from './SessionChange';
+export * from './SessionChangeKind';
+export * from './GotoStepRequest';
+export * from './ConclusionFeedbackRequest';
+export * from './ConclusionFeedbackResponse';
+export * from './AbandonSessionRequest';
+export * from './AbandonSessionResponse';
+export * from './ResumeSessionResponse';
+export * from './SessionStepSummary';
+export * from './StepNoteRequest';
+export * from './SkipToConclusionRequest';
+export * from './StepNoteResponse';
 export * from './NavigationOption';
 export * from './HistoryStep';
 export * from './SessionHistoryResponse';
+export * from './StepAttachment';
 export * from './SessionsListResponse';
 export * from './SessionSummary';
 export * from './DashboardStats';
 export * from './ConclusionStats';
+export * from './ConclusionEffectiveness';
+export * from './UnsureQuestionStats';
+export * from './CategoryQuota';
 export * from './CategoryStats';
 export * from './AuditLogsResponse';
 export * from './AuditLogEntry';
 export * from './DeleteSessionsResponse';
+export * from './ArchiveSessionsRequest';
+export * from './ArchiveSessionsResponse';
+export * from './ArchivedSession';
+export * from './SessionDetail';
+export * from './MergeSessionsRequest';
+export * from './MergeSessionsResponse';
 export * from './CategoryListResponse';
 export * from './RenameCategoryRequest';
 export * from './CategoryUpdateResponse';